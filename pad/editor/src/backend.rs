@@ -1,10 +1,11 @@
 use std::{
     any::Any,
-    cell::RefCell,
-    collections::{BTreeSet, HashMap},
+    cell::{Cell, RefCell},
+    collections::{BTreeSet, HashMap, VecDeque},
     hash::{DefaultHasher, Hash, Hasher},
     io::Cursor,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
         Mutex,
@@ -12,18 +13,26 @@ use std::{
 };
 
 use crate::{get_ast_time, START_TIME};
+use base64::engine::{general_purpose::STANDARD, Engine};
 use js_sys::Date;
 use leptos::*;
-use uiua::{now, GitTarget, Handle, Report, Span, SysBackend, Uiua, EXAMPLE_TXT, EXAMPLE_UA};
+use uiua::{
+    now, FileMetadata, GitTarget, Handle, HttpRequest, HttpResponse, Report, Span, SysBackend,
+    Uiua, WebSocketMessage, EXAMPLE_TXT, EXAMPLE_UA,
+};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{HtmlAudioElement, Request, RequestInit, RequestMode, Response};
+use web_sys::{
+    AbortSignal, BinaryType, HtmlAudioElement, MessageEvent, Request, RequestInit, RequestMode,
+    Response, Storage, WebSocket,
+};
 
 pub struct WebBackend {
     pub stdout: Mutex<Vec<OutputItem>>,
     pub stderr: Mutex<String>,
     pub trace: Mutex<String>,
     streams: Mutex<HashMap<Handle, VirtualStream>>,
+    websockets: Mutex<HashMap<Handle, String>>,
     id: u64,
     breakpoint: AtomicUsize,
     output_enabled: AtomicBool,
@@ -43,8 +52,62 @@ thread_local! {
             ("example.txt", EXAMPLE_TXT)
         ]
         .map(|(path, content)| (PathBuf::from(path), content.as_bytes().to_vec()))
-        .into(),
+        .into_iter()
+        .chain(load_persisted_files())
+        .collect(),
     );
+    static PERSIST_FILES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The local storage key prefix for persisted files
+const FILE_STORAGE_PREFIX: &str = "uiua-file:";
+
+/// Set whether file changes are saved across sessions
+///
+/// This is only enabled for the pad so that examples on other pages do not leave files behind
+pub fn set_persist_files(persist: bool) {
+    PERSIST_FILES.with(|p| p.set(persist));
+}
+
+fn file_storage() -> Option<Storage> {
+    if cfg!(target_arch = "wasm32") {
+        window().local_storage().ok().flatten()
+    } else {
+        None
+    }
+}
+
+fn load_persisted_files() -> Vec<(PathBuf, Vec<u8>)> {
+    let Some(storage) = file_storage() else {
+        return Vec::new();
+    };
+    let len = storage.length().unwrap_or(0);
+    (0..len)
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter_map(|key| {
+            let path = key.strip_prefix(FILE_STORAGE_PREFIX)?;
+            let encoded = storage.get_item(&key).ok().flatten()?;
+            let contents = STANDARD.decode(encoded).ok()?;
+            Some((PathBuf::from(path), contents))
+        })
+        .collect()
+}
+
+fn persist_file(path: &Path, contents: Option<&[u8]>) {
+    if !PERSIST_FILES.with(Cell::get) {
+        return;
+    }
+    let Some(storage) = file_storage() else {
+        return;
+    };
+    let key = format!("{FILE_STORAGE_PREFIX}{}", path.display());
+    let res = match contents {
+        Some(contents) => storage.set_item(&key, &STANDARD.encode(contents)),
+        None => storage.remove_item(&key),
+    };
+    if let Err(e) = res {
+        logging::warn!("Failed to persist {}: {e:?}", path.display());
+    }
 }
 
 fn weewuh() {
@@ -60,15 +123,47 @@ fn weewuh() {
 }
 
 pub fn drop_file(path: PathBuf, contents: Vec<u8>) {
+    persist_file(&path, Some(&contents));
     FILES.with(|files| files.borrow_mut().insert(path, contents));
 }
 
 pub fn delete_file(path: &PathBuf) {
+    persist_file(path, None);
     FILES.with(|files| files.borrow_mut().remove(path));
 }
 
 thread_local! {
     static BREAKPOINTS: RefCell<HashMap<u64, (u64, usize)>> = Default::default();
+    /// Open WebSockets by URL
+    ///
+    /// These outlive a single run so that connecting and receiving can happen in the background
+    static WEBSOCKETS: RefCell<HashMap<String, WebSocketConnection>> = Default::default();
+}
+
+struct WebSocketConnection {
+    socket: WebSocket,
+    messages: Rc<RefCell<VecDeque<WebSocketMessage>>>,
+}
+
+impl WebSocketConnection {
+    fn open(url: &str) -> Result<Self, String> {
+        let socket = WebSocket::new(url).map_err(|e| format!("{e:?}"))?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        let messages = Rc::new(RefCell::new(VecDeque::new()));
+        let queue = messages.clone();
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let data = event.data();
+            let message = if let Some(text) = data.as_string() {
+                WebSocketMessage::Text(text)
+            } else {
+                WebSocketMessage::Binary(js_sys::Uint8Array::new(&data).to_vec())
+            };
+            queue.borrow_mut().push_back(message);
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        on_message.forget();
+        Ok(WebSocketConnection { socket, messages })
+    }
 }
 
 impl Default for WebBackend {
@@ -98,6 +193,7 @@ impl WebBackend {
             stderr: String::new().into(),
             trace: String::new().into(),
             streams: HashMap::new().into(),
+            websockets: HashMap::new().into(),
             id,
             breakpoint: AtomicUsize::new(0),
             output_enabled: AtomicBool::new(true),
@@ -118,6 +214,7 @@ pub enum OutputItem {
     Svg(String, Option<String>),
     Image(Vec<u8>, Option<String>),
     Gif(Vec<u8>, Option<String>),
+    Frames(Vec<image::RgbaImage>, f64, Option<String>),
     Audio(Vec<u8>, Option<String>),
     Report(Report),
     Faint(String),
@@ -134,8 +231,9 @@ impl OutputItem {
 impl WebBackend {
     fn new_handle(&self) -> Handle {
         let streams = self.streams.lock().unwrap();
+        let websockets = self.websockets.lock().unwrap();
         for handle in (Handle::FIRST_UNRESERVED.0..u64::MAX).map(Handle) {
-            if !streams.contains_key(&handle) {
+            if !streams.contains_key(&handle) && !websockets.contains_key(&handle) {
                 return handle;
             }
         }
@@ -226,6 +324,20 @@ impl SysBackend for WebBackend {
         (self.stdout.lock().unwrap()).push(OutputItem::Gif(gif_bytes, label.map(Into::into)));
         Ok(())
     }
+    fn show_frames(
+        &self,
+        frames: Vec<image::DynamicImage>,
+        frame_rate: f64,
+        label: Option<&str>,
+    ) -> Result<(), String> {
+        let frames = frames.into_iter().map(|frame| frame.into_rgba8()).collect();
+        (self.stdout.lock().unwrap()).push(OutputItem::Frames(
+            frames,
+            frame_rate,
+            label.map(Into::into),
+        ));
+        Ok(())
+    }
     fn list_dir(&self, mut path: &str) -> Result<Vec<String>, String> {
         if path.starts_with("./") {
             path = &path[2..];
@@ -249,6 +361,35 @@ impl SysBackend for WebBackend {
     fn file_exists(&self, path: &str) -> bool {
         self.file(path.as_ref(), |_| {}).is_ok()
     }
+    fn file_metadata(&self, path: &str) -> Result<FileMetadata, String> {
+        let path = Path::new(path);
+        FILES.with(|files| {
+            let files = files.borrow();
+            if let Some(contents) = files.get(path) {
+                Ok(FileMetadata {
+                    size: contents.len() as u64,
+                    modified: None,
+                    is_dir: false,
+                })
+            } else if files.keys().any(|file| file.starts_with(path)) {
+                Ok(FileMetadata {
+                    size: 0,
+                    modified: None,
+                    is_dir: true,
+                })
+            } else {
+                Err(format!("File not found: {}", path.display()))
+            }
+        })
+    }
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        let contents = FILES
+            .with(|files| files.borrow_mut().remove(Path::new(from)))
+            .ok_or_else(|| format!("File not found: {from}"))?;
+        persist_file(Path::new(from), None);
+        drop_file(to.into(), contents);
+        Ok(())
+    }
     fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
         FILES.with(|files| {
             if !files.borrow().contains_key(path) {
@@ -257,6 +398,7 @@ impl SysBackend for WebBackend {
                 *files.borrow_mut().get_mut(path).unwrap() = contents.to_vec();
             }
         });
+        persist_file(path, Some(contents));
         Ok(())
     }
     fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
@@ -279,6 +421,7 @@ impl SysBackend for WebBackend {
     fn create_file(&self, path: &Path) -> Result<Handle, String> {
         let handle = self.new_handle();
         FILES.with(|files| files.borrow_mut().insert(path.into(), Vec::new()));
+        persist_file(path, Some(&[]));
         self.streams.lock().unwrap().insert(
             handle,
             VirtualStream {
@@ -291,6 +434,12 @@ impl SysBackend for WebBackend {
         Ok(handle)
     }
     fn close(&self, handle: Handle) -> Result<(), String> {
+        if let Some(url) = self.websockets.lock().unwrap().remove(&handle) {
+            if let Some(conn) = WEBSOCKETS.with(|sockets| sockets.borrow_mut().remove(&url)) {
+                conn.socket.close().map_err(|e| format!("{e:?}"))?;
+            }
+            return Ok(());
+        }
         let stream = self
             .streams
             .lock()
@@ -345,6 +494,7 @@ impl SysBackend for WebBackend {
     }
     fn delete(&self, path: &str) -> Result<(), String> {
         FILES.with(|files| files.borrow_mut().remove(Path::new(path)));
+        persist_file(Path::new(path), None);
         Ok(())
     }
     fn trash(&self, path: &str) -> Result<(), String> {
@@ -464,6 +614,87 @@ impl SysBackend for WebBackend {
             None => Err("Waiting for module, try running to check...".into()),
         }
     }
+    fn http_request(&self, request: HttpRequest) -> Result<HttpResponse, String> {
+        thread_local! {
+            static RESPONSES: RefCell<HashMap<HttpRequest, Option<Result<HttpResponse, String>>>>
+                = Default::default();
+        }
+        let res = RESPONSES.with(|responses| {
+            let mut responses = responses.borrow_mut();
+            if let Some(res) = responses.get(&request) {
+                // Only GET responses are kept so that rerunning replays them.
+                // Other requests may have side effects, so they are sent again.
+                if res.is_some() && !request.method.eq_ignore_ascii_case("GET") {
+                    return responses.remove(&request).flatten();
+                }
+                res.clone()
+            } else {
+                logging::log!("Making {} request to {}", request.method, request.url);
+                responses.insert(request.clone(), None);
+                spawn_local(async move {
+                    let res = fetch_request(&request).await;
+                    RESPONSES.with(|responses| {
+                        responses.borrow_mut().insert(request, Some(res));
+                    });
+                });
+                None
+            }
+        });
+        res.unwrap_or_else(|| Err("Waiting for response, try running to check...".into()))
+    }
+    fn ws_connect(&self, url: &str) -> Result<Handle, String> {
+        let state = WEBSOCKETS.with(|sockets| -> Result<u16, String> {
+            let mut sockets = sockets.borrow_mut();
+            if let Some(conn) = sockets.get(url) {
+                if conn.socket.ready_state() != WebSocket::CLOSED {
+                    return Ok(conn.socket.ready_state());
+                }
+            }
+            logging::log!("Connecting to {url}");
+            sockets.insert(url.into(), WebSocketConnection::open(url)?);
+            Ok(WebSocket::CONNECTING)
+        })?;
+        match state {
+            WebSocket::OPEN => {
+                let handle = self.new_handle();
+                (self.websockets.lock().unwrap()).insert(handle, url.into());
+                Ok(handle)
+            }
+            WebSocket::CONNECTING => Err("Waiting for connection, try running to check...".into()),
+            _ => Err("WebSocket is closing".into()),
+        }
+    }
+    fn ws_send(&self, handle: Handle, message: WebSocketMessage) -> Result<(), String> {
+        let url = (self.websockets.lock().unwrap().get(&handle).cloned())
+            .ok_or("Invalid websocket handle")?;
+        WEBSOCKETS.with(|sockets| {
+            let sockets = sockets.borrow();
+            let conn = sockets.get(&url).ok_or("WebSocket was closed")?;
+            let res = match message {
+                WebSocketMessage::Text(text) => conn.socket.send_with_str(&text),
+                WebSocketMessage::Binary(bytes) => conn.socket.send_with_u8_array(&bytes),
+            };
+            res.map_err(|e| format!("{e:?}"))
+        })
+    }
+    fn ws_receive(&self, handle: Handle) -> Result<Option<WebSocketMessage>, String> {
+        let url = (self.websockets.lock().unwrap().get(&handle).cloned())
+            .ok_or("Invalid websocket handle")?;
+        WEBSOCKETS.with(|sockets| {
+            let sockets = sockets.borrow();
+            let Some(conn) = sockets.get(&url) else {
+                return Ok(None);
+            };
+            let message = conn.messages.borrow_mut().pop_front();
+            if let Some(message) = message {
+                Ok(Some(message))
+            } else if conn.socket.ready_state() == WebSocket::OPEN {
+                Err("Waiting for message, try running to check...".into())
+            } else {
+                Ok(None)
+            }
+        })
+    }
     fn timezone(&self) -> Result<f64, String> {
         if !cfg!(target_arch = "wasm32") {
             return Ok(0.0);
@@ -519,3 +750,44 @@ pub async fn fetch(url: &str) -> Result<String, String> {
         Err(text)
     }
 }
+
+async fn fetch_request(request: &HttpRequest) -> Result<HttpResponse, String> {
+    let opts = RequestInit::new();
+    opts.set_method(&request.method);
+    opts.set_mode(RequestMode::Cors);
+    if !request.body.is_empty() {
+        opts.set_body(&js_sys::Uint8Array::from(request.body.as_slice()).into());
+    }
+    if let Some(timeout) = request.timeout {
+        opts.set_signal(Some(&AbortSignal::timeout_with_f64(
+            timeout.as_secs_f64() * 1000.0,
+        )));
+    }
+    let req = Request::new_with_str_and_init(&request.url, &opts).map_err(|e| format!("{e:?}"))?;
+    for (name, value) in &request.headers {
+        (req.headers().set(name, value)).map_err(|e| format!("{e:?}"))?;
+    }
+    let window = web_sys::window().unwrap();
+    let resp_value = JsFuture::from(window.fetch_with_request(&req))
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    let resp: Response = resp_value.dyn_into().unwrap();
+    let mut headers = Vec::new();
+    if let Ok(Some(entries)) = js_sys::try_iter(&resp.headers()) {
+        for entry in entries {
+            let entry: js_sys::Array = entry.map_err(|e| format!("{e:?}"))?.into();
+            if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string())
+            {
+                headers.push((name, value));
+            }
+        }
+    }
+    let buffer = JsFuture::from(resp.array_buffer().map_err(|e| format!("{e:?}"))?)
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    Ok(HttpResponse {
+        status: resp.status(),
+        headers,
+        body: js_sys::Uint8Array::new(&buffer).to_vec(),
+    })
+}
@@ -25,9 +25,9 @@ use web_sys::{
 };
 
 use utils::*;
-use utils::{element, format_insert_file_code, get_ast_time};
+use utils::{animate_frames, element, format_insert_file_code, get_ast_time};
 
-use backend::{delete_file, drop_file, OutputItem};
+use backend::{delete_file, drop_file, set_persist_files, OutputItem};
 use js_sys::Date;
 use std::sync::OnceLock;
 
@@ -57,6 +57,7 @@ pub fn Editor<'a>(
     #[prop(optional)] examples: Option<Vec<String>>,
 ) -> impl IntoView {
     START_TIME.get_or_init(|| Date::now() / 1000.0);
+    set_persist_files(mode == EditorMode::Pad);
 
     let no_run = no_run
         || mode == EditorMode::Pad && !get_autorun()
@@ -288,6 +289,16 @@ pub fn Editor<'a>(
                 </div>)
                 .into_view()
             }
+            OutputItem::Frames(frames, frame_rate, label) => {
+                let (width, height) = frames.first().map_or((0, 0), |frame| frame.dimensions());
+                let canvas_ref = create_node_ref::<html::Canvas>();
+                canvas_ref.on_load(move |canvas| animate_frames(&canvas, frames, frame_rate));
+                view!(<div class="output-media-wrapper">
+                    <div class="output-image-label">{label}</div>
+                    <canvas class="output-image" width=width height=height node_ref=canvas_ref/>
+                </div>)
+                .into_view()
+            }
             OutputItem::Audio(bytes, label) => {
                 let encoded = STANDARD.encode(bytes);
                 let src = format!("data:audio/wav;base64,{}", encoded);
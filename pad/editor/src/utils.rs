@@ -19,10 +19,10 @@ use uiua::{
     Spans, Uiua, UiuaError, UiuaResult, Value,
 };
 use unicode_segmentation::UnicodeSegmentation;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{Clamped, JsCast};
 use web_sys::{
-    DomRect, Event, HtmlDivElement, HtmlSpanElement, HtmlStyleElement, HtmlTextAreaElement,
-    KeyboardEvent, MouseEvent,
+    CanvasRenderingContext2d, DomRect, Event, HtmlCanvasElement, HtmlDivElement, HtmlSpanElement,
+    HtmlStyleElement, HtmlTextAreaElement, ImageData, KeyboardEvent, MouseEvent,
 };
 
 use crate::{
@@ -1154,6 +1154,62 @@ fn run_code_single(id: &str, code: &str) -> (Vec<OutputItem>, Option<UiuaError>)
     (output, error)
 }
 
+/// Draw frames to a canvas in a loop, paced by `requestAnimationFrame`
+pub fn animate_frames(canvas: &HtmlCanvasElement, frames: Vec<image::RgbaImage>, frame_rate: f64) {
+    let Some(context) = canvas.get_context("2d").ok().flatten() else {
+        return;
+    };
+    let context: CanvasRenderingContext2d = context.unchecked_into();
+    let images: Vec<ImageData> = (frames.iter())
+        .filter_map(|frame| {
+            ImageData::new_with_u8_clamped_array_and_sh(
+                Clamped(frame.as_raw()),
+                frame.width(),
+                frame.height(),
+            )
+            .ok()
+        })
+        .collect();
+    if images.is_empty() {
+        return;
+    }
+    let frame_ms = 1000.0 / frame_rate.max(1.0 / 60.0);
+    let start = window().performance().map_or(0.0, |perf| perf.now());
+    draw_frame(
+        canvas.clone(),
+        context,
+        images,
+        start,
+        frame_ms,
+        None,
+        false,
+    );
+}
+
+fn draw_frame(
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    images: Vec<ImageData>,
+    start: f64,
+    frame_ms: f64,
+    last: Option<usize>,
+    mounted: bool,
+) {
+    // Stop animating once the canvas is removed from the page
+    let connected = canvas.is_connected();
+    if mounted && !connected {
+        return;
+    }
+    let now = window().performance().map_or(start, |perf| perf.now());
+    let i = ((now - start) / frame_ms) as usize % images.len();
+    if last != Some(i) {
+        _ = context.put_image_data(&images[i], 0.0, 0.0);
+    }
+    request_animation_frame(move || {
+        draw_frame(canvas, context, images, start, frame_ms, Some(i), connected)
+    });
+}
+
 pub fn report_view(report: &Report) -> impl IntoView {
     let mut newline_indices = Vec::new();
     for (i, frag) in report.fragments.iter().enumerate() {
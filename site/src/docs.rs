@@ -429,6 +429,7 @@ impl Allowed {
                 ("thread", &[PrimClass::Thread]),
                 ("map", &[PrimClass::Map]),
                 ("encoding encode", &[PrimClass::Encoding]),
+                ("string text", &[PrimClass::String]),
                 ("ffi", &[PrimClass::Sys(SysOpClass::Ffi)]),
                 ("misc", &[PrimClass::Sys(SysOpClass::Misc)]),
             ] {
@@ -476,6 +477,7 @@ impl Allowed {
                 PrimClass::Thread => "threads",
                 PrimClass::Map => "map-functions",
                 PrimClass::Encoding => "encoding",
+                PrimClass::String => "string-functions",
                 PrimClass::Misc => "misc-functions",
                 PrimClass::Sys(_) => "system-functions",
             };
@@ -554,6 +556,7 @@ impl Allowed {
                 PrimClass::Thread => ("Thread".into_view(), "Work with OS threads"),
                 PrimClass::Map => ("Map".into_view(), "Use arrays as hash maps"),
                 PrimClass::Encoding => ("Encoding".into_view(), "Convert to and from different encodings"),
+                PrimClass::String => ("String".into_view(), "Work with text"),
                 PrimClass::Misc => ("Miscellaneous".into_view(), ""),
                 PrimClass::Sys(class) => {
                     match class {
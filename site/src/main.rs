@@ -483,7 +483,7 @@ pub fn PadPage() -> impl IntoView {
             }
         }
         <br/>
-        <p>"You can load files into the pad by dragging and dropping them into the window."</p>
+        <p>"You can load files into the pad by dragging and dropping them into the window. Files loaded or written in the pad are saved in your browser's local storage, so they are still there the next time you open the pad."</p>
         <p>"Replace "<code>"pad"</code>" in links with "<code>"embed"</code>" or "<code>"embedpad"</code>" to embed the editor."</p>
         <p>"Keyboard shortcuts:"</p>
         <code class="code-block">
@@ -511,6 +511,11 @@ pub fn Subscripts() -> impl IntoView {
         subscript(Transpose, "Repeat", "# Experimental!\n△ ⍉₃ °△1_2_3_4_5"),
         subscript(Sqrt, "Nth root", "√₃ [8 27 125]"),
         subscript(Neg, "Nth root of unity", "# Experimental!\n⁅₃ [⍥₄⊸¯₈] 1"),
+        subscript(
+            Sin,
+            "N units per turn",
+            "# Experimental!\n∿₃₆₀ [0 90 180 270]",
+        ),
         subscript(Round, "To N decimal places", "⁅₃ π"),
         subscript(Floor, "To N decimal places", "# Experimental!\n⌊₄ π\n⌊₄ τ"),
         subscript(Ceil, "To N decimal places", "# Experimental!\n⌈₄ π\n⌈₄ τ"),
@@ -221,6 +221,21 @@ pub fn Documentation() -> impl IntoView {
         <p>"These can be put in functions as well."</p>
         <Editor example="# Get the average of a list\n# ? List\nAvg ← (\n  ⟜/+ # List Sum ? List\n  ⧻   # Length ? List\n  ÷   # Avg ? Length List\n)"/>
 
+        <Hd id="type-annotations">"Type Annotations"</Hd>
+        <p>"Names in a comment signature can be followed by a "<code>":"</code>" and a type. In files marked "<code>"# Experimental!"</code>", types in the forms below are checked."</p>
+        <p>"A type is one of "<code>"Num"</code>", "<code>"Complex"</code>", "<code>"Char"</code>", "<code>"Box"</code>", or "<code>"Any"</code>". It may be followed by a subscripted rank or by a shape in brackets. Dimensions in a shape are separated by "<code>"_"</code>" and can be either numbers or names. A name must be the same length everywhere it appears in a signature."</p>
+        <Editor example="# Experimental!\n# Add two vectors\n# ? A:Num[n] B:Num[n]\nAddVec ← +\nAddVec [1 2] [3 4]\nAddVec [1 2] [3 4 5]"/> // Should fail
+        <p>"Annotated values are checked when the function is called. If they are known when the code is compiled, they are checked then instead."</p>
+        <Editor example="# Experimental!\n# Mean:Num[] ? Xs:Num₁\nMean ← ÷⧻⟜/+\nMean [1 2 6]\nMean \"abc\""/> // Should fail
+        <p>"Types that are not in these forms are treated as documentation only."</p>
+
+        <Hd id="overloads"><code>"# Overload!"</code></Hd>
+        <p>"A function marked with "<code>"# Overload!"</code>" is added as another implementation of the earlier function with the same name instead of replacing it. This is experimental."</p>
+        <p>"When the function is called, the first overload whose annotated arguments match is run. All overloads must have the same signature. An overload without annotated arguments matches anything."</p>
+        <Editor example="# Experimental!\n# ? X:Num₀\nDescribe ← $\"Number _\"\n# Overload!\n# ? X:Char₁\nDescribe ← $\"String _\"\n# Overload!\n# ? X:Num₁\nDescribe ← $\"List of _ numbers\" ⧻\nDescribe 5\nDescribe \"hi\"\nDescribe [1 2 3]"/>
+        <p>"If no overload matches, the error lists each candidate and why it did not match."</p>
+        <Editor example="# Experimental!\n# ? X:Num₀\nDescribe ← $\"Number _\"\n# Overload!\n# ? X:Char₁\nDescribe ← $\"String _\"\nDescribe {1 2}"/> // Should fail
+
         <Hd id="track-caller"><code>"# Track caller!"</code></Hd>
 
         <p>"When writing code that other people might use, it is important that error messages point to a useful place. When a function errors, the error message often refers to the base built-in function that emitted the error."</p>
@@ -240,6 +255,13 @@ pub fn Documentation() -> impl IntoView {
         <p><code>"# Deprecated!"</code>" works for constants, macros, and modules as well."</p>
         <Editor example="# Deprecated! Don't use it!\n┌─╴Foo\n  # Deprecated! It's no good!\n  Bar ← 5\n└─╴\nFoo~Bar"/> // Should fail
 
+        <Hd id="edition"><code>"# Edition!"</code></Hd>
+
+        <p>"The "<code>"# Edition!"</code>" semantic comment selects the language edition of a file. Later editions can change how code is interpreted without breaking files written for earlier ones."</p>
+        <p>"Files without an edition use edition "<code>"2024"</code>", where deprecated primitives and old glyphs still work with a warning. In edition "<code>"2025"</code>", they are errors."</p>
+        <Editor example="# Edition! 2025\n⸮5"/> // Should fail
+        <p>"The edition applies to the code after the comment in the same file, so it should go at the top. Imported files choose their own editions."</p>
+
         <Hd id="external"><code>"# External!"</code></Hd>
 
         <p>"The "<code>"# External!"</code>" semantic comment marks functions that are provided by Rust code. These functions don't require a Uiua implementation and will show up in the LSP."</p>
@@ -442,7 +442,8 @@ fn prim_docs() {
         for line in &prim.doc().lines {
             if let PrimDocLine::Example(ex) = line {
                 if [
-                    "&sl", "&tcpc", "&tlsc", "&ast", "&clip", "&frab", "&fmd", "&b",
+                    "&sl", "&tcpc", "&tlsc", "&udp", "&ws", "&http", "&ast", "&clip", "&frab",
+                    "&fmd", "&fwt", "&rls", "&dcs", "&b",
                 ]
                 .iter()
                 .any(|prim| ex.input().contains(prim))
@@ -886,7 +886,7 @@ F 2 9
 F 10 11"/>
 
         <Hd id="local-bindings">"A Note on Local Bindings"</Hd>
-        <p>"Bindings in Uiua can "<em>"only"</em>" be global. There is no way to give a name to a value within an inline function. A "<code>"←"</code>" inside "<code>"()"</code>"s is a syntax error."</p>
+        <p>"Bindings in Uiua can "<em>"only"</em>" be global. There is no way to give a name to a value within an inline function. A "<code>"←"</code>" inside "<code>"()"</code>"s is an error, unless you enable the "<A href="/docs/experimental#local-bindings">"experimental local bindings"</A>" feature."</p>
         <p>"This is a deliberate design decision. It forces you to write tacit code, a.k.a. code with functions that do not mention their arguments. Uiua is designed to make writing tacit code as workable as possible. "<em>"How"</em>" it does this will be discussed in "<A href="/tutorial/morestack">"later"</A>" "<A href="/tutorial/advancedarray">"sections"</A>"."</p>
 
         <Hd id="format-strings">"Format Strings"</Hd>
@@ -1615,7 +1615,7 @@ fn TutorialModules() -> impl IntoView {
 
         <Hd id="web-files">"Files on the Website"</Hd>
         <p>"Using files as modules involves loading files from the file system."</p>
-        <p>"This website has a virtual file system. You can write to virtual files with "<Prim prim=Sys(SysOp::FWriteAll)/>". You can also drag and drop files from your computer into the editor to make them available to import."</p>
+        <p>"This website has a virtual file system. You can write to virtual files with "<Prim prim=Sys(SysOp::FWriteAll)/>". You can also drag and drop files from your computer into the editor to make them available to import. Files in the "<A href="/pad">"pad"</A>" are saved between sessions."</p>
         <p>"There is also a test module that can always be imported as "<code>"example.ua"</code>". Its contents is:"</p>
         <Editor example=EXAMPLE_UA/>
 
@@ -1644,7 +1644,8 @@ fn TutorialModules() -> impl IntoView {
 
         <Hd id="visibility">"Visibility"</Hd>
         <p>"All bindings in a module bound with the normal "<code>"←"</code>" arrow are public and can be used by importers of the module."</p>
-        <p>"However, modules imported in modules, as well as their same-name imports (the names on lines that start with "<code>"~"</code>"), are private."</p>
+        <p>"This includes modules imported in modules, as well as their same-name imports (the names on lines that start with "<code>"~"</code>"). This means a module can re-export items from the modules it imports."</p>
+        <Editor example="┌─╴Mod\n  ~ \"example.ua\" ~ Square\n└─╴\nMod~Square 5"/>
         <p>"You may have noticed in the example file that one binding uses a special "<code>"↚"</code>" arrow. This indicates that the binding is private."</p>
         <p>"Private bindings cannot be accessed from outside the file in which they are defined."</p>
         <Editor example="~ \"example.ua\" ~ RangeDiff"/> // Should fail
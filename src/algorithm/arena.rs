@@ -0,0 +1,60 @@
+//! A buffer for building many arrays of rows at once
+
+use ecow::EcoVec;
+
+use crate::{cowslice::CowSlice, Array, ArrayValue, Shape};
+
+/// A single buffer that groups of rows are copied into
+///
+/// Each finished group becomes an [`Array`] whose data is a slice of the shared buffer,
+/// so building any number of groups only allocates once.
+/// The buffer is freed when the last of the groups is dropped.
+///
+/// Because the groups share the buffer, none of them is ever uniquely owned.
+/// An operation that would otherwise modify a group in place copies that group's
+/// rows into a new allocation first. This trades that copy for the per-group
+/// allocations that building the groups separately would need.
+pub(crate) struct RowArena<T> {
+    data: EcoVec<T>,
+    row_len: usize,
+    row_count: usize,
+    group_start: usize,
+    group_lens: Vec<usize>,
+}
+
+impl<T: ArrayValue> RowArena<T> {
+    /// Create an arena with room for the given number of rows
+    pub fn with_capacity(row_count: usize, row_len: usize) -> Self {
+        Self {
+            data: EcoVec::with_capacity(row_count * row_len),
+            row_len,
+            row_count: 0,
+            group_start: 0,
+            group_lens: Vec::new(),
+        }
+    }
+    /// Add a row to the current group
+    pub fn push_row(&mut self, row: &[T]) {
+        debug_assert_eq!(row.len(), self.row_len);
+        self.data.extend_from_slice(row);
+        self.row_count += 1;
+    }
+    /// Finish the current group
+    pub fn end_group(&mut self) {
+        self.group_lens.push(self.row_count - self.group_start);
+        self.group_start = self.row_count;
+    }
+    /// Turn the finished groups into arrays with the given row shape
+    pub fn into_groups(self, row_shape: Shape) -> impl ExactSizeIterator<Item = Array<T>> {
+        let data = CowSlice::from(self.data);
+        let row_len = self.row_len;
+        let mut start = 0;
+        self.group_lens.into_iter().map(move |len| {
+            let mut shape = row_shape.clone();
+            shape.insert(0, len);
+            let group = Array::new(shape, data.slice(start * row_len..(start + len) * row_len));
+            start += len;
+            group
+        })
+    }
+}
@@ -275,9 +275,7 @@ impl<T: ArrayValue> Array<T> {
                         other.shape
                     }
                 };
-                let rot_len = self.data.len();
-                other.data.extend_from_cowslice(self.data);
-                other.data.as_mut_slice().rotate_right(rot_len);
+                other.data.extend_front_from_slice(&self.data);
                 other.shape = target_shape;
                 other.shape[0] += 1;
                 // Combine map keys
@@ -337,7 +335,13 @@ impl<T: ArrayValue> Array<T> {
                         }
                     }
 
-                    if self.data.len() >= other.data.len() {
+                    // Extend whichever buffer can be extended in place. If neither can,
+                    // both are copied once into a new buffer.
+                    let append = match (self.data.is_unique(), other.data.is_unique()) {
+                        (true, true) => self.data.len() >= other.data.len(),
+                        (a_unique, b_unique) => a_unique || !b_unique,
+                    };
+                    if append {
                         if self.meta().label.is_none() {
                             if let Some(label) = other.take_label() {
                                 self.meta_mut().label = Some(label);
@@ -351,9 +355,7 @@ impl<T: ArrayValue> Array<T> {
                                 other.meta_mut().label = Some(label);
                             }
                         }
-                        let rot_len = self.data.len();
-                        other.data.extend_from_cowslice(self.data);
-                        other.data.as_mut_slice().rotate_right(rot_len);
+                        other.data.extend_front_from_slice(&self.data);
                         other.shape[0] += self.shape[0];
                         self = other;
                     }
@@ -629,6 +631,12 @@ impl<T: ArrayValue> Array<T> {
         } else {
             a_shape.first().copied().unwrap_or(1)
         };
+        if unjoin_count > self.row_count() {
+            return Err(env.error(format!(
+                "Cannot unjoin {unjoin_count} rows from an array with {} rows",
+                self.row_count()
+            )));
+        }
         let unjoined_slice;
         if is_end {
             let split_pos = self.element_count() - unjoin_count * row_len;
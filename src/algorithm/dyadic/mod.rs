@@ -26,7 +26,7 @@ use crate::{
     cowslice::{cowslice, CowSlice},
     val_as_arr,
     value::Value,
-    Shape, Uiua, UiuaResult, RNG,
+    ImplPrimitive, Shape, Uiua, UiuaResult,
 };
 
 macro_rules! par_if {
@@ -45,7 +45,8 @@ macro_rules! par_if {
 }
 
 use super::{
-    shape_prefixes_match, validate_size, validate_size_of, ArrayCmpSlice, FillContext, SizeError,
+    shape_prefixes_match, validate_size, validate_size_of, ArrayCmpSlice, FillContext, ReshapeMode,
+    SizeError,
 };
 
 impl Value {
@@ -184,7 +185,7 @@ impl<T: Clone + std::fmt::Debug + Send + Sync> Array<T> {
 
 impl Value {
     /// `reshape` this value with another
-    pub fn reshape(&mut self, shape: &Self, env: &Uiua) -> UiuaResult {
+    pub fn reshape(&mut self, shape: &Self, mode: ReshapeMode, env: &Uiua) -> UiuaResult {
         let target_shape = shape.as_ints_or_infs(
             env,
             "Shape should be a single integer \
@@ -192,14 +193,19 @@ impl Value {
         )?;
         if shape.rank() == 0 {
             let n = target_shape[0];
-            val_as_arr!(self, |a| a.reshape_scalar(n, env))
+            val_as_arr!(self, |a| a.reshape_scalar(n, mode, env))
         } else {
-            self.reshape_impl(&target_shape, env)
+            self.reshape_impl(&target_shape, mode, env)
         }
     }
-    pub(crate) fn reshape_impl(&mut self, dims: &[Result<isize, bool>], env: &Uiua) -> UiuaResult {
+    pub(crate) fn reshape_impl(
+        &mut self,
+        dims: &[Result<isize, bool>],
+        mode: ReshapeMode,
+        env: &Uiua,
+    ) -> UiuaResult {
         self.match_fill(env);
-        val_as_arr!(self, |a| a.reshape(dims, env))
+        val_as_arr!(self, |a| a.reshape(dims, mode, env))
     }
     pub(crate) fn undo_reshape(&mut self, old_shape: &Self, env: &Uiua) -> UiuaResult {
         if old_shape.as_nat(env, "").is_ok() {
@@ -210,7 +216,7 @@ impl Value {
             || orig_shape.iter().product::<usize>() == self.shape().iter().product::<usize>()
         {
             let orig_shape_spec: Vec<_> = orig_shape.iter().map(|&d| Ok(d as isize)).collect();
-            self.reshape_impl(&orig_shape_spec, env)
+            self.reshape_impl(&orig_shape_spec, ReshapeMode::Auto, env)
         } else {
             Err(env.error(format!(
                 "Cannot unreshape array because its old shape was {}, \
@@ -221,7 +227,7 @@ impl Value {
         }
     }
     pub(crate) fn reshape_scalar(&mut self, count: Result<isize, bool>, env: &Uiua) -> UiuaResult {
-        val_as_arr!(self, |a| a.reshape_scalar(count, env))
+        val_as_arr!(self, |a| a.reshape_scalar(count, ReshapeMode::Auto, env))
     }
 }
 
@@ -252,15 +258,37 @@ impl<T: Clone> Array<T> {
 }
 
 impl<T: ArrayValue> Array<T> {
+    /// Get the fill value to pad with in a [`ReshapeMode`]
+    ///
+    /// `Ok(None)` means that the data should be cycled instead.
+    fn reshape_fill(mode: ReshapeMode, env: &Uiua) -> UiuaResult<Option<T>> {
+        Ok(match mode {
+            ReshapeMode::Auto => env.scalar_fill::<T>().ok(),
+            ReshapeMode::Exact | ReshapeMode::Cycle => None,
+            ReshapeMode::Fill => Some(env.scalar_fill::<T>().map_err(|e| {
+                env.error(format!(
+                    "{} requires a fill value{e}",
+                    ImplPrimitive::ReshapeSub(mode)
+                ))
+                .fill()
+            })?),
+        })
+    }
     /// `reshape` this array by replicating it as the rows of a new array
-    pub fn reshape_scalar(&mut self, count: Result<isize, bool>, env: &Uiua) -> UiuaResult {
+    pub fn reshape_scalar(
+        &mut self,
+        count: Result<isize, bool>,
+        mode: ReshapeMode,
+        env: &Uiua,
+    ) -> UiuaResult {
         self.take_map_keys();
         match count {
             Ok(count) => {
+                let fill = Self::reshape_fill(mode, env)?;
                 if count < 0 {
                     self.reverse();
                 }
-                self.reshape_scalar_integer(count.unsigned_abs(), env.scalar_fill::<T>().ok())
+                self.reshape_scalar_integer(count.unsigned_abs(), fill)
                     .map_err(|e| env.error(e))
             }
             Err(rev) => {
@@ -272,9 +300,14 @@ impl<T: ArrayValue> Array<T> {
         }
     }
     /// `reshape` the array
-    pub fn reshape(&mut self, dims: &[Result<isize, bool>], env: &Uiua) -> UiuaResult {
-        let fill = env.scalar_fill::<T>();
-        let axes = derive_shape(&self.shape, dims, fill.is_ok(), env)?;
+    pub fn reshape(
+        &mut self,
+        dims: &[Result<isize, bool>],
+        mode: ReshapeMode,
+        env: &Uiua,
+    ) -> UiuaResult {
+        let fill = Self::reshape_fill(mode, env)?;
+        let axes = derive_shape(&self.shape, dims, fill.is_some(), env)?;
         if (axes.first()).map_or(true, |&d| d.unsigned_abs() != self.row_count()) {
             self.take_map_keys();
         }
@@ -284,20 +317,37 @@ impl<T: ArrayValue> Array<T> {
         let shape: Shape = axes.iter().map(|&s| s.unsigned_abs()).collect();
         validate_size::<T>(shape.iter().copied(), env)?;
         let target_len: usize = shape.iter().product();
+        if mode == ReshapeMode::Exact && self.data.len() != target_len {
+            return Err(env.error(format!(
+                "{} cannot reshape an array with {} element{} to shape {shape}, \
+                which has {target_len} element{}",
+                ImplPrimitive::ReshapeSub(mode),
+                self.data.len(),
+                if self.data.len() == 1 { "" } else { "s" },
+                if target_len == 1 { "" } else { "s" },
+            )));
+        }
         if self.data.len() < target_len {
-            match env.scalar_fill::<T>() {
-                Ok(fill) => {
+            match fill {
+                Some(fill) => {
                     let start = self.data.len();
                     self.data.extend_repeat(&fill, target_len - start);
                 }
-                Err(e) => {
+                None => {
                     if self.data.is_empty() {
                         if !shape.contains(&0) {
-                            return Err(env
-                                .error(format!(
+                            return Err(if mode == ReshapeMode::Cycle {
+                                env.error(format!(
+                                    "{} cannot cycle an empty array",
+                                    ImplPrimitive::ReshapeSub(mode)
+                                ))
+                            } else {
+                                let e = env.scalar_fill::<T>().err().unwrap_or_default();
+                                env.error(format!(
                                     "Cannot reshape empty array without a fill value{e}"
                                 ))
-                                .fill());
+                                .fill()
+                            });
                         }
                     } else if self.rank() == 0 {
                         self.data = cowslice![self.data[0].clone(); target_len];
@@ -755,10 +805,10 @@ impl<T: ArrayValue> Array<T> {
                     if count < 1.0 {
                         continue;
                     }
-                    into_slice.clone_from_slice(from_rows.next().expect(
-                        "number of true counts was verified \
-                        to match from row count",
-                    ))
+                    let from_row = from_rows
+                        .next()
+                        .ok_or_else(|| env.error("Undo keep ran out of rows to restore").bug())?;
+                    into_slice.clone_from_slice(from_row);
                 }
             }
             Ordering::Less => {
@@ -807,10 +857,9 @@ impl<T: ArrayValue> Array<T> {
                     if count < 1.0 {
                         rows.push(into_row);
                     } else {
-                        let from_row = from_rows.next().expect(
-                            "number of true counts was verified \
-                            to match from row count",
-                        );
+                        let from_row = from_rows.next().ok_or_else(|| {
+                            env.error("Undo keep ran out of rows to restore").bug()
+                        })?;
                         rows.extend(from_row.into_rows());
                     }
                 }
@@ -927,6 +976,38 @@ impl<T: ArrayValue> Array<T> {
     ) -> UiuaResult {
         let mut filled = false;
         let fill = env.scalar_fill::<T>();
+        if depth == 0 && by_depth == 0 && by.rank() <= 1 && by.element_count() == 1 {
+            if let &[row_count, ..] = &*self.shape {
+                if row_count > 0 {
+                    let by = by.data[0];
+                    let row_len = self.row_len();
+                    match fill {
+                        // Rotating only the rows lets the buffer move the shorter side
+                        Err(_) => {
+                            let mid = by.rem_euclid(row_count as isize) as usize;
+                            self.data.rotate_left(mid * row_len);
+                        }
+                        // A filled shift drops rows from one end and pads the other,
+                        // so only the shifted-in rows are written
+                        Ok(fill) => {
+                            let shift = by.unsigned_abs().min(row_count) * row_len;
+                            if by > 0 {
+                                self.data = self.data.slice(shift..);
+                                self.data.extend_repeat(&fill, shift);
+                            } else {
+                                self.data.truncate(self.data.len() - shift);
+                                self.data.extend_front_repeat_slice(&[fill], shift);
+                            }
+                            self.reset_meta_flags();
+                        }
+                    }
+                    if let Some(keys) = self.map_keys_mut() {
+                        keys.rotate(by);
+                    }
+                }
+                return Ok(());
+            }
+        }
         self.depth_slices(&by, depth, by_depth, env, |ash, a, bsh, b, env| {
             if bsh.len() > 1 {
                 return Err(env.error(format!("Cannot rotate by rank {} array", bsh.len())));
@@ -1101,6 +1182,16 @@ impl Array<f64> {
                 b.shape()
             )));
         }
+        if a.rank() == 2 && a_row_shape == b_row_shape {
+            let data = super::linalg::mul_rows(
+                &a.data,
+                a.row_count(),
+                &b.data,
+                b.row_count(),
+                env.rt.parallelism != crate::Parallelism::Off,
+            );
+            return Ok(Array::new([a.row_count(), b.row_count()], data.as_slice()));
+        }
         let prod_shape = if a_row_shape.len() >= b_row_shape.len() {
             &a_row_shape
         } else {
@@ -1142,7 +1233,8 @@ impl Array<f64> {
         if result_chunk_size > 0 {
             let iter = (a.row_slices()).zip(result_slice.chunks_exact_mut(result_chunk_size));
             par_if!(
-                a.row_count() > 100 || b.row_count() > 100,
+                env.rt.parallelism != crate::Parallelism::Off
+                    && (a.row_count() > 100 || b.row_count() > 100),
                 (iter.par_bridge()).for_each(|(a_row, res_row)| inner(a_row, res_row)),
                 iter.for_each(|(a_row, res_row)| inner(a_row, res_row))
             )
@@ -1496,7 +1588,7 @@ fn digits_needed_for_base(n: f64, base: f64) -> usize {
     if n == 0.0 {
         0
     } else {
-        n.abs().log(base).floor() as usize + 1
+        (n.abs().log(base).floor() as usize).saturating_add(1)
     }
 }
 
@@ -1511,6 +1603,9 @@ impl<T: RealArrayValue> Array<T> {
         if base.is_nan() {
             return Err(env.error("Base cannot be NaN"));
         }
+        if (-1.0..0.0).contains(&base) {
+            return Err(env.error("Negative base must be less than ¯1"));
+        }
         Ok(if base >= 0.0 {
             let max_row_len = self
                 .data
@@ -1538,6 +1633,9 @@ impl<T: RealArrayValue> Array<T> {
             for n in &self.data {
                 let mut row = Vec::new();
                 let mut n = n.to_f64();
+                if n.is_infinite() {
+                    return Err(env.error("Cannot get the digits of infinity in a negative base"));
+                }
                 while n.abs() > f64::EPSILON {
                     row.push(n.rem_euclid(base));
                     n = n.div_euclid(base);
@@ -1782,10 +1880,7 @@ impl Value {
     }
     /// Generate randomly seeded arrays
     pub fn gen(&self, seed: &Self, env: &Uiua) -> UiuaResult<Value> {
-        let mut hasher = DefaultHasher::new();
-        seed.hash(&mut hasher);
-        let seed = hasher.finish();
-        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut rng = seed.seeded_rng();
 
         const SHAPE_REQ: &str = "Shape must be an array of natural \
             numbers with at most rank 2";
@@ -1835,15 +1930,105 @@ impl Value {
             }
         })
     }
+    /// Sample random indices with the given weights
+    pub fn sample(&self, seed: &Self, weights: &Self, env: &Uiua) -> UiuaResult<Array<f64>> {
+        let shape = Shape::from(self.as_nats(env, "Shape must be a list of natural numbers")?);
+        let weights = weights.as_nums(env, "Weights must be a list of numbers")?;
+        if weights.iter().any(|w| !(w.is_finite() && *w >= 0.0)) {
+            return Err(env.error("Weights must be finite and non-negative"));
+        }
+        let mut cumulative = Vec::with_capacity(weights.len());
+        let mut total = 0.0;
+        for w in weights {
+            total += w;
+            cumulative.push(total);
+        }
+        if total <= 0.0 {
+            return Err(env.error("Weights must have a positive sum"));
+        }
+        // The last index with a positive weight
+        let last = cumulative.partition_point(|&c| c < total);
+        let elem_count = validate_size::<f64>(shape.iter().copied(), env)?;
+        let mut rng = seed.seeded_rng();
+        let mut data = eco_vec![0.0; elem_count];
+        for x in data.make_mut() {
+            let r = rng.gen::<f64>() * total;
+            *x = cumulative.partition_point(|&c| c <= r).min(last) as f64;
+        }
+        Ok(Array::new(shape, data))
+    }
+    /// Create a random number generator from a seed value
+    pub(crate) fn seeded_rng(&self) -> SmallRng {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        SmallRng::seed_from_u64(hasher.finish())
+    }
     /// Pick a random row of an array
-    pub fn random_row(&self, env: &Uiua) -> UiuaResult<Value> {
+    pub fn random_row(&self, env: &mut Uiua) -> UiuaResult<Value> {
         match self.row_count() {
             0 => Err(env.error("Cannot pick random row of an empty array").fill()),
             1 => Ok(self.row(0)),
             len => {
-                let i = RNG.with_borrow_mut(|rng| rng.gen_range(0..len));
+                let i = env.rt.rng.gen_range(0..len);
                 Ok(self.row(i))
             }
         }
     }
+    /// Seed the environment's random number generator
+    pub fn seed(&self, env: &mut Uiua) {
+        env.rt.rng = self.seeded_rng();
+    }
+    /// Split the environment's random number generator into independent seeds
+    pub fn seeds(&self, env: &mut Uiua) -> UiuaResult<Array<f64>> {
+        let n = self.as_nat(env, "Seed count must be a natural number")?;
+        validate_size::<f64>([n], env)?;
+        // Seeds are kept within the range of exactly representable integers
+        let data: EcoVec<f64> = (0..n)
+            .map(|_| env.rt.rng.gen_range(0..1u64 << f64::MANTISSA_DIGITS) as f64)
+            .collect();
+        Ok(data.into())
+    }
+    /// Generate an array of uniformly distributed random numbers in the range `[0, 1)`
+    pub fn uniform(&self, env: &mut Uiua) -> UiuaResult<Array<f64>> {
+        let (shape, mut data) = self.random_buffer(env)?;
+        for x in data.make_mut() {
+            *x = env.rt.rng.gen();
+        }
+        Ok(Array::new(shape, data))
+    }
+    /// Generate an array of normally distributed random numbers
+    ///
+    /// The numbers have a mean of `0` and a standard deviation of `1`.
+    pub fn normal(&self, env: &mut Uiua) -> UiuaResult<Array<f64>> {
+        let (shape, mut data) = self.random_buffer(env)?;
+        // Box-Muller transform, which generates numbers in pairs
+        for pair in data.make_mut().chunks_mut(2) {
+            let u: f64 = 1.0 - env.rt.rng.gen::<f64>();
+            let v: f64 = env.rt.rng.gen();
+            let r = (-2.0 * u.ln()).sqrt();
+            let theta = f64::consts::TAU * v;
+            pair[0] = r * theta.cos();
+            if let Some(x) = pair.get_mut(1) {
+                *x = r * theta.sin();
+            }
+        }
+        Ok(Array::new(shape, data))
+    }
+    /// Generate an array of random natural numbers less than a maximum
+    pub fn randint(&self, max: &Self, env: &mut Uiua) -> UiuaResult<Array<f64>> {
+        let max = max.as_nat(env, "Maximum must be a natural number")?;
+        if max == 0 {
+            return Err(env.error("Maximum must be positive"));
+        }
+        let (shape, mut data) = self.random_buffer(env)?;
+        for x in data.make_mut() {
+            *x = env.rt.rng.gen_range(0..max) as f64;
+        }
+        Ok(Array::new(shape, data))
+    }
+    pub(crate) fn random_buffer(&self, env: &Uiua) -> UiuaResult<(Shape, EcoVec<f64>)> {
+        let shape = Shape::from(self.as_nats(env, "Shape must be a list of natural numbers")?);
+        let elem_count = validate_size::<f64>(shape.iter().copied(), env)?;
+        Ok((shape, eco_vec![0.0; elem_count]))
+    }
 }
@@ -11,7 +11,7 @@ use ecow::{eco_vec, EcoVec};
 use crate::{
     algorithm::{max_shape, validate_size},
     array::*,
-    cowslice::cowslice,
+    cowslice::{cowslice, CowSlice},
     value::Value,
     Shape, Uiua, UiuaResult,
 };
@@ -100,6 +100,166 @@ impl<T: ArrayValue> Array<T> {
     }
 }
 
+#[derive(Clone, Copy)]
+enum SetOp {
+    Union,
+    Intersect,
+    Difference,
+}
+
+impl SetOp {
+    fn name(self) -> &'static str {
+        match self {
+            SetOp::Union => "union",
+            SetOp::Intersect => "intersection",
+            SetOp::Difference => "difference",
+        }
+    }
+}
+
+impl Value {
+    /// Get the `union` of the rows of this value and another
+    pub fn union(self, other: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(other, SetOp::Union, env)
+    }
+    /// Get the rows of another value that are also in this one
+    pub fn intersect(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(from, SetOp::Intersect, env)
+    }
+    /// Get the rows of another value that are not in this one
+    pub fn difference(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(from, SetOp::Difference, env)
+    }
+    fn set_op(self, other: Self, op: SetOp, env: &Uiua) -> UiuaResult<Self> {
+        self.generic_bin_into(
+            other,
+            |a, b| a.set_op(b, op, env).map(Into::into),
+            |a, b| a.set_op(b, op, env).map(Into::into),
+            |a, b| a.set_op(b, op, env).map(Into::into),
+            |a, b| a.set_op(b, op, env).map(Into::into),
+            |a, b| a.set_op(b, op, env).map(Into::into),
+            |a, b| {
+                env.error(format!(
+                    "Cannot get the {} of {} array and {} array",
+                    op.name(),
+                    a.type_name(),
+                    b.type_name(),
+                ))
+            },
+        )
+    }
+}
+
+/// A set of array rows
+///
+/// Rows with wildcards cannot be hashed, so those fall back to a linear scan
+enum RowSet<'a, T> {
+    Hashed(HashSet<ArrayCmpSlice<'a, T>>),
+    Scanned(Vec<&'a [T]>),
+}
+
+impl<'a, T: ArrayValue> RowSet<'a, T> {
+    fn new(has_wildcard: bool) -> Self {
+        if has_wildcard {
+            RowSet::Scanned(Vec::new())
+        } else {
+            RowSet::Hashed(HashSet::new())
+        }
+    }
+    fn contains(&self, row: &'a [T]) -> bool {
+        match self {
+            RowSet::Hashed(set) => set.contains(&ArrayCmpSlice(row)),
+            RowSet::Scanned(rows) => rows.iter().any(|&r| ArrayCmpSlice(r) == ArrayCmpSlice(row)),
+        }
+    }
+    fn insert(&mut self, row: &'a [T]) -> bool {
+        match self {
+            RowSet::Hashed(set) => set.insert(ArrayCmpSlice(row)),
+            RowSet::Scanned(rows) => {
+                let new = !rows.iter().any(|&r| ArrayCmpSlice(r) == ArrayCmpSlice(row));
+                if new {
+                    rows.push(row);
+                }
+                new
+            }
+        }
+    }
+}
+
+impl<T: ArrayValue> Array<T> {
+    /// Get the `union` of the rows of this array and another
+    pub fn union(self, other: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(other, SetOp::Union, env)
+    }
+    /// Get the rows of another array that are also in this one
+    pub fn intersect(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(from, SetOp::Intersect, env)
+    }
+    /// Get the rows of another array that are not in this one
+    pub fn difference(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.set_op(from, SetOp::Difference, env)
+    }
+    fn set_op(mut self, mut other: Self, op: SetOp, env: &Uiua) -> UiuaResult<Self> {
+        self.take_map_keys();
+        other.take_map_keys();
+        // Empty lists take on the row shape of the other array
+        if self.shape == [0] && other.rank() > 0 {
+            self.shape = other.shape.clone();
+            self.shape[0] = 0;
+        } else if other.shape == [0] && self.rank() > 0 {
+            other.shape = self.shape.clone();
+            other.shape[0] = 0;
+        }
+        // Scalars and single rows are treated as lists of one row
+        match self.rank().cmp(&other.rank()) {
+            Ordering::Equal if self.rank() == 0 => {
+                self.shape.fix();
+                other.shape.fix();
+            }
+            Ordering::Less if self.rank() + 1 == other.rank() => self.shape.fix(),
+            Ordering::Greater if other.rank() + 1 == self.rank() => other.shape.fix(),
+            _ => {}
+        }
+        if self.rank() != other.rank() || self.shape[1..] != other.shape[1..] {
+            return Err(env.error(format!(
+                "Cannot get the {} of arrays with shapes {} and {}",
+                op.name(),
+                self.shape,
+                other.shape
+            )));
+        }
+        let has_wildcard =
+            self.data.iter().any(T::has_wildcard) || other.data.iter().any(T::has_wildcard);
+        let mut seen = RowSet::new(has_wildcard);
+        let mut rows = Vec::new();
+        match op {
+            SetOp::Union => {
+                for row in self.row_slices().chain(other.row_slices()) {
+                    if seen.insert(row) {
+                        rows.push(row);
+                    }
+                }
+            }
+            SetOp::Intersect | SetOp::Difference => {
+                let mut members = RowSet::new(has_wildcard);
+                for row in self.row_slices() {
+                    members.insert(row);
+                }
+                let want = matches!(op, SetOp::Intersect);
+                for row in other.row_slices() {
+                    if members.contains(row) == want && seen.insert(row) {
+                        rows.push(row);
+                    }
+                }
+            }
+        }
+        let mut shape = other.shape.clone();
+        shape[0] = rows.len();
+        let data: CowSlice<T> = rows.into_iter().flatten().cloned().collect();
+        Ok(Array::new(shape, data))
+    }
+}
+
 impl Value {
     /// Get the `index of` the rows of this value in another
     pub fn index_of(&self, haystack: &Value, env: &Uiua) -> UiuaResult<Value> {
@@ -578,3 +738,78 @@ impl<T: ArrayValue> Array<T> {
         Ok(val)
     }
 }
+
+impl Value {
+    /// Assign values to bins given edges or a bin count
+    ///
+    /// Returns the count of values in each bin and the bin index of each value
+    pub fn bin(&self, values: &Self, env: &Uiua) -> UiuaResult<(Array<f64>, Array<f64>)> {
+        let values = match values {
+            Value::Num(arr) => arr.clone(),
+            Value::Byte(arr) => arr.convert_ref(),
+            val => {
+                return Err(env.error(format!(
+                    "Cannot bin {}, only numbers",
+                    val.type_name_plural()
+                )))
+            }
+        };
+        let edges: Vec<f64> = if self.rank() == 0 {
+            let count = self.as_nat(env, "Bin count must be a natural number")?;
+            if count == 0 {
+                return Err(env.error("Bin count must be positive"));
+            }
+            validate_size::<f64>([count.saturating_add(1)], env)?;
+            let (mut min, mut max) = (values.data.iter())
+                .filter(|n| !n.is_nan())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &n| {
+                    (min.min(n), max.max(n))
+                });
+            if min > max {
+                (min, max) = (0.0, 1.0);
+            } else if min == max {
+                (min, max) = (min - 0.5, max + 0.5);
+            }
+            (0..=count)
+                .map(|i| min + (max - min) * i as f64 / count as f64)
+                .collect()
+        } else {
+            if self.rank() != 1 {
+                return Err(env.error(format!(
+                    "Bin edges must be a list, but they have shape {}",
+                    self.shape()
+                )));
+            }
+            let edges = self.as_nums(env, "Bin edges must be a list of numbers")?;
+            if edges.len() < 2 {
+                return Err(env.error("There must be at least 2 bin edges"));
+            }
+            if edges.iter().any(|e| e.is_nan()) || edges.windows(2).any(|w| w[0] > w[1]) {
+                return Err(env.error("Bin edges must be sorted"));
+            }
+            edges
+        };
+        let bins = edges.len() - 1;
+        let (first, last) = (edges[0], edges[bins]);
+        let mut counts = vec![0.0; bins];
+        let indices: EcoVec<f64> = (values.data.iter())
+            .map(|&n| {
+                if !(first..=last).contains(&n) {
+                    return -1.0;
+                }
+                // The last bin includes its upper edge
+                let i = if n == last {
+                    bins - 1
+                } else {
+                    edges.partition_point(|&e| e <= n) - 1
+                };
+                counts[i] += 1.0;
+                i as f64
+            })
+            .collect();
+        Ok((
+            Array::from(counts.as_slice()),
+            Array::new(values.shape.clone(), indices),
+        ))
+    }
+}
@@ -508,9 +508,8 @@ impl<T: ArrayValue> Array<T> {
                             let diff = abs_taking - row_count;
                             if fill_elems > 0 {
                                 let reps = diff * row_len / fill_elems;
-                                self.data.extend_repeat_slice(&fill.data, reps);
+                                self.data.extend_front_repeat_slice(&fill.data, reps);
                             }
-                            self.data.as_mut_slice().rotate_right(diff * row_len);
                         }
                         Err(e) => {
                             return Err(env
@@ -525,8 +524,7 @@ impl<T: ArrayValue> Array<T> {
                         }
                     }
                 } else {
-                    self.data.as_mut_slice().rotate_right(abs_taking * row_len);
-                    self.data.truncate(abs_taking * row_len);
+                    self.data = self.data.slice((row_count - abs_taking) * row_len..);
                 }
                 if let Some(s) = self.shape.get_mut(0) {
                     *s = if filled {
@@ -675,10 +673,10 @@ impl<T: ArrayValue> Array<T> {
                 let row_count = self.row_count();
                 let abs_dropping = dropping.unsigned_abs().min(row_count);
                 if dropping >= 0 {
-                    self.data.as_mut_slice().rotate_left(abs_dropping * row_len);
+                    self.data = self.data.slice(abs_dropping * row_len..);
+                } else {
                     self.data.truncate((row_count - abs_dropping) * row_len);
                 }
-                self.data.truncate((row_count - abs_dropping) * row_len);
                 if self.shape.is_empty() {
                     self.shape.push(1);
                 }
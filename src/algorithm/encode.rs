@@ -1,5 +1,6 @@
 //! En/decode Uiua arrays to/from media formats
 
+use ecow::EcoVec;
 #[cfg(feature = "audio_encode")]
 use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 #[cfg(feature = "image")]
@@ -8,7 +9,7 @@ use serde::*;
 
 use crate::SysBackend;
 #[allow(unused_imports)]
-use crate::{Array, Uiua, UiuaResult, Value};
+use crate::{algorithm::validate_size_impl, Array, Boxed, Complex, Uiua, UiuaResult, Value};
 
 /// Conversion of a value to some media format based on the value's shape
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +22,144 @@ pub enum SmartOutput {
     Svg { svg: String, original: Value },
 }
 
+/// Options for reading and writing delimiter-separated values
+#[derive(Debug, Clone, Copy)]
+pub struct DsvOptions {
+    /// The field delimiter
+    pub delimiter: u8,
+    /// The quote character
+    pub quote: u8,
+    /// Whether there is a header row
+    pub header: bool,
+}
+
+impl Default for DsvOptions {
+    fn default() -> Self {
+        DsvOptions {
+            delimiter: b',',
+            quote: b'"',
+            header: false,
+        }
+    }
+}
+
+impl DsvOptions {
+    /// Parse options from a value
+    ///
+    /// The first character is the delimiter, the second is the quote character,
+    /// and a boolean sets whether there is a header row.
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let mut options = DsvOptions::default();
+        let opts: Vec<Value> = if value.rank() == 0 {
+            vec![value.clone().unboxed()]
+        } else {
+            value.rows().map(Value::unboxed).collect()
+        };
+        let mut chars = 0;
+        let mut set_header = false;
+        for opt in opts {
+            match opt {
+                Value::Char(c) if c.rank() == 0 => {
+                    let c = u8::try_from(c.data[0])
+                        .map_err(|_| env.error("CSV delimiter and quote must be ASCII"))?;
+                    match chars {
+                        0 => options.delimiter = c,
+                        1 => options.quote = c,
+                        _ => return Err(env.error("CSV options may have at most 2 characters")),
+                    }
+                    chars += 1;
+                }
+                Value::Num(_) | Value::Byte(_) if opt.rank() == 0 => {
+                    if set_header {
+                        return Err(env.error("Cannot set CSV header option twice"));
+                    }
+                    options.header = opt.as_bool(env, "CSV header option must be a boolean")?;
+                    set_header = true;
+                }
+                opt => {
+                    return Err(env.error(format!(
+                        "CSV options must be scalar characters or booleans, \
+                        but an option is {}",
+                        opt.type_name_plural()
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The style of table rendered by [`Primitive::Tabulate`](crate::Primitive::Tabulate)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// A Markdown table
+    Markdown,
+    /// Comma-separated values
+    Csv,
+    /// Aligned plain text
+    Text,
+}
+
+/// Options for rendering tables
+#[derive(Debug, Clone, Copy)]
+pub struct TableOptions {
+    /// The table style
+    pub style: TableStyle,
+    /// Whether the first row is a header row
+    pub header: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            style: TableStyle::Text,
+            header: false,
+        }
+    }
+}
+
+impl TableOptions {
+    /// Parse options from a value
+    ///
+    /// A string sets the style, and a boolean sets whether the first row is a header row.
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let mut options = TableOptions::default();
+        let opts: Vec<Value> = if value.rank() == 0 || matches!(value, Value::Char(_)) {
+            vec![value.clone().unboxed()]
+        } else {
+            value.rows().map(Value::unboxed).collect()
+        };
+        for opt in opts {
+            match opt {
+                Value::Char(_) if opt.rank() <= 1 => {
+                    options.style = match opt.as_string(env, "")?.as_str() {
+                        "md" | "markdown" => TableStyle::Markdown,
+                        "csv" => TableStyle::Csv,
+                        "text" | "txt" => TableStyle::Text,
+                        style => {
+                            return Err(env.error(format!(
+                                "Unknown table style {style:?}. \
+                                Valid styles are \"md\", \"csv\", and \"text\"."
+                            )))
+                        }
+                    }
+                }
+                Value::Num(_) | Value::Byte(_) if opt.rank() == 0 => {
+                    options.header = opt.as_bool(env, "Table header option must be a boolean")?;
+                }
+                opt => {
+                    return Err(env.error(format!(
+                        "Table options must be strings or booleans, \
+                        but an option is {}",
+                        opt.type_name_plural()
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
 impl SmartOutput {
     /// Convert a value to a SmartOutput
     pub fn from_value(value: Value, backend: &dyn SysBackend) -> Self {
@@ -101,7 +240,9 @@ pub(crate) fn image_encode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "image"))]
-    Err(env.error("Image encoding is not supported in this environment"))
+    Err(env
+        .error("Image encoding is not supported in this environment")
+        .system())
 }
 
 pub(crate) fn image_decode(env: &mut Uiua) -> UiuaResult {
@@ -139,7 +280,9 @@ pub(crate) fn image_decode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "image"))]
-    Err(env.error("Image decoding is not supported in this environment"))
+    Err(env
+        .error("Image decoding is not supported in this environment")
+        .system())
 }
 
 pub(crate) fn gif_encode(env: &mut Uiua) -> UiuaResult {
@@ -152,7 +295,25 @@ pub(crate) fn gif_encode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "gif"))]
-    Err(env.error("GIF encoding is not supported in this environment"))
+    Err(env
+        .error("GIF encoding is not supported in this environment")
+        .system())
+}
+
+pub(crate) fn apng_encode(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "apng")]
+    {
+        let frame_rate = env.pop(1)?.as_num(env, "Frame rate must be a number")?;
+        let value = env.pop(2)?;
+        let bytes =
+            crate::encode::value_to_apng_bytes(&value, frame_rate).map_err(|e| env.error(e))?;
+        env.push(Array::<u8>::from(bytes.as_slice()));
+        Ok(())
+    }
+    #[cfg(not(feature = "apng"))]
+    Err(env
+        .error("APNG encoding is not supported in this environment")
+        .system())
 }
 
 pub(crate) fn gif_decode(env: &mut Uiua) -> UiuaResult {
@@ -168,7 +329,9 @@ pub(crate) fn gif_decode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "gif"))]
-    Err(env.error("GIF encoding is not supported in this environment"))
+    Err(env
+        .error("GIF encoding is not supported in this environment")
+        .system())
 }
 
 pub(crate) fn audio_encode(env: &mut Uiua) -> UiuaResult {
@@ -198,7 +361,9 @@ pub(crate) fn audio_encode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "audio_encode"))]
-    Err(env.error("Audio encoding is not supported in this environment"))
+    Err(env
+        .error("Audio encoding is not supported in this environment")
+        .system())
 }
 
 pub(crate) fn audio_decode(env: &mut Uiua) -> UiuaResult {
@@ -233,7 +398,9 @@ pub(crate) fn audio_decode(env: &mut Uiua) -> UiuaResult {
         Ok(())
     }
     #[cfg(not(feature = "audio_encode"))]
-    Err(env.error("Audio decoding is not supported in this environment"))
+    Err(env
+        .error("Audio decoding is not supported in this environment")
+        .system())
 }
 
 #[doc(hidden)]
@@ -519,24 +686,28 @@ fn array_from_wav_bytes_impl<T: hound::Sample>(
 #[doc(hidden)]
 #[cfg(feature = "gif")]
 pub fn value_to_gif_bytes(value: &Value, frame_rate: f64) -> Result<Vec<u8>, String> {
+    let frames = (value.rows())
+        .map(|row| value_to_image(&row).map(|image| image.into_rgba8()))
+        .collect::<Result<Vec<_>, _>>()?;
+    images_to_gif_bytes(frames, frame_rate)
+}
+
+#[doc(hidden)]
+#[cfg(feature = "gif")]
+pub fn images_to_gif_bytes(
+    frames: Vec<image::RgbaImage>,
+    frame_rate: f64,
+) -> Result<Vec<u8>, String> {
     use std::collections::{HashMap, HashSet};
 
     use color_quant::NeuQuant;
     use gif::{DisposalMethod, Frame};
     use image::Rgba;
 
-    if value.row_count() == 0 {
+    let Some(first) = frames.first() else {
         return Err("Cannot convert empty array into GIF".into());
-    }
-    let mut frames = Vec::with_capacity(value.row_count());
-    let mut width = 0;
-    let mut height = 0;
-    for row in value.rows() {
-        let image = value_to_image(&row)?.into_rgba8();
-        width = image.width();
-        height = image.height();
-        frames.push(image);
-    }
+    };
+    let (width, height) = first.dimensions();
     if width > u16::MAX as u32 || height > u16::MAX as u32 {
         return Err(format!(
             "GIF dimensions must be at most {}x{}, but the frames are {}x{}",
@@ -632,6 +803,32 @@ pub fn value_to_gif_bytes(value: &Value, frame_rate: f64) -> Result<Vec<u8>, Str
     Ok(bytes.into_inner())
 }
 
+#[doc(hidden)]
+#[cfg(feature = "apng")]
+pub fn value_to_apng_bytes(value: &Value, frame_rate: f64) -> Result<Vec<u8>, String> {
+    if value.row_count() == 0 {
+        return Err("Cannot convert empty array into APNG".into());
+    }
+    let frames = (value.rows())
+        .map(|row| value_to_image(&row).map(|image| image.into_rgba8()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (width, height) = frames[0].dimensions();
+    const MIN_FRAME_RATE: f64 = 1.0 / 60.0;
+    let delay = ((1.0 / frame_rate.max(MIN_FRAME_RATE)).abs() * 1000.0).round() as u16;
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    (encoder.set_animated(frames.len() as u32, 0)).map_err(|e| e.to_string())?;
+    (encoder.set_frame_delay(delay, 1000)).map_err(|e| e.to_string())?;
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    for image in frames {
+        (writer.write_image_data(image.as_raw())).map_err(|e| e.to_string())?;
+    }
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
 #[doc(hidden)]
 #[cfg(feature = "gif")]
 pub fn gif_bytes_to_value(bytes: &[u8]) -> Result<(f64, Value), gif::DecodingError> {
@@ -680,13 +877,865 @@ pub fn gif_bytes_to_value(bytes: &[u8]) -> Result<(f64, Value), gif::DecodingErr
     Ok((frame_rate, num))
 }
 
+pub(crate) fn npy_encode(env: &mut Uiua) -> UiuaResult {
+    let value = env.pop(1)?;
+    let bytes = value_to_npy_bytes(&value).map_err(|e| env.error(e))?;
+    env.push(Array::<u8>::from(bytes.as_slice()));
+    Ok(())
+}
+
+pub(crate) fn npy_decode(env: &mut Uiua) -> UiuaResult {
+    let bytes = env
+        .pop(1)?
+        .as_bytes(env, "Npy bytes must be a byte array")?;
+    let value = npy_bytes_to_value(&bytes).map_err(|e| env.error(e))?;
+    env.push(value);
+    Ok(())
+}
+
+pub(crate) fn npz_encode(env: &mut Uiua) -> UiuaResult {
+    let value = env.pop(1)?;
+    if !value.is_map() {
+        return Err(env.error("Npz encoding requires a map of names to arrays"));
+    }
+    let (keys, values) = value.unmap(env)?;
+    let names = keys.as_strings(env, "Npz array names must be strings")?;
+    let arrays: Vec<(String, Value)> = names
+        .into_iter()
+        .zip(values.into_rows().map(Value::unboxed))
+        .collect();
+    let bytes = arrays_to_npz_bytes(&arrays).map_err(|e| env.error(e))?;
+    env.push(Array::<u8>::from(bytes.as_slice()));
+    Ok(())
+}
+
+pub(crate) fn npz_decode(env: &mut Uiua) -> UiuaResult {
+    let bytes = env
+        .pop(1)?
+        .as_bytes(env, "Npz bytes must be a byte array")?;
+    let arrays = npz_bytes_to_arrays(&bytes).map_err(|e| env.error(e))?;
+    let mut keys = EcoVec::new();
+    let mut values = EcoVec::new();
+    for (name, value) in arrays {
+        keys.push(Boxed(name.into()));
+        values.push(Boxed(value));
+    }
+    let mut values: Value = Array::from(values).into();
+    values.map(Array::from(keys).into(), env)?;
+    env.push(values);
+    Ok(())
+}
+
+pub(crate) fn arrow_encode(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "arrow")]
+    {
+        let value = env.pop(1)?;
+        if !value.is_map() {
+            return Err(env.error("Arrow encoding requires a map of column names to columns"));
+        }
+        let (keys, values) = value.unmap(env)?;
+        let names = keys.as_strings(env, "Arrow column names must be strings")?;
+        let columns: Vec<(String, Value)> = names
+            .into_iter()
+            .zip(values.into_rows().map(Value::unboxed))
+            .collect();
+        let bytes = crate::arrow::columns_to_ipc_bytes(&columns).map_err(|e| env.error(e))?;
+        env.push(Array::<u8>::from(bytes.as_slice()));
+        Ok(())
+    }
+    #[cfg(not(feature = "arrow"))]
+    Err(env
+        .error("Arrow encoding is not supported in this environment")
+        .system())
+}
+
+pub(crate) fn arrow_decode(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "arrow")]
+    {
+        let bytes = env
+            .pop(1)?
+            .as_bytes(env, "Arrow bytes must be a byte array")?;
+        let columns = crate::arrow::ipc_bytes_to_columns(&bytes).map_err(|e| env.error(e))?;
+        let mut keys = EcoVec::new();
+        let mut values = EcoVec::new();
+        for (name, value) in columns {
+            keys.push(Boxed(name.into()));
+            values.push(Boxed(value));
+        }
+        let mut values: Value = Array::from(values).into();
+        values.map(Array::from(keys).into(), env)?;
+        env.push(values);
+        Ok(())
+    }
+    #[cfg(not(feature = "arrow"))]
+    Err(env
+        .error("Arrow decoding is not supported in this environment")
+        .system())
+}
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Encode a value in NumPy's `.npy` format
+///
+/// Bytes are encoded as `u1`, numbers as `f8`, complex numbers as `c16`, and characters as `U1`.
+pub fn value_to_npy_bytes(value: &Value) -> Result<Vec<u8>, String> {
+    let (descr, data): (&str, Vec<u8>) = match value {
+        Value::Byte(arr) => ("|u1", arr.data.to_vec()),
+        Value::Num(arr) => (
+            "<f8",
+            arr.data.iter().flat_map(|n| n.to_le_bytes()).collect(),
+        ),
+        Value::Complex(arr) => (
+            "<c16",
+            (arr.data.iter())
+                .flat_map(|c| c.re.to_le_bytes().into_iter().chain(c.im.to_le_bytes()))
+                .collect(),
+        ),
+        Value::Char(arr) => (
+            "<U1",
+            (arr.data.iter())
+                .flat_map(|&c| (c as u32).to_le_bytes())
+                .collect(),
+        ),
+        Value::Box(_) => return Err("Cannot encode boxes as npy".into()),
+    };
+    let shape = match value.shape().dims() {
+        [] => "()".to_string(),
+        [n] => format!("({n},)"),
+        dims => format!(
+            "({})",
+            dims.iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    // The header is padded so that the data is 64-byte aligned
+    let prefix_len = if header.len() + 11 > u16::MAX as usize {
+        12
+    } else {
+        10
+    };
+    while (prefix_len + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+    let mut bytes = Vec::with_capacity(prefix_len + header.len() + data.len());
+    bytes.extend_from_slice(NPY_MAGIC);
+    if prefix_len == 10 {
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    } else {
+        bytes.extend_from_slice(&[2, 0]);
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    }
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend(data);
+    Ok(bytes)
+}
+
+/// Decode a value from NumPy's `.npy` format
+///
+/// Booleans and unsigned bytes become byte arrays. Other integer and float types become number arrays.
+/// Unicode strings become character arrays, with an extra axis for strings longer than 1 character.
+pub fn npy_bytes_to_value(bytes: &[u8]) -> Result<Value, String> {
+    let rest = (bytes.strip_prefix(NPY_MAGIC)).ok_or("Data is not in npy format")?;
+    let (header, data) = match rest {
+        [1, _, a, b, rest @ ..] => {
+            let len = u16::from_le_bytes([*a, *b]) as usize;
+            (rest.get(..len), rest.get(len..))
+        }
+        [2 | 3, _, a, b, c, d, rest @ ..] => {
+            let len = u32::from_le_bytes([*a, *b, *c, *d]) as usize;
+            (rest.get(..len), rest.get(len..))
+        }
+        [major, ..] => return Err(format!("Unsupported npy version {major}")),
+        [] => return Err("Npy header is missing".into()),
+    };
+    let (Some(header), Some(data)) = (header, data) else {
+        return Err("Npy header is truncated".into());
+    };
+    let header = std::str::from_utf8(header).map_err(|_| "Npy header is not valid text")?;
+
+    // Parse the header dictionary
+    let field = |name: &str| -> Result<&str, String> {
+        let start = header
+            .find(&format!("'{name}'"))
+            .ok_or_else(|| format!("Npy header is missing {name:?}"))?;
+        let rest = header[start + name.len() + 2..].trim_start();
+        let rest = rest.strip_prefix(':').ok_or("Invalid npy header")?;
+        Ok(rest.trim_start())
+    };
+    let descr = field("descr")?;
+    let descr = (descr.strip_prefix('\'').and_then(|s| s.split('\'').next()))
+        .ok_or("Invalid npy dtype description")?;
+    let fortran_order = field("fortran_order")?.starts_with("True");
+    let shape = field("shape")?;
+    let shape =
+        (shape.strip_prefix('(').and_then(|s| s.split(')').next())).ok_or("Invalid npy shape")?;
+    let mut shape: Vec<usize> = (shape.split(','))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| format!("Invalid npy dimension {s:?}"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Parse the dtype
+    let (big_endian, descr) = match descr.as_bytes().first() {
+        Some(b'>') => (true, &descr[1..]),
+        Some(b'<' | b'|' | b'=') => (false, &descr[1..]),
+        _ => (false, descr),
+    };
+    let kind = descr.chars().next().ok_or("Missing npy dtype")?;
+    let size: usize = (descr[kind.len_utf8()..].parse())
+        .map_err(|_| format!("Unsupported npy dtype {descr:?}"))?;
+    let too_large = || format!("Npy shape {shape:?} is too large");
+    let elem_size = if kind == 'U' {
+        size.checked_mul(4)
+    } else {
+        Some(size)
+    }
+    .ok_or_else(too_large)?;
+    let count = (shape.iter()).try_fold(1usize, |acc, &dim| acc.checked_mul(dim));
+    let count = count.ok_or_else(too_large)?;
+    validate_size_impl(elem_size.max(1), shape.iter().copied()).map_err(|e| e.to_string())?;
+    let byte_count = count.checked_mul(elem_size).ok_or_else(too_large)?;
+    let data = (data.get(..byte_count)).ok_or("Npy data is truncated")?;
+    let elems = data.chunks_exact(elem_size.max(1)).map(|chunk| {
+        let mut chunk = chunk.to_vec();
+        if big_endian && kind != 'U' {
+            chunk.reverse();
+        }
+        chunk
+    });
+    macro_rules! nums {
+        ($ty:ty) => {
+            elems
+                .map(|c| <$ty>::from_le_bytes(c.try_into().unwrap()) as f64)
+                .collect::<EcoVec<f64>>()
+        };
+    }
+    let value: Value = match (kind, size) {
+        ('b' | 'u', 1) => Array::new(shape.as_slice(), EcoVec::from(data)).into(),
+        ('i', 1) => Array::new(shape.as_slice(), nums!(i8)).into(),
+        ('i', 2) => Array::new(shape.as_slice(), nums!(i16)).into(),
+        ('i', 4) => Array::new(shape.as_slice(), nums!(i32)).into(),
+        ('i', 8) => Array::new(shape.as_slice(), nums!(i64)).into(),
+        ('u', 2) => Array::new(shape.as_slice(), nums!(u16)).into(),
+        ('u', 4) => Array::new(shape.as_slice(), nums!(u32)).into(),
+        ('u', 8) => Array::new(shape.as_slice(), nums!(u64)).into(),
+        ('f', 2) => {
+            let data: EcoVec<f64> = elems
+                .map(|c| f16_to_f64(u16::from_le_bytes([c[0], c[1]])))
+                .collect();
+            Array::new(shape.as_slice(), data).into()
+        }
+        ('f', 4) => Array::new(shape.as_slice(), nums!(f32)).into(),
+        ('f', 8) => Array::new(shape.as_slice(), nums!(f64)).into(),
+        ('c', 8 | 16) => {
+            let data: EcoVec<Complex> = elems
+                .map(|c| {
+                    let (re, im) = c.split_at(size / 2);
+                    let (mut re, mut im) = (re.to_vec(), im.to_vec());
+                    if big_endian {
+                        // Reversing the whole element also swapped the parts
+                        std::mem::swap(&mut re, &mut im);
+                    }
+                    let part = |bytes: &[u8]| {
+                        if size == 8 {
+                            f32::from_le_bytes(bytes.try_into().unwrap()) as f64
+                        } else {
+                            f64::from_le_bytes(bytes.try_into().unwrap())
+                        }
+                    };
+                    Complex::new(part(&re), part(&im))
+                })
+                .collect();
+            Array::new(shape.as_slice(), data).into()
+        }
+        ('U', _) => {
+            let mut chars = EcoVec::with_capacity(count * size);
+            for c in data.chunks_exact(4) {
+                let c: [u8; 4] = c.try_into().unwrap();
+                let n = if big_endian {
+                    u32::from_be_bytes(c)
+                } else {
+                    u32::from_le_bytes(c)
+                };
+                let c = char::from_u32(n).ok_or("Invalid character in npy data")?;
+                chars.push(if c == '\0' { ' ' } else { c });
+            }
+            if size != 1 {
+                shape.push(size);
+            }
+            Array::new(shape.as_slice(), chars).into()
+        }
+        ('S', _) => {
+            if size != 1 {
+                shape.push(size);
+            }
+            Array::new(shape.as_slice(), EcoVec::from(data)).into()
+        }
+        _ => return Err(format!("Unsupported npy dtype {descr:?}")),
+    };
+    Ok(if fortran_order {
+        fortran_to_c_order(value)
+    } else {
+        value
+    })
+}
+
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits >> 15 == 1 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let frac = (bits & 0x3ff) as f64;
+    sign * match exp {
+        0 => frac * 2f64.powi(-24),
+        0x1f if frac == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + frac / 1024.0) * 2f64.powi(exp - 15),
+    }
+}
+
+/// Reorder the data of a value that was read in Fortran (column-major) order
+fn fortran_to_c_order(value: Value) -> Value {
+    fn reorder<T: Clone>(shape: &[usize], data: &[T]) -> EcoVec<T> {
+        let mut result = EcoVec::with_capacity(data.len());
+        let mut index = vec![0; shape.len()];
+        for _ in 0..data.len() {
+            let mut offset = 0;
+            let mut stride = 1;
+            for (&i, &dim) in index.iter().zip(shape) {
+                offset += i * stride;
+                stride *= dim;
+            }
+            result.push(data[offset].clone());
+            for j in (0..shape.len()).rev() {
+                index[j] += 1;
+                if index[j] < shape[j] {
+                    break;
+                }
+                index[j] = 0;
+            }
+        }
+        result
+    }
+    match value {
+        Value::Num(arr) => Array::new(arr.shape.clone(), reorder(&arr.shape, &arr.data)).into(),
+        Value::Byte(arr) => Array::new(arr.shape.clone(), reorder(&arr.shape, &arr.data)).into(),
+        Value::Complex(arr) => Array::new(arr.shape.clone(), reorder(&arr.shape, &arr.data)).into(),
+        Value::Char(arr) => Array::new(arr.shape.clone(), reorder(&arr.shape, &arr.data)).into(),
+        Value::Box(arr) => Array::new(arr.shape.clone(), reorder(&arr.shape, &arr.data)).into(),
+    }
+}
+
+/// Encode named values in NumPy's `.npz` format
+///
+/// Each value is encoded with [`value_to_npy_bytes`] and stored uncompressed in a zip archive.
+pub fn arrays_to_npz_bytes(arrays: &[(String, Value)]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut central = Vec::new();
+    for (name, value) in arrays {
+        let data = value_to_npy_bytes(value)?;
+        let name = format!("{name}.npy");
+        let offset = u32::try_from(bytes.len()).map_err(|_| "Npz data is too large")?;
+        let size = u32::try_from(data.len()).map_err(|_| "Npz data is too large")?;
+        let crc = crc32(&data);
+        // Fields shared by the local and central headers: version, flags,
+        // method, time, date, crc, sizes, and name length
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&0x21u16.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        // Local header
+        bytes.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        bytes.extend_from_slice(&common);
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend(data);
+        // Central directory header
+        central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes());
+        central.extend_from_slice(&common);
+        central.extend_from_slice(&[0; 6]);
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_offset = u32::try_from(bytes.len()).map_err(|_| "Npz data is too large")?;
+    let count = u16::try_from(arrays.len()).map_err(|_| "Too many arrays for npz")?;
+    bytes.extend_from_slice(&central);
+    // End of central directory
+    bytes.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&count.to_le_bytes());
+    bytes.extend_from_slice(&count.to_le_bytes());
+    bytes.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&central_offset.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    Ok(bytes)
+}
+
+/// Decode named values from NumPy's `.npz` format
+///
+/// Only uncompressed archives, like those written by `numpy.savez`, are supported.
+pub fn npz_bytes_to_arrays(bytes: &[u8]) -> Result<Vec<(String, Value)>, String> {
+    let u16_at = |i: usize| -> Result<usize, String> {
+        (bytes.get(i..i + 2))
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .ok_or_else(|| "Npz data is truncated".into())
+    };
+    let u32_at = |i: usize| -> Result<usize, String> {
+        (bytes.get(i..i + 4))
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| "Npz data is truncated".into())
+    };
+    let end = (0..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&i| bytes[i..].starts_with(&0x06054b50u32.to_le_bytes()))
+        .ok_or("Data is not in npz format")?;
+    let count = u16_at(end + 10)?;
+    let mut entry = u32_at(end + 16)?;
+    let mut arrays = Vec::with_capacity(count);
+    for _ in 0..count {
+        if u32_at(entry)? != 0x02014b50 {
+            return Err("Invalid npz central directory".into());
+        }
+        let method = u16_at(entry + 10)?;
+        let size = u32_at(entry + 20)?;
+        let name_len = u16_at(entry + 28)?;
+        let extra_len = u16_at(entry + 30)?;
+        let comment_len = u16_at(entry + 32)?;
+        let offset = u32_at(entry + 42)?;
+        let name = (bytes.get(entry + 46..entry + 46 + name_len)).ok_or("Npz data is truncated")?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        entry += 46 + name_len + extra_len + comment_len;
+        if method != 0 {
+            return Err(format!(
+                "Npz entry {name:?} is compressed, which is not supported"
+            ));
+        }
+        let start = offset + 30 + u16_at(offset + 26)? + u16_at(offset + 28)?;
+        let data = (bytes.get(start..start + size)).ok_or("Npz data is truncated")?;
+        let value = npy_bytes_to_value(data)?;
+        let name = match name.strip_suffix(".npy") {
+            Some(name) => name.into(),
+            None => name,
+        };
+        arrays.push((name, value));
+    }
+    Ok(arrays)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// A text encoding used by [`Primitive::Encode`](crate::Primitive::Encode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Standard base64 with padding
+    Base64,
+    /// URL-safe base64 without padding
+    Base64Url,
+    /// Base32 with padding
+    Base32,
+    /// Lowercase hexadecimal
+    Hex,
+    /// URL percent-encoding
+    Url,
+    /// HTML entity escaping
+    Html,
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+impl TextEncoding {
+    /// Parse an encoding from a value
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let name = value.as_string(env, "Encoding must be a string")?;
+        Ok(match name.as_str() {
+            "base64" => TextEncoding::Base64,
+            "base64url" => TextEncoding::Base64Url,
+            "base32" => TextEncoding::Base32,
+            "hex" => TextEncoding::Hex,
+            "url" => TextEncoding::Url,
+            "html" => TextEncoding::Html,
+            name => {
+                return Err(env.error(format!(
+                    "Unknown encoding {name:?}. Valid encodings are \"base64\", \
+                    \"base64url\", \"base32\", \"hex\", \"url\", and \"html\"."
+                )))
+            }
+        })
+    }
+    /// Whether decoding gives a string rather than bytes
+    pub fn decodes_to_string(self) -> bool {
+        matches!(self, TextEncoding::Url | TextEncoding::Html)
+    }
+    /// Encode bytes
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Base64 => base64_encode(bytes, BASE64_ALPHABET, true),
+            TextEncoding::Base64Url => base64_encode(bytes, BASE64_URL_ALPHABET, false),
+            TextEncoding::Base32 => {
+                let mut s = String::with_capacity(bytes.len().div_ceil(5) * 8);
+                for chunk in bytes.chunks(5) {
+                    let mut buf = [0; 5];
+                    buf[..chunk.len()].copy_from_slice(chunk);
+                    let n = buf.iter().fold(0u64, |n, &b| (n << 8) | b as u64);
+                    let len = (chunk.len() * 8).div_ceil(5);
+                    for i in 0..8 {
+                        s.push(if i < len {
+                            BASE32_ALPHABET[(n >> (35 - i * 5)) as usize & 31] as char
+                        } else {
+                            '='
+                        });
+                    }
+                }
+                s
+            }
+            TextEncoding::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+            TextEncoding::Url => {
+                let mut s = String::with_capacity(bytes.len());
+                for &b in bytes {
+                    if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                        s.push(b as char);
+                    } else {
+                        s.push_str(&format!("%{b:02X}"));
+                    }
+                }
+                s
+            }
+            TextEncoding::Html => {
+                let mut s = String::with_capacity(bytes.len());
+                for c in String::from_utf8_lossy(bytes).chars() {
+                    match c {
+                        '&' => s.push_str("&amp;"),
+                        '<' => s.push_str("&lt;"),
+                        '>' => s.push_str("&gt;"),
+                        '"' => s.push_str("&quot;"),
+                        '\'' => s.push_str("&#39;"),
+                        c => s.push(c),
+                    }
+                }
+                s
+            }
+        }
+    }
+    /// Decode a string into bytes
+    pub fn decode(self, s: &str) -> Result<Vec<u8>, String> {
+        match self {
+            TextEncoding::Base64 | TextEncoding::Base64Url => {
+                let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+                let (mut acc, mut bits) = (0u32, 0);
+                for c in s.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+                    let n = match c {
+                        'A'..='Z' => c as u32 - 'A' as u32,
+                        'a'..='z' => c as u32 - 'a' as u32 + 26,
+                        '0'..='9' => c as u32 - '0' as u32 + 52,
+                        '+' | '-' => 62,
+                        '/' | '_' => 63,
+                        c => return Err(format!("Invalid base64 character {c:?}")),
+                    };
+                    acc = (acc << 6) | n;
+                    bits += 6;
+                    if bits >= 8 {
+                        bits -= 8;
+                        bytes.push((acc >> bits) as u8);
+                    }
+                }
+                Ok(bytes)
+            }
+            TextEncoding::Base32 => {
+                let mut bytes = Vec::with_capacity(s.len() / 8 * 5);
+                let (mut acc, mut bits) = (0u32, 0);
+                for c in s.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+                    let n = match c.to_ascii_uppercase() {
+                        c @ 'A'..='Z' => c as u32 - 'A' as u32,
+                        c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+                        c => return Err(format!("Invalid base32 character {c:?}")),
+                    };
+                    acc = (acc << 5) | n;
+                    bits += 5;
+                    if bits >= 8 {
+                        bits -= 8;
+                        bytes.push((acc >> bits) as u8);
+                    }
+                }
+                Ok(bytes)
+            }
+            TextEncoding::Hex => {
+                let digits: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+                if digits.len() % 2 != 0 {
+                    return Err("Hex string must have an even number of digits".into());
+                }
+                (digits.chunks_exact(2))
+                    .map(|pair| {
+                        let digit = |c: char| {
+                            c.to_digit(16)
+                                .ok_or_else(|| format!("Invalid hex digit {c:?}"))
+                        };
+                        Ok((digit(pair[0])? * 16 + digit(pair[1])?) as u8)
+                    })
+                    .collect()
+            }
+            TextEncoding::Url => {
+                let bytes = s.as_bytes();
+                let mut decoded = Vec::with_capacity(bytes.len());
+                let mut i = 0;
+                while i < bytes.len() {
+                    if bytes[i] == b'%' {
+                        let hex = (bytes.get(i + 1..i + 3))
+                            .and_then(|hex| std::str::from_utf8(hex).ok())
+                            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                            .ok_or("Invalid percent-encoding")?;
+                        decoded.push(hex);
+                        i += 3;
+                    } else {
+                        decoded.push(bytes[i]);
+                        i += 1;
+                    }
+                }
+                Ok(decoded)
+            }
+            TextEncoding::Html => {
+                let mut decoded = String::with_capacity(s.len());
+                let mut rest = s;
+                while let Some(start) = rest.find('&') {
+                    decoded.push_str(&rest[..start]);
+                    rest = &rest[start..];
+                    let entity =
+                        (rest.find(';'))
+                            .map(|end| (&rest[1..end], end))
+                            .and_then(|(name, end)| {
+                                let c = match name {
+                                    "amp" => '&',
+                                    "lt" => '<',
+                                    "gt" => '>',
+                                    "quot" => '"',
+                                    "apos" => '\'',
+                                    "nbsp" => '\u{a0}',
+                                    _ => {
+                                        let n = if let Some(hex) = name
+                                            .strip_prefix("#x")
+                                            .or_else(|| name.strip_prefix("#X"))
+                                        {
+                                            u32::from_str_radix(hex, 16).ok()?
+                                        } else {
+                                            name.strip_prefix('#')?.parse().ok()?
+                                        };
+                                        char::from_u32(n)?
+                                    }
+                                };
+                                Some((c, end))
+                            });
+                    if let Some((c, end)) = entity {
+                        decoded.push(c);
+                        rest = &rest[end + 1..];
+                    } else {
+                        decoded.push('&');
+                        rest = &rest[1..];
+                    }
+                }
+                decoded.push_str(rest);
+                Ok(decoded.into_bytes())
+            }
+        }
+    }
+}
+
+fn base64_encode(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut buf = [0; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let n = (buf[0] as u32) << 16 | (buf[1] as u32) << 8 | buf[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(alphabet[(n >> (18 - i * 6)) as usize & 63] as char);
+            } else if pad {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+pub(crate) fn text_encode(encoding: Value, value: Value, env: &Uiua) -> UiuaResult<Value> {
+    let encoding = TextEncoding::from_value(&encoding, env)?;
+    let bytes = match value {
+        Value::Char(_) => value
+            .as_string(env, "Text to encode must be a string")?
+            .into_bytes(),
+        value => value.as_bytes(env, "Data to encode must be a string or bytes")?,
+    };
+    Ok(encoding.encode(&bytes).into())
+}
+
+pub(crate) fn text_decode(encoding: Value, value: Value, env: &Uiua) -> UiuaResult<Value> {
+    let encoding = TextEncoding::from_value(&encoding, env)?;
+    let s = value.as_string(env, "Encoded text must be a string")?;
+    let bytes = encoding.decode(&s).map_err(|e| env.error(e))?;
+    Ok(if encoding.decodes_to_string() {
+        String::from_utf8(bytes)
+            .map_err(|_| env.error("Decoded text is not valid UTF-8"))?
+            .into()
+    } else {
+        Array::<u8>::from(bytes.as_slice()).into()
+    })
+}
+
+/// A compression format used by [`Primitive::Compress`](crate::Primitive::Compress)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Gzip
+    Gzip,
+    /// Raw deflate
+    Deflate,
+    /// Zlib
+    Zlib,
+    /// Zstandard
+    Zstd,
+}
+
+impl Compression {
+    /// Parse a compression format from a value
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let name = value.as_string(env, "Compression format must be a string")?;
+        Ok(match name.as_str() {
+            "gzip" | "gz" => Compression::Gzip,
+            "deflate" => Compression::Deflate,
+            "zlib" => Compression::Zlib,
+            "zstd" | "zst" => Compression::Zstd,
+            name => {
+                return Err(env.error(format!(
+                    "Unknown compression format {name:?}. Valid formats are \
+                    \"gzip\", \"deflate\", \"zlib\", and \"zstd\"."
+                )))
+            }
+        })
+    }
+    /// Compress bytes
+    #[allow(unused_variables)]
+    pub fn compress(self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        #[cfg(feature = "compress")]
+        use {flate2::write::*, std::io::Write};
+        match self {
+            #[cfg(feature = "compress")]
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).map_err(|e| e.to_string())?;
+                encoder.finish().map_err(|e| e.to_string())
+            }
+            #[cfg(feature = "compress")]
+            Compression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).map_err(|e| e.to_string())?;
+                encoder.finish().map_err(|e| e.to_string())
+            }
+            #[cfg(feature = "compress")]
+            Compression::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes).map_err(|e| e.to_string())?;
+                encoder.finish().map_err(|e| e.to_string())
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(bytes, 0).map_err(|e| e.to_string()),
+            #[allow(unreachable_patterns)]
+            format => Err(format!(
+                "{format:?} compression is not supported in this environment"
+            )),
+        }
+    }
+    /// Decompress bytes
+    pub fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        use std::io::Read;
+        let mut decompressed = Vec::new();
+        (self.decoder(bytes)?)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| e.to_string())?;
+        Ok(decompressed)
+    }
+    /// Wrap a reader so that reading from it decompresses
+    #[allow(unused_variables)]
+    pub fn decoder<'a, R: std::io::Read + Send + 'a>(
+        self,
+        reader: R,
+    ) -> Result<Box<dyn std::io::Read + Send + 'a>, String> {
+        #[cfg(feature = "compress")]
+        use flate2::read::*;
+        match self {
+            #[cfg(feature = "compress")]
+            Compression::Gzip => Ok(Box::new(MultiGzDecoder::new(reader))),
+            #[cfg(feature = "compress")]
+            Compression::Deflate => Ok(Box::new(DeflateDecoder::new(reader))),
+            #[cfg(feature = "compress")]
+            Compression::Zlib => Ok(Box::new(ZlibDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Box::new(
+                zstd::Decoder::new(reader).map_err(|e| e.to_string())?,
+            )),
+            #[allow(unreachable_patterns)]
+            format => Err(format!(
+                "{format:?} decompression is not supported in this environment"
+            )),
+        }
+    }
+}
+
+pub(crate) fn compress(format: Value, value: Value, env: &Uiua) -> UiuaResult<Value> {
+    let format = Compression::from_value(&format, env)?;
+    let bytes = match value {
+        Value::Char(_) => value
+            .as_string(env, "Data to compress must be a string")?
+            .into_bytes(),
+        value => value.as_bytes(env, "Data to compress must be a string or bytes")?,
+    };
+    let compressed = format.compress(&bytes).map_err(|e| env.error(e))?;
+    Ok(Array::<u8>::from(compressed.as_slice()).into())
+}
+
+pub(crate) fn decompress(format: Value, value: Value, env: &Uiua) -> UiuaResult<Value> {
+    let format = Compression::from_value(&format, env)?;
+    let bytes = value.as_bytes(env, "Data to decompress must be bytes")?;
+    let decompressed = format.decompress(&bytes).map_err(|e| env.error(e))?;
+    Ok(Array::<u8>::from(decompressed.as_slice()).into())
+}
+
 pub(crate) fn layout_text(options: Value, text: Value, env: &Uiua) -> UiuaResult<Value> {
     #[cfg(feature = "font_shaping")]
     {
         layout_text_impl(options, text, env)
     }
     #[cfg(not(feature = "font_shaping"))]
-    Err(env.error("Text layout is not supported in this environment"))
+    Err(env
+        .error("Text layout is not supported in this environment")
+        .system())
 }
 
 #[cfg(feature = "font_shaping")]
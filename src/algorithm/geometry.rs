@@ -0,0 +1,265 @@
+//! Vector products, distances, and planar geometry
+
+use ecow::EcoVec;
+
+use crate::{Array, ArrayFlags, Primitive, Shape, Uiua, UiuaResult, Value};
+
+use super::{linalg::real_array, validate_size};
+
+/// Get the length of the vectors along the last axis of an array
+fn vector_len(arr: &Array<f64>, prim: Primitive, env: &Uiua) -> UiuaResult<usize> {
+    match arr.shape.last() {
+        Some(&d) => Ok(d),
+        None => Err(env.error(format!(
+            "{} expects an array of vectors, but it got a scalar",
+            prim.format()
+        ))),
+    }
+}
+
+/// Pair up the vectors of two arrays
+///
+/// The arrays must have the same shape, or one must be a single vector.
+/// Returns the shape of the pairs and the length of the vectors.
+fn vector_pairs(
+    a: &Array<f64>,
+    b: &Array<f64>,
+    prim: Primitive,
+    env: &Uiua,
+) -> UiuaResult<(Shape, usize)> {
+    let (a_d, b_d) = (vector_len(a, prim, env)?, vector_len(b, prim, env)?);
+    let leading = if a.shape == b.shape {
+        &a.shape
+    } else if a.rank() == 1 && a_d == b_d {
+        &b.shape
+    } else if b.rank() == 1 && a_d == b_d {
+        &a.shape
+    } else {
+        return Err(env.error(format!(
+            "Cannot {} arrays of shape {} and {}",
+            prim.format(),
+            a.shape,
+            b.shape
+        )));
+    };
+    let mut shape = leading.clone();
+    shape.pop();
+    Ok((shape, a_d))
+}
+
+/// Iterate over the pairs of vectors from [`vector_pairs`]
+fn zip_vectors<'a>(
+    a: &'a Array<f64>,
+    b: &'a Array<f64>,
+    shape: &Shape,
+    d: usize,
+) -> impl Iterator<Item = (&'a [f64], &'a [f64])> {
+    let rows = |arr: &'a Array<f64>| {
+        let single = arr.rank() == 1;
+        (0..).map(move |i| {
+            let start = if single { 0 } else { i * d };
+            &arr.data[start..start + d]
+        })
+    };
+    rows(a).zip(rows(b)).take(shape.elements())
+}
+
+fn dot_slices(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+pub fn dot(env: &mut Uiua) -> UiuaResult {
+    let a = real_array(env.pop(1)?, Primitive::Dot, env)?;
+    let b = real_array(env.pop(2)?, Primitive::Dot, env)?;
+    let (shape, d) = vector_pairs(&a, &b, Primitive::Dot, env)?;
+    let data: EcoVec<f64> = zip_vectors(&a, &b, &shape, d)
+        .map(|(a, b)| dot_slices(a, b))
+        .collect();
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+pub fn cross(env: &mut Uiua) -> UiuaResult {
+    let a = real_array(env.pop(1)?, Primitive::Cross, env)?;
+    let b = real_array(env.pop(2)?, Primitive::Cross, env)?;
+    let (mut shape, d) = vector_pairs(&a, &b, Primitive::Cross, env)?;
+    let pairs = zip_vectors(&a, &b, &shape, d);
+    let data: EcoVec<f64> = match d {
+        // The z component of the 3D cross product
+        2 => pairs.map(|(a, b)| a[0] * b[1] - a[1] * b[0]).collect(),
+        3 => {
+            shape.push(3);
+            pairs
+                .flat_map(|(a, b)| {
+                    [
+                        a[1] * b[2] - a[2] * b[1],
+                        a[2] * b[0] - a[0] * b[2],
+                        a[0] * b[1] - a[1] * b[0],
+                    ]
+                })
+                .collect()
+        }
+        _ => {
+            return Err(env.error(format!(
+                "{} expects 2D or 3D vectors, but the vectors have {d} components",
+                Primitive::Cross.format()
+            )))
+        }
+    };
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+pub fn norm(env: &mut Uiua) -> UiuaResult {
+    let arr = real_array(env.pop(1)?, Primitive::Norm, env)?;
+    let d = vector_len(&arr, Primitive::Norm, env)?;
+    let mut shape = arr.shape.clone();
+    shape.pop();
+    let data: EcoVec<f64> = if d == 0 {
+        EcoVec::from(vec![0.0; shape.elements()])
+    } else {
+        (arr.data.chunks_exact(d))
+            .map(|v| dot_slices(v, v).sqrt())
+            .collect()
+    };
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+/// Get the dimension of a list of points
+fn point_dim(arr: &Array<f64>, prim: Primitive, env: &Uiua) -> UiuaResult<usize> {
+    if arr.rank() != 2 {
+        return Err(env.error(format!(
+            "{} expects a list of points, but the array has shape {}",
+            prim.format(),
+            arr.shape
+        )));
+    }
+    Ok(arr.shape[1])
+}
+
+pub fn dist_mat(env: &mut Uiua) -> UiuaResult {
+    let a = real_array(env.pop(1)?, Primitive::DistMat, env)?;
+    let b = real_array(env.pop(2)?, Primitive::DistMat, env)?;
+    let (a_d, b_d) = (
+        point_dim(&a, Primitive::DistMat, env)?,
+        point_dim(&b, Primitive::DistMat, env)?,
+    );
+    if a_d != b_d {
+        return Err(env.error(format!(
+            "Cannot get distances between {a_d}D and {b_d}D points"
+        )));
+    }
+    let (n, m) = (a.row_count(), b.row_count());
+    validate_size::<f64>([n, m], env)?;
+    let mut data = EcoVec::with_capacity(n * m);
+    for i in 0..n {
+        let p = &a.data[i * a_d..][..a_d];
+        for j in 0..m {
+            let q = &b.data[j * b_d..][..b_d];
+            let sq: f64 = p.iter().zip(q).map(|(p, q)| (p - q) * (p - q)).sum();
+            data.push(sq.sqrt());
+        }
+    }
+    env.push(Array::new([n, m], data));
+    Ok(())
+}
+
+/// Get 2D points from an array, checking that they are finite
+fn planar_points(arr: &Array<f64>, prim: Primitive, env: &Uiua) -> UiuaResult<Vec<[f64; 2]>> {
+    if point_dim(arr, prim, env)? != 2 {
+        return Err(env.error(format!(
+            "{} expects 2D points, but the array has shape {}",
+            prim.format(),
+            arr.shape
+        )));
+    }
+    if arr.data.iter().any(|x| !x.is_finite()) {
+        return Err(env.error(format!("{}'s points must be finite", prim.format())));
+    }
+    Ok(arr.data.chunks_exact(2).map(|p| [p[0], p[1]]).collect())
+}
+
+/// The cross product of `b - a` and `c - a`, which is positive if `a`, `b`, `c` turn counterclockwise
+fn turn(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+pub fn hull(env: &mut Uiua) -> UiuaResult {
+    let arr = real_array(env.pop(1)?, Primitive::Hull, env)?;
+    let points = planar_points(&arr, Primitive::Hull, env)?;
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&i, &j| {
+        let (p, q) = (points[i], points[j]);
+        (p[0].total_cmp(&q[0])).then(p[1].total_cmp(&q[1]))
+    });
+    order.dedup_by_key(|&mut i| points[i]);
+    // Andrew's monotone chain, building the lower hull and then the upper hull
+    let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+    if order.len() < 3 {
+        hull = order;
+    } else {
+        let push = |hull: &mut Vec<usize>, i: usize, min_len: usize| {
+            while hull.len() >= min_len
+                && turn(
+                    points[hull[hull.len() - 2]],
+                    points[hull[hull.len() - 1]],
+                    points[i],
+                ) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(i);
+        };
+        for &i in &order {
+            push(&mut hull, i, 2);
+        }
+        let min_len = hull.len() + 1;
+        for &i in order.iter().rev().skip(1) {
+            push(&mut hull, i, min_len);
+        }
+        // The first point is also the last
+        hull.pop();
+    }
+    env.push(hull.into_iter().map(|i| i as f64).collect::<Value>());
+    Ok(())
+}
+
+pub fn in_poly(env: &mut Uiua) -> UiuaResult {
+    let poly = real_array(env.pop(1)?, Primitive::InPoly, env)?;
+    let poly = planar_points(&poly, Primitive::InPoly, env)?;
+    let arr = real_array(env.pop(2)?, Primitive::InPoly, env)?;
+    if arr.shape.last() != Some(&2) {
+        return Err(env.error(format!(
+            "{} expects 2D points, but the array has shape {}",
+            Primitive::InPoly.format(),
+            arr.shape
+        )));
+    }
+    let mut shape = arr.shape.clone();
+    shape.pop();
+    let edges = || poly.iter().zip(poly.iter().cycle().skip(1));
+    let data: EcoVec<u8> = (arr.data.chunks_exact(2))
+        .map(|p| {
+            let (x, y) = (p[0], p[1]);
+            // Points on the boundary are inside
+            let on_edge = edges().any(|(&a, &b)| {
+                turn(a, b, [x, y]) == 0.0
+                    && (a[0].min(b[0])..=a[0].max(b[0])).contains(&x)
+                    && (a[1].min(b[1])..=a[1].max(b[1])).contains(&y)
+            });
+            // Even-odd rule with a ray cast in the +x direction
+            let crossings = edges()
+                .filter(|(&a, &b)| {
+                    (a[1] > y) != (b[1] > y)
+                        && x < a[0] + (y - a[1]) * (b[0] - a[0]) / (b[1] - a[1])
+                })
+                .count();
+            (on_edge || crossings % 2 == 1) as u8
+        })
+        .collect();
+    let mut arr = Array::new(shape, data);
+    arr.meta_mut().flags.set(ArrayFlags::BOOLEAN, true);
+    env.push(arr);
+    Ok(())
+}
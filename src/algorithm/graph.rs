@@ -0,0 +1,314 @@
+//! Graph traversal, connectivity, ordering, and shortest paths
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{Primitive, Shape, Uiua, UiuaResult, Value};
+
+/// A directed graph with the outgoing edges of each node sorted by target
+struct Graph {
+    /// The edges of node `i` are at `offsets[i]..offsets[i + 1]`
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<f64>,
+}
+
+impl Graph {
+    /// Read either an adjacency matrix or a boxed list of edges
+    fn from_value(val: Value, prim: Primitive, env: &Uiua) -> UiuaResult<Self> {
+        match val {
+            Value::Box(arr) if arr.rank() == 0 => {
+                let edges = arr.data.into_iter().next().unwrap().0;
+                Self::from_edges(edges, prim, env)
+            }
+            Value::Num(arr) => {
+                let n = Self::matrix_size(&arr.shape, prim, env)?;
+                if arr.data.iter().any(|w| w.is_nan()) {
+                    return Err(env.error("Adjacency matrix cannot contain NaN"));
+                }
+                Ok(Self::from_matrix(&arr.data, n, |w| w))
+            }
+            Value::Byte(arr) => {
+                let n = Self::matrix_size(&arr.shape, prim, env)?;
+                Ok(Self::from_matrix(&arr.data, n, f64::from))
+            }
+            val => Err(env.error(format!(
+                "{} expects an adjacency matrix or a boxed list of edges, but it got {}",
+                prim.format(),
+                val.type_name_plural()
+            ))),
+        }
+    }
+    fn matrix_size(shape: &Shape, prim: Primitive, env: &Uiua) -> UiuaResult<usize> {
+        if shape.len() != 2 || shape[0] != shape[1] {
+            return Err(env.error(format!(
+                "{} expects an adjacency matrix or a boxed list of edges, \
+                but the array has shape {shape}",
+                prim.format()
+            )));
+        }
+        Ok(shape[0])
+    }
+    fn from_matrix<T: Copy + Default + PartialEq>(
+        data: &[T],
+        n: usize,
+        weight: impl Fn(T) -> f64,
+    ) -> Self {
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        if n > 0 {
+            for row in data.chunks_exact(n) {
+                for (j, &w) in row.iter().enumerate() {
+                    if w != T::default() {
+                        targets.push(j);
+                        weights.push(weight(w));
+                    }
+                }
+                offsets.push(targets.len());
+            }
+        }
+        Graph {
+            offsets,
+            targets,
+            weights,
+        }
+    }
+    fn from_edges(edges: Value, prim: Primitive, env: &Uiua) -> UiuaResult<Self> {
+        let edges = match edges {
+            Value::Num(arr) => arr,
+            Value::Byte(arr) => arr.convert(),
+            val => {
+                return Err(env.error(format!(
+                    "Graph edges must be numbers, but they are {}",
+                    val.type_name_plural()
+                )))
+            }
+        };
+        if edges.rank() != 2 || !matches!(edges.shape[1], 2 | 3) {
+            return Err(env.error(format!(
+                "{} expects a list of edges with 2 or 3 columns, \
+                but the edges have shape {}",
+                prim.format(),
+                edges.shape
+            )));
+        }
+        let cols = edges.shape[1];
+        let mut triples = Vec::with_capacity(edges.row_count());
+        let mut n = 0;
+        for edge in edges.data.chunks_exact(cols) {
+            let mut node = |i: f64| {
+                if i < 0.0 || i.fract() != 0.0 || i > usize::MAX as f64 {
+                    return Err(env.error(format!(
+                        "Graph nodes must be natural numbers, but an edge has {}",
+                        i
+                    )));
+                }
+                let i = i as usize;
+                n = n.max(i + 1);
+                Ok(i)
+            };
+            let (from, to) = (node(edge[0])?, node(edge[1])?);
+            let weight = edge.get(2).copied().unwrap_or(1.0);
+            if weight.is_nan() {
+                return Err(env.error("Edge weights cannot be NaN"));
+            }
+            triples.push((from, to, weight));
+        }
+        // Stable, so parallel edges keep their order
+        triples.sort_by_key(|&(from, to, _)| (from, to));
+        let mut offsets = vec![0; n + 1];
+        for &(from, ..) in &triples {
+            offsets[from + 1] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+        Ok(Graph {
+            offsets,
+            targets: triples.iter().map(|&(_, to, _)| to).collect(),
+            weights: triples.iter().map(|&(.., w)| w).collect(),
+        })
+    }
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+    fn neighbors(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+    fn edges(&self, node: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.offsets[node]..self.offsets[node + 1];
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[range].iter().copied())
+    }
+    /// Read a start node or list of start nodes
+    fn starts(&self, val: Value, env: &Uiua) -> UiuaResult<Vec<usize>> {
+        if val.rank() > 1 {
+            return Err(env.error(format!(
+                "Start nodes must be a scalar or list, but their shape is {}",
+                val.shape()
+            )));
+        }
+        let starts = val.as_nats(env, "Start nodes must be natural numbers")?;
+        if let Some(&start) = starts.iter().find(|&&i| i >= self.len()) {
+            return Err(env.error(format!(
+                "Node {start} is out of bounds of a graph with {} node{}",
+                self.len(),
+                if self.len() == 1 { "" } else { "s" }
+            )));
+        }
+        Ok(starts)
+    }
+}
+
+fn nodes_value(nodes: impl IntoIterator<Item = usize>) -> Value {
+    nodes.into_iter().map(|i| i as f64).collect()
+}
+
+pub fn bfs(env: &mut Uiua) -> UiuaResult {
+    let starts = env.pop(1)?;
+    let graph = Graph::from_value(env.pop(2)?, Primitive::Bfs, env)?;
+    let starts = graph.starts(starts, env)?;
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::new();
+    for start in starts {
+        if !visited[start] {
+            visited[start] = true;
+            order.push(start);
+        }
+    }
+    // The order doubles as the queue
+    let mut i = 0;
+    while i < order.len() {
+        for &next in graph.neighbors(order[i]) {
+            if !visited[next] {
+                visited[next] = true;
+                order.push(next);
+            }
+        }
+        i += 1;
+    }
+    env.push(nodes_value(order));
+    Ok(())
+}
+
+pub fn dfs(env: &mut Uiua) -> UiuaResult {
+    let starts = env.pop(1)?;
+    let graph = Graph::from_value(env.pop(2)?, Primitive::Dfs, env)?;
+    let starts = graph.starts(starts, env)?;
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::new();
+    let mut stack = Vec::new();
+    for start in starts {
+        stack.push(start);
+        while let Some(node) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            order.push(node);
+            // Reversed so that lower neighbors are visited first
+            stack.extend(graph.neighbors(node).iter().rev().filter(|&&i| !visited[i]));
+        }
+    }
+    env.push(nodes_value(order));
+    Ok(())
+}
+
+pub fn components(env: &mut Uiua) -> UiuaResult {
+    let graph = Graph::from_value(env.pop(1)?, Primitive::Components, env)?;
+    // Union-find, ignoring edge direction
+    let mut parents: Vec<usize> = (0..graph.len()).collect();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for from in 0..graph.len() {
+        for &to in graph.neighbors(from) {
+            let (a, b) = (root(&mut parents, from), root(&mut parents, to));
+            // Keep the lowest node as the root
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+    // Components are numbered in order of their lowest node
+    let mut labels = vec![0; graph.len()];
+    let mut count = 0;
+    for i in 0..graph.len() {
+        let r = root(&mut parents, i);
+        labels[i] = if r == i {
+            count += 1;
+            count - 1
+        } else {
+            labels[r]
+        };
+    }
+    env.push(nodes_value(labels));
+    Ok(())
+}
+
+pub fn toposort(env: &mut Uiua) -> UiuaResult {
+    let graph = Graph::from_value(env.pop(1)?, Primitive::TopoSort, env)?;
+    let mut in_degrees = vec![0usize; graph.len()];
+    for &to in &graph.targets {
+        in_degrees[to] += 1;
+    }
+    // Always take the lowest ready node so that the order is deterministic
+    let mut ready: BinaryHeap<Reverse<usize>> = (in_degrees.iter().enumerate())
+        .filter(|(_, &d)| d == 0)
+        .map(|(i, _)| Reverse(i))
+        .collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+        for &next in graph.neighbors(node) {
+            in_degrees[next] -= 1;
+            if in_degrees[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+    if order.len() < graph.len() {
+        return Err(env.error("Cannot topologically sort a graph with a cycle"));
+    }
+    env.push(nodes_value(order));
+    Ok(())
+}
+
+pub fn shortest(env: &mut Uiua) -> UiuaResult {
+    let starts = env.pop(1)?;
+    let graph = Graph::from_value(env.pop(2)?, Primitive::Shortest, env)?;
+    let starts = graph.starts(starts, env)?;
+    if graph.weights.iter().any(|&w| w < 0.0) {
+        return Err(env.error("Cannot find shortest paths in a graph with negative edge weights"));
+    }
+    let mut dists = vec![f64::INFINITY; graph.len()];
+    let mut prevs = vec![-1.0; graph.len()];
+    // Non-negative floats order the same as their bits
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        dists[start] = 0.0;
+        queue.push(Reverse((0f64.to_bits(), start)));
+    }
+    while let Some(Reverse((dist, node))) = queue.pop() {
+        let dist = f64::from_bits(dist);
+        if dist > dists[node] {
+            continue;
+        }
+        for (next, w) in graph.edges(node) {
+            let new = dist + w;
+            if new < dists[next] {
+                dists[next] = new;
+                prevs[next] = node as f64;
+                queue.push(Reverse((new.to_bits(), next)));
+            }
+        }
+    }
+    env.push(prevs.into_iter().collect::<Value>());
+    env.push(dists.into_iter().collect::<Value>());
+    Ok(())
+}
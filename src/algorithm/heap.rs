@@ -0,0 +1,114 @@
+//! Binary heap operations on the rows of arrays
+
+use std::cmp::Ordering;
+
+use crate::{val_as_arr, Array, ArrayValue, Primitive, Uiua, UiuaResult, Value};
+
+impl Value {
+    /// Arrange the rows of the value into a binary min-heap
+    pub fn heapify(&mut self, env: &Uiua) -> UiuaResult {
+        self.validate_heap(Primitive::Heap, env)?;
+        val_as_arr!(self, Array::heapify);
+        Ok(())
+    }
+    /// Push the rows of a value onto a heap
+    ///
+    /// The value is joined to the heap as with `join`
+    pub fn heap_push(self, heap: Self, env: &Uiua) -> UiuaResult<Self> {
+        heap.validate_heap(Primitive::HeapPush, env)?;
+        let start = heap.row_count();
+        let mut heap = heap.join(self, true, env)?;
+        val_as_arr!(&mut heap, |arr| {
+            for i in start..arr.row_count() {
+                arr.heap_sift_up(i);
+            }
+        });
+        Ok(heap)
+    }
+    /// Pop the smallest row from a heap
+    ///
+    /// Returns the row and the rest of the heap
+    pub fn heap_pop(mut self, env: &Uiua) -> UiuaResult<(Self, Self)> {
+        self.validate_heap(Primitive::HeapPop, env)?;
+        if self.row_count() == 0 {
+            return Err(env.error(format!(
+                "Cannot {} from an empty heap",
+                Primitive::HeapPop.format()
+            )));
+        }
+        let min = val_as_arr!(&mut self, |arr| arr.heap_pop().into());
+        Ok((min, self))
+    }
+    fn validate_heap(&self, prim: Primitive, env: &Uiua) -> UiuaResult {
+        if self.rank() == 0 {
+            return Err(env.error(format!("Cannot {} a scalar", prim.format())));
+        }
+        if self.is_map() {
+            return Err(env.error(format!("Cannot {} a map array", prim.format())));
+        }
+        Ok(())
+    }
+}
+
+impl<T: ArrayValue> Array<T> {
+    /// Arrange the rows of the array into a binary min-heap
+    pub fn heapify(&mut self) {
+        for i in (0..self.row_count() / 2).rev() {
+            self.heap_sift_down(i);
+        }
+    }
+    /// Remove the smallest row from a heap
+    ///
+    /// # Panics
+    /// Panics if the array has no rows
+    fn heap_pop(&mut self) -> Self {
+        let last = self.row_count() - 1;
+        self.heap_swap_rows(0, last);
+        let min = self.pop_row().unwrap();
+        self.heap_sift_down(0);
+        min
+    }
+    fn heap_sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap_cmp_rows(i, parent) != Ordering::Less {
+                break;
+            }
+            self.heap_swap_rows(i, parent);
+            i = parent;
+        }
+    }
+    fn heap_sift_down(&mut self, mut i: usize) {
+        let len = self.row_count();
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < len && self.heap_cmp_rows(child, smallest) == Ordering::Less {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                break;
+            }
+            self.heap_swap_rows(i, smallest);
+            i = smallest;
+        }
+    }
+    fn heap_cmp_rows(&self, a: usize, b: usize) -> Ordering {
+        (self.row_slice(a).iter())
+            .zip(self.row_slice(b))
+            .map(|(a, b)| a.array_cmp(b))
+            .find(|x| x != &Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+    fn heap_swap_rows(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let row_len = self.row_len();
+        let data = self.data.as_mut_slice();
+        for k in 0..row_len {
+            data.swap(a * row_len + k, b * row_len + k);
+        }
+    }
+}
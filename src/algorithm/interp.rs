@@ -0,0 +1,253 @@
+//! Interpolation and resampling
+
+use ecow::EcoVec;
+
+use crate::{Array, Primitive, Shape, Uiua, UiuaResult};
+
+use super::{linalg::real_array, validate_size};
+
+/// Catmull-Rom weights for the 4 samples around a point that is `t` past the second one
+fn cubic_weights(t: f64) -> [f64; 4] {
+    let (t2, t3) = (t * t, t * t * t);
+    [
+        (-t3 + 2.0 * t2 - t) / 2.0,
+        (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0,
+        (-3.0 * t3 + 4.0 * t2 + t) / 2.0,
+        (t3 - t2) / 2.0,
+    ]
+}
+
+/// Get the samples along an axis that contribute to a point, and their weights
+fn axis_taps(x: f64, len: usize, cubic: bool, taps: &mut Vec<(usize, f64)>) {
+    taps.clear();
+    let max = (len - 1) as f64;
+    let x = x.clamp(0.0, max);
+    let base = x.floor().min(max);
+    let t = x - base;
+    let base = base as isize;
+    let clamp = |i: isize| i.clamp(0, len as isize - 1) as usize;
+    if cubic {
+        for (k, w) in cubic_weights(t).into_iter().enumerate() {
+            taps.push((clamp(base - 1 + k as isize), w));
+        }
+    } else {
+        taps.push((clamp(base), 1.0 - t));
+        taps.push((clamp(base + 1), t));
+    }
+}
+
+/// Interpolate an array at fractional indices into its first `axes` axes
+///
+/// If `axes` is `None`, each point is a single index into the first axis.
+pub fn interp(axes: Option<usize>, cubic: bool, env: &mut Uiua) -> UiuaResult {
+    let prim = if cubic {
+        Primitive::Cubic
+    } else {
+        Primitive::Interp
+    };
+    let points = real_array(env.pop(1)?, prim, env)?;
+    let arr = real_array(env.pop(2)?, prim, env)?;
+    let n = axes.unwrap_or(1);
+    let mut shape: Shape = if let Some(n) = axes {
+        if points.shape.last() != Some(&n) {
+            return Err(env.error(format!(
+                "{}'s points must each have {n} coordinate{}, \
+                but the points have shape {}",
+                prim.format(),
+                if n == 1 { "" } else { "s" },
+                points.shape
+            )));
+        }
+        let mut shape = points.shape.clone();
+        shape.pop();
+        shape
+    } else {
+        points.shape.clone()
+    };
+    if n > arr.rank() {
+        return Err(env.error(format!(
+            "Cannot {} along {n} axes of an array of rank {}",
+            prim.format(),
+            arr.rank()
+        )));
+    }
+    if arr.shape[..n].contains(&0) {
+        return Err(env.error(format!(
+            "Cannot {} an array of shape {}",
+            prim.format(),
+            arr.shape
+        )));
+    }
+    let axis_lens = &arr.shape[..n];
+    let row_len: usize = arr.shape[n..].iter().product();
+    // The flat stride of each interpolated axis
+    let strides: Vec<usize> = (0..n)
+        .map(|i| arr.shape[i + 1..].iter().product())
+        .collect();
+    shape.extend_from_slice(&arr.shape[n..]);
+    validate_size::<f64>(shape.iter().copied(), env)?;
+    let mut data = EcoVec::with_capacity(shape.elements());
+    let mut taps: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    let mut row = vec![0.0; row_len];
+    for point in points.data.chunks_exact(n) {
+        for ((taps, &x), &len) in taps.iter_mut().zip(point).zip(axis_lens) {
+            axis_taps(x, len, cubic, taps);
+        }
+        row.fill(0.0);
+        // Sum over every combination of taps
+        let mut indices = vec![0; n];
+        'corners: loop {
+            let mut offset = 0;
+            let mut weight = 1.0;
+            for ((taps, &i), stride) in taps.iter().zip(&indices).zip(&strides) {
+                let (j, w) = taps[i];
+                offset += j * stride;
+                weight *= w;
+            }
+            if weight != 0.0 {
+                let src = &arr.data[offset..][..row_len];
+                for (r, s) in row.iter_mut().zip(src) {
+                    *r += weight * s;
+                }
+            }
+            for (i, taps) in indices.iter_mut().zip(&taps).rev() {
+                *i += 1;
+                if *i < taps.len() {
+                    continue 'corners;
+                }
+                *i = 0;
+            }
+            break;
+        }
+        data.extend_from_slice(&row);
+    }
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
+/// A filter for resampling
+#[derive(Clone, Copy)]
+enum Filter {
+    Box,
+    Linear,
+    Cubic,
+}
+
+impl Filter {
+    fn support(self) -> f64 {
+        match self {
+            Filter::Box => 0.5,
+            Filter::Linear => 1.0,
+            Filter::Cubic => 2.0,
+        }
+    }
+    fn weight(self, x: f64) -> f64 {
+        let x = x.abs();
+        match self {
+            Filter::Box => (x < 0.5) as u8 as f64,
+            Filter::Linear => (1.0 - x).max(0.0),
+            // Catmull-Rom
+            Filter::Cubic if x < 1.0 => (3.0 * x * x * x - 5.0 * x * x + 2.0) / 2.0,
+            Filter::Cubic if x < 2.0 => (-x * x * x + 5.0 * x * x - 8.0 * x + 4.0) / 2.0,
+            Filter::Cubic => 0.0,
+        }
+    }
+}
+
+/// Get the input samples and normalized weights for each output sample along an axis
+///
+/// The filter is widened when downsampling so that every input sample contributes.
+fn resample_taps(from: usize, to: usize, filter: Filter) -> Vec<Vec<(usize, f64)>> {
+    let scale = from as f64 / to as f64;
+    let width = scale.max(1.0);
+    let support = filter.support() * width;
+    (0..to)
+        .map(|o| {
+            let center = (o as f64 + 0.5) * scale;
+            let start = (center - support).floor().max(0.0) as usize;
+            let end = ((center + support).ceil() as usize).min(from);
+            let mut taps: Vec<(usize, f64)> = (start..end)
+                .map(|i| (i, filter.weight((i as f64 + 0.5 - center) / width)))
+                .filter(|&(_, w)| w != 0.0)
+                .collect();
+            let total: f64 = taps.iter().map(|&(_, w)| w).sum();
+            if total == 0.0 {
+                // Only possible with a box filter exactly between samples
+                let i = (center.floor() as usize).min(from - 1);
+                taps = vec![(i, 1.0)];
+            } else {
+                for (_, w) in &mut taps {
+                    *w /= total;
+                }
+            }
+            taps
+        })
+        .collect()
+}
+
+/// Resample the leading axes of an array to a new shape
+///
+/// `degree` is the degree of the filter's polynomial pieces
+pub fn resample(degree: usize, env: &mut Uiua) -> UiuaResult {
+    let filter = match degree {
+        0 => Filter::Box,
+        1 => Filter::Linear,
+        _ => Filter::Cubic,
+    };
+    let new_lens = env.pop(1)?;
+    if new_lens.rank() > 1 {
+        return Err(env.error(format!(
+            "Resample shape must be a scalar or list, but its shape is {}",
+            new_lens.shape()
+        )));
+    }
+    let new_lens = new_lens.as_nats(env, "Resample shape must be natural numbers")?;
+    let arr = real_array(env.pop(2)?, Primitive::Resample, env)?;
+    if new_lens.len() > arr.rank() {
+        return Err(env.error(format!(
+            "Cannot resample {} axes of an array of rank {}",
+            new_lens.len(),
+            arr.rank()
+        )));
+    }
+    let mut new_shape = arr.shape.clone();
+    new_shape[..new_lens.len()].copy_from_slice(&new_lens);
+    validate_size::<f64>(new_shape.iter().copied(), env)?;
+    let (mut shape, mut data) = (arr.shape, arr.data);
+    // Resample one axis at a time
+    for (axis, &to) in new_lens.iter().enumerate() {
+        let from = shape[axis];
+        if from == to {
+            continue;
+        }
+        if from == 0 {
+            return Err(env.error(format!(
+                "Cannot resample an axis of length 0 to length {to}"
+            )));
+        }
+        let outer: usize = shape[..axis].iter().product();
+        let inner: usize = shape[axis + 1..].iter().product();
+        shape[axis] = to;
+        if outer * inner == 0 {
+            data = EcoVec::new().into();
+            continue;
+        }
+        let taps = resample_taps(from, to, filter);
+        let mut resampled = EcoVec::with_capacity(outer * to * inner);
+        let mut row = vec![0.0; inner];
+        for block in data.chunks_exact(from * inner) {
+            for taps in &taps {
+                row.fill(0.0);
+                for &(i, w) in taps {
+                    for (r, s) in row.iter_mut().zip(&block[i * inner..][..inner]) {
+                        *r += w * s;
+                    }
+                }
+                resampled.extend_from_slice(&row);
+            }
+        }
+        data = resampled.into();
+    }
+    env.push(Array::new(shape, data));
+    Ok(())
+}
@@ -0,0 +1,914 @@
+//! Matrix decompositions, least-squares solving, and tensor contraction
+
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+
+use crate::{Array, Complex, Primitive, Uiua, UiuaResult, Value};
+
+/// A dense row-major matrix
+#[derive(Clone)]
+struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+    fn identity(n: usize) -> Self {
+        let mut mat = Self::zeros(n, n);
+        for i in 0..n {
+            mat[(i, i)] = 1.0;
+        }
+        mat
+    }
+    fn from_value(val: Value, prim: Primitive, env: &Uiua) -> UiuaResult<Self> {
+        let arr = real_array(val, prim, env)?;
+        let &[rows, cols] = arr.shape().dims() else {
+            return Err(env.error(format!(
+                "{} expects a matrix, but the array has shape {}",
+                prim.format(),
+                arr.shape()
+            )));
+        };
+        Ok(Matrix {
+            rows,
+            cols,
+            data: arr.data.into_iter().collect(),
+        })
+    }
+    fn into_value(self) -> Value {
+        Array::new([self.rows, self.cols], self.data.as_slice()).into()
+    }
+    fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)];
+            }
+        }
+        t
+    }
+    fn mul(&self, other: &Self) -> Self {
+        self.mul_transposed(&other.transpose(), false)
+    }
+    /// Multiply by the transpose of `other`
+    fn mul_transposed(&self, other: &Self, parallel: bool) -> Self {
+        Matrix {
+            rows: self.rows,
+            cols: other.rows,
+            data: mul_rows(&self.data, self.rows, &other.data, other.rows, parallel),
+        }
+    }
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for Matrix {
+    type Output = f64;
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+pub(crate) fn real_array(val: Value, prim: Primitive, env: &Uiua) -> UiuaResult<Array<f64>> {
+    match val {
+        Value::Num(arr) => Ok(arr),
+        Value::Byte(arr) => Ok(arr.convert()),
+        val => Err(env.error(format!(
+            "Cannot {} {}",
+            prim.format(),
+            val.type_name_plural()
+        ))),
+    }
+}
+
+/// The number of rows of the left operand that share a tile of the right operand
+const ROW_BLOCK: usize = 16;
+/// The number of rows of the right operand that are kept hot while a block is multiplied
+const TILE: usize = 64;
+
+/// Multiply row-major matrices `A` and `Bᵀ`
+///
+/// `a` has `a_rows` rows and `b` has `b_rows` rows, and their rows have the same length.
+/// Because both operands are walked along their rows, every inner loop is a contiguous
+/// dot product. Blocks of `A` are multiplied by tiles of `B` so that a tile is reused
+/// while it is still in cache, and blocks are computed in parallel if `parallel` is set.
+pub(crate) fn mul_rows(
+    a: &[f64],
+    a_rows: usize,
+    b: &[f64],
+    b_rows: usize,
+    parallel: bool,
+) -> Vec<f64> {
+    let mut prod = vec![0.0; a_rows * b_rows];
+    if a_rows == 0 || b_rows == 0 || a.is_empty() {
+        return prod;
+    }
+    let inner = a.len() / a_rows;
+    debug_assert_eq!(b.len(), b_rows * inner);
+    let block = |(a_block, prod_block): (&[f64], &mut [f64])| {
+        for tile_start in (0..b_rows).step_by(TILE) {
+            let tile_end = (tile_start + TILE).min(b_rows);
+            for (a_row, prod_row) in a_block
+                .chunks_exact(inner)
+                .zip(prod_block.chunks_exact_mut(b_rows))
+            {
+                for (j, b_row) in
+                    (tile_start..tile_end).zip(b[tile_start * inner..].chunks_exact(inner))
+                {
+                    prod_row[j] = dot(a_row, b_row);
+                }
+            }
+        }
+    };
+    let blocks = a
+        .chunks(ROW_BLOCK * inner)
+        .zip(prod.chunks_mut(ROW_BLOCK * b_rows));
+    #[cfg(not(target_arch = "wasm32"))]
+    if parallel && a_rows * b_rows * inner > 100_000 {
+        blocks.par_bridge().for_each(block);
+        return prod;
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = parallel;
+    blocks.for_each(block);
+    prod
+}
+
+/// A dot product with independent accumulators
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    let mut acc = [0.0; 4];
+    let (a_chunks, b_chunks) = (a.chunks_exact(4), b.chunks_exact(4));
+    let rem: f64 = (a_chunks.remainder().iter())
+        .zip(b_chunks.remainder())
+        .map(|(a, b)| a * b)
+        .sum();
+    for (a, b) in a_chunks.zip(b_chunks) {
+        for i in 0..4 {
+            acc[i] += a[i] * b[i];
+        }
+    }
+    (acc[0] + acc[1]) + (acc[2] + acc[3]) + rem
+}
+
+/// LU decomposition with partial pivoting
+///
+/// Returns `L`, `U`, and the row permutation `P` such that `P A = L U`
+fn lu_impl(mut a: Matrix) -> (Matrix, Matrix, Vec<usize>) {
+    let (m, n) = (a.rows, a.cols);
+    let k = m.min(n);
+    let mut perm: Vec<usize> = (0..m).collect();
+    for j in 0..k {
+        let mut p = j;
+        for i in j + 1..m {
+            if a[(i, j)].abs() > a[(p, j)].abs() {
+                p = i;
+            }
+        }
+        if p != j {
+            a.swap_rows(p, j);
+            perm.swap(p, j);
+        }
+        let pivot = a[(j, j)];
+        if pivot == 0.0 {
+            continue;
+        }
+        for i in j + 1..m {
+            let f = a[(i, j)] / pivot;
+            a[(i, j)] = f;
+            for c in j + 1..n {
+                a[(i, c)] -= f * a[(j, c)];
+            }
+        }
+    }
+    let mut l = Matrix::zeros(m, k);
+    for i in 0..m {
+        for j in 0..k.min(i + 1) {
+            l[(i, j)] = if i == j { 1.0 } else { a[(i, j)] };
+        }
+    }
+    let mut u = Matrix::zeros(k, n);
+    for i in 0..k {
+        for j in i..n {
+            u[(i, j)] = a[(i, j)];
+        }
+    }
+    (l, u, perm)
+}
+
+/// Thin QR decomposition using Householder reflections
+///
+/// The diagonal of `R` is non-negative
+fn qr_impl(mut r: Matrix) -> (Matrix, Matrix) {
+    let (m, n) = (r.rows, r.cols);
+    let k = m.min(n);
+    let mut reflectors = Vec::with_capacity(k);
+    for j in 0..k {
+        let mut v: Vec<f64> = (j..m).map(|i| r[(i, j)]).collect();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let alpha = if v[0] > 0.0 { -norm } else { norm };
+        v[0] -= alpha;
+        let v_norm2: f64 = v.iter().map(|x| x * x).sum();
+        if v_norm2 == 0.0 {
+            reflectors.push(None);
+            continue;
+        }
+        for c in j..n {
+            let dot: f64 = (v.iter().enumerate()).map(|(i, x)| x * r[(j + i, c)]).sum();
+            let f = 2.0 * dot / v_norm2;
+            for (i, x) in v.iter().enumerate() {
+                r[(j + i, c)] -= f * x;
+            }
+        }
+        reflectors.push(Some((v, v_norm2)));
+    }
+    let mut q = Matrix::zeros(m, k);
+    for i in 0..k {
+        q[(i, i)] = 1.0;
+    }
+    for (j, reflector) in reflectors.iter().enumerate().rev() {
+        let Some((v, v_norm2)) = reflector else {
+            continue;
+        };
+        for c in 0..k {
+            let dot: f64 = (v.iter().enumerate()).map(|(i, x)| x * q[(j + i, c)]).sum();
+            let f = 2.0 * dot / v_norm2;
+            for (i, x) in v.iter().enumerate() {
+                q[(j + i, c)] -= f * x;
+            }
+        }
+    }
+    let mut r_thin = Matrix::zeros(k, n);
+    for i in 0..k {
+        let sign = if r[(i, i)] < 0.0 { -1.0 } else { 1.0 };
+        for j in i..n {
+            r_thin[(i, j)] = sign * r[(i, j)];
+        }
+        if sign < 0.0 {
+            for row in 0..m {
+                q[(row, i)] = -q[(row, i)];
+            }
+        }
+    }
+    (q, r_thin)
+}
+
+/// Thin singular value decomposition using one-sided Jacobi rotations
+///
+/// Returns `U`, the singular values in descending order, and `Vᵀ`
+fn svd_impl(a: Matrix) -> (Matrix, Vec<f64>, Matrix) {
+    if a.rows < a.cols {
+        let (u, s, vt) = svd_impl(a.transpose());
+        return (vt.transpose(), s, u.transpose());
+    }
+    let (m, n) = (a.rows, a.cols);
+    let mut u = a;
+    let mut v = Matrix::identity(n);
+    for _ in 0..100 {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let (mut alpha, mut beta, mut gamma) = (0.0, 0.0, 0.0);
+                for i in 0..m {
+                    alpha += u[(i, p)] * u[(i, p)];
+                    beta += u[(i, q)] * u[(i, q)];
+                    gamma += u[(i, p)] * u[(i, q)];
+                }
+                if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for mat in [&mut u, &mut v] {
+                    for i in 0..mat.rows {
+                        let (xp, xq) = (mat[(i, p)], mat[(i, q)]);
+                        mat[(i, p)] = c * xp - s * xq;
+                        mat[(i, q)] = s * xp + c * xq;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+    let norms: Vec<f64> = (0..n)
+        .map(|j| (0..m).map(|i| u[(i, j)] * u[(i, j)]).sum::<f64>().sqrt())
+        .collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| norms[b].total_cmp(&norms[a]));
+    let mut u_sorted = Matrix::zeros(m, n);
+    let mut vt = Matrix::zeros(n, n);
+    for (j, &o) in order.iter().enumerate() {
+        let norm = norms[o];
+        if norm > 0.0 {
+            for i in 0..m {
+                u_sorted[(i, j)] = u[(i, o)] / norm;
+            }
+        } else {
+            complete_column(&mut u_sorted, j);
+        }
+        for i in 0..n {
+            vt[(j, i)] = v[(i, o)];
+        }
+    }
+    let s = order.iter().map(|&o| norms[o]).collect();
+    (u_sorted, s, vt)
+}
+
+/// Fill a column with a unit vector orthogonal to the columns before it
+fn complete_column(mat: &mut Matrix, j: usize) {
+    let mut best: Option<(f64, Vec<f64>)> = None;
+    for e in 0..mat.rows {
+        let mut col = vec![0.0; mat.rows];
+        col[e] = 1.0;
+        for k in 0..j {
+            let dot: f64 = (0..mat.rows).map(|i| mat[(i, k)] * col[i]).sum();
+            for (i, x) in col.iter_mut().enumerate() {
+                *x -= dot * mat[(i, k)];
+            }
+        }
+        let norm = col.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if best.as_ref().map_or(true, |(best, _)| norm > *best) {
+            best = Some((norm, col));
+        }
+    }
+    if let Some((norm, col)) = best.filter(|(norm, _)| *norm > 0.0) {
+        for (i, x) in col.into_iter().enumerate() {
+            mat[(i, j)] = x / norm;
+        }
+    }
+}
+
+pub fn lu(env: &mut Uiua) -> UiuaResult {
+    let a = Matrix::from_value(env.pop(1)?, Primitive::Lu, env)?;
+    let (l, u, perm) = lu_impl(a);
+    env.push(perm.into_iter().map(|i| i as f64).collect::<Value>());
+    env.push(u.into_value());
+    env.push(l.into_value());
+    Ok(())
+}
+
+pub fn qr(env: &mut Uiua) -> UiuaResult {
+    let a = Matrix::from_value(env.pop(1)?, Primitive::Qr, env)?;
+    let (q, r) = qr_impl(a);
+    env.push(r.into_value());
+    env.push(q.into_value());
+    Ok(())
+}
+
+pub fn svd(env: &mut Uiua) -> UiuaResult {
+    let a = Matrix::from_value(env.pop(1)?, Primitive::Svd, env)?;
+    let (u, s, vt) = svd_impl(a);
+    env.push(vt.into_value());
+    env.push(s.into_iter().collect::<Value>());
+    env.push(u.into_value());
+    Ok(())
+}
+
+pub fn least_squares(env: &mut Uiua) -> UiuaResult {
+    let a = Matrix::from_value(env.pop(1)?, Primitive::LeastSquares, env)?;
+    let mut b = env.pop(2)?;
+    let b_is_vector = b.rank() == 1;
+    if b_is_vector {
+        b.shape_mut().push(1);
+    }
+    let b = Matrix::from_value(b, Primitive::LeastSquares, env)?;
+    if b.rows != a.rows {
+        return Err(env.error(format!(
+            "Cannot solve a system with {} equations using {} targets",
+            a.rows, b.rows
+        )));
+    }
+    let x = least_squares_impl(a, &b);
+    env.push(if b_is_vector {
+        x.data.into_iter().collect::<Value>()
+    } else {
+        x.into_value()
+    });
+    Ok(())
+}
+
+/// Find the minimum-norm `X` that minimizes the distance between `A X` and `B`
+fn least_squares_impl(a: Matrix, b: &Matrix) -> Matrix {
+    let (u, s, vt) = svd_impl(a);
+    let tolerance = s.first().copied().unwrap_or(0.0) * (u.rows.max(vt.cols) as f64) * f64::EPSILON;
+    let mut utb = u.transpose().mul(b);
+    for (i, &s) in s.iter().enumerate() {
+        let inv = if s > tolerance { 1.0 / s } else { 0.0 };
+        for j in 0..utb.cols {
+            utb[(i, j)] *= inv;
+        }
+    }
+    vt.transpose().mul(&utb)
+}
+
+/// Solve a least-squares system for a single column of targets
+///
+/// `a` is a row-major matrix with `cols` columns and a row for each target
+pub(crate) fn least_squares_column(a: Vec<f64>, cols: usize, b: &[f64]) -> Vec<f64> {
+    let a = Matrix {
+        rows: b.len(),
+        cols,
+        data: a,
+    };
+    let b = Matrix {
+        rows: b.len(),
+        cols: 1,
+        data: b.to_vec(),
+    };
+    least_squares_impl(a, &b).data
+}
+
+/// Solve `A X = B` for a square `A` using its LU decomposition
+///
+/// Returns `None` if `A` is singular
+fn lu_solve(a: Matrix, b: &Matrix) -> Option<Matrix> {
+    let n = a.rows;
+    let (l, u, perm) = lu_impl(a);
+    if (0..n).any(|i| u[(i, i)] == 0.0) {
+        return None;
+    }
+    let mut x = Matrix::zeros(n, b.cols);
+    for c in 0..b.cols {
+        for i in 0..n {
+            let mut sum = b[(perm[i], c)];
+            for j in 0..i {
+                sum -= l[(i, j)] * x[(j, c)];
+            }
+            x[(i, c)] = sum;
+        }
+        for i in (0..n).rev() {
+            let mut sum = x[(i, c)];
+            for j in i + 1..n {
+                sum -= u[(i, j)] * x[(j, c)];
+            }
+            x[(i, c)] = sum / u[(i, i)];
+        }
+    }
+    Some(x)
+}
+
+fn square_matrix(val: Value, prim: Primitive, env: &Uiua) -> UiuaResult<Matrix> {
+    let a = Matrix::from_value(val, prim, env)?;
+    if a.rows != a.cols {
+        return Err(env.error(format!(
+            "{} expects a square matrix, but the matrix has shape {}×{}",
+            prim.format(),
+            a.rows,
+            a.cols
+        )));
+    }
+    Ok(a)
+}
+
+pub fn matmul(env: &mut Uiua) -> UiuaResult {
+    let a = real_array(env.pop(1)?, Primitive::MatMul, env)?;
+    let b = real_array(env.pop(2)?, Primitive::MatMul, env)?;
+    let (a_rows, inner) = match *a.shape().dims() {
+        [n] => (None, n),
+        [rows, cols] => (Some(rows), cols),
+        _ => {
+            return Err(env.error(format!(
+                "{} expects a matrix or vector, but the first array has shape {}",
+                Primitive::MatMul.format(),
+                a.shape()
+            )))
+        }
+    };
+    let (b_inner, b_cols) = match *b.shape().dims() {
+        [n] => (n, None),
+        [rows, cols] => (rows, Some(cols)),
+        _ => {
+            return Err(env.error(format!(
+                "{} expects a matrix or vector, but the second array has shape {}",
+                Primitive::MatMul.format(),
+                b.shape()
+            )))
+        }
+    };
+    if inner != b_inner {
+        return Err(env.error(format!(
+            "Cannot multiply arrays of shape {} and {}",
+            a.shape(),
+            b.shape()
+        )));
+    }
+    let b = Matrix {
+        rows: b_inner,
+        cols: b_cols.unwrap_or(1),
+        data: b.data.into_iter().collect(),
+    };
+    let data = mul_rows(
+        &a.data,
+        a_rows.unwrap_or(1),
+        &b.transpose().data,
+        b.cols,
+        env.rt.parallelism != crate::Parallelism::Off,
+    );
+    env.push(match (a_rows, b_cols) {
+        (Some(rows), Some(cols)) => Array::new([rows, cols], data.as_slice()).into(),
+        (None, None) => data[0].into(),
+        _ => data.into_iter().collect::<Value>(),
+    });
+    Ok(())
+}
+
+pub fn solve(env: &mut Uiua) -> UiuaResult {
+    let a = square_matrix(env.pop(1)?, Primitive::Solve, env)?;
+    let mut b = env.pop(2)?;
+    let b_is_vector = b.rank() == 1;
+    if b_is_vector {
+        b.shape_mut().push(1);
+    }
+    let b = Matrix::from_value(b, Primitive::Solve, env)?;
+    if b.rows != a.rows {
+        return Err(env.error(format!(
+            "Cannot solve a system with {} equations using {} targets",
+            a.rows, b.rows
+        )));
+    }
+    let x =
+        lu_solve(a, &b).ok_or_else(|| env.error("Cannot solve a system with a singular matrix"))?;
+    env.push(if b_is_vector {
+        x.data.into_iter().collect::<Value>()
+    } else {
+        x.into_value()
+    });
+    Ok(())
+}
+
+pub fn det(env: &mut Uiua) -> UiuaResult {
+    let a = square_matrix(env.pop(1)?, Primitive::Det, env)?;
+    let n = a.rows;
+    let (_, u, perm) = lu_impl(a);
+    // The sign of a permutation is the parity of its length minus its cycle count
+    let mut visited = vec![false; n];
+    let mut odd = false;
+    for start in 0..n {
+        let mut i = start;
+        let mut len = 0;
+        while !visited[i] {
+            visited[i] = true;
+            i = perm[i];
+            len += 1;
+        }
+        if len > 0 && len % 2 == 0 {
+            odd = !odd;
+        }
+    }
+    let prod: f64 = (0..n).map(|i| u[(i, i)]).product();
+    env.push(if odd { -prod } else { prod });
+    Ok(())
+}
+
+pub fn inverse(env: &mut Uiua) -> UiuaResult {
+    let a = square_matrix(env.pop(1)?, Primitive::Inverse, env)?;
+    let n = a.rows;
+    let inv = lu_solve(a, &Matrix::identity(n))
+        .ok_or_else(|| env.error("Cannot invert a singular matrix"))?;
+    env.push(inv.into_value());
+    Ok(())
+}
+
+pub fn eig(env: &mut Uiua) -> UiuaResult {
+    let a = square_matrix(env.pop(1)?, Primitive::Eig, env)?;
+    let symmetric = (0..a.rows).all(|i| (0..i).all(|j| a[(i, j)] == a[(j, i)]));
+    if symmetric {
+        let mut vals = symmetric_eigenvalues(a);
+        vals.sort_by(|a, b| b.total_cmp(a));
+        env.push(vals.into_iter().collect::<Value>());
+        return Ok(());
+    }
+    let scale = a.data.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let mut vals = general_eigenvalues(a)
+        .ok_or_else(|| env.error("Eigenvalue computation did not converge"))?;
+    let tolerance = 100.0 * f64::EPSILON * scale;
+    for val in &mut vals {
+        if val.im.abs() <= tolerance {
+            val.im = 0.0;
+        }
+    }
+    vals.sort_by(|a, b| b.re.total_cmp(&a.re).then(b.im.total_cmp(&a.im)));
+    env.push(if vals.iter().all(|val| val.im == 0.0) {
+        vals.into_iter().map(|val| val.re).collect::<Value>()
+    } else {
+        Array::from_iter(vals).into()
+    });
+    Ok(())
+}
+
+/// Eigenvalues of a symmetric matrix using cyclic Jacobi rotations
+fn symmetric_eigenvalues(mut a: Matrix) -> Vec<f64> {
+    let n = a.rows;
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..i).map(move |j| (i, j)))
+            .map(|(i, j)| a[(i, j)] * a[(i, j)])
+            .sum();
+        let diag: f64 = (0..n).map(|i| a[(i, i)] * a[(i, i)]).sum();
+        if off <= f64::EPSILON * f64::EPSILON * diag {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[(p, q)] == 0.0 {
+                    continue;
+                }
+                let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * a[(p, q)]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (kp, kq) = (a[(k, p)], a[(k, q)]);
+                    a[(k, p)] = c * kp - s * kq;
+                    a[(k, q)] = s * kp + c * kq;
+                }
+                for k in 0..n {
+                    let (pk, qk) = (a[(p, k)], a[(q, k)]);
+                    a[(p, k)] = c * pk - s * qk;
+                    a[(q, k)] = s * pk + c * qk;
+                }
+            }
+        }
+    }
+    (0..n).map(|i| a[(i, i)]).collect()
+}
+
+/// Eigenvalues of a general matrix
+///
+/// The matrix is reduced to upper Hessenberg form, then deflated with
+/// Wilkinson-shifted QR steps in complex arithmetic
+fn general_eigenvalues(mut a: Matrix) -> Option<Vec<Complex>> {
+    let n = a.rows;
+    // Householder reduction to Hessenberg form
+    for k in 0..n.saturating_sub(2) {
+        let mut v: Vec<f64> = (k + 1..n).map(|i| a[(i, k)]).collect();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        v[0] += if v[0] < 0.0 { -norm } else { norm };
+        let vv: f64 = v.iter().map(|x| x * x).sum();
+        for j in 0..n {
+            let f = 2.0 * (0..v.len()).map(|i| v[i] * a[(k + 1 + i, j)]).sum::<f64>() / vv;
+            for (i, vi) in v.iter().enumerate() {
+                a[(k + 1 + i, j)] -= f * vi;
+            }
+        }
+        for i in 0..n {
+            let f = 2.0 * (0..v.len()).map(|j| a[(i, k + 1 + j)] * v[j]).sum::<f64>() / vv;
+            for (j, vj) in v.iter().enumerate() {
+                a[(i, k + 1 + j)] -= f * vj;
+            }
+        }
+    }
+    let mut h: Vec<Complex> = a.data.iter().map(|&x| x.into()).collect();
+    let at = |i: usize, j: usize| i * n + j;
+    let mut vals = Vec::with_capacity(n);
+    let mut hi = n;
+    let mut iters = 0;
+    let mut rots = Vec::with_capacity(n);
+    while hi > 0 {
+        // Find the start of the active unreduced block
+        let mut lo = hi - 1;
+        while lo > 0 {
+            let sub = h[at(lo, lo - 1)].abs();
+            if sub <= f64::EPSILON * (h[at(lo - 1, lo - 1)].abs() + h[at(lo, lo)].abs()) {
+                h[at(lo, lo - 1)] = Complex::ZERO;
+                break;
+            }
+            lo -= 1;
+        }
+        if lo == hi - 1 {
+            vals.push(h[at(lo, lo)]);
+            hi -= 1;
+            iters = 0;
+            continue;
+        }
+        iters += 1;
+        if iters > 30 * n {
+            return None;
+        }
+        // Wilkinson shift from the trailing 2×2 block
+        let (p, q) = (h[at(hi - 2, hi - 2)], h[at(hi - 2, hi - 1)]);
+        let (r, d) = (h[at(hi - 1, hi - 2)], h[at(hi - 1, hi - 1)]);
+        let mut shift = if iters % 11 == 10 {
+            d + r.abs()
+        } else {
+            let half = (p + d) * 0.5;
+            let disc = (half * half - (p * d - q * r)).sqrt();
+            let (s1, s2) = (half + disc, half - disc);
+            if (s1 - d).abs() < (s2 - d).abs() {
+                s1
+            } else {
+                s2
+            }
+        };
+        if shift.is_nan() {
+            shift = d;
+        }
+        for k in lo..hi {
+            h[at(k, k)] -= shift;
+        }
+        // H - μI = QR
+        rots.clear();
+        for k in lo..hi - 1 {
+            let (x, y) = (h[at(k, k)], h[at(k + 1, k)]);
+            let norm = x.abs().hypot(y.abs());
+            let (c, s) = if norm == 0.0 {
+                (Complex::ONE, Complex::ZERO)
+            } else {
+                (x / norm, y / norm)
+            };
+            for j in k..hi {
+                let (u, v) = (h[at(k, j)], h[at(k + 1, j)]);
+                h[at(k, j)] = c.conj() * u + s.conj() * v;
+                h[at(k + 1, j)] = c * v - s * u;
+            }
+            rots.push((c, s));
+        }
+        // RQ + μI
+        for (k, &(c, s)) in (lo..).zip(&rots) {
+            for i in lo..(k + 2).min(hi) {
+                let (u, v) = (h[at(i, k)], h[at(i, k + 1)]);
+                h[at(i, k)] = u * c + v * s;
+                h[at(i, k + 1)] = v * c.conj() - u * s.conj();
+            }
+        }
+        for k in lo..hi {
+            h[at(k, k)] += shift;
+        }
+    }
+    Some(vals)
+}
+
+pub fn einsum(env: &mut Uiua) -> UiuaResult {
+    let spec = env
+        .pop(1)?
+        .as_string(env, "Einsum specification must be a string")?;
+    let operands = match env.pop(2)? {
+        Value::Box(arr) if arr.rank() <= 1 => arr.data.iter().map(|b| b.0.clone()).collect(),
+        val => vec![val],
+    };
+    let operands = (operands.into_iter())
+        .map(|val| match val {
+            Value::Num(arr) => Ok(arr),
+            Value::Byte(arr) => Ok(arr.convert()),
+            val => Err(env.error(format!(
+                "Cannot {} {}",
+                Primitive::Einsum.format(),
+                val.type_name_plural()
+            ))),
+        })
+        .collect::<UiuaResult<Vec<Array<f64>>>>()?;
+
+    // Parse the specification
+    let indices = |term: &str| -> UiuaResult<Vec<char>> {
+        let term: Vec<char> = term.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(c) = term.iter().find(|c| !c.is_alphabetic()) {
+            return Err(env.error(format!("Invalid einsum index {c:?}")));
+        }
+        Ok(term)
+    };
+    let (inputs, output) = match spec.split_once("->") {
+        Some((inputs, output)) => (inputs, Some(output)),
+        None => (spec.as_str(), None),
+    };
+    let inputs = (inputs.split(','))
+        .map(indices)
+        .collect::<UiuaResult<Vec<_>>>()?;
+    if inputs.len() != operands.len() {
+        return Err(env.error(format!(
+            "Einsum specification has {} input(s), but {} array(s) were given",
+            inputs.len(),
+            operands.len()
+        )));
+    }
+    let mut letters: Vec<char> = Vec::new();
+    let mut dims: Vec<usize> = Vec::new();
+    for (term, arr) in inputs.iter().zip(&operands) {
+        if term.len() != arr.rank() {
+            return Err(env.error(format!(
+                "Einsum term {:?} has {} indices, but the array has rank {}",
+                term.iter().collect::<String>(),
+                term.len(),
+                arr.rank()
+            )));
+        }
+        for (&c, &dim) in term.iter().zip(arr.shape()) {
+            if let Some(i) = letters.iter().position(|&l| l == c) {
+                if dims[i] != dim {
+                    return Err(env.error(format!(
+                        "Einsum index {c:?} has inconsistent sizes {} and {dim}",
+                        dims[i]
+                    )));
+                }
+            } else {
+                letters.push(c);
+                dims.push(dim);
+            }
+        }
+    }
+    let output = match output {
+        Some(output) => {
+            let output = indices(output)?;
+            for (i, c) in output.iter().enumerate() {
+                if output[..i].contains(c) {
+                    return Err(env.error(format!("Einsum output index {c:?} is repeated")));
+                }
+                if !letters.contains(c) {
+                    return Err(env.error(format!(
+                        "Einsum output index {c:?} does not appear in the inputs"
+                    )));
+                }
+            }
+            output
+        }
+        None => {
+            // Implicitly, the output is the indices that appear once, in order
+            let mut output: Vec<char> = (letters.iter().copied())
+                .filter(|c| inputs.iter().flatten().filter(|d| *d == c).count() == 1)
+                .collect();
+            output.sort_unstable();
+            output
+        }
+    };
+
+    // Order the indices so that the output ones come first
+    let mut order: Vec<usize> = (output.iter())
+        .map(|c| letters.iter().position(|l| l == c).unwrap())
+        .collect();
+    let contracted: Vec<usize> = (0..letters.len()).filter(|i| !order.contains(i)).collect();
+    order.extend(contracted);
+    let dims: Vec<usize> = order.iter().map(|&i| dims[i]).collect();
+    let letters: Vec<char> = order.iter().map(|&i| letters[i]).collect();
+    let out_shape: Vec<usize> = dims[..output.len()].to_vec();
+    let out_len: usize = out_shape.iter().product();
+    let inner_len: usize = dims[output.len()..].iter().product();
+
+    // The stride of each index in each operand
+    let strides: Vec<Vec<usize>> = (inputs.iter().zip(&operands))
+        .map(|(term, arr)| {
+            let mut arr_strides = vec![0; arr.rank()];
+            let mut stride = 1;
+            for (i, &dim) in arr.shape().iter().enumerate().rev() {
+                arr_strides[i] = stride;
+                stride *= dim;
+            }
+            (letters.iter())
+                .map(|l| {
+                    (term.iter().zip(&arr_strides))
+                        .filter(|(c, _)| *c == l)
+                        .map(|(_, s)| s)
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut result = vec![0.0; out_len];
+    let mut counter = vec![0; letters.len()];
+    let mut offsets = vec![0; operands.len()];
+    for i in 0..out_len * inner_len {
+        let prod: f64 = (operands.iter().zip(&offsets))
+            .map(|(arr, &offset)| arr.data[offset])
+            .product();
+        result[i / inner_len] += prod;
+        // Advance the indices
+        for j in (0..letters.len()).rev() {
+            counter[j] += 1;
+            for (offset, strides) in offsets.iter_mut().zip(&strides) {
+                *offset += strides[j];
+            }
+            if counter[j] < dims[j] {
+                break;
+            }
+            counter[j] = 0;
+            for (offset, strides) in offsets.iter_mut().zip(&strides) {
+                *offset -= strides[j] * dims[j];
+            }
+        }
+    }
+    env.push(Array::new(out_shape.as_slice(), result.as_slice()));
+    Ok(())
+}
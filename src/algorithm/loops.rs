@@ -10,7 +10,11 @@ use std::{
 use ecow::{eco_vec, EcoVec};
 
 use crate::{
-    algorithm::{fixed_rows, get_ops, pervade::pervade_dim, FixedRowsData},
+    algorithm::{
+        fixed_rows, get_ops,
+        pervade::{add, max, min, mul, pervade_dim},
+        FixedRowsData,
+    },
     array::{Array, ArrayValue},
     cowslice::CowSlice,
     types::push_empty_rows_value,
@@ -19,7 +23,7 @@ use crate::{
     Boxed, Node, Ops, Primitive, ScalarNum, Shape, SigNode, Signature, Uiua, UiuaResult,
 };
 
-use super::{multi_output, validate_size_impl};
+use super::{arena::RowArena, multi_output, par_loop, validate_size_impl, MultiOutput};
 
 pub fn flip<A, B, C>(f: impl Fn(A, B) -> C + Copy) -> impl Fn(B, A) -> C + Copy {
     move |b, a| f(a, b)
@@ -163,12 +167,13 @@ pub fn repeat(ops: Ops, with_inverse: bool, count_convergence: bool, env: &mut U
         for output in outputs.into_iter().rev() {
             let mut new_value = Value::from_row_values(output, env)?;
             let mut new_shape = new_shape.clone();
+            if is_empty {
+                new_value = new_value.first_dim_zero();
+                new_shape[0] = 0;
+            }
             new_shape.extend_from_slice(&new_value.shape()[1..]);
             *new_value.shape_mut() = new_shape;
             new_value.validate_shape();
-            if is_empty {
-                new_value.pop_row();
-            }
             env.push(new_value);
         }
         Ok(())
@@ -202,7 +207,7 @@ fn repeat_impl(f: SigNode, inv: Option<SigNode>, n: f64, env: &mut Uiua) -> Uiua
             }
             env.exec(f.clone())?;
             let next = env.pop("converging function result")?;
-            let converged = next == prev;
+            let converged = next.approx_eq(&prev, env.rt.tolerance);
             if converged {
                 env.push(next);
                 break;
@@ -302,6 +307,161 @@ pub fn do_(ops: Ops, env: &mut Uiua) -> UiuaResult {
     env.remove_n(preserve_count, comp_sig.args)
 }
 
+/// The state of a [`Primitive::Stream`] that is consuming values from its generator
+#[derive(Clone)]
+pub(crate) struct StreamFrame {
+    consumer: SigNode,
+    limit: Option<usize>,
+    count: usize,
+    values: Vec<Value>,
+    stopped: bool,
+}
+
+pub fn stream(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [generator, consumer] = get_ops(ops, env)?;
+    let reducing = consumer.sig == Signature::new(2, 1);
+    if !reducing && consumer.sig != Signature::new(1, 1) {
+        return Err(env.error(format!(
+            "{}'s consumer must have signature {} or {}, but its signature is {}",
+            Primitive::Stream.format(),
+            Signature::new(1, 1),
+            Signature::new(2, 1),
+            consumer.sig
+        )));
+    }
+    let limit = (env.pop("stream limit")?)
+        .as_nat_or_inf(env, "Stream limit must be a natural number or infinity")?;
+    let mut values = Vec::new();
+    if limit == Some(0) {
+        for i in 0..generator.sig.args {
+            env.pop(i + 1)?;
+        }
+    } else {
+        let outputs = generator.sig.outputs;
+        env.rt.stream_stack.push(StreamFrame {
+            consumer,
+            limit,
+            count: 0,
+            values: Vec::new(),
+            stopped: false,
+        });
+        let res = env.exec_clean_stack(generator);
+        let frame = (env.rt.stream_stack.pop())
+            .ok_or_else(|| env.error("Stream frame is missing").bug())?;
+        match res {
+            Ok(()) => {
+                for i in 0..outputs {
+                    env.pop(("generator output", i + 1))?;
+                }
+            }
+            // The generator was stopped by yielding the last value
+            Err(_) if frame.stopped => {}
+            Err(e) => return Err(e),
+        }
+        values = frame.values;
+    }
+    if reducing {
+        let acc = values.pop().ok_or_else(|| {
+            env.error(format!(
+                "Cannot reduce a {} that yielded no values",
+                Primitive::Stream.format()
+            ))
+        })?;
+        env.push(acc);
+    } else {
+        env.push(Value::from_row_values(values, env)?);
+    }
+    Ok(())
+}
+
+pub fn yield_(env: &mut Uiua) -> UiuaResult {
+    let value = env.pop(1)?;
+    let Some(mut frame) = env.rt.stream_stack.pop() else {
+        return Err(env.error(format!(
+            "{} can only be used in a {}'s generator",
+            Primitive::Yield.format(),
+            Primitive::Stream.format()
+        )));
+    };
+    // The frame is removed while the consumer runs so that
+    // yields in the consumer go to any outer stream
+    let res = if frame.stopped {
+        Ok(())
+    } else if frame.consumer.sig.args == 2 {
+        match frame.values.pop() {
+            Some(acc) => {
+                env.push(value);
+                env.push(acc);
+                env.exec(frame.consumer.clone())
+                    .and_then(|_| env.pop("reduced value"))
+                    .map(|acc| frame.values.push(acc))
+            }
+            None => {
+                frame.values.push(value);
+                Ok(())
+            }
+        }
+    } else {
+        env.push(value);
+        env.exec(frame.consumer.clone())
+            .and_then(|_| env.pop("consumed value"))
+            .map(|val| frame.values.push(val))
+    };
+    frame.count += 1;
+    frame.stopped |= frame.limit.is_some_and(|limit| frame.count >= limit);
+    let stopped = frame.stopped;
+    env.rt.stream_stack.push(frame);
+    res?;
+    if stopped {
+        return Err(env.error(format!(
+            "{} was stopped because its {} has enough values",
+            Primitive::Yield.format(),
+            Primitive::Stream.format()
+        )));
+    }
+    Ok(())
+}
+
+/// A handler installed by [`Primitive::Handle`]
+#[derive(Clone)]
+pub(crate) struct HandlerFrame {
+    name: String,
+    handler: SigNode,
+}
+
+pub fn handle(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [handler, body] = get_ops(ops, env)?;
+    if handler.sig != Signature::new(1, 1) {
+        return Err(env.error(format!(
+            "{}'s handler must have signature {}, but its signature is {}",
+            Primitive::Handle.format(),
+            Signature::new(1, 1),
+            handler.sig
+        )));
+    }
+    let name = (env.pop("effect name")?).as_string(env, "Effect name must be a string")?;
+    let depth = env.rt.handler_stack.len();
+    env.rt.handler_stack.push(HandlerFrame { name, handler });
+    let res = env.exec(body);
+    env.rt.handler_stack.truncate(depth);
+    res
+}
+
+pub fn perform(env: &mut Uiua) -> UiuaResult {
+    let name = (env.pop("effect name")?).as_string(env, "Effect name must be a string")?;
+    let value = env.pop(2)?;
+    let Some(i) = (env.rt.handler_stack.iter()).rposition(|frame| frame.name == name) else {
+        return Err(env.error(format!("Unhandled effect {name:?}")));
+    };
+    // Handlers above and including the matching one are removed while it
+    // runs so that effects performed in the handler go to outer handlers
+    let frames = env.rt.handler_stack.split_off(i);
+    env.push(value);
+    let res = env.exec(frames[0].handler.clone());
+    env.rt.handler_stack.extend(frames);
+    res
+}
+
 pub fn split_by(f: SigNode, by_scalar: bool, keep_empty: bool, env: &mut Uiua) -> UiuaResult {
     let delim = env.pop(1)?;
     let haystack = env.pop(2)?;
@@ -484,14 +644,14 @@ where
                 let data = self.source.data.slice(start * row_len..end * row_len);
                 let mut shape = self.source.shape.clone();
                 shape[0] = end - start;
+                self.len -= 1;
                 return Some(Array::new(shape, data).into());
             }
         }
         None
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.len - self.curr;
-        (len, Some(len))
+        (self.len, Some(self.len))
     }
 }
 
@@ -507,7 +667,6 @@ where
     Array<T>: Into<Value>,
 {
     fn partition_groups(self, markers: &Array<i64>) -> Box<dyn ExactSizeIterator<Item = Value>> {
-        let mut groups = Vec::new();
         if markers.rank() == 1 {
             let mut count = 0;
             let mut last_marker = i64::MAX;
@@ -525,20 +684,17 @@ where
             })
         } else {
             let row_shape: Shape = self.shape()[markers.rank()..].into();
+            let row_len = row_shape.elements();
             let indices = multi_partition_indices(markers);
+            let row_count = indices.iter().map(|(_, indices)| indices.len()).sum();
+            let mut arena = RowArena::with_capacity(row_count, row_len);
             for (_, indices) in indices {
-                let mut group = Vec::with_capacity(indices.len());
                 for index in indices {
-                    group.push(self.row_shaped_slice(index, row_shape.clone()));
+                    arena.push_row(&self.data[index * row_len..(index + 1) * row_len]);
                 }
-                groups.push(group);
+                arena.end_group();
             }
-            Box::new(
-                groups
-                    .into_iter()
-                    .map(Array::from_row_arrays_infallible)
-                    .map(Into::into),
-            )
+            Box::new(arena.into_groups(row_shape).map(Into::into))
         }
     }
     fn partition_firsts(mut self, markers: &[i64]) -> Self {
@@ -852,6 +1008,110 @@ pub fn group(f: SigNode, env: &mut Uiua) -> UiuaResult {
     )
 }
 
+pub fn group_by(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    crate::profile_function!();
+    let [key, f] = get_ops(ops, env)?;
+    if key.sig != (1, 1) {
+        return Err(env.error(format!(
+            "{}'s key function must have signature |1, but its signature is {}",
+            Primitive::GroupBy.format(),
+            key.sig
+        )));
+    }
+    if f.sig.args > 1 {
+        return Err(env.error(format!(
+            "{}'s group function must take at most 1 argument, but its signature is {}",
+            Primitive::GroupBy.format(),
+            f.sig
+        )));
+    }
+    let xs = env.pop(1)?;
+    if xs.rank() == 0 {
+        return Err(env.error(format!("Cannot {} a scalar", Primitive::GroupBy.format())));
+    }
+    env.push(xs.clone());
+    env.exec(key)?;
+    let keys = env.pop("group keys")?;
+    if keys.rank() == 0 || keys.row_count() != xs.row_count() {
+        return Err(env.error(format!(
+            "{}'s key function must return an array with the same number of rows \
+            as its argument, but {} rows were returned for {} rows",
+            Primitive::GroupBy.format(),
+            if keys.rank() == 0 {
+                0
+            } else {
+                keys.row_count()
+            },
+            xs.row_count()
+        )));
+    }
+    // Hash the keys into group indices in order of first appearance
+    let indices = keys.classify();
+    // Reduce groups without creating them
+    if let Some(prim) = group_reduction(&f).filter(|_| xs.row_count() > 0) {
+        let arr = match &xs {
+            Value::Num(arr) => Some(arr.clone()),
+            Value::Byte(arr) if matches!(prim, Primitive::Add | Primitive::Mul) => {
+                Some(arr.convert_ref())
+            }
+            _ => None,
+        };
+        if let Some(arr) = arr {
+            let indices = indices.as_nats(env, "")?;
+            env.push(group_reduce(arr, &indices, prim));
+            return Ok(());
+        }
+    }
+    env.push(xs);
+    env.push(indices);
+    group(f, env)
+}
+
+/// Get the primitive of a group function that reduces each group
+fn group_reduction(f: &SigNode) -> Option<Primitive> {
+    use Primitive::*;
+    let [Node::Mod(Reduce, args, _)] = f.node.as_slice() else {
+        return None;
+    };
+    match args.as_slice() {
+        [g] => match g.node {
+            Node::Prim(prim @ (Add | Mul | Max | Min), _) => Some(prim),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reduce each group into an accumulator row as the rows are visited
+fn group_reduce(arr: Array<f64>, indices: &[usize], prim: Primitive) -> Array<f64> {
+    let f = match prim {
+        Primitive::Add => add::num_num,
+        Primitive::Mul => mul::num_num,
+        Primitive::Max => max::num_num,
+        Primitive::Min => min::num_num,
+        _ => unreachable!("group reduction with {prim}"),
+    };
+    let groups = indices.iter().max().map_or(0, |&i| i + 1);
+    let row_len = arr.row_len();
+    let mut data = eco_vec![0.0; groups * row_len];
+    let slice = data.make_mut();
+    let mut started = vec![false; groups];
+    for (&g, row) in indices.iter().zip(arr.row_slices()) {
+        let acc = &mut slice[g * row_len..(g + 1) * row_len];
+        if started[g] {
+            for (a, &b) in acc.iter_mut().zip(row) {
+                *a = f(*a, b);
+            }
+        } else {
+            acc.copy_from_slice(row);
+            started[g] = true;
+        }
+    }
+    let mut shape = arr.shape.clone();
+    shape[0] = groups;
+    Array::new(shape, data)
+}
+
 impl Value {
     fn group_groups(self, indices: &Array<isize>) -> Vec<Self> {
         val_as_arr!(self, |arr| arr
@@ -874,19 +1134,38 @@ impl Value {
 impl<T: ArrayValue> Array<T> {
     fn group_groups(self, indices: &Array<isize>) -> impl Iterator<Item = Self> {
         let Some(&max_index) = indices.data.iter().max() else {
-            return Vec::<Vec<Self>>::new()
-                .into_iter()
-                .map(Array::from_row_arrays_infallible);
+            return RowArena::with_capacity(0, 0).into_groups(Shape::default());
         };
         let buckets = (max_index.max(-1) + 1).max(0) as usize;
-        let mut groups: Vec<Vec<Self>> = vec![Vec::new(); buckets];
-        let row_shape = self.shape()[indices.rank()..].into();
-        for (&g, r) in (indices.data.iter()).zip(self.into_row_shaped_slices(row_shape)) {
-            if g >= 0 && g < buckets as isize {
-                groups[g as usize].push(r);
+        let row_shape: Shape = self.shape()[indices.rank()..].into();
+        let row_len = row_shape.elements();
+        // Sort the row indices by group
+        let mut starts = vec![0; buckets + 1];
+        for &g in &indices.data {
+            if g >= 0 {
+                starts[g as usize + 1] += 1;
+            }
+        }
+        for i in 1..starts.len() {
+            starts[i] += starts[i - 1];
+        }
+        let mut next = starts.clone();
+        let mut order = vec![0; starts[buckets]];
+        for (r, &g) in indices.data.iter().enumerate() {
+            if g >= 0 {
+                order[next[g as usize]] = r;
+                next[g as usize] += 1;
             }
         }
-        groups.into_iter().map(Array::from_row_arrays_infallible)
+        // Copy the rows into the arena
+        let mut arena = RowArena::with_capacity(order.len(), row_len);
+        for g in 0..buckets {
+            for &r in &order[starts[g]..starts[g + 1]] {
+                arena.push_row(&self.data[r * row_len..(r + 1) * row_len]);
+            }
+            arena.end_group();
+        }
+        arena.into_groups(row_shape)
     }
     fn group_firsts(self, indices: &[isize], env: &Uiua) -> UiuaResult<Self> {
         let buckets = (indices.iter().copied().max().unwrap_or(-1) + 1).max(0) as usize;
@@ -1039,7 +1318,14 @@ where
     I::IntoIter: ExactSizeIterator,
 {
     let sig = f.sig;
-    let indices = env.pop(1)?.as_number_array(env, indices_error)?;
+    let mut indices = env.pop(1)?;
+    // Interned arrays are grouped by their codes
+    if prim == Primitive::Group {
+        if let Some(categories) = indices.categories() {
+            indices = categories.codes.clone();
+        }
+    }
+    let indices = indices.as_number_array(env, indices_error)?;
     let values: Vec<Value> = (0..sig.args.max(1))
         .map(|i| env.pop(i + 2))
         .collect::<UiuaResult<_>>()?;
@@ -1097,7 +1383,7 @@ where
 
     let mut is_scalar = false;
     let mut group_count = 0;
-    let mut groups: Vec<_> = values
+    let groups: Vec<Vec<Value>> = values
         .into_iter()
         .map(|xs| {
             let mut empty_shape = xs.shape().clone();
@@ -1105,31 +1391,42 @@ where
             *empty_shape.row_count_mut() = 0;
             let groups = get_groups(xs, &indices).into_iter();
             group_count = groups.size_hint().0;
-            groups.map(move |mut group| {
-                if group.row_count() == 0 {
-                    group.shape_mut().clone_from(&empty_shape);
-                    group.validate_shape();
-                }
-                group
-            })
+            groups
+                .map(move |mut group| {
+                    if group.row_count() == 0 {
+                        group.shape_mut().clone_from(&empty_shape);
+                        group.validate_shape();
+                    }
+                    group
+                })
+                .collect()
         })
         .collect();
-    let mut rows = multi_output(sig.outputs, Vec::with_capacity(groups.len()));
-    env.without_fill(|env| -> UiuaResult {
-        for _ in 0..group_count {
-            for group in groups.iter_mut().rev() {
-                env.push(group.next().unwrap());
-            }
-            env.exec(f.clone())?;
-            for i in 0..sig.outputs {
-                let value = env.pop(|| format!("{}'s function result", prim.format()))?;
-                rows[i].push(value);
-            }
-            if sig.args == 0 {
-                env.pop("excess value")?;
-            }
-        }
-        Ok(())
+    let mut rows = multi_output(sig.outputs, Vec::with_capacity(group_count));
+    env.without_fill(|env| {
+        par_loop(
+            &f,
+            0..group_count,
+            |g, env| -> UiuaResult<MultiOutput<Value>> {
+                for group in groups.iter().rev() {
+                    env.push(group[g].clone());
+                }
+                env.exec(f.clone())?;
+                let outputs = (0..sig.outputs)
+                    .map(|_| env.pop(|| format!("{}'s function result", prim.format())))
+                    .collect::<UiuaResult<_>>()?;
+                if sig.args == 0 {
+                    env.pop("excess value")?;
+                }
+                Ok(outputs)
+            },
+            |outputs| {
+                for (rows, value) in rows.iter_mut().zip(outputs) {
+                    rows.push(value);
+                }
+            },
+            env,
+        )
     })?;
     for rows in rows.into_iter().rev() {
         let mut val = Value::from_row_values(rows, env)?;
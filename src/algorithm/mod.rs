@@ -13,25 +13,36 @@ use std::{
 };
 
 use ecow::{EcoString, EcoVec};
+use serde::{Deserialize, Serialize};
 use tinyvec::TinyVec;
 
 use crate::{
     cowslice::ecovec_extend_cowslice, Array, ArrayValue, Boxed, CodeSpan, Complex,
-    ExactDoubleIterator, Inputs, Ops, PersistentMeta, Shape, SigNode, Signature, Span, Uiua,
-    UiuaError, UiuaErrorKind, UiuaResult, Value,
+    ExactDoubleIterator, Inputs, Locale, MessageId, Ops, PersistentMeta, Primitive, Shape, SigNode,
+    Signature, Span, Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value,
 };
 
+mod arena;
 mod dyadic;
 pub mod encode;
+pub mod geometry;
+pub mod graph;
+mod heap;
+pub mod interp;
+pub mod linalg;
 pub mod loops;
 pub mod map;
 mod monadic;
+pub mod numeric;
 pub mod path;
 pub mod pervade;
 pub mod reduce;
+pub mod stats;
 pub mod stencil;
+mod string;
 pub mod table;
 pub mod tuples;
+pub mod view;
 pub mod zip;
 
 pub(crate) fn get_ops<const N: usize>(
@@ -40,13 +51,14 @@ pub(crate) fn get_ops<const N: usize>(
 ) -> UiuaResult<[SigNode; N]> {
     ops.try_into().map_err(|ops: EcoVec<SigNode>| {
         env.error(if ops.len() < N {
-            #[cfg(debug_assertions)]
-            panic!("Not enough operands");
-            #[cfg(not(debug_assertions))]
+            if cfg!(debug_assertions) && !env.rt.hardened {
+                panic!("Not enough operands");
+            }
             "Not enough operands. This is a bug in the interpreter."
         } else {
             "Too many operands.  This is a bug in the interpreter."
         })
+        .bug()
     })
 }
 
@@ -117,6 +129,43 @@ impl fmt::Display for FillShapeError {
     }
 }
 
+/// How [`reshape`](Primitive::Reshape) makes an array's data fit a shape
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ReshapeMode {
+    /// Pad with the fill value if there is one, otherwise cycle the data
+    #[default]
+    Auto,
+    /// Error if the data does not exactly fit the shape
+    Exact,
+    /// Cycle the data, even if there is a fill value
+    Cycle,
+    /// Pad with the fill value, which is required
+    Fill,
+}
+
+impl ReshapeMode {
+    /// Get the mode selected by a subscript
+    pub fn from_subscript(n: i32) -> Option<Self> {
+        Some(match n {
+            0 => ReshapeMode::Exact,
+            1 => ReshapeMode::Cycle,
+            2 => ReshapeMode::Fill,
+            _ => return None,
+        })
+    }
+    /// Get the subscript that selects this mode
+    pub fn subscript(&self) -> Option<i32> {
+        Some(match self {
+            ReshapeMode::Auto => return None,
+            ReshapeMode::Exact => 0,
+            ReshapeMode::Cycle => 1,
+            ReshapeMode::Fill => 2,
+        })
+    }
+}
+
 pub fn validate_size<T>(sizes: impl IntoIterator<Item = usize>, env: &Uiua) -> UiuaResult<usize> {
     validate_size_of::<T>(sizes).map_err(|e| env.error(e))
 }
@@ -177,6 +226,12 @@ pub(crate) fn validate_size_impl(
 pub trait ErrorContext {
     type Error: FillError;
     fn error(&self, msg: impl ToString) -> Self::Error;
+    fn locale(&self) -> Locale {
+        Locale::default()
+    }
+    fn message_error(&self, id: MessageId, args: &[&dyn fmt::Display]) -> Self::Error {
+        self.error(id.format(self.locale(), args))
+    }
 }
 
 impl ErrorContext for Uiua {
@@ -184,6 +239,12 @@ impl ErrorContext for Uiua {
     fn error(&self, msg: impl ToString) -> Self::Error {
         self.error(msg)
     }
+    fn locale(&self) -> Locale {
+        self.locale()
+    }
+    fn message_error(&self, id: MessageId, args: &[&dyn fmt::Display]) -> Self::Error {
+        self.message_error(id, args)
+    }
 }
 
 impl ErrorContext for (&CodeSpan, &Inputs) {
@@ -213,6 +274,13 @@ impl ErrorContext for IgnoreError {
 
 pub trait FillError: fmt::Debug {
     fn is_fill(&self) -> bool;
+    /// Attach a diagram of two shapes that do not agree along an axis
+    fn with_shape_diagram(self, _a: &[usize], _b: &[usize], _axis: usize, _locale: Locale) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
 
 impl FillError for () {
@@ -225,6 +293,9 @@ impl FillError for UiuaError {
     fn is_fill(&self) -> bool {
         self.is_fill
     }
+    fn with_shape_diagram(self, a: &[usize], b: &[usize], axis: usize, locale: Locale) -> Self {
+        self.with_info(shape_mismatch_info(a, b, axis, locale))
+    }
 }
 
 impl FillError for Infallible {
@@ -325,6 +396,67 @@ pub(crate) fn shape_prefixes_match(a: &[usize], b: &[usize]) -> bool {
     a.iter().zip(b).all(|(a, b)| a == b)
 }
 
+/// Get the first axis along which two shapes' prefixes do not match
+pub(crate) fn shape_prefix_mismatch(a: &[usize], b: &[usize]) -> Option<usize> {
+    a.iter().zip(b).position(|(a, b)| a != b)
+}
+
+/// A diagram of two shapes aligned by axis, with one axis underlined
+pub(crate) struct ShapeDiagram<'a> {
+    pub a: &'a [usize],
+    pub b: &'a [usize],
+    pub axis: usize,
+    pub locale: Locale,
+}
+
+impl fmt::Display for ShapeDiagram<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = self.a.len().max(self.b.len());
+        let widths: Vec<usize> = (0..rank)
+            .map(|i| {
+                [self.a.get(i), self.b.get(i)]
+                    .into_iter()
+                    .flatten()
+                    .map(|dim| dim.to_string().len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let heading = MessageId::ShapesDifferAlongAxis.format(self.locale, &[&self.axis]);
+        writeln!(f, "{heading}")?;
+        for shape in [self.a, self.b] {
+            write!(f, "  [")?;
+            for (i, dim) in shape.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " × ")?;
+                }
+                write!(f, "{dim:>0$}", widths[i])?;
+            }
+            writeln!(f, "]")?;
+        }
+        let indent = 3 + widths.iter().take(self.axis).map(|w| w + 3).sum::<usize>();
+        let width = widths.get(self.axis).copied().unwrap_or(1);
+        write!(f, "{:indent$}{}", "", "─".repeat(width))
+    }
+}
+
+/// Get the info to attach to an error about shapes that do not agree along an axis
+pub(crate) fn shape_mismatch_info(
+    a: &[usize],
+    b: &[usize],
+    axis: usize,
+    locale: Locale,
+) -> [(String, Option<Span>); 2] {
+    let hint = MessageId::ShapeMismatchHint.format(
+        locale,
+        &[&Primitive::Fill.format(), &Primitive::Fix.format()],
+    );
+    [
+        (ShapeDiagram { a, b, axis, locale }.to_string(), None),
+        (hint, None),
+    ]
+}
+
 fn fill_value_shape<C>(
     val: &mut Value,
     target: &Shape,
@@ -448,17 +580,26 @@ where
     {
         Ok(())
     } else {
-        Err(C::fill_error(ctx.error(match (a_err, b_err) {
+        let error = match (a_err, b_err) {
             (Some(FillShapeError::Size(e)), _) | (_, Some(FillShapeError::Size(e))) => {
-                e.to_string()
+                return Err(C::fill_error(ctx.error(e)))
             }
             (Some(e), _) | (_, Some(e)) => {
-                format!("Shapes {} and {} do not match{e}", a.shape(), b.shape())
+                let message =
+                    MessageId::ShapeMismatch.format(ctx.locale(), &[a.shape(), b.shape()]);
+                ctx.error(format!("{message}{e}"))
             }
-            (None, None) => {
-                format!("Shapes {} and {} do not match", a.shape(), b.shape())
-            }
-        })))
+            (None, None) => ctx.message_error(MessageId::ShapeMismatch, &[a.shape(), b.shape()]),
+        };
+        // If only the ranks differ, the first axis past the shorter shape is the culprit
+        let axis =
+            shape_prefix_mismatch(a.shape(), b.shape()).unwrap_or_else(|| a.rank().min(b.rank()));
+        Err(C::fill_error(error.with_shape_diagram(
+            a.shape(),
+            b.shape(),
+            axis,
+            ctx.locale(),
+        )))
     }
 }
 
@@ -600,7 +741,7 @@ pub fn switch(
             if all_scalar {
                 new_value.undo_fix();
             } else if is_empty {
-                new_value.pop_row();
+                new_value = new_value.first_dim_zero();
             }
             new_shape.extend_from_slice(&new_value.shape()[1..]);
             *new_value.shape_mut() = new_shape;
@@ -641,6 +782,122 @@ pub fn try_(ops: Ops, env: &mut Uiua) -> UiuaResult {
     Ok(())
 }
 
+/// Throw a structured error value whose payload maps names to values
+fn throw_structured(
+    message: String,
+    fields: impl IntoIterator<Item = (&'static str, Value)>,
+    env: &Uiua,
+) -> UiuaResult {
+    let (keys, values): (Vec<_>, Vec<_>) = (fields.into_iter())
+        .map(|(key, val)| (Boxed(key.into()), Boxed(val)))
+        .unzip();
+    let mut payload = Value::from_iter(values);
+    payload.map(Value::from_iter(keys), env)?;
+    let value = Value::error_value(message.into(), payload, env)?;
+    Err(UiuaErrorKind::Throw(value.into(), env.span(), env.asm.inputs.clone().into()).into())
+}
+
+pub fn expect(env: &mut Uiua) -> UiuaResult {
+    let expected = env.pop("expected value")?;
+    let actual = env.pop("actual value")?;
+    let tolerance = if env.rt.match_tolerance {
+        env.rt.tolerance
+    } else {
+        0.0
+    };
+    if actual.approx_eq(&expected, tolerance) {
+        return Ok(());
+    }
+    let message = format!(
+        "Expected {}, but got {}",
+        expected.format(),
+        actual.format()
+    );
+    let span = env.span().to_string();
+    throw_structured(
+        message,
+        [
+            ("expected", expected),
+            ("actual", actual),
+            ("span", span.into()),
+        ],
+        env,
+    )
+}
+
+pub fn requires(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [cond, f] = get_ops(ops, env)?;
+    if env.rt.contracts {
+        check_contract(cond, f.sig.args, "Precondition", "arguments", env)?;
+    }
+    env.exec(f)
+}
+
+pub fn ensures(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [cond, f] = get_ops(ops, env)?;
+    let outputs = f.sig.outputs;
+    env.exec(f)?;
+    if env.rt.contracts {
+        check_contract(cond, outputs, "Postcondition", "outputs", env)?;
+    }
+    Ok(())
+}
+
+/// Run a contract's condition on copies of the top values of the stack
+fn check_contract(
+    cond: SigNode,
+    available: usize,
+    kind: &str,
+    checked: &'static str,
+    env: &mut Uiua,
+) -> UiuaResult {
+    if cond.sig.outputs != 1 || cond.sig.args > available {
+        return Err(env.error(format!(
+            "{kind}'s signature must be at most {}, but it is {}",
+            Signature::new(available, 1),
+            cond.sig
+        )));
+    }
+    let vals = env.copy_n(cond.sig.args)?;
+    for val in vals.iter().cloned() {
+        env.push(val);
+    }
+    env.exec(cond)?;
+    let res = env.pop("condition result")?;
+    if res.as_nat(env, "").is_ok_and(|n| n == 1) {
+        return Ok(());
+    }
+    let vals = Value::from_iter(vals.into_iter().rev().map(Boxed));
+    throw_structured(format!("{kind} failed"), [(checked, vals)], env)
+}
+
+pub fn sort_by(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    if f.sig != (1, 1) {
+        return Err(env.error(format!(
+            "{}'s function must have signature |1, but its signature is {}",
+            Primitive::SortBy.format(),
+            f.sig
+        )));
+    }
+    let xs = env.pop(1)?;
+    env.push(xs.clone());
+    env.exec(f)?;
+    let keys = env.pop("sort keys")?;
+    if keys.row_count() != xs.row_count() {
+        return Err(env.error(format!(
+            "{}'s function must return an array with the same number of rows \
+            as its argument, but {} rows were returned for {} rows",
+            Primitive::SortBy.format(),
+            keys.row_count(),
+            xs.row_count()
+        )));
+    }
+    let sorted = Value::from(keys.rise()).select(xs, env)?;
+    env.push(sorted);
+    Ok(())
+}
+
 pub fn format(parts: &[EcoString], env: &mut Uiua) -> UiuaResult {
     fn format_val(chars: &mut EcoVec<char>, val: Value) {
         match val {
@@ -728,11 +985,18 @@ fn fixed_rows(
         for b in a + 1..args.len() {
             let b_row_count = args[b].row_count();
             if a_row_count != b_row_count && !(a_row_count == 1 || b_row_count == 1) {
-                return Err(env.error(format!(
-                    "Cannot {prim} arrays with different number of rows, shapes {} and {}",
-                    args[a].shape(),
-                    args[b].shape(),
-                )));
+                return Err(env
+                    .error(format!(
+                        "Cannot {prim} arrays with different number of rows, shapes {} and {}",
+                        args[a].shape(),
+                        args[b].shape(),
+                    ))
+                    .with_info(shape_mismatch_info(
+                        args[a].shape(),
+                        args[b].shape(),
+                        0,
+                        env.locale(),
+                    )));
             }
         }
     }
@@ -771,35 +1035,150 @@ fn fixed_rows(
     })
 }
 
+/// The minimum estimated duration, in seconds, of a loop for its
+/// remaining iterations to be run in parallel with [`Parallelism::Auto`](crate::Parallelism::Auto)
+#[cfg(not(target_arch = "wasm32"))]
+const PAR_LOOP_MIN_SECS: f64 = 0.002;
+
+/// The number of iterations in each chunk of a loop run with
+/// [`Parallelism::Deterministic`](crate::Parallelism::Deterministic)
+#[cfg(not(target_arch = "wasm32"))]
+const PAR_LOOP_CHUNK_LEN: usize = 16;
+
+/// Run the iterations of a loop of a function, possibly in parallel
+///
+/// `items` gives the input of each iteration, `iteration` runs an iteration
+/// on its input, and `collect` receives each iteration's result.
+///
+/// Items are pulled lazily while the loop runs serially, and are only
+/// collected up front once the loop is going to run in parallel.
+///
+/// Iterations are only run in parallel if the function is pure. How they
+/// are distributed depends on the environment's [`Parallelism`](crate::Parallelism). Results
+/// are always collected in order, and if multiple iterations fail, the
+/// error from the lowest one is returned, just as if the loop had been run
+/// serially.
+pub(crate) fn par_loop<A: Send, T: Send>(
+    f: &SigNode,
+    items: impl ExactSizeIterator<Item = A>,
+    iteration: impl Fn(A, &mut Uiua) -> UiuaResult<T> + Sync,
+    mut collect: impl FnMut(T),
+    env: &mut Uiua,
+) -> UiuaResult {
+    #[cfg(not(target_arch = "wasm32"))]
+    if items.len() > 1
+        && env.rt.parallelism != crate::Parallelism::Off
+        && rayon::current_num_threads() > 1
+        && f.node.is_pure(crate::Purity::Pure, &env.asm)
+    {
+        use crate::Parallelism;
+        use rayon::prelude::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut items = items;
+        // Iterations after the lowest failed iteration need not be run
+        let failed = AtomicUsize::new(usize::MAX);
+        let run = |i: usize, item: A, env: &mut Uiua| {
+            if i > failed.load(Ordering::Relaxed) {
+                return None;
+            }
+            let res = iteration(item, env);
+            if res.is_err() {
+                failed.fetch_min(i, Ordering::Relaxed);
+                env.rt.stack.clear();
+                env.rt.under_stack.clear();
+            }
+            Some(res)
+        };
+        let results: Vec<Option<UiuaResult<T>>> = match env.rt.parallelism {
+            Parallelism::Off => unreachable!(),
+            Parallelism::Auto => {
+                // Time the first iteration to decide if parallelism is worth it
+                let count = items.len();
+                let start = env.rt.backend.now();
+                if let Some(item) = items.next() {
+                    collect(iteration(item, env)?);
+                }
+                let estimate = (env.rt.backend.now() - start) * (count - 1) as f64;
+                if estimate < PAR_LOOP_MIN_SECS {
+                    for item in items {
+                        collect(iteration(item, env)?);
+                    }
+                    return Ok(());
+                }
+                let worker = env.parallel_worker();
+                (items.collect::<Vec<_>>())
+                    .into_par_iter()
+                    .enumerate()
+                    .map_init(|| worker.clone(), |env, (i, item)| run(i, item, env))
+                    .collect()
+            }
+            Parallelism::Deterministic => {
+                // Each chunk is run serially in its own environment
+                let worker = env.parallel_worker();
+                let chunks: Vec<Vec<Option<UiuaResult<T>>>> = (items.collect::<Vec<_>>())
+                    .into_par_iter()
+                    .enumerate()
+                    .chunks(PAR_LOOP_CHUNK_LEN)
+                    .map(|chunk| {
+                        let mut env = worker.clone();
+                        let mut results = Vec::with_capacity(chunk.len());
+                        for (i, item) in chunk {
+                            let res = run(i, item, &mut env);
+                            let stop = !matches!(res, Some(Ok(_)));
+                            results.push(res);
+                            if stop {
+                                break;
+                            }
+                        }
+                        results
+                    })
+                    .collect();
+                chunks.into_iter().flatten().collect()
+            }
+        };
+        for res in results {
+            match res {
+                Some(res) => collect(res?),
+                None => break,
+            }
+        }
+        return Ok(());
+    }
+    for item in items {
+        collect(iteration(item, env)?);
+    }
+    Ok(())
+}
+
 #[cfg(not(feature = "fft"))]
-pub fn fft(env: &mut Uiua) -> UiuaResult {
-    Err(env.error("FFT is not available in this environment"))
+pub fn fft(_axes: usize, env: &mut Uiua) -> UiuaResult {
+    Err(env
+        .error("FFT is not available in this environment")
+        .system())
 }
 
 #[cfg(not(feature = "fft"))]
-pub fn unfft(env: &mut Uiua) -> UiuaResult {
-    Err(env.error("FFT is not available in this environment"))
+pub fn unfft(_axes: usize, env: &mut Uiua) -> UiuaResult {
+    Err(env
+        .error("FFT is not available in this environment")
+        .system())
 }
 
+/// Run the FFT over the last `axes` axes of an array
 #[cfg(feature = "fft")]
-pub fn fft(env: &mut Uiua) -> UiuaResult {
-    fft_impl(env, rustfft::FftPlanner::plan_fft_forward)
+pub fn fft(axes: usize, env: &mut Uiua) -> UiuaResult {
+    fft_impl(axes, false, env)
 }
 
+/// Run the inverse FFT over the last `axes` axes of an array
 #[cfg(feature = "fft")]
-pub fn unfft(env: &mut Uiua) -> UiuaResult {
-    fft_impl(env, rustfft::FftPlanner::plan_fft_inverse)
+pub fn unfft(axes: usize, env: &mut Uiua) -> UiuaResult {
+    fft_impl(axes, true, env)
 }
 
 #[cfg(feature = "fft")]
-fn fft_impl(
-    env: &mut Uiua,
-    plan: fn(&mut rustfft::FftPlanner<f64>, usize) -> std::sync::Arc<dyn rustfft::Fft<f64>>,
-) -> UiuaResult {
-    use bytemuck::must_cast_slice_mut;
-
-    use rustfft::{num_complex::Complex64, FftPlanner};
-
+fn fft_impl(axes: usize, inverse: bool, env: &mut Uiua) -> UiuaResult {
     use crate::Complex;
 
     let mut arr: Array<Complex> = match env.pop(1)? {
@@ -814,25 +1193,195 @@ fn fft_impl(
         env.push(0);
         return Ok(());
     }
-    let list_row_len: usize = arr.shape[arr.rank() - 1..].iter().product();
-    if list_row_len == 0 {
+    let axes = axes.min(arr.rank());
+    let transformed: usize = arr.shape[arr.rank() - axes..].iter().product();
+    if transformed == 0 {
         env.push(arr);
         return Ok(());
     }
+    let shape = arr.shape.clone();
+    fft_nd(arr.data.as_mut_slice(), &shape, axes, inverse);
+    let scaling_factor = 1.0 / (transformed as f64).sqrt();
+    for c in arr.data.as_mut_slice() {
+        *c *= scaling_factor;
+    }
+    env.push(arr);
+    Ok(())
+}
+
+/// Convolve two arrays of the same rank
+///
+/// Small inputs are convolved directly. Larger ones are multiplied in
+/// the frequency domain if FFT is available.
+pub fn convolve(env: &mut Uiua) -> UiuaResult {
+    let a = env.pop(1)?;
+    let b = env.pop(2)?;
+    if a.rank() != b.rank() {
+        return Err(env.error(format!(
+            "Cannot convolve arrays of rank {} and {}",
+            a.rank(),
+            b.rank()
+        )));
+    }
+    let shape: Shape = (a.shape().iter().zip(b.shape().iter()))
+        .map(|(&a, &b)| if a == 0 || b == 0 { 0 } else { a + b - 1 })
+        .collect();
+    let val: Value = match (a, b) {
+        (Value::Complex(a), b) | (b, Value::Complex(a)) => {
+            let b: Array<Complex> = complex_operand(b, env)?;
+            let data = convolve_complex(&a, &b, &shape);
+            Array::new(shape, data.into_iter().collect::<EcoVec<_>>()).into()
+        }
+        (a, b) => {
+            let a = real_operand(a, env)?;
+            let b = real_operand(b, env)?;
+            let integers = (a.data.iter().chain(b.data.iter())).all(|x| x.fract() == 0.0);
+            let data: EcoVec<f64> = if use_fft_convolution(&a.shape, &b.shape, &shape) {
+                let a = a.convert::<Complex>();
+                let b = b.convert::<Complex>();
+                let data = convolve_complex(&a, &b, &shape).into_iter();
+                if integers {
+                    data.map(|c| c.re.round()).collect()
+                } else {
+                    data.map(|c| c.re).collect()
+                }
+            } else {
+                convolve_direct(&a, &b, &shape).into_iter().collect()
+            };
+            Array::new(shape, data).into()
+        }
+    };
+    env.push(val);
+    Ok(())
+}
+
+fn real_operand(val: Value, env: &Uiua) -> UiuaResult<Array<f64>> {
+    match val {
+        Value::Num(arr) => Ok(arr),
+        Value::Byte(arr) => Ok(arr.convert()),
+        val => Err(env.error(format!("Cannot convolve {}", val.type_name_plural()))),
+    }
+}
+
+fn complex_operand(val: Value, env: &Uiua) -> UiuaResult<Array<Complex>> {
+    match val {
+        Value::Complex(arr) => Ok(arr),
+        val => Ok(real_operand(val, env)?.convert()),
+    }
+}
+
+/// Whether multiplying in the frequency domain is expected to beat direct convolution
+fn use_fft_convolution(a: &Shape, b: &Shape, out: &Shape) -> bool {
+    let out = out.elements() as f64;
+    cfg!(feature = "fft") && (a.elements() * b.elements()) as f64 > 16.0 * out * out.log2().max(1.0)
+}
+
+/// The flat offset in the output of each element of an operand
+fn convolution_offsets(shape: &Shape, out: &Shape) -> Vec<usize> {
+    let mut offsets = vec![0];
+    for (&len, out_stride) in shape.iter().zip(out_strides(out)) {
+        offsets = (offsets.iter())
+            .flat_map(|&offset| (0..len).map(move |i| offset + i * out_stride))
+            .collect();
+    }
+    offsets
+}
+
+fn out_strides(out: &Shape) -> Vec<usize> {
+    (0..out.len())
+        .map(|i| out[i + 1..].iter().product())
+        .collect()
+}
+
+fn convolve_direct<T>(a: &Array<T>, b: &Array<T>, out: &Shape) -> Vec<T>
+where
+    T: ArrayValue + Copy + Default + std::ops::Add<Output = T> + std::ops::Mul<Output = T>,
+{
+    let mut data = vec![T::default(); out.elements()];
+    if data.is_empty() {
+        return data;
+    }
+    let b_offsets = convolution_offsets(&b.shape, out);
+    for (&x, a_offset) in a.data.iter().zip(convolution_offsets(&a.shape, out)) {
+        for (&y, b_offset) in b.data.iter().zip(&b_offsets) {
+            let z = &mut data[a_offset + b_offset];
+            *z = *z + x * y;
+        }
+    }
+    data
+}
+
+#[cfg(not(feature = "fft"))]
+fn convolve_complex(a: &Array<Complex>, b: &Array<Complex>, out: &Shape) -> Vec<Complex> {
+    convolve_direct(a, b, out)
+}
+
+#[cfg(feature = "fft")]
+fn convolve_complex(a: &Array<Complex>, b: &Array<Complex>, out: &Shape) -> Vec<Complex> {
+    if out.elements() == 0 || !use_fft_convolution(&a.shape, &b.shape, out) {
+        return convolve_direct(a, b, out);
+    }
+    let pad = |arr: &Array<Complex>| {
+        let mut padded = vec![Complex::ZERO; out.elements()];
+        for (&x, offset) in arr.data.iter().zip(convolution_offsets(&arr.shape, out)) {
+            padded[offset] = x;
+        }
+        fft_nd(&mut padded, out, out.len(), false);
+        padded
+    };
+    let mut data = pad(a);
+    for (x, y) in data.iter_mut().zip(pad(b)) {
+        *x *= y;
+    }
+    fft_nd(&mut data, out, out.len(), true);
+    let scaling_factor = 1.0 / out.elements() as f64;
+    for c in &mut data {
+        *c *= scaling_factor;
+    }
+    data
+}
+
+/// Run an unscaled FFT in place over the last `axes` axes of row-major data
+#[cfg(feature = "fft")]
+fn fft_nd(data: &mut [crate::Complex], shape: &[usize], axes: usize, inverse: bool) {
+    use bytemuck::must_cast_slice_mut;
+    use rustfft::{num_complex::Complex64, FftPlanner};
+
+    if data.is_empty() {
+        return;
+    }
     let mut planner = FftPlanner::new();
-    let scaling_factor = 1.0 / (list_row_len as f64).sqrt();
-    for row in arr.data.as_mut_slice().chunks_exact_mut(list_row_len) {
-        let fft = plan(&mut planner, row.len());
+    let mut buffer = Vec::new();
+    for axis in shape.len() - axes..shape.len() {
+        let len = shape[axis];
+        if len <= 1 {
+            continue;
+        }
+        let fft = if inverse {
+            planner.plan_fft_inverse(len)
+        } else {
+            planner.plan_fft_forward(len)
+        };
+        let stride: usize = shape[axis + 1..].iter().product();
         // NOTE: This works as long as Uiua's `complex` and `num_complex::Complex64` have
         // the same layout. the `Complex64` layout should remain stable since they are
         // maintaining compatibility with C. So we only need to ensure that we keep
         // the same (real, imaginary) ordering that they do.
-        let slice: &mut [Complex64] = must_cast_slice_mut(row);
-        fft.process(slice);
-        for c in row {
-            *c *= scaling_factor;
+        if stride == 1 {
+            let slice: &mut [Complex64] = must_cast_slice_mut(data);
+            fft.process(slice);
+            continue;
+        }
+        // Lanes along inner axes are not contiguous, so they are gathered into a buffer
+        for block in data.chunks_exact_mut(len * stride) {
+            for offset in 0..stride {
+                buffer.clear();
+                buffer.extend((0..len).map(|i| block[i * stride + offset]));
+                fft.process(must_cast_slice_mut(buffer.as_mut_slice()));
+                for (i, c) in buffer.iter().enumerate() {
+                    block[i * stride + offset] = *c;
+                }
+            }
         }
     }
-    env.push(arr);
-    Ok(())
 }
@@ -11,7 +11,9 @@ use std::{
     io::Write,
     iter::{self, once},
     mem::size_of,
+    ops::Range,
     ptr, slice,
+    sync::Arc,
     time::Duration,
 };
 
@@ -22,15 +24,17 @@ use time::{Date, Month, OffsetDateTime, Time};
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
+    algorithm::encode::{DsvOptions, TableOptions, TableStyle},
     array::*,
     cowslice::{cowslice, CowSlice},
     grid_fmt::GridFmt,
+    lex::split_tokens,
     val_as_arr,
     value::Value,
     Boxed, Complex, Primitive, Shape, Uiua, UiuaResult,
 };
 
-use super::{validate_size, ArrayCmpSlice, FillContext};
+use super::{validate_size, ArrayCmpSlice, FillContext, ReshapeMode};
 
 impl Value {
     /// Make the value 1-dimensional
@@ -173,7 +177,7 @@ impl Value {
             } else {
                 let spec: Vec<Result<isize, bool>> =
                     orig_shape.iter().map(|&d| Ok(d as isize)).collect();
-                self.reshape_impl(&spec, env)
+                self.reshape_impl(&spec, ReshapeMode::Auto, env)
             }
         }
     }
@@ -198,7 +202,8 @@ impl Value {
                 match (
                     s.strip_suffix("i").and_then(|s| s.split_once("r")),
                     s.strip_suffix("i").and_then(|s| s.split_once("+")),
-                    s.strip_suffix("i").and_then(|s| s.split_once("-")),
+                    (s.strip_suffix("i").and_then(|s| s.split_once("-")))
+                        .filter(|(re, _)| !re.is_empty()),
                 ) {
                     (Some((re, im)), None, _) | (None, Some((re, im)), _) => {
                         let re = parse_uiua_num(re.into(), env);
@@ -212,8 +217,13 @@ impl Value {
                         re.and_then(|re| im.map(|im| Complex { re, im: -im }.into()))
                             .or_else(|e| env.value_fill().cloned().ok_or(e))?
                     }
-                    _ => parse_uiua_num(s.into(), env)
+                    _ => parse_uiua_num(s.as_str().into(), env)
                         .map(Into::into)
+                        .or_else(|e| {
+                            parse_uiua_imag(&s, env)
+                                .map(|im| Complex { re: 0.0, im }.into())
+                                .ok_or(e)
+                        })
                         .or_else(|e| env.value_fill().cloned().ok_or(e))?,
                 }
             }
@@ -319,6 +329,15 @@ impl Value {
     }
 }
 
+/// Parse the imaginary part of a purely imaginary number like `3i`
+fn parse_uiua_imag(s: &str, env: &Uiua) -> Option<f64> {
+    match s.strip_suffix('i')? {
+        "" => Some(1.0),
+        "-" | "`" | "¯" => Some(-1.0),
+        im => parse_uiua_num(im.into(), env).ok(),
+    }
+}
+
 fn parse_uiua_num(mut s: Cow<str>, env: &Uiua) -> UiuaResult<f64> {
     let mut mul = 1.0;
     if s.contains('¯') {
@@ -888,7 +907,7 @@ impl<T: ArrayValue> Array<T> {
         }
         let forward = amnt.is_positive();
         // Early return if any dimension is 0, because there are no elements
-        if self.shape[depth..].iter().any(|&d| d == 0) || depth > 0 && self.shape[depth - 1] == 0 {
+        if self.data.is_empty() {
             if forward {
                 self.shape[depth..].rotate_left(trans_count);
             } else {
@@ -897,53 +916,32 @@ impl<T: ArrayValue> Array<T> {
             return;
         }
         let square_matrix = trans_rank == 2 && self.shape[depth] == self.shape[depth + 1];
-        let s = self.shape[depth];
         // Count the number of subarrays
         let subs: usize = if forward {
             self.shape[depth..].iter().take(trans_count).product()
         } else {
             self.shape[depth..].iter().rev().skip(trans_count).product()
         };
-        let data_slice = self.data.as_mut_slice();
-        // Divide the array into chunks at the given depth
-        for data in data_slice.chunks_exact_mut(self.shape[depth..].iter().product()) {
+        let chunk_len: usize = self.shape[depth..].iter().product();
+        // Any transpose is a matrix transpose of each chunk,
+        // where the subarrays are the rows of the matrix
+        let stride = chunk_len / subs;
+        if square_matrix {
             // Special in-place case for square matrices
-            if square_matrix {
-                if subs > 500 {
-                    // This is pretty unsafe, but no indices should collide, so it's fine? 🤷
-                    let ptr: usize = data.as_mut_ptr() as usize;
-                    (0..s - 1).into_par_iter().for_each(|i| {
-                        let ptr: *mut T = ptr as *mut _;
-                        for j in i + 1..s {
-                            unsafe {
-                                ptr::swap_nonoverlapping(ptr.add(i * s + j), ptr.add(j * s + i), 1);
-                            }
-                        }
-                    });
-                } else {
-                    for i in 0..s - 1 {
-                        for j in i + 1..s {
-                            data.swap(i * s + j, j * s + i);
-                        }
-                    }
-                }
-                continue;
-            }
-            let stride = data.len() / subs;
-            // The operation to perform on each subarray
-            let op = |(temp_chunk_i, chunk): (usize, &mut [T])| {
-                for (chunk_i, item) in chunk.iter_mut().enumerate() {
-                    *item = data[chunk_i * stride + temp_chunk_i].clone();
-                }
-            };
-            // Perform the operation on each subarray
-            let mut temp = data.to_vec();
-            if subs > 500 {
-                temp.par_chunks_mut(subs).enumerate().for_each(op);
-            } else {
-                temp.chunks_mut(subs).enumerate().for_each(op);
+            for data in self.data.as_mut_slice().chunks_exact_mut(chunk_len) {
+                transpose_square(data, subs);
+            }
+        } else if depth == 0 {
+            // The whole array is transposed, so the result can be written to a new buffer
+            let mut temp = EcoVec::from(self.data.as_slice());
+            transpose_into(&self.data, temp.make_mut(), subs, stride);
+            self.data = temp.into();
+        } else {
+            let mut temp = self.data.as_slice()[..chunk_len].to_vec();
+            for data in self.data.as_mut_slice().chunks_exact_mut(chunk_len) {
+                transpose_into(data, &mut temp, subs, stride);
+                data.clone_from_slice(&temp);
             }
-            data.clone_from_slice(&temp);
         }
         if forward {
             self.shape[depth..].rotate_left(trans_count);
@@ -953,6 +951,87 @@ impl<T: ArrayValue> Array<T> {
     }
 }
 
+/// The side length of the blocks that are transposed directly
+const TRANSPOSE_BLOCK: usize = 16;
+
+/// Transpose a row-major matrix into another buffer
+///
+/// The matrix is recursively split along its longer side until the pieces are small enough to fit in cache.
+/// This is cache-oblivious, so large matrices do not thrash the cache no matter their shape.
+fn transpose_into<T: Clone + Send + Sync>(src: &[T], dst: &mut [T], rows: usize, cols: usize) {
+    if rows > 500 {
+        // Each band of destination rows is a band of source columns
+        let band = TRANSPOSE_BLOCK * 4;
+        (dst.par_chunks_mut(band * rows).enumerate()).for_each(|(i, dst)| {
+            let start = i * band;
+            let end = (start + band).min(cols);
+            transpose_block(src, dst, rows, cols, start, 0..rows, start..end);
+        });
+    } else {
+        transpose_block(src, dst, rows, cols, 0, 0..rows, 0..cols);
+    }
+}
+
+/// Transpose a block of a row-major matrix
+///
+/// `dst` starts at the destination row `dst_start`
+fn transpose_block<T: Clone>(
+    src: &[T],
+    dst: &mut [T],
+    rows: usize,
+    cols: usize,
+    dst_start: usize,
+    r: Range<usize>,
+    c: Range<usize>,
+) {
+    if r.len() <= TRANSPOSE_BLOCK && c.len() <= TRANSPOSE_BLOCK {
+        for j in c {
+            let dst_row = &mut dst[(j - dst_start) * rows..][..rows];
+            for i in r.clone() {
+                dst_row[i] = src[i * cols + j].clone();
+            }
+        }
+    } else if r.len() >= c.len() {
+        let mid = r.start + r.len() / 2;
+        transpose_block(src, dst, rows, cols, dst_start, r.start..mid, c.clone());
+        transpose_block(src, dst, rows, cols, dst_start, mid..r.end, c);
+    } else {
+        let mid = c.start + c.len() / 2;
+        transpose_block(src, dst, rows, cols, dst_start, r.clone(), c.start..mid);
+        transpose_block(src, dst, rows, cols, dst_start, r, mid..c.end);
+    }
+}
+
+/// Transpose a row-major square matrix in place
+///
+/// Elements are swapped a pair of blocks at a time so that both blocks stay in cache.
+fn transpose_square<T: Send>(data: &mut [T], s: usize) {
+    let swap_blocks = |data: *mut T, bi: usize| {
+        for bj in (bi..s).step_by(TRANSPOSE_BLOCK) {
+            for i in bi..(bi + TRANSPOSE_BLOCK).min(s) {
+                let start = if bi == bj { i + 1 } else { bj };
+                for j in start..(bj + TRANSPOSE_BLOCK).min(s) {
+                    // Each pair of indices is only swapped once, so no swaps overlap
+                    unsafe {
+                        ptr::swap_nonoverlapping(data.add(i * s + j), data.add(j * s + i), 1)
+                    };
+                }
+            }
+        }
+    };
+    if s > 500 {
+        // This is pretty unsafe, but no indices should collide, so it's fine? 🤷
+        let ptr = data.as_mut_ptr() as usize;
+        ((0..s).into_par_iter().step_by(TRANSPOSE_BLOCK))
+            .for_each(|bi| swap_blocks(ptr as *mut T, bi));
+    } else {
+        let ptr = data.as_mut_ptr();
+        for bi in (0..s).step_by(TRANSPOSE_BLOCK) {
+            swap_blocks(ptr, bi);
+        }
+    }
+}
+
 impl Value {
     /// Get the `rise` of the value
     pub fn rise(&self) -> Array<f64> {
@@ -976,13 +1055,52 @@ impl Value {
     pub(crate) fn sort_down_depth(&mut self, depth: usize) {
         val_as_arr!(self, |a| a.sort_down_depth(depth))
     }
+    /// Get the indices of the rows that would be at the given positions if the value were sorted
+    pub(crate) fn grade_range(&self, range: Range<usize>, descending: bool) -> Vec<usize> {
+        val_as_arr!(self, |a| a.grade_range(range, descending))
+    }
+    /// `take` the `rise` of the value without sorting the rows that are not taken
+    pub(crate) fn rise_take(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.grade_take(from, false, env)
+    }
+    /// `take` the `fall` of the value without sorting the rows that are not taken
+    pub(crate) fn fall_take(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        self.grade_take(from, true, env)
+    }
+    fn grade_take(self, from: Self, descending: bool, env: &Uiua) -> UiuaResult<Self> {
+        if let (Ok(k), true) = (self.as_int(env, ""), from.rank() > 0) {
+            let n = from.row_count();
+            let count = k.unsigned_abs();
+            if count <= n {
+                let range = if k >= 0 { 0..count } else { n - count..n };
+                return Ok(from.grade_range(range, descending).into_iter().collect());
+            }
+        }
+        let grade = if descending { from.fall() } else { from.rise() };
+        self.take(grade.into(), env)
+    }
+    /// Get the `top` rows of the value
+    pub fn top(self, from: Self, env: &Uiua) -> UiuaResult<Self> {
+        let k = self.as_int(env, "Top count must be an integer")?;
+        if from.rank() == 0 {
+            return Err(env.error("Cannot get the top rows of a scalar"));
+        }
+        let n = from.row_count();
+        let count = k.unsigned_abs().min(n);
+        let range = if k >= 0 { 0..count } else { n - count..n };
+        let indices: Value = from.grade_range(range, true).into_iter().collect();
+        indices.select(from, env)
+    }
     /// `classify` the rows of the value
     pub fn classify(&self) -> Self {
         if self.rank() == 0 {
             return 0.into();
         }
         let map_keys = self.map_keys().cloned();
-        let mut val: Value = val_as_arr!(self, Array::classify).into_iter().collect();
+        let mut val: Value = match self.categories() {
+            Some(categories) => categories.codes.clone(),
+            None => val_as_arr!(self, Array::classify).into_iter().collect(),
+        };
         if let Some(map_keys) = map_keys {
             val.meta_mut().map_keys = Some(map_keys);
         }
@@ -996,8 +1114,48 @@ impl Value {
         }
         val
     }
+    /// `intern` the rows of the value
+    ///
+    /// The result can be [`classify`](Value::classify)ed, [`deduplicate`](Value::deduplicate)d,
+    /// and used as [`group`](crate::Primitive::Group) indices without hashing its rows again
+    pub fn intern(&self) -> Self {
+        if self.rank() == 0 {
+            return self.clone();
+        }
+        let (mut interned, table, codes) = val_as_arr!(self, |a| {
+            let (interned, table, codes) = a.intern();
+            (Value::from(interned), Value::from(table), codes)
+        });
+        if let Some(meta) = interned.get_meta_mut() {
+            meta.categories = None;
+        }
+        let source = interned.clone();
+        interned.meta_mut().categories = Some(Arc::new(Categories {
+            source,
+            table,
+            codes,
+        }));
+        interned
+    }
+    /// Get the unique rows of the value and the `counts` of each
+    pub fn counts(&self) -> (Self, Self) {
+        val_as_arr!(self, |a| {
+            let (unique, counts) = a.counts();
+            (unique.into(), counts)
+        })
+    }
     /// `deduplicate` the rows of the value
     pub fn deduplicate(&mut self, env: &Uiua) -> UiuaResult {
+        if self.map_keys().is_none() {
+            if let Some(categories) = self.categories() {
+                let mut table = categories.table.clone();
+                if let Some(label) = self.take_label() {
+                    table.meta_mut().label = Some(label);
+                }
+                *self = table;
+                return Ok(());
+            }
+        }
         val_as_arr!(self, |a| a.deduplicate(env))
     }
     /// Mask the `unique` rows of the value
@@ -1060,6 +1218,40 @@ impl<T: ArrayValue> Array<T> {
         });
         indices
     }
+    /// Get the indices of the rows that would be at the given positions if the array were sorted
+    ///
+    /// Ties are broken by index, so the result is the same as that of a full stable sort.
+    /// Rows outside the range are only partitioned, not sorted.
+    pub(crate) fn grade_range(&self, range: Range<usize>, descending: bool) -> Vec<usize> {
+        let n = self.row_count();
+        if self.rank() == 0 || range.is_empty() || range.start >= n {
+            return Vec::new();
+        }
+        let end = range.end.min(n);
+        let row_len = self.row_len();
+        let row = |i: usize| ArrayCmpSlice(&self.data[i * row_len..(i + 1) * row_len]);
+        let cmp = |&a: &usize, &b: &usize| {
+            let ord = if descending {
+                row(b).cmp(&row(a))
+            } else {
+                row(a).cmp(&row(b))
+            };
+            ord.then(a.cmp(&b))
+        };
+        let mut indices: Vec<usize> = (0..n).collect();
+        if range.start > 0 {
+            indices.select_nth_unstable_by(range.start, cmp);
+        }
+        let rest = &mut indices[range.start..];
+        let len = end - range.start;
+        if len < rest.len() {
+            rest.select_nth_unstable_by(len, cmp);
+        }
+        rest[..len].sort_unstable_by(cmp);
+        indices.truncate(end);
+        indices.drain(..range.start);
+        indices
+    }
     /// Get the `fall` of the array
     pub fn fall(&self) -> Array<f64> {
         if self.rank() == 0 {
@@ -1252,6 +1444,58 @@ impl<T: ArrayValue> Array<T> {
             Array::new(classified_shape, classified).into()
         }
     }
+    /// `intern` the rows of the array
+    ///
+    /// Returns the array with equal rows sharing data,
+    /// the table of unique rows, and the codes
+    pub fn intern(&self) -> (Self, Self, Value) {
+        let mut classes = HashMap::new();
+        let mut table = CowSlice::new();
+        let mut codes = Vec::with_capacity(self.row_count());
+        for row in self.row_slices() {
+            let new_class = classes.len();
+            let class = *classes.entry(ArrayCmpSlice(row)).or_insert_with(|| {
+                table.extend_from_slice(row);
+                new_class
+            });
+            codes.push(class);
+        }
+        // Rebuild the array from the table so that equal rows share data
+        let row_len = self.row_len();
+        let mut data = EcoVec::with_capacity(self.element_count());
+        for &code in &codes {
+            data.extend_from_slice(&table[code * row_len..(code + 1) * row_len]);
+        }
+        let mut interned = Array::new(self.shape.clone(), data);
+        interned.meta.clone_from(&self.meta);
+        let mut shape = self.shape.clone();
+        shape[0] = classes.len();
+        let table = Array::new(shape, table);
+        (interned, table, codes.into_iter().collect())
+    }
+    /// Get the unique rows of the array and the `counts` of each
+    pub fn counts(&self) -> (Self, Value) {
+        if self.rank() == 0 {
+            let mut unique = self.clone();
+            unique.fix();
+            return (unique, [1usize].into_iter().collect());
+        }
+        let mut indices = HashMap::new();
+        let mut unique = CowSlice::new();
+        let mut counts: Vec<usize> = Vec::new();
+        for row in self.row_slices() {
+            let new_index = counts.len();
+            let index = *indices.entry(ArrayCmpSlice(row)).or_insert_with(|| {
+                unique.extend_from_slice(row);
+                counts.push(0);
+                new_index
+            });
+            counts[index] += 1;
+        }
+        let mut shape = self.shape.clone();
+        shape[0] = counts.len();
+        (Array::new(shape, unique), counts.into_iter().collect())
+    }
     /// `deduplicate` the rows of the array
     pub fn deduplicate(&mut self, env: &Uiua) -> UiuaResult {
         if self.rank() == 0 {
@@ -2085,21 +2329,66 @@ impl Value {
 }
 
 impl Value {
+    /// Split Uiua code into tokens
+    pub(crate) fn tokenize(&self, env: &Uiua) -> UiuaResult<Self> {
+        let code = self.as_string(env, "Code to tokenize must be a string")?;
+        let tokens =
+            split_tokens(&code).map_err(|e| env.error(format!("Cannot tokenize code: {e}")))?;
+        Ok(tokens
+            .into_iter()
+            .map(|s| Boxed(s.into()))
+            .collect::<Array<_>>()
+            .into())
+    }
+    /// Join tokens back into Uiua code
+    pub(crate) fn untokenize(&self, env: &Uiua) -> UiuaResult<Self> {
+        let tokens = self.as_strings(env, "Tokens must be a list of strings")?;
+        Ok(tokens.concat().into())
+    }
     pub(crate) fn to_csv(&self, env: &Uiua) -> UiuaResult<String> {
+        let delimiter = u8::try_from(env.scalar_fill::<char>().unwrap_or(','))
+            .map_err(|_| env.error("CSV delimiter must be ASCII"))?;
+        let options = DsvOptions {
+            delimiter,
+            ..DsvOptions::default()
+        };
+        self.to_dsv(options, env)
+    }
+    #[cfg_attr(not(feature = "csv"), allow(unused_variables))]
+    pub(crate) fn to_dsv(&self, options: DsvOptions, env: &Uiua) -> UiuaResult<String> {
         #[cfg(not(feature = "csv"))]
-        return Err(env.error("CSV support is not enabled in this environment"));
+        return Err(env
+            .error("CSV support is not enabled in this environment")
+            .system());
         #[cfg(feature = "csv")]
         {
-            let delimiter = u8::try_from(env.scalar_fill::<char>().unwrap_or(','))
-                .map_err(|_| env.error("CSV delimiter must be ASCII"))?;
-
             let mut buf = Vec::new();
             let mut writer = csv::WriterBuilder::new()
                 .flexible(true)
-                .delimiter(delimiter)
+                .delimiter(options.delimiter)
+                .quote(options.quote)
                 .from_writer(&mut buf);
 
             match self.rank() {
+                _ if options.header && self.is_map() => {
+                    let (keys, values) = self.clone().unmap(env)?;
+                    writer
+                        .write_record(keys.unboxed().rows().map(|k| k.unboxed().format()))
+                        .map_err(|e| env.error(e))?;
+                    let columns: Vec<Value> = values.into_rows().map(Value::unboxed).collect();
+                    let len = columns.iter().map(Value::row_count).max().unwrap_or(0);
+                    for i in 0..len {
+                        writer
+                            .write_record(columns.iter().map(|col| {
+                                if i < col.row_count() {
+                                    col.row(i).unboxed().format()
+                                } else {
+                                    String::new()
+                                }
+                            }))
+                            .map_err(|e| env.error(e))?;
+                    }
+                }
                 0 => writer
                     .write_record([self.format()])
                     .map_err(|e| env.error(e))?,
@@ -2125,9 +2414,142 @@ impl Value {
             Ok(s)
         }
     }
+    pub(crate) fn to_table(&self, options: TableOptions, env: &Uiua) -> UiuaResult<String> {
+        if options.style == TableStyle::Csv {
+            let options = DsvOptions {
+                header: true,
+                ..DsvOptions::default()
+            };
+            return self.to_dsv(options, env);
+        }
+        // Each cell is its text and whether it is a number
+        type Cell = (String, bool);
+        let cell = |val: Value| -> Cell {
+            let val = val.unboxed();
+            let is_num = val.rank() == 0 && matches!(val, Value::Num(_) | Value::Byte(_));
+            (val.format().replace('\n', " "), is_num)
+        };
+        let (header, rows): (Option<Vec<String>>, Vec<Vec<Cell>>) = if self.is_map() {
+            let (keys, values) = self.clone().unmap(env)?;
+            let header = keys.unboxed().rows().map(|k| cell(k).0).collect();
+            let columns: Vec<Value> = values.into_rows().map(Value::unboxed).collect();
+            let len = columns.iter().map(Value::row_count).max().unwrap_or(0);
+            let rows = (0..len)
+                .map(|i| {
+                    (columns.iter())
+                        .map(|col| {
+                            if i < col.row_count() {
+                                cell(col.row(i))
+                            } else {
+                                (String::new(), true)
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            (Some(header), rows)
+        } else if self.rank() == 2 {
+            let mut rows = self.rows().map(|row| row.into_rows().map(cell).collect());
+            let header = if options.header {
+                (rows.next()).map(|row: Vec<(String, bool)>| row.into_iter().map(|c| c.0).collect())
+            } else {
+                None
+            };
+            (header, rows.collect())
+        } else {
+            return Err(env.error(format!(
+                "Table must be a rank 2 array or a map, but it is rank {}",
+                self.rank()
+            )));
+        };
+        let col_count = (header.as_ref().map(Vec::len).into_iter())
+            .chain(rows.first().map(Vec::len))
+            .max()
+            .unwrap_or(0);
+        let escape = |s: &str| match options.style {
+            TableStyle::Markdown => s.replace('|', "\\|"),
+            _ => s.into(),
+        };
+        let min_width = if options.style == TableStyle::Markdown {
+            3
+        } else {
+            0
+        };
+        let mut widths = vec![min_width; col_count];
+        let mut numeric = vec![!rows.is_empty(); col_count];
+        for (i, s) in header.iter().flatten().enumerate() {
+            widths[i] = widths[i].max(escape(s).chars().count());
+        }
+        for row in &rows {
+            for (i, (s, is_num)) in row.iter().enumerate() {
+                widths[i] = widths[i].max(escape(s).chars().count());
+                numeric[i] &= *is_num;
+            }
+        }
+        let pad = |i: usize, s: &str| {
+            let s = escape(s);
+            let padding = " ".repeat(widths[i] - s.chars().count());
+            if numeric[i] {
+                padding + &s
+            } else {
+                s + &padding
+            }
+        };
+        let mut lines = Vec::new();
+        match options.style {
+            TableStyle::Markdown => {
+                let header = header.unwrap_or_else(|| vec![String::new(); col_count]);
+                let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+                lines.push(line(
+                    header.iter().enumerate().map(|(i, s)| pad(i, s)).collect(),
+                ));
+                lines.push(line(
+                    (widths.iter().zip(&numeric))
+                        .map(|(&w, &num)| {
+                            if num {
+                                "-".repeat(w - 1) + ":"
+                            } else {
+                                "-".repeat(w)
+                            }
+                        })
+                        .collect(),
+                ));
+                for row in &rows {
+                    lines.push(line(
+                        row.iter()
+                            .enumerate()
+                            .map(|(i, (s, _))| pad(i, s))
+                            .collect(),
+                    ));
+                }
+            }
+            TableStyle::Text | TableStyle::Csv => {
+                let line = |cells: Vec<String>| cells.join("  ").trim_end().to_string();
+                if let Some(header) = &header {
+                    lines.push(line(
+                        header.iter().enumerate().map(|(i, s)| pad(i, s)).collect(),
+                    ));
+                    lines.push(line(widths.iter().map(|&w| "-".repeat(w)).collect()));
+                }
+                for row in &rows {
+                    lines.push(line(
+                        row.iter()
+                            .enumerate()
+                            .map(|(i, (s, _))| pad(i, s))
+                            .collect(),
+                    ));
+                }
+            }
+        }
+        let mut table = lines.join("\n");
+        table.push('\n');
+        Ok(table)
+    }
     pub(crate) fn to_xlsx(&self, env: &Uiua) -> UiuaResult<Vec<u8>> {
         #[cfg(not(feature = "simple_excel_writer"))]
-        return Err(env.error("XLSX encoding is not enabled in this environment"));
+        return Err(env
+            .error("XLSX encoding is not enabled in this environment")
+            .system());
         #[cfg(feature = "simple_excel_writer")]
         {
             use simple_excel_writer::*;
@@ -2194,18 +2616,28 @@ impl Value {
         }
     }
     pub(crate) fn from_csv(csv_str: &str, env: &mut Uiua) -> UiuaResult<Self> {
+        let delimiter = u8::try_from(env.scalar_unfill::<char>().unwrap_or(','))
+            .map_err(|_| env.error("CSV delimiter must be ASCII"))?;
+        let options = DsvOptions {
+            delimiter,
+            ..DsvOptions::default()
+        };
+        Self::from_dsv(csv_str, options, env)
+    }
+    #[cfg_attr(not(feature = "csv"), allow(unused_variables))]
+    pub(crate) fn from_dsv(dsv_str: &str, options: DsvOptions, env: &mut Uiua) -> UiuaResult<Self> {
         #[cfg(not(feature = "csv"))]
-        return Err(env.error("CSV support is not enabled in this environment"));
+        return Err(env
+            .error("CSV support is not enabled in this environment")
+            .system());
         #[cfg(feature = "csv")]
         {
-            let delimiter = u8::try_from(env.scalar_unfill::<char>().unwrap_or(','))
-                .map_err(|_| env.error("CSV delimiter must be ASCII"))?;
-
             let mut reader = csv::ReaderBuilder::new()
                 .has_headers(false)
                 .flexible(true)
-                .delimiter(delimiter)
-                .from_reader(csv_str.as_bytes());
+                .delimiter(options.delimiter)
+                .quote(options.quote)
+                .from_reader(dsv_str.as_bytes());
 
             let fill = env.value_fill().cloned().unwrap_or_else(|| "".into());
             env.with_fill(fill, |env| {
@@ -2218,13 +2650,32 @@ impl Value {
                     }
                     rows.push(Array::new(row.len(), row));
                 }
-                Array::from_row_arrays(rows, env).map(Into::into)
+                if !options.header {
+                    return Array::from_row_arrays(rows, env).map(Into::into);
+                }
+                if rows.is_empty() {
+                    return Err(env.error("Cannot read a header from empty CSV"));
+                }
+                // Rows are padded along with the header so that every column has a key
+                let rows = Array::from_row_arrays(rows, env)?;
+                let keys = rows.row(0);
+                let mut columns = if rows.row_count() > 1 {
+                    rows.slice_rows(1, rows.row_count())
+                } else {
+                    rows.first_dim_zero()
+                };
+                columns.transpose();
+                let mut columns: Value = columns.into();
+                columns.map(keys.into(), env)?;
+                Ok(columns)
             })
         }
     }
     pub(crate) fn from_xlsx(_xlsx: &[u8], env: &mut Uiua) -> UiuaResult<Self> {
         #[cfg(not(feature = "calamine"))]
-        return Err(env.error("XLSX decoding is not enabled in this environment"));
+        return Err(env
+            .error("XLSX decoding is not enabled in this environment")
+            .system());
         #[cfg(feature = "calamine")]
         {
             use calamine::*;
@@ -2564,6 +3015,17 @@ enum BinType {
 const MAX_BINARY_DEPTH: usize = if cfg!(debug_assertions) { 10 } else { 32 };
 
 impl Value {
+    /// Encode the value into a compact binary format
+    ///
+    /// The encoding includes the value's type, shape, and metadata.
+    /// It is the format used by [`Primitive::Binary`].
+    pub fn to_bytes(&self) -> UiuaResult<Vec<u8>> {
+        self.to_binary(&Uiua::with_safe_sys())
+    }
+    /// Decode a value from bytes produced by [`Value::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> UiuaResult<Self> {
+        Self::from_binary(bytes, &Uiua::with_safe_sys())
+    }
     pub(crate) fn to_binary(&self, env: &Uiua) -> UiuaResult<Vec<u8>> {
         let mut bytes = Vec::new();
         self.to_binary_impl(&mut bytes, 0, env)?;
@@ -0,0 +1,360 @@
+//! Polynomials, root finding, and numerical calculus
+
+use ecow::EcoVec;
+
+use crate::{
+    algorithm::{get_ops, linalg, validate_size},
+    Array, Ops, Primitive, SigNode, Uiua, UiuaResult, Value,
+};
+
+use super::linalg::real_array;
+
+/// Evaluate a polynomial with coefficients in ascending order of power
+fn horner(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+pub fn poly_eval(env: &mut Uiua) -> UiuaResult {
+    let coeffs = real_array(env.pop(1)?, Primitive::PolyVal, env)?;
+    if coeffs.rank() > 1 {
+        return Err(env.error(format!(
+            "Polynomial coefficients must be a list, but their shape is {}",
+            coeffs.shape
+        )));
+    }
+    let mut xs = real_array(env.pop(2)?, Primitive::PolyVal, env)?;
+    for x in xs.data.as_mut_slice() {
+        *x = horner(&coeffs.data, *x);
+    }
+    env.push(xs);
+    Ok(())
+}
+
+pub fn poly_fit(env: &mut Uiua) -> UiuaResult {
+    let degree = env
+        .pop(1)?
+        .as_nat(env, "Polynomial degree must be a natural number")?;
+    let xs = real_array(env.pop(2)?, Primitive::PolyFit, env)?;
+    let ys = real_array(env.pop(3)?, Primitive::PolyFit, env)?;
+    if xs.rank() != 1 || ys.rank() != 1 {
+        return Err(env.error(format!(
+            "{} expects lists of points, but the arrays have shapes {} and {}",
+            Primitive::PolyFit.format(),
+            xs.shape,
+            ys.shape
+        )));
+    }
+    if xs.row_count() != ys.row_count() {
+        return Err(env.error(format!(
+            "Cannot fit {} x values to {} y values",
+            xs.row_count(),
+            ys.row_count()
+        )));
+    }
+    if xs.row_count() == 0 {
+        return Err(env.error("Cannot fit a polynomial to no points"));
+    }
+    let cols = degree.saturating_add(1);
+    validate_size::<f64>([xs.row_count(), cols], env)?;
+    // Vandermonde matrix
+    let mut a = Vec::with_capacity(xs.row_count() * cols);
+    for &x in &xs.data {
+        let mut power = 1.0;
+        for _ in 0..cols {
+            a.push(power);
+            power *= x;
+        }
+    }
+    let coeffs = linalg::least_squares_column(a, cols, &ys.data);
+    env.push(coeffs.into_iter().collect::<Value>());
+    Ok(())
+}
+
+fn check_function(f: &SigNode, prim: Primitive, env: &Uiua) -> UiuaResult {
+    if f.sig != (1, 1) {
+        return Err(env.error(format!(
+            "{}'s function must have signature |1, but its signature is {}",
+            prim.format(),
+            f.sig
+        )));
+    }
+    Ok(())
+}
+
+/// Call a numeric function on all points at once
+fn call_points(
+    f: &SigNode,
+    xs: &Array<f64>,
+    prim: Primitive,
+    env: &mut Uiua,
+) -> UiuaResult<Array<f64>> {
+    env.push(xs.clone());
+    env.exec(f.clone())?;
+    let ys = env.pop("function result")?;
+    let ys = real_array(ys, prim, env)
+        .map_err(|_| env.error(format!("{}'s function must return numbers", prim.format())))?;
+    if ys.shape != xs.shape {
+        return Err(env.error(format!(
+            "{}'s function must return an array with the same shape as its argument, \
+            but it returned shape {} for shape {}",
+            prim.format(),
+            ys.shape,
+            xs.shape
+        )));
+    }
+    Ok(ys)
+}
+
+/// Offset every point by a multiple of a step size
+///
+/// Returns the offset points and the exactly representable offsets
+fn offset_points(xs: &Array<f64>, hs: &[f64], scale: f64) -> (Array<f64>, Vec<f64>) {
+    let mut shifted = xs.clone();
+    let mut offsets = Vec::with_capacity(hs.len());
+    for (x, h) in shifted.data.as_mut_slice().iter_mut().zip(hs) {
+        let new = *x + scale * h;
+        offsets.push(new - *x);
+        *x = new;
+    }
+    (shifted, offsets)
+}
+
+/// A step size for finite differences that balances truncation and rounding error
+fn step_sizes(xs: &Array<f64>, base: f64) -> Vec<f64> {
+    xs.data.iter().map(|x| base * x.abs().max(1.0)).collect()
+}
+
+const NEWTON_MAX_ITERATIONS: usize = 100;
+
+pub fn newton(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    check_function(&f, Primitive::Newton, env)?;
+    let mut xs = real_array(env.pop(1)?, Primitive::Newton, env)?;
+    let mut done = vec![false; xs.element_count()];
+    let mut converged = vec![false; xs.element_count()];
+    let hs = step_sizes(&xs, f64::EPSILON.cbrt());
+    for _ in 0..NEWTON_MAX_ITERATIONS {
+        if done.iter().all(|&d| d) {
+            break;
+        }
+        let ys = call_points(&f, &xs, Primitive::Newton, env)?;
+        let (above, up) = offset_points(&xs, &hs, 1.0);
+        let (below, down) = offset_points(&xs, &hs, -1.0);
+        let ys_above = call_points(&f, &above, Primitive::Newton, env)?;
+        let ys_below = call_points(&f, &below, Primitive::Newton, env)?;
+        let xs_data = xs.data.as_mut_slice();
+        for i in 0..xs_data.len() {
+            if done[i] {
+                continue;
+            }
+            let y = ys.data[i];
+            if y == 0.0 {
+                done[i] = true;
+                converged[i] = true;
+                continue;
+            }
+            let slope = (ys_above.data[i] - ys_below.data[i]) / (up[i] - down[i]);
+            let step = y / slope;
+            let x = xs_data[i] - step;
+            if !x.is_finite() {
+                done[i] = true;
+                continue;
+            }
+            xs_data[i] = x;
+            if step.abs() <= 64.0 * f64::EPSILON * x.abs().max(1.0) {
+                done[i] = true;
+                converged[i] = true;
+            }
+        }
+    }
+    for (x, converged) in xs.data.as_mut_slice().iter_mut().zip(converged) {
+        if !converged {
+            *x = f64::NAN;
+        }
+    }
+    env.push(xs);
+    Ok(())
+}
+
+/// Enough halvings to shrink any finite interval to adjacent numbers
+const BISECT_MAX_ITERATIONS: usize = 2200;
+
+pub fn bisect(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    check_function(&f, Primitive::Bisect, env)?;
+    let mut lo = real_array(env.pop(1)?, Primitive::Bisect, env)?;
+    let mut hi = real_array(env.pop(2)?, Primitive::Bisect, env)?;
+    if lo.shape != hi.shape {
+        if lo.rank() == 0 {
+            lo = Array::new(
+                hi.shape.clone(),
+                EcoVec::from_elem(lo.data[0], hi.element_count()),
+            );
+        } else if hi.rank() == 0 {
+            hi = Array::new(
+                lo.shape.clone(),
+                EcoVec::from_elem(hi.data[0], lo.element_count()),
+            );
+        } else {
+            return Err(env.error(format!(
+                "Cannot {} with bounds of shapes {} and {}",
+                Primitive::Bisect.format(),
+                lo.shape,
+                hi.shape
+            )));
+        }
+    }
+    let mut ys_lo = call_points(&f, &lo, Primitive::Bisect, env)?;
+    let mut ys_hi = call_points(&f, &hi, Primitive::Bisect, env)?;
+    let mut done = vec![false; lo.element_count()];
+    for i in 0..done.len() {
+        let (a, b) = (lo.data[i], hi.data[i]);
+        let (ya, yb) = (ys_lo.data[i], ys_hi.data[i]);
+        if !a.is_finite() || !b.is_finite() {
+            return Err(env.error(format!(
+                "{}'s bounds must be finite, but they are {a} and {b}",
+                Primitive::Bisect.format()
+            )));
+        }
+        if ya == 0.0 {
+            hi.data.as_mut_slice()[i] = a;
+            done[i] = true;
+        } else if yb == 0.0 {
+            lo.data.as_mut_slice()[i] = b;
+            done[i] = true;
+        } else if ya.is_nan() || yb.is_nan() || ya.signum() == yb.signum() {
+            return Err(env.error(format!(
+                "{}'s function must have different signs at the bounds, \
+                but it is {ya} at {a} and {yb} at {b}",
+                Primitive::Bisect.format()
+            )));
+        }
+    }
+    for _ in 0..BISECT_MAX_ITERATIONS {
+        if done.iter().all(|&d| d) {
+            break;
+        }
+        let mut mid = lo.clone();
+        for ((m, &b), &done) in mid.data.as_mut_slice().iter_mut().zip(&hi.data).zip(&done) {
+            if !done {
+                *m = 0.5 * *m + 0.5 * b;
+            }
+        }
+        let ys_mid = call_points(&f, &mid, Primitive::Bisect, env)?;
+        let lo_data = lo.data.as_mut_slice();
+        let hi_data = hi.data.as_mut_slice();
+        let ys_lo_data = ys_lo.data.as_mut_slice();
+        let ys_hi_data = ys_hi.data.as_mut_slice();
+        for i in 0..done.len() {
+            if done[i] {
+                continue;
+            }
+            let (m, y) = (mid.data[i], ys_mid.data[i]);
+            if m == lo_data[i] || m == hi_data[i] {
+                // The bounds are adjacent, so pick the closer one
+                let closest = if ys_lo_data[i].abs() <= ys_hi_data[i].abs() {
+                    lo_data[i]
+                } else {
+                    hi_data[i]
+                };
+                lo_data[i] = closest;
+                hi_data[i] = closest;
+                done[i] = true;
+            } else if y == 0.0 {
+                lo_data[i] = m;
+                hi_data[i] = m;
+                done[i] = true;
+            } else if y.signum() == ys_lo_data[i].signum() {
+                lo_data[i] = m;
+                ys_lo_data[i] = y;
+            } else {
+                hi_data[i] = m;
+                ys_hi_data[i] = y;
+            }
+        }
+    }
+    for (a, &b) in lo.data.as_mut_slice().iter_mut().zip(&hi.data) {
+        *a = 0.5 * *a + 0.5 * b;
+    }
+    env.push(lo);
+    Ok(())
+}
+
+pub fn nderivative(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    check_function(&f, Primitive::NDerivative, env)?;
+    let xs = real_array(env.pop(1)?, Primitive::NDerivative, env)?;
+    // Five-point central difference
+    let hs = step_sizes(&xs, f64::EPSILON.powf(0.2));
+    let mut result = xs.clone();
+    for x in result.data.as_mut_slice() {
+        *x = 0.0;
+    }
+    for (scale, weight) in [(-2.0, 1.0), (-1.0, -8.0), (1.0, 8.0), (2.0, -1.0)] {
+        let (shifted, _) = offset_points(&xs, &hs, scale);
+        let ys = call_points(&f, &shifted, Primitive::NDerivative, env)?;
+        for ((r, y), h) in result.data.as_mut_slice().iter_mut().zip(&ys.data).zip(&hs) {
+            *r += weight * y / (12.0 * h);
+        }
+    }
+    env.push(result);
+    Ok(())
+}
+
+/// The number of pieces each interval is split into for integration
+const INTEGRAL_SUBDIVISIONS: usize = 8;
+/// Five-point Gauss-Legendre nodes and weights on `[-1, 1]`
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.0, 0.568_888_888_888_888_9),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+pub fn nintegral(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    check_function(&f, Primitive::NIntegral, env)?;
+    let xs = real_array(env.pop(1)?, Primitive::NIntegral, env)?;
+    if xs.rank() != 1 {
+        return Err(env.error(format!(
+            "{} expects a list of points, but the array has shape {}",
+            Primitive::NIntegral.format(),
+            xs.shape
+        )));
+    }
+    let intervals = xs.row_count().saturating_sub(1);
+    let per_interval = INTEGRAL_SUBDIVISIONS * GAUSS_LEGENDRE.len();
+    validate_size::<f64>([intervals, per_interval], env)?;
+    let mut nodes = EcoVec::with_capacity(intervals * per_interval);
+    for w in xs.data.windows(2) {
+        let width = (w[1] - w[0]) / INTEGRAL_SUBDIVISIONS as f64;
+        for k in 0..INTEGRAL_SUBDIVISIONS {
+            let center = w[0] + (k as f64 + 0.5) * width;
+            for (node, _) in GAUSS_LEGENDRE {
+                nodes.push(center + 0.5 * width * node);
+            }
+        }
+    }
+    let ys = call_points(&f, &Array::from(nodes), Primitive::NIntegral, env)?;
+    let mut result = EcoVec::with_capacity(xs.row_count());
+    let mut total = 0.0;
+    if !xs.data.is_empty() {
+        result.push(total);
+    }
+    for (w, ys) in xs
+        .data
+        .windows(2)
+        .zip(ys.data.chunks_exact(per_interval.max(1)))
+    {
+        let width = (w[1] - w[0]) / INTEGRAL_SUBDIVISIONS as f64;
+        let sum: f64 = (ys.iter())
+            .zip(GAUSS_LEGENDRE.iter().cycle())
+            .map(|(y, (_, weight))| weight * y)
+            .sum();
+        total += 0.5 * width * sum;
+        result.push(total);
+    }
+    env.push(Array::from(result));
+    Ok(())
+}
@@ -6,10 +6,10 @@ use std::{
 
 use ecow::eco_vec;
 
-use crate::{algorithm::loops::flip, array::*, Uiua, UiuaError, UiuaResult, Value};
+use crate::{algorithm::loops::flip, array::*, MessageId, Uiua, UiuaError, UiuaResult, Value};
 use crate::{Complex, Shape};
 
-use super::{multi_output, FillContext, MultiOutput};
+use super::{multi_output, shape_mismatch_info, FillContext, MultiOutput};
 
 pub trait PervasiveFn<A, B> {
     type Output;
@@ -69,6 +69,20 @@ pub(crate) fn pervade_dim(a: usize, b: usize) -> usize {
     }
 }
 
+fn incompatible_shapes_error(
+    ash: &Shape,
+    bsh: &Shape,
+    axis: usize,
+    e: impl Display,
+    env: &Uiua,
+) -> UiuaError {
+    let id = MessageId::IncompatibleShapes;
+    let message = id.format(env.locale(), &[ash, bsh]);
+    (env.error(format!("{message}{e}")))
+        .with_message_id(id)
+        .with_info(shape_mismatch_info(ash, bsh, axis, env.locale()))
+}
+
 fn derive_new_shape(
     ash: &Shape,
     bsh: &Shape,
@@ -94,11 +108,7 @@ fn derive_new_shape(
                             )));
                         }
                         Ok(_) => ad.max(bd),
-                        Err(e) => {
-                            return Err(
-                                env.error(format!("Shapes {ash} and {bsh} are not compatible{e}"))
-                            )
-                        }
+                        Err(e) => return Err(incompatible_shapes_error(ash, bsh, i, e, env)),
                     }
                 } else {
                     match b_fill_sh {
@@ -108,11 +118,7 @@ fn derive_new_shape(
                             )));
                         }
                         Ok(_) => ad.max(bd),
-                        Err(e) => {
-                            return Err(
-                                env.error(format!("Shapes {ash} and {bsh} are not compatible{e}"))
-                            )
-                        }
+                        Err(e) => return Err(incompatible_shapes_error(ash, bsh, i, e, env)),
                     }
                 }
             }
@@ -306,11 +312,7 @@ where
                                 requires_fill = true;
                                 pervade_dim(ad, bd)
                             }
-                            Some(e) => {
-                                return Err(env.error(format!(
-                                    "Shapes {ash} and {bsh} are not compatible{e}"
-                                )))
-                            }
+                            Some(e) => return Err(incompatible_shapes_error(ash, bsh, i, e, env)),
                         }
                     }
                 }
@@ -443,8 +445,19 @@ where
                 }
             }
             ([al, ash @ ..], [bl, bsh @ ..]) => {
-                let a_row_len = a.len() / al;
-                let b_row_len = b.len() / bl;
+                if c.is_empty() {
+                    return;
+                }
+                let a_row_len = if *al == 0 {
+                    ash.iter().product()
+                } else {
+                    a.len() / al
+                };
+                let b_row_len = if *bl == 0 {
+                    bsh.iter().product()
+                } else {
+                    b.len() / bl
+                };
                 let c_row_len = c.len() / al.max(bl);
                 match al.cmp(bl) {
                     Ordering::Equal => {
@@ -516,8 +529,19 @@ where
                 }
             }
             ([al, ash @ ..], [bl, bsh @ ..]) => {
-                let a_row_len = a.len() / al;
-                let b_row_len = b.len() / bl;
+                if c.is_empty() {
+                    return;
+                }
+                let a_row_len = if *al == 0 {
+                    ash.iter().product()
+                } else {
+                    a.len() / al
+                };
+                let b_row_len = if *bl == 0 {
+                    bsh.iter().product()
+                } else {
+                    b.len() / bl
+                };
                 let c_row_len = c.len() / al.max(bl);
                 match al.cmp(bl) {
                     Ordering::Equal => {
@@ -922,6 +946,43 @@ pub mod asin {
         env.error(format!("Cannot get the arcsine of {a}"))
     }
 }
+/// Sine where a full turn is `turn` units
+///
+/// Multiples of a quarter turn give exact results
+pub mod sin_turn {
+    use super::*;
+    use std::f64::consts::TAU;
+    pub fn num(a: f64, turn: f64) -> f64 {
+        if turn < 0.0 {
+            return -num(a, -turn);
+        }
+        let a = a.rem_euclid(turn);
+        let quarters = a * 4.0 / turn;
+        if quarters.fract() == 0.0 {
+            [0.0, 1.0, 0.0, -1.0][quarters as usize % 4]
+        } else {
+            (a / turn * TAU).sin()
+        }
+    }
+    pub fn com(a: Complex, turn: f64) -> Complex {
+        if a.im == 0.0 {
+            num(a.re, turn).into()
+        } else {
+            (a * (TAU / turn)).sin()
+        }
+    }
+}
+/// Arcsine where a full turn is `turn` units
+pub mod asin_turn {
+    use super::*;
+    use std::f64::consts::TAU;
+    pub fn num(a: f64, turn: f64) -> f64 {
+        a.asin() / TAU * turn
+    }
+    pub fn com(a: Complex, turn: f64) -> Complex {
+        a.asin() * (turn / TAU)
+    }
+}
 pub mod acos {
     use super::*;
     pub fn num(a: f64) -> f64 {
@@ -1013,6 +1074,36 @@ pub mod complex_im {
     }
 }
 
+pub mod complex_arg {
+    use super::*;
+
+    pub fn com(a: Complex) -> f64 {
+        a.arg()
+    }
+    pub fn num(a: f64) -> f64 {
+        0.0f64.atan2(a)
+    }
+    pub fn byte(_a: u8) -> f64 {
+        0.0
+    }
+    pub fn error<T: Display>(a: T, env: &Uiua) -> UiuaError {
+        env.error(format!("Cannot get the argument of {a}"))
+    }
+}
+pub mod complex_conj {
+    use super::*;
+
+    pub fn com(a: Complex) -> Complex {
+        a.conj()
+    }
+    pub fn generic<T>(a: T) -> T {
+        a
+    }
+    pub fn error<T: Display>(a: T, env: &Uiua) -> UiuaError {
+        env.error(format!("Cannot get the conjugate of {a}"))
+    }
+}
+
 macro_rules! eq_impl {
     ($name:ident $eq:tt $ordering:expr) => {
         pub mod $name {
@@ -1128,10 +1219,10 @@ pub mod add {
         b + a.into()
     }
     pub fn num_char(a: f64, b: char) -> char {
-        char::from_u32((b as i64 + a as i64) as u32).unwrap_or('\0')
+        char::from_u32((b as i64).saturating_add(a as i64) as u32).unwrap_or('\0')
     }
     pub fn char_num(a: char, b: f64) -> char {
-        char::from_u32((b as i64 + a as i64) as u32).unwrap_or('\0')
+        char::from_u32((b as i64).saturating_add(a as i64) as u32).unwrap_or('\0')
     }
     pub fn byte_char(a: u8, b: char) -> char {
         char::from_u32((b as i64 + a as i64) as u32).unwrap_or('\0')
@@ -1403,7 +1494,7 @@ pub mod or {
             return (a << shift) as f64;
         }
         fn recurse(a: f64, b: f64) -> f64 {
-            if b <= 8.0 * f64::EPSILON {
+            if b.is_nan() || b <= 8.0 * f64::EPSILON {
                 return a;
             }
             recurse(b, a.rem_euclid(b))
@@ -9,7 +9,7 @@ use crate::{
     algorithm::{get_ops, loops::flip, multi_output, pervade::*},
     check::nodes_sig,
     cowslice::cowslice,
-    Array, ArrayValue, Complex, ImplPrimitive, Node, Ops, Primitive, Shape, SigNode, Uiua,
+    Array, ArrayValue, Boxed, Complex, ImplPrimitive, Node, Ops, Primitive, Shape, SigNode, Uiua,
     UiuaResult, Value,
 };
 
@@ -453,6 +453,10 @@ pub fn reduce_content(ops: Ops, env: &mut Uiua) -> UiuaResult {
                 });
                 return Ok(());
             }
+            if let Some(joined) = join_strings(&xs) {
+                env.push(joined);
+                return Ok(());
+            }
             let mut rows = xs.into_rows().map(Value::unboxed);
             (rows.next().unwrap(), rows)
         };
@@ -471,6 +475,31 @@ pub fn reduce_content(ops: Ops, env: &mut Uiua) -> UiuaResult {
     Ok(())
 }
 
+/// Join a list of boxed strings into a single string
+///
+/// The string is built in one buffer rather than joining each string to
+/// the accumulator in turn. Returns `None` if not all the boxes contain
+/// plain strings.
+fn join_strings(xs: &Value) -> Option<Value> {
+    let Value::Box(arr) = xs else {
+        return None;
+    };
+    let mut len = 0;
+    for Boxed(val) in &arr.data {
+        match val {
+            Value::Char(s) if s.rank() <= 1 && s.meta().is_default() => len += s.data.len(),
+            _ => return None,
+        }
+    }
+    let mut chars = EcoVec::with_capacity(len);
+    for Boxed(val) in &arr.data {
+        if let Value::Char(s) = val {
+            chars.extend_from_slice(&s.data);
+        }
+    }
+    Some(chars.into())
+}
+
 fn generic_reduce_inner(
     f: SigNode,
     depth: usize,
@@ -968,3 +997,185 @@ pub fn fold(ops: Ops, env: &mut Uiua) -> UiuaResult {
     }
     Ok(())
 }
+
+/// Get the primitive of a reduction that can be fused with others
+pub(crate) fn fusable_reduction(node: &Node) -> Option<Primitive> {
+    let [Node::Mod(Primitive::Reduce, args, _)] = node.as_slice() else {
+        return None;
+    };
+    let [f] = args.as_slice() else {
+        return None;
+    };
+    let prim = f.node.as_primitive()?;
+    matches!(
+        prim,
+        Primitive::Add | Primitive::Mul | Primitive::Max | Primitive::Min
+    )
+    .then_some(prim)
+}
+
+/// Get a monadic pervasive primitive that can be fused with others
+pub(crate) fn fusable_monadic(node: &Node) -> Option<Primitive> {
+    use Primitive::*;
+    let prim = node.as_primitive()?;
+    matches!(
+        prim,
+        Neg | Not | Abs | Sign | Sqrt | Sin | Floor | Ceil | Round
+    )
+    .then_some(prim)
+}
+
+/// Bind the number function of a fusable primitive
+///
+/// Each function gets its own type, so code using it is monomorphized
+macro_rules! with_fused_fn {
+    (reduce $prim:expr, |$f:ident| $body:expr) => {
+        match $prim {
+            Primitive::Add => {
+                let $f = add::num_num;
+                $body
+            }
+            Primitive::Mul => {
+                let $f = mul::num_num;
+                $body
+            }
+            Primitive::Max => {
+                let $f = max::num_num;
+                $body
+            }
+            Primitive::Min => {
+                let $f = min::num_num;
+                $body
+            }
+            prim => unreachable!("fused reduction with {prim}"),
+        }
+    };
+    (monadic $prim:expr, |$f:ident| $body:expr) => {
+        match $prim {
+            Primitive::Neg => {
+                let $f = scalar_neg::num;
+                $body
+            }
+            Primitive::Not => {
+                let $f = not::num;
+                $body
+            }
+            Primitive::Abs => {
+                let $f = scalar_abs::num;
+                $body
+            }
+            Primitive::Sign => {
+                let $f = sign::num;
+                $body
+            }
+            Primitive::Sqrt => {
+                let $f = sqrt::num;
+                $body
+            }
+            Primitive::Sin => {
+                let $f = sin::num;
+                $body
+            }
+            Primitive::Floor => {
+                let $f = floor::num;
+                $body
+            }
+            Primitive::Ceil => {
+                let $f = ceil::num;
+                $body
+            }
+            Primitive::Round => {
+                let $f = round::num;
+                $body
+            }
+            prim => unreachable!("fused monadic function {prim}"),
+        }
+    };
+}
+
+/// Call two functions on the same array, traversing it only once if possible
+///
+/// This is what [`fork`](Primitive::Fork) of two reductions or of two monadic pervasive functions compiles to
+pub fn fused_fork(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f, g] = get_ops(ops, env)?;
+    let xs = env.pop(1)?;
+    if let Value::Num(arr) = &xs {
+        if let Some((a, b)) = fused_fork_nums(arr, &f, &g, env) {
+            env.push(b);
+            env.push(a);
+            return Ok(());
+        }
+    }
+    env.push(xs.clone());
+    env.exec(g)?;
+    env.push(xs);
+    env.exec(f)
+}
+
+fn fused_fork_nums(
+    arr: &Array<f64>,
+    f: &SigNode,
+    g: &SigNode,
+    env: &Uiua,
+) -> Option<(Array<f64>, Array<f64>)> {
+    if let (Some(f), Some(g)) = (fusable_reduction(&f.node), fusable_reduction(&g.node)) {
+        if arr.rank() == 0 || arr.row_count() == 0 || arr.row_len() == 0 {
+            return None;
+        }
+        if env.scalar_fill::<f64>().is_ok() {
+            return None;
+        }
+        return Some(with_fused_fn!(reduce f, |f| with_fused_fn!(reduce g, |g| {
+            fused_reduce2(arr, f, g)
+        })));
+    }
+    let (f, g) = (fusable_monadic(&f.node)?, fusable_monadic(&g.node)?);
+    let (mut a, mut b) = with_fused_fn!(monadic f, |f| with_fused_fn!(monadic g, |g| {
+        fused_map2(arr, f, g)
+    }));
+    a.meta = arr.meta.clone();
+    b.meta = arr.meta.clone();
+    Some((a, b))
+}
+
+fn fused_reduce2(
+    arr: &Array<f64>,
+    f: impl Fn(f64, f64) -> f64,
+    g: impl Fn(f64, f64) -> f64,
+) -> (Array<f64>, Array<f64>) {
+    if arr.rank() == 1 {
+        let first = arr.data[0];
+        let (a, b) = (arr.data[1..].iter()).fold((first, first), |(a, b), &x| (f(a, x), g(b, x)));
+        return (a.into(), b.into());
+    }
+    let row_len = arr.row_len();
+    let mut a: EcoVec<f64> = arr.data[..row_len].into();
+    let mut b = a.clone();
+    let (a_slice, b_slice) = (a.make_mut(), b.make_mut());
+    for row in arr.data[row_len..].chunks_exact(row_len) {
+        for ((a, b), &x) in a_slice.iter_mut().zip(&mut *b_slice).zip(row) {
+            *a = f(*a, x);
+            *b = g(*b, x);
+        }
+    }
+    let shape = Shape::from(&arr.shape[1..]);
+    (Array::new(shape.clone(), a), Array::new(shape, b))
+}
+
+fn fused_map2(
+    arr: &Array<f64>,
+    f: impl Fn(f64) -> f64,
+    g: impl Fn(f64) -> f64,
+) -> (Array<f64>, Array<f64>) {
+    let mut a = eco_vec![0.0; arr.data.len()];
+    let mut b = eco_vec![0.0; arr.data.len()];
+    let (a_slice, b_slice) = (a.make_mut(), b.make_mut());
+    for ((a, b), &x) in a_slice.iter_mut().zip(&mut *b_slice).zip(&arr.data) {
+        *a = f(x);
+        *b = g(x);
+    }
+    (
+        Array::new(arr.shape.clone(), a),
+        Array::new(arr.shape.clone(), b),
+    )
+}
@@ -0,0 +1,422 @@
+//! Statistical distributions
+
+use std::f64::consts::{PI, SQRT_2, TAU};
+
+use rand::Rng;
+
+use crate::{Array, Primitive, Uiua, UiuaResult, Value};
+
+/// A probability distribution
+#[derive(Debug, Clone, Copy)]
+enum Distribution {
+    Normal { mean: f64, sd: f64 },
+    Exponential { rate: f64 },
+    Poisson { rate: f64 },
+    Binomial { n: f64, p: f64 },
+}
+
+impl Distribution {
+    fn pop(env: &mut Uiua) -> UiuaResult<Self> {
+        let name = (env.pop(1)?).as_string(env, "Distribution must be a string")?;
+        let params = (env.pop(2)?).as_nums(env, "Distribution parameters must be numbers")?;
+        let expect = |names: &str, count: usize| -> UiuaResult {
+            if params.len() == count {
+                Ok(())
+            } else {
+                Err(env.error(format!(
+                    "The {name} distribution expects {names}, \
+                    but {} parameter{} were given",
+                    params.len(),
+                    if params.len() == 1 { "" } else { "s" }
+                )))
+            }
+        };
+        let dist = match name.as_str() {
+            "normal" => {
+                expect("a mean and a standard deviation", 2)?;
+                Distribution::Normal {
+                    mean: params[0],
+                    sd: params[1],
+                }
+            }
+            "exponential" => {
+                expect("a rate", 1)?;
+                Distribution::Exponential { rate: params[0] }
+            }
+            "poisson" => {
+                expect("a rate", 1)?;
+                Distribution::Poisson { rate: params[0] }
+            }
+            "binomial" => {
+                expect("a number of trials and a probability", 2)?;
+                Distribution::Binomial {
+                    n: params[0],
+                    p: params[1],
+                }
+            }
+            _ => {
+                return Err(env.error(format!(
+                    "Unknown distribution {name:?}. The known distributions are \
+                    \"normal\", \"exponential\", \"poisson\", and \"binomial\""
+                )))
+            }
+        };
+        let valid = match dist {
+            Distribution::Normal { mean, sd } => mean.is_finite() && sd.is_finite() && sd > 0.0,
+            Distribution::Exponential { rate } => rate.is_finite() && rate > 0.0,
+            Distribution::Poisson { rate } => rate.is_finite() && rate >= 0.0,
+            Distribution::Binomial { n, p } => {
+                n.is_finite() && n >= 0.0 && n.fract() == 0.0 && (0.0..=1.0).contains(&p)
+            }
+        };
+        if !valid {
+            let requirement = match dist {
+                Distribution::Normal { .. } => "a finite mean and a positive standard deviation",
+                Distribution::Exponential { .. } => "a positive rate",
+                Distribution::Poisson { .. } => "a non-negative rate",
+                Distribution::Binomial { .. } => {
+                    "a natural number of trials and a probability between 0 and 1"
+                }
+            };
+            return Err(env.error(format!("The {name} distribution requires {requirement}")));
+        }
+        Ok(dist)
+    }
+    fn pdf(&self, x: f64) -> f64 {
+        match *self {
+            Distribution::Normal { mean, sd } => {
+                let z = (x - mean) / sd;
+                (-0.5 * z * z).exp() / (sd * TAU.sqrt())
+            }
+            Distribution::Exponential { rate } => {
+                if x < 0.0 {
+                    0.0
+                } else {
+                    rate * (-rate * x).exp()
+                }
+            }
+            Distribution::Poisson { rate } => {
+                if x < 0.0 || x.fract() != 0.0 {
+                    0.0
+                } else {
+                    poisson_pmf(rate, x)
+                }
+            }
+            Distribution::Binomial { n, p } => {
+                if x < 0.0 || x > n || x.fract() != 0.0 {
+                    0.0
+                } else {
+                    binomial_pmf(n, p, x)
+                }
+            }
+        }
+    }
+    fn cdf(&self, x: f64) -> f64 {
+        if x.is_nan() {
+            return f64::NAN;
+        }
+        match *self {
+            Distribution::Normal { mean, sd } => 0.5 * erfc(-(x - mean) / (sd * SQRT_2)),
+            Distribution::Exponential { rate } => {
+                if x < 0.0 {
+                    0.0
+                } else {
+                    -(-rate * x).exp_m1()
+                }
+            }
+            Distribution::Poisson { rate } => {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                let mut sum = 0.0;
+                let mut k = 0.0;
+                while k <= x {
+                    let pmf = poisson_pmf(rate, k);
+                    sum += pmf;
+                    if k > rate && pmf <= f64::EPSILON * sum {
+                        break;
+                    }
+                    k += 1.0;
+                }
+                sum.min(1.0)
+            }
+            Distribution::Binomial { n, p } => {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                if x >= n {
+                    return 1.0;
+                }
+                let mut sum = 0.0;
+                let mut k = 0.0;
+                while k <= x {
+                    sum += binomial_pmf(n, p, k);
+                    k += 1.0;
+                }
+                sum.min(1.0)
+            }
+        }
+    }
+    fn quantile(&self, p: f64) -> f64 {
+        if !(0.0..=1.0).contains(&p) {
+            return f64::NAN;
+        }
+        match *self {
+            Distribution::Normal { mean, sd } => mean + sd * normal_quantile(p),
+            Distribution::Exponential { rate } => -(-p).ln_1p() / rate,
+            Distribution::Poisson { rate } => {
+                if p == 1.0 {
+                    return f64::INFINITY;
+                }
+                let mut sum = 0.0;
+                let mut k = 0.0;
+                loop {
+                    let pmf = poisson_pmf(rate, k);
+                    sum += pmf;
+                    if sum >= p || k > rate && pmf <= f64::EPSILON * sum {
+                        break k;
+                    }
+                    k += 1.0;
+                }
+            }
+            Distribution::Binomial { n, p: prob } => {
+                let mut sum = 0.0;
+                let mut k = 0.0;
+                while k < n {
+                    sum += binomial_pmf(n, prob, k);
+                    if sum >= p {
+                        break;
+                    }
+                    k += 1.0;
+                }
+                k
+            }
+        }
+    }
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match *self {
+            Distribution::Normal { mean, sd } => {
+                // Box-Muller transform
+                let u: f64 = 1.0 - rng.gen::<f64>();
+                let v: f64 = rng.gen();
+                mean + sd * (-2.0 * u.ln()).sqrt() * (TAU * v).cos()
+            }
+            // Inverse transform sampling
+            _ => self.quantile(rng.gen()),
+        }
+    }
+}
+
+fn poisson_pmf(rate: f64, k: f64) -> f64 {
+    if rate == 0.0 {
+        return if k == 0.0 { 1.0 } else { 0.0 };
+    }
+    // The direct formula is more precise but can overflow
+    let direct = if k <= 170.0 {
+        let factorial = (1..=k as u64).fold(1.0, |acc, i| acc * i as f64);
+        rate.powf(k) / factorial * (-rate).exp()
+    } else {
+        0.0
+    };
+    if direct.is_normal() {
+        direct
+    } else {
+        (k * rate.ln() - rate - ln_gamma(k + 1.0)).exp()
+    }
+}
+
+fn binomial_pmf(n: f64, p: f64, k: f64) -> f64 {
+    if p == 0.0 || p == 1.0 {
+        let certain = if p == 0.0 { 0.0 } else { n };
+        return if k == certain { 1.0 } else { 0.0 };
+    }
+    // The direct formula is more precise but can overflow
+    let j = k.min(n - k);
+    let direct = if j <= 1000.0 {
+        let choose = (1..=j as u64).fold(1.0, |acc, i| acc * (n - j + i as f64) / i as f64);
+        choose * p.powf(k) * (1.0 - p).powf(n - k)
+    } else {
+        0.0
+    };
+    if direct.is_normal() {
+        direct
+    } else {
+        let ln_choose = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+        (ln_choose + k * p.ln() + (n - k) * (-p).ln_1p()).exp()
+    }
+}
+
+/// The natural logarithm of the gamma function for positive arguments
+fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation with g = 7
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum =
+        (COEFFS.iter().enumerate().skip(1)).fold(COEFFS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * TAU.ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The complementary error function
+fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+    if x < 2.5 {
+        // Series with only positive terms
+        let x2 = x * x;
+        let mut term = x;
+        let mut sum = x;
+        let mut i = 0.0;
+        while term > sum * f64::EPSILON {
+            i += 1.0;
+            term *= 2.0 * x2 / (2.0 * i + 1.0);
+            sum += term;
+        }
+        return 1.0 - 2.0 / PI.sqrt() * (-x2).exp() * sum;
+    }
+    // Continued fraction, evaluated with Lentz's method
+    let tiny = 1e-300;
+    let mut f = x;
+    let mut c = x;
+    let mut d = 0.0;
+    for k in 1..1000 {
+        let a = k as f64 / 2.0;
+        d = x + a * d;
+        if d == 0.0 {
+            d = tiny;
+        }
+        d = 1.0 / d;
+        c = x + a / c;
+        if c == 0.0 {
+            c = tiny;
+        }
+        let delta = c * d;
+        f *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+    (-x * x).exp() / (PI.sqrt() * f)
+}
+
+/// The quantile function of the standard normal distribution
+fn normal_quantile(p: f64) -> f64 {
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+    // Acklam's rational approximation
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    let poly = |coeffs: &[f64], x: f64| coeffs.iter().fold(0.0, |acc, c| acc * x + c);
+    let tail = |p: f64| {
+        let q = (-2.0 * p.ln()).sqrt();
+        poly(&C, q) / (poly(&D, q) * q + 1.0)
+    };
+    const P_LOW: f64 = 0.02425;
+    let mut x = if p < P_LOW {
+        tail(p)
+    } else if p > 1.0 - P_LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        poly(&A, r) * q / (poly(&B, r) * r + 1.0)
+    };
+    // Refine with Halley's method
+    for _ in 0..2 {
+        let e = 0.5 * erfc(-x / SQRT_2) - p;
+        let u = e * TAU.sqrt() * (0.5 * x * x).exp();
+        x -= u / (1.0 + 0.5 * x * u);
+    }
+    x
+}
+
+fn map_dist(prim: Primitive, env: &mut Uiua, f: impl Fn(&Distribution, f64) -> f64) -> UiuaResult {
+    let dist = Distribution::pop(env)?;
+    let mut arr: Array<f64> = match env.pop(3)? {
+        Value::Num(arr) => arr,
+        Value::Byte(arr) => arr.convert(),
+        val => {
+            return Err(env.error(format!(
+                "Cannot get the {} of {}",
+                prim.format(),
+                val.type_name_plural()
+            )))
+        }
+    };
+    for x in arr.data.as_mut_slice() {
+        *x = f(&dist, *x);
+    }
+    env.push(arr);
+    Ok(())
+}
+
+pub fn pdf(env: &mut Uiua) -> UiuaResult {
+    map_dist(Primitive::Pdf, env, Distribution::pdf)
+}
+
+pub fn cdf(env: &mut Uiua) -> UiuaResult {
+    map_dist(Primitive::Cdf, env, Distribution::cdf)
+}
+
+pub fn quantile(env: &mut Uiua) -> UiuaResult {
+    map_dist(Primitive::Quantile, env, Distribution::quantile)
+}
+
+pub fn draw(env: &mut Uiua) -> UiuaResult {
+    let dist = Distribution::pop(env)?;
+    let (shape, mut data) = env.pop(3)?.random_buffer(env)?;
+    for x in data.make_mut() {
+        *x = dist.sample(&mut env.rt.rng);
+    }
+    env.push(Array::new(shape, data));
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::{collections::VecDeque, mem::take};
 
-use ecow::EcoVec;
+use ecow::{eco_vec, EcoVec};
 
 use crate::{
     algorithm::{pervade::*, validate_size, FillContext, MultiOutput},
@@ -60,9 +60,133 @@ pub fn stencil(ops: Ops, env: &mut Uiua) -> UiuaResult {
     xs.match_fill(env);
     let has_fill = env.fill().value_for(&xs).is_some();
     let dims = derive_dims(&size, xs.shape(), has_fill, env)?;
+    if let Some((prim, all)) = window_reduction(&f) {
+        match xs {
+            Value::Num(arr) => {
+                let fill = env.scalar_fill::<f64>().ok();
+                return stencil_reduce(arr, &dims, prim, all, fill, env);
+            }
+            Value::Byte(arr) if matches!(prim, Primitive::Add | Primitive::Mul) => {
+                let fill = env.scalar_fill::<f64>().ok();
+                return stencil_reduce(arr.convert(), &dims, prim, all, fill, env);
+            }
+            val => xs = val,
+        }
+    }
     val_as_arr!(xs, |arr| stencil_array(arr, &dims, f, env))
 }
 
+/// Get the primitive of a window function that reduces each window
+///
+/// The boolean is whether the window is deshaped before being reduced
+fn window_reduction(f: &SigNode) -> Option<(Primitive, bool)> {
+    use Primitive::*;
+    let reduce_prim = |node: &Node| match node {
+        Node::Mod(Reduce, args, _) => match args.as_slice() {
+            [g] => match g.node {
+                Node::Prim(prim @ (Add | Mul | Max | Min), _) => Some(prim),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    };
+    match f.node.as_slice() {
+        [reduce] => reduce_prim(reduce).map(|prim| (prim, false)),
+        [Node::Prim(Deshape, _), reduce] => reduce_prim(reduce).map(|prim| (prim, true)),
+        _ => None,
+    }
+}
+
+/// Reduce each window without creating it
+///
+/// This is what makes things like moving sums and image filters
+/// use memory proportional to the output rather than to all the windows
+fn stencil_reduce(
+    arr: Array<f64>,
+    dims: &[WindowDim],
+    prim: Primitive,
+    all: bool,
+    fill: Option<f64>,
+    env: &mut Uiua,
+) -> UiuaResult {
+    let f = match prim {
+        Primitive::Add => add::num_num,
+        Primitive::Mul => mul::num_num,
+        Primitive::Max => max::num_num,
+        Primitive::Min => min::num_num,
+        _ => unreachable!("stencil reduction with {prim}"),
+    };
+    let mut shape_prefix = Shape::SCALAR;
+    for (d, s) in dims.iter().zip(&arr.shape) {
+        let total_len = *s + 2 * d.fill * d.stride;
+        shape_prefix.push((total_len + d.stride).saturating_sub(d.size) / d.stride);
+    }
+    let cell_shape = &arr.shape[dims.len()..];
+    let cell_len: usize = cell_shape.iter().product();
+    if shape_prefix.contains(&0) {
+        let mut shape = shape_prefix;
+        shape.extend(dims.iter().map(|d| d.size));
+        shape.extend_from_slice(cell_shape);
+        env.push(Array::<f64>::new(shape, EcoVec::new()));
+        return Ok(());
+    }
+    // The shape of each window's result
+    let result_shape: Shape = if all {
+        Shape::SCALAR
+    } else {
+        (dims.iter().skip(1).map(|d| d.size))
+            .chain(cell_shape.iter().copied())
+            .collect()
+    };
+    let result_len = result_shape.elements();
+    let mut shape = shape_prefix.clone();
+    shape.extend(result_shape);
+    let elem_count = validate_size::<f64>(shape.iter().copied(), env)?;
+    let mut data = eco_vec![0.0; elem_count];
+    if elem_count == 0 {
+        env.push(Array::new(shape, data));
+        return Ok(());
+    }
+    let fill = fill.unwrap_or_else(f64::proxy);
+    let offset_count: usize = dims.iter().map(|d| d.size).product();
+    let slice = data.make_mut();
+    let mut corner = vec![0isize; dims.len()];
+    let mut offset = vec![0usize; dims.len()];
+    let mut curr = vec![0isize; dims.len()];
+    for (w, result) in slice.chunks_exact_mut(result_len).enumerate() {
+        // Find the window's corner
+        let mut rem = w;
+        for ((c, d), n) in corner.iter_mut().zip(dims).zip(&shape_prefix).rev() {
+            *c = (rem % n * d.stride) as isize - (d.fill * d.stride) as isize;
+            rem /= n;
+        }
+        for o in 0..offset_count {
+            let mut rem = o;
+            for (off, d) in offset.iter_mut().zip(dims).rev() {
+                *off = rem % d.size;
+                rem /= d.size;
+            }
+            for (i, c) in curr.iter_mut().enumerate() {
+                *c = corner[i] + offset[i] as isize;
+            }
+            let cell = arr.shape.i_dims_to_flat(&curr);
+            for k in 0..cell_len {
+                let x = cell.map_or(fill, |i| arr.data[i * cell_len + k]);
+                let e = o * cell_len + k;
+                let (r, first) = if all {
+                    (0, e == 0)
+                } else {
+                    (e % result_len, e < result_len)
+                };
+                result[r] = if first { x } else { f(result[r], x) };
+            }
+        }
+    }
+    env.push(Array::new(shape, data));
+    Ok(())
+}
+
 fn stencil_array<T: ArrayValue>(
     mut arr: Array<T>,
     dims: &[WindowDim],
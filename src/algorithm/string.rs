@@ -0,0 +1,503 @@
+//! Algorithms for working with strings
+
+use std::{iter::repeat, mem::take};
+
+use ecow::EcoVec;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{Array, Boxed, Locale, Primitive, Shape, Uiua, UiuaResult, Value};
+
+use super::{validate_size, FillContext};
+
+impl Value {
+    /// Convert a string to lowercase
+    pub fn lower(self, env: &Uiua) -> UiuaResult<Self> {
+        self.map_strings(Primitive::Lower, env, &|s| Ok(s.to_lowercase()))
+    }
+    /// Convert a string to uppercase
+    pub fn upper(self, env: &Uiua) -> UiuaResult<Self> {
+        self.map_strings(Primitive::Upper, env, &|s| Ok(s.to_uppercase()))
+    }
+    /// Convert a string to Unicode Normalization Form C
+    pub fn nfc(self, env: &Uiua) -> UiuaResult<Self> {
+        self.map_strings(Primitive::Nfc, env, &|s| Ok(s.nfc().collect()))
+    }
+    /// Convert a string to Unicode Normalization Form D
+    pub fn nfd(self, env: &Uiua) -> UiuaResult<Self> {
+        self.map_strings(Primitive::Nfd, env, &|s| Ok(s.nfd().collect()))
+    }
+    /// Remove leading and trailing whitespace from a string
+    pub fn trim(self, env: &Uiua) -> UiuaResult<Self> {
+        self.map_strings(Primitive::Trim, env, &|s| Ok(s.trim().into()))
+    }
+    /// Pad a string to a width
+    ///
+    /// A negative width pads the start of the string instead of the end
+    pub fn pad(&self, string: Self, env: &Uiua) -> UiuaResult<Self> {
+        let width = self.as_int(env, "Pad width must be an integer")?;
+        validate_size::<char>([width.unsigned_abs()], env)?;
+        let fill = env.scalar_fill::<char>().unwrap_or(' ');
+        string.map_strings(Primitive::Pad, env, &|s| {
+            let len = s.chars().count();
+            let padding = repeat(fill).take(width.unsigned_abs().saturating_sub(len));
+            Ok(if width < 0 {
+                padding.chain(s.chars()).collect()
+            } else {
+                s.chars().chain(padding).collect()
+            })
+        })
+    }
+    /// Replace every occurrence of a substring
+    pub fn replace(&self, new: &Self, string: Self, env: &Uiua) -> UiuaResult<Self> {
+        let old = self.as_string(env, "Text to replace must be a string")?;
+        if old.is_empty() {
+            return Err(env.error(format!(
+                "Cannot {} an empty string",
+                Primitive::Replace.format()
+            )));
+        }
+        let new = new.as_string(env, "Replacement must be a string")?;
+        string.map_strings(Primitive::Replace, env, &|s| Ok(s.replace(&old, &new)))
+    }
+    /// Split a string by a delimiter
+    ///
+    /// Empty parts are kept so that joining the parts with the same delimiter gives back the original string
+    pub fn split(&self, string: &Self, env: &Uiua) -> UiuaResult<Self> {
+        let delim = self.as_string(env, "Delimiter must be a string")?;
+        if delim.is_empty() {
+            return Err(env.error(format!(
+                "Cannot {} by an empty delimiter",
+                Primitive::Split.format()
+            )));
+        }
+        let s = string.as_string(env, "Text to split must be a string")?;
+        let data: EcoVec<Boxed> = s.split(&delim).map(|part| Boxed(part.into())).collect();
+        Ok(data.into())
+    }
+    /// Join a list of strings with a separator
+    pub fn join_with(&self, strings: &Self, env: &Uiua) -> UiuaResult<Self> {
+        let sep = self.as_string(env, "Separator must be a string")?;
+        if strings.rank() == 1 && strings.row_count() == 0 {
+            return Ok(String::new().into());
+        }
+        let strings = strings.as_strings(env, "Strings to join must be a list of strings")?;
+        Ok(strings.join(&sep).into())
+    }
+    /// Apply a string function to a string or to every string in an array
+    ///
+    /// Character arrays of rank 2 or more are mapped by row,
+    /// and box arrays are mapped by box.
+    fn map_strings(
+        self,
+        prim: Primitive,
+        env: &Uiua,
+        f: &impl Fn(&str) -> UiuaResult<String>,
+    ) -> UiuaResult<Self> {
+        Ok(match self {
+            Value::Char(arr) if arr.rank() == 0 => {
+                let s = f(&arr.data[0].to_string())?;
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c.into(),
+                    _ => s.into(),
+                }
+            }
+            Value::Char(arr) if arr.rank() == 1 => f(&arr.data.iter().collect::<String>())?.into(),
+            Value::Char(arr) if arr.row_count() == 0 => arr.into(),
+            Value::Char(arr) => {
+                let rows = (arr.rows())
+                    .map(|row| Value::from(row).map_strings(prim, env, f))
+                    .collect::<UiuaResult<Vec<_>>>()?;
+                Value::from_row_values(rows, env)?
+            }
+            Value::Box(mut arr) => {
+                for Boxed(val) in arr.data.as_mut_slice() {
+                    *val = take(val).map_strings(prim, env, f)?;
+                }
+                arr.into()
+            }
+            val if val.element_count() == 0 => val,
+            val => {
+                return Err(env.error(format!(
+                    "Cannot {} {}",
+                    prim.format(),
+                    val.type_name_plural()
+                )))
+            }
+        })
+    }
+}
+
+/// Options for formatting numbers as strings
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NumFormat {
+    /// Whether to show a `+` for non-negative numbers
+    pub sign: bool,
+    /// Whether to pad with zeros instead of spaces
+    pub zero: bool,
+    /// The minimum width of the string
+    pub width: usize,
+    /// The thousands separator
+    pub group: Option<char>,
+    /// The number of digits after the decimal point
+    pub precision: Option<usize>,
+    /// Whether to use scientific notation
+    pub exp: bool,
+}
+
+impl NumFormat {
+    /// Parse a format from a value
+    ///
+    /// A number sets the precision.
+    /// A string is a format spec of the form `[+][0][width][,|_][.precision][e]`.
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let format = if let Value::Num(_) | Value::Byte(_) = value {
+            let precision =
+                value.as_nat(env, "Number format precision must be a natural number")?;
+            NumFormat {
+                precision: Some(precision),
+                ..Default::default()
+            }
+        } else {
+            let spec = value.as_string(env, "Number format must be a number or a string")?;
+            NumFormat::parse(&spec)
+                .ok_or_else(|| env.error(format!("Invalid number format {spec:?}")))?
+        };
+        validate_size::<char>([format.width], env)?;
+        validate_size::<char>([format.precision.unwrap_or(0)], env)?;
+        Ok(format)
+    }
+    fn parse(spec: &str) -> Option<Self> {
+        let mut format = NumFormat::default();
+        let mut rest = spec;
+        if let Some(r) = rest.strip_prefix('+') {
+            format.sign = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('0') {
+            format.zero = true;
+            rest = r;
+        }
+        let width_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if width_len > 0 {
+            format.width = rest[..width_len].parse().ok()?;
+            rest = &rest[width_len..];
+        }
+        if let Some(c @ (',' | '_')) = rest.chars().next() {
+            format.group = Some(c);
+            rest = &rest[1..];
+        }
+        if let Some(r) = rest.strip_prefix('.') {
+            let prec_len = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+            format.precision = Some(r[..prec_len].parse().ok()?);
+            rest = &r[prec_len..];
+        }
+        if let Some(r) = rest.strip_prefix('e') {
+            format.exp = true;
+            rest = r;
+        }
+        rest.is_empty().then_some(format)
+    }
+    /// Format a number
+    pub fn format(&self, n: f64) -> String {
+        let abs = n.abs();
+        let mut body = match (self.exp, self.precision) {
+            (true, Some(prec)) => format!("{abs:.prec$e}"),
+            (true, None) => format!("{abs:e}"),
+            (false, Some(prec)) => format!("{abs:.prec$}"),
+            (false, None) => abs.to_string(),
+        };
+        if let Some(group) = self.group.filter(|_| n.is_finite()) {
+            let int_len = body
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(body.len());
+            let mut grouped = String::with_capacity(body.len() + int_len / 3);
+            for (i, c) in body[..int_len].chars().enumerate() {
+                if i > 0 && (int_len - i) % 3 == 0 {
+                    grouped.push(group);
+                }
+                grouped.push(c);
+            }
+            grouped.push_str(&body[int_len..]);
+            body = grouped;
+        }
+        let sign = if n < 0.0 {
+            "-"
+        } else if self.sign && !n.is_nan() {
+            "+"
+        } else {
+            ""
+        };
+        let len = sign.chars().count() + body.chars().count();
+        let padding = self.width.saturating_sub(len);
+        if self.zero && n.is_finite() {
+            format!("{sign}{}{body}", "0".repeat(padding))
+        } else {
+            format!("{}{sign}{body}", " ".repeat(padding))
+        }
+    }
+}
+
+/// Options for parsing numbers from strings
+#[derive(Debug, Clone, Copy)]
+pub struct NumParseOptions {
+    /// The base of the digits
+    pub base: u32,
+    /// The decimal separator
+    pub decimal: char,
+    /// The digit group separator
+    pub group: Option<char>,
+}
+
+impl Default for NumParseOptions {
+    fn default() -> Self {
+        NumParseOptions {
+            base: 10,
+            decimal: '.',
+            group: None,
+        }
+    }
+}
+
+impl NumParseOptions {
+    /// Parse options from a value
+    ///
+    /// A number sets the base, and a string sets the separators of a locale.
+    /// The first character is the decimal separator, and the second is the digit group separator.
+    pub fn from_value(value: &Value, env: &Uiua) -> UiuaResult<Self> {
+        let mut options = NumParseOptions::default();
+        let opts: Vec<Value> = if value.rank() == 0 || matches!(value, Value::Char(_)) {
+            vec![value.clone().unboxed()]
+        } else {
+            value.rows().map(Value::unboxed).collect()
+        };
+        let mut chars = 0;
+        for opt in opts {
+            match opt {
+                Value::Char(c) if c.rank() == 0 => {
+                    match chars {
+                        0 => options.decimal = c.data[0],
+                        1 => options.group = Some(c.data[0]),
+                        _ => {
+                            return Err(
+                                env.error("Number parse options may have at most 2 characters")
+                            )
+                        }
+                    }
+                    chars += 1;
+                }
+                Value::Char(_) if opt.rank() == 1 => {
+                    let locale: Locale =
+                        (opt.as_string(env, "")?.parse()).map_err(|e| env.error(e))?;
+                    (options.decimal, options.group) = match locale {
+                        Locale::English => ('.', Some(',')),
+                        Locale::Spanish | Locale::German => (',', Some('.')),
+                    };
+                }
+                Value::Num(_) | Value::Byte(_) if opt.rank() == 0 => {
+                    let base = opt.as_nat(env, "Number base must be a natural number")?;
+                    if !(2..=36).contains(&base) {
+                        return Err(env.error(format!(
+                            "Number base must be between 2 and 36, but it is {base}"
+                        )));
+                    }
+                    options.base = base as u32;
+                }
+                opt => {
+                    return Err(env.error(format!(
+                        "Number parse options must be numbers, strings, or scalar characters, \
+                        but an option is {}",
+                        opt.type_name_plural()
+                    )))
+                }
+            }
+        }
+        if options.group == Some(options.decimal) {
+            return Err(env.error("Decimal separator and digit group separator must be different"));
+        }
+        Ok(options)
+    }
+    /// Parse a number
+    ///
+    /// On failure, returns the index of the first character that could not be parsed
+    pub fn parse(&self, s: &str) -> Result<f64, usize> {
+        let chars: Vec<char> = s.chars().collect();
+        let start = chars.iter().take_while(|c| c.is_whitespace()).count();
+        let end = chars.len()
+            - chars[start..]
+                .iter()
+                .rev()
+                .take_while(|c| c.is_whitespace())
+                .count();
+        let chars = &chars[..end];
+        let mut i = start;
+        let neg = match chars.get(i) {
+            Some('-' | '¯') => true,
+            Some('+') => false,
+            _ => false,
+        };
+        if matches!(chars.get(i), Some('-' | '¯' | '+')) {
+            i += 1;
+        }
+        let sign = if neg { -1.0 } else { 1.0 };
+        let rest: String = chars[i..].iter().collect::<String>().to_lowercase();
+        match rest.as_str() {
+            "∞" | "inf" | "infinity" => return Ok(sign * f64::INFINITY),
+            "nan" => return Ok(f64::NAN),
+            _ => {}
+        }
+        let is_digit = |c: Option<&char>| c.is_some_and(|c| c.is_digit(self.base));
+        // Collect the digits
+        let mut int_digits = Vec::new();
+        while let Some(&c) = chars.get(i) {
+            if let Some(d) = c.to_digit(self.base) {
+                int_digits.push(d);
+            } else if !(Some(c) == self.group
+                && !int_digits.is_empty()
+                && is_digit(chars.get(i + 1)))
+            {
+                break;
+            }
+            i += 1;
+        }
+        let mut frac_digits = Vec::new();
+        if chars.get(i) == Some(&self.decimal) {
+            i += 1;
+            while let Some(d) = chars.get(i).and_then(|c| c.to_digit(self.base)) {
+                frac_digits.push(d);
+                i += 1;
+            }
+        }
+        if int_digits.is_empty() && frac_digits.is_empty() {
+            return Err(i);
+        }
+        let mut exp = String::new();
+        if self.base == 10 && matches!(chars.get(i), Some('e' | 'E')) {
+            i += 1;
+            if matches!(chars.get(i), Some('-' | '¯')) {
+                exp.push('-');
+                i += 1;
+            } else if chars.get(i) == Some(&'+') {
+                i += 1;
+            }
+            let exp_start = exp.len();
+            while let Some(&c) = chars.get(i).filter(|c| c.is_ascii_digit()) {
+                exp.push(c);
+                i += 1;
+            }
+            if exp.len() == exp_start {
+                return Err(i);
+            }
+        }
+        if i < chars.len() {
+            return Err(i);
+        }
+        let n = if self.base == 10 {
+            // Parsing the normalized digits gives correct rounding
+            let digit = |&d: &u32| char::from_digit(d, 10).unwrap();
+            let mut normalized: String = int_digits.iter().map(digit).collect();
+            normalized.push('.');
+            normalized.extend(frac_digits.iter().map(digit));
+            if !exp.is_empty() {
+                normalized.push('e');
+                normalized.push_str(&exp);
+            }
+            normalized.parse::<f64>().unwrap()
+        } else {
+            let base = self.base as f64;
+            let int = int_digits.iter().fold(0.0, |acc, &d| acc * base + d as f64);
+            let frac = frac_digits
+                .iter()
+                .rev()
+                .fold(0.0, |acc, &d| (acc + d as f64) / base);
+            int + frac
+        };
+        Ok(sign * n)
+    }
+}
+
+impl Value {
+    /// Format numbers as strings
+    ///
+    /// A scalar becomes a string, and other arrays become arrays of boxed strings
+    pub fn format_num(&self, nums: &Self, env: &Uiua) -> UiuaResult<Self> {
+        let format = NumFormat::from_value(self, env)?;
+        let nums = match nums {
+            Value::Num(arr) => arr.clone(),
+            Value::Byte(arr) => arr.convert_ref(),
+            val => {
+                return Err(env.error(format!(
+                    "Cannot {} {}",
+                    Primitive::FormatNum.format(),
+                    val.type_name_plural()
+                )))
+            }
+        };
+        if nums.rank() == 0 {
+            return Ok(format.format(nums.data[0]).into());
+        }
+        let data: EcoVec<Boxed> = (nums.data.iter())
+            .map(|&n| Boxed(format.format(n).into()))
+            .collect();
+        Ok(Array::new(nums.shape.clone(), data).into())
+    }
+    /// Parse strings as numbers
+    ///
+    /// Returns the numbers and the index of the first invalid character in each string.
+    /// The index is `-1` for strings that were parsed successfully.
+    pub fn parse_nums(&self, strings: &Self, env: &Uiua) -> UiuaResult<(Self, Self)> {
+        let options = NumParseOptions::from_value(self, env)?;
+        let (shape, strings): (Shape, Vec<String>) = match strings {
+            Value::Char(arr) if arr.rank() <= 1 => (Shape::SCALAR, vec![arr.data.iter().collect()]),
+            Value::Char(arr) => {
+                let shape = arr.shape[..arr.rank() - 1].into();
+                let row_len = *arr.shape.last().unwrap();
+                let strings = if row_len == 0 {
+                    vec![String::new(); arr.shape.elements()]
+                } else {
+                    arr.data
+                        .chunks_exact(row_len)
+                        .map(|s| s.iter().collect())
+                        .collect()
+                };
+                (shape, strings)
+            }
+            Value::Box(arr) => {
+                let mut strings = Vec::with_capacity(arr.data.len());
+                for Boxed(val) in &arr.data {
+                    strings.push(val.as_string(env, "Strings to parse must be strings")?);
+                }
+                (arr.shape.clone(), strings)
+            }
+            val if val.element_count() == 0 => (val.shape().clone(), Vec::new()),
+            val => {
+                return Err(env.error(format!(
+                    "Cannot {} {}",
+                    Primitive::ParseNum.format(),
+                    val.type_name_plural()
+                )))
+            }
+        };
+        let fill = env.scalar_fill::<f64>().unwrap_or(f64::NAN);
+        let mut nums = EcoVec::with_capacity(strings.len());
+        let mut errors = EcoVec::with_capacity(strings.len());
+        for s in &strings {
+            match options.parse(s) {
+                Ok(n) => {
+                    nums.push(n);
+                    errors.push(-1.0);
+                }
+                Err(i) => {
+                    nums.push(fill);
+                    errors.push(i as f64);
+                }
+            }
+        }
+        Ok((
+            Array::new(shape.clone(), nums).into(),
+            Array::new(shape, errors).into(),
+        ))
+    }
+}
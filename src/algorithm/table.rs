@@ -1,10 +1,10 @@
 //! Algorithms for tabling modifiers
 
 use ecow::eco_vec;
+use rand::Rng;
 
 use crate::{
     algorithm::{get_ops, pervade::*, zip::rows1, FillContext},
-    random,
     value::Value,
     Array, ArrayValue, Complex, ImplPrimitive, Node, Ops, Primitive, Shape, SigNode, Uiua,
     UiuaResult,
@@ -307,7 +307,7 @@ pub fn table_list(f: SigNode, xs: Value, ys: Value, env: &mut Uiua) -> UiuaResul
                 let shape = [xs.row_count(), ys.row_count()];
                 let mut data = eco_vec![0.0; xs.row_count() * ys.row_count()];
                 for n in data.make_mut() {
-                    *n = random();
+                    *n = env.rt.rng.gen();
                 }
                 env.push(Array::new(shape, data));
             }
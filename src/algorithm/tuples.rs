@@ -172,6 +172,7 @@ fn tuple2(f: SigNode, env: &mut Uiua) -> UiuaResult {
             where
                 Value: From<Array<T>>,
             {
+                validate_size::<usize>([k], env)?;
                 let mut cache = HashMap::new();
                 let mut curr = vec![0; k];
                 let mut data = EcoVec::new();
@@ -0,0 +1,272 @@
+//! Lazy strided views of arrays
+//!
+//! Runs of axis-reordering and row-selecting primitives like
+//! [`Primitive::Transpose`], [`Primitive::Reverse`], and [`Primitive::Take`]
+//! are fused by the optimizer. The primitives are applied to a view that
+//! only records how each of its axes maps onto the source array. The
+//! elements are only copied once, when the view is materialized.
+//!
+//! Arrays themselves do not carry strides. Only adjacent primitives with
+//! constant arguments in the same function are fused, and the view is
+//! materialized at the end of the run, even if the result is then passed
+//! to another reordering primitive elsewhere.
+
+use ecow::EcoVec;
+
+use crate::{
+    val_as_arr, Array, ArrayValue, ImplPrimitive, Node, Ops, Primitive, Uiua, UiuaResult, Value,
+};
+
+use super::get_ops;
+
+/// Check if a node can be applied to a [`View`]
+///
+/// Returns the number of nodes it takes up and whether it reorders elements
+pub(crate) fn view_op_len(nodes: &[Node]) -> Option<(usize, bool)> {
+    use Primitive::*;
+    Some(match nodes {
+        [Node::Prim(Transpose | Reverse, _), ..] => (1, true),
+        [Node::ImplPrim(ImplPrimitive::TransposeN(_), _), ..] => (1, true),
+        [Node::Prim(First | Last, _), ..] => (1, false),
+        [Node::Push(n), Node::Prim(prim @ (Take | Drop | Rotate), _), ..]
+            if view_op_ints(n).is_some() =>
+        {
+            (2, *prim == Rotate)
+        }
+        _ => return None,
+    })
+}
+
+fn view_op_ints(val: &Value) -> Option<Vec<isize>> {
+    if val.rank() > 1 {
+        return None;
+    }
+    match val {
+        Value::Num(arr) => (arr.data.iter())
+            .map(|&n| (n.fract() == 0.0 && n.abs() < isize::MAX as f64).then_some(n as isize))
+            .collect(),
+        Value::Byte(arr) => Some(arr.data.iter().map(|&n| n as isize).collect()),
+        _ => None,
+    }
+}
+
+/// Run a fused sequence of view operations
+///
+/// Operations that cannot be done on the view, such as those that would
+/// need a fill value or throw an error, are run normally after the view
+/// is materialized.
+pub fn strided_view(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    let nodes = f.node.as_slice();
+    let val = env.pop(1)?;
+    let has_fill = env.fill().value().is_some();
+    let (val, applied) = if (val.meta().label.is_some() || val.meta().map_keys.is_some())
+        || val.meta().pointer.is_some()
+        || val.meta().handle_kind.is_some()
+    {
+        (val, 0)
+    } else {
+        val_as_arr!(val, |arr| {
+            let mut view = View::new(&arr);
+            let applied = view.apply(nodes, has_fill);
+            let val: Value = if applied == 0 {
+                arr.into()
+            } else {
+                view.materialize(&arr).into()
+            };
+            (val, applied)
+        })
+    };
+    env.push(val);
+    if applied < nodes.len() {
+        env.exec(Node::from(&nodes[applied..]))?;
+    }
+    Ok(())
+}
+
+/// An axis of a [`View`]
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    /// The number of elements between rows of the source axis
+    stride: usize,
+    /// The length of the source axis
+    src_len: usize,
+    /// The length of the view axis
+    len: usize,
+    /// The index into the source axis of the first row of the view axis
+    start: usize,
+    /// Whether the view axis is reversed relative to the source axis
+    rev: bool,
+}
+
+impl Axis {
+    /// Get the source index of a row
+    fn src_index(&self, i: usize) -> usize {
+        if self.src_len == 0 {
+            self.start
+        } else if self.rev {
+            (self.start + self.src_len - i % self.src_len) % self.src_len
+        } else {
+            (self.start + i) % self.src_len
+        }
+    }
+    /// Move the start of the axis forward by some number of rows
+    fn advance(&mut self, n: usize) {
+        self.start = self.src_index(n);
+    }
+}
+
+/// A lazy view of an array
+#[derive(Debug)]
+struct View {
+    axes: Vec<Axis>,
+    offset: usize,
+}
+
+impl View {
+    fn new<T>(arr: &Array<T>) -> Self {
+        let mut stride = 1;
+        let mut axes: Vec<Axis> = (arr.shape.iter().rev())
+            .map(|&len| {
+                let axis = Axis {
+                    stride,
+                    src_len: len,
+                    len,
+                    start: 0,
+                    rev: false,
+                };
+                stride *= len;
+                axis
+            })
+            .collect();
+        axes.reverse();
+        View { axes, offset: 0 }
+    }
+    /// Apply as many operations as possible
+    ///
+    /// Returns the number of nodes that were applied
+    fn apply(&mut self, nodes: &[Node], has_fill: bool) -> usize {
+        let mut applied = 0;
+        while let Some((len, _)) = view_op_len(&nodes[applied..]) {
+            if !self.apply_op(&nodes[applied..], has_fill) {
+                break;
+            }
+            applied += len;
+        }
+        applied
+    }
+    fn apply_op(&mut self, nodes: &[Node], has_fill: bool) -> bool {
+        use Primitive::*;
+        // Some operations on scalar boxes apply to their contents,
+        // so scalars are left to the primitives themselves
+        if self.axes.is_empty() {
+            return false;
+        }
+        match nodes {
+            [Node::Prim(Transpose, _), ..] => self.axes.rotate_left(1),
+            &[Node::ImplPrim(ImplPrimitive::TransposeN(amnt), _), ..] => {
+                let count = amnt.unsigned_abs() as usize % self.axes.len();
+                if amnt > 0 {
+                    self.axes.rotate_left(count);
+                } else {
+                    self.axes.rotate_right(count);
+                }
+            }
+            [Node::Prim(Reverse, _), ..] => {
+                let axis = &mut self.axes[0];
+                if axis.len > 0 {
+                    axis.advance(axis.len - 1);
+                    axis.rev = !axis.rev;
+                }
+            }
+            [Node::Prim(prim @ (First | Last), _), ..] => {
+                let axis = self.axes[0];
+                if axis.len == 0 {
+                    return false;
+                }
+                let i = if *prim == First { 0 } else { axis.len - 1 };
+                self.offset += axis.src_index(i) * axis.stride;
+                self.axes.remove(0);
+            }
+            [Node::Push(n), Node::Prim(prim, _), ..] => {
+                let Some(ns) = view_op_ints(n) else {
+                    return false;
+                };
+                if ns.len() > self.axes.len() {
+                    return false;
+                }
+                let multi = ns.len() > 1;
+                let mut axes = self.axes.clone();
+                for (axis, n) in axes.iter_mut().zip(ns) {
+                    let abs = n.unsigned_abs();
+                    match prim {
+                        Take => {
+                            if abs > axis.len {
+                                return false;
+                            }
+                            if n < 0 {
+                                axis.advance(axis.len - abs);
+                            }
+                            axis.len = abs;
+                        }
+                        Drop => {
+                            // Dropping more than all of an axis along
+                            // multiple axes is left to the primitive
+                            if multi && abs > axis.len {
+                                return false;
+                            }
+                            let abs = abs.min(axis.len);
+                            if n >= 0 {
+                                axis.advance(abs);
+                            }
+                            axis.len -= abs;
+                        }
+                        Rotate => {
+                            if has_fill || axis.len != axis.src_len || axis.len == 0 {
+                                return false;
+                            }
+                            let n = n.rem_euclid(axis.len as isize) as usize;
+                            axis.advance(n);
+                        }
+                        _ => return false,
+                    }
+                }
+                self.axes = axes;
+            }
+            _ => return false,
+        }
+        true
+    }
+    /// Copy the viewed elements into a new array
+    fn materialize<T: ArrayValue>(&self, arr: &Array<T>) -> Array<T> {
+        let shape: Vec<usize> = self.axes.iter().map(|axis| axis.len).collect();
+        let mut data = EcoVec::with_capacity(shape.iter().product());
+        if !shape.contains(&0) {
+            self.materialize_axes(&self.axes, self.offset, &arr.data, &mut data);
+        }
+        let mut new = Array::new(shape.as_slice(), data);
+        new.meta.clone_from(&arr.meta);
+        new
+    }
+    fn materialize_axes<T: Clone>(
+        &self,
+        axes: &[Axis],
+        offset: usize,
+        src: &[T],
+        dst: &mut EcoVec<T>,
+    ) {
+        match axes {
+            [] => dst.push(src[offset].clone()),
+            [axis] if !axis.rev && axis.stride == 1 && axis.start + axis.len <= axis.src_len => {
+                let start = offset + axis.start;
+                dst.extend_from_slice(&src[start..start + axis.len]);
+            }
+            [axis, rest @ ..] => {
+                for i in 0..axis.len {
+                    let offset = offset + axis.src_index(i) * axis.stride;
+                    self.materialize_axes(rest, offset, src, dst);
+                }
+            }
+        }
+    }
+}
@@ -2,15 +2,19 @@
 
 use std::{cell::RefCell, collections::HashMap, iter::repeat, mem::swap, rc::Rc};
 
-use ecow::eco_vec;
+use ecow::{eco_vec, EcoVec};
+use rand::Rng;
 
 use crate::{
-    algorithm::pervade::bin_pervade_values, cowslice::CowSlice, get_ops, random,
+    algorithm::pervade::bin_pervade_values, cowslice::CowSlice, get_ops,
     types::push_empty_rows_value, val_as_arr, value::Value, Array, Boxed, ImplPrimitive, Node, Ops,
     PersistentMeta, Primitive, Shape, SigNode, Uiua, UiuaResult,
 };
 
-use super::{fill_value_shapes, fixed_rows, multi_output, FixedRowsData, MultiOutput};
+use super::{
+    fill_value_shapes, fixed_rows, multi_output, par_loop, shape_mismatch_info, FixedRowsData,
+    MultiOutput,
+};
 
 type ValueMonFn = Rc<dyn Fn(Value, usize, &mut Uiua) -> UiuaResult<Value>>;
 type ValueMon2Fn = Box<dyn Fn(Value, usize, &mut Uiua) -> UiuaResult<(Value, Value)>>;
@@ -80,12 +84,12 @@ fn impl_prim_mon_fast_fn(prim: ImplPrimitive, span: usize) -> Option<ValueMonFn>
             Value::transpose_depth(&mut v, d, n);
             Ok(v)
         }),
-        ReplaceRand => spanned_mon_fn(span, |v, d, _| {
+        ReplaceRand => mon_fn(|v, d, env| {
             let shape = &v.shape()[..d.min(v.rank())];
             let elem_count: usize = shape.iter().product();
             let mut data = eco_vec![0.0; elem_count];
             for n in data.make_mut() {
-                *n = random();
+                *n = env.rt.rng.gen();
             }
             Ok(Array::new(shape, data).into())
         }),
@@ -93,6 +97,10 @@ fn impl_prim_mon_fast_fn(prim: ImplPrimitive, span: usize) -> Option<ValueMonFn>
             v.sort_down_depth(d);
             Ok(v)
         }),
+        DupPervasive(prim) => {
+            let f = prim.dyadic_pervasive_fn()?;
+            spanned_mon_fn(span, move |v, _, env| f(v.clone(), v, env))
+        }
         _ => return None,
     })
 }
@@ -347,6 +355,11 @@ pub(crate) fn f_dy_fast_fn(
                 1,
             )
         }
+        &[Node::ImplPrim(ImplPrimitive::FlipPervasive(prim), span)] => {
+            let f = prim_dy_fast_fn(prim, span, b_filled, a_filled)?;
+            let f = Box::new(move |a, b, ad, bd, env: &mut Uiua| f(b, a, bd, ad, env));
+            return Some((f, 0, 0));
+        }
         [Node::Prim(Flip, _), rest @ ..] => {
             let (f, a, b) = f_dy_fast_fn(rest, a_filled, b_filled)?;
             let f = Box::new(move |a, b, ad, bd, env: &mut Uiua| f(b, a, bd, ad, env));
@@ -550,6 +563,37 @@ fn eachn(f: SigNode, mut args: Vec<Value>, env: &mut Uiua) -> UiuaResult {
     Ok(())
 }
 
+/// Run a function on the arguments for each row, collecting its outputs
+///
+/// `args` gives the values to push for each row, bottom-most first.
+///
+/// Rows may be run in parallel. See [`par_loop`].
+fn exec_rows(
+    f: &SigNode,
+    args: impl ExactSizeIterator<Item = Vec<Value>>,
+    inv: bool,
+    new_rows: &mut MultiOutput<Vec<Value>>,
+    env: &mut Uiua,
+) -> UiuaResult {
+    par_loop(
+        f,
+        args,
+        |args, env| -> UiuaResult<MultiOutput<Value>> {
+            env.push_all(args);
+            env.exec(f.clone())?;
+            (0..f.sig.outputs)
+                .map(|_| Ok(env.pop("rows' function result")?.boxed_if(inv)))
+                .collect()
+        },
+        |outputs| {
+            for (new_rows, val) in new_rows.iter_mut().zip(outputs) {
+                new_rows.push(val);
+            }
+        },
+        env,
+    )
+}
+
 pub fn rows(f: SigNode, inv: bool, env: &mut Uiua) -> UiuaResult {
     crate::profile_function!();
     match f.sig.args {
@@ -625,13 +669,8 @@ pub fn rows1(f: SigNode, mut xs: Value, inv: bool, env: &mut Uiua) -> UiuaResult
                 }
             }
         } else {
-            for row in xs.into_rows() {
-                env.push(row.unboxed_if(inv));
-                env.exec(f.clone())?;
-                for i in 0..outputs {
-                    new_rows[i].push(env.pop("rows' function result")?.boxed_if(inv));
-                }
-            }
+            let args = xs.into_rows().map(|row| vec![row.unboxed_if(inv)]);
+            exec_rows(&f, args, inv, &mut new_rows, env)?;
         }
         Ok(())
     })?;
@@ -663,14 +702,8 @@ fn rows2(f: SigNode, mut xs: Value, mut ys: Value, inv: bool, env: &mut Uiua) ->
                         }
                     }
                 } else {
-                    for x in xs.into_rows() {
-                        env.push(ys.clone());
-                        env.push(x.unboxed_if(inv));
-                        env.exec(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?.boxed_if(inv));
-                        }
-                    }
+                    let args = (xs.into_rows()).map(|x| vec![ys.clone(), x.unboxed_if(inv)]);
+                    exec_rows(&f, args, inv, &mut new_rows, env)?;
                 }
                 Ok(())
             })?;
@@ -697,14 +730,8 @@ fn rows2(f: SigNode, mut xs: Value, mut ys: Value, inv: bool, env: &mut Uiua) ->
                         }
                     }
                 } else {
-                    for y in ys.into_rows() {
-                        env.push(y.unboxed_if(inv));
-                        env.push(xs.clone());
-                        env.exec(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?.boxed_if(inv));
-                        }
-                    }
+                    let args = (ys.into_rows()).map(|y| vec![y.unboxed_if(inv), xs.clone()]);
+                    exec_rows(&f, args, inv, &mut new_rows, env)?;
                 }
                 Ok(())
             })?;
@@ -712,15 +739,17 @@ fn rows2(f: SigNode, mut xs: Value, mut ys: Value, inv: bool, env: &mut Uiua) ->
         }
         (a, b) => {
             if a != b {
-                return Err(env.error(format!(
-                    "Cannot {} arrays with different number of rows {a} and {b}",
-                    if inv {
-                        Primitive::Inventory
-                    } else {
-                        Primitive::Rows
-                    }
-                    .format(),
-                )));
+                return Err(env
+                    .error(format!(
+                        "Cannot {} arrays with different number of rows {a} and {b}",
+                        if inv {
+                            Primitive::Inventory
+                        } else {
+                            Primitive::Rows
+                        }
+                        .format(),
+                    ))
+                    .with_info(shape_mismatch_info(xs.shape(), ys.shape(), 0, env.locale())));
             }
             if !inv {
                 if let Some((f, a, b)) = f_dy_fast_fn(
@@ -760,14 +789,9 @@ fn rows2(f: SigNode, mut xs: Value, mut ys: Value, inv: bool, env: &mut Uiua) ->
                         }
                     }
                 } else {
-                    for (x, y) in xs.into_rows().zip(ys.into_rows()) {
-                        env.push(y.unboxed_if(inv));
-                        env.push(x.unboxed_if(inv));
-                        env.exec(f.clone())?;
-                        for i in 0..outputs {
-                            new_rows[i].push(env.pop("rows's function result")?.boxed_if(inv));
-                        }
-                    }
+                    let args = (xs.into_rows().zip(ys.into_rows()))
+                        .map(|(x, y)| vec![y.unboxed_if(inv), x.unboxed_if(inv)]);
+                    exec_rows(&f, args, inv, &mut new_rows, env)?;
                 }
                 Ok(())
             })?;
@@ -791,22 +815,16 @@ fn rowsn(f: SigNode, args: Vec<Value>, inv: bool, env: &mut Uiua) -> UiuaResult
         per_meta,
         ..
     } = fixed_rows(prim.format(), outputs, args, env)?;
+    let args = (0..row_count).map(|_| {
+        (rows.iter_mut().rev())
+            .map(|arg| match arg {
+                Ok(rows) => rows.next().unwrap().unboxed_if(inv),
+                Err(row) => row.clone().unboxed_if(inv),
+            })
+            .collect()
+    });
     let mut new_values = multi_output(outputs, Vec::new());
-    env.without_fill(|env| -> UiuaResult {
-        for _ in 0..row_count {
-            for arg in rows.iter_mut().rev() {
-                match arg {
-                    Ok(rows) => env.push(rows.next().unwrap().unboxed_if(inv)),
-                    Err(row) => env.push(row.clone().unboxed_if(inv)),
-                }
-            }
-            env.exec(f.clone())?;
-            for i in 0..outputs {
-                new_values[i].push(env.pop("rows's function result")?.boxed_if(inv));
-            }
-        }
-        Ok(())
-    })?;
+    env.without_fill(|env| exec_rows(&f, args, inv, &mut new_values, env))?;
     for new_values in new_values.into_iter().rev() {
         let mut rowsed = Value::from_row_values(new_values, env)?;
         if all_scalar {
@@ -821,6 +839,114 @@ fn rowsn(f: SigNode, args: Vec<Value>, inv: bool, env: &mut Uiua) -> UiuaResult
     Ok(())
 }
 
+/// Call a function on each row of some arrays, collecting the errors of
+/// the rows that fail instead of returning the first one
+pub fn catch(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    if f.sig.outputs > 1 {
+        return Err(env.error(format!(
+            "{}'s function must have at most 1 output, but its signature is {}",
+            Primitive::Catch.format(),
+            f.sig
+        )));
+    }
+    let mut args = Vec::with_capacity(f.sig.args);
+    for i in 0..f.sig.args {
+        args.push(env.pop(i + 1)?);
+    }
+    let FixedRowsData {
+        mut rows,
+        row_count,
+        ..
+    } = fixed_rows(Primitive::Catch.format(), 0, args, env)?;
+    let args = (0..row_count).map(|_| {
+        (rows.iter_mut().rev())
+            .map(|arg| match arg {
+                Ok(rows) => rows.next().unwrap(),
+                Err(row) => row.clone(),
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut results = EcoVec::with_capacity(row_count);
+    let mut failed = EcoVec::with_capacity(row_count);
+    env.without_fill(|env| {
+        par_loop(
+            &f,
+            args,
+            |args, env| -> UiuaResult<Result<Value, Value>> {
+                env.push_all(args);
+                Ok(match env.exec_clean_stack(f.clone()) {
+                    Ok(()) if f.sig.outputs == 0 => Ok(Value::default()),
+                    Ok(()) => Ok(env.pop("catch's function result")?),
+                    Err(err) => Err(err.value()),
+                })
+            },
+            |res| {
+                failed.push(res.is_err() as u8);
+                results.push(Boxed(res.unwrap_or_else(|err| err)));
+            },
+            env,
+        )
+    })?;
+    env.push(Array::from(results));
+    env.push(Array::from(failed));
+    Ok(())
+}
+
+/// Call a function on some of the rows of some arrays
+pub fn resume(ops: Ops, env: &mut Uiua) -> UiuaResult {
+    let [f] = get_ops(ops, env)?;
+    let start = (env.pop("start")?).as_nat(env, "Start must be a natural number")?;
+    let count = (env.pop("count")?).as_nat(env, "Count must be a natural number")?;
+    let mut args = Vec::with_capacity(f.sig.args);
+    for i in 0..f.sig.args {
+        args.push(env.pop(i + 1)?);
+    }
+    // Arrays with one row are reused for every row unless all arrays have one row
+    let mut first: Option<&Value> = None;
+    for arg in &args {
+        let n = arg.row_count();
+        if n == 1 {
+            continue;
+        }
+        match first {
+            Some(first) if first.row_count() != n => {
+                return Err(env
+                    .error(format!(
+                        "Cannot {} arrays with different number of rows {} and {n}",
+                        Primitive::Resume.format(),
+                        first.row_count()
+                    ))
+                    .with_info(shape_mismatch_info(
+                        first.shape(),
+                        arg.shape(),
+                        0,
+                        env.locale(),
+                    )))
+            }
+            _ => first = Some(arg),
+        }
+    }
+    let all_1 = first.is_none();
+    let row_count = first.map_or(1, Value::row_count);
+    let start = start.min(row_count);
+    let end = start.saturating_add(count).min(row_count);
+    for mut arg in args.into_iter().rev() {
+        if all_1 && arg.rank() == 0 {
+            arg.fix();
+        }
+        if start == end {
+            arg = arg.first_dim_zero();
+        } else if all_1 || arg.row_count() != 1 {
+            arg = arg.slice_rows(start, end);
+        }
+        env.push(arg);
+    }
+    rows(f, false, env)?;
+    env.push(end);
+    Ok(())
+}
+
 pub fn reduce_conjoin_inventory(ops: Ops, env: &mut Uiua) -> UiuaResult {
     let [f] = get_ops(ops, env)?;
     if f.sig.outputs != 1 {
@@ -1,5 +1,5 @@
 use std::{
-    any::TypeId,
+    any::Any,
     cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
@@ -54,6 +54,9 @@ pub struct ArrayMeta {
     /// The kind of system handle
     #[serde(skip)]
     pub handle_kind: Option<HandleKind>,
+    /// The categories of an interned array
+    #[serde(skip)]
+    pub categories: Option<Arc<Categories>>,
 }
 
 impl ArrayMeta {
@@ -79,6 +82,7 @@ impl ArrayMeta {
         if self.handle_kind != other.handle_kind {
             self.handle_kind = None;
         }
+        self.categories = None;
     }
     /// Check if the metadata is the default
     pub fn is_default(&self) -> bool {
@@ -87,9 +91,32 @@ impl ArrayMeta {
             && self.handle_kind.is_none()
             && self.pointer.is_none()
             && self.flags.is_empty()
+            && self.categories.is_none()
+    }
+}
+
+/// The unique rows and codes of an interned array
+#[derive(Debug, Clone)]
+pub struct Categories {
+    /// The interned array
+    ///
+    /// Holding onto the array's data means that changing it makes a copy,
+    /// so the categories can tell when they no longer apply.
+    pub(crate) source: Value,
+    /// The unique rows
+    pub table: Value,
+    /// The index into the table of each row
+    pub codes: Value,
+}
+
+impl PartialEq for Categories {
+    fn eq(&self, other: &Self) -> bool {
+        self.table == other.table && self.codes == other.codes
     }
 }
 
+impl Eq for Categories {}
+
 /// Array pointer metadata
 #[derive(Debug, Clone, Copy)]
 pub struct MetaPtr {
@@ -160,6 +187,7 @@ pub static DEFAULT_META: ArrayMeta = ArrayMeta {
     map_keys: None,
     pointer: None,
     handle_kind: None,
+    categories: None,
 };
 
 /// Array metadata that can be persisted across operations
@@ -314,6 +342,10 @@ impl<T> Array<T> {
     pub fn elements(&self) -> impl ExactDoubleIterator<Item = &T> {
         self.data.iter()
     }
+    /// Get the elements of the array as a slice in row-major order
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
     /// Get the metadata of the array
     pub fn meta(&self) -> &ArrayMeta {
         self.meta.as_deref().unwrap_or(&DEFAULT_META)
@@ -380,6 +412,22 @@ impl<T> Array<T> {
         let row_len = self.row_len();
         &self.data[row * row_len..(row + 1) * row_len]
     }
+    /// Get a borrowed view of the array
+    pub fn view(&self) -> ArrayView<'_, T> {
+        ArrayView {
+            shape: &self.shape,
+            data: &self.data,
+        }
+    }
+    /// Get an iterator over borrowed views of the rows of the array
+    ///
+    /// Unlike [`Array::rows`], this does not create new arrays, so it does not touch
+    /// the reference count of the array's data.
+    pub fn row_views(
+        &self,
+    ) -> impl ExactSizeIterator<Item = ArrayView<'_, T>> + DoubleEndedIterator {
+        self.view().row_views()
+    }
     /// Combine the metadata of two arrays
     ///
     /// This combines:
@@ -396,6 +444,73 @@ impl<T> Array<T> {
     }
 }
 
+/// A borrowed view of an array's shape and data
+///
+/// Views can be created with [`Array::view`] and [`Array::row_views`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct ArrayView<'a, T> {
+    shape: &'a [usize],
+    data: &'a [T],
+}
+
+impl<T> Clone for ArrayView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayView<'_, T> {}
+
+impl<'a, T> ArrayView<'a, T> {
+    /// Get the shape of the view
+    pub fn shape(&self) -> &'a [usize] {
+        self.shape
+    }
+    /// Get the elements of the view
+    pub fn data(&self) -> &'a [T] {
+        self.data
+    }
+    /// Get the rank of the view
+    pub fn rank(&self) -> usize {
+        self.shape.len()
+    }
+    /// Get the number of rows in the view
+    pub fn row_count(&self) -> usize {
+        self.shape.first().copied().unwrap_or(1)
+    }
+    /// Get the number of elements in a row
+    pub fn row_len(&self) -> usize {
+        self.shape.iter().skip(1).product()
+    }
+    /// Get a view of a row
+    #[track_caller]
+    pub fn row(&self, row: usize) -> Self {
+        if self.rank() == 0 {
+            return *self;
+        }
+        let row_count = self.row_count();
+        if row >= row_count {
+            panic!("row index out of bounds: {} >= {}", row, row_count);
+        }
+        let row_len = self.row_len();
+        ArrayView {
+            shape: &self.shape[1..],
+            data: &self.data[row * row_len..(row + 1) * row_len],
+        }
+    }
+    /// Get an iterator over views of the rows
+    pub fn row_views(self) -> impl ExactSizeIterator<Item = Self> + DoubleEndedIterator {
+        (0..self.row_count()).map(move |row| self.row(row))
+    }
+}
+
+impl<T: Clone> ArrayView<'_, T> {
+    /// Copy the view into a new array
+    pub fn to_array(&self) -> Array<T> {
+        Array::new(self.shape, CowSlice::from(self.data))
+    }
+}
+
 impl<T: ArrayValue> Array<T> {
     /// Create a scalar array
     pub fn scalar(data: T) -> Self {
@@ -429,12 +544,6 @@ impl<T: ArrayValue> Array<T> {
     pub fn rows(&self) -> impl ExactSizeIterator<Item = Self> + DoubleEndedIterator + '_ {
         (0..self.row_count()).map(|row| self.row(row))
     }
-    pub(crate) fn row_shaped_slice(&self, index: usize, row_shape: Shape) -> Self {
-        let row_len = row_shape.elements();
-        let start = index * row_len;
-        let end = start + row_len;
-        Self::new(row_shape, self.data.slice(start..end))
-    }
     /// Get an iterator over the row arrays of the array that have the given shape
     pub fn row_shaped_slices(
         &self,
@@ -562,11 +671,12 @@ impl<T: Clone> Array<T> {
         T: Into<U> + 'static,
         U: Clone + 'static,
     {
-        if TypeId::of::<T>() == TypeId::of::<U>() {
-            unsafe { std::mem::transmute::<Array<T>, Array<U>>(self) }
-        } else {
-            self.convert_with(Into::into)
+        let mut arr = Some(self);
+        match (&mut arr as &mut dyn Any).downcast_mut::<Option<Array<U>>>() {
+            Some(same) => same.take(),
+            None => arr.take().map(|arr| arr.convert_with(Into::into)),
         }
+        .expect("array should only be taken once")
     }
     /// Convert the elements of the array with a function
     pub fn convert_with<U: Clone>(self, f: impl FnMut(T) -> U) -> Array<U> {
@@ -1438,6 +1548,7 @@ impl<T: ArrayValueSer> From<Array<T>> for ArrayRep<T> {
     fn from(mut arr: Array<T>) -> Self {
         if let Some(meta) = arr.meta.take().filter(|meta| **meta != DEFAULT_META) {
             let mut meta = Arc::unwrap_or_clone(meta);
+            meta.categories = None;
             let map_keys = meta.map_keys.take();
             if meta == DEFAULT_META {
                 if let Some(map_keys) = map_keys {
@@ -0,0 +1,165 @@
+//! Conversion between Uiua values and Apache Arrow data
+//!
+//! Uiua tables are [`map`](crate::Primitive::Map)s of column names to columns.
+//! These functions convert such tables to and from Arrow [`RecordBatch`]es and the Arrow IPC format.
+//!
+//! Column data is copied when converting, since Uiua and Arrow use different buffer types.
+
+use std::{io::Cursor, sync::Arc};
+
+use arrow_array::{
+    cast::AsArray, types::*, Array as _, ArrayRef, Float64Array, RecordBatch, StringArray,
+    UInt8Array,
+};
+use arrow_ipc::{reader::FileReader, reader::StreamReader, writer::StreamWriter};
+use arrow_schema::{DataType, Field, Schema};
+use ecow::EcoVec;
+
+use crate::{Array, Boxed, Value};
+
+/// Convert named columns into a [`RecordBatch`]
+///
+/// Number arrays become `Float64` columns and byte arrays become `UInt8` columns.
+/// Lists of boxed strings and rank 2 character arrays become `Utf8` columns.
+/// All columns must have the same length.
+pub fn columns_to_record_batch(columns: &[(String, Value)]) -> Result<RecordBatch, String> {
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays = Vec::with_capacity(columns.len());
+    for (name, value) in columns {
+        let array = value_to_array(value).map_err(|e| format!("Column {name:?}: {e}"))?;
+        fields.push(Field::new(name, array.data_type().clone(), false));
+        arrays.push(array);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).map_err(|e| e.to_string())
+}
+
+fn value_to_array(value: &Value) -> Result<ArrayRef, String> {
+    Ok(match value {
+        Value::Num(arr) if arr.rank() == 1 => {
+            Arc::new(Float64Array::from(arr.data.to_vec())) as ArrayRef
+        }
+        Value::Byte(arr) if arr.rank() == 1 => Arc::new(UInt8Array::from(arr.data.to_vec())),
+        Value::Char(arr) if arr.rank() == 2 => Arc::new(StringArray::from_iter_values(
+            arr.rows().map(|row| row.data.iter().collect::<String>()),
+        )),
+        Value::Box(arr) if arr.rank() == 1 => {
+            let strings = (arr.data.iter())
+                .map(|Boxed(val)| val.as_string_opt())
+                .collect::<Option<Vec<_>>>()
+                .ok_or("Boxed columns must contain only strings")?;
+            Arc::new(StringArray::from(strings))
+        }
+        Value::Complex(_) => return Err("Complex columns are not supported".into()),
+        Value::Char(arr) => {
+            return Err(format!(
+                "Character columns must be rank 2, but this one is rank {}",
+                arr.rank()
+            ))
+        }
+        value => {
+            return Err(format!(
+                "Columns must be rank 1, but this one is rank {}",
+                value.rank()
+            ))
+        }
+    })
+}
+
+/// Convert a [`RecordBatch`] into named columns
+///
+/// `Boolean` and `UInt8` columns without nulls become byte arrays.
+/// Other integer and float columns become number arrays, with nulls as `NaN`.
+/// `Utf8` columns become lists of boxed strings, with nulls as empty strings.
+pub fn record_batch_to_columns(batch: &RecordBatch) -> Result<Vec<(String, Value)>, String> {
+    (batch.schema().fields().iter())
+        .zip(batch.columns())
+        .map(|(field, array)| {
+            let value =
+                array_to_value(array).map_err(|e| format!("Column {:?}: {e}", field.name()))?;
+            Ok((field.name().clone(), value))
+        })
+        .collect()
+}
+
+fn array_to_value(array: &ArrayRef) -> Result<Value, String> {
+    macro_rules! nums {
+        ($ty:ty) => {{
+            let array = array.as_primitive::<$ty>();
+            let data: EcoVec<f64> = (array.iter())
+                .map(|n| n.map_or(f64::NAN, |n| n as f64))
+                .collect();
+            Array::from(data).into()
+        }};
+    }
+    macro_rules! strings {
+        ($offset:ty) => {{
+            let data: EcoVec<Boxed> = (array.as_string::<$offset>().iter())
+                .map(|s| Boxed(s.unwrap_or_default().into()))
+                .collect();
+            Array::from(data).into()
+        }};
+    }
+    Ok(match array.data_type() {
+        DataType::Boolean if array.null_count() == 0 => {
+            let data: EcoVec<u8> = (array.as_boolean().values().iter()).map(u8::from).collect();
+            Array::from(data).into()
+        }
+        DataType::Boolean => {
+            let data: EcoVec<f64> = (array.as_boolean().iter())
+                .map(|b| b.map_or(f64::NAN, |b| b as u8 as f64))
+                .collect();
+            Array::from(data).into()
+        }
+        DataType::UInt8 if array.null_count() == 0 => {
+            let data: EcoVec<u8> = array.as_primitive::<UInt8Type>().values().to_vec().into();
+            Array::from(data).into()
+        }
+        DataType::UInt8 => nums!(UInt8Type),
+        DataType::UInt16 => nums!(UInt16Type),
+        DataType::UInt32 => nums!(UInt32Type),
+        DataType::UInt64 => nums!(UInt64Type),
+        DataType::Int8 => nums!(Int8Type),
+        DataType::Int16 => nums!(Int16Type),
+        DataType::Int32 => nums!(Int32Type),
+        DataType::Int64 => nums!(Int64Type),
+        DataType::Float32 => nums!(Float32Type),
+        DataType::Float64 => nums!(Float64Type),
+        DataType::Utf8 => strings!(i32),
+        DataType::LargeUtf8 => strings!(i64),
+        ty => return Err(format!("Arrow type {ty} is not supported")),
+    })
+}
+
+/// Encode named columns in the Arrow IPC streaming format
+pub fn columns_to_ipc_bytes(columns: &[(String, Value)]) -> Result<Vec<u8>, String> {
+    let batch = columns_to_record_batch(columns)?;
+    let mut bytes = Vec::new();
+    let mut writer =
+        StreamWriter::try_new(&mut bytes, &batch.schema()).map_err(|e| e.to_string())?;
+    writer.write(&batch).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    drop(writer);
+    Ok(bytes)
+}
+
+/// Decode named columns from Arrow IPC data
+///
+/// Both the streaming and file formats are supported.
+/// The record batches are concatenated.
+pub fn ipc_bytes_to_columns(bytes: &[u8]) -> Result<Vec<(String, Value)>, String> {
+    let (schema, batches) = if bytes.starts_with(b"ARROW1") {
+        let reader = FileReader::try_new(Cursor::new(bytes), None).map_err(|e| e.to_string())?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>();
+        (schema, batches)
+    } else {
+        let reader = StreamReader::try_new(bytes, None).map_err(|e| e.to_string())?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>();
+        (schema, batches)
+    };
+    let batches = batches.map_err(|e| e.to_string())?;
+    let batch =
+        arrow_select::concat::concat_batches(&schema, &batches).map_err(|e| e.to_string())?;
+    record_batch_to_columns(&batch)
+}
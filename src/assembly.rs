@@ -13,11 +13,15 @@ use serde::*;
 
 use crate::{
     compile::{LocalName, Module},
-    is_ident_char, CodeSpan, FunctionId, InputSrc, IntoInputSrc, Node, SigNode, Signature, Span,
-    Uiua, UiuaResult, Value,
+    is_ident_char,
+    lex::SUBSCRIPT_DIGITS,
+    CodeSpan, FunctionId, InputSrc, IntoInputSrc, Node, SigNode, Signature, Span, Uiua, UiuaResult,
+    Value,
 };
 
 /// A compiled Uiua assembly
+///
+/// Cloning an assembly is cheap, as its code and constants are reference counted.
 #[derive(Clone)]
 pub struct Assembly {
     /// The top-level node
@@ -574,12 +578,296 @@ pub struct DocCommentArg {
     pub ty: Option<EcoString>,
 }
 
+impl DocCommentArg {
+    /// Parse the argument's type descriptor as a checkable [`TypeAnnotation`]
+    pub fn annotation(&self) -> Option<TypeAnnotation> {
+        self.ty.as_ref()?.parse().ok()
+    }
+}
+
+/// A type and shape constraint in a doc comment signature
+///
+/// The element type is one of `Any`, `Num`, `Complex`, `Char`, or `Box`.
+/// It may be followed by a subscripted rank, like `Num₂`,
+/// or by a shape in brackets with dimensions separated by `_`, like `Num[n_3]`.
+/// A named dimension must have the same length everywhere it appears in a signature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TypeAnnotation {
+    /// The element type
+    pub ty: AnnotatedType,
+    /// The shape constraint
+    pub shape: ShapeAnnotation,
+}
+
+/// An element type in a [`TypeAnnotation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnnotatedType {
+    /// Any type
+    Any,
+    /// Real numbers
+    Num,
+    /// Real or complex numbers
+    Complex,
+    /// Characters
+    Char,
+    /// Boxes
+    Box,
+}
+
+/// A shape constraint in a [`TypeAnnotation`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShapeAnnotation {
+    /// Any shape
+    Any,
+    /// A specific rank
+    Rank(usize),
+    /// Specific dimensions
+    Dims(EcoVec<AnnotatedDim>),
+}
+
+/// A dimension in a [`ShapeAnnotation`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnnotatedDim {
+    /// A fixed length
+    Fixed(usize),
+    /// A named length
+    Named(EcoString),
+}
+
+impl AnnotatedType {
+    fn matches(&self, val: &Value) -> bool {
+        match (self, val) {
+            (AnnotatedType::Any, _) => true,
+            (AnnotatedType::Num, Value::Num(_) | Value::Byte(_)) => true,
+            (AnnotatedType::Complex, Value::Num(_) | Value::Byte(_) | Value::Complex(_)) => true,
+            (AnnotatedType::Char, Value::Char(_)) => true,
+            (AnnotatedType::Box, Value::Box(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl TypeAnnotation {
+    /// Check that a value matches the annotation
+    ///
+    /// Named dimensions are bound in `dims` the first time they are seen.
+    /// On failure, returns a description of how the value differs.
+    pub(crate) fn check(
+        &self,
+        val: &Value,
+        dims: &mut Vec<(EcoString, usize)>,
+    ) -> Result<(), String> {
+        if !self.ty.matches(val) {
+            return Err(format!("it contains {}", val.type_name_plural()));
+        }
+        match &self.shape {
+            ShapeAnnotation::Any => {}
+            ShapeAnnotation::Rank(rank) => {
+                if val.rank() != *rank {
+                    return Err(format!("it has rank {}", val.rank()));
+                }
+            }
+            ShapeAnnotation::Dims(ann_dims) => {
+                let shape = val.shape();
+                if shape.len() != ann_dims.len() {
+                    return Err(format!("it has shape {shape}"));
+                }
+                for (dim, &len) in ann_dims.iter().zip(shape.iter()) {
+                    match dim {
+                        AnnotatedDim::Fixed(n) if *n != len => {
+                            return Err(format!("it has shape {shape}"))
+                        }
+                        AnnotatedDim::Fixed(_) => {}
+                        AnnotatedDim::Named(name) => match dims.iter().find(|(n, _)| n == name) {
+                            Some((_, n)) if *n != len => {
+                                return Err(format!("it has shape {shape} while {name} is {n}"))
+                            }
+                            Some(_) => {}
+                            None => dims.push((name.clone(), len)),
+                        },
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TypeAnnotation {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = (s.char_indices())
+            .find(|&(_, c)| c == '[' || SUBSCRIPT_DIGITS.contains(&c))
+            .map_or(s.len(), |(i, _)| i);
+        let (ty, shape) = s.split_at(split);
+        let ty = match ty {
+            "Any" => AnnotatedType::Any,
+            "Num" => AnnotatedType::Num,
+            "Complex" => AnnotatedType::Complex,
+            "Char" => AnnotatedType::Char,
+            "Box" => AnnotatedType::Box,
+            _ => return Err(()),
+        };
+        let shape = if shape.is_empty() {
+            ShapeAnnotation::Any
+        } else if let Some(dims) = shape.strip_prefix('[') {
+            let dims = dims.strip_suffix(']').ok_or(())?;
+            let mut ann_dims = EcoVec::new();
+            if !dims.is_empty() {
+                for dim in dims.split('_') {
+                    ann_dims.push(if let Ok(n) = dim.parse() {
+                        AnnotatedDim::Fixed(n)
+                    } else if !dim.is_empty() && dim.chars().all(is_ident_char) {
+                        AnnotatedDim::Named(dim.into())
+                    } else {
+                        return Err(());
+                    });
+                }
+            }
+            ShapeAnnotation::Dims(ann_dims)
+        } else {
+            let mut rank = 0;
+            for c in shape.chars() {
+                let digit = SUBSCRIPT_DIGITS.iter().position(|&d| d == c).ok_or(())?;
+                rank = rank * 10 + digit;
+            }
+            ShapeAnnotation::Rank(rank)
+        };
+        Ok(TypeAnnotation { ty, shape })
+    }
+}
+
+impl fmt::Display for TypeAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.ty)?;
+        match &self.shape {
+            ShapeAnnotation::Any => Ok(()),
+            ShapeAnnotation::Rank(rank) => {
+                for c in rank.to_string().chars() {
+                    write!(f, "{}", SUBSCRIPT_DIGITS[(c as u8 - b'0') as usize])?;
+                }
+                Ok(())
+            }
+            ShapeAnnotation::Dims(dims) => {
+                write!(f, "[")?;
+                for (i, dim) in dims.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "_")?;
+                    }
+                    match dim {
+                        AnnotatedDim::Fixed(n) => write!(f, "{n}")?,
+                        AnnotatedDim::Named(name) => write!(f, "{name}")?,
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A check of doc comment type annotations against the top values of the stack
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TypeCheck {
+    /// The possessive name of the checked function, like `F's`
+    pub name: EcoString,
+    /// Whether the checked values are outputs rather than arguments
+    pub outputs: bool,
+    /// The names and annotations of the values, starting from the top of the stack
+    pub values: EcoVec<(EcoString, Option<TypeAnnotation>)>,
+}
+
+impl TypeCheck {
+    pub(crate) fn check(&self, env: &Uiua) -> UiuaResult {
+        let height = env.require_height(self.values.len())?;
+        let vals: Vec<&Value> = env.stack()[height..].iter().rev().collect();
+        self.check_values(&vals)
+            .map_err(|message| env.error(message))
+    }
+    /// Check values, starting from the top of the stack, against the annotations
+    pub(crate) fn check_values(&self, vals: &[&Value]) -> Result<(), String> {
+        let mut dims = Vec::new();
+        for (i, ((name, ann), val)) in self.values.iter().zip(vals).enumerate() {
+            let Some(ann) = ann else {
+                continue;
+            };
+            if let Err(reason) = ann.check(val, &mut dims) {
+                let kind = if self.outputs { "output" } else { "argument" };
+                let which = if name.is_empty() {
+                    format!("{kind} {}", i + 1)
+                } else {
+                    format!("{kind} `{name}`")
+                };
+                return Err(format!(
+                    "{} {which} should be {ann}, but {reason}",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheck {
+    /// Get the annotated values in the form they appear in a comment signature
+    pub fn values_string(&self) -> String {
+        (self.values.iter())
+            .map(|(name, ann)| match ann {
+                Some(ann) if name.is_empty() => ann.to_string(),
+                Some(ann) => format!("{name}:{ann}"),
+                None if name.is_empty() => "_".into(),
+                None => name.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A choice between the overloads of a function based on their argument type annotations
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TypeDispatch {
+    /// The name of the overloaded function
+    pub name: EcoString,
+    /// The number of arguments
+    pub args: usize,
+    /// The argument checks of each overload
+    ///
+    /// An overload without annotated arguments matches anything
+    pub overloads: EcoVec<Option<Arc<TypeCheck>>>,
+}
+
+impl TypeDispatch {
+    /// Get the index of the first overload that matches the top values of the stack
+    pub(crate) fn dispatch(&self, env: &Uiua) -> UiuaResult<usize> {
+        let height = env.require_height(self.args)?;
+        let vals: Vec<&Value> = env.stack()[height..].iter().rev().collect();
+        self.dispatch_values(&vals)
+            .map_err(|message| env.error(message))
+    }
+    /// Get the index of the first overload that matches values, starting from the top of the stack
+    pub(crate) fn dispatch_values(&self, vals: &[&Value]) -> Result<usize, String> {
+        let mut message = format!(
+            "No overload of {} matches its arguments. The candidates are:",
+            self.name
+        );
+        for (i, check) in self.overloads.iter().enumerate() {
+            let Some(check) = check else {
+                return Ok(i);
+            };
+            match check.check_values(vals) {
+                Ok(()) => return Ok(i),
+                Err(reason) => {
+                    message.push_str(&format!("\n  ? {}\n    {reason}", check.values_string()))
+                }
+            }
+        }
+        Err(message)
+    }
+}
+
 impl FromStr for DocCommentSig {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.trim_end().ends_with('?') && !s.trim_end().ends_with(" ?")
-            || !(s.chars()).all(|c| c.is_whitespace() || "?$:".contains(c) || is_ident_char(c))
-        {
+        if s.trim_end().ends_with('?') && !s.trim_end().ends_with(" ?") || !is_sig_text(s) {
             return Err(());
         }
         // Split into args and outputs
@@ -639,6 +927,31 @@ impl FromStr for DocCommentSig {
     }
 }
 
+/// Whether some text only contains characters allowed in a doc comment signature
+///
+/// Shapes in type annotations may also contain digits and `_` between brackets.
+fn is_sig_text(s: &str) -> bool {
+    let mut in_shape = false;
+    for c in s.chars() {
+        let valid = match c {
+            '[' if !in_shape => {
+                in_shape = true;
+                true
+            }
+            ']' if in_shape => {
+                in_shape = false;
+                true
+            }
+            c if in_shape => c == '_' || c.is_ascii_digit() || is_ident_char(c),
+            c => c.is_whitespace() || "?$:".contains(c) || is_ident_char(c),
+        };
+        if !valid {
+            return false;
+        }
+    }
+    !in_shape
+}
+
 impl From<String> for DocComment {
     fn from(text: String) -> Self {
         Self::from(text.as_str())
@@ -651,8 +964,7 @@ impl From<&str> for DocComment {
         let sig_line = text.lines().position(|line| {
             line.chars().filter(|&c| "$?".contains(c)).count() == 1
                 && !line.trim().ends_with('?')
-                && (line.chars())
-                    .all(|c| c.is_whitespace() || "?$:".contains(c) || is_ident_char(c))
+                && is_sig_text(line)
         });
         let raw_text = if let Some(i) = sig_line {
             sig = text.lines().nth(i).unwrap().parse().ok();
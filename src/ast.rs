@@ -9,7 +9,7 @@ use crate::{
     function::Signature,
     lex::{CodeSpan, Sp},
     parse::ident_modifier_args,
-    BindingCounts, Ident, Primitive, SemanticComment, SUBSCRIPT_DIGITS,
+    BindingCounts, Complex, Ident, Primitive, SemanticComment, SUBSCRIPT_DIGITS,
 };
 
 /// A top-level item
@@ -40,6 +40,8 @@ pub struct Binding {
     pub public: bool,
     /// Whether the binding is a code macro
     pub code_macro: bool,
+    /// Whether the code macro takes its operands as tokens
+    pub token_macro: bool,
     /// The signature
     pub signature: Option<Sp<Signature>>,
     /// The code
@@ -266,11 +268,22 @@ pub struct InlineMacro {
     pub ident: Sp<Ident>,
 }
 
+/// A local binding inside a function
+#[derive(Clone)]
+pub struct LocalBinding {
+    /// The name of the local
+    pub name: Sp<Ident>,
+    /// The span of the ←
+    pub arrow_span: CodeSpan,
+    /// The words that produce the local's value
+    pub words: Vec<Sp<Word>>,
+}
+
 /// A word
 #[derive(Clone)]
 #[allow(missing_docs)]
 pub enum Word {
-    Number(Result<f64, String>),
+    Number(Result<Complex, String>),
     Char(String),
     String(String),
     MultilineString(Vec<Sp<String>>),
@@ -300,6 +313,7 @@ pub enum Word {
     },
     Subscripted(Box<Subscripted>),
     InlineMacro(InlineMacro),
+    LocalBinding(LocalBinding),
 }
 
 impl PartialEq for Word {
@@ -342,6 +356,10 @@ impl PartialEq for Word {
             }
             (Self::Placeholder(_), Self::Placeholder(_)) => false,
             (Self::Comment(a), Self::Comment(b)) => a == b,
+            (Self::LocalBinding(a), Self::LocalBinding(b)) => {
+                a.name.value == b.name.value
+                    && (a.words.iter().map(|w| &w.value)).eq(b.words.iter().map(|w| &w.value))
+            }
             _ => discriminant(self) == discriminant(other),
         }
     }
@@ -424,6 +442,9 @@ impl fmt::Debug for Word {
             Word::InlineMacro(InlineMacro { ident, func, .. }) => {
                 write!(f, "func_macro({:?}{}))", func.value, ident.value)
             }
+            Word::LocalBinding(binding) => {
+                write!(f, "local({} ← {:?})", binding.name.value, binding.words)
+            }
         }
     }
 }
@@ -0,0 +1,136 @@
+//! A builder for constructing nested values from Rust
+
+use ecow::EcoVec;
+
+use crate::{Array, Boxed, Value};
+
+/// A builder for constructing nested values from Rust
+///
+/// [`ValueBuilder::list`] builds a list of boxes, so its items can have any shape and type.
+/// [`ValueBuilder::array`] builds an array whose rows must all have the same shape and type.
+///
+/// Rows are validated as they are pushed. The first invalid row is reported by [`ValueBuilder::build`].
+/// ```rust
+/// use uiua::*;
+///
+/// let value = ValueBuilder::list()
+///     .push_str("Alice")
+///     .push_nums([1.0, 2.0, 3.0])
+///     .push_builder(ValueBuilder::array().push_nums([1.0, 2.0]).push_nums([3.0, 4.0]))
+///     .build()
+///     .unwrap();
+/// assert_eq!(value.shape(), &[3]);
+/// assert_eq!(value.format(), r#"["Alice" [1 2 3] [[1 2] [3 4]]]"#);
+///
+/// let res = ValueBuilder::array().push_nums([1.0, 2.0]).push_nums([3.0]).build();
+/// assert!(res.is_err());
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ValueBuilder {
+    boxed: bool,
+    rows: Vec<Value>,
+    error: Option<String>,
+}
+
+impl ValueBuilder {
+    /// Start building a list of boxes
+    pub fn list() -> Self {
+        ValueBuilder {
+            boxed: true,
+            rows: Vec::new(),
+            error: None,
+        }
+    }
+    /// Start building an array whose rows all have the same shape and type
+    pub fn array() -> Self {
+        ValueBuilder {
+            boxed: false,
+            ..Self::list()
+        }
+    }
+    /// Push a row
+    pub fn push(mut self, row: impl Into<Value>) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let row = row.into();
+        if !self.boxed {
+            if let Some(first) = self.rows.first() {
+                if let Err(e) = check_row(first, &row) {
+                    self.error = Some(format!("Row {}: {e}", self.rows.len()));
+                    return self;
+                }
+            }
+        }
+        self.rows.push(row);
+        self
+    }
+    /// Push a string row
+    pub fn push_str(self, s: &str) -> Self {
+        self.push(s)
+    }
+    /// Push a number row
+    pub fn push_num(self, n: f64) -> Self {
+        self.push(n)
+    }
+    /// Push a list of numbers as a row
+    pub fn push_nums(self, nums: impl IntoIterator<Item = f64>) -> Self {
+        self.push(nums.into_iter().collect::<EcoVec<_>>())
+    }
+    /// Build a nested value and push it as a row
+    ///
+    /// If the nested builder has an invalid row, this builder will report it.
+    pub fn push_builder(mut self, builder: ValueBuilder) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        match builder.build() {
+            Ok(row) => self.push(row),
+            Err(e) => {
+                self.error = Some(format!("Row {}: {e}", self.rows.len()));
+                self
+            }
+        }
+    }
+    /// Build the value
+    ///
+    /// Returns an error if any row was invalid
+    pub fn build(self) -> Result<Value, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(if self.boxed {
+            let boxes: EcoVec<Boxed> = self.rows.into_iter().map(Boxed).collect();
+            Array::from(boxes).into()
+        } else {
+            Value::from_row_values_infallible(self.rows)
+        })
+    }
+}
+
+fn check_row(first: &Value, row: &Value) -> Result<(), String> {
+    let same_type = matches!(
+        (first, row),
+        (
+            Value::Num(_) | Value::Byte(_) | Value::Complex(_),
+            Value::Num(_) | Value::Byte(_) | Value::Complex(_)
+        ) | (Value::Char(_), Value::Char(_))
+            | (Value::Box(_), Value::Box(_))
+    );
+    if !same_type {
+        return Err(format!(
+            "Cannot add {} row to an array of {}",
+            row.type_name(),
+            first.type_name_plural()
+        ));
+    }
+    if row.shape() != first.shape() {
+        return Err(format!(
+            "Row has shape {}, but previous rows have shape {}",
+            row.shape(),
+            first.shape()
+        ));
+    }
+    Ok(())
+}
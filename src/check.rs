@@ -429,6 +429,32 @@ impl VirtualEnv {
                     let [f] = get_args_nodes(args)?;
                     self.node(&f.node)?;
                 }
+                Catch => {
+                    let [f] = get_args(args)?;
+                    self.handle_args_outputs(f.args, 2);
+                }
+                Resume => {
+                    let [f] = get_args(args)?;
+                    self.handle_args_outputs(f.args + 2, f.outputs + 1);
+                }
+                Stream => {
+                    let [generator, _consumer] = get_args(args)?;
+                    self.handle_args_outputs(generator.args + 1, 1);
+                }
+                Requires | Ensures => {
+                    let [_cond, f] = get_args(args)?;
+                    self.handle_sig(f);
+                }
+                SortBy | Newton | NDerivative | NIntegral => self.handle_args_outputs(1, 1),
+                Bisect => self.handle_args_outputs(2, 1),
+                GroupBy => {
+                    let [_key, f] = get_args(args)?;
+                    self.handle_args_outputs(1, f.outputs);
+                }
+                Handle => {
+                    let [_handler, body] = get_args(args)?;
+                    self.handle_args_outputs(body.args + 1, body.outputs);
+                }
                 Table | Tuples => {
                     let [sig] = get_args(args)?;
                     self.handle_sig(sig);
@@ -448,6 +474,15 @@ impl VirtualEnv {
                     let [sig] = get_args(args)?;
                     self.handle_args_outputs(sig.args, 1);
                 }
+                Update => {
+                    let [sig] = get_args(args)?;
+                    self.handle_args_outputs(sig.args.max(1), 1);
+                }
+                Scope => {
+                    let args_count = args.iter().map(|sn| sn.sig.args).max().unwrap_or(0);
+                    let outputs = args.iter().map(|sn| sn.sig.outputs).sum();
+                    self.handle_args_outputs(args_count, outputs);
+                }
                 Repeat => {
                     let [f] = get_args_nodes(args)?;
                     let n = self.pop();
@@ -571,6 +606,10 @@ impl VirtualEnv {
                     let [f] = get_args(args)?;
                     self.handle_sig(f);
                 }
+                Sys(SysOp::ReadChunks) => {
+                    let [f] = get_args(args)?;
+                    self.handle_args_outputs(f.args + 1, f.outputs);
+                }
                 Sys(SysOp::AudioStream) => {
                     let [f] = get_args(args)?;
                     self.handle_args_outputs(f.args.saturating_sub(1), f.outputs.saturating_sub(1));
@@ -672,6 +711,14 @@ impl VirtualEnv {
             },
             Node::SetOutputComment { .. } => {}
             Node::ValidateType { .. } => self.handle_args_outputs(1, 1),
+            Node::CheckTypes(check, _) => {
+                let n = check.values.len();
+                self.handle_args_outputs(n, n)
+            }
+            Node::DispatchTypes(dispatch, _) => {
+                let n = dispatch.args;
+                self.handle_args_outputs(n, n + 1)
+            }
             Node::PushUnder(n, _) => {
                 for _ in 0..*n {
                     self.under.push(self.stack.pop());
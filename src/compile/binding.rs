@@ -1,12 +1,13 @@
 //! Compiler code for bindings
 
-use crate::BindingMeta;
+use crate::{BindingMeta, TypeDispatch};
 
 use super::*;
 
 impl Compiler {
     pub(super) fn binding(&mut self, binding: Binding, prelude: BindingPrelude) -> UiuaResult {
         let public = binding.public;
+        let overload = prelude.overload;
 
         let last_word = binding.words.iter().last();
 
@@ -115,6 +116,12 @@ impl Compiler {
             if max_placeholder.is_some() {
                 return Err(self.error(span.clone(), "Code macros may not contain placeholders"));
             }
+            if binding.token_macro {
+                self.experimental_error(span, || {
+                    "Token macros are experimental. To use them, add \
+                    `# Experimental!` to the top of the file."
+                });
+            }
             // Code macro
             if ident_margs == 0 {
                 self.add_error(
@@ -130,7 +137,7 @@ impl Compiler {
                 Ok(s) => {
                     if let Some(declared) = binding.signature {
                         if s != declared.value {
-                            self.add_error(
+                            self.add_signature_error(
                                 span.clone(),
                                 format!(
                                     "Code macro signature mismatch: \
@@ -146,7 +153,7 @@ impl Compiler {
                     if let Some(sig) = binding.signature {
                         sig.value
                     } else {
-                        self.add_error(
+                        self.add_signature_error(
                             span.clone(),
                             format!("Cannot infer code macro signature: {e}"),
                         );
@@ -160,7 +167,7 @@ impl Compiler {
                 Signature::new(0, 0),
             ];
             if !ALLOWED_SIGS.contains(&sig) {
-                self.add_error(
+                self.add_signature_error(
                     span.clone(),
                     format!(
                         "Code macros must have a signature of {} or {}, \
@@ -181,6 +188,7 @@ impl Compiler {
             let mac = CodeMacro {
                 root: SigNode::new(sig, node),
                 names: self.scope.names.clone(),
+                tokens: binding.token_macro,
             };
             self.code_macros.insert(local.index, mac);
             return Ok(());
@@ -238,14 +246,20 @@ impl Compiler {
             );
             let words = binding.words.clone();
             let mut recursive = false;
-            self.analyze_macro_body(&name, &words, &mut recursive);
+            let mut local_names = HashSet::new();
+            recurse_words(&words, &mut |word| {
+                if let Word::LocalBinding(binding) = &word.value {
+                    local_names.insert(binding.name.value.clone());
+                }
+            });
+            self.analyze_macro_body(&name, &words, &local_names, &mut recursive);
             if recursive {
                 self.experimental_error(span, || {
                     "Recursive index macros are experimental. \
                     Add `# Experimental!` to the top of the file to use them."
                 });
                 if binding.signature.is_none() {
-                    self.add_error(
+                    self.add_signature_error(
                         span.clone(),
                         "Recursive index macro must have a \
                         signature declared after the ←",
@@ -356,10 +370,12 @@ impl Compiler {
         if external {
             if node.is_empty() {
                 let Some(sig) = &binding.signature else {
-                    return Err(self.error(
-                        span.clone(),
-                        "Empty external functions must have a signature declared",
-                    ));
+                    return Err(self
+                        .error(
+                            span.clone(),
+                            "Empty external functions must have a signature declared",
+                        )
+                        .signature());
                 };
                 let sig = sig.value;
                 let span = self.add_span(span.clone());
@@ -404,10 +420,19 @@ impl Compiler {
 
                 if sig == (0, 1) && !self_referenced && !is_func && !is_obverse && !is_method {
                     // Binding is a constant
+                    if overload {
+                        self.add_error(span.clone(), "Only functions can be overloaded");
+                    }
                     let val = if let [Node::Push(v)] = node.as_slice() {
                         Some(v.clone())
                     } else if node.is_pure(Purity::Pure, &self.asm) {
-                        match self.comptime_node(&node) {
+                        // The language server recompiles on every edit, so it only evaluates small constants
+                        let limits = if self.pre_eval_mode == PreEvalMode::Lsp {
+                            PreEvalLimits::INLINE
+                        } else {
+                            PreEvalLimits::BINDING
+                        };
+                        match self.comptime_node(&node, limits) {
                             Ok(Some(vals)) => vals.into_iter().next(),
                             Ok(None) => None,
                             Err(e) => {
@@ -447,7 +472,7 @@ impl Compiler {
                         }
                         Err(sp) => {
                             let sp = sp.clone();
-                            self.add_error(
+                            self.add_signature_error(
                                 sp.span,
                                 format!(
                                     "This line's signature is undefined: {}. \
@@ -480,6 +505,10 @@ impl Compiler {
                             span: spandex,
                         });
                     }
+                } else if overload {
+                    // Binding is an overload of an earlier function
+                    let func = make_fn(node, sig, self);
+                    self.bind_overload(name, local, func, spandex, meta)?;
                 } else {
                     // Binding is a normal function
                     let func = make_fn(node, sig, self);
@@ -496,13 +525,89 @@ impl Compiler {
                     },
                 );
             }
-            Err(e) => self.add_error(
+            Err(e) => self.add_signature_error(
                 binding.name.span.clone(),
                 format!("Cannot infer function signature: {e}"),
             ),
         }
         Ok(())
     }
+    /// Bind a function that dispatches between it and the overloads of an earlier function
+    fn bind_overload(
+        &mut self,
+        name: Ident,
+        local: LocalName,
+        func: Function,
+        spandex: usize,
+        meta: BindingMeta,
+    ) -> UiuaResult {
+        let span = self.get_span(spandex).clone().code().unwrap();
+        self.experimental_error(&span, || {
+            "Overloads are experimental. Add `# Experimental!` to the top of the file to use them."
+        });
+        let prev = (self.scope.names.get(&name)).and_then(|prev| {
+            match &self.asm.bindings[prev.index].kind {
+                BindingKind::Func(f) => Some((prev.index, f.clone())),
+                _ => None,
+            }
+        });
+        let Some((prev_index, prev_func)) = prev else {
+            self.add_error(
+                span,
+                format!("`{name}` is marked as an overload, but there is no earlier function to overload"),
+            );
+            return self.compile_bind_function(name, local, func, spandex, meta);
+        };
+        let mut overloads =
+            (self.overloads.get(&prev_index).cloned()).unwrap_or_else(|| eco_vec![prev_func]);
+        if overloads[0].sig != func.sig {
+            self.add_error(
+                span,
+                format!(
+                    "Overloads of `{name}` must have the same signature, \
+                    but this one has signature {} while the first has signature {}",
+                    func.sig, overloads[0].sig
+                ),
+            );
+            return self.compile_bind_function(name, local, func, spandex, meta);
+        }
+        overloads.push(func.clone());
+        // Find the argument checks added by each overload's comment signature
+        let checks = (overloads.iter())
+            .map(|f| {
+                let mut node = &self.asm[f];
+                loop {
+                    match node.as_slice().first()? {
+                        Node::CheckTypes(check, _) if !check.outputs => break Some(check.clone()),
+                        Node::TrackCaller(inner) | Node::NoInline(inner) => node = inner,
+                        _ => break None,
+                    }
+                }
+            })
+            .collect();
+        let dispatch = TypeDispatch {
+            name: name.clone(),
+            args: func.sig.args,
+            overloads: checks,
+        };
+        let branches = (overloads.iter())
+            .map(|f| SigNode::new(f.sig, Node::Call(f.clone(), spandex)))
+            .collect();
+        let node = Node::from_iter([
+            Node::DispatchTypes(dispatch.into(), spandex),
+            Node::Switch {
+                branches,
+                sig: func.sig,
+                under_cond: false,
+                span: spandex,
+            },
+        ]);
+        let dispatcher = self
+            .asm
+            .add_function(FunctionId::Named(name.clone()), func.sig, node);
+        self.overloads.insert(local.index, overloads);
+        self.compile_bind_function(name, local, dispatcher, spandex, meta)
+    }
     pub(super) fn module(&mut self, m: Sp<ScopedModule>, prelude: BindingPrelude) -> UiuaResult {
         let m = m.value;
         let (scope_kind, name_and_local) = match m.kind {
@@ -623,22 +728,35 @@ impl Compiler {
         }
         Ok(())
     }
-    fn analyze_macro_body(&mut self, macro_name: &str, words: &[Sp<Word>], recursive: &mut bool) {
+    fn analyze_macro_body(
+        &mut self,
+        macro_name: &str,
+        words: &[Sp<Word>],
+        local_names: &HashSet<Ident>,
+        recursive: &mut bool,
+    ) {
         for word in words {
             let mut path_locals = None;
             let mut name_local = None;
             match &word.value {
-                Word::Strand(items) => self.analyze_macro_body(macro_name, items, recursive),
+                Word::Strand(items) => {
+                    self.analyze_macro_body(macro_name, items, local_names, recursive)
+                }
                 Word::Array(arr) => arr.lines.iter().for_each(|line| {
-                    self.analyze_macro_body(macro_name, line, recursive);
+                    self.analyze_macro_body(macro_name, line, local_names, recursive);
                 }),
                 Word::Func(func) => func.lines.iter().for_each(|line| {
-                    self.analyze_macro_body(macro_name, line, recursive);
+                    self.analyze_macro_body(macro_name, line, local_names, recursive);
                 }),
                 Word::Pack(pack) => pack.branches.iter().for_each(|branch| {
-                    (branch.value.lines.iter())
-                        .for_each(|line| self.analyze_macro_body(macro_name, line, recursive))
+                    (branch.value.lines.iter()).for_each(|line| {
+                        self.analyze_macro_body(macro_name, line, local_names, recursive)
+                    })
                 }),
+                Word::LocalBinding(binding) => {
+                    self.analyze_macro_body(macro_name, &binding.words, local_names, recursive)
+                }
+                Word::Ref(r) if r.path.is_empty() && local_names.contains(&r.name.value) => {}
                 Word::Ref(r) => match self.ref_local(r) {
                     Ok(Some((pl, l))) => {
                         path_locals = Some((&r.path, pl));
@@ -673,16 +791,21 @@ impl Compiler {
                             let recursive = &mut *recursive;
                             if let Err(e) = self.in_scope(ScopeKind::AllInModule, move |comp| {
                                 comp.scope.names.extend(names);
-                                comp.analyze_macro_body(macro_name, &m.operands, recursive);
+                                comp.analyze_macro_body(
+                                    macro_name,
+                                    &m.operands,
+                                    local_names,
+                                    recursive,
+                                );
                                 Ok(())
                             }) {
                                 self.errors.push(e);
                             }
                         } else {
-                            self.analyze_macro_body(macro_name, &m.operands, recursive)
+                            self.analyze_macro_body(macro_name, &m.operands, local_names, recursive)
                         }
                     } else {
-                        self.analyze_macro_body(macro_name, &m.operands, recursive)
+                        self.analyze_macro_body(macro_name, &m.operands, local_names, recursive)
                     }
                 }
                 _ => {}
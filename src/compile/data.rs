@@ -94,6 +94,9 @@ impl Compiler {
             comment: Option<String>,
             validator_inv: Option<Node>,
             init: Option<SigNode>,
+            /// The default value of an optional argument
+            default: Option<SigNode>,
+            validator: Option<Node>,
         }
         let mut fields = Vec::new();
         // Collect fields
@@ -119,7 +122,7 @@ impl Compiler {
                 let validator_and_inv = if let Some(validator) = data_field.validator {
                     let mut validator = self.words_sig(validator.words)?;
                     if validator.sig.args != 1 {
-                        self.add_error(
+                        self.add_signature_error(
                             data_field.name.span.clone(),
                             format!(
                                 "Field validator must have 1 \
@@ -129,7 +132,7 @@ impl Compiler {
                         );
                     }
                     if validator.sig.outputs > 1 {
-                        self.add_error(
+                        self.add_signature_error(
                             data_field.name.span.clone(),
                             format!(
                                 "Field validator must have 0 or 1 \
@@ -205,6 +208,7 @@ impl Compiler {
                     None
                 };
                 // Compile initializer
+                let mut default = None;
                 if (data_field.init.as_ref())
                     .is_some_and(|default| !default.words.iter().any(|w| w.value.is_code()))
                 {
@@ -226,11 +230,14 @@ impl Compiler {
                     }
                     // Compile words
                     let mut sn = self.words_sig(init.words)?;
+                    if sn.sig == Signature::new(0, 1) {
+                        default = Some(sn.clone());
+                    }
                     if let Some((va_node, _)) = &validator_and_inv {
                         sn.node.push(va_node.clone());
                     }
                     if sn.sig.outputs != 1 {
-                        self.add_error(
+                        self.add_signature_error(
                             data_field.name.span.clone(),
                             format!(
                                 "Field initializer must have \
@@ -257,8 +264,10 @@ impl Compiler {
                     global_index: 0,
                     comment,
                     span,
+                    validator: validator_and_inv.as_ref().map(|(va, _)| va.clone()),
                     validator_inv: validator_and_inv.map(|(_, inv)| inv),
                     init,
+                    default,
                 });
             }
         }
@@ -340,16 +349,43 @@ impl Compiler {
             .iter()
             .map(|f| f.init.as_ref().map(|sn| sn.sig.args).unwrap_or(1))
             .sum();
-        let mut node = if has_fields {
+        // Optional arguments are read from a map bound to `options`
+        let make_fields = |comp: &mut Self, options: Option<usize>| {
+            if !has_fields {
+                return Node::empty();
+            }
             let mut inner = Node::default();
             for field in fields.iter().rev() {
-                let mut arg = if let Some(sn) = &field.init {
-                    sn.clone()
-                } else {
-                    self.code_meta
-                        .global_references
-                        .insert(field.name_span.clone(), field.global_index);
-                    SigNode::new(Signature::new(1, 1), Node::empty())
+                let mut arg = match (&field.init, &field.default, options) {
+                    (Some(_), Some(default), Some(def)) => {
+                        let mut node = Node::from_iter([
+                            Node::GetLocal { def, span },
+                            Node::new_push(field.name.as_str()),
+                            Node::Mod(
+                                Primitive::Fill,
+                                eco_vec![
+                                    default.clone(),
+                                    SigNode::new(
+                                        Signature::new(2, 1),
+                                        Node::Prim(Primitive::Get, span)
+                                    )
+                                ],
+                                span,
+                            ),
+                            Node::ImplPrim(ImplPrimitive::UnBox, span),
+                        ]);
+                        if let Some(validator) = &field.validator {
+                            node.push(validator.clone());
+                        }
+                        SigNode::new(Signature::new(0, 1), node)
+                    }
+                    (Some(sn), ..) => sn.clone(),
+                    (None, ..) => {
+                        comp.code_meta
+                            .global_references
+                            .insert(field.name_span.clone(), field.global_index);
+                        SigNode::new(Signature::new(1, 1), Node::empty())
+                    }
                 };
                 if boxed {
                     arg.node.push(Node::Label(field.name.clone(), span));
@@ -379,9 +415,8 @@ impl Compiler {
                 prim: None,
                 span,
             }
-        } else {
-            Node::empty()
         };
+        let mut node = make_fields(self, None);
         // Handle variant
         if data.variant {
             node.push(Node::new_push(variant_index));
@@ -484,6 +519,11 @@ impl Compiler {
         self.scope.data_def = Some(scope_data_def.clone());
 
         let mut function_stuff = None;
+        let mut with_stuff = None;
+        let options: Vec<&str> = (fields.iter())
+            .filter(|field| field.default.is_some())
+            .map(|field| field.name.as_str())
+            .collect();
         // Call function
         if let Some(words) = data.func {
             self.in_method(&scope_data_def, |comp| {
@@ -494,22 +534,25 @@ impl Compiler {
                 }
                 let inner = comp.words_sig(words)?;
                 let span = comp.add_span(word_span.clone());
-                let mut construct = Node::Call(constructor_func.clone(), span);
-                for _ in 0..inner.sig.args {
-                    construct = Node::Mod(
-                        Primitive::Dip,
-                        eco_vec![construct.sig_node().unwrap()],
-                        span,
-                    );
-                }
-                let node = Node::from_iter([
-                    construct,
-                    Node::WithLocal {
-                        def: def_index,
-                        inner: inner.into(),
-                        span,
-                    },
-                ]);
+                let call_with = |constructor: &Function| {
+                    let mut construct = Node::Call(constructor.clone(), span);
+                    for _ in 0..inner.sig.args {
+                        construct = Node::Mod(
+                            Primitive::Dip,
+                            eco_vec![construct.sig_node().unwrap()],
+                            span,
+                        );
+                    }
+                    Node::from_iter([
+                        construct,
+                        Node::WithLocal {
+                            def: def_index,
+                            inner: inner.clone().into(),
+                            span,
+                        },
+                    ])
+                };
+                let node = call_with(&constructor_func);
                 let sig = comp.sig_of(&node, &word_span)?;
                 let local = LocalName {
                     index: comp.next_global,
@@ -520,6 +563,42 @@ impl Compiler {
                     comp.asm
                         .add_function(FunctionId::Named(constructor_name.clone()), sig, node);
                 function_stuff = Some((local, func, span));
+                // Call function with optional arguments
+                if !options.is_empty() && !data.variant {
+                    let options_def = comp.asm.bind_def(DefInfo {
+                        name: "options".into(),
+                    });
+                    let node = make_fields(comp, Some(options_def));
+                    let constructor = comp.asm.add_function(
+                        FunctionId::Named(constructor_name.clone()),
+                        Signature::new(constructor_args, 1),
+                        node,
+                    );
+                    let mut node = Node::from_iter([
+                        Node::GetLocal {
+                            def: options_def,
+                            span,
+                        },
+                        Node::new_push(Array::from_iter(options.iter().copied())),
+                        Node::ImplPrim(ImplPrimitive::ValidateOptions, span),
+                    ]);
+                    node.push(call_with(&constructor));
+                    let node = Node::WithLocal {
+                        def: options_def,
+                        inner: SigNode::new(sig, node).into(),
+                        span,
+                    };
+                    let with_sig = Signature::new(sig.args + 1, sig.outputs);
+                    let local = LocalName {
+                        index: comp.next_global,
+                        public: true,
+                    };
+                    comp.next_global += 1;
+                    let func =
+                        comp.asm
+                            .add_function(FunctionId::Named("With".into()), with_sig, node);
+                    with_stuff = Some((local, func, span));
+                }
                 Ok(())
             })?;
         }
@@ -528,6 +607,17 @@ impl Compiler {
         if let Some((local, func, span)) = function_stuff {
             self.compile_bind_function("Call".into(), local, func, span, BindingMeta::default())?;
         }
+        if let Some((local, func, span)) = with_stuff {
+            let comment = format!(
+                "Call `{def_name}` with a map of optional arguments\nOptions: {}",
+                options.join(", ")
+            );
+            let meta = BindingMeta {
+                comment: Some(DocComment::from(comment.as_str())),
+                ..Default::default()
+            };
+            self.compile_bind_function("With".into(), local, func, span, meta)?;
+        }
 
         // Bind the constructor
         let meta = BindingMeta {
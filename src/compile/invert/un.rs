@@ -258,6 +258,12 @@ pub static ANTI_PATTERNS: &[&dyn InvertPattern] = &[
     &(Pick, AntiPick),
     &(Base, AntiBase),
     &(AntiBase, Base),
+    &(Dsv, UnDsv),
+    &(UnDsv, Dsv),
+    &(Encode, UnEncode),
+    &(UnEncode, Encode),
+    &(Compress, UnCompress),
+    &(UnCompress, Compress),
     &MatrixDivPat,
     &NoUnder(AntiCouplePat),
     &AntiFillPat,
@@ -990,6 +996,7 @@ impl InvertPattern for Trivial {
                 Ok((input, Label(label.clone(), *span)))
             }
             [node @ SetOutputComment { .. }, input @ ..] => Ok((input, node.clone())),
+            [CheckTypes(..), input @ ..] => Ok((input, Node::empty())),
             [Call(f, _), input @ ..] => Ok((input, asm[f].un_inverse(asm).map_err(|e| e.func(f))?)),
             [ImplPrim(ValidateNonBoxedVariant, _), input @ ..] => Ok((input, Node::empty())),
             _ => generic(),
@@ -1011,6 +1018,7 @@ impl InvertPattern for AntiTrivial {
                 Ok((input, TrackCaller(inner.anti_inverse(asm)?.into())))
             }
             [node @ SetOutputComment { .. }, input @ ..] => Ok((input, node.clone())),
+            [CheckTypes(..), input @ ..] => Ok((input, Node::empty())),
             [Call(f, _), input @ ..] => {
                 let mut node = asm[f].clone();
                 node.extend(input.iter().cloned());
@@ -1032,6 +1040,7 @@ inverse!(PrimPat, input, _, Prim(prim, span), {
         Sin => ImplPrim(Asin, span),
         Atan => ImplPrim(UnAtan, span),
         Complex => ImplPrim(UnComplex, span),
+        Conjugate => Prim(Conjugate, span),
         Reverse => Prim(Reverse, span),
         Transpose => ImplPrim(TransposeN(-1), span),
         Bits => ImplPrim(UnBits, span),
@@ -1041,6 +1050,8 @@ inverse!(PrimPat, input, _, Prim(prim, span), {
         Utf8 => ImplPrim(UnUtf8, span),
         Graphemes => ImplPrim(UnGraphemes, span),
         Parse => ImplPrim(UnParse, span),
+        Error => ImplPrim(UnError, span),
+        Tokenize => ImplPrim(UnTokenize, span),
         Fix => ImplPrim(UnFix, span),
         Shape => ImplPrim(UnShape, span),
         Map => ImplPrim(UnMap, span),
@@ -1051,8 +1062,12 @@ inverse!(PrimPat, input, _, Prim(prim, span), {
         ImageEncode => ImplPrim(ImageDecode, span),
         Sys(SysOp::Clip) => ImplPrim(UnClip, span),
         Sys(SysOp::RawMode) => ImplPrim(UnRawMode, span),
+        Sys(SysOp::ChangeDirectory) => ImplPrim(UnChangeDirectory, span),
         Json => ImplPrim(UnJson, span),
         Binary => ImplPrim(UnBinary, span),
+        Npy => ImplPrim(UnNpy, span),
+        Npz => ImplPrim(UnNpz, span),
+        Arrow => ImplPrim(UnArrow, span),
         Csv => ImplPrim(UnCsv, span),
         Xlsx => ImplPrim(UnXlsx, span),
         Fft => ImplPrim(UnFft, span),
@@ -1073,6 +1088,10 @@ inverse!(ImplPrimPat, input, _, ImplPrim(prim, span), {
     let inv = match prim {
         UnPop => Prim(Pop, span),
         Asin => Prim(Sin, span),
+        SinSub(n) => ImplPrim(AsinSub(n), span),
+        FftSub(n) => ImplPrim(UnFftSub(n), span),
+        UnFftSub(n) => ImplPrim(FftSub(n), span),
+        AsinSub(n) => ImplPrim(SinSub(n), span),
         Cos => ImplPrim(Acos, span),
         Acos => ImplPrim(Cos, span),
         TransposeN(n) => ImplPrim(TransposeN(-n), span),
@@ -1085,6 +1104,8 @@ inverse!(ImplPrimPat, input, _, ImplPrim(prim, span), {
         UnComplex => Prim(Complex, span),
         UnCouple => Prim(Couple, span),
         UnParse => Prim(Parse, span),
+        UnError => Prim(Error, span),
+        UnTokenize => Prim(Tokenize, span),
         UnFix => Prim(Fix, span),
         UnShape => Prim(Shape, span),
         UnMap => Prim(Map, span),
@@ -1094,6 +1115,9 @@ inverse!(ImplPrimPat, input, _, ImplPrim(prim, span), {
         UnBox => Prim(Box, span),
         UnJson => Prim(Json, span),
         UnBinary => Prim(Binary, span),
+        UnNpy => Prim(Npy, span),
+        UnNpz => Prim(Npz, span),
+        UnArrow => Prim(Arrow, span),
         UnCsv => Prim(Csv, span),
         UnXlsx => Prim(Xlsx, span),
         UnFft => Prim(Fft, span),
@@ -1103,6 +1127,7 @@ inverse!(ImplPrimPat, input, _, ImplPrim(prim, span), {
         UnDatetime => Prim(DateTime, span),
         UnRawMode => Prim(Sys(SysOp::RawMode), span),
         UnClip => Prim(Sys(SysOp::Clip), span),
+        UnChangeDirectory => Prim(Sys(SysOp::ChangeDirectory), span),
         StackN { n, inverse } => ImplPrim(
             StackN {
                 n,
@@ -246,18 +246,34 @@ static UNDER_PATTERNS: &[&dyn UnderPattern] = &[
     &MaybeVal(Store1Copy(Sys(SysOp::TcpAccept), Sys(SysOp::Close))),
     &MaybeVal(Store1Copy(Sys(SysOp::TcpListen), Sys(SysOp::Close))),
     &MaybeVal(Store1Copy(Sys(SysOp::TlsListen), Sys(SysOp::Close))),
+    &MaybeVal(Store1Copy(Sys(SysOp::UdpBind), Sys(SysOp::Close))),
+    &MaybeVal(Store1Copy(Sys(SysOp::FWatch), Sys(SysOp::Close))),
+    &MaybeVal(Store1Copy(Sys(SysOp::WsConnect), Sys(SysOp::Close))),
+    &MaybeVal(Store1Copy(Sys(SysOp::SerialOpen), Sys(SysOp::Close))),
     &MaybeVal(Stash(1, Sys(SysOp::FReadAllStr), Sys(SysOp::FWriteAll))),
     &MaybeVal(Stash(1, Sys(SysOp::FReadAllBytes), Sys(SysOp::FWriteAll))),
+    &MaybeVal(Stash(1, Sys(SysOp::FLoad), Sys(SysOp::FSave))),
     &MaybeVal((
         Sys(SysOp::RunStream),
         (Sys(SysOp::RunStream), CopyUnd(3)),
         (PopUnd(3), TryClose, TryClose, TryClose),
     )),
+    &MaybeVal((
+        Sys(SysOp::RunStreamEnv),
+        (Sys(SysOp::RunStreamEnv), CopyUnd(3)),
+        (PopUnd(3), TryClose, TryClose, TryClose),
+    )),
     &MaybeVal((
         Sys(SysOp::RawMode),
         (UnRawMode, PushUnd(1), Sys(SysOp::RawMode)),
         (PopUnd(1), Sys(SysOp::RawMode)),
     )),
+    &MaybeVal((
+        Sys(SysOp::ChangeDirectory),
+        (UnChangeDirectory, PushUnd(1), Sys(SysOp::ChangeDirectory)),
+        (PopUnd(1), Sys(SysOp::ChangeDirectory)),
+    )),
+    &(Sys(SysOp::Clip), Sys(SysOp::Clip), UnClip),
     // Patterns that need to be last
     &StashAntiPat,
     &FlipPat,
@@ -556,26 +572,37 @@ under!(RepeatPat, input, g_sig, inverse, asm, {
     } else {
         (input, None)
     };
-    let (f, span, input) = match input {
+    let (f, inv, span, input) = match input {
         [Mod(Repeat, args, span), input @ ..] => {
             let [f] = args.as_slice() else {
                 return generic();
             };
-            (f, *span, input)
+            (f, None, *span, input)
         }
         [ImplMod(RepeatWithInverse, args, span), input @ ..] => {
-            let [f, _] = args.as_slice() else {
+            let [f, inv] = args.as_slice() else {
                 return generic();
             };
-            (f, *span, input)
+            (f, Some(inv), *span, input)
         }
         _ => return generic(),
     };
     let (f_before, f_after) = f.under_inverse(g_sig, inverse, asm)?;
+    // A negative count repeats the inverse, so the inverse needs its own before and after
+    let inv = inv
+        .and_then(|inv| inv.under_inverse(g_sig, inverse, asm).ok())
+        .filter(|(inv_before, inv_after)| {
+            inv_before.sig == f_before.sig.inverse() && inv_after.sig == f_after.sig.inverse()
+        });
+    let repeat = |f: SigNode, inv: Option<SigNode>| match inv {
+        Some(inv) => ImplMod(RepeatWithInverse, eco_vec![f, inv], span),
+        None => Mod(Repeat, eco_vec![f], span),
+    };
+    let (inv_before, inv_after) = inv.unzip();
     let mut befores = val.clone().unwrap_or(CopyToUnder(1, span));
-    befores.push(Mod(Repeat, eco_vec![f_before], span));
+    befores.push(repeat(f_before, inv_before));
     let mut afters = val.unwrap_or(PopUnder(1, span));
-    afters.push(Mod(Repeat, eco_vec![f_after], span));
+    afters.push(repeat(f_after, inv_after));
     Ok((input, befores, afters))
 });
 
@@ -841,6 +868,7 @@ impl UnderPattern for Trivial {
             [node @ SetOutputComment { .. }, input @ ..] => {
                 Ok((input, node.clone(), Node::empty()))
             }
+            [node @ CheckTypes(..), input @ ..] => Ok((input, node.clone(), Node::empty())),
             [Call(f, _), input @ ..] => {
                 let (before, after) = asm[f]
                     .under_inverse(g_sig, inverse, asm)
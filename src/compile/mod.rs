@@ -14,6 +14,7 @@ use std::{
     fmt, fs,
     iter::once,
     mem::{replace, swap, take},
+    ops::Range,
     panic::{catch_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
     slice,
@@ -25,20 +26,23 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    algorithm::ReshapeMode,
     ast::*,
     check::{nodes_sig, SigCheckError, SigCheckErrorKind},
     format::{format_word, format_words},
     function::DynamicFunction,
     ident_modifier_args,
-    lex::{CodeSpan, Sp, Span},
+    lex::{split_tokens, CodeSpan, Sp, Span},
     lsp::{CodeMeta, ImportSrc, SetInverses, SigDecl},
-    parse::{flip_unsplit_lines, max_placeholder, parse, split_words},
-    Array, ArrayLen, Assembly, BindingKind, BindingMeta, Boxed, CustomInverse, Diagnostic,
-    DiagnosticKind, DocComment, DocCommentSig, Function, FunctionId, GitTarget, Ident,
-    ImplPrimitive, InputSrc, IntoInputSrc, IntoSysBackend, Node, PrimClass, Primitive, Purity,
-    RunMode, SemanticComment, SigNode, Signature, SysBackend, Uiua, UiuaError, UiuaErrorKind,
-    UiuaResult, Value, CONSTANTS, EXAMPLE_UA, SUBSCRIPT_DIGITS, VERSION,
+    parse::{flip_unsplit_lines, max_placeholder, parse_impl, split_words},
+    Array, ArrayLen, Assembly, BindingKind, BindingMeta, Boxed, CustomInverse, DefInfo, Diagnostic,
+    DiagnosticKind, DocComment, DocCommentArg, DocCommentSig, Edition, Function, FunctionId,
+    GitTarget, Ident, ImplPrimitive, InputSrc, IntoInputSrc, IntoSysBackend, Locale, MessageId,
+    Node, PrimClass, Primitive, Purity, RunMode, SemanticComment, SigNode, Signature, SysBackend,
+    TypeCheck, Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value, CONSTANTS, EXAMPLE_UA,
+    SUBSCRIPT_DIGITS, VERSION,
 };
+use pre_eval::PreEvalLimits;
 pub use pre_eval::PreEvalMode;
 
 /// The Uiua compiler
@@ -48,6 +52,8 @@ pub struct Compiler {
     pub(crate) code_meta: CodeMeta,
     /// The name of the current bindings
     current_bindings: Vec<CurrentBinding>,
+    /// Local bindings in the functions currently being compiled
+    fn_locals: Vec<FnLocal>,
     /// The index of the next global binding
     next_global: usize,
     /// The current scope
@@ -66,6 +72,8 @@ pub struct Compiler {
     code_macros: HashMap<usize, CodeMacro>,
     /// Indices of named external functions
     externals: HashMap<Ident, usize>,
+    /// The overloads of overloaded functions, keyed by binding index
+    overloads: HashMap<usize, EcoVec<Function>>,
     /// The depth of compile-time evaluation
     comptime_depth: usize,
     /// Whether the compiler is in a try
@@ -86,6 +94,8 @@ pub struct Compiler {
     macro_env: Uiua,
     /// Start addresses
     start_addrs: Vec<usize>,
+    /// The locale that error messages are shown in
+    locale: Locale,
 }
 
 impl Default for Compiler {
@@ -94,6 +104,7 @@ impl Default for Compiler {
             asm: Assembly::default(),
             code_meta: CodeMeta::default(),
             current_bindings: Vec::new(),
+            fn_locals: Vec::new(),
             next_global: 0,
             scope: Scope::default(),
             higher_scopes: Vec::new(),
@@ -103,6 +114,7 @@ impl Default for Compiler {
             index_macros: HashMap::new(),
             code_macros: HashMap::new(),
             externals: HashMap::new(),
+            overloads: HashMap::new(),
             comptime_depth: 0,
             in_try: false,
             errors: Vec::new(),
@@ -113,6 +125,7 @@ impl Default for Compiler {
             pre_eval_mode: PreEvalMode::default(),
             macro_env: Uiua::default(),
             start_addrs: Vec::new(),
+            locale: Locale::default(),
         }
     }
 }
@@ -123,6 +136,7 @@ struct BindingPrelude {
     track_caller: bool,
     no_inline: bool,
     external: bool,
+    overload: bool,
     deprecation: Option<EcoString>,
 }
 
@@ -154,6 +168,7 @@ struct IndexMacro {
 struct CodeMacro {
     root: SigNode,
     names: IndexMap<Ident, LocalName>,
+    tokens: bool,
 }
 
 impl AsRef<Assembly> for Compiler {
@@ -176,6 +191,14 @@ struct CurrentBinding {
     global_index: usize,
 }
 
+#[derive(Clone)]
+struct FnLocal {
+    name: Ident,
+    def: usize,
+    /// The number of higher scopes when the local was bound
+    scope_depth: usize,
+}
+
 /// A scope where names are defined
 #[derive(Debug, Clone)]
 pub(crate) struct Scope {
@@ -194,8 +217,8 @@ pub(crate) struct Scope {
     pub experimental: bool,
     /// Whether an error has been emitted for experimental features
     experimental_error: bool,
-    /// Whether an error has been emitted for fill function signatures
-    fill_sig_error: bool,
+    /// The language edition
+    edition: Edition,
     /// The stack height between top-level statements
     stack_height: Result<usize, Sp<SigCheckError>>,
 }
@@ -246,7 +269,7 @@ impl Default for Scope {
             data_variants: 0,
             experimental: false,
             experimental_error: false,
-            fill_sig_error: false,
+            edition: Edition::default(),
             stack_height: Ok(0),
         }
     }
@@ -326,6 +349,17 @@ impl Compiler {
         self.scope.experimental = experimental;
         self
     }
+    /// Set the language edition of code that does not declare one
+    pub fn edition(&mut self, edition: Edition) -> &mut Self {
+        self.scope.edition = edition;
+        self
+    }
+    /// Set the locale that error messages are shown in
+    pub fn locale(&mut self, locale: Locale) -> &mut Self {
+        self.locale = locale;
+        self.macro_env.rt.locale = locale;
+        self
+    }
     /// Get the backend
     pub fn backend(&self) -> Arc<dyn SysBackend> {
         self.macro_env.rt.backend.clone()
@@ -426,7 +460,9 @@ impl Compiler {
     }
     fn load_impl(&mut self, input: &str, src: InputSrc) -> UiuaResult<&mut Self> {
         let node_start = self.asm.root.len();
-        let (items, errors, diagnostics) = parse(input, src.clone(), &mut self.asm.inputs);
+        let experimental = self.allow_experimental();
+        let (items, errors, diagnostics) =
+            parse_impl(input, src.clone(), &mut self.asm.inputs, experimental);
         for diagnostic in diagnostics {
             self.emit_diagnostic_impl(diagnostic);
         }
@@ -633,6 +669,7 @@ code:
                         prelude.track_caller = true
                     }
                     Word::SemanticComment(SemanticComment::External) => prelude.external = true,
+                    Word::SemanticComment(SemanticComment::Overload) => prelude.overload = true,
                     Word::SemanticComment(SemanticComment::Deprecated(s)) => {
                         prelude.deprecation = Some(s.clone())
                     }
@@ -839,6 +876,16 @@ code:
                     url = format!("/{url}");
                 }
                 url = format!("github.com{url}");
+                if self.scope.edition.removes_deprecated() {
+                    return Err(self.error(
+                        span.clone(),
+                        format!(
+                            "Implicit GitHub URLs were removed in edition {}. \
+                            Prefix the URL with `github.com/` or `https://github.com/`.",
+                            self.scope.edition
+                        ),
+                    ));
+                }
                 self.emit_diagnostic(
                     "Implicit GitHub URLs are deprecated and \
                     will be removed in the future. Prefix the \
@@ -891,6 +938,7 @@ code:
                 self.asm.inputs.strings = comp.asm.inputs.strings;
                 self.asm.inputs.files.extend(comp.asm.inputs.files);
                 self.scope.experimental = comp.scope.experimental;
+                self.scope.edition = comp.scope.edition;
                 self.diagnostics.extend(comp.diagnostics);
             } else {
                 let input: EcoString = String::from_utf8(bytes)
@@ -983,9 +1031,10 @@ code:
         span: &CodeSpan,
     ) {
         let mut spandex: Option<usize> = None;
+        let is_method = matches!(self.scope.kind, ScopeKind::Method(_));
         // Validate comment signature
         if let Ok(mut sig) = node.sig() {
-            if let ScopeKind::Method(_) = self.scope.kind {
+            if is_method {
                 sig.args += 1;
             }
             if !comment_sig.matches_sig(sig) {
@@ -1024,6 +1073,35 @@ code:
                 node.push(labels);
             }
         }
+        // Add type checks
+        if self.allow_experimental() {
+            let type_check = |args: &[DocCommentArg], outputs: bool| {
+                let values: EcoVec<_> = (args.iter())
+                    .map(|arg| (arg.name.clone(), arg.annotation()))
+                    .collect();
+                (values.iter().any(|(_, ann)| ann.is_some())).then(|| {
+                    Arc::new(TypeCheck {
+                        name: name.into(),
+                        outputs,
+                        values,
+                    })
+                })
+            };
+            // The first argument of a method is bound before the body is called
+            let args = (comment_sig.args.as_deref()).map(|args| match args {
+                [_, rest @ ..] if is_method => rest,
+                args => args,
+            });
+            if let Some(check) = args.and_then(|args| type_check(args, false)) {
+                let span = *spandex.get_or_insert_with(|| self.add_span(span.clone()));
+                node.prepend(Node::CheckTypes(check, span));
+            }
+            let outputs = comment_sig.outputs.as_deref();
+            if let Some(check) = outputs.and_then(|outputs| type_check(outputs, true)) {
+                let span = *spandex.get_or_insert_with(|| self.add_span(span.clone()));
+                node.push(Node::CheckTypes(check, span));
+            }
+        }
     }
     fn args(&mut self, words: Vec<Sp<Word>>) -> UiuaResult<EcoVec<SigNode>> {
         words
@@ -1191,6 +1269,7 @@ code:
             // Compile the word
             let node = self.word(word)?;
             let sig = node.sig().ok();
+            self.check_annotated_call(&nodes, &node, &span);
             nodes.push(node);
             a = b;
             b = Some(PrevWord(modif, prim, sig, span));
@@ -1200,6 +1279,37 @@ code:
         }
         Ok(nodes)
     }
+    /// Check a call's type annotations against arguments known at compile time
+    fn check_annotated_call(&mut self, prev: &Node, node: &Node, span: &CodeSpan) {
+        let first = match node.as_slice().first() {
+            Some(Node::Call(f, _)) => self.asm[f].as_slice().first().cloned(),
+            first => first.cloned(),
+        };
+        let count = match &first {
+            Some(Node::CheckTypes(check, _)) => check.values.len(),
+            Some(Node::DispatchTypes(dispatch, _)) => dispatch.args,
+            _ => return,
+        };
+        let prev = prev.as_slice();
+        let Some(start) = prev.len().checked_sub(count) else {
+            return;
+        };
+        let mut vals = Vec::with_capacity(count);
+        for node in prev[start..].iter().rev() {
+            match node {
+                Node::Push(val) => vals.push(val),
+                _ => return,
+            }
+        }
+        let res = match &first {
+            Some(Node::CheckTypes(check, _)) => check.check_values(&vals),
+            Some(Node::DispatchTypes(dispatch, _)) => dispatch.dispatch_values(&vals).map(drop),
+            _ => Ok(()),
+        };
+        if let Err(message) = res {
+            self.add_error(span.clone(), message);
+        }
+    }
     fn word_sig(&mut self, word: Sp<Word>) -> UiuaResult<SigNode> {
         let span = word.span.clone();
         let node = self.word(word)?;
@@ -1219,13 +1329,16 @@ code:
         let curr_addr = &curr as *const u8 as usize;
         let diff = curr_addr.abs_diff(start_addr);
         if diff > MAX_RECURSION_DEPTH {
-            return Err(self.error(span.clone(), "Compilation recursion limit reached"));
+            return Err(self
+                .error(span.clone(), "Compilation recursion limit reached")
+                .limit());
         }
         Ok(())
     }
     fn word(&mut self, word: Sp<Word>) -> UiuaResult<Node> {
         self.check_depth(&word.span)?;
         Ok(match word.value {
+            Word::Number(Ok(n)) if n.im == 0.0 => Node::new_push(n.re),
             Word::Number(Ok(n)) => Node::new_push(n),
             Word::Number(Err(s)) => {
                 self.add_error(word.span.clone(), format!("Invalid number `{s}`"));
@@ -1404,10 +1517,12 @@ code:
                             ))
                         }
                         _ => {
-                            return Err(self.error(
-                                word.span.clone(),
-                                format!("Cannot infer array signature: {e}"),
-                            ))
+                            return Err(self
+                                .error(
+                                    word.span.clone(),
+                                    format!("Cannot infer array signature: {e}"),
+                                )
+                                .signature())
                         }
                     },
                 };
@@ -1495,6 +1610,7 @@ code:
             Word::OutputComment { i, n } => Node::SetOutputComment { i, n },
             Word::Subscripted(sub) => self.subscript(*sub, word.span)?,
             Word::Comment(_) | Word::Spaces | Word::BreakLine | Word::FlipLine => Node::empty(),
+            Word::LocalBinding(binding) => self.local_binding(binding, word.span)?,
             Word::InlineMacro(_) => {
                 self.add_error(
                     word.span.clone(),
@@ -1581,7 +1697,15 @@ code:
             SemanticComment::NoInline => Node::NoInline(inner.into()),
             SemanticComment::TrackCaller => Node::TrackCaller(inner.into()),
             SemanticComment::External => inner,
+            SemanticComment::Overload => inner,
             SemanticComment::Deprecated(_) => inner,
+            SemanticComment::Edition(s) => {
+                match s.parse() {
+                    Ok(edition) => self.scope.edition = edition,
+                    Err(e) => self.add_error(span, e),
+                }
+                inner
+            }
             SemanticComment::Boo => {
                 self.add_error(span, "The compiler is scared!");
                 inner
@@ -1602,9 +1726,10 @@ code:
             }) {
                 Ok(Some((path_locals, local)))
             } else {
-                Err(self.error(
+                Err(self.message_error(
                     r.name.span.clone(),
-                    format!("Item `{}` not found", r.name.value,),
+                    MessageId::ItemNotFound,
+                    &[&r.name.value],
                 ))
             }
         } else if let Some(local) = self.find_name(&r.name.value, r.in_macro_arg) {
@@ -1612,9 +1737,10 @@ code:
         } else if r.path.is_empty() && CONSTANTS.iter().any(|def| def.name == r.name.value) {
             Ok(None)
         } else {
-            Err(self.error(
+            Err(self.message_error(
                 r.name.span.clone(),
-                format!("Unknown identifier `{}`", r.name.value),
+                MessageId::UnknownIdentifier,
+                &[&r.name.value],
             ))
         }
     }
@@ -1758,17 +1884,27 @@ code:
     }
     fn ident(&mut self, ident: Ident, span: CodeSpan, skip_local: bool) -> UiuaResult<Node> {
         Ok(
-            if let Some(curr) = (self.current_bindings.last_mut()).filter(|curr| curr.name == ident)
+            if let Some(def) = self
+                .find_fn_local(&ident, skip_local)
+                .map(|local| local.def)
+            {
+                // Name is a function local
+                let span = self.add_span(span);
+                Node::GetLocal { def, span }
+            } else if let Some(curr) =
+                (self.current_bindings.last_mut()).filter(|curr| curr.name == ident)
             {
                 // Name is a recursive call
                 let Some(sig) = curr.signature else {
-                    return Err(self.error(
-                        span,
-                        format!(
-                            "Recursive function `{ident}` must have a \
-                            signature declared after the ←."
-                        ),
-                    ));
+                    return Err(self
+                        .error(
+                            span,
+                            format!(
+                                "Recursive function `{ident}` must have a \
+                                signature declared after the ←."
+                            ),
+                        )
+                        .signature());
                 };
                 curr.recurses += 1;
                 (self.code_meta.global_references).insert(span.clone(), curr.global_index);
@@ -1789,7 +1925,7 @@ code:
                         .resolve(self.scope_file_path(), &*self.backend()),
                 )
             } else {
-                return Err(self.error(span, format!("Unknown identifier `{ident}`")));
+                return Err(self.message_error(span, MessageId::UnknownIdentifier, &[&ident]));
             },
         )
     }
@@ -1873,10 +2009,22 @@ code:
         }
     }
     fn func(&mut self, func: Func, span: CodeSpan) -> UiuaResult<Node> {
+        let locals_start = self.fn_locals.len();
         let mut root = Node::empty();
+        let mut res = Ok(());
         for line in func.lines {
-            root.push(self.line(line, false)?);
+            match self.line(line, false) {
+                Ok(node) => root.push(node),
+                Err(e) => {
+                    res = Err(e);
+                    break;
+                }
+            }
         }
+        let locals: Vec<usize> = (self.fn_locals.drain(locals_start..))
+            .map(|local| local.def)
+            .collect();
+        res?;
 
         // Validate signature
         let sig = match root.sig() {
@@ -1887,8 +2035,15 @@ code:
                 }
                 Some(sig)
             }
-            Err(e) => return Err(self.error(span, format!("Cannot infer function signature: {e}"))),
+            Err(e) => {
+                return Err(self
+                    .error(span, format!("Cannot infer function signature: {e}"))
+                    .signature())
+            }
         };
+        if let Some(sig) = sig.filter(|_| !locals.is_empty()) {
+            root = self.with_fn_locals(root, sig, &locals, &span);
+        }
         if let Some(sig) = sig {
             self.code_meta.function_sigs.insert(
                 span.clone(),
@@ -1902,6 +2057,70 @@ code:
         }
         Ok(root)
     }
+    /// Bind a local inside a function
+    fn local_binding(&mut self, binding: LocalBinding, span: CodeSpan) -> UiuaResult<Node> {
+        self.experimental_error(&span, || {
+            "Local bindings are experimental. To use them, \
+            add `# Experimental!` to the top of the file."
+        });
+        let binds_top = !binding.words.iter().any(|w| w.value.is_code());
+        let mut node = self.words(binding.words)?;
+        if !binds_top {
+            let sig = self.sig_of(&node, &span)?;
+            if sig.outputs != 1 {
+                self.add_signature_error(
+                    binding.name.span.clone(),
+                    format!(
+                        "Local binding `{}`'s code must have 1 output, \
+                        but its signature is {sig}",
+                        binding.name.value
+                    ),
+                );
+            }
+        }
+        let def = self.asm.bind_def(DefInfo {
+            name: binding.name.value.clone(),
+        });
+        let spandex = self.add_span(span);
+        node.push(Node::SetLocal { def, span: spandex });
+        self.fn_locals.push(FnLocal {
+            name: binding.name.value,
+            def,
+            scope_depth: self.higher_scopes.len(),
+        });
+        Ok(node)
+    }
+    /// Give a function's locals a place to live while it runs
+    fn with_fn_locals(
+        &mut self,
+        mut node: Node,
+        sig: Signature,
+        defs: &[usize],
+        span: &CodeSpan,
+    ) -> Node {
+        let span = self.add_span(span.clone());
+        for &def in defs {
+            // The local's slot sits below the function's arguments
+            let mut slot = Node::new_push(Value::default());
+            for _ in 0..sig.args {
+                slot = Node::Mod(Primitive::Dip, eco_vec![slot.sig_node().unwrap()], span);
+            }
+            let inner = SigNode::new(sig, node).into();
+            node = Node::from_iter([slot, Node::WithLocal { def, inner, span }]);
+        }
+        node
+    }
+    fn find_fn_local(&self, name: &str, skip_local: bool) -> Option<&FnLocal> {
+        let depth = self.higher_scopes.len();
+        (self.fn_locals.iter().rev()).find(|local| {
+            local.name == name
+                && if skip_local {
+                    local.scope_depth < depth
+                } else {
+                    local.scope_depth == depth
+                }
+        })
+    }
     fn switch(&mut self, branches: Vec<Sp<Word>>, span: CodeSpan) -> UiuaResult<Node> {
         let count = branches.len();
         // Compile branches
@@ -1950,7 +2169,7 @@ code:
                 } else if arg.sig.outputs == sig.outputs {
                     sig.args = sig.args.max(arg.sig.args)
                 } else {
-                    self.add_error(
+                    self.add_signature_error(
                         span.clone(),
                         format!(
                             "Switch branch's signature {} is \
@@ -1975,8 +2194,170 @@ code:
             under_cond: false,
         })
     }
+    /// Compile a [`Primitive::Pattern`] into nested [`Primitive::Try`]s
+    ///
+    /// Each branch is split into its pattern and body.
+    /// Only the pattern is tried, and the body is wrapped in a [`Primitive::Case`]
+    /// so that its errors are not caught.
+    fn pattern_match(&mut self, branches: Vec<Sp<Word>>, span: CodeSpan) -> UiuaResult<Node> {
+        let is_pattern_word = |word: &Sp<Word>| match &word.value {
+            Word::Modified(m) => m.modifier.value == Modifier::Primitive(Primitive::Un),
+            _ => false,
+        };
+        // Compile branches
+        let mut compiled: Vec<(SigNode, bool, CodeSpan)> = Vec::with_capacity(branches.len());
+        let mut seen_patterns: Vec<String> = Vec::new();
+        let mut catch_all: Option<CodeSpan> = None;
+        for branch in branches {
+            let branch_span = branch.span.clone();
+            if catch_all.is_some() {
+                self.emit_diagnostic(
+                    "This branch is unreachable because a previous branch has no pattern",
+                    DiagnosticKind::Warning,
+                    branch_span,
+                );
+                continue;
+            }
+            // Split the pattern from the body
+            let (pattern, body) = match branch.value {
+                Word::Func(mut func) => {
+                    let mut pattern = Vec::new();
+                    if let Some(line) =
+                        (func.lines.iter_mut()).find(|line| line.iter().any(|w| w.value.is_code()))
+                    {
+                        let mut split = line.len();
+                        for (i, word) in line.iter().enumerate().rev() {
+                            if is_pattern_word(word) {
+                                split = i;
+                            } else if word.value.is_code() {
+                                break;
+                            }
+                        }
+                        pattern = line.split_off(split);
+                    }
+                    (pattern, branch_span.clone().sp(Word::Func(func)))
+                }
+                _ if is_pattern_word(&branch) => (
+                    vec![branch],
+                    branch_span.clone().sp(Word::Func(Func {
+                        signature: None,
+                        lines: Vec::new(),
+                        closed: true,
+                    })),
+                ),
+                _ => (Vec::new(), branch),
+            };
+            if pattern.is_empty() {
+                catch_all = Some(branch_span.clone());
+                compiled.push((self.word_sig(body)?, false, branch_span));
+                continue;
+            }
+            // Check for duplicate patterns
+            let pattern_span =
+                (pattern.first().unwrap().span.clone()).merge(pattern.last().unwrap().span.clone());
+            let pattern_text: String = pattern_span.as_str(&self.asm.inputs, |s| {
+                s.chars().filter(|c| !c.is_whitespace()).collect()
+            });
+            if seen_patterns.contains(&pattern_text) {
+                self.emit_diagnostic(
+                    "This pattern is the same as a previous one, so this branch is unreachable",
+                    DiagnosticKind::Warning,
+                    pattern_span,
+                );
+            } else {
+                seen_patterns.push(pattern_text);
+            }
+            // Compile
+            let declared_sig = match &body.value {
+                Word::Func(func) => func.signature.clone(),
+                _ => None,
+            };
+            let pattern = self.words_sig(pattern)?;
+            let body = self.word_sig(body)?;
+            let sig = body.sig.compose(pattern.sig);
+            if let Some(declared) = declared_sig {
+                if declared.value != sig {
+                    self.emit_diagnostic(
+                        format!(
+                            "Signature mismatch: declared {} but inferred {sig}",
+                            declared.value
+                        ),
+                        DiagnosticKind::Warning,
+                        declared.span,
+                    );
+                }
+            }
+            if let Some(sig_decl) = self.code_meta.function_sigs.get_mut(&branch_span) {
+                sig_decl.sig = sig;
+            }
+            let spandex = self.add_span(branch_span.clone());
+            let node = Node::from_iter([
+                pattern.node,
+                Node::Mod(Primitive::Case, eco_vec![body], spandex),
+            ]);
+            compiled.push((SigNode::new(sig, node), true, branch_span));
+        }
+        if catch_all.is_none() {
+            self.emit_diagnostic(
+                format!(
+                    "{} is not exhaustive. Add a final branch \
+                    without a pattern to handle other values.",
+                    Primitive::Pattern.format()
+                ),
+                DiagnosticKind::Warning,
+                span.clone(),
+            );
+        }
+        let spandex = self.add_span(span);
+        // Chain branches from last to first
+        let mut rest: Option<SigNode> = None;
+        for (mut tried, has_pattern, branch_span) in compiled.into_iter().rev() {
+            if !has_pattern {
+                rest = Some(tried);
+                continue;
+            }
+            let mut handler = rest.take().unwrap_or_else(|| {
+                let node = Node::from_iter([
+                    Node::new_push(0),
+                    Node::new_push("No pattern matched"),
+                    Node::Prim(Primitive::Assert, spandex),
+                ]);
+                SigNode::new(tried.sig, node)
+            });
+            if tried.sig.is_compatible_with(handler.sig) {
+                // Branches that take fewer arguments leave the rest on the stack
+                let sig = tried.sig.max_with(handler.sig);
+                tried.sig = sig;
+                handler.sig = sig;
+            } else if tried.sig.outputs != handler.sig.outputs || tried.sig.args < handler.sig.args
+            {
+                self.add_signature_error(
+                    branch_span,
+                    format!(
+                        "Pattern branch's signature {} is \
+                        incompatible with later branches {}",
+                        tried.sig, handler.sig
+                    ),
+                );
+            }
+            // Otherwise, later branches discard excess arguments
+            let sig = tried.sig;
+            let node = Node::Mod(Primitive::Try, eco_vec![tried, handler], spandex);
+            rest = Some(SigNode::new(sig, node));
+        }
+        Ok(rest.map(|sn| sn.node).unwrap_or_default())
+    }
     fn handle_primitive_deprecation(&mut self, prim: Primitive, span: &CodeSpan) {
         if let Some(suggestion) = prim.deprecation_suggestion() {
+            if self.scope.edition.removes_deprecated() {
+                let message = format!(
+                    "{} was removed in edition {}, {suggestion}",
+                    prim.format(),
+                    self.scope.edition
+                );
+                self.add_error(span.clone(), message);
+                return;
+            }
             if !self.deprecated_prim_errors.insert(prim) {
                 return;
             }
@@ -1997,14 +2378,9 @@ code:
         }
     }
     fn handle_primitive_experimental(&mut self, prim: Primitive, span: &CodeSpan) {
-        if prim.is_experimental() {
-            self.experimental_error(span, || {
-                format!(
-                    "{} is experimental. To use it, add \
-                    `# Experimental!` to the top of the file.",
-                    prim.format()
-                )
-            });
+        if prim.is_experimental() && self.mark_experimental_error() {
+            let e = self.message_error(span.clone(), MessageId::Experimental, &[&prim.format()]);
+            self.errors.push(e);
         }
     }
     fn validate_primitive(&mut self, prim: Primitive, span: &CodeSpan) {
@@ -2053,7 +2429,7 @@ code:
                 let n_span = n.span;
                 match n.value {
                     SubNOrSide::N(n) => Node::from_iter([
-                        self.word(n_span.sp(Word::Number(Ok(n as f64))))?,
+                        self.word(n_span.sp(Word::Number(Ok((n as f64).into()))))?,
                         self.primitive(prim, span),
                     ]),
                     SubNOrSide::Side(side) => {
@@ -2082,11 +2458,52 @@ code:
                         && prim.subscript_sig(Some(2)).is_some_and(|sig| sig == (1, 1)) =>
                     {
                         Node::from_iter([
-                            self.word(n_span.sp(Word::Number(Ok(n as f64))))?,
+                            self.word(n_span.sp(Word::Number(Ok((n as f64).into()))))?,
                             self.primitive(prim, span),
                         ])
                     }
                     Deshape => Node::ImplPrim(ImplPrimitive::DeshapeSub(n), self.add_span(span)),
+                    Interp | Cubic => {
+                        self.subscript_experimental(prim, &span);
+                        let axes = self.positive_subscript(n, prim, span.clone())?;
+                        if axes == 0 {
+                            self.add_error(span.clone(), "Cannot interpolate along 0 axes");
+                        }
+                        let cubic = prim == Cubic;
+                        let interp = ImplPrimitive::InterpSub {
+                            axes: axes.max(1),
+                            cubic,
+                        };
+                        Node::ImplPrim(interp, self.add_span(span))
+                    }
+                    Resample => {
+                        self.subscript_experimental(prim, &span);
+                        if !matches!(n, 0 | 1 | 3) {
+                            self.add_error(
+                                span.clone(),
+                                "Resample subscript must be 0 for box, \
+                                1 for linear, or 3 for cubic",
+                            );
+                        }
+                        let degree = if matches!(n, 0 | 1 | 3) {
+                            n as usize
+                        } else {
+                            1
+                        };
+                        Node::ImplPrim(ImplPrimitive::ResampleSub(degree), self.add_span(span))
+                    }
+                    Reshape => {
+                        self.subscript_experimental(prim, &span);
+                        let Some(mode) = ReshapeMode::from_subscript(n) else {
+                            self.add_error(
+                                span.clone(),
+                                "Reshape subscript must be 0 for exact, \
+                                1 for cycle, or 2 for fill",
+                            );
+                            return Ok(self.primitive(prim, span));
+                        };
+                        Node::ImplPrim(ImplPrimitive::ReshapeSub(mode), self.add_span(span))
+                    }
                     Transpose => {
                         self.subscript_experimental(prim, &span);
                         if n > 100 {
@@ -2111,6 +2528,23 @@ code:
                         };
                         Node::from_iter([Node::new_push(root_of_unity), self.primitive(Mul, span)])
                     }
+                    Sin => {
+                        self.subscript_experimental(prim, &span);
+                        if n == 0 {
+                            self.add_error(span.clone(), "A full turn cannot be 0 units");
+                        }
+                        Node::ImplPrim(ImplPrimitive::SinSub(n), self.add_span(span))
+                    }
+                    Fft => {
+                        self.subscript_experimental(prim, &span);
+                        if n < 1 {
+                            self.add_error(span.clone(), "FFT must be over at least 1 axis");
+                        }
+                        Node::ImplPrim(
+                            ImplPrimitive::FftSub(n.max(1) as usize),
+                            self.add_span(span),
+                        )
+                    }
                     Sqrt => {
                         if n == 0 {
                             self.add_error(span.clone(), "Cannot take 0th root");
@@ -2368,24 +2802,39 @@ impl Compiler {
         let e = self.error(span, message);
         self.errors.push(e);
     }
-    fn experimental_error<S>(&mut self, span: &CodeSpan, message: impl FnOnce() -> S)
-    where
-        S: ToString,
-    {
+    fn add_signature_error(&mut self, span: impl Into<Span>, message: impl ToString) {
+        let e = self.error(span, message).signature();
+        self.errors.push(e);
+    }
+    fn allow_experimental(&self) -> bool {
+        self.experimental_scopes().any(|sc| sc.experimental)
+    }
+    fn experimental_scopes(&self) -> impl Iterator<Item = &Scope> {
         let take = self
             .scopes()
             .position(|sc| matches!(sc.kind, ScopeKind::File(_)))
             .map(|i| i + 1)
             .unwrap_or(usize::MAX);
-        if !self
-            .scopes()
-            .take(take)
-            .any(|sc| sc.experimental || sc.experimental_error)
-        {
-            self.scope.experimental_error = true;
+        self.scopes().take(take)
+    }
+    fn experimental_error<S>(&mut self, span: &CodeSpan, message: impl FnOnce() -> S)
+    where
+        S: ToString,
+    {
+        if self.mark_experimental_error() {
             self.add_error(span.clone(), message().to_string());
         }
     }
+    /// Mark that an experimental error was emitted in this scope
+    ///
+    /// Returns `false` if an error should not be emitted
+    fn mark_experimental_error(&mut self) -> bool {
+        if (self.experimental_scopes()).any(|sc| sc.experimental || sc.experimental_error) {
+            return false;
+        }
+        self.scope.experimental_error = true;
+        true
+    }
     fn error(&self, span: impl Into<Span>, message: impl ToString) -> UiuaError {
         UiuaErrorKind::Run {
             message: span.into().sp(message.to_string()),
@@ -2394,6 +2843,15 @@ impl Compiler {
         }
         .into()
     }
+    fn message_error(
+        &self,
+        span: impl Into<Span>,
+        id: MessageId,
+        args: &[&dyn fmt::Display],
+    ) -> UiuaError {
+        self.error(span, id.format(self.locale, args))
+            .with_message_id(id)
+    }
     fn error_with_info<S, M>(
         &self,
         span: impl Into<Span>,
@@ -2533,6 +2991,7 @@ impl Compiler {
                 span.clone(),
                 format!("Cannot infer function signature: {e}"),
             )
+            .signature()
         })
     }
 }
@@ -2557,6 +3016,20 @@ fn set_in_macro_arg(words: &mut [Sp<Word>]) {
     });
 }
 
+fn set_in_macro_arg_in(words: &mut [Sp<Word>], ranges: &[Range<usize>]) {
+    recurse_words_mut(words, &mut |word| {
+        let start = word.span.start.byte_pos as usize;
+        if !ranges.iter().any(|range| range.contains(&start)) {
+            return;
+        }
+        match &mut word.value {
+            Word::Ref(r) => r.in_macro_arg = true,
+            Word::IncompleteRef { in_macro_arg, .. } => *in_macro_arg = true,
+            _ => {}
+        }
+    });
+}
+
 fn recurse_words(words: &[Sp<Word>], f: &mut dyn FnMut(&Sp<Word>)) {
     for word in words {
         f(word);
@@ -2572,6 +3045,7 @@ fn recurse_words(words: &[Sp<Word>], f: &mut dyn FnMut(&Sp<Word>)) {
             Word::Pack(pack) => pack.branches.iter().for_each(|branch| {
                 (branch.value.lines.iter()).for_each(|line| recurse_words(line, f))
             }),
+            Word::LocalBinding(binding) => recurse_words(&binding.words, f),
             _ => {}
         }
     }
@@ -2592,6 +3066,7 @@ fn recurse_words_mut(words: &mut [Sp<Word>], f: &mut dyn FnMut(&mut Sp<Word>)) {
                 (branch.value.lines.iter_mut()).for_each(|line| recurse_words_mut(line, f))
             }),
             Word::Subscripted(sub) => recurse_words_mut(slice::from_mut(&mut sub.word), f),
+            Word::LocalBinding(binding) => recurse_words_mut(&mut binding.words, f),
             _ => {}
         }
         f(word);
@@ -135,6 +135,15 @@ impl Compiler {
                     .collect(),
                 modifier.span.clone(),
             ),
+            Modifier::Primitive(Primitive::Pattern) => {
+                self.handle_primitive_experimental(Primitive::Pattern, &modifier.span);
+                self.pattern_match(
+                    (pack.branches.iter().cloned())
+                        .map(|sp| sp.map(Word::Func))
+                        .collect(),
+                    modifier.span.clone(),
+                )
+            }
             Modifier::Primitive(Primitive::Obverse) => {
                 let mut nodes = Vec::new();
                 let mut spans = Vec::new();
@@ -279,6 +288,15 @@ impl Compiler {
                 let span = self.add_span(modifier.span.clone());
                 Ok(Node::Mod(Primitive::Path, args, span))
             }
+            Modifier::Primitive(Primitive::Scope) => {
+                self.handle_primitive_experimental(Primitive::Scope, &modifier.span);
+                let mut args = EcoVec::with_capacity(pack.branches.len());
+                for branch in pack.branches.iter().cloned() {
+                    args.push(self.word_sig(branch.map(Word::Func))?);
+                }
+                let span = self.add_span(modifier.span.clone());
+                Ok(Node::Mod(Primitive::Scope, args, span))
+            }
             Modifier::Primitive(Primitive::Path) if pack.branches.len() == 3 => {
                 let mut args = EcoVec::with_capacity(3);
                 for branch in pack.branches.iter().cloned() {
@@ -550,7 +568,7 @@ impl Compiler {
                     SubNOrSide::Side(side) => {
                         let op = self.monadic_modifier_op(modified)?.0;
                         if op.sig.args != 2 {
-                            self.add_error(
+                            self.add_signature_error(
                                 modified.modifier.span.clone().merge(n.span),
                                 format!(
                                     "Sided {}'s function must have 2 arguments, \
@@ -593,7 +611,7 @@ impl Compiler {
                 let span = self.add_span(modified.modifier.span.clone());
                 let (a, b, _, _) = self.dyadic_modifier_ops(modified)?;
                 if a.sig.args != 2 || b.sig.args != 2 {
-                    self.add_error(
+                    self.add_signature_error(
                         modified.modifier.span.clone().merge(side.span),
                         format!(
                             "Sided {}'s functions must both have 2 arguments, \
@@ -666,6 +684,16 @@ impl Compiler {
             }
             prim @ (Above | Below) => {
                 let (mut sn, _) = self.monadic_modifier_op(modified)?;
+                if sn.sig.args < 2 && self.scope.edition.removes_deprecated() {
+                    return Err(self.error(
+                        modified.modifier.span.clone(),
+                        format!(
+                            "{} with < 2 arguments was removed in edition {}",
+                            prim.format(),
+                            self.scope.edition
+                        ),
+                    ));
+                }
                 if sn.sig.args < 2 {
                     self.emit_diagnostic(
                         format!(
@@ -686,7 +714,7 @@ impl Compiler {
                 let (SigNode { mut node, sig }, _) = self.monadic_modifier_op(modified)?;
                 match sig.args {
                     0 | 1 => {
-                        self.add_error(
+                        self.add_signature_error(
                             modified.modifier.span.clone(),
                             format!(
                                 "{}'s function must take at least 2 arguments, \
@@ -726,7 +754,7 @@ impl Compiler {
                         node
                     }
                     _ => {
-                        self.add_error(
+                        self.add_signature_error(
                             modified.modifier.span.clone(),
                             format!(
                                 "Currently, {}'s function may take at most, \
@@ -792,7 +820,7 @@ impl Compiler {
                 let mut node = Node::Mod(Primitive::Tuples, eco_vec![sn], span);
                 if let Some(n) = subscript.and_then(|n| self.subscript_n(n, Tuples.format())) {
                     if inner_sig.args != 2 {
-                        self.add_error(
+                        self.add_signature_error(
                             modified.modifier.span.clone().merge(n.span),
                             format!(
                                 "{} can only be subscripted if its function \
@@ -812,7 +840,7 @@ impl Compiler {
                 let mut node = Node::Mod(Primitive::Stencil, eco_vec![sn], span);
                 if let Some(n) = subscript.and_then(|n| self.subscript_n(n, Stencil.format())) {
                     if inner_sig.args != 1 {
-                        self.add_error(
+                        self.add_signature_error(
                             modified.modifier.span.clone().merge(n.span),
                             format!(
                                 "{} can only be subscripted if its function \
@@ -982,6 +1010,13 @@ impl Compiler {
                 modified.code_operands().cloned().collect(),
                 modified.modifier.span.clone(),
             )?,
+            Pattern => {
+                self.handle_primitive_experimental(Pattern, &modified.modifier.span);
+                self.pattern_match(
+                    modified.code_operands().cloned().collect(),
+                    modified.modifier.span.clone(),
+                )?
+            }
             Fill => {
                 let mut operands = modified.code_operands().rev().cloned();
 
@@ -995,16 +1030,14 @@ impl Compiler {
                 let fill_word = operands.next().unwrap();
                 let fill_span = fill_word.span.clone();
                 let fill = self.word_sig(fill_word)?;
-                if fill.sig.outputs > 1 && !self.scope.fill_sig_error {
-                    self.scope.fill_sig_error = true;
-                    self.add_error(
-                        fill_span,
+                if fill.sig.outputs > 1 {
+                    self.experimental_error(&fill_span, || {
                         format!(
-                            "{} function can have at most 1 output, but its signature is {}",
-                            Primitive::Fill.format(),
-                            fill.sig
-                        ),
-                    );
+                            "{} functions with multiple outputs are experimental. \
+                            To use them, add `# Experimental!` to the top of the file.",
+                            Primitive::Fill.format()
+                        )
+                    });
                 }
                 let span = self.add_span(modified.modifier.span.clone());
                 Node::Mod(Primitive::Fill, eco_vec![fill, f], span)
@@ -1245,7 +1278,7 @@ impl Compiler {
                         ));
                     }
                 };
-                self.quote(&code, Some("quote".into()), &modified.modifier.span)?
+                self.quote(&code, &[], Some("quote".into()), &modified.modifier.span)?
             }
             Sig => {
                 let (sn, _) = self.monadic_modifier_op(modified)?;
@@ -1310,6 +1343,7 @@ impl Compiler {
             let code_mac = CodeMacro {
                 root,
                 names: Default::default(),
+                tokens: false,
             };
             self.code_macro(None, span, operands, code_mac)?
         } else {
@@ -1401,8 +1435,21 @@ impl Compiler {
                     let node = self.suppress_diagnostics(|comp| {
                         comp.temp_scope(mac.names, macro_local, |comp| comp.words(mac.words))
                     })?;
-                    // Add
+                    // Check the declared signature
+                    // Recursive macros use it as the signature of the recursive call instead
                     let sig = self.sig_of(&node, &modifier_span)?;
+                    let declared = mac.sig.filter(|_| !mac.recursive);
+                    if let Some(declared) = declared.filter(|&declared| declared != sig) {
+                        self.add_signature_error(
+                            modifier_span.clone(),
+                            format!(
+                                "{}'s signature is declared as {declared}, \
+                                but with these operands it is {sig}",
+                                r.name.value
+                            ),
+                        );
+                    }
+                    // Add
                     let id = FunctionId::Macro(Some(r.name.value), r.name.span);
                     let func = self.asm.add_function(id, sig, node);
                     if let Some(macro_local) = macro_local {
@@ -1490,11 +1537,23 @@ impl Compiler {
                         formatted = formatted[1..formatted.len() - 1].to_string();
                     }
                 }
-                Boxed(formatted.trim().into())
+                let formatted = formatted.trim();
+                if !mac.tokens {
+                    return Boxed(formatted.into());
+                }
+                // Token macros get each operand as a list of tokens
+                let tokens = split_tokens(formatted).unwrap_or_else(|_| vec![formatted]);
+                Boxed(
+                    tokens
+                        .into_iter()
+                        .map(|s| Boxed(s.into()))
+                        .collect::<Array<_>>()
+                        .into(),
+                )
             })
             .collect();
-
         let mut code: Option<String> = None;
+        let mut operand_ranges = Vec::new();
         (|| -> UiuaResult {
             if let Some(index) = self.node_unbound_index(&mac.root.node) {
                 let name = self.scope.names.iter().find_map(|(name, local)| {
@@ -1536,6 +1595,32 @@ impl Compiler {
 
                 let val = env.pop("macro result")?;
 
+                // Token macro output
+                if mac.tokens {
+                    let code = code.get_or_insert_with(String::new);
+                    let Value::Box(items) = val else {
+                        code.push_str(
+                            &val.as_string(env, "Token macro output must be a list of tokens")?,
+                        );
+                        return Ok(());
+                    };
+                    for Boxed(item) in items.data {
+                        if let Some(s) = item.as_string_opt() {
+                            code.push_str(&s);
+                        } else {
+                            // Operand token lists are spliced in as-is
+                            let tokens = item.as_strings(
+                                env,
+                                "Token macro output must be a list of tokens or operands",
+                            )?;
+                            let start = code.len();
+                            tokens.iter().for_each(|s| code.push_str(s));
+                            operand_ranges.push(start..code.len());
+                        }
+                    }
+                    return Ok(());
+                }
+
                 // Parse the macro output
                 let strings = match val
                     .as_strings(env, "Code macro output must be a string or list of strings")
@@ -1571,7 +1656,7 @@ impl Compiler {
                 .insert(full_span, (mac_name.clone(), code.clone()));
             self.suppress_diagnostics(|comp| {
                 comp.temp_scope(mac.names, None, |comp| {
-                    comp.quote(&code, mac_name, &modifier_span)
+                    comp.quote(&code, &operand_ranges, mac_name, &modifier_span)
                 })
             })
         } else {
@@ -1642,17 +1727,41 @@ impl Compiler {
         words.retain(|word| !matches!(word.value, Word::Placeholder(_)));
         error.map_or(Ok(()), Err)
     }
-    fn quote(&mut self, code: &str, name: Option<Ident>, span: &CodeSpan) -> UiuaResult<Node> {
-        let (items, errors, _) = parse(
+    fn quote(
+        &mut self,
+        code: &str,
+        operand_ranges: &[Range<usize>],
+        name: Option<Ident>,
+        span: &CodeSpan,
+    ) -> UiuaResult<Node> {
+        let experimental = self.allow_experimental();
+        let (mut items, errors, _) = parse_impl(
             code,
             InputSrc::Macro(span.clone().into()),
             &mut self.asm.inputs,
+            experimental,
         );
         if !errors.is_empty() {
             return Err(UiuaErrorKind::Parse(errors, self.asm.inputs.clone().into())
                 .error()
                 .trace_macro(name, span.clone()));
         }
+        // Resolve names that came from macro operands at the call site
+        if !operand_ranges.is_empty() {
+            for item in &mut items {
+                match item {
+                    Item::Words(lines) => {
+                        for line in lines {
+                            set_in_macro_arg_in(line, operand_ranges);
+                        }
+                    }
+                    Item::Binding(binding) => {
+                        set_in_macro_arg_in(&mut binding.words, operand_ranges)
+                    }
+                    _ => {}
+                }
+            }
+        }
 
         let root_node_len = self.asm.root.len();
         // Compile the generated items
@@ -1749,6 +1858,7 @@ impl Compiler {
             names,
             experimental: self.scope.experimental,
             experimental_error: self.scope.experimental_error,
+            edition: self.scope.edition,
             ..Default::default()
         };
         self.higher_scopes
@@ -1,6 +1,15 @@
 use super::*;
 
-use crate::{check::nodes_clean_sig, ImplPrimitive::*, Node::*, Primitive::*};
+use crate::{
+    algorithm::{
+        reduce::{fusable_monadic, fusable_reduction},
+        view::view_op_len,
+    },
+    check::nodes_clean_sig,
+    ImplPrimitive::*,
+    Node::*,
+    Primitive::*,
+};
 
 pub(crate) const DEBUG: bool = false;
 
@@ -54,6 +63,8 @@ impl Node {
                 optimized |= optimize_run(nodes, level, opt_single);
                 self.normalize();
             }
+            // Strided view operations must stay unfused
+            ImplMod(StridedView, ..) => {}
             Mod(_, args, _) | ImplMod(_, args, _) => {
                 for arg in args.make_mut() {
                     optimized |= arg.node.optimize_impl(level, true);
@@ -133,12 +144,16 @@ static OPTIMIZATIONS: &[&dyn Optimization] = &[
     &ReduceContentOpt,
     &ReduceConjoinInventoryOpt,
     &PathOpt,
+    &GradeTakeOpt,
     &SplitByOpt,
     &AllSameOpt,
     &RepeatRandOpt,
     &PopConst,
     &TraceOpt,
     &ValidateTypeOpt,
+    &StridedViewOpt,
+    &FusePervasiveOpt,
+    &FuseForkOpt,
 ];
 
 opt!(
@@ -196,6 +211,81 @@ opt!(
     )
 );
 
+opt!(
+    FusePervasiveOpt,
+    (
+        [Prim(Dup, _), Prim(prim, span)](prim.dyadic_pervasive_fn().is_some()),
+        ImplPrim(DupPervasive(*prim), *span)
+    ),
+    (
+        [Prim(Flip, _), Prim(prim, span)](prim.dyadic_pervasive_fn().is_some()),
+        ImplPrim(FlipPervasive(*prim), *span)
+    ),
+);
+
+opt!(
+    FuseForkOpt,
+    (
+        [Mod(On, args, span), f](match args.as_slice() {
+            [g] if g.sig == (1, 1) => {
+                (fusable_reduction(f).is_some() && fusable_reduction(&g.node).is_some())
+                    || (fusable_monadic(f).is_some() && fusable_monadic(&g.node).is_some())
+            }
+            _ => false,
+        }),
+        ImplMod(
+            FusedFork,
+            eco_vec![SigNode::new((1, 1), f.clone()), args[0].clone()],
+            *span
+        )
+    ),
+    (
+        [Prim(Dup, span), Mod(Both, args, _)](match args.as_slice() {
+            [f] => f.sig == (1, 1) && is_simple_pure(&f.node),
+            _ => false,
+        }),
+        [args[0].node.clone(), Prim(Dup, *span)]
+    ),
+);
+
+/// Check if a node is made only of constants and pure primitives
+///
+/// Unlike [`Node::is_pure`], this does not need an assembly to look up called functions
+fn is_simple_pure(node: &Node) -> bool {
+    node.as_slice().iter().all(|node| match node {
+        Push(_) => true,
+        Prim(prim, _) => prim.purity() == Purity::Pure,
+        ImplPrim(prim, _) => prim.purity() == Purity::Pure,
+        Mod(prim, args, _) => {
+            prim.purity() == Purity::Pure && args.iter().all(|sn| is_simple_pure(&sn.node))
+        }
+        _ => false,
+    })
+}
+
+#[derive(Debug)]
+struct StridedViewOpt;
+impl Optimization for StridedViewOpt {
+    fn match_and_replace(&self, nodes: &mut EcoVec<Node>) -> bool {
+        match_and_replace(nodes, |nodes| {
+            let mut len = 0;
+            let mut op_count = 0;
+            let mut reorders = false;
+            while let Some((n, reorder)) = view_op_len(&nodes[len..]) {
+                len += n;
+                op_count += 1;
+                reorders |= reorder;
+            }
+            if op_count < 2 || !reorders {
+                return None;
+            }
+            let span = nodes[..len].iter().find_map(|node| node.span())?;
+            let inner = Node::from(&nodes[..len]).sig_node().ok()?;
+            Some((len, ImplMod(StridedView, eco_vec![inner], span)))
+        })
+    }
+}
+
 #[derive(Debug)]
 struct ReduceDepthOpt;
 impl Optimization for ReduceDepthOpt {
@@ -304,6 +394,18 @@ opt!(
     ),
 );
 
+opt!(
+    GradeTakeOpt,
+    (
+        [Prim(Rise, span), Push(n), Prim(Take, _)],
+        [Push(n.clone()), ImplPrim(RiseTake, *span)]
+    ),
+    (
+        [Prim(Fall, span), Push(n), Prim(Take, _)],
+        [Push(n.clone()), ImplPrim(FallTake, *span)]
+    ),
+);
+
 #[derive(Debug)]
 struct PathOpt;
 impl Optimization for PathOpt {
@@ -24,12 +24,39 @@ pub enum PreEvalMode {
     Lsp,
 }
 
-const MAX_PRE_EVAL_ELEMS: usize = 1000;
-const MAX_PRE_EVAL_RANK: usize = 4;
+/// Limits on what may be pre-evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct PreEvalLimits {
+    max_elems: usize,
+    max_rank: usize,
+    time: Duration,
+}
+
+impl PreEvalLimits {
+    /// Limits for sections of lines and functions
+    ///
+    /// These are small because the results are inlined at every use
+    pub const INLINE: Self = Self {
+        max_elems: 1000,
+        max_rank: 4,
+        time: Duration::from_millis(40),
+    };
+    /// Limits for the bodies of constant bindings
+    ///
+    /// These are larger because the result is only stored once in the assembly
+    pub const BINDING: Self = Self {
+        max_elems: 10_000_000,
+        max_rank: usize::MAX,
+        time: Duration::from_secs(1),
+    };
+    fn allows(&self, val: &Value) -> bool {
+        val.element_count() <= self.max_elems && val.rank() <= self.max_rank
+    }
+}
 
 impl PreEvalMode {
     #[allow(unused)]
-    fn matches_nodes(&self, nodes: &[Node], asm: &Assembly) -> bool {
+    fn matches_nodes(&self, nodes: &[Node], limits: PreEvalLimits, asm: &Assembly) -> bool {
         if nodes.iter().all(|node| matches!(node, Node::Push(_))) {
             return false;
         }
@@ -38,34 +65,53 @@ impl PreEvalMode {
         }
         fn recurse<'a>(
             mode: PreEvalMode,
+            limits: PreEvalLimits,
             nodes: &'a [Node],
             asm: &'a Assembly,
             visited: &mut IndexSet<&'a Function>,
         ) -> bool {
-            if nodes.iter().any(|node| {
-                matches!(
-                    node,
-                    Node::Push(val)
-                        if val.element_count() > MAX_PRE_EVAL_ELEMS
-                        || val.rank() > MAX_PRE_EVAL_RANK
-                )
+            if nodes
+                .iter()
+                .any(|node| matches!(node, Node::Push(val) if !limits.allows(val)))
+            {
+                return false;
+            }
+            // Convergence and matching may depend on the runtime's tolerance
+            if nodes.iter().enumerate().any(|(i, node)| match node {
+                Node::Prim(Primitive::Match, _)
+                | Node::ImplMod(ImplPrimitive::RepeatCountConvergence, ..) => true,
+                Node::Mod(Primitive::Repeat, ..)
+                | Node::ImplMod(ImplPrimitive::RepeatWithInverse, ..) => {
+                    match i.checked_sub(1).map(|i| &nodes[i]) {
+                        Some(Node::Prim(Primitive::Infinity, _)) => true,
+                        Some(Node::Push(Value::Num(n))) => n.data.iter().any(|n| n.is_infinite()),
+                        _ => false,
+                    }
+                }
+                _ => false,
             }) {
                 return false;
             }
             let len = visited.len();
             let matches = nodes.iter().all(|node| match node {
-                Node::Run(nodes) => nodes.iter().all(|node| recurse(mode, node, asm, visited)),
+                Node::Run(nodes) => nodes
+                    .iter()
+                    .all(|node| recurse(mode, limits, node, asm, visited)),
                 Node::Mod(prim, args, _) => {
                     prim.purity() == Purity::Pure
-                        && args.iter().all(|sn| recurse(mode, &sn.node, asm, visited))
+                        && args
+                            .iter()
+                            .all(|sn| recurse(mode, limits, &sn.node, asm, visited))
                 }
                 Node::ImplMod(prim, args, _) => {
                     prim.purity() == Purity::Pure
-                        && args.iter().all(|sn| recurse(mode, &sn.node, asm, visited))
+                        && args
+                            .iter()
+                            .all(|sn| recurse(mode, limits, &sn.node, asm, visited))
                 }
                 Node::NoInline(_) => false,
-                Node::Array { inner, .. } => recurse(mode, inner, asm, visited),
-                Node::Call(func, _) => recurse(mode, &asm[func], asm, visited),
+                Node::Array { inner, .. } => recurse(mode, limits, inner, asm, visited),
+                Node::Call(func, _) => recurse(mode, limits, &asm[func], asm, visited),
                 node => {
                     node.is_limit_bounded(asm)
                         && match mode {
@@ -77,13 +123,13 @@ impl PreEvalMode {
             visited.truncate(len);
             matches
         }
-        recurse(*self, nodes, asm, &mut IndexSet::new())
+        recurse(*self, limits, nodes, asm, &mut IndexSet::new())
     }
 }
 
 impl Compiler {
-    fn can_pre_eval(&self, nodes: &[Node]) -> bool {
-        self.pre_eval_mode.matches_nodes(nodes, &self.asm)
+    fn can_pre_eval(&self, nodes: &[Node], limits: PreEvalLimits) -> bool {
+        self.pre_eval_mode.matches_nodes(nodes, limits, &self.asm)
     }
     pub(super) fn pre_eval(&self, node: &Node) -> Option<(Node, Vec<UiuaError>)> {
         let mut errors = Vec::new();
@@ -99,12 +145,12 @@ impl Compiler {
         'start: while start < node.len() {
             for end in (start + 1..=node.len()).rev() {
                 let section = &node[start..end];
-                if self.can_pre_eval(section)
+                if self.can_pre_eval(section, PreEvalLimits::INLINE)
                     && nodes_clean_sig(section).is_some_and(|sig| sig.args == 0 && sig.outputs > 0)
                 {
                     // println!("section: {section:?}");
                     let mut success = false;
-                    match self.comptime_node(&section.into()) {
+                    match self.comptime_node(&section.into(), PreEvalLimits::INLINE) {
                         Ok(Some(values)) => {
                             // println!("values: {values:?}");
                             for val in &values {
@@ -137,7 +183,11 @@ impl Compiler {
         }
         new.map(|new| (new, errors))
     }
-    pub(super) fn comptime_node(&self, node: &Node) -> UiuaResult<Option<Vec<Value>>> {
+    pub(super) fn comptime_node(
+        &self,
+        node: &Node,
+        limits: PreEvalLimits,
+    ) -> UiuaResult<Option<Vec<Value>>> {
         if node.iter().all(|node| matches!(node, Node::Push(_))) {
             return Ok(Some(
                 node.iter()
@@ -148,14 +198,16 @@ impl Compiler {
                     .collect(),
             ));
         }
-        if !self.can_pre_eval(node) {
+        if !self.can_pre_eval(node, limits) {
             return Ok(None);
         }
+        type Cache = HashMap<(Node, PreEvalLimits), Option<Vec<Value>>>;
         thread_local! {
-            static CACHE: RefCell<HashMap<Node, Option<Vec<Value>>>> = RefCell::new(HashMap::new());
+            static CACHE: RefCell<Cache> = RefCell::new(HashMap::new());
         }
         CACHE.with(|cache| {
-            if let Some(stack) = cache.borrow_mut().get(node) {
+            let key = (node.clone(), limits);
+            if let Some(stack) = cache.borrow_mut().get(&key) {
                 return Ok(stack.clone());
             }
             let mut asm = self.asm.clone();
@@ -170,22 +222,23 @@ impl Compiler {
             } else {
                 Uiua::with_safe_sys()
             }
-            .with_execution_limit(Duration::from_millis(40));
+            .with_execution_limit(limits.time);
             match env.run_asm(asm) {
                 Ok(()) => {
                     let stack = env.take_stack();
-                    let res = if stack.iter().any(|v| {
-                        v.element_count() > MAX_PRE_EVAL_ELEMS || v.rank() > MAX_PRE_EVAL_RANK
-                    }) {
-                        None
-                    } else {
+                    let res = if stack.iter().all(|v| limits.allows(v)) {
                         Some(stack)
+                    } else {
+                        None
                     };
-                    cache.borrow_mut().insert(env.asm.root, res.clone());
+                    // Large binding values are stored in the assembly, so they are not kept here too
+                    if (res.iter().flatten()).all(|v| PreEvalLimits::INLINE.allows(v)) {
+                        cache.borrow_mut().insert(key, res.clone());
+                    }
                     Ok(res)
                 }
                 Err(e) if matches!(e.kind, UiuaErrorKind::Timeout(..)) => {
-                    cache.borrow_mut().insert(env.asm.root, None);
+                    cache.borrow_mut().insert(key, None);
                     Ok(None)
                 }
                 Err(e) => Err(e),
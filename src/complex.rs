@@ -89,6 +89,13 @@ impl Complex {
         // Do not use `self.re.hypot(self.im)` because it is slower, especially on WASM
         (self.re * self.re + self.im * self.im).sqrt()
     }
+    /// Get the complex conjugate of a complex number
+    pub fn conj(self) -> Self {
+        Self {
+            re: self.re,
+            im: -self.im,
+        }
+    }
     /// Get the arctangent of a complex number
     pub fn atan2(self, x: impl Into<Self>) -> Complex {
         let y = self;
@@ -30,6 +30,10 @@ use ecow::EcoVec;
 ///
 /// `CowSlice`s are reference-counted buffers that also have associated start and end indices.
 /// This allows them to be split into chunks without copying the data.
+///
+/// A buffer that is not shared can grow at either end in amortized O(1) time,
+/// so accumulating into an array in a loop does not copy it on every iteration.
+/// A shared buffer is copied once, after which the copy is unique.
 pub struct CowSlice<T> {
     data: EcoVec<T>,
     start: usize,
@@ -92,7 +96,7 @@ impl<T: Clone> CowSlice<T> {
         &mut self.data.make_mut()[self.start..self.end]
     }
     pub fn extend_from_slice(&mut self, other: &[T]) {
-        self.modify(|vec| vec.extend_from_slice(other))
+        self.modify_end(other.len(), |vec| vec.extend_from_slice(other))
     }
     #[track_caller]
     pub fn slice<R>(&self, range: R) -> Self
@@ -156,22 +160,96 @@ impl<T: Clone> CowSlice<T> {
             res
         }
     }
+    /// Modify the buffer past the end of the slice
+    ///
+    /// If the buffer has to be copied, room is made for `additional` more elements
+    /// so that the copy does not have to grow again. The copy is still given at
+    /// least double the length so that repeated appends stay amortized.
     #[track_caller]
-    fn modify_end<F, R>(&mut self, f: F) -> R
+    fn modify_end<F, R>(&mut self, additional: usize, f: F) -> R
     where
         F: FnOnce(&mut EcoVec<T>) -> R,
     {
+        if self.data.is_unique() {
+            // Nothing else can see elements past the end
+            self.data.truncate(self.end);
+            // Reclaim space at the front once it is more than half the buffer
+            if self.start * 2 > self.data.len() {
+                self.data.make_mut().rotate_left(self.start);
+                self.data.truncate(self.len());
+                self.end = self.len();
+                self.start = 0;
+            }
+        }
         if self.data.is_unique() && self.end == self.data.len() {
             let res = f(&mut self.data);
             self.end = self.data.len();
             res
         } else {
-            let mut vec = EcoVec::from(&**self);
+            let capacity = (self.len().saturating_add(additional)).max(self.len() * 2);
+            let mut vec = EcoVec::with_capacity(capacity);
+            vec.extend_from_slice(self);
             let res = f(&mut vec);
             *self = vec.into();
             res
         }
     }
+    /// Insert elements at the front of the buffer
+    ///
+    /// Extra space is left before the start so that repeated front
+    /// insertions are amortized O(1)
+    pub fn extend_front_from_slice(&mut self, other: &[T]) {
+        self.extend_front_repeat_slice(other, 1);
+    }
+    /// Prepend `count` copies of a slice
+    ///
+    /// If the buffer is unique and there is enough room before the start, no allocation is made
+    pub fn extend_front_repeat_slice(&mut self, slice: &[T], count: usize) {
+        let Some(first) = slice.first() else {
+            return;
+        };
+        let added = slice.len() * count;
+        if added == 0 {
+            return;
+        }
+        if self.data.is_unique() && self.start >= added {
+            let start = self.start - added;
+            for chunk in self.data.make_mut()[start..self.start].chunks_exact_mut(slice.len()) {
+                chunk.clone_from_slice(slice);
+            }
+            self.start = start;
+        } else {
+            let slack = self.len() / 2;
+            let mut vec = EcoVec::with_capacity(slack + added + self.len());
+            extend_repeat(&mut vec, first, slack);
+            extend_repeat_slice(&mut vec, slice, count);
+            vec.extend_from_slice(self);
+            self.end = vec.len();
+            self.start = slack;
+            self.data = vec;
+        }
+    }
+    /// Rotate the elements to the left by `mid`
+    ///
+    /// If the buffer is unique, only the shorter side is moved
+    pub fn rotate_left(&mut self, mid: usize) {
+        let len = self.len();
+        assert!(mid <= len);
+        if mid == 0 || mid == len {
+            return;
+        }
+        if !self.data.is_unique() {
+            self.as_mut_slice().rotate_left(mid);
+        } else if mid <= len / 2 {
+            let front = self[..mid].to_vec();
+            self.start += mid;
+            self.extend_from_vec(front);
+        } else {
+            let back = self[mid..].to_vec();
+            self.truncate(mid);
+            self.extend_front_from_slice(&back);
+        }
+    }
     /// Clear the buffer
     pub fn clear(&mut self) {
         if self.is_unique() {
@@ -184,12 +262,14 @@ impl<T: Clone> CowSlice<T> {
     }
     /// Reserve space for at least `additional` more elements
     pub fn reserve(&mut self, additional: usize) {
-        self.modify_end(|vec| vec.reserve(additional))
+        self.modify_end(additional, |vec| vec.reserve(additional))
     }
     /// Ensure that the capacity is at least `min`
     pub fn reserve_min(&mut self, min: usize) {
         if self.data.capacity() < min {
-            self.modify_end(|vec| vec.reserve(min - vec.len()))
+            self.modify_end(min.saturating_sub(self.len()), |vec| {
+                vec.reserve(min - vec.len())
+            })
         }
     }
     pub fn split_off(&mut self, at: usize) -> Self {
@@ -221,27 +301,29 @@ impl<T: Clone> CowSlice<T> {
     }
     #[track_caller]
     pub fn extend_from_array<const N: usize>(&mut self, array: [T; N]) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(array) })
+        self.modify_end(N, |data| unsafe { data.extend_from_trusted(array) })
     }
     #[track_caller]
     pub fn extend_from_vec(&mut self, vec: Vec<T>) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(vec) })
+        self.modify_end(vec.len(), |data| unsafe { data.extend_from_trusted(vec) })
     }
     #[track_caller]
     pub fn extend_from_ecovec(&mut self, vec: EcoVec<T>) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(vec) })
+        self.modify_end(vec.len(), |data| unsafe { data.extend_from_trusted(vec) })
     }
     #[track_caller]
     pub fn extend_from_cowslice(&mut self, slice: CowSlice<T>) {
-        self.modify_end(|data| unsafe { data.extend_from_trusted(slice) })
+        self.extend_from_slice(&slice)
     }
     #[track_caller]
     pub fn extend_repeat(&mut self, elem: &T, count: usize) {
-        self.modify_end(|data| extend_repeat(data, elem, count))
+        self.modify_end(count, |data| extend_repeat(data, elem, count))
     }
     #[track_caller]
     pub fn extend_repeat_slice(&mut self, slice: &[T], count: usize) {
-        self.modify_end(|data| extend_repeat_slice(data, slice, count))
+        self.modify_end(slice.len().saturating_mul(count), |data| {
+            extend_repeat_slice(data, slice, count)
+        })
     }
     #[track_caller]
     pub unsafe fn extend_from_trusted<I>(&mut self, iter: I)
@@ -249,7 +331,8 @@ impl<T: Clone> CowSlice<T> {
         I: IntoIterator<Item = T>,
         I::IntoIter: ExactSizeIterator,
     {
-        self.modify_end(|data| data.extend_from_trusted(iter))
+        let iter = iter.into_iter();
+        self.modify_end(iter.len(), |data| data.extend_from_trusted(iter))
     }
 }
 
@@ -332,6 +415,92 @@ impl<T> Deref for CowSlice<T> {
     }
 }
 
+#[test]
+fn cow_slice_deque() {
+    let mut slice = CowSlice::from([3, 4]);
+    slice.extend_front_from_slice(&[2]);
+    slice.extend_front_from_slice(&[0, 1]);
+    slice.extend_from_slice(&[5]);
+    assert_eq!(slice, [0, 1, 2, 3, 4, 5]);
+
+    let shared = slice.clone();
+    slice.rotate_left(2);
+    assert_eq!(slice, [2, 3, 4, 5, 0, 1]);
+    slice.rotate_left(5);
+    assert_eq!(slice, [1, 2, 3, 4, 5, 0]);
+    assert_eq!(shared, [0, 1, 2, 3, 4, 5]);
+
+    for i in 0..100 {
+        slice = slice.slice(1..);
+        slice.extend_from_slice(&[i]);
+    }
+    assert_eq!(slice, [94, 95, 96, 97, 98, 99]);
+    assert!(slice.data.len() <= 2 * slice.len() + 1);
+
+    // Shifting in at the front reuses the slack before the start
+    let mut slice = CowSlice::from([1, 2, 3, 4]);
+    slice.extend_front_repeat_slice(&[0], 4);
+    assert_eq!(slice, [0, 0, 0, 0, 1, 2, 3, 4]);
+    let mut reallocations = 0;
+    for i in 0..100 {
+        let ptr = slice.data.as_ptr();
+        slice.truncate(slice.len() - 2);
+        slice.extend_front_repeat_slice(&[i], 2);
+        if slice.data.as_ptr() != ptr {
+            reallocations += 1;
+        }
+    }
+    assert_eq!(slice, [99, 99, 98, 98, 97, 97, 96, 96]);
+    assert!(reallocations <= 50, "{reallocations} reallocations");
+}
+
+#[test]
+fn cow_slice_accumulate() {
+    let mut slice = CowSlice::<String>::new();
+    let mut reallocations = 0;
+    for i in 0..1000 {
+        let ptr = slice.data.as_ptr();
+        if i % 2 == 0 {
+            slice.extend_from_slice(&[i.to_string()]);
+        } else {
+            slice.extend_front_from_slice(&[i.to_string()]);
+        }
+        if slice.data.as_ptr() != ptr {
+            reallocations += 1;
+        }
+    }
+    assert_eq!(slice.len(), 1000);
+    assert_eq!(slice.first().unwrap(), "999");
+    assert_eq!(slice.last().unwrap(), "998");
+    assert!(reallocations < 40, "{reallocations} reallocations");
+
+    // Only the first append after sharing copies
+    let shared = slice.clone();
+    slice.extend_from_slice(&["a".into()]);
+    let ptr = slice.data.as_ptr();
+    slice.extend_from_slice(&["b".into()]);
+    assert_eq!(slice.data.as_ptr(), ptr);
+    assert_eq!(shared.len(), 1000);
+    assert_eq!(slice.len(), 1002);
+}
+
+#[test]
+fn cow_slice_shared_extend() {
+    // Extending a shared slice copies it once into a buffer with room for both parts
+    let mut slice = CowSlice::from([1, 2, 3]);
+    let shared = slice.clone();
+    slice.extend_from_slice(&[4, 5, 6, 7, 8]);
+    assert_eq!(slice, [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(shared, [1, 2, 3]);
+    assert_eq!(slice.data.capacity(), 8);
+
+    let mut sub = shared.slice(2..);
+    sub.extend_from_cowslice(shared.clone());
+    assert_eq!(sub, [3, 1, 2, 3]);
+    assert_eq!(sub.data.capacity(), 4);
+    assert_eq!(shared, [1, 2, 3]);
+}
+
 #[test]
 fn cow_slice_deref_mut() {
     let mut slice = CowSlice::from([1, 2, 3, 4]);
@@ -520,7 +689,8 @@ impl<T: Clone> FromIterator<T> for CowSlice<T> {
 
 impl<T: Clone> Extend<T> for CowSlice<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        self.modify_end(|vec| vec.extend(iter))
+        let iter = iter.into_iter();
+        self.modify_end(iter.size_hint().0, |vec| vec.extend(iter))
     }
 }
 
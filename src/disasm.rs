@@ -0,0 +1,206 @@
+//! Disassemble compiled functions
+//!
+//! Each instruction is shown on its own line with its signature and the line and column of the code it was compiled from.
+//! The operands of modifiers and the bodies of arrays, switches, and other nested nodes are indented below the instruction that contains them.
+//!
+//! Because the disassembly shows the code after optimization, it can be used to check which optimizations were applied to a function.
+
+use std::fmt::Write;
+
+use crate::{Assembly, BindingKind, Node, SigNode, Span};
+
+/// The maximum width of a pushed value before it is summarized
+const MAX_VALUE_WIDTH: usize = 40;
+
+/// Disassemble the functions bound in an assembly
+///
+/// If a name is given, only bindings with that name are disassembled.
+/// Returns an error if there are no such bindings.
+pub fn disassemble(asm: &Assembly, name: Option<&str>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut found = false;
+    for binding in &asm.bindings {
+        let binding_name = binding.span.as_str(&asm.inputs, |s| s.to_string());
+        if name.is_some_and(|name| name != binding_name) {
+            continue;
+        }
+        let BindingKind::Func(f) = &binding.kind else {
+            if name.is_some() {
+                return Err(format!("{binding_name} is not a function"));
+            }
+            continue;
+        };
+        found = true;
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let sn = SigNode::new(f.sig, asm[f].clone());
+        out.push_str(&disassemble_function(
+            asm,
+            &binding_name,
+            &sn,
+            &binding.span,
+        ));
+    }
+    match name {
+        Some(name) if !found => Err(format!("No function named {name} was found")),
+        _ => Ok(out),
+    }
+}
+
+/// Disassemble a single function
+fn disassemble_function(
+    asm: &Assembly,
+    name: &str,
+    sn: &SigNode,
+    span: &impl std::fmt::Display,
+) -> String {
+    let mut lines = Vec::new();
+    disassemble_node(asm, &sn.node, 1, &mut lines);
+    let width = lines
+        .iter()
+        .map(|line| line.indent * 2 + line.text.chars().count())
+        .max()
+        .unwrap_or(0);
+    let sig_width = (lines.iter())
+        .map(|line| line.sig.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    _ = writeln!(out, "{name} {} at {span}", sn.sig);
+    for line in lines {
+        let indent = line.indent * 2;
+        let pad = width - indent - line.text.chars().count();
+        let mut row = format!(
+            "{:indent$}{}{:pad$}  {:sig_width$}  {}",
+            "", line.text, "", line.sig, line.loc
+        );
+        row.truncate(row.trim_end().len());
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+/// A line of disassembly
+struct Line {
+    indent: usize,
+    text: String,
+    sig: String,
+    loc: String,
+}
+
+fn disassemble_node(asm: &Assembly, node: &Node, indent: usize, lines: &mut Vec<Line>) {
+    let mut line = |text: String, node: &Node| {
+        let sig = node
+            .sig()
+            .map(|sig| sig.to_string())
+            .unwrap_or_else(|_| "?".into());
+        let loc = match node.span().and_then(|i| asm.spans.get(i)) {
+            Some(Span::Code(span)) => span.start.to_string(),
+            _ => String::new(),
+        };
+        lines.push(Line {
+            indent,
+            text,
+            sig,
+            loc,
+        });
+    };
+    match node {
+        Node::Run(nodes) => {
+            for node in nodes {
+                disassemble_node(asm, node, indent, lines);
+            }
+        }
+        Node::Push(val) => {
+            let mut text = format!("push {val}");
+            if text.contains('\n') || text.chars().count() > MAX_VALUE_WIDTH {
+                text = format!("push {} {}", val.shape(), val.type_name_plural());
+            }
+            line(text, node);
+        }
+        Node::Prim(prim, _) => line(prim.to_string(), node),
+        Node::ImplPrim(prim, _) => line(prim.to_string(), node),
+        Node::Mod(_, args, _) | Node::ImplMod(_, args, _) => {
+            let text = match node {
+                Node::Mod(prim, ..) => prim.to_string(),
+                Node::ImplMod(prim, ..) => prim.to_string(),
+                _ => unreachable!(),
+            };
+            line(text, node);
+            disassemble_sig_nodes(asm, "function", args, indent + 1, lines);
+        }
+        Node::Array {
+            len, inner, boxed, ..
+        } => {
+            let text = if *boxed {
+                format!("box array {len}")
+            } else {
+                format!("array {len}")
+            };
+            line(text, node);
+            disassemble_node(asm, inner, indent + 1, lines);
+        }
+        Node::Switch { branches, .. } => {
+            line(format!("switch {}", branches.len()), node);
+            disassemble_sig_nodes(asm, "branch", branches, indent + 1, lines);
+        }
+        Node::CustomInverse(cust, _) => {
+            line("custom inverse".into(), node);
+            if let Ok(normal) = &cust.normal {
+                disassemble_sig_node(asm, normal, indent + 1, lines);
+            }
+        }
+        Node::WithLocal { inner, .. } => {
+            line("with local".into(), node);
+            disassemble_sig_node(asm, inner, indent + 1, lines);
+        }
+        Node::NoInline(inner) | Node::TrackCaller(inner) => {
+            disassemble_node(asm, inner, indent, lines)
+        }
+        Node::Call(func, _) => line(format!("call {}", func.id), node),
+        node => line(format!("{node:?}"), node),
+    }
+}
+
+/// Disassemble the operands of a node
+///
+/// If there are multiple operands, each is put under a numbered header.
+fn disassemble_sig_nodes(
+    asm: &Assembly,
+    kind: &str,
+    sns: &[SigNode],
+    indent: usize,
+    lines: &mut Vec<Line>,
+) {
+    if let [sn] = sns {
+        disassemble_sig_node(asm, sn, indent, lines);
+        return;
+    }
+    for (i, sn) in sns.iter().enumerate() {
+        lines.push(Line {
+            indent,
+            text: format!("{kind} {i}"),
+            sig: sn.sig.to_string(),
+            loc: String::new(),
+        });
+        disassemble_sig_node(asm, sn, indent + 1, lines);
+    }
+}
+
+/// Disassemble a node in its own block
+///
+/// Empty blocks are shown as identity so that every operand has a line.
+fn disassemble_sig_node(asm: &Assembly, sn: &SigNode, indent: usize, lines: &mut Vec<Line>) {
+    if sn.node.as_slice().is_empty() {
+        lines.push(Line {
+            indent,
+            text: "(identity)".into(),
+            sig: sn.sig.to_string(),
+            loc: String::new(),
+        });
+    } else {
+        disassemble_node(asm, &sn.node, indent, lines);
+    }
+}
@@ -0,0 +1,63 @@
+//! Language editions
+
+use std::{fmt, str::FromStr};
+
+use serde::*;
+
+/// A set of language behaviors that a file opts into
+///
+/// Files select an edition with a `# Edition! <year>` comment. Files without one
+/// use [`Edition::E2024`], so existing programs keep their behavior when
+/// later editions change the meaning of something.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Edition {
+    /// The original edition
+    #[default]
+    E2024,
+    /// Deprecated primitives and syntax are errors instead of warnings,
+    /// and old glyphs that were kept for compatibility are no longer accepted
+    E2025,
+}
+
+impl Edition {
+    /// All editions, from oldest to newest
+    pub const ALL: [Self; 2] = [Edition::E2024, Edition::E2025];
+    /// The newest edition
+    pub const LATEST: Self = Edition::E2025;
+    /// The edition's year
+    pub fn year(&self) -> u16 {
+        match self {
+            Edition::E2024 => 2024,
+            Edition::E2025 => 2025,
+        }
+    }
+    /// Whether deprecated primitives, glyphs, and syntax are errors
+    pub fn removes_deprecated(&self) -> bool {
+        *self >= Edition::E2025
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.year())
+    }
+}
+
+impl FromStr for Edition {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Edition::ALL
+            .into_iter()
+            .find(|edition| edition.year().to_string() == s.trim())
+            .ok_or_else(|| {
+                let known: Vec<_> = Edition::ALL.iter().map(|e| e.to_string()).collect();
+                format!(
+                    "Unknown edition {:?}. Known editions are {}",
+                    s.trim(),
+                    known.join(", ")
+                )
+            })
+    }
+}
@@ -7,12 +7,13 @@ use crate::{
     lex::{Sp, Span},
     parse::ParseError,
     value::Value,
-    CodeSpan, Ident, InputSrc, Inputs,
+    Boxed, CodeSpan, Ident, InputSrc, Inputs, MessageId, Uiua,
 };
 
 /// An error produced when running/compiling/formatting a Uiua program
 #[derive(Debug, Clone)]
 #[must_use]
+#[non_exhaustive]
 pub struct UiuaError {
     /// The kind of error
     pub kind: UiuaErrorKind,
@@ -22,10 +23,14 @@ pub struct UiuaError {
     pub is_fill: bool,
     /// Whether the error can escape a single `try`
     pub is_case: bool,
+    /// The category of the error
+    pub category: ErrorCategory,
     /// Bundled errors
     pub multi: Vec<Self>,
     /// Additional info about the error
     pub infos: Vec<(String, Option<Span>)>,
+    /// The stable id of the error's message, if it was localized
+    pub message_id: Option<MessageId>,
 }
 
 /// The kind of an error produced when running/compiling/formatting a Uiua program
@@ -50,28 +55,63 @@ pub enum UiuaErrorKind {
     Throw(Box<Value>, Span, Box<Inputs>),
     /// Maximum execution time exceeded
     Timeout(Span, Box<Inputs>),
+    /// The fuel budget was used up
+    OutOfFuel(Span, Box<Inputs>),
     /// The compiler panicked
     CompilerPanic(String),
     /// The program was interrupted
     Interrupted,
 }
 
+/// A broad category of error
+///
+/// This can be used to tell user errors apart from interpreter bugs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The code could not be parsed
+    Parse,
+    /// A function's signature could not be inferred or did not match its declaration
+    Signature,
+    /// An error occurred while compiling or executing a program
+    Runtime,
+    /// An error occurred while interacting with the system
+    System,
+    /// An execution limit was reached or the program was interrupted
+    Limit,
+    /// The interpreter itself has a bug
+    Bug,
+}
+
 impl UiuaErrorKind {
     /// Turn the error kind into an error
     pub fn error(self) -> UiuaError {
         self.into()
     }
+    /// Get the default category of errors of this kind
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            UiuaErrorKind::Load(..) | UiuaErrorKind::Format(..) => ErrorCategory::System,
+            UiuaErrorKind::Parse(..) => ErrorCategory::Parse,
+            UiuaErrorKind::Run { .. } | UiuaErrorKind::Throw(..) => ErrorCategory::Runtime,
+            UiuaErrorKind::Timeout(..)
+            | UiuaErrorKind::OutOfFuel(..)
+            | UiuaErrorKind::Interrupted => ErrorCategory::Limit,
+            UiuaErrorKind::CompilerPanic(_) => ErrorCategory::Bug,
+        }
+    }
 }
 
 impl From<UiuaErrorKind> for UiuaError {
     fn from(kind: UiuaErrorKind) -> Self {
         Self {
+            category: kind.category(),
             kind,
             trace: Vec::new(),
             is_fill: false,
             is_case: false,
             multi: Vec::new(),
             infos: Vec::new(),
+            message_id: None,
         }
     }
 }
@@ -104,8 +144,12 @@ impl fmt::Display for UiuaError {
                 Ok(())
             }
             UiuaErrorKind::Run { message: error, .. } => write!(f, "{error}"),
-            UiuaErrorKind::Throw(value, span, _) => write!(f, "{span}: {value}"),
+            UiuaErrorKind::Throw(value, span, _) => match value.error_parts() {
+                Some((message, _)) => write!(f, "{span}: {message}"),
+                None => write!(f, "{span}: {value}"),
+            },
             UiuaErrorKind::Timeout(..) => write!(f, "Maximum execution time exceeded"),
+            UiuaErrorKind::OutOfFuel(..) => write!(f, "Out of fuel"),
             UiuaErrorKind::CompilerPanic(message) => message.fmt(f),
             UiuaErrorKind::Interrupted => write!(f, "# Program interrupted"),
         }
@@ -141,10 +185,71 @@ impl UiuaError {
         error
     }
     /// Mark the error as fill-related
+    /// Set the stable id of the error's message
+    pub(crate) fn with_message_id(mut self, id: MessageId) -> Self {
+        self.message_id = Some(id);
+        self
+    }
     pub(crate) fn fill(mut self) -> Self {
         self.is_fill = true;
         self
     }
+    fn categorize(mut self, category: ErrorCategory) -> Self {
+        if let UiuaErrorKind::Run { .. } = self.kind {
+            self.category = category;
+        }
+        self
+    }
+    /// Mark the error as signature-related
+    pub(crate) fn signature(self) -> Self {
+        self.categorize(ErrorCategory::Signature)
+    }
+    /// Mark the error as system-related
+    pub(crate) fn system(self) -> Self {
+        self.categorize(ErrorCategory::System)
+    }
+    /// Mark the error as caused by an execution limit
+    pub(crate) fn limit(self) -> Self {
+        self.categorize(ErrorCategory::Limit)
+    }
+    /// Mark the error as caused by an interpreter crash
+    pub(crate) fn bug(self) -> Self {
+        self.categorize(ErrorCategory::Bug)
+    }
+    /// Check if the error is caused by a bug in the interpreter rather than by user code
+    pub fn is_bug(&self) -> bool {
+        self.category == ErrorCategory::Bug
+    }
+    /// Get the span where the error occurred, if it has one
+    pub fn span(&self) -> Option<Span> {
+        match &self.kind {
+            UiuaErrorKind::Parse(errors, _) => errors.first().map(|e| e.span.clone().into()),
+            UiuaErrorKind::Run { message, .. } => Some(message.span.clone()),
+            UiuaErrorKind::Throw(_, span, _)
+            | UiuaErrorKind::Timeout(span, _)
+            | UiuaErrorKind::OutOfFuel(span, _) => Some(span.clone()),
+            _ => None,
+        }
+    }
+    /// Get the error message without its location
+    pub fn message(&self) -> String {
+        match &self.kind {
+            UiuaErrorKind::Parse(errors, _) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.value.to_string()).collect();
+                messages.join("\n")
+            }
+            UiuaErrorKind::Run { message, .. } => message.value.clone(),
+            UiuaErrorKind::Throw(value, ..) => match value.error_parts() {
+                Some((message, _)) => message,
+                None => value.to_string(),
+            },
+            _ => self.to_string(),
+        }
+    }
+    /// Get the call trace of the error, from innermost to outermost
+    pub fn call_trace(&self) -> &[TraceFrame] {
+        &self.trace
+    }
     /// Add a span to the trace of the error
     pub fn trace(mut self, span: CodeSpan) -> Self {
         let frame = TraceFrame {
@@ -255,6 +360,41 @@ fn format_trace(trace: &[TraceFrame]) -> Vec<String> {
     lines
 }
 
+impl Value {
+    /// Create a structured error value from a message and a payload
+    ///
+    /// The result is a map with `message` and `payload` keys
+    pub fn error_value(message: Value, payload: Value, env: &Uiua) -> UiuaResult<Value> {
+        let message = message.as_string(env, "Error message must be a string")?;
+        let keys = Value::from(["message", "payload"].map(|key| Boxed(key.into())));
+        let mut value = Value::from([Boxed(message.into()), Boxed(payload)]);
+        value.map(keys, env)?;
+        Ok(value)
+    }
+    /// Get the message and payload of a structured error value
+    pub fn error_parts(&self) -> Option<(String, Value)> {
+        if !self.is_map() {
+            return None;
+        }
+        let mut message = None;
+        let mut payload = None;
+        for (key, value) in self.map_kv() {
+            match key.as_string_opt()?.as_str() {
+                "message" => message = Some(value.unboxed().as_string_opt()?),
+                "payload" => payload = Some(value.unboxed()),
+                _ => return None,
+            }
+        }
+        message.zip(payload)
+    }
+    /// Split a structured error value into its message and payload
+    pub fn unerror(&self, env: &Uiua) -> UiuaResult<(Value, Value)> {
+        let (message, payload) =
+            (self.error_parts()).ok_or_else(|| env.error("Value is not an error value"))?;
+        Ok((message.into(), payload))
+    }
+}
+
 impl Error for UiuaError {}
 
 impl From<Infallible> for UiuaError {
@@ -295,14 +435,18 @@ impl UiuaError {
                 }
                 report
             }
-            UiuaErrorKind::Throw(message, span, inputs) => {
-                Report::new_multi(kind, inputs, [(&message, span.clone())])
-            }
+            UiuaErrorKind::Throw(value, span, inputs) => match value.error_parts() {
+                Some((message, _)) => Report::new_multi(kind, inputs, [(&message, span.clone())]),
+                None => Report::new_multi(kind, inputs, [(&value, span.clone())]),
+            },
             UiuaErrorKind::Timeout(span, inputs) => Report::new_multi(
                 kind,
                 inputs,
                 [("Maximum execution time exceeded", span.clone())],
             ),
+            UiuaErrorKind::OutOfFuel(span, inputs) => {
+                Report::new_multi(kind, inputs, [("Out of fuel", span.clone())])
+            }
             UiuaErrorKind::CompilerPanic(message) => Report::new(kind, message),
             UiuaErrorKind::Load(..) | UiuaErrorKind::Format(..) => {
                 Report::new(kind, self.to_string())
@@ -320,7 +464,8 @@ impl UiuaError {
             UiuaErrorKind::Parse(_, inputs)
             | UiuaErrorKind::Run { inputs, .. }
             | UiuaErrorKind::Throw(_, _, inputs)
-            | UiuaErrorKind::Timeout(_, inputs) => inputs,
+            | UiuaErrorKind::Timeout(_, inputs)
+            | UiuaErrorKind::OutOfFuel(_, inputs) => inputs,
             _ => &default_inputs,
         };
         for (info, span) in &self.infos {
@@ -0,0 +1,277 @@
+//! An interactive terminal viewer for large arrays
+
+use crate::{SysBackend, Value};
+
+/// Rows with more elements than this are summarized by their shape
+const MAX_FORMATTED_ELEMENTS: usize = 1000;
+
+/// An action in the explorer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerKey {
+    /// Select the previous row
+    Up,
+    /// Select the next row
+    Down,
+    /// Move up a page
+    PageUp,
+    /// Move down a page
+    PageDown,
+    /// Select the first row
+    Top,
+    /// Select the last row
+    Bottom,
+    /// Drill into the selected row
+    Open,
+    /// Go back to the parent array
+    Back,
+    /// Close the explorer
+    Quit,
+}
+
+struct Level {
+    value: Value,
+    label: String,
+    selected: usize,
+    scroll: usize,
+}
+
+impl Level {
+    fn new(value: Value, label: String) -> Self {
+        Level {
+            value: value.unboxed(),
+            label,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+    fn row_count(&self) -> usize {
+        if self.value.rank() == 0 {
+            1
+        } else {
+            self.value.row_count()
+        }
+    }
+}
+
+/// The state of an array explorer
+///
+/// The explorer shows one row of the current array per line.
+/// Rows can be opened to view them on their own, and boxes are opened automatically.
+pub struct Explorer {
+    levels: Vec<Level>,
+    page_height: usize,
+}
+
+impl Explorer {
+    /// Create a new explorer for a value
+    pub fn new(value: Value) -> Self {
+        Explorer {
+            levels: vec![Level::new(value, String::new())],
+            page_height: 10,
+        }
+    }
+    /// Get the value currently being viewed
+    pub fn current(&self) -> &Value {
+        &self.level().value
+    }
+    /// Get the index of the selected row
+    pub fn selected(&self) -> usize {
+        self.level().selected
+    }
+    /// Get the indices of the rows that were opened to reach the current value
+    pub fn path(&self) -> String {
+        (self.levels.iter().skip(1))
+            .map(|level| level.label.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    fn level(&self) -> &Level {
+        self.levels.last().expect("explorer should have a level")
+    }
+    fn level_mut(&mut self) -> &mut Level {
+        self.levels
+            .last_mut()
+            .expect("explorer should have a level")
+    }
+    /// Handle a key
+    ///
+    /// Returns `false` if the explorer should close
+    pub fn handle(&mut self, key: ExplorerKey) -> bool {
+        let page = self.page_height.max(1);
+        let level = self.level_mut();
+        let last = level.row_count().saturating_sub(1);
+        match key {
+            ExplorerKey::Up => level.selected = level.selected.saturating_sub(1),
+            ExplorerKey::Down => level.selected = (level.selected + 1).min(last),
+            ExplorerKey::PageUp => level.selected = level.selected.saturating_sub(page),
+            ExplorerKey::PageDown => level.selected = (level.selected + page).min(last),
+            ExplorerKey::Top => level.selected = 0,
+            ExplorerKey::Bottom => level.selected = last,
+            ExplorerKey::Open => {
+                if level.value.rank() > 0 && level.value.row_count() > 0 {
+                    let row = level.value.row(level.selected);
+                    let label = level.selected.to_string();
+                    self.levels.push(Level::new(row, label));
+                }
+            }
+            ExplorerKey::Back => {
+                if self.levels.len() > 1 {
+                    self.levels.pop();
+                }
+            }
+            ExplorerKey::Quit => return false,
+        }
+        true
+    }
+    /// Render the explorer as lines of text that fit in the given size
+    pub fn render(&mut self, width: usize, height: usize) -> Vec<String> {
+        self.page_height = height.saturating_sub(2).max(1);
+        let page = self.page_height;
+        let path = self.path();
+        let level = self.level_mut();
+        let count = level.row_count();
+        if level.selected < level.scroll {
+            level.scroll = level.selected;
+        } else if level.selected >= level.scroll + page {
+            level.scroll = level.selected + 1 - page;
+        }
+        let mut lines = Vec::with_capacity(height);
+        let header = format!(
+            "[{path}] {} {}  {}/{count}",
+            level.value.type_name(),
+            level.value.shape_string(),
+            (level.selected + 1).min(count),
+        );
+        lines.push(truncate(header, width));
+        let index_width = count.to_string().len();
+        for i in level.scroll..(level.scroll + page).min(count) {
+            let row = if level.value.rank() == 0 {
+                level.value.clone()
+            } else {
+                level.value.row(i)
+            };
+            let marker = if i == level.selected { '>' } else { ' ' };
+            let line = format!("{marker} {i:>index_width$} │ {}", summarize(&row));
+            lines.push(truncate(line, width));
+        }
+        lines.push(truncate(
+            "↑↓ move  PgUp/PgDn page  ⏎/→ open  ←/⌫ back  q quit".into(),
+            width,
+        ));
+        lines
+    }
+}
+
+/// Format a row on a single line
+fn summarize(row: &Value) -> String {
+    if row.element_count() > MAX_FORMATTED_ELEMENTS {
+        format!("{} {}", row.type_name(), row.shape_string())
+    } else {
+        row.format().replace('\n', "\\n")
+    }
+}
+
+fn truncate(s: String, width: usize) -> String {
+    if s.chars().count() <= width {
+        return s;
+    }
+    let mut s: String = s.chars().take(width.saturating_sub(1)).collect();
+    s.push('…');
+    s
+}
+
+/// Read a key from stdin
+///
+/// Returns `None` for keys the explorer does not use
+fn read_key(backend: &dyn SysBackend) -> Result<Option<ExplorerKey>, String> {
+    let read = || backend.scan_stdin(Some(1)).map(|b| b.first().copied());
+    Ok(match read()? {
+        None | Some(b'q' | 3) => Some(ExplorerKey::Quit),
+        Some(b'k') => Some(ExplorerKey::Up),
+        Some(b'j') => Some(ExplorerKey::Down),
+        Some(b'b') => Some(ExplorerKey::PageUp),
+        Some(b' ' | b'f') => Some(ExplorerKey::PageDown),
+        Some(b'g') => Some(ExplorerKey::Top),
+        Some(b'G') => Some(ExplorerKey::Bottom),
+        Some(b'\r' | b'\n' | b'l') => Some(ExplorerKey::Open),
+        Some(b'h' | 8 | 127) => Some(ExplorerKey::Back),
+        Some(0x1b) => {
+            if read()? != Some(b'[') {
+                return Ok(None);
+            }
+            match read()? {
+                Some(b'A') => Some(ExplorerKey::Up),
+                Some(b'B') => Some(ExplorerKey::Down),
+                Some(b'C') => Some(ExplorerKey::Open),
+                Some(b'D') => Some(ExplorerKey::Back),
+                Some(b'H') => Some(ExplorerKey::Top),
+                Some(b'F') => Some(ExplorerKey::Bottom),
+                Some(b'5') => read()?.map(|_| ExplorerKey::PageUp),
+                Some(b'6') => read()?.map(|_| ExplorerKey::PageDown),
+                _ => None,
+            }
+        }
+        Some(_) => None,
+    })
+}
+
+/// Explore a value interactively in the terminal
+///
+/// This uses the backend's terminal functions, so it requires raw mode support
+pub fn explore(value: Value, backend: &dyn SysBackend) -> Result<(), String> {
+    let was_raw = backend.get_raw_mode()?;
+    backend.set_raw_mode(true)?;
+    let mut explorer = Explorer::new(value);
+    let res = (|| loop {
+        let (width, height) = backend.term_size()?;
+        backend.clear_terminal()?;
+        for (i, line) in explorer.render(width, height).into_iter().enumerate() {
+            backend.set_cursor(i, 0)?;
+            if i == 0 {
+                backend.set_text_color(Some([235, 136, 68]))?;
+            }
+            backend.print_str_stdout(&line)?;
+            if i == 0 {
+                backend.set_text_color(None)?;
+            }
+        }
+        if let Some(key) = read_key(backend)? {
+            if !explorer.handle(key) {
+                break Ok(());
+            }
+        }
+    })();
+    _ = backend.clear_terminal();
+    _ = backend.set_raw_mode(was_raw);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Uiua;
+
+    #[test]
+    fn navigation() {
+        let mut env = Uiua::with_safe_sys();
+        env.run_str("{⇡3 \"ab\" [1_2 3_4]}").unwrap();
+        let mut explorer = Explorer::new(env.pop("value").unwrap());
+        let lines = explorer.render(80, 10);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("> 0"));
+        explorer.handle(ExplorerKey::Bottom);
+        explorer.handle(ExplorerKey::Open);
+        assert_eq!(explorer.path(), "2");
+        assert_eq!(explorer.current().shape(), [2, 2]);
+        explorer.handle(ExplorerKey::Down);
+        explorer.handle(ExplorerKey::Open);
+        assert_eq!(explorer.path(), "2 1");
+        assert_eq!(explorer.current().format(), "[3 4]");
+        explorer.handle(ExplorerKey::Back);
+        explorer.handle(ExplorerKey::Back);
+        explorer.handle(ExplorerKey::Back);
+        assert_eq!(explorer.path(), "");
+        assert_eq!(explorer.selected(), 2);
+        assert!(!explorer.handle(ExplorerKey::Quit));
+    }
+}
@@ -1,9 +1,42 @@
+use std::fmt;
+
 use crate::{Array, Boxed, Complex, Uiua, Value};
 
+/// The type of array a fill value can be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FillKind {
+    Num,
+    Char,
+    Complex,
+    Box,
+}
+
+impl FillKind {
+    pub fn of(val: &Value) -> Self {
+        match val {
+            Value::Num(_) | Value::Byte(_) => FillKind::Num,
+            Value::Char(_) => FillKind::Char,
+            Value::Complex(_) => FillKind::Complex,
+            Value::Box(_) => FillKind::Box,
+        }
+    }
+}
+
+impl fmt::Display for FillKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FillKind::Num => write!(f, "number"),
+            FillKind::Char => write!(f, "character"),
+            FillKind::Complex => write!(f, "complex"),
+            FillKind::Box => write!(f, "box"),
+        }
+    }
+}
+
 pub struct Fill<'a> {
     env: &'a Uiua,
-    value_fill: fn(env: &'a Uiua) -> Option<&'a Value>,
-    other_value_fill: fn(env: &'a Uiua) -> Option<&'a Value>,
+    value_fills: fn(env: &'a Uiua) -> Option<&'a [Value]>,
+    other_value_fills: fn(env: &'a Uiua) -> Option<&'a [Value]>,
     other_error: &'static str,
 }
 
@@ -11,24 +44,29 @@ impl<'a> Fill<'a> {
     pub fn new(env: &'a Uiua) -> Self {
         Self {
             env,
-            value_fill: Uiua::value_fill,
-            other_value_fill: Uiua::value_unfill,
+            value_fills: Uiua::value_fills,
+            other_value_fills: Uiua::value_unfills,
             other_error: ". An unfill is set, but not a normal fill.",
         }
     }
     pub fn new_un(env: &'a Uiua) -> Self {
         Self {
             env,
-            value_fill: Uiua::value_unfill,
-            other_value_fill: Uiua::value_fill,
+            value_fills: Uiua::value_unfills,
+            other_value_fills: Uiua::value_fills,
             other_error: ". A normal fill is set, but not an unfill.",
         }
     }
     pub fn value(&self) -> Option<&Value> {
-        (self.value_fill)(self.env)
+        (self.value_fills)(self.env)?.first()
+    }
+    /// Get the fill value of the given kind, or the first fill value if there is none
+    fn value_of(&self, kind: FillKind) -> Option<&Value> {
+        let fills = (self.value_fills)(self.env)?;
+        (fills.iter().find(|val| FillKind::of(val) == kind)).or(fills.first())
     }
     pub(crate) fn num_scalar(&self) -> Result<f64, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Num) {
             Some(Value::Num(n)) if n.rank() == 0 => Ok(n.data[0]),
             Some(Value::Num(_)) => Err(self.error(true)),
             Some(Value::Byte(n)) if n.rank() == 0 => Ok(n.data[0] as f64),
@@ -37,14 +75,14 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn num_array(&self) -> Result<Array<f64>, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Num) {
             Some(Value::Num(n)) => Ok(n.clone()),
             Some(Value::Byte(n)) => Ok(n.convert_ref()),
             _ => Err(self.error(false)),
         }
     }
     pub(crate) fn byte_scalar(&self) -> Result<u8, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Num) {
             Some(Value::Num(n))
                 if n.rank() == 0
                     && n.data[0].fract() == 0.0
@@ -60,7 +98,7 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn byte_array(&self) -> Result<Array<u8>, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Num) {
             Some(Value::Num(n))
                 if (n.data.iter()).all(|&n| n.fract() == 0.0 && (0.0..=255.0).contains(&n)) =>
             {
@@ -71,20 +109,20 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn char_scalar(&self) -> Result<char, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Char) {
             Some(Value::Char(c)) if c.rank() == 0 => Ok(c.data[0]),
             Some(Value::Char(_)) => Err(self.error(true)),
             _ => Err(self.error(false)),
         }
     }
     pub(crate) fn char_array(&self) -> Result<Array<char>, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Char) {
             Some(Value::Char(c)) => Ok(c.clone()),
             _ => Err(self.error(false)),
         }
     }
     pub(crate) fn box_scalar(&self) -> Result<Boxed, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Box) {
             Some(Value::Box(b)) if b.rank() == 0 => Ok(b.data[0].clone()),
             Some(Value::Box(_)) => Err(self.error(true)),
             Some(val) => Ok(Boxed(val.clone())),
@@ -92,14 +130,14 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn box_array(&self) -> Result<Array<Boxed>, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Box) {
             Some(Value::Box(b)) => Ok(b.clone()),
             Some(val) => Ok(Array::new([], [Boxed(val.clone())])),
             None => Err(self.error(false)),
         }
     }
     pub(crate) fn complex_scalar(&self) -> Result<Complex, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Complex) {
             Some(Value::Num(n)) if n.rank() == 0 => Ok(Complex::new(n.data[0], 0.0)),
             Some(Value::Num(_)) => Err(self.error(true)),
             Some(Value::Byte(n)) if n.rank() == 0 => Ok(Complex::new(n.data[0] as f64, 0.0)),
@@ -110,7 +148,7 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn complex_array(&self) -> Result<Array<Complex>, &'static str> {
-        match self.value() {
+        match self.value_of(FillKind::Complex) {
             Some(Value::Num(n)) => Ok(n.convert_ref()),
             Some(Value::Byte(n)) => Ok(n.convert_ref()),
             Some(Value::Complex(c)) => Ok(c.clone()),
@@ -118,7 +156,7 @@ impl<'a> Fill<'a> {
         }
     }
     pub(crate) fn value_for(&self, val: &Value) -> Option<&Value> {
-        let fill = self.value()?;
+        let fill = self.value_of(FillKind::of(val))?;
         match (val, fill) {
             (Value::Num(_) | Value::Byte(_), Value::Num(_) | Value::Byte(_))
             | (Value::Char(_), Value::Char(_))
@@ -136,7 +174,7 @@ impl<'a> Fill<'a> {
                 Some(Value::Complex(_)) => ". A complex fill is set, but is is not a scalar.",
                 Some(Value::Box(_)) => ". A box fill is set, but is is not a scalar.",
                 None => {
-                    if (self.other_value_fill)(self.env).is_some() {
+                    if (self.other_value_fills)(self.env).is_some() {
                         self.other_error
                     } else {
                         ""
@@ -155,7 +193,7 @@ impl<'a> Fill<'a> {
                 }
                 Some(Value::Box(_)) => ". A box fill is set, but the array is not boxed values.",
                 None => {
-                    if (self.other_value_fill)(self.env).is_some() {
+                    if (self.other_value_fills)(self.env).is_some() {
                         self.other_error
                     } else {
                         ""
@@ -675,6 +675,9 @@ impl Formatter<'_> {
                 if binding.code_macro {
                     self.output.push('^');
                 }
+                if binding.token_macro {
+                    self.output.push('^');
+                }
                 if !binding.words.is_empty() || binding.signature.is_some() {
                     self.output.push(' ');
                 }
@@ -915,7 +918,11 @@ impl Formatter<'_> {
     fn format_word(&mut self, word: &Sp<Word>, depth: usize) {
         match &word.value {
             Word::Number(Ok(n)) => {
-                let grid_str = n.grid_string(false);
+                let grid_str = if n.im == 0.0 {
+                    n.re.grid_string(false)
+                } else {
+                    n.grid_string(false)
+                };
                 let formatted = word.span.as_str(self.inputs, |s| {
                     if !s.contains('/')
                         && !grid_str.contains('…')
@@ -1285,6 +1292,16 @@ impl Formatter<'_> {
                 }
                 self.push(&ident.span, &ident.value);
             }
+            Word::LocalBinding(binding) => {
+                self.push(&binding.name.span, &binding.name.value);
+                let arrow = if binding.words.is_empty() {
+                    " ←"
+                } else {
+                    " ← "
+                };
+                self.push(&binding.arrow_span, arrow);
+                self.format_words(&binding.words, true, depth);
+            }
         }
     }
     fn format_primitive(&mut self, prim: Primitive, span: &CodeSpan) {
@@ -1534,6 +1551,9 @@ pub(crate) fn word_is_multiline(word: &Word) -> bool {
         }
         Word::Placeholder(_) => false,
         Word::Subscripted(sub) => word_is_multiline(&sub.word.value),
+        Word::LocalBinding(binding) => {
+            (binding.words.iter()).any(|word| word_is_multiline(&word.value))
+        }
         Word::Comment(_) => true,
         Word::Spaces => false,
         Word::BreakLine => true,
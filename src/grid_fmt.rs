@@ -4,6 +4,7 @@ use std::{
     f64::consts::{PI, TAU},
     iter::once,
     mem::take,
+    sync::atomic::{self, AtomicUsize},
 };
 
 use crate::{
@@ -18,6 +19,19 @@ use crate::{
 type Grid<T = char> = Vec<Vec<T>>;
 type Metagrid = Grid<Grid>;
 
+static DISPLAY_PRECISION: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Set the maximum number of decimal digits shown when pretty-printing numbers
+///
+/// This applies to the whole process. `None` shows numbers at full precision.
+pub fn set_display_precision(precision: Option<usize>) {
+    DISPLAY_PRECISION.store(precision.unwrap_or(usize::MAX), atomic::Ordering::Relaxed);
+}
+
+fn display_precision() -> Option<usize> {
+    Some(DISPLAY_PRECISION.load(atomic::Ordering::Relaxed)).filter(|&p| p != usize::MAX)
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct GridFmtParams {
     pub boxed: bool,
@@ -93,6 +107,16 @@ impl GridFmt for f64 {
             } else {
                 format!("{prefix}{minus}{num}τ/{denom}")
             }
+        } else if let Some(precision) = display_precision() {
+            let mut rounded = format!("{positive:.precision$}");
+            if rounded.contains('.') {
+                rounded.truncate(rounded.trim_end_matches('0').trim_end_matches('.').len());
+            }
+            if rounded == "0" {
+                rounded
+            } else {
+                format!("{minus}{rounded}")
+            }
         } else {
             let mut pos_formatted = positive.to_string();
             if pos_formatted.len() >= 17 {
@@ -0,0 +1,124 @@
+//! Conversions between Uiua arrays and [`ndarray`] and [`nalgebra`] types
+//!
+//! Number and byte arrays can be converted to `f64` arrays and matrices from these crates and back.
+//! Data is copied when converting, and array metadata like labels and map keys is not kept.
+
+use ecow::EcoVec;
+
+use crate::{Array, Value};
+
+fn value_to_nums(value: &Value, target: &str) -> Result<Array<f64>, String> {
+    match value {
+        Value::Num(arr) => Ok(arr.clone()),
+        Value::Byte(arr) => Ok(arr.convert_ref()),
+        value => Err(format!(
+            "Cannot convert {} array to {target}",
+            value.type_name()
+        )),
+    }
+}
+
+#[cfg(feature = "ndarray")]
+mod nd {
+    use ndarray::{ArrayBase, ArrayD, Data, Dimension, IxDyn};
+
+    use super::*;
+    use crate::Shape;
+
+    impl From<Array<f64>> for ArrayD<f64> {
+        fn from(arr: Array<f64>) -> Self {
+            let shape = IxDyn(&arr.shape);
+            let data: Vec<f64> = arr.data.into_iter().collect();
+            ArrayD::from_shape_vec(shape, data).expect("array shape should match its data")
+        }
+    }
+
+    impl TryFrom<&Value> for ArrayD<f64> {
+        type Error = String;
+        fn try_from(value: &Value) -> Result<Self, Self::Error> {
+            value_to_nums(value, "an ndarray").map(Into::into)
+        }
+    }
+
+    impl TryFrom<Value> for ArrayD<f64> {
+        type Error = String;
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+            (&value).try_into()
+        }
+    }
+
+    impl<S, D> From<ArrayBase<S, D>> for Array<f64>
+    where
+        S: Data<Elem = f64>,
+        D: Dimension,
+    {
+        fn from(arr: ArrayBase<S, D>) -> Self {
+            let shape = Shape::from(arr.shape());
+            let data: EcoVec<f64> = arr.iter().copied().collect();
+            Array::new(shape, data)
+        }
+    }
+
+    impl<S, D> From<ArrayBase<S, D>> for Value
+    where
+        S: Data<Elem = f64>,
+        D: Dimension,
+    {
+        fn from(arr: ArrayBase<S, D>) -> Self {
+            Array::from(arr).into()
+        }
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod na {
+    use nalgebra::DMatrix;
+
+    use super::*;
+
+    impl TryFrom<Array<f64>> for DMatrix<f64> {
+        type Error = String;
+        fn try_from(arr: Array<f64>) -> Result<Self, Self::Error> {
+            let &[rows, cols] = &*arr.shape else {
+                return Err(format!(
+                    "Only rank 2 arrays can be converted to a matrix, \
+                    but this array is rank {}",
+                    arr.rank()
+                ));
+            };
+            Ok(DMatrix::from_row_iterator(
+                rows,
+                cols,
+                arr.data.iter().copied(),
+            ))
+        }
+    }
+
+    impl TryFrom<&Value> for DMatrix<f64> {
+        type Error = String;
+        fn try_from(value: &Value) -> Result<Self, Self::Error> {
+            value_to_nums(value, "a matrix")?.try_into()
+        }
+    }
+
+    impl TryFrom<Value> for DMatrix<f64> {
+        type Error = String;
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+            (&value).try_into()
+        }
+    }
+
+    impl From<DMatrix<f64>> for Array<f64> {
+        fn from(mat: DMatrix<f64>) -> Self {
+            // nalgebra matrices are column-major
+            let data: EcoVec<f64> = mat.transpose().iter().copied().collect();
+            Array::new([mat.nrows(), mat.ncols()], data)
+        }
+    }
+
+    impl From<DMatrix<f64>> for Value {
+        fn from(mat: DMatrix<f64>) -> Self {
+            Array::from(mat).into()
+        }
+    }
+}
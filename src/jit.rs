@@ -0,0 +1,642 @@
+//! A JIT compiler for hot numeric functions
+//!
+//! This is enabled with the `jit` feature.
+//! The interpreter counts how many times each function is called.
+//! Once a function is hot, it is compiled to native code with Cranelift,
+//! as long as it only uses pervasive math and stack manipulation.
+//! The compiled code runs elementwise over number arguments that are scalars or that all have the same shape.
+//! Anything else falls back to the interpreter.
+//!
+//! Operations whose semantics are not plain IEEE arithmetic call back into the interpreter's own scalar functions,
+//! so compiled functions produce the same results as interpreted ones.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    iter::repeat,
+    mem,
+    sync::Arc,
+};
+
+use cranelift_codegen::{
+    ir::{condcodes::IntCC, types, AbiParam, FuncRef, InstBuilder, MemFlags, Signature},
+    settings::{self, Configurable},
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+use ecow::EcoVec;
+use parking_lot::Mutex;
+
+use crate::{
+    algorithm::pervade::*, Array, ArrayMeta, Function, ImplPrimitive, Node, Primitive, SigNode,
+    Uiua, Value,
+};
+
+/// The number of times a function must be called before it is compiled
+pub(crate) const HOT_CALL_COUNT: u32 = 100;
+/// The maximum number of arguments a compiled function can take
+const MAX_ARGS: usize = 16;
+/// The maximum depth of inlined function calls
+const MAX_CALL_DEPTH: usize = 50;
+
+/// Call counts and compiled kernels
+#[derive(Clone, Default)]
+pub(crate) struct JitCache {
+    /// How many times each function has been called
+    calls: HashMap<Function, u32>,
+    /// Compiled kernels keyed by function and which arguments are bytes
+    kernels: HashMap<(Function, u64), Arc<Kernel>>,
+    /// Functions that cannot be compiled
+    unsupported: HashSet<Function>,
+}
+
+type KernelFn = unsafe extern "C" fn(*const *const f64, *const usize, *const *mut f64, usize);
+
+/// A compiled function
+struct Kernel {
+    func: KernelFn,
+    /// The outputs, top of the stack first
+    outputs: Vec<Output>,
+    /// The module that owns the compiled code
+    module: Mutex<Option<JITModule>>,
+}
+
+/// Information about a kernel output
+#[derive(Clone, Copy)]
+struct Output {
+    /// Whether the interpreter would store the output as a byte array
+    is_byte: bool,
+    /// Bit flags for the arguments the output depends on
+    deps: u64,
+}
+
+impl Drop for Kernel {
+    fn drop(&mut self) {
+        if let Some(module) = self.module.get_mut().take() {
+            // SAFETY: The only pointer into the module's memory is `func`, which is dropped with it
+            unsafe { module.free_memory() };
+        }
+    }
+}
+
+/// Try to run a function with the JIT
+///
+/// Returns whether the function was run.
+/// If it was not, the stack is left untouched and the function should be interpreted.
+pub(crate) fn try_call(env: &mut Uiua, f: &Function) -> bool {
+    let args = f.sig.args;
+    if args == 0 || args > MAX_ARGS || f.sig.outputs == 0 {
+        return false;
+    }
+    let jit = &mut env.rt.jit;
+    if jit.unsupported.contains(f) {
+        return false;
+    }
+    let calls = jit.calls.entry(f.clone()).or_default();
+    if *calls < HOT_CALL_COUNT {
+        *calls += 1;
+        return false;
+    }
+    // Check the arguments
+    let stack = &env.rt.stack;
+    let Some(bottom) = stack.len().checked_sub(args) else {
+        return false;
+    };
+    let mut bytes = 0u64;
+    let mut shaped = 0u64;
+    let mut shape = None;
+    for (i, arg) in stack[bottom..].iter().rev().enumerate() {
+        let arg_shape = match arg {
+            Value::Num(arr) => &arr.shape,
+            Value::Byte(arr) => {
+                bytes |= 1 << i;
+                &arr.shape
+            }
+            _ => return false,
+        };
+        if !is_plain(arg.meta()) {
+            return false;
+        }
+        if arg_shape.is_empty() {
+            continue;
+        }
+        shaped |= 1 << i;
+        match shape {
+            Some(shape) if shape != arg_shape => return false,
+            Some(_) => {}
+            None => shape = Some(arg_shape),
+        }
+    }
+    // Get or compile the kernel
+    let kernel = match jit.kernels.get(&(f.clone(), bytes)) {
+        Some(kernel) => kernel.clone(),
+        None => match compile(&env.asm[f], f.sig.args, bytes, &env.asm) {
+            Ok(kernel) => {
+                let kernel = Arc::new(kernel);
+                (jit.kernels).insert((f.clone(), bytes), kernel.clone());
+                kernel
+            }
+            Err(_) => {
+                jit.unsupported.insert(f.clone());
+                return false;
+            }
+        },
+    };
+    // Run the kernel
+    let shape = shape.cloned().unwrap_or_default();
+    let len = shape.elements();
+    // Outputs that only depend on scalars are scalars
+    let output_shaped: Vec<bool> = (kernel.outputs.iter())
+        .map(|out| out.deps & shaped != 0)
+        .collect();
+    if len == 0 && output_shaped.contains(&false) {
+        return false;
+    }
+    let arg_data: Vec<Cow<[f64]>> = (stack[bottom..].iter().rev())
+        .map(|arg| match arg {
+            Value::Num(arr) => Cow::Borrowed(&arr.data[..]),
+            Value::Byte(arr) => Cow::Owned(arr.data.iter().map(|&b| b as f64).collect()),
+            _ => unreachable!(),
+        })
+        .collect();
+    let arg_ptrs: Vec<*const f64> = arg_data.iter().map(|data| data.as_ptr()).collect();
+    let steps: Vec<usize> = (arg_data.iter())
+        .map(|data| {
+            if data.len() == 1 {
+                0
+            } else {
+                mem::size_of::<f64>()
+            }
+        })
+        .collect();
+    let mut outputs: Vec<EcoVec<f64>> = (kernel.outputs.iter())
+        .map(|_| repeat(0.0).take(len).collect())
+        .collect();
+    let out_ptrs: Vec<*mut f64> = (outputs.iter_mut())
+        .map(|data| data.make_mut().as_mut_ptr())
+        .collect();
+    // SAFETY: Each argument has 1 or `len` elements and is stepped accordingly,
+    // and each output has `len` elements
+    unsafe { (kernel.func)(arg_ptrs.as_ptr(), steps.as_ptr(), out_ptrs.as_ptr(), len) };
+    env.rt.stack.truncate(bottom);
+    let outputs = outputs.into_iter().zip(&kernel.outputs).zip(output_shaped);
+    for ((mut data, out), shaped) in outputs.rev() {
+        let shape = if shaped {
+            shape.clone()
+        } else {
+            data.truncate(1);
+            Default::default()
+        };
+        let val: Value = if out.is_byte {
+            let data: EcoVec<u8> = data.iter().map(|&n| n as u8).collect();
+            Array::new(shape, data).into()
+        } else {
+            Array::new(shape, data).into()
+        };
+        env.rt.stack.push(val);
+    }
+    true
+}
+
+/// Whether a value's metadata allows it to be treated as plain numbers
+fn is_plain(meta: &ArrayMeta) -> bool {
+    meta.label.is_none()
+        && meta.map_keys.is_none()
+        && meta.pointer.is_none()
+        && meta.handle_kind.is_none()
+}
+
+/// Compile a function to a kernel that runs elementwise
+///
+/// The kernel takes pointers to the arguments, the byte steps of the arguments,
+/// pointers to the outputs, and the number of elements.
+/// The top of the stack is the first argument and the first output.
+fn compile(node: &Node, args: usize, bytes: u64, asm: &crate::Assembly) -> Result<Kernel, String> {
+    let mut flags = settings::builder();
+    flags.set("opt_level", "speed").map_err(|e| e.to_string())?;
+    let isa = cranelift_native::builder()?
+        .finish(settings::Flags::new(flags))
+        .map_err(|e| e.to_string())?;
+    let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+    for &(name, ptr) in LIBCALLS {
+        builder.symbol(name, ptr);
+    }
+    let mut module = JITModule::new(builder);
+    let ptr_type = module.target_config().pointer_type();
+    let mut ctx = module.make_context();
+    for _ in 0..4 {
+        (ctx.func.signature.params).push(AbiParam::new(ptr_type));
+    }
+    let mut fn_ctx = FunctionBuilderContext::new();
+    let mut b = FunctionBuilder::new(&mut ctx.func, &mut fn_ctx);
+    let entry = b.create_block();
+    let header = b.create_block();
+    let body = b.create_block();
+    let exit = b.create_block();
+    b.append_block_params_for_function_params(entry);
+    b.append_block_param(header, ptr_type);
+    let flags = MemFlags::trusted();
+
+    // Load the argument and output pointers
+    b.switch_to_block(entry);
+    let [arg_ptrs, steps, out_ptrs, len] = b.block_params(entry).try_into().unwrap();
+    let ptr_size = ptr_type.bytes() as i32;
+    let arg_ptrs: Vec<_> = (0..args as i32)
+        .map(|i| {
+            let ptr = b.ins().load(ptr_type, flags, arg_ptrs, i * ptr_size);
+            let step = b.ins().load(ptr_type, flags, steps, i * ptr_size);
+            (ptr, step)
+        })
+        .collect();
+    let zero = b.ins().iconst(ptr_type, 0);
+    b.ins().jump(header, &[zero]);
+
+    // Check the loop condition
+    b.switch_to_block(header);
+    let i = b.block_params(header)[0];
+    let in_bounds = b.ins().icmp(IntCC::UnsignedLessThan, i, len);
+    b.ins().brif(in_bounds, body, &[], exit, &[]);
+
+    // Compute one element
+    b.switch_to_block(body);
+    let mut emitter = Emitter {
+        b,
+        module: &mut module,
+        asm,
+        stack: Vec::new(),
+        imports: HashMap::new(),
+        call_depth: 0,
+    };
+    for (i_arg, &(ptr, step)) in arg_ptrs.iter().enumerate().rev() {
+        let offset = emitter.b.ins().imul(i, step);
+        let addr = emitter.b.ins().iadd(ptr, offset);
+        let val = emitter.b.ins().load(types::F64, flags, addr, 0);
+        let is_byte = bytes & (1 << i_arg) != 0;
+        emitter.stack.push(Slot::new(val, is_byte, 1 << i_arg));
+    }
+    emitter.node(node)?;
+    let Emitter {
+        mut b,
+        stack: outputs,
+        ..
+    } = emitter;
+    let offset = b.ins().imul_imm(i, mem::size_of::<f64>() as i64);
+    for (j, slot) in outputs.iter().rev().enumerate() {
+        let ptr = b.ins().load(ptr_type, flags, out_ptrs, j as i32 * ptr_size);
+        let addr = b.ins().iadd(ptr, offset);
+        b.ins().store(flags, slot.val, addr, 0);
+    }
+    let next = b.ins().iadd_imm(i, 1);
+    b.ins().jump(header, &[next]);
+
+    b.switch_to_block(exit);
+    b.ins().return_(&[]);
+    b.seal_all_blocks();
+    b.finalize();
+
+    let id = (module.declare_function("kernel", Linkage::Export, &ctx.func.signature))
+        .map_err(|e| e.to_string())?;
+    (module.define_function(id, &mut ctx)).map_err(|e| e.to_string())?;
+    module.clear_context(&mut ctx);
+    module.finalize_definitions().map_err(|e| e.to_string())?;
+    let code = module.get_finalized_function(id);
+    // SAFETY: The function was defined with this signature
+    let func = unsafe { mem::transmute::<*const u8, KernelFn>(code) };
+    Ok(Kernel {
+        func,
+        outputs: (outputs.iter().rev())
+            .map(|slot| Output {
+                is_byte: slot.is_byte,
+                deps: slot.deps,
+            })
+            .collect(),
+        module: Mutex::new(Some(module)),
+    })
+}
+
+/// A value on the simulated stack
+#[derive(Clone, Copy)]
+struct Slot {
+    val: cranelift_codegen::ir::Value,
+    /// Whether the interpreter would store this value as a byte
+    is_byte: bool,
+    /// Bit flags for the arguments this value depends on
+    deps: u64,
+    /// The value, if it is a constant
+    constant: Option<f64>,
+}
+
+impl Slot {
+    fn new(val: cranelift_codegen::ir::Value, is_byte: bool, deps: u64) -> Self {
+        Slot {
+            val,
+            is_byte,
+            deps,
+            constant: None,
+        }
+    }
+}
+
+struct Emitter<'a> {
+    b: FunctionBuilder<'a>,
+    module: &'a mut JITModule,
+    asm: &'a crate::Assembly,
+    stack: Vec<Slot>,
+    imports: HashMap<&'static str, FuncRef>,
+    call_depth: usize,
+}
+
+impl Emitter<'_> {
+    fn pop(&mut self) -> Result<Slot, String> {
+        (self.stack.pop()).ok_or_else(|| "The function's signature could not be followed".into())
+    }
+    fn top(&self, n: usize) -> Result<Slot, String> {
+        (self.stack.len().checked_sub(n + 1))
+            .map(|i| self.stack[i])
+            .ok_or_else(|| "The function's signature could not be followed".into())
+    }
+    fn call(
+        &mut self,
+        name: &'static str,
+        args: &[cranelift_codegen::ir::Value],
+    ) -> cranelift_codegen::ir::Value {
+        let func_ref = if let Some(func_ref) = self.imports.get(name) {
+            *func_ref
+        } else {
+            let mut sig = Signature::new(self.module.isa().default_call_conv());
+            for _ in args {
+                sig.params.push(AbiParam::new(types::F64));
+            }
+            sig.returns.push(AbiParam::new(types::F64));
+            let id = (self.module)
+                .declare_function(name, Linkage::Import, &sig)
+                .expect("libcall should be declarable");
+            let func_ref = self.module.declare_func_in_func(id, self.b.func);
+            self.imports.insert(name, func_ref);
+            func_ref
+        };
+        let inst = self.b.ins().call(func_ref, args);
+        self.b.inst_results(inst)[0]
+    }
+    fn node(&mut self, node: &Node) -> Result<(), String> {
+        match node {
+            Node::Run(nodes) => {
+                for node in nodes {
+                    self.node(node)?;
+                }
+            }
+            Node::Push(val) => {
+                let (n, is_byte) = match val {
+                    Value::Num(arr) if arr.rank() == 0 => (arr.data[0], false),
+                    Value::Byte(arr) if arr.rank() == 0 => (arr.data[0] as f64, true),
+                    _ => return Err("Only scalar number constants are supported".into()),
+                };
+                if !is_plain(val.meta()) {
+                    return Err("Constants with metadata are not supported".into());
+                }
+                let val = self.b.ins().f64const(n);
+                self.stack.push(Slot {
+                    val,
+                    is_byte,
+                    deps: 0,
+                    constant: Some(n),
+                });
+            }
+            Node::Prim(prim, _) => self.prim(*prim)?,
+            Node::ImplPrim(ImplPrimitive::DupPervasive(prim), _) => {
+                self.prim(Primitive::Dup)?;
+                self.prim(*prim)?;
+            }
+            Node::ImplPrim(ImplPrimitive::FlipPervasive(prim), _) => {
+                self.prim(Primitive::Flip)?;
+                self.prim(*prim)?;
+            }
+            Node::Mod(prim, args, _) => self.modifier(*prim, args)?,
+            Node::Call(f, _) => {
+                if self.call_depth >= MAX_CALL_DEPTH {
+                    return Err("Recursive functions are not supported".into());
+                }
+                self.call_depth += 1;
+                let asm = self.asm;
+                self.node(&asm[f])?;
+                self.call_depth -= 1;
+            }
+            Node::NoInline(inner) | Node::TrackCaller(inner) => self.node(inner)?,
+            _ => return Err("The function uses features that are not supported".into()),
+        }
+        Ok(())
+    }
+    fn prim(&mut self, prim: Primitive) -> Result<(), String> {
+        use Primitive::*;
+        match prim {
+            Identity => {}
+            Dup => {
+                let x = self.top(0)?;
+                self.stack.push(x);
+            }
+            Over => {
+                let y = self.top(1)?;
+                self.stack.push(y);
+            }
+            Flip => {
+                let x = self.pop()?;
+                let y = self.pop()?;
+                self.stack.push(x);
+                self.stack.push(y);
+            }
+            Pop => _ = self.pop()?,
+            Not | Neg | Abs | Sqrt | Floor | Ceil | Sign | Sin | Round => {
+                let x = self.pop()?;
+                let val = match prim {
+                    Not => {
+                        let one = self.b.ins().f64const(1.0);
+                        self.b.ins().fsub(one, x.val)
+                    }
+                    Neg => self.b.ins().fneg(x.val),
+                    Abs => self.b.ins().fabs(x.val),
+                    Sqrt => self.b.ins().sqrt(x.val),
+                    Floor => self.b.ins().floor(x.val),
+                    Ceil => self.b.ins().ceil(x.val),
+                    Sign => self.call("uiua_jit_sign", &[x.val]),
+                    Sin => self.call("uiua_jit_sin", &[x.val]),
+                    _ => self.call("uiua_jit_round", &[x.val]),
+                };
+                // These keep bytes as bytes
+                let is_byte = x.is_byte && matches!(prim, Abs | Sign | Floor | Ceil | Round);
+                self.stack.push(Slot::new(val, is_byte, x.deps));
+            }
+            Pow => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                // Small integer powers are special-cased by the interpreter
+                let val = match a.constant {
+                    None => return Err("Only constant exponents are supported".into()),
+                    Some(1.0) => {
+                        self.stack.push(b);
+                        return Ok(());
+                    }
+                    Some(2.0) => self.b.ins().fmul(b.val, b.val),
+                    Some(-1.0) => {
+                        let one = self.b.ins().f64const(1.0);
+                        self.b.ins().fdiv(one, b.val)
+                    }
+                    Some(_) if a.is_byte && !b.is_byte => {
+                        self.call("uiua_jit_powi", &[a.val, b.val])
+                    }
+                    Some(_) => self.call("uiua_jit_pow", &[a.val, b.val]),
+                };
+                self.stack.push(Slot::new(val, false, b.deps));
+            }
+            Add | Sub | Mul | Div | Modulus | Log | Atan | Min | Max | Eq | Ne | Lt | Le | Gt
+            | Ge => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let val = match prim {
+                    Add => self.b.ins().fadd(b.val, a.val),
+                    Sub => self.b.ins().fsub(b.val, a.val),
+                    Mul => self.b.ins().fmul(b.val, a.val),
+                    Div => self.b.ins().fdiv(b.val, a.val),
+                    _ => {
+                        let name = match prim {
+                            Modulus => "uiua_jit_modulus",
+                            Log => "uiua_jit_log",
+                            Atan => "uiua_jit_atan",
+                            Min => "uiua_jit_min",
+                            Max => "uiua_jit_max",
+                            Eq => "uiua_jit_eq",
+                            Ne => "uiua_jit_ne",
+                            Lt => "uiua_jit_lt",
+                            Le => "uiua_jit_le",
+                            Gt => "uiua_jit_gt",
+                            _ => "uiua_jit_ge",
+                        };
+                        self.call(name, &[a.val, b.val])
+                    }
+                };
+                let is_byte = match prim {
+                    // Comparing two number arrays reuses a number buffer in the interpreter
+                    Eq | Ne | Lt | Le | Gt | Ge => a.is_byte || b.is_byte,
+                    Min | Max => a.is_byte && b.is_byte,
+                    _ => false,
+                };
+                self.stack.push(Slot::new(val, is_byte, a.deps | b.deps));
+            }
+            prim => return Err(format!("{} is not supported", prim.format())),
+        }
+        Ok(())
+    }
+    fn modifier(&mut self, prim: Primitive, args: &[SigNode]) -> Result<(), String> {
+        use Primitive::*;
+        match (prim, args) {
+            (Dip, [f]) => {
+                let x = self.pop()?;
+                self.node(&f.node)?;
+                self.stack.push(x);
+            }
+            (Gap, [f]) => {
+                self.pop()?;
+                self.node(&f.node)?;
+            }
+            (On, [f]) => {
+                let x = self.top(0)?;
+                self.node(&f.node)?;
+                self.stack.push(x);
+            }
+            (By, [f]) if f.sig.args > 0 => {
+                let y = self.top(f.sig.args - 1)?;
+                self.node(&f.node)?;
+                let i = self.stack.len().saturating_sub(f.sig.outputs);
+                self.stack.insert(i, y);
+            }
+            (Fork, [f, g]) => {
+                let n = f.sig.args.max(g.sig.args);
+                let start = (self.stack.len().checked_sub(n))
+                    .ok_or("The function's signature could not be followed")?;
+                let fork_args = self.stack.split_off(start);
+                self.stack.extend_from_slice(&fork_args[n - g.sig.args..]);
+                self.node(&g.node)?;
+                self.stack.extend_from_slice(&fork_args[n - f.sig.args..]);
+                self.node(&f.node)?;
+            }
+            (prim, _) => return Err(format!("{} is not supported", prim.format())),
+        }
+        Ok(())
+    }
+}
+
+macro_rules! libcalls {
+    ($(($name:literal, $f:ident, |$($arg:ident),*| $body:expr)),* $(,)?) => {
+        $(
+            extern "C" fn $f($($arg: f64),*) -> f64 {
+                $body
+            }
+        )*
+        /// Functions that compiled code can call and their names
+        const LIBCALLS: &[(&str, *const u8)] = &[$(($name, $f as *const u8)),*];
+    };
+}
+
+libcalls!(
+    ("uiua_jit_sign", jit_sign, |a| sign::num(a)),
+    ("uiua_jit_sin", jit_sin, |a| sin::num(a)),
+    ("uiua_jit_round", jit_round, |a| round::num(a)),
+    ("uiua_jit_pow", jit_pow, |a, b| scalar_pow::num_num(a, b)),
+    ("uiua_jit_powi", jit_powi, |a, b| scalar_pow::byte_num(
+        a as u8, b
+    )),
+    ("uiua_jit_modulus", jit_modulus, |a, b| modulus::num_num(
+        a, b
+    )),
+    ("uiua_jit_log", jit_log, |a, b| log::num_num(a, b)),
+    ("uiua_jit_atan", jit_atan, |a, b| atan2::num_num(a, b)),
+    ("uiua_jit_min", jit_min, |a, b| min::num_num(a, b)),
+    ("uiua_jit_max", jit_max, |a, b| max::num_num(a, b)),
+    ("uiua_jit_eq", jit_eq, |a, b| is_eq::num_num(a, b) as f64),
+    ("uiua_jit_ne", jit_ne, |a, b| is_ne::num_num(a, b) as f64),
+    ("uiua_jit_lt", jit_lt, |a, b| other_is_lt::num_num(a, b)
+        as f64),
+    ("uiua_jit_le", jit_le, |a, b| other_is_le::num_num(a, b)
+        as f64),
+    ("uiua_jit_gt", jit_gt, |a, b| other_is_gt::num_num(a, b)
+        as f64),
+    ("uiua_jit_ge", jit_ge, |a, b| other_is_ge::num_num(a, b)
+        as f64),
+);
+
+#[cfg(test)]
+#[test]
+fn jit_matches_interpreter() {
+    fn same_types(a: &Value, b: &Value) -> bool {
+        match (a, b) {
+            (Value::Box(a), Value::Box(b)) => {
+                (a.data.iter().zip(&b.data)).all(|(a, b)| same_types(&a.0, &b.0))
+            }
+            _ => mem::discriminant(a) == mem::discriminant(b),
+        }
+    }
+    let mut env = Uiua::with_safe_sys();
+    env.run_str(
+        "F ← ⊃(+1×2|≥0.5|◌5)
+G ← ⁿ3
+H ← ⊃(=1|≠|<2|>0.5)
+# Random so that this is not evaluated at compile time
+X ← ÷200⇡+200×0⚂
+B ← ≥0.5 X
+≡(□[F]) X
+≡(□[⊃(+1×2|≥0.5|◌5)]) X
+≡G X
+≡(ⁿ3) X
+≡(□{H}) X B
+≡(□{⊃(=1|≠|<2|>0.5)}) X B
+≡(□{H}) B B
+≡(□{⊃(=1|≠|<2|>0.5)}) B B",
+    )
+    .unwrap();
+    let stack = env.take_stack();
+    for pair in stack.chunks(2) {
+        assert_eq!(pair[0], pair[1]);
+        assert!(same_types(&pair[0], &pair[1]));
+    }
+    assert_eq!(env.rt.jit.kernels.len(), 4);
+}
@@ -28,6 +28,41 @@ pub fn lex(
     input: &str,
     src: impl IntoInputSrc,
     inputs: &mut Inputs,
+) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>, InputSrc) {
+    lex_impl(input, src, inputs, false)
+}
+
+/// Split Uiua code into the source text of its tokens
+///
+/// Whitespace between tokens is kept as its own item,
+/// so concatenating the result gives back the original code.
+pub(crate) fn split_tokens(input: &str) -> Result<Vec<&str>, LexError> {
+    let (tokens, errors, _) = lex(input, (), &mut Inputs::default());
+    if let Some(error) = errors.into_iter().next() {
+        return Err(error.value);
+    }
+    let mut end = 0;
+    let mut split = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let start = token.span.start.byte_pos as usize;
+        if start > end {
+            split.push(&input[end..start]);
+        }
+        end = token.span.end.byte_pos as usize;
+        split.push(&input[start..end]);
+    }
+    if end < input.len() {
+        split.push(&input[end..]);
+    }
+    Ok(split)
+}
+
+/// Lex a Uiua source file, optionally with experimental features already enabled
+pub(crate) fn lex_impl(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &mut Inputs,
+    experimental: bool,
 ) -> (Vec<Sp<Token>>, Vec<Sp<LexError>>, InputSrc) {
     let src = inputs.add_src(src, input);
 
@@ -96,6 +131,8 @@ pub fn lex(
         src: src.clone(),
         tokens: VecDeque::new(),
         errors: Vec::new(),
+        edition: crate::Edition::default(),
+        experimental,
     }
     .run();
     (tokens, errors, src)
@@ -113,6 +150,7 @@ pub enum LexError {
     ExpectedNumber,
     LineTooLong(usize),
     FileTooLong,
+    RemovedGlyph(String, crate::Edition),
 }
 
 impl fmt::Display for LexError {
@@ -135,6 +173,9 @@ impl fmt::Display for LexError {
             LexError::ExpectedNumber => write!(f, "Expected number"),
             LexError::LineTooLong(n) => write!(f, "Line {n} is too long"),
             LexError::FileTooLong => write!(f, "File is too long"),
+            LexError::RemovedGlyph(c, edition) => {
+                write!(f, "{c:?} was removed in edition {edition}")
+            }
         }
     }
 }
@@ -804,8 +845,12 @@ pub enum SemanticComment {
     TrackCaller,
     /// Mark that a function should be bound externally
     External,
+    /// Mark a function as an overload of an earlier function with the same name
+    Overload,
     /// Mark a function as deprecated
     Deprecated(EcoString),
+    /// Select the language edition of a file
+    Edition(EcoString),
     #[doc(hidden)]
     Boo,
 }
@@ -819,8 +864,10 @@ impl fmt::Display for SemanticComment {
             SemanticComment::NoInline => write!(f, "# No inline!"),
             SemanticComment::TrackCaller => write!(f, "# Track caller!"),
             SemanticComment::External => write!(f, "# External!"),
+            SemanticComment::Overload => write!(f, "# Overload!"),
             SemanticComment::Deprecated(s) if s.is_empty() => write!(f, "# Deprecated!"),
             SemanticComment::Deprecated(s) => write!(f, "# Deprecated! {s}"),
+            SemanticComment::Edition(s) => write!(f, "# Edition! {s}"),
             SemanticComment::Boo => write!(f, "# Boo!"),
         }
     }
@@ -833,6 +880,10 @@ struct Lexer<'a> {
     src: InputSrc,
     tokens: VecDeque<Sp<Token>>,
     errors: Vec<Sp<LexError>>,
+    /// The edition selected so far, which determines which legacy glyphs are allowed
+    edition: crate::Edition,
+    /// Whether experimental features are enabled so far, which allows imaginary number literals
+    experimental: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -960,6 +1011,11 @@ impl<'a> Lexer<'a> {
 
             match c {
                 // Backwards compatibility
+                "∶" | "⮌" | "¨" | "𝄈" | "⍛" | "◫" | "∈" if self.edition.removes_deprecated() =>
+                {
+                    let error = LexError::RemovedGlyph(c.into(), self.edition);
+                    self.errors.push(self.end_span(start).sp(error));
+                }
                 "∶" => self.end(Primitive::Flip, start),
                 "⮌" => self.end(Primitive::Orient, start),
                 "¨" | "𝄈" => self.end(Primitive::Backward, start),
@@ -1088,14 +1144,23 @@ impl<'a> Lexer<'a> {
                             comment.remove(0);
                         }
                         match comment.trim() {
-                            "Experimental!" => self.end(Experimental, start),
+                            "Experimental!" => {
+                                self.experimental = true;
+                                self.end(Experimental, start)
+                            }
                             "No inline!" => self.end(NoInline, start),
                             "Track caller!" => self.end(TrackCaller, start),
                             "External!" => self.end(External, start),
+                            "Overload!" => self.end(Overload, start),
                             "Boo!" => self.end(Boo, start),
                             s => {
                                 if let Some(suf) = s.strip_prefix("Deprecated!") {
                                     self.end(Deprecated(suf.trim().into()), start);
+                                } else if let Some(suf) = s.strip_prefix("Edition!") {
+                                    if let Ok(edition) = suf.parse() {
+                                        self.edition = edition;
+                                    }
+                                    self.end(Edition(suf.trim().into()), start);
                                 } else {
                                     self.end(Comment, start);
                                 }
@@ -1509,6 +1574,15 @@ impl<'a> Lexer<'a> {
                 self.loc = loc_before_e;
             }
         }
+        // Imaginary unit
+        // Without experimental features, `2i` is `2` followed by `i`
+        let loc_before_i = self.loc;
+        if self.experimental
+            && self.next_char_exact("i")
+            && (self.peek_char()).is_some_and(|c| c.chars().all(is_ident_char))
+        {
+            self.loc = loc_before_i;
+        }
         true
     }
     fn character(
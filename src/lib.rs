@@ -17,6 +17,7 @@ uiua = { version = "*", default-features = false, features = ["batteries"] }
 The main entry point is the [`Uiua`] struct, which is the Uiua runtime. It must be created with a [`SysBackend`]. [`Uiua::with_native_sys`] is a convenient way to create a Uiua runtime that uses the same backend as the Uiua CLI, though keep in mind it gives full access to the filesystem and TCP sockets and so probably shouldn't be used in a sandboxed environment.
 
 [`Value`] is the generic value type. It wraps one of five [`Array`] types.
+Nested values can be constructed with a [`ValueBuilder`].
 
 You can run Uiua code with [`Uiua::run_str`] or [`Uiua::run_file`].
 ```rust
@@ -84,6 +85,20 @@ let res = uiua.pop_num().unwrap();
 assert_eq!(res, 5.0);
 ```
 
+To run the same program over many sets of inputs, compile it once and use [`Uiua::run_batch`].
+```rust
+use uiua::*;
+
+let asm = Compiler::new().load_str("×2 +").unwrap().finish();
+let mut uiua = Uiua::with_native_sys();
+let outputs = uiua.run_batch(asm, [[1, 2], [3, 4], [5, 6]]);
+let sums: Vec<i64> = outputs
+    .into_iter()
+    .map(|res| res.unwrap()[0].as_int(&uiua, "").unwrap() as i64)
+    .collect();
+assert_eq!(sums, [6, 14, 22]);
+```
+
 Bindings can be retrieved with [`Uiua::bound_values`] or [`Uiua::bound_functions`].
 ```rust
 use uiua::*;
@@ -126,13 +141,23 @@ The `uiua` crate has the following noteable feature flags:
     - `regex`: Enables the `regex` function
     - `image`: Enables image encoding and decoding
     - `gif`: Enables GIF encoding and decoding
+    - `apng`: Enables animated PNG encoding
     - `audio_encode`: Enables audio encoding and decoding
+    - `arrow`: Enables Apache Arrow IPC encoding and decoding and the [`arrow`] module
+    - `compress`: Enables gzip, deflate, and zlib compression
 - `native_sys`: Enables the [`NativeSys`] backend. This is the default backend used by the interpreter.
 - `audio`: Enables audio features in the [`NativeSys`] backend.
+- `zstd`: Enables zstd compression
 - `https`: Enables the `&httpsw` system function
+- `http`: Enables the `&http` system function in the [`NativeSys`] backend
+- `websocket`: Enables the `&wsc`, `&wss`, and `&wsr` system functions in the [`NativeSys`] backend
 - `invoke`: Enables the `&invk` system function
 - `trash`: Enables the `&ftr` system function
 - `raw_mode`: Enables the `&raw` system function
+- `serial`: Enables the serial port system functions in the [`NativeSys`] backend
+- `jit`: Compiles frequently called numeric functions to native code with Cranelift
+- `ndarray`: Enables conversions between number arrays and `ndarray::ArrayD<f64>`
+- `nalgebra`: Enables conversions between rank 2 number arrays and `nalgebra::DMatrix<f64>`
 */
 
 #![allow(
@@ -145,32 +170,48 @@ The `uiua` crate has the following noteable feature flags:
 
 mod algorithm;
 mod array;
+#[cfg(feature = "arrow")]
+pub mod arrow;
 mod assembly;
 pub mod ast;
 mod boxed;
+mod builder;
 mod check;
 mod compile;
 mod complex;
 mod constant;
 mod cowslice;
+pub mod disasm;
+mod edition;
 mod error;
+#[cfg(feature = "explorer")]
+pub mod explorer;
 mod ffi;
 mod fill;
 pub mod format;
 mod function;
 mod grid_fmt;
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+mod interop;
+#[cfg(feature = "jit")]
+mod jit;
 mod lex;
 pub mod lsp;
+mod memo;
+mod messages;
 mod parse;
 mod primitive;
 #[doc(hidden)]
 pub mod profile;
 mod run;
+mod settings;
 mod shape;
 #[cfg(feature = "stand")]
 #[doc(hidden)]
 pub mod stand;
 mod sys;
+mod trace;
+pub mod transpile;
 mod tree;
 mod types;
 mod value;
@@ -184,20 +225,27 @@ pub use self::{
     array::*,
     assembly::*,
     boxed::*,
+    builder::*,
     compile::*,
     complex::*,
     constant::*,
+    edition::*,
     error::*,
     ffi::*,
     function::*,
+    grid_fmt::set_display_precision,
     lex::is_ident_char,
     lex::*,
     lsp::{SpanKind, Spans},
+    memo::*,
+    messages::*,
     parse::{ident_modifier_args, parse, ParseError},
     primitive::*,
     run::*,
+    settings::*,
     shape::*,
     sys::*,
+    trace::*,
     tree::*,
     value::*,
 };
@@ -282,6 +330,224 @@ mod tests {
         _ = std::fs::remove_file("example.ua");
     }
 
+    #[test]
+    #[cfg(feature = "native_sys")]
+    fn parallelism_modes() {
+        use super::*;
+        let path = Path::new("tests/loops.ua");
+        let code = std::fs::read_to_string(path).unwrap();
+        for parallelism in [Parallelism::Off, Parallelism::Deterministic] {
+            let mut env = Uiua::with_native_sys().with_parallelism(parallelism);
+            let mut comp = Compiler::new();
+            if let Err(e) = comp
+                .load_str_src(&code, path)
+                .and_then(|comp| env.run_asm(comp.finish()))
+            {
+                panic!(
+                    "Test failed with {parallelism:?} parallelism:\n{}",
+                    e.report()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn memo_cache() {
+        use super::*;
+        let mut env =
+            Uiua::with_safe_sys().with_memo_config(MemoConfig::default().with_capacity(2));
+        env.run_str("F ← memo(+1)\nG ← ×2").unwrap();
+        let mut functions = env.bound_functions();
+        let f = functions.remove("F").unwrap();
+        for n in [1, 2, 1, 3] {
+            env.push(n);
+            env.call(&f).unwrap();
+        }
+        let g = functions.remove("G").unwrap();
+        for n in [5, 5] {
+            env.push(n);
+            env.call_memoized(&g).unwrap();
+        }
+        let stack: Vec<f64> = (env.take_stack().into_iter())
+            .map(|val| val.as_num(&env, "").unwrap())
+            .collect();
+        assert_eq!(stack, [2.0, 3.0, 2.0, 4.0, 10.0, 10.0]);
+        let stats = env.memo_cache().stats();
+        assert_eq!((stats.hits, stats.misses), (2, 4));
+        assert_eq!((stats.entries, stats.evictions), (2, 2));
+    }
+
+    #[test]
+    fn tolerance() {
+        use super::*;
+        let run = |mut env: Uiua, code: &str| -> Vec<f64> {
+            env.run_str(code).unwrap();
+            (env.take_stack().into_iter())
+                .map(|val| val.as_num(&env, "").unwrap())
+                .collect()
+        };
+        let code = "⍥(×0.5)∞ 1\n≍ 1 1.0001";
+        assert_eq!(run(Uiua::with_safe_sys(), code), [0.0, 0.0]);
+        let env = Uiua::with_safe_sys().with_tolerance(1e-3);
+        assert_eq!(run(env, code), [0.5f64.powi(10), 0.0]);
+        let env = (Uiua::with_safe_sys())
+            .with_tolerance(1e-3)
+            .with_match_tolerance(true);
+        assert_eq!(run(env, code), [0.5f64.powi(10), 1.0]);
+    }
+
+    #[test]
+    fn audio_sample_rate() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        let default = env.audio_sample_rate();
+        env.run_str("&asr").unwrap();
+        assert_eq!(env.pop_num().unwrap(), default as f64);
+        let mut env = Uiua::with_safe_sys().with_audio_sample_rate(8000);
+        env.run_str("&asr").unwrap();
+        assert_eq!(env.pop_num().unwrap(), 8000.0);
+        env.run_str("°audio audio \"wav\" &asr ÷4 ∿×τ×440 ÷⟜⇡ &asr")
+            .unwrap();
+        let _format = env.pop_string().unwrap();
+        assert_eq!(env.pop_num().unwrap(), 8000.0);
+    }
+
+    #[test]
+    fn http_request() {
+        use super::*;
+        use std::{any::Any, time::Duration};
+        #[derive(Default)]
+        struct HttpSys(parking_lot::Mutex<Option<HttpRequest>>);
+        impl SysBackend for HttpSys {
+            fn any(&self) -> &dyn Any {
+                self
+            }
+            fn any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+            fn http_request(&self, request: HttpRequest) -> Result<HttpResponse, String> {
+                *self.0.lock() = Some(request);
+                Ok(HttpResponse {
+                    status: 201,
+                    headers: vec![("content-type".into(), "text/plain".into())],
+                    body: b"done".to_vec(),
+                })
+            }
+        }
+        let mut env =
+            Uiua::with_backend(HttpSys::default()).with_http_timeout(Duration::from_secs(5));
+        env.run_str(
+            "# Experimental!\n\
+            &http \"POST\" \"https://uiua.org\" map {\"Accept\"} {\"text/plain\"} \"Hi\"",
+        )
+        .unwrap();
+        assert_eq!(env.pop_num().unwrap(), 201.0);
+        let headers = env.pop("headers").unwrap();
+        let content_type = headers.get(&"content-type".into(), &env).unwrap();
+        assert_eq!(content_type.unboxed(), Value::from("text/plain"));
+        assert_eq!(env.pop_string().unwrap(), "done");
+        let request = (env.rt.backend.any().downcast_ref::<HttpSys>())
+            .and_then(|sys| sys.0.lock().take())
+            .unwrap();
+        assert_eq!(
+            request,
+            HttpRequest {
+                method: "POST".into(),
+                url: "https://uiua.org".into(),
+                headers: vec![("Accept".into(), "text/plain".into())],
+                body: b"Hi".to_vec(),
+                timeout: Some(Duration::from_secs(5)),
+            }
+        );
+    }
+
+    #[test]
+    fn value_bytes() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        env.run_str("map {\"a\" \"b\"} {[1 2 3] ÷2 °△2_3}").unwrap();
+        let val = env.pop("value").unwrap();
+        let bytes = val.to_bytes().unwrap();
+        assert_eq!(Value::from_bytes(&bytes).unwrap(), val);
+        assert!(Value::from_bytes(&[255]).is_err());
+    }
+
+    #[test]
+    fn npy_bytes() {
+        use super::*;
+        use encode::*;
+        let mut env = Uiua::with_safe_sys();
+        env.run_str("÷2 °△2_3_4").unwrap();
+        let val = env.pop("value").unwrap();
+        let bytes = value_to_npy_bytes(&val).unwrap();
+        assert_eq!(npy_bytes_to_value(&bytes).unwrap(), val);
+        // Fortran-ordered i4 array, as written by NumPy
+        let mut fortran = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+        let header = "{'descr': '<i4', 'fortran_order': True, 'shape': (2, 3), }";
+        fortran.extend(format!("{header:<117}\n").bytes());
+        fortran.extend(
+            [1, 4, 2, 5, 3, 6]
+                .iter()
+                .flat_map(|n: &i32| n.to_le_bytes()),
+        );
+        env.run_str("[1_2_3 4_5_6]").unwrap();
+        assert_eq!(
+            npy_bytes_to_value(&fortran).unwrap(),
+            env.pop("value").unwrap()
+        );
+        let arrays = vec![("a".to_string(), val.clone()), ("b".into(), "hi".into())];
+        let bytes = arrays_to_npz_bytes(&arrays).unwrap();
+        assert_eq!(npz_bytes_to_arrays(&bytes).unwrap(), arrays);
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn arrow_columns() {
+        use super::*;
+        use arrow_array::{Int32Array, RecordBatch, StringArray};
+        use std::sync::Arc;
+        let batch = RecordBatch::try_from_iter([
+            (
+                "n",
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])) as _,
+            ),
+            (
+                "s",
+                Arc::new(StringArray::from(vec![Some("a"), Some("b"), None])) as _,
+            ),
+        ])
+        .unwrap();
+        let columns = arrow::record_batch_to_columns(&batch).unwrap();
+        assert_eq!(columns[0].0, "n");
+        let Value::Num(n) = &columns[0].1 else {
+            panic!("expected numbers")
+        };
+        assert!(n.data[0] == 1.0 && n.data[1].is_nan() && n.data[2] == 3.0);
+        let bytes = arrow::columns_to_ipc_bytes(&columns[1..]).unwrap();
+        assert_eq!(arrow::ipc_bytes_to_columns(&bytes).unwrap(), columns[1..]);
+        assert!(arrow::ipc_bytes_to_columns(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "ndarray", feature = "nalgebra"))]
+    fn linalg_conversions() {
+        use super::*;
+        use nalgebra::DMatrix;
+        use ndarray::ArrayD;
+        let mut env = Uiua::with_safe_sys();
+        env.run_str("↯2_3⇡6").unwrap();
+        let value = env.pop("matrix").unwrap();
+        let nd = ArrayD::<f64>::try_from(&value).unwrap();
+        assert_eq!(nd.shape(), [2, 3]);
+        assert_eq!(nd[[1, 0]], 3.0);
+        assert_eq!(Value::from(nd), value);
+        let mat = DMatrix::<f64>::try_from(&value).unwrap();
+        assert_eq!((mat.nrows(), mat.ncols(), mat[(1, 0)]), (2, 3, 3.0));
+        assert_eq!(Value::from(mat), value);
+        assert!(DMatrix::<f64>::try_from(Value::from([1.0, 2.0])).is_err());
+        assert!(ArrayD::<f64>::try_from(Value::from("abc")).is_err());
+    }
+
     #[test]
     #[cfg(feature = "native_sys")]
     fn error_dont_crash() {
@@ -314,6 +580,589 @@ mod tests {
         }
     }
 
+    #[test]
+    fn constant_bindings() {
+        use super::*;
+        let asm = Compiler::new()
+            .load_str("T ← ⇡100000\nU ← ×2 T\nV ← ⚂")
+            .unwrap()
+            .finish();
+        let kind = |name: &str| {
+            let binding = asm.bindings.iter();
+            let mut binding = binding.filter(|b| b.span.as_str(&asm.inputs, |s| s == name));
+            binding.next().unwrap().kind.clone()
+        };
+        assert!(matches!(kind("T"), BindingKind::Const(Some(v)) if v.element_count() == 100000));
+        assert!(matches!(kind("U"), BindingKind::Const(Some(v)) if v.element_count() == 100000));
+        assert!(matches!(kind("V"), BindingKind::Const(None)));
+    }
+
+    #[test]
+    fn transpile_to_rust() {
+        use super::*;
+        let asm = Compiler::new()
+            .load_str("SumSq ← /+×.\nNorm ← √SumSq\nPrint ← &p")
+            .unwrap()
+            .finish();
+        let rust = transpile::assembly_to_rust(&asm);
+        assert_eq!(rust.functions, ["SumSq", "Norm"]);
+        assert_eq!(rust.skipped.len(), 1);
+        assert_eq!(rust.skipped[0].0, "Print");
+        assert!(rust.source.contains("pub fn sum_sq(a0: &Arr) -> Arr"));
+        assert!(rust.source.contains("pub fn norm(a0: &Arr) -> Arr"));
+    }
+
+    #[test]
+    fn transpiled_rust_matches_interpreter() {
+        use super::*;
+        use std::{fs, process::Command};
+
+        let code = "\
+SumSq ← /+×.
+Norm ← √SumSq
+Cumul ← \\+⇌
+Double ← ⊟×2.
+Axpy ← +×
+Tr ← ⍉";
+        // Uiua calls and the equivalent Rust calls
+        let cases = [
+            ("SumSq [1 2 3]", "sum_sq(&Arr::list(vec![1.0, 2.0, 3.0]))"),
+            ("Norm [3 4 12]", "norm(&Arr::list(vec![3.0, 4.0, 12.0]))"),
+            (
+                "Cumul [1 2 3 4]",
+                "cumul(&Arr::list(vec![1.0, 2.0, 3.0, 4.0]))",
+            ),
+            ("Double [1.5 ¯2]", "double(&Arr::list(vec![1.5, -2.0]))"),
+            (
+                "Axpy 2 [1 2] [3 4]",
+                "axpy(&Arr::scalar(2.0), &Arr::list(vec![1.0, 2.0]), &Arr::list(vec![3.0, 4.0]))",
+            ),
+            (
+                "Tr [1_2_3 4_5_6]",
+                "tr(&Arr::new(vec![2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]))",
+            ),
+        ];
+        let asm = Compiler::new().load_str(code).unwrap().finish();
+        let rust = transpile::assembly_to_rust(&asm);
+        assert!(rust.skipped.is_empty(), "{:?}", rust.skipped);
+
+        // Run the functions in the interpreter
+        let expected: Vec<String> = (cases.iter())
+            .map(|(call, _)| {
+                let mut env = Uiua::with_safe_sys();
+                env.run_str(&format!("{code}\n{call}")).unwrap();
+                let mut val = env.pop("result").unwrap();
+                let shape = val.shape().dims().to_vec();
+                val.deshape();
+                let nums = val.as_nums(&env, "").unwrap();
+                format!("{shape:?} {nums:?}")
+            })
+            .collect();
+
+        // Compile and run the generated Rust
+        let mut source = rust.source;
+        source.push_str("\nfn main() {\n");
+        for (_, call) in &cases {
+            source.push_str(&format!(
+                "    let res = {call};\n    println!(\"{{:?}} {{:?}}\", res.shape, res.data);\n"
+            ));
+        }
+        source.push_str("}\n");
+        let dir = std::env::temp_dir().join(format!("uiua_transpile_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let src_path = dir.join("main.rs");
+        let bin_path = dir.join("main");
+        fs::write(&src_path, source).unwrap();
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+        let compiled = Command::new(rustc)
+            .args(["--edition", "2021", "-o"])
+            .arg(&bin_path)
+            .arg(&src_path)
+            .output()
+            .unwrap();
+        let output = compiled
+            .status
+            .success()
+            .then(|| Command::new(&bin_path).output().unwrap());
+        _ = fs::remove_dir_all(&dir);
+        assert!(
+            compiled.status.success(),
+            "{}",
+            String::from_utf8_lossy(&compiled.stderr)
+        );
+        let output = output.unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let actual: Vec<&str> = stdout.lines().collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn disassemble() {
+        use super::*;
+        let asm = Compiler::new()
+            .load_str("F ← /+×.\nG ← ⊃(+|×)\nX ← 5")
+            .unwrap()
+            .finish();
+        let f = disasm::disassemble(&asm, Some("F")).unwrap();
+        let mut lines = f.lines();
+        assert!(lines.next().unwrap().starts_with("F |1.1 at "));
+        let reduce = lines.nth(1).unwrap();
+        assert!(reduce.trim_start().starts_with('/'));
+        assert!(reduce.ends_with("|1.1  1:5"));
+        let all = disasm::disassemble(&asm, None).unwrap();
+        assert!(all.contains("function 1"));
+        assert!(!all.contains("X "));
+        assert!(disasm::disassemble(&asm, Some("X")).is_err());
+        assert!(disasm::disassemble(&asm, Some("Y")).is_err());
+    }
+
+    #[test]
+    fn shared_asm() {
+        use super::*;
+        use std::{sync::Arc, thread};
+        let asm = Compiler::new()
+            .load_str("F ← +1\nX ← ⇡⋕\"1000\"\nF /+ X")
+            .unwrap()
+            .finish();
+        let asm = Arc::new(asm);
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let asm = asm.clone();
+                thread::spawn(move || {
+                    let mut env = Uiua::default();
+                    env.run_shared_asm(&asm).unwrap();
+                    assert_eq!(env.asm.functions.as_ptr(), asm.functions.as_ptr());
+                    env.pop_int().unwrap()
+                })
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), 499501);
+        }
+    }
+
+    #[test]
+    fn fuel() {
+        use super::*;
+        let mut env = Uiua::default().with_fuel(1000);
+        env.run_str("+ 1 2").unwrap();
+        let remaining = env.fuel().unwrap();
+        assert!(remaining < 1000);
+        env.push(0);
+        let Err(err) = env.run_str("⍢(+1)1") else {
+            panic!("infinite loop did not run out of fuel")
+        };
+        assert!(matches!(err.kind, UiuaErrorKind::OutOfFuel(..)));
+        assert_eq!(env.fuel(), Some(0));
+        env.set_fuel(None);
+        env.run_str("⍢(+1)(<100) 0").unwrap();
+        assert_eq!(env.fuel(), None);
+    }
+
+    #[test]
+    fn fill_api() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        env.with_fill(0.into(), |env| env.run_str("↙4 [1 2]"))
+            .unwrap();
+        assert_eq!(env.pop(1).unwrap().format(), "[1 2 0 0]");
+        env.with_fills([0.into(), '-'.into()], |env| {
+            env.run_str("↙3 [1 2]\n↙3 \"ab\"")
+        })
+        .unwrap();
+        assert_eq!(env.pop(1).unwrap().format(), "ab-");
+        assert_eq!(env.pop(2).unwrap().format(), "[1 2 0]");
+        let res = env.with_fills([0.into(), 1.into()], |_| Ok(()));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn contracts() {
+        use super::*;
+        let code = "# Experimental!\nrequires(≥0|√) ¯4\nensures(>0|∘) 0";
+        assert!(Uiua::with_safe_sys().run_str(code).is_err());
+        let mut env = Uiua::with_safe_sys().with_contracts(false);
+        assert!(!env.contracts());
+        env.run_str(code).unwrap();
+        assert_eq!(env.pop(1).unwrap().format(), "0");
+        let Err(err) = env.run_str("# Experimental!\nexpect 1 2") else {
+            panic!("expect was disabled with contracts")
+        };
+        assert_eq!(err.message(), "Expected 1, but got 2");
+    }
+
+    #[test]
+    fn settings() {
+        use super::*;
+        let path = std::env::temp_dir().join("uiua_settings_test.ua");
+        std::fs::write(&path, "Fuel ← 1000\nParallelism ← \"off\"\nContracts ← 0").unwrap();
+        let from_file = Settings::from_file(&path).unwrap();
+        let mut flags = Settings::default();
+        flags.fuel = Some(10);
+        let settings = from_file.layer(flags.mark_as_flags());
+        let entries = settings.entries();
+        let entry = |name: &str| entries.iter().find(|e| e.name == name).unwrap();
+        assert_eq!(entry("Fuel").value.as_deref(), Some("10"));
+        assert_eq!(entry("Fuel").source, SettingSource::Flag);
+        assert_eq!(entry("Parallelism").value.as_deref(), Some("off"));
+        assert_eq!(
+            entry("Parallelism").source,
+            SettingSource::File(path.clone())
+        );
+        assert_eq!(entry("Tolerance").source, SettingSource::Default);
+        let env = Uiua::with_safe_sys().with_settings(&settings);
+        assert_eq!(env.fuel(), Some(10));
+        assert_eq!(env.parallelism(), Parallelism::Off);
+        assert!(!env.contracts());
+        std::fs::write(&path, "Fuel ← ¯1").unwrap();
+        assert!(Settings::from_file(&path).is_err());
+        std::fs::write(&path, "ExecutionLimit ← ∞").unwrap();
+        assert!(Settings::from_file(&path).is_err());
+        std::fs::write(&path, "HttpTimeout ← 1e300").unwrap();
+        let settings = Settings::from_file(&path).unwrap();
+        let env = Uiua::with_safe_sys().with_settings(&settings);
+        assert_eq!(env.http_timeout(), Some(std::time::Duration::MAX));
+        std::fs::write(&path, "Permissions ← \"stdio\"").unwrap();
+        let settings = Settings::from_file(&path).unwrap();
+        let mut env = Uiua::with_safe_sys().with_settings(&settings);
+        assert!(env.run_str("&p 5").is_ok());
+        let Err(err) = env.run_str("&fe \"example.ua\"") else {
+            panic!("&fe should not be permitted");
+        };
+        assert!(err.to_string().contains("not permitted"));
+        std::fs::write(&path, "Permissions ← \"files\"").unwrap();
+        assert!(Settings::from_file(&path).is_err());
+        _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn seed() {
+        use super::*;
+        let run = |seed| {
+            let mut env = Uiua::with_safe_sys().with_seed(seed);
+            env.run_str("# Experimental!\n⊟⊃(uniform 5|[⍥⚂5])").unwrap();
+            env.pop(1).unwrap()
+        };
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn locale() {
+        use super::*;
+        assert_eq!("es_ES.UTF-8".parse(), Ok(Locale::Spanish));
+        assert!("xx".parse::<Locale>().is_err());
+        for id in MessageId::ALL {
+            for locale in Locale::ALL {
+                let template = id.template(locale);
+                let count = |t: &str| t.matches('{').count();
+                assert_eq!(count(template), count(id.template(Locale::English)));
+            }
+        }
+        let run = |locale, code| {
+            let mut env = Uiua::with_safe_sys().with_locale(locale);
+            env.run_str(code).err().unwrap()
+        };
+        let error = run(Locale::English, "+1");
+        assert_eq!(error.message_id, Some(MessageId::StackEmpty));
+        assert!(error.message().starts_with("Stack was empty"));
+        let error = run(Locale::German, "+1");
+        assert_eq!(error.message_id, Some(MessageId::StackEmpty));
+        assert!(error.message().starts_with("Der Stapel war leer"));
+        let error = run(Locale::Spanish, "+1");
+        assert_eq!(
+            error.message(),
+            "La pila estaba vacía al obtener argumento 2"
+        );
+        let error = run(Locale::Spanish, "foo");
+        assert_eq!(error.message_id, Some(MessageId::UnknownIdentifier));
+        assert_eq!(error.message(), "Identificador desconocido `foo`");
+        let error = run(Locale::Spanish, "+[1 2] [1 2 3]");
+        assert_eq!(error.message_id, Some(MessageId::IncompatibleShapes));
+        assert_eq!(error.message(), "Las formas [2] y [3] no son compatibles");
+    }
+
+    #[test]
+    fn edition() {
+        use super::*;
+        assert_eq!("2025".parse(), Ok(Edition::E2025));
+        assert!("2023".parse::<Edition>().is_err());
+        let compile = |code: &str| {
+            let mut comp = Compiler::new();
+            let res = comp.load_str(code).map(drop);
+            (res, comp.take_diagnostics())
+        };
+        // Deprecated primitives are warnings in the default edition
+        let (res, diagnostics) = compile("⸮1");
+        assert!(res.is_ok());
+        assert!(diagnostics.iter().any(|d| d.message.contains("deprecated")));
+        // and errors in later editions
+        let (res, _) = compile("# Edition! 2025\n⸮1");
+        let error = res.err().unwrap().to_string();
+        assert!(error.contains("removed in edition 2025"), "{error}");
+        // Old glyphs are only accepted in the default edition
+        assert!(compile("◫2 [1 2 3]").0.is_ok());
+        assert!(compile("# Edition! 2025\n◫2 [1 2 3]").0.is_err());
+        let (res, _) = compile("# Experimental!\n# Edition! 2025\n◠+ 1 2");
+        assert!(res.is_ok());
+        assert!(compile("# Experimental!\n◠¯ 1").0.is_ok());
+        assert!(compile("# Experimental!\n# Edition! 2025\n◠¯ 1").0.is_err());
+        assert!(compile("# Edition! 2023\n1").0.is_err());
+        assert!(compile("# Edition! 2024\n⸮1").0.is_ok());
+    }
+
+    #[test]
+    fn macro_signatures() {
+        use super::*;
+        let compile = |code: &str| Compiler::new().load_str(code).map(drop);
+        assert!(compile("F! ← |2 /^0⊂\nF!+ [1] [2 3]").is_ok());
+        assert!(compile("F! ← |2 ⊂^0\nF!(+1) 1 2").is_ok());
+        let error = compile("F! ← |2 /^0⊂\nF!(⊂1) [1] [2 3]")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("declared as |2.1"), "{error}");
+        assert!(compile("F! ← |2 ⊂^0\nF!+ 1 2 3").is_err());
+    }
+
+    #[test]
+    fn trace() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys().with_trace(true);
+        env.run_str("F ← |1 +1⚂\n≡F ⇡3").unwrap();
+        let events = env.trace().unwrap().events();
+        let count = |name: &str, category: &str| {
+            (events.iter())
+                .filter(|event| event.name == name && event.category == category)
+                .count()
+        };
+        assert_eq!(count("F", "function"), 3);
+        assert_eq!(count("rows", "primitive"), 1);
+        let json: serde_json::Value =
+            serde_json::from_str(&env.trace().unwrap().to_chrome_json()).unwrap();
+        assert_eq!(json["traceEvents"].as_array().unwrap().len(), events.len());
+        assert_eq!(json["traceEvents"][0]["ph"], "X");
+        assert!(Uiua::with_safe_sys().trace().is_none());
+    }
+
+    #[test]
+    fn error_categories() {
+        use super::*;
+        let error = |code: &str| match Uiua::with_safe_sys().run_str(code) {
+            Ok(_) => panic!("{code:?} did not fail"),
+            Err(err) => err,
+        };
+        let category = |code: &str| error(code).category;
+        assert_eq!(category("(+"), ErrorCategory::Parse);
+        assert_eq!(category("⨬(1|1 2) 0"), ErrorCategory::Signature);
+        assert_eq!(category("F ← F\nF"), ErrorCategory::Signature);
+        assert_eq!(category("⍤\"Oh no!\" 0"), ErrorCategory::Runtime);
+        assert_eq!(category("&fo \"nonexistent.txt\""), ErrorCategory::System);
+        assert_eq!(category("F ← |1 (F -1)\nF 5"), ErrorCategory::Limit);
+
+        let err = error("1\n+ [1 2] [1 2 3]");
+        assert_eq!(err.category, ErrorCategory::Runtime);
+        assert!(!err.is_bug());
+        assert_eq!(err.message(), "Shapes [2] and [3] are not compatible");
+        let Some(Span::Code(span)) = err.span() else {
+            panic!("runtime error has no span")
+        };
+        assert_eq!(span.start.line, 2);
+
+        let err = error("F ← |1 (F -1)\nF 5");
+        assert!(!err.call_trace().is_empty());
+    }
+
+    #[test]
+    fn shape_diagrams() {
+        use super::*;
+        let infos = |code: &str| match Uiua::with_safe_sys().run_str(code) {
+            Ok(_) => panic!("{code:?} did not fail"),
+            Err(err) => err
+                .infos
+                .into_iter()
+                .map(|(info, _)| info)
+                .collect::<Vec<_>>(),
+        };
+        let pervasive = infos("+ ↯2_3_4 0 ↯2_5 0");
+        assert_eq!(
+            pervasive[0],
+            "The shapes differ along axis 1\n  [2 × 3 × 4]\n  [2 × 5]\n       ─"
+        );
+        assert!(pervasive[1].contains("fill"));
+        let rows = infos("≡+ ↯10_3 0 ↯2_3 0");
+        assert_eq!(
+            rows[0],
+            "The shapes differ along axis 0\n  [10 × 3]\n  [ 2 × 3]\n   ──"
+        );
+        let mut env = Uiua::with_safe_sys().with_locale(Locale::Spanish);
+        let Err(err) = env.run_str("+ ↯2_3_4 0 ↯2_5 0") else {
+            panic!("shapes should not match");
+        };
+        assert!(err.infos[0]
+            .0
+            .starts_with("Las formas difieren en el eje 1"));
+        assert!(err.infos[1].0.starts_with("Usa"));
+    }
+
+    #[test]
+    fn no_panic() {
+        use super::*;
+        use std::{thread, time::Duration};
+
+        // Values and functions that tend to hit edge cases
+        let values = [
+            "[]",
+            "0",
+            "¯1",
+            "1.5",
+            "∞",
+            "NaN",
+            "1e300",
+            "@a",
+            "\"ab\"",
+            "[1 2 3]",
+            "[[1 2] [3 4]]",
+            "°△0_3",
+            "{1 \"ab\"}",
+        ];
+        let funcs = ["+", "⇌", "□", "1"];
+        let tuples = |n: usize, values: &[&str]| {
+            let mut tuples = vec![String::new()];
+            for _ in 0..n {
+                tuples = (tuples.iter())
+                    .flat_map(|t| values.iter().map(move |v| format!("{t} {v}")))
+                    .collect();
+            }
+            tuples
+        };
+        let mut codes = Vec::new();
+        for prim in Primitive::non_deprecated() {
+            if matches!(prim, Primitive::Sys(_)) || prim.purity() != Purity::Pure {
+                continue;
+            }
+            match (prim.modifier_args(), prim.args()) {
+                (None, Some(n @ 1..=2)) => {
+                    codes.extend(
+                        tuples(n, &values)
+                            .into_iter()
+                            .map(|args| format!("{prim}{args}")),
+                    );
+                }
+                (None, Some(3)) => {
+                    codes.extend(
+                        tuples(3, &values[..6])
+                            .into_iter()
+                            .map(|args| format!("{prim}{args}")),
+                    );
+                }
+                (Some(m @ 1..=2), _) => {
+                    let args = (tuples(1, &values).into_iter())
+                        .chain(tuples(1, &values).into_iter().map(|a| format!(" []{a}")))
+                        .chain(tuples(1, &values).into_iter().map(|a| format!(" °△0_3{a}")))
+                        .collect::<Vec<_>>();
+                    for fs in tuples(m, &funcs) {
+                        let fs = fs.trim().replace(' ', "|");
+                        for args in &args {
+                            codes.push(format!("{prim}({fs}){args}"));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = codes.len().div_ceil(threads);
+        thread::scope(|s| {
+            for chunk in codes.chunks(chunk_size) {
+                s.spawn(move || {
+                    for code in chunk {
+                        let code = format!("# Experimental!\n{code}");
+                        let mut env = Uiua::with_safe_sys()
+                            .hardened(true)
+                            .with_execution_limit(Duration::from_millis(100));
+                        if let Err(e) = env.run_str(&code) {
+                            assert!(!e.is_bug(), "{code}\n{e}");
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn run_batch() {
+        use super::*;
+        let asm = Compiler::new().load_str("F ← ⊂⊙+\nF").unwrap().finish();
+        let mut env = Uiua::default();
+        let mut outputs = env.run_batch(asm, [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8]]);
+        assert!(outputs.pop().unwrap().is_err());
+        let outputs: Vec<Vec<f64>> = (outputs.into_iter())
+            .map(|res| res.unwrap()[0].as_nums(&env, "").unwrap())
+            .collect();
+        assert_eq!(outputs, [[3.0, 3.0], [6.0, 9.0]]);
+        assert!(env.stack().is_empty());
+    }
+
+    #[test]
+    fn array_row_views() {
+        use super::*;
+        let arr = Array::<f64>::new([2, 3], [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let rows: Vec<_> = arr.row_views().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].shape(), [3]);
+        assert_eq!(rows[1].data(), [4.0, 5.0, 6.0]);
+        assert!(std::ptr::eq(rows[0].data(), arr.row_slice(0)));
+        let cells: Vec<_> = rows[1].row_views().map(|cell| cell.data()[0]).collect();
+        assert_eq!(cells, [4.0, 5.0, 6.0]);
+        assert_eq!(rows[0].to_array(), arr.row(0));
+    }
+
+    #[test]
+    fn value_builder() {
+        use super::*;
+        let table = ValueBuilder::array()
+            .push_str("ab")
+            .push_str("cd")
+            .build()
+            .unwrap();
+        assert_eq!(table.shape(), &[2, 2]);
+        let nested = ValueBuilder::list()
+            .push_num(1.0)
+            .push_builder(ValueBuilder::list().push_str("x"))
+            .build()
+            .unwrap();
+        let mut env = Uiua::with_safe_sys();
+        env.push(nested);
+        env.run_str("°□⊢°□⊣").unwrap();
+        assert_eq!(env.pop_string().unwrap(), "x");
+        let err = ValueBuilder::array()
+            .push_nums([1.0])
+            .push_str("a")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "Row 1: Cannot add character row to an array of numbers"
+        );
+        let err = ValueBuilder::list()
+            .push_builder(ValueBuilder::array().push_num(1.0).push_nums([2.0]))
+            .build()
+            .unwrap_err();
+        assert!(err.starts_with("Row 0: Row 1: Row has shape [1]"));
+    }
+
+    #[test]
+    fn type_annotations() {
+        use super::*;
+        let code = "# ? X:Char[n] Y:Num[n]\nF ← ⊙◌\nF \"ab\" [1 2 3]";
+        assert!(Compiler::new().load_str(code).is_ok());
+        let Err(err) = Compiler::new().load_str(&format!("# Experimental!\n{code}")) else {
+            panic!("mismatched annotation was not caught at compile time")
+        };
+        assert!(err.to_string().contains("argument `Y` should be Num[n]"));
+    }
+
     #[test]
     #[cfg(feature = "native_sys")]
     fn assembly_round_trip() {
@@ -754,6 +754,14 @@ impl Spanner {
                     }
                     spans.push(ident.span.clone().sp(mac_delim_kind));
                 }
+                Word::LocalBinding(binding) => {
+                    spans.push(binding.name.span.clone().sp(SpanKind::Ident {
+                        docs: None,
+                        original: true,
+                    }));
+                    spans.push(binding.arrow_span.clone().sp(SpanKind::Delimiter));
+                    spans.extend(self.words_spans(&binding.words));
+                }
             }
         }
         spans.retain(|sp| !sp.span.as_str(self.inputs(), str::is_empty));
@@ -27,9 +27,9 @@ use uiua::{
     format::{format_file, format_str, FormatConfig, FormatConfigSource},
     lex,
     lsp::BindingDocsKind,
-    print_stack, Assembly, CodeSpan, Compiler, NativeSys, PreEvalMode, PrimClass, PrimDocFragment,
-    PrimDocLine, Primitive, RunMode, SafeSys, SpanKind, Spans, Token, Uiua, UiuaError,
-    UiuaErrorKind, UiuaResult, CONSTANTS,
+    print_stack, set_display_precision, Assembly, CodeSpan, Compiler, NativeSys, Parallelism,
+    PreEvalMode, PrimClass, PrimDocFragment, PrimDocLine, Primitive, RunMode, SafeSys, Settings,
+    SpanKind, Spans, Token, Uiua, UiuaError, UiuaErrorKind, UiuaResult, CONSTANTS,
 };
 
 static PRESSED_CTRL_C: AtomicBool = AtomicBool::new(false);
@@ -126,8 +126,22 @@ fn main() {
         .next()
         .filter(|arg| arg.ends_with(".ua") || arg.contains(is_separator))
     {
+        // Allow separating the program's arguments with `--`, as with `uiua run`
+        if args.peek().is_some_and(|arg| arg == "--") {
+            args.next();
+        }
         let args = args.collect();
-        run(path.as_ref(), args, false, None, None, None, false);
+        let settings = Settings::load(Some(path.as_ref())).unwrap_or_else(fail);
+        run(
+            path.as_ref(),
+            args,
+            false,
+            None,
+            &settings,
+            None,
+            None,
+            false,
+        );
         return;
     }
 
@@ -181,6 +195,9 @@ fn main() {
             time_instrs,
             limit,
             mode,
+            parallelism,
+            no_contracts,
+            trace_out,
             #[cfg(feature = "audio")]
             audio_options,
             window,
@@ -197,6 +214,12 @@ fn main() {
                     }
                 }
             };
+            let mut flags = Settings::default();
+            flags.execution_limit = limit;
+            flags.parallelism = parallelism;
+            flags.contracts = no_contracts.then_some(false);
+            let settings =
+                (Settings::load(Some(&path)).unwrap_or_else(fail)).layer(flags.mark_as_flags());
             #[cfg(feature = "audio")]
             setup_audio(audio_options);
             set_use_window(window);
@@ -204,13 +227,21 @@ fn main() {
                 &path,
                 args,
                 time_instrs,
-                limit,
                 mode,
+                &settings,
+                trace_out,
                 (!no_format).then_some(formatter_options),
                 no_color,
             );
         }
-        Some(Comm::Build { path, output }) => {
+        Some(Comm::Build {
+            path,
+            output,
+            #[cfg(feature = "stand")]
+            exe,
+            #[cfg(feature = "stand")]
+            runtime,
+        }) => {
             let path = if let Some(path) = path {
                 path
             } else {
@@ -228,12 +259,82 @@ fn main() {
                 .load_file(&path)
                 .unwrap_or_else(fail)
                 .finish();
+            #[cfg(feature = "stand")]
+            if exe {
+                let bytes =
+                    uiua::stand::bundle_exe(&assembly, runtime.as_deref()).unwrap_or_else(fail);
+                let output = output.unwrap_or_else(|| {
+                    let extension = match &runtime {
+                        Some(runtime) => runtime.extension().unwrap_or_default(),
+                        None => env::consts::EXE_EXTENSION.as_ref(),
+                    };
+                    path.with_extension(extension)
+                });
+                if let Err(e) = uiua::stand::write_exe(&output, &bytes) {
+                    eprintln!("Failed to write executable: {e}");
+                    exit(1);
+                }
+                return;
+            }
             let output = output.unwrap_or_else(|| path.with_extension("uasm"));
             let uasm = assembly.to_uasm();
             if let Err(e) = fs::write(output, uasm) {
                 eprintln!("Failed to write assembly: {e}");
             }
         }
+        Some(Comm::Transpile { path, output }) => {
+            let path = if let Some(path) = path {
+                path
+            } else {
+                match working_file_path() {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return;
+                    }
+                }
+            };
+            let assembly = Compiler::with_backend(NativeSys)
+                .mode(RunMode::Normal)
+                .print_diagnostics(true)
+                .load_file(&path)
+                .unwrap_or_else(fail)
+                .finish();
+            let rust = uiua::transpile::assembly_to_rust(&assembly);
+            for (name, reason) in &rust.skipped {
+                eprintln!("Skipped {name}: {reason}");
+            }
+            let output = output.unwrap_or_else(|| path.with_extension("rs"));
+            if let Err(e) = fs::write(output, rust.source) {
+                eprintln!("Failed to write Rust source: {e}");
+            }
+        }
+        Some(Comm::Disasm { path, name }) => {
+            let path = if let Some(path) = path {
+                path
+            } else {
+                match working_file_path() {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return;
+                    }
+                }
+            };
+            let assembly = Compiler::with_backend(NativeSys)
+                .mode(RunMode::Normal)
+                .print_diagnostics(true)
+                .load_file(&path)
+                .unwrap_or_else(fail)
+                .finish();
+            match uiua::disasm::disassemble(&assembly, name.as_deref()) {
+                Ok(disasm) => print!("{disasm}"),
+                Err(e) => {
+                    eprintln!("{e}");
+                    exit(1);
+                }
+            }
+        }
         Some(Comm::Eval {
             code,
             no_color,
@@ -244,7 +345,11 @@ fn main() {
         }) => {
             #[cfg(feature = "audio")]
             setup_audio(audio_options);
-            let mut rt = Uiua::with_native_sys().with_args(args);
+            let settings = Settings::load(None).unwrap_or_else(fail);
+            set_display_precision(settings.display_precision);
+            let mut rt = Uiua::with_native_sys()
+                .with_args(args)
+                .with_settings(&settings);
             rt.compile_run(|comp| {
                 comp.mode(RunMode::Normal)
                     .experimental(experimental)
@@ -274,9 +379,12 @@ fn main() {
                 FormatConfig::from_source(formatter_options.format_config_source, Some(&path))
                     .unwrap_or_else(fail);
             format_file(&path, &config).unwrap_or_else(fail);
+            let settings = Settings::load(Some(&path)).unwrap_or_else(fail);
+            set_display_precision(settings.display_precision);
             let mut rt = Uiua::with_native_sys()
                 .with_file_path(&path)
-                .with_args(args);
+                .with_args(args)
+                .with_settings(&settings);
             let res = rt.compile_run(|comp| {
                 comp.mode(RunMode::Test)
                     .print_diagnostics(true)
@@ -322,6 +430,7 @@ fn main() {
             #[cfg(feature = "audio")]
             audio_options,
             stack,
+            load,
             args,
         }) => {
             let config = FormatConfig {
@@ -332,14 +441,21 @@ fn main() {
 
             #[cfg(feature = "audio")]
             setup_audio(audio_options);
-            let mut rt = Uiua::with_native_sys().with_args(args);
+            let settings = Settings::load(file.as_deref()).unwrap_or_else(fail);
+            set_display_precision(settings.display_precision);
+            let mut rt = Uiua::with_native_sys()
+                .with_args(args)
+                .with_settings(&settings);
             let mut compiler = Compiler::with_backend(NativeSys);
-            compiler.mode(RunMode::Normal).print_diagnostics(true);
+            compiler
+                .mode(RunMode::Normal)
+                .print_diagnostics(true)
+                .locale(rt.locale());
             if let Some(file) = file {
                 compiler.load_file(file).unwrap_or_else(fail);
                 rt.run_compiler(&mut compiler).unwrap_or_else(fail);
             }
-            repl(rt, compiler, true, stack, config);
+            repl(rt, compiler, load, true, stack, config);
         }
         Some(Comm::Update {
             main,
@@ -394,22 +510,10 @@ fn main() {
                         })
                         .unwrap_or_else(|| "program".into());
                     let path = PathBuf::from(name).with_extension(env::consts::EXE_EXTENSION);
-                    #[allow(clippy::needless_borrows_for_generic_args)]
-                    if let Err(e) = fs::write(&path, bytes) {
+                    if let Err(e) = uiua::stand::write_exe(&path, &bytes) {
                         eprintln!("Failed to write executable: {e}");
                         exit(1);
                     }
-                    // Set executable permissions on Unix
-                    #[cfg(unix)]
-                    if let Err(e) = (|| {
-                        use std::os::unix::fs::PermissionsExt;
-                        let mut perms = fs::metadata(&path)?.permissions();
-                        perms.set_mode(0o755);
-                        fs::set_permissions(&path, perms)
-                    })() {
-                        eprintln!("Failed to set executable permissions: {e}");
-                        exit(1);
-                    }
                 }
                 Err(e) => {
                     eprintln!("Failed to build executable: {e}");
@@ -420,6 +524,7 @@ fn main() {
         Some(Comm::Doc { name }) => doc(&name),
         Some(Comm::Check { path }) => check(path).unwrap_or_else(fail),
         Some(Comm::Find { path, text, raw }) => find(path, text, raw).unwrap_or_else(fail),
+        Some(Comm::Config { path }) => config(path.as_deref()).unwrap_or_else(fail),
         None => {
             set_use_window(app.window);
             let res = match working_file_path() {
@@ -451,20 +556,24 @@ fn main() {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run(
     path: &Path,
     args: Vec<String>,
     time_instrs: bool,
-    limit: Option<f64>,
     mode: Option<RunMode>,
+    settings: &Settings,
+    trace_out: Option<PathBuf>,
     formatter_options: Option<FormatterOptions>,
     no_color: bool,
 ) {
+    set_display_precision(settings.display_precision);
     let mut rt = Uiua::with_native_sys()
         .with_file_path(path)
         .with_args(args)
         .time_instrs(time_instrs)
-        .maybe_with_execution_limit(limit.map(Duration::from_secs_f64));
+        .with_settings(settings)
+        .with_trace(trace_out.is_some());
     if path.extension().is_some_and(|ext| ext == "uasm") {
         let uasm = match fs::read_to_string(path) {
             Ok(json) => json,
@@ -490,6 +599,11 @@ fn run(
         }
         let mode = mode.unwrap_or(RunMode::Normal);
         let res = rt.compile_run(|comp| comp.mode(mode).print_diagnostics(true).load_file(path));
+        if let Some((trace_out, trace)) = trace_out.as_ref().zip(rt.trace()) {
+            if let Err(e) = fs::write(trace_out, trace.to_chrome_json()) {
+                eprintln!("Failed to write trace: {e}");
+            }
+        }
         if let Err(e) = &res {
             println!("{}", e.report());
         }
@@ -761,6 +875,18 @@ enum Comm {
         limit: Option<f64>,
         #[clap(long, help = "Run the file in a specific mode")]
         mode: Option<RunMode>,
+        #[clap(
+            long,
+            help = "How loops may be run in parallel (off, auto, or deterministic)"
+        )]
+        parallelism: Option<Parallelism>,
+        #[clap(long, help = "Don't check the conditions of requires and ensures")]
+        no_contracts: bool,
+        #[clap(
+            long,
+            help = "Write an execution trace in the Chrome trace-event format to a file"
+        )]
+        trace_out: Option<PathBuf>,
         #[cfg(feature = "audio")]
         #[clap(flatten)]
         audio_options: AudioOptions,
@@ -779,6 +905,29 @@ enum Comm {
         path: Option<PathBuf>,
         #[clap(short, long, help = "The path to the output file")]
         output: Option<PathBuf>,
+        #[cfg(feature = "stand")]
+        #[clap(long, help = "Build a standalone executable instead of an assembly")]
+        exe: bool,
+        #[cfg(feature = "stand")]
+        #[clap(
+            long,
+            requires = "exe",
+            help = "The uiua executable to bundle the program with. \
+                    Use one built for another platform to cross-compile."
+        )]
+        runtime: Option<PathBuf>,
+    },
+    #[clap(about = "Transpile numeric functions to Rust source (experimental)")]
+    Transpile {
+        path: Option<PathBuf>,
+        #[clap(short, long, help = "The path to the output file")]
+        output: Option<PathBuf>,
+    },
+    #[clap(about = "Print the compiled instructions of a file's functions")]
+    Disasm {
+        path: Option<PathBuf>,
+        #[clap(short, long, help = "Only disassemble the function with this name")]
+        name: Option<String>,
     },
     #[clap(about = "Evaluate an expression and print its output")]
     Eval {
@@ -849,6 +998,11 @@ enum Comm {
         #[clap(long, help = "Disable color and other formatting")]
         raw: bool,
     },
+    #[clap(about = "Show the effective interpreter settings")]
+    Config {
+        #[clap(help = "The file whose settings to show")]
+        path: Option<PathBuf>,
+    },
     #[clap(about = "Run the Uiua interpreter in a REPL")]
     Repl {
         #[clap(help = "A Uiua file to run before the REPL starts")]
@@ -860,6 +1014,12 @@ enum Comm {
         audio_options: AudioOptions,
         #[clap(short = 's', long, help = "Don't clear the stack after each line")]
         stack: bool,
+        #[clap(
+            short = 'l',
+            long,
+            help = "Session scripts to run in the REPL before it starts"
+        )]
+        load: Vec<PathBuf>,
         #[clap(trailing_var_arg = true)]
         args: Vec<String>,
     },
@@ -1095,81 +1255,235 @@ fn format_multi_files(config: &FormatConfig) -> Result<(), UiuaError> {
     Ok(())
 }
 
-fn repl(mut env: Uiua, mut compiler: Compiler, color: bool, stack: bool, config: FormatConfig) {
-    env = env.with_interrupt_hook(|| PRESSED_CTRL_C.swap(false, Ordering::Relaxed));
-    compiler.pre_eval_mode(PreEvalMode::Line);
+fn repl(
+    env: Uiua,
+    compiler: Compiler,
+    load: Vec<PathBuf>,
+    color: bool,
+    stack: bool,
+    config: FormatConfig,
+) {
+    let mut repl = Repl {
+        env: env.with_interrupt_hook(|| PRESSED_CTRL_C.swap(false, Ordering::Relaxed)),
+        compiler,
+        color,
+        stack,
+        config,
+        session: Vec::new(),
+    };
+    repl.compiler.pre_eval_mode(PreEvalMode::Line);
     println!(
         "Uiua {} (end with ctrl+C, type `help` for a list of commands)\n",
         env!("CARGO_PKG_VERSION")
     );
+    for path in load {
+        repl.load(&path);
+    }
     let mut line_reader = DefaultEditor::new().expect("Failed to read from Stdin");
     loop {
-        let mut code = match line_reader.readline("    ") {
-            Ok(code) => {
-                match code.trim() {
-                    "help" => {
-                        println!(
-                            "\n\
-                            clear - Clear the stack \n\
-                            exit  - Exit the repl \n\
-                            help  - Show this message \n\
-                            "
-                        );
-                        continue;
-                    }
-                    "clear" | "cls" => {
-                        env.take_stack();
-                        println!();
-                        continue;
-                    }
-                    "exit" => break,
-                    _ => {}
-                }
-                code
-            }
+        let code = match line_reader.readline("    ") {
+            Ok(code) => code,
             Err(ReadlineError::Eof | ReadlineError::Interrupted) => break,
             Err(_) => panic!("Failed to read from Stdin"),
         };
-        if code.is_empty() {
+        let trimmed = code.trim();
+        if trimmed.is_empty() {
             continue;
         }
-
-        match format_str(&code, &config) {
-            Ok(formatted) => {
-                code = formatted.output;
+        let (command, arg) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+        let arg = arg.trim();
+        match command {
+            "help" | ":help" => {
+                println!(
+                    "\n\
+                    :load <file>    - Run a file in the session \n\
+                    :save <file>    - Save the session's successful lines to a file \n\
+                    :type <code>    - Show the signature of some code without running it \n\
+                    :time <code>    - Run some code and show how long it took \n\
+                    :explore        - Explore the value on top of the stack \n\
+                    :explore <code> - Run some code and explore its result \n\
+                    :clear          - Clear the stack \n\
+                    exit            - Exit the repl \n\
+                    help            - Show this message \n\
+                    "
+                );
+                continue;
+            }
+            "clear" | "cls" | ":clear" => {
+                repl.env.take_stack();
+                println!();
+                continue;
+            }
+            "exit" | ":exit" => break,
+            ":load" | ":save" | ":type" | ":time" if arg.is_empty() => {
+                eprintln!("{command} requires an argument");
+                continue;
+            }
+            ":load" => {
                 _ = line_reader.add_history_entry(&code);
+                repl.load(Path::new(arg));
+                continue;
             }
-            Err(e) => {
+            ":save" => {
                 _ = line_reader.add_history_entry(&code);
-                eprintln!("{}", e.report());
+                repl.save(Path::new(arg));
+                continue;
+            }
+            ":type" => {
+                let Some(code) = repl.format(arg, &mut line_reader) else {
+                    continue;
+                };
+                repl.show_type(&code);
+                continue;
+            }
+            ":time" => {
+                let Some(code) = repl.format(arg, &mut line_reader) else {
+                    continue;
+                };
+                let start = Instant::now();
+                let success = repl.run(&code, None);
+                if success {
+                    println!("⏱ {:.3}ms", start.elapsed().as_secs_f64() * 1000.0);
+                }
+                continue;
+            }
+            #[cfg(feature = "explorer")]
+            ":explore" => {
+                if arg.is_empty() {
+                    repl.explore(None);
+                } else if let Some(code) = repl.format(arg, &mut line_reader) {
+                    repl.explore(Some(&code));
+                }
                 continue;
             }
+            command if command.starts_with(':') => {
+                eprintln!("Unknown command {command}. Type `help` for a list of commands.");
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(code) = repl.format(&code, &mut line_reader) {
+            repl.run(&code, None);
         }
+    }
+}
 
-        let backup_comp = compiler.clone();
-        let backup_stack = env.stack().to_vec();
-        let res = compiler.load_str(&code).map(drop);
-        println!("    {}", color_code(&code, &compiler));
-        let res = res.and_then(|()| env.run_compiler(&mut compiler));
+/// The state of a REPL session
+struct Repl {
+    env: Uiua,
+    compiler: Compiler,
+    color: bool,
+    stack: bool,
+    config: FormatConfig,
+    /// The code of every line that ran successfully
+    session: Vec<String>,
+}
 
+impl Repl {
+    /// Format a line and add it to the history
+    fn format(&self, code: &str, line_reader: &mut DefaultEditor) -> Option<String> {
+        match format_str(code, &self.config) {
+            Ok(formatted) => {
+                _ = line_reader.add_history_entry(&formatted.output);
+                Some(formatted.output)
+            }
+            Err(e) => {
+                _ = line_reader.add_history_entry(code);
+                eprintln!("{}", e.report());
+                None
+            }
+        }
+    }
+    /// Run some code, restoring the session if it fails
+    ///
+    /// If a path is given, the code is compiled as that file
+    fn run(&mut self, code: &str, path: Option<&Path>) -> bool {
+        let backup_comp = self.compiler.clone();
+        let backup_stack = self.env.stack().to_vec();
+        let res = if let Some(path) = path {
+            self.compiler.load_str_src(code, path).map(drop)
+        } else {
+            let res = self.compiler.load_str(code).map(drop);
+            println!("    {}", color_code(code, &self.compiler));
+            res
+        };
+        let res = res.and_then(|()| self.env.run_compiler(&mut self.compiler));
+        self.compiler.assembly_mut().root.clear();
         match res {
             Ok(()) => {
-                print_stack(env.stack(), color);
-                if !stack {
-                    env.take_stack();
+                self.session.push(code.trim_end().into());
+                print_stack(self.env.stack(), self.color);
+                if !self.stack {
+                    self.env.take_stack();
                 }
+                true
             }
             Err(e) => {
-                compiler = backup_comp;
-                env.take_stack();
+                self.compiler = backup_comp;
+                self.env.take_stack();
                 for val in backup_stack {
-                    env.push(val);
+                    self.env.push(val);
                 }
                 eprintln!("{}", e.report());
-                print_stack(env.stack(), color);
+                print_stack(self.env.stack(), self.color);
+                false
+            }
+        }
+    }
+    /// Run a file in the session
+    fn load(&mut self, path: &Path) {
+        match fs::read_to_string(path) {
+            Ok(code) => {
+                self.run(&code, Some(path));
             }
+            Err(e) => eprintln!("Failed to read {}: {e}", path.display()),
+        }
+    }
+    /// Save the session's code to a file
+    fn save(&self, path: &Path) {
+        let mut code = self.session.join("\n");
+        code.push('\n');
+        match fs::write(path, code) {
+            Ok(()) => println!("Saved session to {}", path.display()),
+            Err(e) => eprintln!("Failed to write {}: {e}", path.display()),
+        }
+    }
+    /// Explore the value on top of the stack, optionally running some code first
+    #[cfg(feature = "explorer")]
+    fn explore(&mut self, code: Option<&str>) {
+        if let Some(code) = code {
+            let stack = self.stack;
+            self.stack = true;
+            let success = self.run(code, None);
+            self.stack = stack;
+            if !success {
+                return;
+            }
+        }
+        let value = self.env.stack().last().cloned();
+        if !self.stack {
+            self.env.take_stack();
+        }
+        let Some(value) = value else {
+            eprintln!("There is no value to explore");
+            return;
+        };
+        if let Err(e) = uiua::explorer::explore(value, &NativeSys) {
+            eprintln!("{e}");
+        }
+    }
+    /// Show the signature of some code without running it
+    fn show_type(&mut self, code: &str) {
+        let mut compiler = self.compiler.clone();
+        let res = compiler.load_str(code).map(drop);
+        println!("    {}", color_code(code, &compiler));
+        match res {
+            Ok(()) => match compiler.assembly().root.sig() {
+                Ok(sig) => println!("{sig}"),
+                Err(e) => eprintln!("{e}"),
+            },
+            Err(e) => eprintln!("{}", e.report()),
         }
-        compiler.assembly_mut().root.clear();
     }
 }
 
@@ -1365,6 +1679,27 @@ fn check(path: Option<PathBuf>) -> UiuaResult {
     Ok(())
 }
 
+fn config(path: Option<&Path>) -> UiuaResult {
+    let entries = Settings::load(path)?.entries();
+    let name_width = entries.iter().map(|e| e.name.len()).max().unwrap_or(0);
+    let var_width = entries.iter().map(|e| e.var.len()).max().unwrap_or(0);
+    let value_width = (entries.iter())
+        .map(|e| e.value.as_ref().map_or(1, |v| v.chars().count()))
+        .max()
+        .unwrap_or(0);
+    for entry in entries {
+        let value = entry.value.unwrap_or_else(|| "-".into());
+        println!(
+            "{:name_width$}  {:var_width$}  {:value_width$}  {}",
+            entry.name.bold(),
+            entry.var.dimmed(),
+            value,
+            entry.source.to_string().bright_black()
+        );
+    }
+    Ok(())
+}
+
 fn find(path: Option<PathBuf>, text: String, raw: bool) -> UiuaResult {
     if raw {
         colored::control::set_override(false);
@@ -0,0 +1,244 @@
+//! Memoization of function results
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use crate::{Function, SigNode, Uiua, UiuaResult, Value};
+
+/// How entries are chosen for eviction when a [`MemoCache`] is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MemoEviction {
+    /// Evict the entry that was used least recently
+    #[default]
+    LeastRecentlyUsed,
+    /// Evict the entry that was added first
+    FirstIn,
+}
+
+/// Configuration for a [`MemoCache`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoConfig {
+    /// The maximum number of entries in the cache
+    ///
+    /// `None` means the cache is unbounded
+    pub capacity: Option<usize>,
+    /// How entries are chosen for eviction when the cache is full
+    pub eviction: MemoEviction,
+}
+
+impl MemoConfig {
+    /// Set the maximum number of entries in the cache
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+    /// Set how entries are chosen for eviction when the cache is full
+    pub fn with_eviction(mut self, eviction: MemoEviction) -> Self {
+        self.eviction = eviction;
+        self
+    }
+}
+
+/// Statistics for a [`MemoCache`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    /// The number of entries in the cache
+    pub entries: usize,
+    /// The number of calls whose results were found in the cache
+    pub hits: u64,
+    /// The number of calls whose results were not found in the cache
+    pub misses: u64,
+    /// The number of entries that have been evicted
+    pub evictions: u64,
+}
+
+/// A cache of memoized function results
+///
+/// Entries are keyed by the identity of the function and a hash of its
+/// arguments. A cache is shared by all threads of a [`Uiua`] runtime.
+#[derive(Default)]
+pub struct MemoCache {
+    config: MemoConfig,
+    inner: Mutex<MemoInner>,
+}
+
+#[derive(Default)]
+struct MemoInner {
+    entries: HashMap<MemoKey, MemoEntry>,
+    /// Entry keys in eviction order
+    order: BTreeMap<u64, MemoKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct MemoKey {
+    function: u64,
+    args: u64,
+}
+
+struct MemoEntry {
+    args: Vec<Value>,
+    outputs: Vec<Value>,
+    tick: u64,
+}
+
+impl fmt::Debug for MemoCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoCache")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl MemoCache {
+    /// Create a new cache
+    pub fn new(config: MemoConfig) -> Self {
+        MemoCache {
+            config,
+            inner: Mutex::default(),
+        }
+    }
+    /// Get the cache's configuration
+    pub fn config(&self) -> MemoConfig {
+        self.config
+    }
+    /// Get statistics for the cache
+    pub fn stats(&self) -> MemoStats {
+        let inner = self.inner.lock();
+        MemoStats {
+            entries: inner.entries.len(),
+            hits: inner.hits,
+            misses: inner.misses,
+            evictions: inner.evictions,
+        }
+    }
+    /// Remove all entries from the cache
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.entries.clear();
+        inner.order.clear();
+    }
+    fn key(function: u64, args: &[Value]) -> MemoKey {
+        let mut hasher = DefaultHasher::new();
+        args.hash(&mut hasher);
+        MemoKey {
+            function,
+            args: hasher.finish(),
+        }
+    }
+    fn get(&self, key: MemoKey, args: &[Value]) -> Option<Vec<Value>> {
+        let mut inner = self.inner.lock();
+        let inner = &mut *inner;
+        let Some(entry) = inner
+            .entries
+            .get_mut(&key)
+            .filter(|entry| entry.args == args)
+        else {
+            inner.misses += 1;
+            return None;
+        };
+        inner.hits += 1;
+        if self.config.eviction == MemoEviction::LeastRecentlyUsed {
+            inner.order.remove(&entry.tick);
+            inner.tick += 1;
+            entry.tick = inner.tick;
+            inner.order.insert(entry.tick, key);
+        }
+        Some(entry.outputs.clone())
+    }
+    fn insert(&self, key: MemoKey, args: Vec<Value>, outputs: Vec<Value>) {
+        if self.config.capacity == Some(0) {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = MemoEntry {
+            args,
+            outputs,
+            tick,
+        };
+        if let Some(old) = inner.entries.insert(key, entry) {
+            inner.order.remove(&old.tick);
+        }
+        inner.order.insert(tick, key);
+        if let Some(capacity) = self.config.capacity {
+            while inner.entries.len() > capacity {
+                let (_, key) = inner.order.pop_first().unwrap();
+                inner.entries.remove(&key);
+                inner.evictions += 1;
+            }
+        }
+    }
+}
+
+impl Uiua {
+    /// Set the configuration of the memoization cache
+    ///
+    /// This replaces the current cache
+    pub fn with_memo_config(mut self, config: MemoConfig) -> Self {
+        self.rt.memo = Arc::new(MemoCache::new(config));
+        self
+    }
+    /// Get the memoization cache
+    ///
+    /// This is the cache used by [`Primitive::Memo`](crate::Primitive::Memo)
+    pub fn memo_cache(&self) -> &MemoCache {
+        &self.rt.memo
+    }
+    /// Call a function, caching its results
+    ///
+    /// If the function was already called with the same arguments,
+    /// the cached outputs are pushed instead
+    pub fn call_memoized(&mut self, f: &Function) -> UiuaResult {
+        let mut hasher = DefaultHasher::new();
+        f.hash(&mut hasher);
+        self.memoized(hasher.finish(), f.sig.args, f.sig.outputs, |env| {
+            env.call(f)
+        })
+    }
+    /// Execute a function, caching its results
+    pub(crate) fn exec_memoized(&mut self, f: SigNode) -> UiuaResult {
+        let mut hasher = DefaultHasher::new();
+        f.node.hash(&mut hasher);
+        self.memoized(hasher.finish(), f.sig.args, f.sig.outputs, |env| {
+            env.exec(f.node)
+        })
+    }
+    fn memoized(
+        &mut self,
+        function: u64,
+        args: usize,
+        outputs: usize,
+        f: impl FnOnce(&mut Self) -> UiuaResult,
+    ) -> UiuaResult {
+        let mut arg_vals = Vec::with_capacity(args);
+        for i in 0..args {
+            arg_vals.push(self.pop(i + 1)?);
+        }
+        let key = MemoCache::key(function, &arg_vals);
+        if let Some(outputs) = self.rt.memo.get(key, &arg_vals) {
+            for val in outputs {
+                self.push(val);
+            }
+            return Ok(());
+        }
+        for arg in arg_vals.iter().rev() {
+            self.push(arg.clone());
+        }
+        f(self)?;
+        let outputs = self.clone_stack_top(outputs)?;
+        self.rt.memo.insert(key, arg_vals, outputs);
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Localized error messages
+//!
+//! Only the most common errors are localized so far.
+//! Other errors are shown in English regardless of the locale and have no [`MessageId`].
+
+use std::{fmt, str::FromStr};
+
+use serde::*;
+
+/// A language that error messages can be shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    /// English
+    #[default]
+    English,
+    /// Spanish
+    Spanish,
+    /// German
+    German,
+}
+
+impl Locale {
+    /// All supported locales
+    pub const ALL: [Self; 3] = [Locale::English, Locale::Spanish, Locale::German];
+    /// The locale's language code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+            Locale::German => "de",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+    /// Parse a locale from a language code
+    ///
+    /// Region and encoding suffixes like in `es_ES.UTF-8` are ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lang = s.trim().split(['_', '-', '.']).next().unwrap_or("");
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(lang))
+            .ok_or_else(|| format!("Unknown locale {s:?}"))
+    }
+}
+
+macro_rules! messages {
+    ($(
+        $(#[doc = $doc:literal])+
+        ($variant:ident, $id:literal, $en:literal, $es:literal, $de:literal)
+    ),* $(,)?) => {
+        /// The id of a localized message
+        ///
+        /// Ids are stable and can be used by tooling to identify errors
+        /// regardless of the locale they are shown in.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        pub enum MessageId {
+            $(
+                $(#[doc = $doc])+
+                $variant,
+            )*
+        }
+
+        impl MessageId {
+            /// All message ids
+            pub const ALL: &'static [Self] = &[$(MessageId::$variant),*];
+            /// Get the message's stable id
+            pub fn id(&self) -> &'static str {
+                match self {
+                    $(MessageId::$variant => $id,)*
+                }
+            }
+            /// Get the message's template in a locale
+            ///
+            /// `{0}`, `{1}`, etc. are replaced with the message's arguments
+            pub fn template(&self, locale: Locale) -> &'static str {
+                match (self, locale) {
+                    $(
+                        (MessageId::$variant, Locale::English) => $en,
+                        (MessageId::$variant, Locale::Spanish) => $es,
+                        (MessageId::$variant, Locale::German) => $de,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+messages!(
+    /// The name of a numbered function argument
+    (
+        Argument,
+        "argument",
+        "argument {0}",
+        "argumento {0}",
+        "Argument {0}"
+    ),
+    /// A function needed more values than were on the stack
+    (
+        StackEmpty,
+        "stack-empty",
+        "Stack was empty when getting {0}",
+        "La pila estaba vacía al obtener {0}",
+        "Der Stapel war leer beim Abrufen von {0}"
+    ),
+    /// The shapes of two arrays do not match
+    (
+        ShapeMismatch,
+        "shape-mismatch",
+        "Shapes {0} and {1} do not match",
+        "Las formas {0} y {1} no coinciden",
+        "Die Formen {0} und {1} stimmen nicht überein"
+    ),
+    /// The shapes of two arrays cannot be pervaded together
+    (
+        IncompatibleShapes,
+        "incompatible-shapes",
+        "Shapes {0} and {1} are not compatible",
+        "Las formas {0} y {1} no son compatibles",
+        "Die Formen {0} und {1} sind nicht kompatibel"
+    ),
+    /// Which axis two shapes differ along, shown above a diagram of the shapes
+    (
+        ShapesDifferAlongAxis,
+        "shapes-differ-along-axis",
+        "The shapes differ along axis {0}",
+        "Las formas difieren en el eje {0}",
+        "Die Formen unterscheiden sich entlang Achse {0}"
+    ),
+    /// How to make the shapes of two arrays agree
+    (
+        ShapeMismatchHint,
+        "shape-mismatch-hint",
+        "Use {0} to pad the shorter axis, or {1} to reuse an array for every row of the other",
+        "Usa {0} para rellenar el eje más corto, o {1} para reutilizar un arreglo en cada fila del otro",
+        "Verwende {0}, um die kürzere Achse aufzufüllen, oder {1}, um ein Array für jede Zeile des anderen wiederzuverwenden"
+    ),
+    /// A name was used that is not bound
+    (
+        UnknownIdentifier,
+        "unknown-identifier",
+        "Unknown identifier `{0}`",
+        "Identificador desconocido `{0}`",
+        "Unbekannter Bezeichner `{0}`"
+    ),
+    /// A module does not contain an item
+    (
+        ItemNotFound,
+        "item-not-found",
+        "Item `{0}` not found",
+        "No se encontró el elemento `{0}`",
+        "Element `{0}` nicht gefunden"
+    ),
+    /// Recursion went too deep
+    (
+        RecursionLimit,
+        "recursion-limit",
+        "Recursion limit reached",
+        "Se alcanzó el límite de recursión",
+        "Rekursionslimit erreicht"
+    ),
+    /// An experimental feature was used without enabling experimental features
+    (
+        Experimental,
+        "experimental",
+        "{0} is experimental. To use it, add `# Experimental!` to the top of the file.",
+        "{0} es experimental. Para usarlo, añade `# Experimental!` al principio del archivo.",
+        "{0} ist experimentell. Um es zu verwenden, füge `# Experimental!` am Anfang der Datei hinzu."
+    ),
+);
+
+impl MessageId {
+    /// Format the message in a locale
+    pub fn format(&self, locale: Locale, args: &[&dyn fmt::Display]) -> String {
+        let template = self.template(locale);
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let arg = (rest.find('}'))
+                .and_then(|end| Some((end, rest[1..end].parse::<usize>().ok()?)))
+                .and_then(|(end, i)| Some((end, args.get(i)?)));
+            if let Some((end, arg)) = arg {
+                message.push_str(&arg.to_string());
+                rest = &rest[end + 1..];
+            } else {
+                message.push('{');
+                rest = &rest[1..];
+            }
+        }
+        message.push_str(rest);
+        message
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     ast::*,
     function::Signature,
     lex::{AsciiToken::*, Token::*, *},
-    BindingCounts, Diagnostic, DiagnosticKind, Ident, Inputs, Primitive,
+    BindingCounts, Complex, Diagnostic, DiagnosticKind, Ident, Inputs, Primitive,
 };
 
 /// An error that occurred while parsing
@@ -118,7 +118,17 @@ pub fn parse(
     src: impl IntoInputSrc,
     inputs: &mut Inputs,
 ) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
-    let (tokens, lex_errors, src) = lex(input, src, inputs);
+    parse_impl(input, src, inputs, false)
+}
+
+/// Parse Uiua code into an AST, optionally with experimental features already enabled
+pub(crate) fn parse_impl(
+    input: &str,
+    src: impl IntoInputSrc,
+    inputs: &mut Inputs,
+    experimental: bool,
+) -> (Vec<Item>, Vec<Sp<ParseError>>, Vec<Diagnostic>) {
+    let (tokens, lex_errors, src) = lex_impl(input, src, inputs, experimental);
     fn parse(
         input: &str,
         inputs: &mut Inputs,
@@ -337,7 +347,7 @@ impl Parser<'_> {
         } else if let Some(data) = self.data_def() {
             Item::Data(data)
         } else {
-            let lines = self.multiline_words(true, false);
+            let lines = self.multiline_words(true, false, false);
             if lines.is_empty() {
                 return None;
             } else {
@@ -420,6 +430,7 @@ struct BindingInit {
     arrow_span: CodeSpan,
     public: bool,
     array_macro: bool,
+    token_macro: bool,
 }
 
 impl Parser<'_> {
@@ -459,6 +470,13 @@ impl Parser<'_> {
         } else {
             false
         };
+        let token_macro =
+            if let Some(span) = array_macro.then(|| self.exact(Caret.into())).flatten() {
+                arrow_span = arrow_span.merge(span);
+                true
+            } else {
+                false
+            };
         if let Some(span) = self.spaces().map(|w| w.span) {
             arrow_span = arrow_span.merge(span);
         }
@@ -468,8 +486,41 @@ impl Parser<'_> {
             arrow_span,
             public,
             array_macro,
+            token_macro,
         })
     }
+    fn local_binding(&mut self) -> Option<Sp<Word>> {
+        let start = self.index;
+        let name = self
+            .ident()
+            .filter(|name| !name.value.ends_with(['!', '‼']));
+        let Some(name) = name else {
+            self.index = start;
+            return None;
+        };
+        let spaces_span = self.spaces().map(|w| w.span);
+        let Some(mut arrow_span) = self.exact(LeftArrow) else {
+            self.index = start;
+            return None;
+        };
+        if let Some(span) = spaces_span {
+            arrow_span = span.merge(arrow_span);
+        }
+        if let Some(span) = self.spaces().map(|w| w.span) {
+            arrow_span = arrow_span.merge(span);
+        }
+        self.validate_binding_name(&name);
+        let words = self.words().unwrap_or_default();
+        let span = match words.last() {
+            Some(last) => name.span.clone().merge(last.span.clone()),
+            None => name.span.clone().merge(arrow_span.clone()),
+        };
+        Some(span.sp(Word::LocalBinding(LocalBinding {
+            name,
+            arrow_span,
+            words,
+        })))
+    }
     fn import_init(&mut self) -> Option<(Option<Sp<Ident>>, CodeSpan, Sp<String>)> {
         let start = self.index;
         // Name
@@ -497,6 +548,7 @@ impl Parser<'_> {
             arrow_span,
             public,
             array_macro,
+            token_macro,
         } = self.binding_init()?;
         // Bad name advice
         if ["\u{200b}", "\u{200c}", "\u{200d}"]
@@ -595,6 +647,7 @@ impl Parser<'_> {
             arrow_span,
             public,
             code_macro: array_macro,
+            token_macro,
             words,
             signature,
             counts: BindingCounts {
@@ -964,6 +1017,7 @@ impl Parser<'_> {
         &mut self,
         check_for_bindings: bool,
         extra_newline: bool,
+        allow_locals: bool,
     ) -> Vec<Vec<Sp<Word>>> {
         let mut lines = Vec::new();
         while self.spaces().is_some() {}
@@ -978,7 +1032,11 @@ impl Parser<'_> {
                 self.index = curr;
                 break;
             }
-            if let Some(words) = self.words() {
+            let local = allow_locals
+                .then(|| self.local_binding())
+                .flatten()
+                .map(|local| vec![local]);
+            if let Some(words) = local.or_else(|| self.words()) {
                 newlines = 0;
                 lines.push(words);
                 while self.exact(Newline).is_some() {
@@ -1237,7 +1295,7 @@ impl Parser<'_> {
                 has_newline = false;
             }
             has_newline |= self.ignore_whitespace();
-            let mut items = self.multiline_words(false, true);
+            let mut items = self.multiline_words(false, true, false);
             if has_newline {
                 items.insert(0, Vec::new());
             }
@@ -1271,7 +1329,7 @@ impl Parser<'_> {
                 has_newline = false;
             }
             has_newline |= self.ignore_whitespace();
-            let mut items = self.multiline_words(false, true);
+            let mut items = self.multiline_words(false, true, false);
             if has_newline {
                 items.insert(0, Vec::new());
             }
@@ -1312,7 +1370,7 @@ impl Parser<'_> {
         }
         Some(word)
     }
-    fn num(&mut self) -> Option<Sp<Result<f64, String>>> {
+    fn num(&mut self) -> Option<Sp<Result<Complex, String>>> {
         let span = self.exact(Token::Number)?;
         let s = &self.input[span.byte_range()];
         fn parse(s: &str) -> Option<f64> {
@@ -1338,11 +1396,13 @@ impl Parser<'_> {
             }
             s.parse().ok()
         }
-        let n: Result<f64, String> = match parse(s) {
-            Some(n) => Ok(n),
+        let n: Result<Complex, String> = match parse(s) {
+            Some(n) => Ok(n.into()),
             None => {
                 if let Some((n, d)) = s.split_once('/').and_then(|(n, d)| parse(n).zip(parse(d))) {
-                    Ok(n / d)
+                    Ok((n / d).into())
+                } else if let Some(im) = s.strip_suffix('i').and_then(parse) {
+                    Ok(Complex::new(0.0, im))
                 } else {
                     Err(s.into())
                 }
@@ -1468,7 +1528,7 @@ impl Parser<'_> {
         if starts_with_newline {
             lines.push(Vec::new());
         }
-        lines.extend(self.multiline_words(false, true));
+        lines.extend(self.multiline_words(false, true, true));
         if lines.is_empty() {
             lines.push(Vec::new());
         }
@@ -1665,6 +1725,10 @@ fn unsplit_word(word: Sp<Word>) -> Sp<Word> {
             sub.word = unsplit_word(sub.word);
             Word::Subscripted(sub)
         }
+        Word::LocalBinding(mut binding) => {
+            binding.words = binding.words.into_iter().map(unsplit_word).collect();
+            Word::LocalBinding(binding)
+        }
         word => word,
     })
 }
@@ -1706,6 +1770,10 @@ fn split_word(word: Sp<Word>) -> Sp<Word> {
             sub.word = split_word(sub.word);
             Word::Subscripted(sub)
         }
+        Word::LocalBinding(mut binding) => {
+            binding.words = binding.words.into_iter().map(split_word).collect();
+            Word::LocalBinding(binding)
+        }
         word => word,
     })
 }
@@ -1756,6 +1824,7 @@ pub(crate) fn max_placeholder(words: &[Sp<Word>]) -> Option<usize> {
                 }
             }
             Word::Subscripted(s) => set(max_placeholder(slice::from_ref(&s.word))),
+            Word::LocalBinding(binding) => set(max_placeholder(&binding.words)),
             _ => {}
         }
     }
@@ -241,6 +241,13 @@ primitive!(
     /// ex: -:η°∿ 0
     /// You can get a tangent function by [divide]ing the [sine] by the cosine.
     /// ex: ÷∩∿+η. 0
+    /// A subscripted [sine] takes its argument in units where a full turn is N. Multiples of a quarter turn are exact.
+    /// ex: # Experimental!
+    ///   : ∿₃₆₀ [0 90 180 270]
+    ///   : ∿₃₆₀+90 [0 60 90]
+    /// [un] gives the arcsine in the same units.
+    /// ex: # Experimental!
+    ///   : °∿₃₆₀ [0 1 ¯1]
     (1, Sin, MonadicPervasive, ("sine", '∿')),
     /// Round to the nearest integer towards `¯∞`
     ///
@@ -493,6 +500,47 @@ primitive!(
     /// A complex number [equals] a real one if the imaginary part is 0 and the real parts [match].
     /// ex: = 5 ℂ0 5
     (2, Complex, DyadicPervasive, ("complex", 'ℂ')),
+    /// Get the real part of a complex number
+    ///
+    /// ex: # Experimental!
+    ///   : real ℂ3 5
+    /// ex: # Experimental!
+    ///   : real [1 2i ℂ4 3]
+    /// Real numbers are their own real part.
+    /// ex: # Experimental!
+    ///   : real [1 2 3]
+    (1, Real, MonadicPervasive, "real"),
+    /// Get the imaginary part of a complex number
+    ///
+    /// ex: # Experimental!
+    ///   : imaginary ℂ3 5
+    /// ex: # Experimental!
+    ///   : imaginary [1 2i ℂ4 3]
+    /// The imaginary part of a real number is `0`.
+    /// ex: # Experimental!
+    ///   : imaginary [1 2 3]
+    (1, Imaginary, MonadicPervasive, "imaginary"),
+    /// Get the argument of a complex number
+    ///
+    /// This is the angle of the number from the positive real axis, in the range `(-π, π]`.
+    /// ex: # Experimental!
+    ///   : argument [1 i ¯1 ¯i ℂ1 1]
+    /// Along with [absolute value], it gives the polar form of a complex number.
+    /// ex: # Experimental!
+    ///   : ⊃⌵argument ℂ4 3
+    /// [un][atangent] and [complex] can convert back from polar form.
+    /// ex: # Experimental!
+    ///   : ×⊃⌵(ℂ°∠argument) ℂ4 3
+    (1, Argument, MonadicPervasive, "argument"),
+    /// Get the complex conjugate of a complex number
+    ///
+    /// The conjugate has the same real part and the [negate]d imaginary part.
+    /// ex: # Experimental!
+    ///   : conjugate [1 2i ℂ4 3 ℂ¯6 ¯5]
+    /// Multiplying a number by its conjugate gives the square of its [absolute value].
+    /// ex: # Experimental!
+    ///   : ×⟜conjugate ℂ4 3
+    (1, Conjugate, MonadicPervasive, "conjugate"),
     /// Get the number of rows in an array
     ///
     /// ex: ⧻5
@@ -683,6 +731,8 @@ primitive!(
     /// ex: ⊏⍏⌵. 6_2_7_0_¯1_5
     ///
     /// [first][rise] and [first][reverse][rise] are optimized in the interpreter to be O(n).
+    /// [take]ing a constant number of rows from the [rise] is optimized to only sort the taken indices.
+    /// ex: ↙3⍏ 6_2_7_0_¯1_5
     (1, Rise, MonadicArray, ("rise", '⍏')),
     /// Get the indices into an array if it were sorted descending
     ///
@@ -697,6 +747,8 @@ primitive!(
     /// ex: ⊏⍖⌵. 6_2_7_0_¯1_5
     ///
     /// [first][fall] and [first][reverse][fall] are optimized in the interpreter to be O(n).
+    /// [take]ing a constant number of rows from the [fall] is optimized to only sort the taken indices.
+    /// ex: ↙3⍖ 6_2_7_0_¯1_5
     (1, Fall, MonadicArray, ("fall", '⍖')),
     /// Get indices where array values are not equal to zero
     ///
@@ -845,6 +897,20 @@ primitive!(
     /// ex: ⬚@ °⋕ +9÷4⇡10
     /// ex: ⬚@0°⋕ +9÷4⇡10
     (1, Parse, Misc, ("parse", '⋕')),
+    /// Split Uiua code into tokens
+    ///
+    /// The result is a list of [box]ed strings. Whitespace and comments are kept as their own tokens, so joining the tokens gives back the original code.
+    /// ex: # Experimental!
+    ///   : tokenize "+1 ⊂[2 3] 4"
+    /// [un][tokenize] joins tokens back together.
+    /// ex: # Experimental!
+    ///   : °tokenize {"×" "2" " " "3"}
+    /// [tokenize] is most useful in code macros. Working with tokens rather than strings means that a transformation will not accidentally affect part of a name, number, or string.
+    /// ex: # Experimental!
+    ///   : Mul! ←^ ⍜tokenize(⍜▽≡(□"×"◌)⊸∊{"+"}) ⊢
+    ///   : Mul!(+ 2 3)
+    ///   : Mul!(⊂"+" "+-")
+    (1, Tokenize, Misc, "tokenize"),
     /// Check if two arrays are exactly the same
     ///
     /// ex: ≍ 1_2_3 [1 2 3]
@@ -1020,6 +1086,22 @@ primitive!(
     /// ex: ↯¯3 [1 2 3 4]
     /// ex: ↯¯∞ [1 2 3 4 5]
     ///
+    /// Subscripted [reshape] chooses how the data is made to fit the shape.
+    /// `₀` requires the data to fit exactly.
+    /// ex: # Experimental!
+    ///   : ↯₀ 2_3 ⇡6
+    /// ex! # Experimental!
+    ///   : ↯₀ 2_3 ⇡5
+    /// `₁` always cycles the data, even if there is a [fill] value.
+    /// ex: # Experimental!
+    ///   : ⬚0↯₁ 3_5 ⇡9
+    /// `₂` always pads with the [fill] value, so it is an error if there isn't one.
+    /// ex: # Experimental!
+    ///   : ⬚0↯₂ 3_5 ⇡9
+    /// ex! # Experimental!
+    ///   : ↯₂ 3_5 ⇡9
+    /// Both `₁` and `₂` still truncate data that does not fit.
+    ///
     /// See also: [deshape]
     (2, Reshape, DyadicArray, ("reshape", '↯')),
     /// Change the rank of an array's rows
@@ -1532,6 +1614,95 @@ primitive!(
     ///   : ⍚₂∘ °△2_3_4
     ///   : ⍚₃∘ °△2_3_4
     ([1], Inventory, IteratingModifier, ("inventory", '⍚')),
+    /// Apply a function to each row of some arrays, collecting errors
+    ///
+    /// [catch] works like [rows], but a row whose function call fails does not stop the others from being processed.
+    /// Instead, the error is caught as a value, just like with [try].
+    /// The first output is a list of each row's [box]ed result, or its [box]ed error if it failed. The second output is a mask of which rows failed.
+    /// ex: # Experimental!
+    ///   : catch(⍤"Negative!" ≥0.) [1 ¯2 3 ¯4]
+    /// This makes it easy to find all the bad records in some data in one pass.
+    /// ex: # Experimental!
+    ///   : ▽ catch⋕ {"1" "2x" "3" "y"}
+    /// If the function has no outputs, the results of successful rows are empty.
+    /// ex: # Experimental!
+    ///   : catch(⍤⊃$"_ is too big"(<10)) [3 14 5 92]
+    /// To catch errors for each element instead of each row, [deshape] the arrays first.
+    /// ex: # Experimental!
+    ///   : ▽ catch⋕ ♭ {"1" "x"; "3" "y"}
+    ([1], Catch, IteratingModifier, "catch"),
+    /// Apply a function to a range of rows of some arrays
+    ///
+    /// [resume] works like [rows], but it only processes some of the rows.
+    /// It takes a start index and a maximum number of rows to process. It returns the function's results for those rows, and the index of the next row to process.
+    /// ex: # Experimental!
+    ///   : resume(×10) 0 3 [1 2 3 4 5]
+    /// ex: # Experimental!
+    ///   : resume(×10) 3 3 [1 2 3 4 5]
+    /// This allows a large array to be processed in smaller batches, such as across multiple frames of an interactive program.
+    /// When all the rows have been processed, the returned index is the number of rows.
+    /// ex: # Experimental!
+    ///   : resume(×10) 5 3 [1 2 3 4 5]
+    /// As with [rows], arrays with exactly one row are reused for every row.
+    /// ex: # Experimental!
+    ///   : resume⊂ 1 2 [1 2 3 4] 0
+    (2[1], Resume, IteratingModifier, "resume"),
+    /// Lazily consume the values yielded by a generator
+    ///
+    /// [stream] takes two functions. The first is a generator, which produces values with [yield]. The second is a consumer, which processes each value as soon as it is yielded.
+    /// [stream] also takes the maximum number of values to consume. Once that many values have been yielded, the generator is stopped.
+    /// This allows the generator to describe an infinite sequence.
+    /// If the consumer has signature `|1.1`, its results are collected into an array, like with [rows].
+    /// ex: # Experimental!
+    ///   : stream(⍢(yield.+1|1)|∘) 5 0
+    /// ex: # Experimental!
+    ///   : stream(⍢(yield.+1|1)|ⁿ2) 5 0
+    /// If the consumer has signature `|2.1`, the values are combined with it, like with [reduce].
+    /// ex: # Experimental!
+    ///   : stream(⍢(yield.+1|1)|+) 100 0
+    /// The maximum may be [infinity] if the generator ends on its own. Any values the generator returns are discarded.
+    /// ex: # Experimental!
+    ///   : stream(⍢(yield.×2|<100)|∘) ∞ 1
+    /// Because values are consumed as they are yielded, a reducing [stream] never stores the whole sequence in an array.
+    (1[2], Stream, IteratingModifier, "stream"),
+    /// Pass a value to the consumer of a [stream]
+    ///
+    /// [yield] can only be called in a [stream]'s generator, including in functions it calls.
+    /// ex: # Experimental!
+    ///   : stream(yield yield 1 2|∘) ∞
+    /// A generator can be an ordinary function.
+    /// ex: # Experimental!
+    ///   : Fib ← ⍢(⊃⋅∘+yield.|1)
+    ///   : stream(Fib|∘) 10 0 1
+    /// ex! # Experimental!
+    ///   : yield 5
+    (1(0), Yield, Misc, "yield", Impure),
+    /// Run a function with a handler for a named effect
+    ///
+    /// [handle] takes two functions and the name of an effect. The first function is the handler, which must have signature `|1.1`. The second is the body.
+    /// While the body runs, any [perform] of the named effect calls the handler with the performed value, and [perform] returns the handler's result.
+    /// ex: # Experimental!
+    ///   : F ← +perform "ask" 0
+    ///   : handle(⋅5|F) "ask" 1
+    /// This works even in functions that the body calls, so effectful code can be tested by swapping out its handlers.
+    /// ex: # Experimental!
+    ///   : Roll ← +1⌊×6≡(perform "random")⇡
+    ///   : handle(⋅0.5|Roll 3) "random"
+    /// A handler that [perform]s its own effect calls the next enclosing handler.
+    /// ex: # Experimental!
+    ///   : handle(+1|handle(×10 perform "n"|perform "n" 2) "n") "n"
+    (1[2], Handle, IteratingModifier, "handle"),
+    /// Perform a named effect
+    ///
+    /// The first argument is the name of the effect. The second is a value to pass to the innermost [handle] of that effect.
+    /// The handler's result is returned.
+    /// ex: # Experimental!
+    ///   : Log ← perform "log"
+    ///   : handle(&p.|Log "hello" Log "world") "log"
+    /// It is an error to [perform] an effect that has no handler.
+    /// ex! # Experimental!
+    ///   : perform "ask" 0
+    (2(1), Perform, Misc, "perform", Impure),
     /// Apply a function to each combination of rows of some arrays
     ///
     /// ex: ⊞+ 1_2_3 4_5_6_7
@@ -1651,6 +1822,10 @@ primitive!(
     /// Adding a third row to the array allows the fill amount to be specified for each axis.
     /// ex: ⬚0⧈□ [2_2 1_1 0_1] +1°△2_2
     /// ex: ⬚0⧈□ [2_2 2_2 0_1] +1°△4_6
+    ///
+    /// If the function [reduce]s each window with [add], [multiply], [maximum], or [minimum], optionally after [deshape]ing it, the windows are never created. This makes things like moving averages and image filters fast and memory-efficient.
+    /// ex: ÷3 ⧈/+ 3 [1 4 2 8 5 7]
+    /// ex: ⬚0⧈(/↥♭) 3_3 [0_0_0_0 0_1_0_0 0_0_0_0 0_0_0_2]
     (2[1], Stencil, IteratingModifier, ("stencil", '⧈')),
     /// Repeat a function a number of times
     ///
@@ -1680,6 +1855,8 @@ primitive!(
     ///   : F 12
     /// [repeat]ing a negative number of times will repeat the function's [un]-inverse.
     /// ex: ⍥(×2)¯5 1024
+    /// This also works with [under].
+    /// ex: ⍜(⍥(×2)¯2)(+1) 8
     ///
     /// The repetition count may be given as a subscript
     /// ex: ⍥₅(×2) 32
@@ -2203,6 +2380,15 @@ primitive!(
     /// ex: ⬚0  °◌
     /// ex: ⬚0⬚◌°◌
     ///
+    /// If the fill function returns multiple values, each value is used as the fill for arrays of its type.
+    /// This allows numbers, characters, and boxes to be filled differently at the same time.
+    /// ex: # Experimental!
+    ///   : ⬚(0 @-){[1_2 3] ["ab" "c"]}
+    /// [fill][un][pop] passes all of the fill values along.
+    /// ex: # Experimental!
+    ///   : F ← ⬚°◌⊟
+    ///   : ⬚(0 @-)F "a" "bc"
+    ///
     /// [fill] and [un][pop] can be used to make a sort of ad-hoc variable system.
     /// ex: a ← (°□⊡0°◌)
     ///   : b ← (°□⊡1°◌)
@@ -2310,6 +2496,43 @@ primitive!(
     ///   : F [1 2 3 4]
     /// And there we go. Task failed successfully!
     ([1], Case, Misc, ("case", '⍩')),
+    /// Match values against patterns
+    ///
+    /// Expects a function pack.
+    /// Each function is a branch that starts with a pattern. The pattern is the [un]s at the end of the first line of the branch, since they are called first.
+    /// The first branch whose pattern matches is called.
+    /// ex: # Experimental!
+    ///   : F ← pattern(
+    ///   :   ⊏3 °(⊂1)
+    ///   : | ⊏1 °(⊂2)
+    ///   : | 0
+    ///   : )
+    ///   : F [1 2 3 4 5]
+    ///   : F [2 3 4 5]
+    ///   : F [5 2 3]
+    /// Patterns can destructure values and put their parts on the stack.
+    /// ex: # Experimental!
+    ///   : F ← pattern(
+    ///   :   $"_ plus _" °[⊙⊙]
+    ///   : | $"just _" °[∘]
+    ///   : | ⋅"nothing"
+    ///   : )
+    ///   : F [1 2]
+    ///   : F [5]
+    ///   : F [1 2 3]
+    /// This is similar to pattern matching with [try] and [case]. However, only the pattern is tried. Errors thrown after a successful match are not caught, so the next branch is not tried.
+    /// ex! # Experimental!
+    ///   : F ← pattern(
+    ///   :   ⊏3 °(⊂1)
+    ///   : | ⊏1 °(⊂2)
+    ///   : | 0
+    ///   : )
+    ///   : F [1 5 8]
+    /// A branch without a pattern matches anything. If there is no such branch, a warning is emitted, and an error is thrown if nothing matches.
+    /// ex! # Experimental!
+    ///   : pattern(+1°1|+2°2) 3
+    /// Branches after one without a pattern are unreachable, as are branches with the same pattern as a previous one. Both emit warnings.
+    ([1], Pattern, Misc, "pattern"),
     /// Throw an error if a condition is not met
     ///
     /// Expects a message and a test value.
@@ -2323,6 +2546,66 @@ primitive!(
     /// ex! ⍤. =8 9
     /// Errors thrown by [assert] can be caught with [try].
     (2(0), Assert, Misc, ("assert", '⍤'), Impure),
+    /// Create a structured error value
+    ///
+    /// Expects a message string and a payload, which can be any value.
+    /// The result is a map with `message` and `payload` keys.
+    /// ex: # Experimental!
+    ///   : error "Not found" 404
+    /// An error value can be thrown with [assert]. The message is shown if it is not caught.
+    /// ex! # Experimental!
+    ///   : ⍤ error "Bad input" [1 2 3] 0
+    /// When caught with [try], the handler gets the error value itself rather than a string. [un][error] gets the message and payload back.
+    /// ex: # Experimental!
+    ///   : ⍣(⍤ error "Bad input" ⊙0 .)⋅{°error} [1 2 3]
+    /// Because the error value is a map, the [box]ed payload can also be accessed with [get].
+    /// ex: # Experimental!
+    ///   : ⍣(⍤ error "Bad input" ⊙0 .)⋅(°□get "payload") [1 2 3]
+    /// [un][error] fails on other values, so it can be used as a pattern in a [pattern] handler.
+    /// ex: # Experimental!
+    ///   : Check ← ⟜(⍤⊃(error "Too big"|≤10))
+    ///   : F ← ⍣Check⋅pattern($"_: _" °error|$"Other: _")
+    ///   : F 5
+    ///   : F 12
+    (2, Error, Misc, "error"),
+    /// Throw a structured error if a value does not match an expected value
+    ///
+    /// The first argument is the expected value. The second is the actual value.
+    /// ex: # Experimental!
+    ///   : expect 3 +1 2
+    /// ex! # Experimental!
+    ///   : expect 4 +1 2
+    /// If the values do not [match], an [error] value is thrown. Its payload is a map with the `expected` and `actual` values and the `span` of the [expect].
+    /// ex: # Experimental!
+    ///   : ⍣(⊸expect 4)⋅(°□get "actual" ◌°error) +1 2
+    (2(0), Expect, Misc, "expect", Impure),
+    /// Check a precondition before calling a function
+    ///
+    /// The first function is the condition. It is called on copies of the top arguments of the second function, and it must return `1` for the second function to be called.
+    /// ex: # Experimental!
+    ///   : Sqrt ← requires(≥0|√)
+    ///   : Sqrt 16
+    /// ex! # Experimental!
+    ///   : Sqrt ← requires(≥0|√)
+    ///   : Sqrt ¯4
+    /// If the condition fails, an [error] value is thrown. Its payload is a map with the checked `arguments`.
+    /// ex: # Experimental!
+    ///   : ⍣(requires(>|-) 2 5)(°□get "arguments" ◌°error)
+    /// Contracts can be disabled with the `--no-contracts` flag of `uiua run` or the interpreter's settings. Conditions are then not called.
+    /// See also: [ensures]
+    ([2], Requires, Misc, "requires", Impure),
+    /// Check a postcondition after calling a function
+    ///
+    /// The first function is the condition. It is called on copies of the top outputs of the second function, and it must return `1`.
+    /// ex: # Experimental!
+    ///   : Mean ← ensures(=0⧻△|÷⊃⧻/+)
+    ///   : Mean [1 2 3 4]
+    /// ex! # Experimental!
+    ///   : Mean ← ensures(=0⧻△|÷⊃⧻/+)
+    ///   : Mean [1_2 3_4]
+    /// If the condition fails, an [error] value is thrown. Its payload is a map with the checked `outputs`.
+    /// Like [requires], contracts can be disabled with the `--no-contracts` flag of `uiua run` or the interpreter's settings.
+    ([2], Ensures, Misc, "ensures", Impure),
     /// Generate a random number in the range `[0, 1)`
     ///
     /// If you need a seeded random number, use [gen].
@@ -2346,6 +2629,8 @@ primitive!(
     /// ex: F ← memo(+⌊×10⚂)
     ///   : ∵F [1 1 2 2 3 3]
     /// In general, this should only be used with functions that perform a potentially expensive calculation.
+    ///
+    /// The cache is shared between threads.
     ([1], Memo, OtherModifier, "memo"),
     /// Run a function at compile time
     ///
@@ -2386,6 +2671,23 @@ primitive!(
     /// The thread pool has as many threads as the machine has processors.
     /// If all threads in the pool are busy, then [pool] will block until a thread is available.
     ([1], Pool, Thread, "pool", Impure),
+    /// Call functions concurrently and wait for all of them to finish
+    ///
+    /// Expects a function pack.
+    /// Each function is called in its own thread with copies of the values it needs, as with [fork].
+    /// Once all functions have finished, their outputs are pushed to the stack in order.
+    /// ex: # Experimental!
+    ///   : scope(/+|/×|⇌) [1 2 3 4]
+    /// Unlike [wait], the outputs of each function are not collected into a single array.
+    /// ex: # Experimental!
+    ///   : scope(⊃⊢⊣|⧻) [1 2 3 4]
+    ///
+    /// If any function errors, the others are interrupted and the first error is thrown.
+    /// ex! # Experimental!
+    ///   : scope(/+|⍤"Oh no!"0|⇌) [1 2 3]
+    ///
+    /// In the web editor, the functions are called one after another.
+    ([1], Scope, Thread, "scope", Impure),
     /// Wait for a thread to finish and push its results to the stack
     ///
     /// The argument must be a thread id returned by [spawn] or [pool].
@@ -2430,6 +2732,53 @@ primitive!(
     /// If no value is available, then an error is thrown.
     /// The error can be caught with [try].
     (1, TryRecv, Thread, "tryrecv", Impure),
+    /// Create a cell that can be shared between threads
+    ///
+    /// Expects an initial value.
+    /// A cell id is pushed to the stack. Like thread ids, cell ids are just numbers.
+    /// The cell's value can be read with [load] and replaced with [store] or [update].
+    /// ex: # Experimental!
+    ///   : cell 5
+    ///   : load .
+    ///
+    /// Cells are shared by all threads, so they can be used to accumulate results from parallel work.
+    /// ex: # Experimental!
+    ///   : C ← cell 0
+    ///   : ◌wait≡spawn(update+ C) ⇡10
+    ///   : load C
+    (1, Cell, Thread, "cell", Impure),
+    /// Get the value of a cell
+    ///
+    /// Expects a cell id returned by [cell].
+    /// ex: # Experimental!
+    ///   : load cell "Hi!"
+    (1, Load, Thread, "load", Impure),
+    /// Set the value of a cell
+    ///
+    /// Expects a cell id returned by [cell] and a new value.
+    /// ex: # Experimental!
+    ///   : C ← cell 1
+    ///   : store C 2
+    ///   : load C
+    (2(0), Store, Thread, "store", Impure),
+    /// Atomically update the value of a cell
+    ///
+    /// Expects a function and a cell id returned by [cell].
+    /// The function is called on the cell's value, and the result becomes the cell's new value.
+    /// The previous value is pushed to the stack.
+    /// ex: # Experimental!
+    ///   : C ← cell 1
+    ///   : update(×10) C
+    ///   : load C
+    ///
+    /// If the function takes more than 1 argument, the additional arguments are taken from the stack below the cell id.
+    /// ex: # Experimental!
+    ///   : C ← cell 1
+    ///   : update+ C 5
+    ///   : load C
+    ///
+    /// While the function is running, other threads can neither [load], [store], nor [update] the cell.
+    ([1], Update, Thread, "update", Impure),
     /// Generate an array of random numbers with a seed
     ///
     /// The first argument is the shape, the second argument is the seed. The returned array will have the given shape where each element is in the range [0, 1).
@@ -2451,6 +2800,141 @@ primitive!(
     /// For non-determinism, [random] can be used as a seed.
     /// ex: ⌊×10 gen 3_4 ⚂
     (2, Gen, Misc, "gen"),
+    /// Sample random indices with weights and a seed
+    ///
+    /// The first argument is the shape, the second is the seed, and the third is a list of weights.
+    /// Each index into the weights is sampled with probability proportional to its weight.
+    /// Indices are sampled with replacement.
+    /// ex: # Experimental!
+    ///   : sample 10 0 [1 1 2]
+    /// ex: # Experimental!
+    ///   : sample 2_5 1 [1 0 3]
+    /// Use [select] to sample rows of an array.
+    /// ex: # Experimental!
+    ///   : ⊏ sample 8 0 [3 2 1] "abc"
+    /// Like [gen], the same seed always gives the same result.
+    /// ex: # Experimental!
+    ///   : ≍ ∩(sample 5 42) [1 2 3] [1 2 3]
+    (3, Sample, Misc, "sample"),
+    /// Seed the random number generator
+    ///
+    /// Any value can be used as a seed. After seeding, [random] and the other random functions produce the same numbers every time.
+    /// ex: # Experimental!
+    ///   : [⍥⚂3] seed 42
+    /// ex: # Experimental!
+    ///   : [⍥(uniform 3 seed 5)2]
+    /// Unlike [gen], the generator's state is kept in the interpreter, so subsequent calls continue the sequence.
+    /// ex: # Experimental!
+    ///   : ⊃(uniform 3|uniform 3) seed 0
+    /// Threads get their own generators, which are split from the parent's when they are spawned.
+    (1(0), Seed, Misc, "seed", Impure),
+    /// Split the random number generator into independent seeds
+    ///
+    /// The argument is the number of seeds. Each seed can be used with [seed] or [gen] to get an independent sequence of random numbers.
+    /// ex: # Experimental!
+    ///   : seeds 3 seed 0
+    /// ex: # Experimental!
+    ///   : ≡(⌊×10 gen 4) seeds 3 seed 0
+    (1, Seeds, Misc, "seeds", Impure),
+    /// Generate an array of random numbers in the range `[0, 1)`
+    ///
+    /// The argument is the shape. This is faster than repeating [random].
+    /// ex: # Experimental!
+    ///   : uniform 5
+    /// ex: # Experimental!
+    ///   : ⌊×10 uniform 3_4
+    /// The numbers come from the same generator as [random], so they are reproducible with [seed].
+    (1, Uniform, Misc, "uniform", Impure),
+    /// Generate an array of normally distributed random numbers
+    ///
+    /// The argument is the shape. The numbers have a mean of `0` and a standard deviation of `1`.
+    /// ex: # Experimental!
+    ///   : normal 5
+    /// Use [multiply] and [add] to change the standard deviation and mean.
+    /// ex: # Experimental!
+    ///   : +10 ×2 normal 2_3
+    (1, Normal, Misc, "normal", Impure),
+    /// Generate an array of random natural numbers less than a maximum
+    ///
+    /// The first argument is the shape. The second is the exclusive maximum.
+    /// ex: # Experimental!
+    ///   : randint 10 6
+    /// ex: # Experimental!
+    ///   : randint 3_4 100
+    (2, RandInt, Misc, "randint", Impure),
+    /// Generate an array of random numbers from a distribution
+    ///
+    /// The first argument is the name of the distribution. The second is a list of its parameters. The third is the shape.
+    /// The known distributions are:
+    /// - `"normal"` with a mean and a standard deviation
+    /// - `"exponential"` with a rate
+    /// - `"poisson"` with a rate
+    /// - `"binomial"` with a number of trials and a probability of success
+    /// ex: # Experimental!
+    ///   : draw "normal" [10 2] 5
+    /// ex: # Experimental!
+    ///   : draw "poisson" 3 2_6
+    /// ex: # Experimental!
+    ///   : draw "binomial" [10 0.5] 8
+    /// The numbers come from the same generator as [random], so they are reproducible with [seed].
+    /// ex: # Experimental!
+    ///   : ⊃(draw "exponential" 2 3|draw "exponential" 2 3) seed 0
+    ///   : ≍
+    /// [pdf], [cdf], and [quantile] take the same distributions.
+    (3, Draw, Misc, "draw", Impure),
+    /// Get the probability density of a distribution
+    ///
+    /// The first argument is the name of the distribution. The second is a list of its parameters. See [draw] for the known distributions.
+    /// This function is pervasive in its third argument.
+    /// ex: # Experimental!
+    ///   : pdf "normal" [0 1] [¯1 0 1]
+    /// ex: # Experimental!
+    ///   : pdf "exponential" 2 0.5
+    /// For the discrete `"poisson"` and `"binomial"` distributions, this is the probability of each value.
+    /// ex: # Experimental!
+    ///   : pdf "binomial" [4 0.5] ⇡5
+    (3, Pdf, Misc, "pdf"),
+    /// Get the cumulative probability of a distribution
+    ///
+    /// This is the probability that a value drawn from the distribution is less than or equal to the third argument.
+    /// The first argument is the name of the distribution. The second is a list of its parameters. See [draw] for the known distributions.
+    /// This function is pervasive in its third argument.
+    /// ex: # Experimental!
+    ///   : cdf "normal" [0 1] [¯1 0 1]
+    /// ex: # Experimental!
+    ///   : cdf "poisson" 3 ⇡6
+    (3, Cdf, Misc, "cdf"),
+    /// Get the value at which a distribution's cumulative probability is reached
+    ///
+    /// This is the inverse of [cdf]. The first argument is the name of the distribution. The second is a list of its parameters. See [draw] for the known distributions.
+    /// This function is pervasive in its third argument.
+    /// ex: # Experimental!
+    ///   : quantile "normal" [0 1] [0.025 0.5 0.975]
+    /// ex: # Experimental!
+    ///   : quantile "exponential" 1 0.5
+    /// For discrete distributions, the result is the smallest value whose cumulative probability is at least the argument.
+    /// ex: # Experimental!
+    ///   : quantile "binomial" [10 0.3] [0.1 0.5 0.9]
+    (3, Quantile, Misc, "quantile"),
+    /// Generate a random version 4 UUID
+    ///
+    /// The result is a string in the standard hyphenated form.
+    /// ex: # Experimental!
+    ///   : uuid
+    /// ex: # Experimental!
+    ///   : △ uuid
+    /// Randomness comes from the operating system if it is available and from the same generator as [random] otherwise.
+    /// Use [gen] if you need reproducible identifiers.
+    (0, Uuid, Misc, "uuid", Impure),
+    /// Generate a random URL-safe ID of a given length
+    ///
+    /// Characters are drawn uniformly from `A-Z`, `a-z`, `0-9`, `_`, and `-`.
+    /// ex: # Experimental!
+    ///   : nanoid 21
+    /// ex: # Experimental!
+    ///   : △ nanoid 8
+    /// Like [uuid], randomness comes from the operating system if it is available.
+    (1, NanoId, Misc, "nanoid", Impure),
     /// Match a regex pattern
     ///
     /// Returns a rank-2 array of [box]ed strings, with one string per matching group and one row per match
@@ -2734,6 +3218,314 @@ primitive!(
     ///
     /// See also: [insert], [has], [get]
     (2, Remove, Map, "remove"),
+    /// Arrange the rows of an array into a binary heap
+    ///
+    /// A heap is an array whose rows are arranged so that the smallest row can be found, removed, and added to quickly.
+    /// Rows are ordered in the same way as with [sort].
+    /// ex: # Experimental!
+    ///   : heap [5 2 8 1 9 3]
+    /// The first row of a heap is always its smallest, so you can peek at it with [first].
+    /// ex: # Experimental!
+    ///   : ⊢ heap [5 2 8 1 9 3]
+    /// Because a sorted array is already a heap, [sort] can also be used to make a heap, but [heap] is faster.
+    ///
+    /// Use [heappush] to add rows to a heap and [heappop] to remove its smallest row.
+    /// These are faster than [sort]ing the array every time a row is added or removed, so heaps are useful as priority queues.
+    /// ex: # Experimental!
+    ///   : heap [3_30 1_10 2_20]
+    ///   : heappop
+    (1, Heap, MonadicArray, "heap"),
+    /// Add rows to a heap
+    ///
+    /// See [heap] for an overview of heaps.
+    ///
+    /// The value is added to the heap as with [join], so adding a row adds a single item.
+    /// ex: # Experimental!
+    ///   : heappush 0 heap [5 2 8]
+    /// Adding an array with the same rank as the heap adds each of its rows.
+    /// ex: # Experimental!
+    ///   : heappush [4 0] heap [5 2 8]
+    (2, HeapPush, DyadicArray, "heappush"),
+    /// Remove the smallest row from a heap
+    ///
+    /// See [heap] for an overview of heaps.
+    ///
+    /// The smallest row is pushed on top of the rest of the heap.
+    /// ex: # Experimental!
+    ///   : heappop heap [5 2 8 1]
+    /// To pop multiple rows, [flip] the rest of the heap back to the top.
+    /// ex: # Experimental!
+    ///   : ⍥(:heappop)3 heap [5 2 8 1]
+    /// Popping from an empty heap is an error.
+    /// ex! # Experimental!
+    ///   : heappop []
+    (1(2), HeapPop, MonadicArray, "heappop"),
+    /// Store each unique row of an array once as a categorical array
+    ///
+    /// The result looks the same as the original array, but equal rows share their data. It also remembers the [classify] code of each row and the [deduplicate]d table of unique rows.
+    /// ex: # Experimental!
+    ///   : intern {"cat" "dog" "cat" "bird" "dog" "cat"}
+    /// [classify] and [deduplicate] use the stored codes and table instead of comparing rows again.
+    /// ex: # Experimental!
+    ///   : ⊃⊛◴ intern {"cat" "dog" "cat" "bird" "dog" "cat"}
+    /// An interned array can be used directly as the indices for [group]. Its codes are used as the indices.
+    /// ex: # Experimental!
+    ///   : ⊕□ ⊸intern {"cat" "dog" "cat" "bird" "dog" "cat"}
+    /// Any operation that changes the array returns an ordinary array.
+    (1, Intern, MonadicArray, "intern"),
+    /// Sort the rows of an array by a key
+    ///
+    /// The function is called on the array and must return an array of keys with the same number of rows.
+    /// The rows are sorted ascending by their keys.
+    /// ex: # Experimental!
+    ///   : sortby⌵ [3 ¯1 ¯4 2 0]
+    /// ex: # Experimental!
+    ///   : sortby≡◇⧻ {"banana" "fig" "apple" "kiwi"}
+    /// The sort is stable, so rows with equal keys keep their order.
+    /// ex: # Experimental!
+    ///   : sortby≡⊢ [3_1 1_2 3_0 1_1]
+    /// [sortby] is equivalent to [select][rise] with the keys.
+    /// ex: # Experimental!
+    ///   : ⊏⍏≡◇⧻. {"banana" "fig" "apple" "kiwi"}
+    /// To sort descending, [negate] numeric keys.
+    /// ex: # Experimental!
+    ///   : sortby(¯≡◇⧻) {"banana" "fig" "apple" "kiwi"}
+    (1[1], SortBy, OtherModifier, "sortby"),
+    /// Group the rows of an array by a key function and process each group
+    ///
+    /// The first function is called on the array and must return an array of keys with the same number of rows.
+    /// Rows with the same key are put in the same group. The groups are in the order that their keys first appear.
+    /// The second function is then called on each group, like in [group].
+    /// ex: # Experimental!
+    ///   : groupby⌊□ [1.5 2.1 1.2 3.7 2.9]
+    /// ex: # Experimental!
+    ///   : groupby(≡◇⊢|□) {"apple" "bob" "avocado" "banana" "cherry"}
+    /// Keys can be any array rows, not just integers.
+    /// ex: # Experimental!
+    ///   : groupby(≡⊣|⧻) ["ab" "cb" "ba" "db" "ea"]
+    /// [groupby] is equivalent to [group] with the [classify]d keys, but the keys are only hashed once.
+    /// ex: # Experimental!
+    ///   : ⊕□ ⊛⌊. [1.5 2.1 1.2 3.7 2.9]
+    /// If the second function [reduce]s with [add], [multiply], [maximum], or [minimum], each group is reduced as its rows are found without creating the group.
+    /// ex: # Experimental!
+    ///   : groupby(◿3|/+) [1 2 3 4 5 6 7]
+    (1[2], GroupBy, AggregatingModifier, "groupby"),
+    /// Get the largest rows of an array, largest first
+    ///
+    /// The first argument is the number of rows to get.
+    /// ex: # Experimental!
+    ///   : top 3 [5 1 8 3 9 2]
+    /// A negative count gets the smallest rows. They are still ordered largest first.
+    /// ex: # Experimental!
+    ///   : top ¯2 [5 1 8 3 9 2]
+    /// [top] is equivalent to [take][select][by][fall], but it only sorts the rows that are taken, so it is much faster for large arrays.
+    /// ex: # Experimental!
+    ///   : ↙3⊏⊸⍖ [5 1 8 3 9 2]
+    /// Multidimensional arrays have their rows compared lexicographically.
+    /// ex: # Experimental!
+    ///   : top 2 [1_5 3_2 3_4 0_9]
+    /// If there are fewer rows than the count, all of them are returned.
+    /// ex: # Experimental!
+    ///   : top 10 [5 1 8]
+    (2, Top, DyadicArray, "top"),
+    /// Get the unique rows of two arrays
+    ///
+    /// The rows of the first array come first. Duplicate rows are removed.
+    /// ex: # Experimental!
+    ///   : union [1 2 3 2] [4 3 5]
+    /// ex: # Experimental!
+    ///   : union {"cat" "dog"} {"dog" "bird"}
+    /// [union] is equivalent to [deduplicate][join], but it does not build the joined array first.
+    /// ex: # Experimental!
+    ///   : ◴⊂ [1 2 3 2] [4 3 5]
+    /// A single row can be used as either argument.
+    /// ex: # Experimental!
+    ///   : union 4 [1 2 3]
+    (2, Union, DyadicArray, "union"),
+    /// Get the rows of the second array that are also in the first
+    ///
+    /// The rows are in the order they appear in the second array. Duplicate rows are removed.
+    /// ex: # Experimental!
+    ///   : intersect [2 4 6 8] [1 2 3 4 5 4]
+    /// ex: # Experimental!
+    ///   : intersect ["ab" "cd"] ["cd" "ef" "ab"]
+    /// [intersect] is equivalent to [deduplicate][keep][memberof] with the arguments flipped, but it hashes the rows of the first array only once.
+    /// ex: # Experimental!
+    ///   : ◴▽⊸∊ [1 2 3 4 5 4] [2 4 6 8]
+    (2, Intersect, DyadicArray, "intersect"),
+    /// Get the rows of the second array that are not in the first
+    ///
+    /// The rows are in the order they appear in the second array. Duplicate rows are removed.
+    /// ex: # Experimental!
+    ///   : difference [2 4 6 8] [1 2 3 4 5 1]
+    /// ex: # Experimental!
+    ///   : difference "aeiou" "hello world"
+    /// Rows of the first array that are not in the second are ignored.
+    /// ex: # Experimental!
+    ///   : difference [1 10] [1 2 3]
+    (2, Difference, DyadicArray, "difference"),
+    /// Get the unique rows of an array and the number of times each occurs
+    ///
+    /// The counts are pushed on top of the unique rows.
+    /// The unique rows are the same as those from [deduplicate], and the counts are the same as those from [group][length][classify], but [counts] computes both at once.
+    /// ex: # Experimental!
+    ///   : counts "mississippi"
+    /// ex: # Experimental!
+    ///   : counts [1_2 3_4 1_2 1_2]
+    /// ex: # Experimental!
+    ///   : ⊕⧻⊛. "mississippi"
+    (1(2), Counts, MonadicArray, "counts"),
+    /// Convert a string to lowercase
+    ///
+    /// Unlike [negate] and [absolute value] on characters, [lower] uses the full Unicode case mappings, so the length of the string can change.
+    /// ex: # Experimental!
+    ///   : lower "Hello, World!"
+    /// ex: # Experimental!
+    ///   : lower "İSTANBUL"
+    /// Boxed strings and the rows of character arrays are each converted.
+    /// ex: # Experimental!
+    ///   : lower {"ABC" "Def"}
+    (1, Lower, String, "lower"),
+    /// Convert a string to uppercase
+    ///
+    /// Unlike [absolute value] on characters, [upper] uses the full Unicode case mappings, so the length of the string can change.
+    /// ex: # Experimental!
+    ///   : upper "Hello, World!"
+    /// ex: # Experimental!
+    ///   : upper "straße"
+    /// Boxed strings and the rows of character arrays are each converted.
+    /// ex: # Experimental!
+    ///   : upper ["abc" "def"]
+    (1, Upper, String, "upper"),
+    /// Convert a string to Unicode Normalization Form C
+    ///
+    /// Some characters can be written either as a single code point or as a base character followed by combining marks.
+    /// [nfc] composes such sequences into single code points where possible.
+    /// ex: # Experimental!
+    ///   : nfc "e\u{301}"
+    ///   : ⧻.
+    /// Normalizing strings before comparing them makes equivalent strings [match].
+    /// ex: # Experimental!
+    ///   : ≍∩nfc "é" "e\u{301}"
+    (1, Nfc, String, "nfc"),
+    /// Convert a string to Unicode Normalization Form D
+    ///
+    /// [nfd] decomposes characters into base characters followed by combining marks.
+    /// ex: # Experimental!
+    ///   : nfd "é"
+    ///   : ⧻.
+    /// This can be used to remove accents.
+    /// ex: # Experimental!
+    ///   : ▽<@\u{300}.nfd "Crème brûlée"
+    (1, Nfd, String, "nfd"),
+    /// Remove leading and trailing whitespace from a string
+    ///
+    /// ex: # Experimental!
+    ///   : trim "  hello world \n"
+    /// Boxed strings are each trimmed.
+    /// ex: # Experimental!
+    ///   : trim {" a " "b  " "  c"}
+    (1, Trim, String, "trim"),
+    /// Pad a string to a width
+    ///
+    /// The first argument is the width. Spaces are added to the end of the string.
+    /// ex: # Experimental!
+    ///   : pad 8 "hello"
+    /// A negative width adds the padding to the start instead.
+    /// ex: # Experimental!
+    ///   : pad ¯8 "hello"
+    /// Strings that are already at least as long as the width are unchanged.
+    /// ex: # Experimental!
+    ///   : pad 3 "hello"
+    /// Boxed strings are each padded. This can be used to align columns.
+    /// ex: # Experimental!
+    ///   : pad ¯5 {"1" "22" "333"}
+    /// A character [fill] sets the padding character.
+    /// ex: # Experimental!
+    ///   : ⬚@0pad ¯5 "42"
+    (2, Pad, String, "pad"),
+    /// Replace every occurrence of a substring
+    ///
+    /// The first argument is the text to replace. The second argument is the replacement.
+    /// ex: # Experimental!
+    ///   : replace "cat" "dog" "The cat sat on the cat"
+    /// The replacement can have a different length.
+    /// ex: # Experimental!
+    ///   : replace "ab" "" "abcabc"
+    /// Boxed strings each have their occurrences replaced.
+    /// ex: # Experimental!
+    ///   : replace @a @o {"cat" "bat" "dog"}
+    (3, Replace, String, "replace"),
+    /// Split a string by a delimiter
+    ///
+    /// The parts are boxed.
+    /// ex: # Experimental!
+    ///   : split ", " "apples, bananas, cherries"
+    /// Unlike [partition], empty parts are kept.
+    /// ex: # Experimental!
+    ///   : split "," "a,,b,"
+    /// ex: # Experimental!
+    ///   : ⊜□≠@, . "a,,b,"
+    /// [split] is the reverse of [joinwith].
+    /// ex: # Experimental!
+    ///   : joinwith "-" split "::" "a::b::c"
+    (2, Split, String, "split"),
+    /// Join a list of strings with a separator
+    ///
+    /// ex: # Experimental!
+    ///   : joinwith ", " {"apples" "bananas" "cherries"}
+    /// The rows of a character array are joined as strings.
+    /// ex: # Experimental!
+    ///   : joinwith "\n" ["abc" "def"]
+    (2, JoinWith, String, "joinwith"),
+    /// Format numbers as strings
+    ///
+    /// The first argument is the format. A number sets the number of digits after the decimal point.
+    /// ex: # Experimental!
+    ///   : fmtnum 2 π
+    /// A string sets more options. It has the form `[+][0][width][,|_][.precision][e]`, where every part is optional.
+    /// - `+` shows the sign of non-negative numbers
+    /// - `0` pads with zeros instead of spaces
+    /// - `width` is the minimum width of the string
+    /// - `,` or `_` separates groups of thousands
+    /// - `.precision` is the number of digits after the decimal point
+    /// - `e` uses scientific notation
+    /// ex: # Experimental!
+    ///   : fmtnum ",.2" 1234567.891
+    /// ex: # Experimental!
+    ///   : fmtnum "08.3" ¯η
+    /// ex: # Experimental!
+    ///   : fmtnum ".3e" 6.02214076e23
+    /// ex: # Experimental!
+    ///   : fmtnum "+" [¯1 0 1]
+    /// Unlike [un][parse], negative numbers use `-` so that the strings can be read by other programs.
+    /// Non-scalar arrays produce [box]ed strings. These can be aligned with a width.
+    /// ex: # Experimental!
+    ///   : fmtnum "8.2" [1 22.5 ¯333.125]
+    (2, FormatNum, String, "fmtnum"),
+    /// Parse strings as numbers with options
+    ///
+    /// The first argument is the options. The second is a string or an array of strings.
+    /// Two arrays are returned: the parsed numbers on top, and below them the index of the first character in each string that could not be parsed.
+    /// The index is `¯1` for strings that were parsed successfully.
+    /// ex: # Experimental!
+    ///   : parsenum {} {"12" "3.5" "1e3" "12x4" "" "¯7"}
+    /// Strings that could not be parsed become `NaN`, or the [fill] value if one is set.
+    /// ex: # Experimental!
+    ///   : ⬚0parsenum {} {"1" "two" "3"}
+    /// A number option sets the base.
+    /// ex: # Experimental!
+    ///   : ⊙◌parsenum 16 {"ff" "7F" "10.8"}
+    /// ex: # Experimental!
+    ///   : ⊙◌parsenum 2 "1011_0101"
+    ///   : parsenum {2 @. @_} "1011_0101"
+    /// A string option sets the separators of a locale.
+    /// ex: # Experimental!
+    ///   : ⊙◌parsenum "de" "1.234.567,89"
+    /// Character options set the decimal separator and digit group separator directly.
+    /// ex: # Experimental!
+    ///   : ⊙◌parsenum {@, @ } "1 234,5"
+    (2(2), ParseNum, String, "parsenum"),
     /// Debug print all stack values without popping them
     ///
     /// This is equivalent to [dump][identity], but is easier to type.
@@ -2844,7 +3636,202 @@ primitive!(
     ///   : [220 277 330 440] # Frequencies
     ///   : ⬚0↙ &asr °⊚       # Put 1 in buffer for each frequency
     ///   : ◌°ℂ °fft          # Run inverse FFT and get the real part
+    ///
+    /// A subscript transforms the last N axes instead of just the last one. This runs a multi-dimensional FFT.
+    /// ex: # Experimental!
+    ///   : ⁅₉ fft₂ [1_0 0_0]
+    /// ex: # Experimental!
+    ///   : ⁅₉ °fft₂ fft₂ [1_2 3_4]
     (1, Fft, Misc, "fft"),
+    /// Convolve two arrays
+    ///
+    /// The arrays must have the same rank. The result is the full convolution, so each axis has the combined length of the arguments' axes minus one.
+    /// ex: # Experimental!
+    ///   : convolve [1 2 3] [0 1 0.5]
+    /// Convolving lists of coefficients multiplies polynomials.
+    /// ex: # Experimental!
+    ///   : convolve [1 1] [1 2 1]
+    /// Higher-rank arrays are convolved along every axis. Here, we blur an edge in an image.
+    /// ex: # Experimental!
+    ///   : ↯4_4 [0 0 9 9]
+    ///   : convolve ÷9 ↯3_3 1
+    /// Large arrays are convolved with the [fft], so the cost grows much more slowly than the product of the arguments' sizes.
+    (2, Convolve, Misc, "convolve"),
+    /// Get the LU decomposition of a matrix
+    ///
+    /// The outputs are a lower triangular matrix `L` with ones on the diagonal, an upper triangular matrix `U`, and a row permutation `P`.
+    /// [select]ing the rows of the input matrix by `P` is the same as multiplying `L` by `U`.
+    /// ex: # Experimental!
+    ///   : lu [4_3 6_3]
+    /// ex: # Experimental!
+    ///   : A ← [2_1_1 4_3_3 8_7_9]
+    ///   : ≍⊙(⊏⊙A) ⊞(/+×)⊙⍉ lu A
+    (1(3), Lu, Misc, "lu"),
+    /// Get the QR decomposition of a matrix
+    ///
+    /// The outputs are a matrix `Q` with orthonormal columns and an upper triangular matrix `R` with a non-negative diagonal.
+    /// Multiplying `Q` by `R` gives the input matrix.
+    /// ex: # Experimental!
+    ///   : qr [3_0 4_5]
+    /// ex: # Experimental!
+    ///   : ⁅₉ ⊞(/+×)⊙⍉ qr [1_2 3_4 5_6]
+    (1(2), Qr, Misc, "qr"),
+    /// Get the singular value decomposition of a matrix
+    ///
+    /// The outputs are a matrix `U` with orthonormal columns, a list of singular values `S` in descending order, and a matrix `Vᵀ` with orthonormal rows.
+    /// Multiplying `U` by the singular values and then by `Vᵀ` gives the input matrix.
+    /// ex: # Experimental!
+    ///   : svd [3_0 4_5]
+    /// ex: # Experimental!
+    ///   : ⁅₉ ⊞(/+×)⊙⍉ ≡× ⊙¤ svd [1_2 3_4 5_6]
+    /// The singular values alone give the rank of a matrix.
+    /// ex: # Experimental!
+    ///   : /+ >1e¯10 ⊙◌◌ svd [1_2_3 2_4_6 1_0_1]
+    (1(3), Svd, Misc, "svd"),
+    /// Solve a linear system in the least-squares sense
+    ///
+    /// The first argument is a matrix `A` and the second is a list or matrix `B`.
+    /// The result `X` minimizes the distance between `A` multiplied by `X` and `B`.
+    /// ex: # Experimental!
+    ///   : lstsq [2_1 1_3] [3 5]
+    /// If the system has more equations than unknowns, this finds the best fit.
+    /// Here, we fit a line to some points. The result is the intercept and the slope.
+    /// ex: # Experimental!
+    ///   : Xs ← [0 1 2 3]
+    ///   : Ys ← [1.1 2.9 5.2 6.8]
+    ///   : lstsq ≡(⊟1) Xs Ys
+    /// If there are many solutions, the one with the smallest magnitude is chosen.
+    /// ex: # Experimental!
+    ///   : lstsq [1_1 1_1] [2 2]
+    (2, LeastSquares, Misc, "lstsq"),
+    /// Multiply and sum arrays according to an index specification
+    ///
+    /// The first argument is a specification string and the second is a list of boxed arrays.
+    /// The specification has a term for each array, separated by commas, and then `->` and the output term.
+    /// Each term has a letter for each axis of its array. Axes with the same letter are multiplied together.
+    /// Letters that do not appear in the output are summed over.
+    /// ex: # Experimental!
+    ///   : einsum "ij,jk->ik" {[1_2 3_4] [5_6 7_8]}
+    /// ex: # Experimental!
+    ///   : einsum "i,i->" {[1 2 3] [4 5 6]}
+    /// ex: # Experimental!
+    ///   : einsum "i,j->ij" {[1 2 3] [4 5]}
+    /// A single array does not need to be boxed.
+    /// Repeating a letter in a term takes a diagonal.
+    /// ex: # Experimental!
+    ///   : einsum "ii->" [1_2 3_4]
+    ///   : einsum "ii->i" [1_2 3_4]
+    /// ex: # Experimental!
+    ///   : einsum "ij->ji" [1_2_3 4_5_6]
+    /// If `->` is omitted, the output has the letters that appear only once, in alphabetical order.
+    /// ex: # Experimental!
+    ///   : einsum "ij,jk" {[1_2 3_4] [5_6 7_8]}
+    /// Letters that appear more than once are summed over.
+    /// ex: # Experimental!
+    ///   : einsum "bij,bjk" {°△2_2_2 °△2_2_2}
+    ///   : einsum "bij,bjk->bik" {°△2_2_2 °△2_2_2}
+    (2, Einsum, Misc, "einsum"),
+    /// Multiply two matrices
+    ///
+    /// This is equivalent to [table]`(`[reduce][add][multiply]`)`[dip][transpose], but uses a dedicated kernel that is much faster for large matrices.
+    /// ex: # Experimental!
+    ///   : matmul [1_2 3_4] [5_6 7_8]
+    /// Either argument may be a vector. Multiplying two vectors gives their dot product.
+    /// ex: # Experimental!
+    ///   : matmul [1_2 3_4] [1 1]
+    /// ex: # Experimental!
+    ///   : matmul [1 2 3] [4 5 6]
+    (2, MatMul, Misc, "matmul"),
+    /// Solve a square linear system
+    ///
+    /// The first argument is a square matrix `A` and the second is a list or matrix `B`.
+    /// The result `X` is such that `A` multiplied by `X` is `B`.
+    /// ex: # Experimental!
+    ///   : solve [2_1 1_3] [3 5]
+    /// ex: # Experimental!
+    ///   : solve [2_0 0_4] [1_2 3_4]
+    /// Unlike [lstsq], this errors if the matrix is singular.
+    /// ex! # Experimental!
+    ///   : solve [1_2 2_4] [1 2]
+    (2, Solve, Misc, "solve"),
+    /// Get the determinant of a square matrix
+    ///
+    /// ex: # Experimental!
+    ///   : det [1_2 3_4]
+    /// ex: # Experimental!
+    ///   : det [2_0_0 0_3_0 0_0_4]
+    (1, Det, Misc, "det"),
+    /// Invert a square matrix
+    ///
+    /// ex: # Experimental!
+    ///   : inverse [4_7 2_6]
+    /// Multiplying a matrix by its inverse gives the identity matrix.
+    /// ex: # Experimental!
+    ///   : ⁅₉ matmul⟜inverse [1_2 3_4]
+    /// Singular matrices cannot be inverted.
+    /// ex! # Experimental!
+    ///   : inverse [1_2 2_4]
+    (1, Inverse, Misc, "inverse"),
+    /// Get the eigenvalues of a square matrix
+    ///
+    /// The eigenvalues are sorted in descending order.
+    /// ex: # Experimental!
+    ///   : eig [2_1 1_2]
+    /// If any eigenvalues are not real, the result is complex.
+    /// ex: # Experimental!
+    ///   : eig [0_¯1 1_0]
+    (1, Eig, Misc, "eig"),
+    /// Evaluate a polynomial
+    ///
+    /// The first argument is a list of coefficients, starting with the constant term.
+    /// The second argument is the points to evaluate the polynomial at.
+    /// The polynomial x²-2x+1 could be represented like this:
+    /// ex: # Experimental!
+    ///   : polyval [1 ¯2 1] [0 1 2 3]
+    /// The result has the same shape as the points.
+    /// ex: # Experimental!
+    ///   : polyval [0 0 1] [1_2 3_4]
+    /// For a single point, this is equivalent to [anti][base].
+    /// ex: # Experimental!
+    ///   : ⌝base 3 [1 ¯2 1]
+    (2, PolyVal, Misc, "polyval"),
+    /// Fit a polynomial to points
+    ///
+    /// The first argument is the degree of the polynomial. The second and third arguments are lists of x and y values.
+    /// The result is a list of coefficients, starting with the constant term, that minimizes the squared error.
+    /// ex: # Experimental!
+    ///   : ⁅₉ polyfit 2 [0 1 2 3] [1 0 1 4]
+    /// If there are more points than coefficients, this finds the best fit.
+    /// Here, we fit a line to some points. The result is the intercept and the slope.
+    /// ex: # Experimental!
+    ///   : polyfit 1 [0 1 2 3] [1.1 2.9 5.2 6.8]
+    /// The result can be passed to [polyval].
+    /// ex: # Experimental!
+    ///   : Xs ← [0 1 2 3]
+    ///   : polyval polyfit 1 Xs [1.1 2.9 5.2 6.8] Xs
+    (3, PolyFit, Misc, "polyfit"),
+    /// Assign values to bins
+    ///
+    /// The first argument is either a list of bin edges or a number of bins.
+    /// The second argument is the values to bin.
+    /// Returns the number of values in each bin and the bin index of each value.
+    /// ex: # Experimental!
+    ///   : bin [0 10 20 30] [5 12 18 25 29 3]
+    /// Each bin includes its lower edge but not its upper edge, except for the last bin, which includes both.
+    /// Values outside the edges are given index `¯1` and are not counted.
+    /// ex: # Experimental!
+    ///   : bin [0 1 2] [0 1 2 3 ¯1]
+    /// If the first argument is a number, that many bins of equal width are created between the minimum and maximum values.
+    /// ex: # Experimental!
+    ///   : bin 4 [1 2 2 3 3 3 4 4 4 4 5]
+    /// Use [pop] to only get the bin indices.
+    /// ex: # Experimental!
+    ///   : ◌ bin 3 [1.5 0.2 2.9 1.1]
+    /// A common use is drawing a histogram.
+    /// ex: # Experimental!
+    ///   : ⊙◌ bin 10 /+ gen 4_1000 0
+    ///   : ⬚0≡(▽:1) ⁅÷10
+    (2(2), Bin, Misc, "bin"),
     /// Find shortest paths in a graph
     ///
     /// [astar] is deprecated in favor of [path].
@@ -3009,6 +3996,253 @@ primitive!(
     ///
     /// See also: [derivative]
     ([1], Integral, Misc, ("integral", '∫')),
+    /// Find a root of a function with Newton's method
+    ///
+    /// The function is called on an array of points and must return an array of the same shape. The argument is the initial guesses.
+    /// The slope of the function is estimated from nearby points, so the function does not need to be differentiable with [derivative].
+    /// ex: # Experimental!
+    ///   : newton(-2×.) 1
+    /// Many guesses can be refined at once.
+    /// ex: # Experimental!
+    ///   : newton(-×2∿.) [¯2 2]
+    /// Different guesses can converge to different roots.
+    /// ex: # Experimental!
+    ///   : newton(-1×.) [¯3 3]
+    /// If a guess does not converge, its result is NaN.
+    /// ex: # Experimental!
+    ///   : newton(+1×.) 1
+    /// See also: [bisect]
+    (1[1], Newton, Misc, "newton"),
+    /// Find a root of a function by bisection
+    ///
+    /// The function is called on an array of points and must return an array of the same shape.
+    /// The arguments are the lower and upper bounds of the search. The function must have different signs at the two bounds.
+    /// ex: # Experimental!
+    ///   : bisect(-2×.) 0 2
+    /// [bisect] is slower than [newton], but it always converges.
+    /// ex: # Experimental!
+    ///   : bisect∿ [2 5] [4 7]
+    /// A scalar bound is used for every search.
+    /// ex: # Experimental!
+    ///   : bisect(-[1 2 3]×.) 0 [2 2 2]
+    /// ex! # Experimental!
+    ///   : bisect(+1×.) 0 2
+    /// See also: [newton]
+    (2[1], Bisect, Misc, "bisect"),
+    /// Estimate the derivative of a function at some points
+    ///
+    /// The function is called on an array of points and must return an array of the same shape.
+    /// Unlike [derivative], any function can be used, but the result is an approximation.
+    /// ex: # Experimental!
+    ///   : ⁅₉ nderivative(×∿.) [0 1 2]
+    /// ex: # Experimental!
+    ///   : ⁅₉ nderivative(ⁿ:2) [0 1 2 3]
+    /// See also: [nintegral]
+    (1[1], NDerivative, Misc, "nderivative"),
+    /// Estimate the integral of a function over a list of points
+    ///
+    /// The function is called on a list of points and must return a list of the same length.
+    /// The result is the integral from the first point to each point, so it starts with `0`.
+    /// Unlike [integral], any function can be used, but the result is an approximation.
+    /// ex: # Experimental!
+    ///   : ⁅₉ nintegral(×.) [0 1 2 3]
+    /// Use the [last] result to get the integral over the whole range.
+    /// ex: # Experimental!
+    ///   : ⁅₉ ⊣ nintegral∿ [0 π]
+    /// Each step between points is integrated separately, so use more points for functions that change quickly.
+    /// ex: # Experimental!
+    ///   : ⁅₉ ⊣ nintegral(∿×100) [0 1]
+    ///   : ⁅₉ ⊣ nintegral(∿×100) ÷10⇡11
+    /// See also: [nderivative]
+    (1[1], NIntegral, Misc, "nintegral"),
+    /// Get the order in which nodes are reached by a breadth-first search of a graph
+    ///
+    /// The first argument is a start node or list of start nodes. The second argument is the graph.
+    /// A graph is either an adjacency matrix, where nonzero values are edges, or a [box]ed list of edges, where each row is a pair of node indices.
+    /// ex: # Experimental!
+    ///   : bfs 0 □[0_1 0_2 1_3 2_3 3_4]
+    /// ex: # Experimental!
+    ///   : bfs 0 [0_1_1 0_0_1 0_0_0]
+    /// Neighbors are visited in order of their index. Only reachable nodes are included.
+    /// ex: # Experimental!
+    ///   : bfs 2 □[2_1 2_0 0_3 1_3]
+    /// If there are multiple start nodes, they are all visited first.
+    /// ex: # Experimental!
+    ///   : bfs [0 3] □[0_1 1_2 3_4 4_5]
+    /// See also: [dfs]
+    (2, Bfs, Misc, "bfs"),
+    /// Get the order in which nodes are reached by a depth-first search of a graph
+    ///
+    /// The first argument is a start node or list of start nodes. The second argument is the graph.
+    /// A graph is either an adjacency matrix, where nonzero values are edges, or a [box]ed list of edges, where each row is a pair of node indices.
+    /// ex: # Experimental!
+    ///   : dfs 0 □[0_1 0_2 1_3 2_3 3_4]
+    /// Neighbors are visited in order of their index. Only reachable nodes are included.
+    /// ex: # Experimental!
+    ///   : dfs 0 [0_1_1_0 0_0_0_1 0_0_0_0 0_0_0_0]
+    /// If there are multiple start nodes, each one that has not already been reached starts a new search.
+    /// ex: # Experimental!
+    ///   : dfs [3 0] □[0_1 1_2 3_4 4_1]
+    /// See also: [bfs]
+    (2, Dfs, Misc, "dfs"),
+    /// Label the connected components of a graph
+    ///
+    /// A graph is either an adjacency matrix, where nonzero values are edges, or a [box]ed list of edges, where each row is a pair of node indices.
+    /// The result has a label for each node. Edge directions are ignored.
+    /// Components are numbered in order of their lowest node.
+    /// ex: # Experimental!
+    ///   : components □[0_1 3_2 2_4 5_5]
+    /// ex: # Experimental!
+    ///   : components [0_0_1 0_0_0 0_0_0]
+    /// The result can be used with [group] to get the nodes in each component.
+    /// ex: # Experimental!
+    ///   : ⊕□⟜(⇡⧻) components □[0_1 3_2 2_4 5_5]
+    (1, Components, Misc, "components"),
+    /// Sort the nodes of a directed acyclic graph so that every edge goes forward
+    ///
+    /// A graph is either an adjacency matrix, where nonzero values are edges, or a [box]ed list of edges, where each row is a pair of node indices.
+    /// When several nodes could come next, the lowest one is chosen.
+    /// ex: # Experimental!
+    ///   : toposort □[3_1 1_0 2_0 3_2]
+    /// ex: # Experimental!
+    ///   : toposort [0_0_0 1_0_0 1_1_0]
+    /// Graphs with cycles cannot be sorted.
+    /// ex! # Experimental!
+    ///   : toposort □[0_1 1_2 2_0]
+    (1, TopoSort, Misc, "toposort"),
+    /// Find the shortest paths from a node to every other node in a graph
+    ///
+    /// The first argument is a start node or list of start nodes. The second argument is the graph.
+    /// A graph is either an adjacency matrix, where nonzero values are edge weights, or a [box]ed list of edges.
+    /// Each edge is a pair of node indices, optionally followed by a weight. Edges without weights have weight `1`.
+    /// The first result is the distance to each node. The second result is the previous node on the shortest path to each node.
+    /// Unreachable nodes have a distance of [infinity] and start and unreachable nodes have a previous node of `¯1`.
+    /// ex: # Experimental!
+    ///   : shortest 0 □[0_1_4 0_2_1 2_1_2 1_3_1]
+    /// ex: # Experimental!
+    ///   : shortest 0 [0_5_1 0_0_0 0_3_0]
+    /// With multiple start nodes, the distance is to the nearest one.
+    /// ex: # Experimental!
+    ///   : shortest [0 4] □[0_1 1_2 2_3 4_3]
+    /// Edge weights cannot be negative.
+    /// ex! # Experimental!
+    ///   : shortest 0 □[0_1_1 1_2_¯1]
+    (2(2), Shortest, Misc, "shortest"),
+    /// Get the dot product of vectors
+    ///
+    /// Vectors are along the last axis. The arrays must have the same shape, or one of them must be a single vector.
+    /// ex: # Experimental!
+    ///   : dot [1 2 3] [4 5 6]
+    /// ex: # Experimental!
+    ///   : dot [1 0] [1_2 3_4 5_6]
+    /// ex: # Experimental!
+    ///   : dot [1_2 3_4] [5_6 7_8]
+    /// See also: [cross]
+    (2, Dot, Misc, "dot"),
+    /// Get the cross product of vectors
+    ///
+    /// Vectors are along the last axis. The arrays must have the same shape, or one of them must be a single vector.
+    /// For 3D vectors, the result is a vector perpendicular to both.
+    /// ex: # Experimental!
+    ///   : cross [1 0 0] [0 1 0]
+    /// ex: # Experimental!
+    ///   : cross [0 0 1] [1_0_0 0_1_0 1_1_1]
+    /// For 2D vectors, the result is the z component of the cross product of the vectors extended to 3D.
+    /// It is positive if the second vector is counterclockwise from the first.
+    /// ex: # Experimental!
+    ///   : cross [1 0] [0_1 0_¯1 2_0]
+    (2, Cross, Misc, "cross"),
+    /// Get the Euclidean length of vectors
+    ///
+    /// Vectors are along the last axis.
+    /// ex: # Experimental!
+    ///   : norm [3 4]
+    /// ex: # Experimental!
+    ///   : norm [1_1 3_4 0_0]
+    /// [divide]ing vectors by their [norm] normalizes them.
+    /// ex: # Experimental!
+    ///   : ≡(÷⊸norm) [3_4 0_2]
+    (1, Norm, Misc, "norm"),
+    /// Get the distances between every pair of points in two lists
+    ///
+    /// The result has a row for each point in the first list and a column for each point in the second.
+    /// ex: # Experimental!
+    ///   : distmat [0_0 1_1] [3_4 0_1 1_1]
+    /// Pass the same list twice to get the distances between all of its points.
+    /// ex: # Experimental!
+    ///   : distmat. [0_0 3_4 6_8]
+    (2, DistMat, Misc, "distmat"),
+    /// Get the convex hull of a list of 2D points
+    ///
+    /// The result is the indices of the points on the hull in counterclockwise order, starting from the leftmost point.
+    /// Points in the middle of a side of the hull are not included.
+    /// ex: # Experimental!
+    ///   : hull [0_0 2_0 1_1 2_2 0_2 1_0]
+    /// [select] the indices to get the points.
+    /// ex: # Experimental!
+    ///   : ⊏⊸hull [0_0 2_0 1_1 2_2 0_2 1_0]
+    (1, Hull, Misc, "hull"),
+    /// Check whether 2D points are inside a polygon
+    ///
+    /// The first argument is the polygon's vertices in order. The second argument is the points to check.
+    /// Points on the polygon's boundary are inside.
+    /// ex: # Experimental!
+    ///   : inpoly [0_0 4_0 4_4 0_4] [2_2 5_2 4_1]
+    /// The polygon may be concave or self-intersecting. A point is inside if a ray from it crosses the boundary an odd number of times.
+    /// ex: # Experimental!
+    ///   : inpoly [0_0 4_0 4_4 2_1 0_4] [2_3 1_1]
+    (2, InPoly, Misc, "inpoly"),
+    /// Linearly interpolate an array at fractional indices
+    ///
+    /// The first argument is the indices. The second argument is the array.
+    /// ex: # Experimental!
+    ///   : interp 1.5 [10 20 30]
+    /// ex: # Experimental!
+    ///   : interp [0 0.25 1.5 2] [10 20 30]
+    /// Indices index into the first axis, so rows are interpolated.
+    /// ex: # Experimental!
+    ///   : interp 0.5 [1_2 3_6]
+    /// Indices outside the array are clamped to its edges.
+    /// ex: # Experimental!
+    ///   : interp [¯1 5] [10 20 30]
+    /// Subscripted [interp] interpolates along that many axes. The last axis of the indices is the coordinates along those axes.
+    /// ex: # Experimental!
+    ///   : interp₂ [0.5_0.5 0_1.5] [0_10_20 30_40_50]
+    /// Use [table] or [rows] to interpolate along separate axes.
+    /// ex: # Experimental!
+    ///   : ≡interp ⊙¤ [0 0.5 1] [0_10_20 30_40_50]
+    /// See also: [cubic], [resample]
+    (2, Interp, Misc, "interp"),
+    /// Smoothly interpolate an array at fractional indices
+    ///
+    /// This works like [interp], but it uses a cubic curve through the 2 elements on either side of each index.
+    /// ex: # Experimental!
+    ///   : cubic [0 0.5 1 1.5 2] [0 1 0]
+    /// ex: # Experimental!
+    ///   : cubic 1.5 [1 4 9 16]
+    /// Like [interp], [cubic] can be subscripted to interpolate along multiple axes.
+    /// ex: # Experimental!
+    ///   : cubic₂ [0.5_0.5 1_1] ⊞+.⇡3
+    /// See also: [interp], [resample]
+    (2, Cubic, Misc, "cubic"),
+    /// Resample an array to a new shape
+    ///
+    /// The first argument is the new length of each of the leading axes. The second argument is the array.
+    /// ex: # Experimental!
+    ///   : resample 6 [0 10 20]
+    /// ex: # Experimental!
+    ///   : resample 2 [1 2 3 4 5 6]
+    /// When downsampling, each new element is a weighted average of all the elements it covers.
+    /// This makes it good for resizing images.
+    /// ex: # Experimental!
+    ///   : resample₀ 2_2 ⊞+.⇡4
+    /// Subscripted [resample] picks a filter by its degree. `₀` averages boxes, `₁` is linear, which is the default, and `₃` is cubic.
+    /// ex: # Experimental!
+    ///   : resample₀ 6 [1 2 3]
+    /// ex: # Experimental!
+    ///   : resample₃ 6 [1 2 3]
+    /// See also: [interp], [cubic]
+    (2, Resample, Misc, "resample"),
     /// Encode an array into a JSON string
     ///
     /// ex: json [1 2 3]
@@ -3058,6 +4292,102 @@ primitive!(
     /// You can easily create a [map] with the headers as keys.
     /// ex: map⊙(⍉⋕)°⊂ °csv "#,Count\n1,5\n2,21\n3,8\n"
     (1, Csv, Encoding, "csv"),
+    /// Encode an array into delimiter-separated values with options
+    ///
+    /// The first argument is a list of options.
+    /// The first character option is the delimiter (default `,`).
+    /// The second character option is the quote character (default `"`).
+    /// A boolean option sets whether there is a header row (default `0`).
+    /// ex: # Experimental!
+    ///   : dsv @\t [1_2_3 4_5_6]
+    /// ex: # Experimental!
+    ///   : dsv {@; @'} [{"a;b" "c"}]
+    /// With a header row, a [map] of column names to columns is written.
+    /// ex: # Experimental!
+    ///   : dsv 1 map {"Name" "Count"} {{"Ann" "Bob"} [5 21]}
+    /// You can use [anti][dsv] to decode values with the same options.
+    /// ex: # Experimental!
+    ///   : ⌝dsv @\t "1\t2\t3\n4\t5\t6"
+    ///   : ⌝dsv {@; @'} "'a;b';c"
+    /// With a header row, decoding gives a [map] of column names to columns.
+    /// ex: # Experimental!
+    ///   : ⌝dsv 1 "Name,Count\nAnn,5\nBob,21"
+    /// ex: # Experimental!
+    ///   : ⋕get "Count" ⌝dsv 1 "Name,Count\nAnn,5\nBob,21"
+    (2, Dsv, Encoding, "dsv"),
+    /// Render a table into a string
+    ///
+    /// The first argument is a list of options.
+    /// A string option sets the style, which is one of `"text"` (the default), `"md"`, or `"csv"`.
+    /// A boolean option sets whether the first row is a header row (default `0`).
+    /// The second argument is a rank 2 array.
+    /// ex: # Experimental!
+    ///   : &p tabulate "text" [1_2_3 40_50_60]
+    /// ex: # Experimental!
+    ///   : &p tabulate {"md" 1} [{"Name" "Count"} {"Ann" 5} {"Bob" 21}]
+    /// Numeric columns are right-aligned.
+    /// If the array is a [map], its keys are used as a header row.
+    /// ex: # Experimental!
+    ///   : &p tabulate "md" map {"Name" "Count"} {{"Ann" "Bob"} [5 21]}
+    /// The `"csv"` style is equivalent to [dsv] with a header row.
+    /// ex: # Experimental!
+    ///   : &p tabulate "csv" map {"Name" "Count"} {{"Ann" "Bob"} [5 21]}
+    (2, Tabulate, Encoding, "tabulate"),
+    /// Encode a string or bytes as text
+    ///
+    /// The first argument is the name of the encoding.
+    /// Strings are encoded as UTF-8.
+    /// ex: # Experimental!
+    ///   : encode "base64" "Hello, World!"
+    /// ex: # Experimental!
+    ///   : encode "hex" [0 15 16 255]
+    /// ex: # Experimental!
+    ///   : encode "url" "a b&c=d/é"
+    /// ex: # Experimental!
+    ///   : encode "html" "<a href=\"x\">Tom & Jerry</a>"
+    /// The supported encodings are:
+    /// - `"base64"` - Standard base64 with padding
+    /// - `"base64url"` - URL-safe base64 without padding
+    /// - `"base32"` - Base32 with padding
+    /// - `"hex"` - Lowercase hexadecimal
+    /// - `"url"` - URL percent-encoding
+    /// - `"html"` - HTML entity escaping
+    ///
+    /// You can use [anti][encode] to decode.
+    /// `"base64"`, `"base64url"`, `"base32"`, and `"hex"` decode to bytes.
+    /// `"url"` and `"html"` decode to strings.
+    /// ex: # Experimental!
+    ///   : ⌝encode "base64" "SGVsbG8sIFdvcmxkIQ=="
+    /// ex: # Experimental!
+    ///   : ⌝encode "url" "a%20b%26c"
+    /// Use [un][utf₈] to convert decoded bytes to a string.
+    /// ex: # Experimental!
+    ///   : °utf₈ ⌝encode "base32" "JBSWY3DP"
+    (2, Encode, Encoding, "encode"),
+    /// Compress a string or bytes
+    ///
+    /// The first argument is the compression format.
+    /// Strings are encoded as UTF-8.
+    /// The result is a byte array.
+    /// ex: # Experimental!
+    ///   : compress "gzip" "Hello, World!"
+    /// ex: # Experimental!
+    ///   : ⧻ compress "zlib" ▽1000 "abc"
+    /// The supported formats are `"gzip"`, `"deflate"`, `"zlib"`, and `"zstd"`.
+    /// `"zstd"` is only available in the native interpreter.
+    ///
+    /// You can use [anti][compress] to decompress.
+    /// ex: # Experimental!
+    ///   : ⌝compress "gzip" compress "gzip" "Hello, World!"
+    /// Use [un][utf₈] to convert decompressed bytes to a string.
+    /// ex: # Experimental!
+    ///   : °utf₈ ⌝compress "deflate" compress "deflate" "Hello, World!"
+    /// Combined with [binary], [compress] can shrink serialized values.
+    /// ex: # Experimental!
+    ///   : ⊃(⧻binary|⧻compress "gzip" binary) ⇡1000
+    ///
+    /// See also: [&dcs]
+    (2, Compress, Encoding, "compress"),
     /// Encode an array into XLSX bytes
     ///
     /// XLSX is a spreadsheet format that can be edited in programs like Microsoft Excel, Google Sheets, and LibreOffice Calc.
@@ -3113,6 +4443,42 @@ primitive!(
     /// ex: # Experimental!
     ///   : ÷∩⧻⟜binary ℂ0 ⇡256
     (1, Binary, Encoding, "binary"),
+    /// Encode an array into NumPy's `.npy` format
+    ///
+    /// The result is a byte array that can be written to a file and loaded with `numpy.load`.
+    /// Bytes are encoded as `u1`, numbers as `f8`, complex numbers as `c16`, and characters as `U1`.
+    /// Box arrays cannot be encoded.
+    /// ex: # Experimental!
+    ///   : npy [1 2 3]
+    /// [un][npy] decodes `.npy` bytes into an array, preserving its shape.
+    /// Booleans and `u1` become byte arrays. Other integer and float types become number arrays.
+    /// ex: # Experimental!
+    ///   : °npy npy °△2_3
+    /// ex: # Experimental!
+    ///   : °npy npy "Hello!"
+    (1, Npy, Encoding, "npy"),
+    /// Encode a map of names to arrays into NumPy's `.npz` format
+    ///
+    /// Each array is encoded with [npy] and stored uncompressed, as `numpy.savez` does.
+    /// ex: # Experimental!
+    ///   : npz map {"x" "y"} {[1 2 3] °△2_2}
+    /// [un][npz] decodes `.npz` bytes into a map of names to boxed arrays.
+    /// Compressed archives, like those written by `numpy.savez_compressed`, are not supported.
+    /// ex: # Experimental!
+    ///   : °npz npz map {"x" "y"} {[1 2 3] °△2_2}
+    (1, Npz, Encoding, "npz"),
+    /// Encode a table into the Apache Arrow IPC format
+    ///
+    /// The table is a [map] of column names to columns.
+    /// Number columns become `Float64`, byte columns become `UInt8`, and string columns become `Utf8`.
+    /// All columns must have the same length.
+    /// ex: # Experimental!
+    ///   : arrow map {"x" "name"} {[1 2 3] {"a" "bc" "def"}}
+    /// [un][arrow] decodes Arrow IPC data, in either the streaming or file format, into a map of column names to boxed columns.
+    /// Integer and float columns become number arrays, with nulls as `NaN`.
+    /// ex: # Experimental!
+    ///   : °arrow arrow map {"x" "name"} {[1 2 3] {"a" "bc" "def"}}
+    (1, Arrow, Encoding, "arrow"),
     /// Convert a value to its code representation
     ///
     /// ex: repr π
@@ -3140,7 +4506,7 @@ primitive!(
     /// A length 3 last axis is an RGB image.
     /// A length 4 last axis is an RGB image with an alpha channel.
     ///
-    /// You can decode a byte array into an image with [un][img].
+    /// You can decode a byte array into an image with [un][img]. `&imd` is an alias for [un][img].
     ///
     /// Supported formats are `jpg`, `png`, `bmp`, `gif`, `ico`, and `qoi`.
     ///
@@ -3156,6 +4522,19 @@ primitive!(
     ///
     /// See also: [&gifs]
     (2, GifEncode, Encoding, "gif"),
+    /// Encode an animated PNG into a byte array
+    ///
+    /// The first argument is a framerate in frames per second.
+    /// The second argument is the animation data and must be a rank 3 or 4 numeric array.
+    /// The rows of the array are the frames of the animation, and their format must conform to that of [img].
+    ///
+    /// Unlike [gif], APNG supports full color and partial transparency.
+    /// ex: # Experimental!
+    ///   : ↙8 apng 10 ÷10 ⇡10_20_20
+    /// [table] and [rows] are useful for generating frames.
+    /// ex: # Experimental!
+    ///   : ⧻ apng 30 ⊞(◿1+) ÷⟜⇡30 ⊞+.÷⟜⇡50
+    (2, ApngEncode, Encoding, "apng"),
     /// Encode audio into a byte array
     ///
     /// The first argument is the format, the second is the audio sample rate, and the third is the audio samples.
@@ -3236,6 +4615,20 @@ macro_rules! impl_primitive {
                 $variant,
             )*
             DeshapeSub(i32),
+            /// Reshape with an explicit way of fitting the data
+            ReshapeSub(ReshapeMode),
+            /// Linear or cubic interpolation along N axes
+            InterpSub { axes: usize, cubic: bool },
+            /// Resampling with a filter of degree N
+            ResampleSub(usize),
+            /// Sine where a full turn is N units
+            SinSub(i32),
+            /// Arcsine where a full turn is N units
+            AsinSub(i32),
+            /// FFT over the last N axes
+            FftSub(usize),
+            /// Inverse FFT over the last N axes
+            UnFftSub(usize),
             UndoDeshape(Option<i32>),
             EachSub(i32),
             TransposeN(i32),
@@ -3250,6 +4643,10 @@ macro_rules! impl_primitive {
             OffSub(usize),
             /// Push the maximum row count of N values
             MaxRowCount(usize),
+            /// A dyadic pervasive function called with the same value for both arguments
+            DupPervasive(Primitive),
+            /// A dyadic pervasive function called with its arguments flipped
+            FlipPervasive(Primitive),
         }
 
         impl ImplPrimitive {
@@ -3257,6 +4654,10 @@ macro_rules! impl_primitive {
                 Some(match self {
                     $($(ImplPrimitive::$variant => $args,)?)*
                     ImplPrimitive::DeshapeSub(_) => 1,
+                    ImplPrimitive::ReshapeSub(_) => 2,
+                    ImplPrimitive::InterpSub { .. } | ImplPrimitive::ResampleSub(_) => 2,
+                    ImplPrimitive::SinSub(_) | ImplPrimitive::AsinSub(_) => 1,
+                    ImplPrimitive::FftSub(_) | ImplPrimitive::UnFftSub(_) => 1,
                     ImplPrimitive::UndoDeshape(_) => 2,
                     ImplPrimitive::TransposeN(_) => 1,
                     ImplPrimitive::UndoTransposeN(n, _) => *n,
@@ -3265,6 +4666,8 @@ macro_rules! impl_primitive {
                     ImplPrimitive::ReduceDepth(_) => 1,
                     ImplPrimitive::StackN { n, .. } => *n,
                     ImplPrimitive::MaxRowCount(n) => *n,
+                    ImplPrimitive::DupPervasive(_) => 1,
+                    ImplPrimitive::FlipPervasive(_) => 2,
                     _ => return None
                 })
             }
@@ -3318,6 +4721,8 @@ impl_primitive!(
     (1(2), UnAtan),
     (1(2), UnComplex),
     (1, UnParse),
+    (1, UnTokenize),
+    (1(2), UnError),
     (1, UnFix),
     (1, UnShape),
     (1[1], UnScan),
@@ -3340,7 +4745,13 @@ impl_primitive!(
     (1, UnSort, Impure),
     (1, UnJson),
     (1, UnBinary),
+    (1, UnNpy),
+    (1, UnNpz),
+    (1, UnArrow),
     (1, UnCsv),
+    (2, UnDsv),
+    (2, UnEncode),
+    (2, UnCompress),
     (1, UnXlsx),
     (1, UnFft),
     (1, UnDatetime),
@@ -3353,6 +4764,7 @@ impl_primitive!(
     (1(3), AudioDecode),
     (0(1), UnRawMode, Impure),
     (1(0), UnClip, Mutating),
+    (0(1), UnChangeDirectory, Impure),
     // Unders
     (1, UndoFix),
     (2, UndoUnbits),
@@ -3385,6 +4797,8 @@ impl_primitive!(
     (2, SetSign),
     // Optimizations
     (1, FirstMinIndex),
+    (2, RiseTake),
+    (2, FallTake),
     (1, FirstMaxIndex),
     (1, LastMinIndex),
     (1, LastMaxIndex),
@@ -3412,6 +4826,8 @@ impl_primitive!(
     (2[1], SplitBy),
     (2[1], SplitByKeepEmpty),
     (2, MatrixDiv),
+    (1(1)[1], StridedView),
+    (1(2)[2], FusedFork),
     // Implementation details
     (1, Utf16),
     ([2], RepeatWithInverse),
@@ -3423,4 +4839,6 @@ impl_primitive!(
     (1, ValidateNonBoxedVariant),
     (2(1), ValidateVariant),
     (2(1), TagVariant),
+    /// Validate that a map of optional arguments only has known keys
+    (2(0), ValidateOptions),
 );
@@ -9,7 +9,7 @@ use regex::Regex;
 
 use core::str;
 use std::{
-    borrow::{BorrowMut, Cow},
+    borrow::Cow,
     cell::RefCell,
     collections::HashMap,
     f64::consts::{PI, TAU},
@@ -26,14 +26,16 @@ use rand::prelude::*;
 use serde::*;
 
 use crate::{
-    algorithm::{self, loops, reduce, table, zip, *},
+    algorithm::{
+        self, geometry, graph, interp, linalg, loops, numeric, reduce, stats, table, view, zip, *,
+    },
     array::Array,
     boxed::Boxed,
     encode,
     lex::{AsciiToken, SUBSCRIPT_DIGITS},
     sys::*,
     value::*,
-    FunctionId, Ops, Shape, Signature, Uiua, UiuaErrorKind, UiuaResult,
+    FunctionId, Node, Ops, Shape, Signature, Uiua, UiuaErrorKind, UiuaResult,
 };
 
 /// Categories of primitives
@@ -56,6 +58,7 @@ pub enum PrimClass {
     Thread,
     Map,
     Encoding,
+    String,
     Misc,
     Sys(SysOpClass),
 }
@@ -98,6 +101,7 @@ impl fmt::Debug for PrimClass {
             Thread => write!(f, "Thread"),
             Map => write!(f, "Map"),
             Encoding => write!(f, "Encoding"),
+            String => write!(f, "String"),
             Misc => write!(f, "Misc"),
             Sys(op) => op.fmt(f),
         }
@@ -155,14 +159,12 @@ impl fmt::Display for Primitive {
     }
 }
 
-fn fmt_subscript(f: &mut fmt::Formatter<'_>, mut i: i32) -> fmt::Result {
+fn fmt_subscript(f: &mut fmt::Formatter<'_>, i: i32) -> fmt::Result {
     if i < 0 {
         write!(f, "₋")?;
-        i = -i;
     }
-    while i > 0 {
-        write!(f, "{}", SUBSCRIPT_DIGITS[i as usize % 10])?;
-        i /= 10;
+    for c in i.unsigned_abs().to_string().bytes() {
+        write!(f, "{}", SUBSCRIPT_DIGITS[(c - b'0') as usize])?;
     }
     Ok(())
 }
@@ -176,6 +178,37 @@ impl fmt::Display for ImplPrimitive {
                 write!(f, "{Deshape}")?;
                 fmt_subscript(f, i)
             }
+            &ReshapeSub(mode) => {
+                write!(f, "{Reshape}")?;
+                match mode.subscript() {
+                    Some(i) => fmt_subscript(f, i),
+                    None => Ok(()),
+                }
+            }
+            &InterpSub { axes, cubic } => {
+                write!(f, "{}", if cubic { Cubic } else { Interp })?;
+                fmt_subscript(f, axes as i32)
+            }
+            &ResampleSub(degree) => {
+                write!(f, "{Resample}")?;
+                fmt_subscript(f, degree as i32)
+            }
+            &SinSub(i) => {
+                write!(f, "{Sin}")?;
+                fmt_subscript(f, i)
+            }
+            &AsinSub(i) => {
+                write!(f, "{Un}{Sin}")?;
+                fmt_subscript(f, i)
+            }
+            &FftSub(n) => {
+                write!(f, "{Fft}")?;
+                fmt_subscript(f, n as i32)
+            }
+            &UnFftSub(n) => {
+                write!(f, "{Un}{Fft}")?;
+                fmt_subscript(f, n as i32)
+            }
             &EachSub(i) => {
                 write!(f, "{Each}")?;
                 fmt_subscript(f, i)
@@ -212,6 +245,8 @@ impl fmt::Display for ImplPrimitive {
             Utf16 => write!(f, "utf₁₆"),
             UnGraphemes => write!(f, "{Un}{Graphemes}"),
             UnParse => write!(f, "{Un}{Parse}"),
+            UnError => write!(f, "{Un}{Error}"),
+            UnTokenize => write!(f, "{Un}{Tokenize}"),
             UnFix => write!(f, "{Un}{Fix}"),
             UnShape => write!(f, "{Un}{Shape}"),
             AntiDrop => write!(f, "{Anti}{Drop}"),
@@ -228,7 +263,13 @@ impl fmt::Display for ImplPrimitive {
             UnSort => write!(f, "{Un}{Sort}"),
             UnJson => write!(f, "{Un}{Json}"),
             UnBinary => write!(f, "{Un}{Binary}"),
+            UnNpy => write!(f, "{Un}{Npy}"),
+            UnNpz => write!(f, "{Un}{Npz}"),
+            UnArrow => write!(f, "{Un}{Arrow}"),
             UnCsv => write!(f, "{Un}{Csv}"),
+            UnDsv => write!(f, "{Anti}{Dsv}"),
+            UnEncode => write!(f, "{Anti}{Encode}"),
+            UnCompress => write!(f, "{Anti}{Compress}"),
             UnXlsx => write!(f, "{Un}{Xlsx}"),
             UnFft => write!(f, "{Un}{Fft}"),
             UnDatetime => write!(f, "{Un}{DateTime}"),
@@ -239,6 +280,7 @@ impl fmt::Display for ImplPrimitive {
             AudioDecode => write!(f, "{Un}{AudioEncode}"),
             UnRawMode => write!(f, "{Un}{}", Primitive::Sys(SysOp::RawMode)),
             UnClip => write!(f, "{Un}{}", Primitive::Sys(SysOp::Clip)),
+            UnChangeDirectory => write!(f, "{Un}{}", Primitive::Sys(SysOp::ChangeDirectory)),
             ProgressiveIndexOf => write!(f, "{Un}{By}{Select}"),
             UndoUnbits => write!(f, "{Under}{Un}{Bits}"),
             AntiBase => write!(f, "{Anti}{Base}"),
@@ -272,6 +314,8 @@ impl fmt::Display for ImplPrimitive {
             SetSign => write!(f, "{Under}{Sign}"),
             // Optimizations
             FirstMinIndex => write!(f, "{First}{Rise}"),
+            RiseTake => write!(f, "{Take}…{Rise}"),
+            FallTake => write!(f, "{Take}…{Fall}"),
             FirstMaxIndex => write!(f, "{First}{Fall}"),
             LastMinIndex => write!(f, "{First}{Reverse}{Rise}"),
             LastMaxIndex => write!(f, "{First}{Reverse}{Fall}"),
@@ -303,6 +347,10 @@ impl fmt::Display for ImplPrimitive {
             SplitBy => write!(f, "{Partition}{Box}{Not}{By}{Mask}"),
             SplitByKeepEmpty => write!(f, "{Un}{Reduce}$\"_…_\""),
             MatrixDiv => write!(f, "{Anti}{Under}{Transpose}({Reduce}{Add}{Mul})"),
+            StridedView => write!(f, "strided view"),
+            FusedFork => write!(f, "{Fork}"),
+            DupPervasive(prim) => write!(f, "{prim}{Dup}"),
+            FlipPervasive(prim) => write!(f, "{prim}{Flip}"),
             &ReduceDepth(n) => {
                 for _ in 0..n {
                     write!(f, "{Rows}")?;
@@ -342,6 +390,7 @@ impl fmt::Display for ImplPrimitive {
             ValidateNonBoxedVariant => write!(f, "|…[…]"),
             ValidateVariant => write!(f, "|…°[…]"),
             TagVariant => write!(f, "<tag variant>"),
+            ValidateOptions => write!(f, "<validate options>"),
         }
     }
 }
@@ -383,6 +432,9 @@ impl fmt::Display for FormatPrimitive {
     }
 }
 
+/// A function that implements a dyadic pervasive primitive
+pub(crate) type DyadicPervasiveFn = fn(Value, Value, &Uiua) -> UiuaResult<Value>;
+
 static ALIASES: Lazy<HashMap<Primitive, &[&str]>> = Lazy::new(|| {
     [
         (Primitive::Identity, &["id"] as &[_]),
@@ -412,31 +464,55 @@ static ALIASES: Lazy<HashMap<Primitive, &[&str]>> = Lazy::new(|| {
         (Primitive::ImageEncode, &["&ime", "imen"]),
         (Primitive::GifEncode, &["&gife", "gifen"]),
         (Primitive::AudioEncode, &["&ae", "auden"]),
+        (Primitive::Real, &["re"]),
+        (Primitive::Imaginary, &["im", "imag"]),
+        (Primitive::Argument, &["arg"]),
+        (Primitive::Conjugate, &["conj"]),
     ]
     .into()
 });
 
+/// Aliases that expand to multiple primitives
+static MULTI_ALIASES: &[(&str, &[Primitive])] =
+    &[("&imd", &[Primitive::Un, Primitive::ImageEncode])];
+
 macro_rules! fill {
     ($ops:expr, $env:expr, $with:ident, $without_but:ident) => {{
         let env = $env;
         let [fill, f] = get_ops($ops, env)?;
         let outputs = fill.sig.outputs;
-        if outputs > 1 {
-            return Err(env.error(format!(
-                "{} function can have at most 1 output, but its signature is {}",
-                Primitive::Fill.format(),
-                fill.sig
-            )));
-        }
         if outputs == 0 {
             return env.$without_but(fill.sig.args, |env| env.exec(fill), |env| env.exec(f));
         }
-        env.exec(fill)?;
-        let fill_value = env.pop("fill value")?;
-        env.$with(fill_value, |env| env.exec(f))?;
+        // Filling with un pop passes all the current fill values along
+        let forwarded = if is_un_pop(&fill.node) {
+            env.last_fills().map(<[Value]>::to_vec)
+        } else {
+            None
+        };
+        let fill_values = if let Some(fill_values) = forwarded {
+            fill_values
+        } else {
+            env.exec(fill)?;
+            let mut fill_values = Vec::with_capacity(outputs);
+            for i in 0..outputs {
+                fill_values.push(env.pop(("fill value", i + 1))?);
+            }
+            fill_values
+        };
+        env.$with(fill_values, |env| env.exec(f))?;
     }};
 }
 
+fn is_un_pop(node: &Node) -> bool {
+    match node {
+        Node::ImplPrim(ImplPrimitive::UnPop, _) => true,
+        Node::CustomInverse(cust, _) => (cust.normal.as_ref()).is_ok_and(|sn| is_un_pop(&sn.node)),
+        Node::Run(nodes) => nodes.len() == 1 && is_un_pop(&nodes[0]),
+        _ => false,
+    }
+}
+
 impl Primitive {
     /// Get an iterator over all primitives
     pub fn all() -> impl Iterator<Item = Self> + Clone {
@@ -513,7 +589,12 @@ impl Primitive {
             (Couple | Box, Some(n)) if n >= 0 => Signature::new(n as usize, 1),
             (Couple, None) => Signature::new(2, 1),
             (Box, None) => Signature::new(1, 1),
-            (Transpose | Sqrt | Round | Floor | Ceil | Rand | Utf8, _) => return self.sig(),
+            (Reshape, Some(n)) if ReshapeMode::from_subscript(n).is_some() => return self.sig(),
+            (Interp | Cubic, Some(n)) if n > 0 => return self.sig(),
+            (Resample, Some(0 | 1 | 3)) => return self.sig(),
+            (Transpose | Sqrt | Sin | Fft | Round | Floor | Ceil | Rand | Utf8, _) => {
+                return self.sig()
+            }
             (Stack, Some(n)) if n >= 0 => Signature::new(n as usize, n as usize),
             _ => return None,
         })
@@ -542,13 +623,96 @@ impl Primitive {
         matches!(
             self,
             (Reach | Slf | Backward | Above | Around)
-                | (Or | Base | Fft | Layout | Binary)
+                | (Catch | Resume | Stream | Yield | Handle | Perform)
+                | (Real | Imaginary | Argument | Conjugate)
+                | (Or | Base | Fft | Convolve | Layout | Binary | Npy | Npz | Arrow | Dsv)
+                | (Lu | Qr | Svd | LeastSquares | Einsum | MatMul | Solve | Det | Inverse | Eig)
+                | (Bin | Sample)
+                | (Seed | Seeds | Uniform | Normal | RandInt)
+                | (Draw | Pdf | Cdf | Quantile)
+                | (Uuid
+                    | NanoId
+                    | ApngEncode
+                    | Tabulate
+                    | Encode
+                    | Compress
+                    | Scope
+                    | Pattern
+                    | Error
+                    | Tokenize)
+                | (Expect | Requires | Ensures)
+                | (Cell | Load | Store | Update)
+                | (Heap | HeapPush | HeapPop | Intern | SortBy | GroupBy | Top)
+                | (Union | Intersect | Difference | Counts)
+                | (Lower | Upper | Nfc | Nfd | Trim | Pad | Replace | Split | JoinWith)
+                | (FormatNum | ParseNum)
                 | Astar
                 | (Derivative | Integral)
-                | Sys(Ffi | MemCopy | MemFree | TlsListen | Breakpoint)
+                | (PolyVal | PolyFit | Newton | Bisect | NDerivative | NIntegral)
+                | (Bfs | Dfs | Components | TopoSort | Shortest)
+                | (Dot | Cross | Norm | DistMat | Hull | InPoly)
+                | (Interp | Cubic | Resample)
+                | Sys(Ffi
+                    | MemCopy
+                    | MemFree
+                    | TlsListen
+                    | Breakpoint
+                    | FSave
+                    | FLoad
+                    | ReadLineSample
+                    | ReadChunks
+                    | TermCursor
+                    | TermClear
+                    | TermColor
+                    | FramesShow
+                    | DecompressStream
+                    | HttpRequest
+                    | RunStreamEnv
+                    | RunWait
+                    | Vars
+                    | FMetadata
+                    | FMove
+                    | FGlob
+                    | FWatch
+                    | FWatchRead
+                    | UdpBind
+                    | UdpSend
+                    | UdpReceive
+                    | WsConnect
+                    | WsSend
+                    | WsReceive
+                    | SerialOpen
+                    | SerialList
+                    | SerialSetBaudRate
+                    | SerialSetTimeout)
                 | (Stringify | Quote | Sig)
         )
     }
+    /// Get the function that implements a dyadic pervasive primitive
+    pub(crate) fn dyadic_pervasive_fn(&self) -> Option<DyadicPervasiveFn> {
+        use Primitive::*;
+        Some(match self {
+            Eq => Value::is_eq,
+            Ne => Value::is_ne,
+            Lt => Value::other_is_lt,
+            Le => Value::other_is_le,
+            Gt => Value::other_is_gt,
+            Ge => Value::other_is_ge,
+            Add => Value::add,
+            Sub => Value::sub,
+            Mul => Value::mul,
+            Div => Value::div,
+            Modulus => Value::modulus,
+            Or => Value::or,
+            Pow => Value::pow,
+            Log => Value::log,
+            Min => Value::min,
+            Max => Value::max,
+            Atan => Value::atan2,
+            Complex => Value::complex,
+            _ => return None,
+        })
+    }
     /// Check if this primitive is deprecated
     pub fn is_deprecated(&self) -> bool {
         self.deprecation_suggestion().is_some()
@@ -624,6 +788,14 @@ impl Primitive {
     }
     /// Try to parse multiple primitives from the concatenation of their name prefixes
     pub fn from_format_name_multi(name: &str) -> Option<Vec<(Self, &str)>> {
+        if let Some((_, prims)) = MULTI_ALIASES.iter().find(|(alias, _)| *alias == name) {
+            let (first, rest) = name.split_at(1);
+            return Some(
+                (prims.iter().enumerate())
+                    .map(|(i, &prim)| (prim, if i == 0 { first } else { rest }))
+                    .collect(),
+            );
+        }
         let mut indices: Vec<usize> = name.char_indices().map(|(i, _)| i).collect();
         if indices.len() < 2 {
             return None;
@@ -786,6 +958,10 @@ impl Primitive {
             Primitive::Abs => env.monadic_env(Value::abs)?,
             Primitive::Sign => env.monadic_env(Value::sign)?,
             Primitive::Sqrt => env.monadic_env(Value::sqrt)?,
+            Primitive::Real => env.monadic_env(Value::complex_re)?,
+            Primitive::Imaginary => env.monadic_env(Value::complex_im)?,
+            Primitive::Argument => env.monadic_env(Value::complex_arg)?,
+            Primitive::Conjugate => env.monadic_env(Value::complex_conj)?,
             Primitive::Sin => env.monadic_env(Value::sin)?,
             Primitive::Floor => env.monadic_env(Value::floor)?,
             Primitive::Ceil => env.monadic_env(Value::ceil)?,
@@ -808,7 +984,14 @@ impl Primitive {
             Primitive::Max => env.dyadic_oo_env(Value::max)?,
             Primitive::Atan => env.dyadic_oo_env(Value::atan2)?,
             Primitive::Complex => env.dyadic_oo_env(Value::complex)?,
-            Primitive::Match => env.dyadic_rr(|a, b| a == b)?,
+            Primitive::Match => env.dyadic_rr_env(|a, b, env| {
+                let tolerance = if env.rt.match_tolerance {
+                    env.rt.tolerance
+                } else {
+                    0.0
+                };
+                Ok(a.approx_eq(b, tolerance))
+            })?,
             Primitive::Join => env.dyadic_oo_env(|a, b, env| a.join(b, true, env))?,
             Primitive::Transpose => env.monadic_mut(Value::transpose)?,
             Primitive::Keep => env.dyadic_oo_env(Value::keep)?,
@@ -846,6 +1029,7 @@ impl Primitive {
                 env.push(val.box_depth(0));
             }
             Primitive::Repr => env.monadic_ref(Value::representation)?,
+            Primitive::Tokenize => env.monadic_ref_env(Value::tokenize)?,
             Primitive::Parse => env.monadic_ref_env(Value::parse_num)?,
             Primitive::Utf8 => env.monadic_ref_env(Value::utf8)?,
             Primitive::Graphemes => env.monadic_ref_env(Value::graphemes)?,
@@ -864,7 +1048,7 @@ impl Primitive {
             Primitive::Reshape => {
                 let shape = env.pop(1)?;
                 let mut array = env.pop(2)?;
-                array.reshape(&shape, env)?;
+                array.reshape(&shape, ReshapeMode::Auto, env)?;
                 env.push(array);
             }
             Primitive::Rerank => {
@@ -913,8 +1097,82 @@ impl Primitive {
                     .into());
                 }
             }
-            Primitive::Rand => env.push(random()),
+            Primitive::Error => {
+                let message = env.pop("message")?;
+                let payload = env.pop("payload")?;
+                env.push(Value::error_value(message, payload, env)?);
+            }
+            Primitive::Expect => algorithm::expect(env)?,
+            Primitive::Yield => loops::yield_(env)?,
+            Primitive::Perform => loops::perform(env)?,
+            Primitive::Rand => {
+                let x: f64 = env.rt.rng.gen();
+                env.push(x)
+            }
             Primitive::Gen => env.dyadic_rr_env(Value::gen)?,
+            Primitive::Sample => {
+                let shape = env.pop(1)?;
+                let seed = env.pop(2)?;
+                let weights = env.pop(3)?;
+                env.push(shape.sample(&seed, &weights, env)?);
+            }
+            Primitive::Seed => {
+                let seed = env.pop(1)?;
+                seed.seed(env);
+            }
+            Primitive::Seeds => {
+                let n = env.pop(1)?;
+                let seeds = n.seeds(env)?;
+                env.push(seeds);
+            }
+            Primitive::Uniform => {
+                let shape = env.pop(1)?;
+                let arr = shape.uniform(env)?;
+                env.push(arr);
+            }
+            Primitive::Normal => {
+                let shape = env.pop(1)?;
+                let arr = shape.normal(env)?;
+                env.push(arr);
+            }
+            Primitive::RandInt => {
+                let shape = env.pop(1)?;
+                let max = env.pop(2)?;
+                let arr = shape.randint(&max, env)?;
+                env.push(arr);
+            }
+            Primitive::Draw => stats::draw(env)?,
+            Primitive::Pdf => stats::pdf(env)?,
+            Primitive::Cdf => stats::cdf(env)?,
+            Primitive::Quantile => stats::quantile(env)?,
+            Primitive::Uuid => {
+                let mut bytes = [0; 16];
+                random_bytes(env, &mut bytes);
+                bytes[6] = (bytes[6] & 0x0f) | 0x40;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                let mut uuid = String::with_capacity(36);
+                for (i, b) in bytes.iter().enumerate() {
+                    if [4, 6, 8, 10].contains(&i) {
+                        uuid.push('-');
+                    }
+                    uuid.push_str(&format!("{b:02x}"));
+                }
+                env.push(uuid);
+            }
+            Primitive::NanoId => {
+                const ALPHABET: &[u8; 64] =
+                    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789_-";
+                let len = env
+                    .pop(1)?
+                    .as_nat(env, "ID length must be a natural number")?;
+                let mut bytes = vec![0; len];
+                random_bytes(env, &mut bytes);
+                let id: String = bytes
+                    .iter()
+                    .map(|&b| ALPHABET[b as usize % 64] as char)
+                    .collect();
+                env.push(id);
+            }
             Primitive::Tag => {
                 static NEXT_TAG: AtomicUsize = AtomicUsize::new(0);
                 let tag = NEXT_TAG.fetch_add(1, atomic::Ordering::Relaxed);
@@ -941,6 +1199,20 @@ impl Primitive {
                 let id = env.pop(1)?;
                 env.try_recv(id)?;
             }
+            Primitive::Cell => {
+                let val = env.pop(1)?;
+                let id = env.new_cell(val);
+                env.push(id);
+            }
+            Primitive::Load => {
+                let id = env.pop(1)?;
+                env.load_cell(id)?;
+            }
+            Primitive::Store => {
+                let id = env.pop(1)?;
+                let val = env.pop(2)?;
+                env.store_cell(id, val)?;
+            }
             Primitive::Now => env.push(env.rt.backend.now()),
             Primitive::TimeZone => {
                 let o = env.rt.backend.timezone().map_err(|e| env.error(e))?;
@@ -971,6 +1243,47 @@ impl Primitive {
                 map.remove(key, env)?;
                 env.push(map);
             }
+            Primitive::Heap => env.monadic_mut_env(Value::heapify)?,
+            Primitive::HeapPush => env.dyadic_oo_env(Value::heap_push)?,
+            Primitive::HeapPop => {
+                let heap = env.pop(1)?;
+                let (min, rest) = heap.heap_pop(env)?;
+                env.push(rest);
+                env.push(min);
+            }
+            Primitive::Top => env.dyadic_oo_env(Value::top)?,
+            Primitive::Union => env.dyadic_oo_env(Value::union)?,
+            Primitive::Intersect => env.dyadic_oo_env(Value::intersect)?,
+            Primitive::Difference => env.dyadic_oo_env(Value::difference)?,
+            Primitive::Counts => {
+                let val = env.pop(1)?;
+                let (unique, counts) = val.counts();
+                env.push(unique);
+                env.push(counts);
+            }
+            Primitive::Lower => env.monadic_env(Value::lower)?,
+            Primitive::Upper => env.monadic_env(Value::upper)?,
+            Primitive::Nfc => env.monadic_env(Value::nfc)?,
+            Primitive::Nfd => env.monadic_env(Value::nfd)?,
+            Primitive::Trim => env.monadic_env(Value::trim)?,
+            Primitive::Pad => env.dyadic_ro_env(Value::pad)?,
+            Primitive::Replace => {
+                let old = env.pop(1)?;
+                let new = env.pop(2)?;
+                let string = env.pop(3)?;
+                env.push(old.replace(&new, string, env)?);
+            }
+            Primitive::Split => env.dyadic_rr_env(Value::split)?,
+            Primitive::JoinWith => env.dyadic_rr_env(Value::join_with)?,
+            Primitive::FormatNum => env.dyadic_rr_env(Value::format_num)?,
+            Primitive::ParseNum => {
+                let options = env.pop(1)?;
+                let strings = env.pop(2)?;
+                let (nums, errors) = options.parse_nums(&strings, env)?;
+                env.push(errors);
+                env.push(nums);
+            }
+            Primitive::Intern => env.monadic_ref(Value::intern)?,
             Primitive::Map => {
                 let keys = env.pop("keys")?;
                 let mut vals = env.pop("values")?;
@@ -982,15 +1295,65 @@ impl Primitive {
             Primitive::Regex => regex(env)?,
             Primitive::Json => env.monadic_ref_env(Value::to_json_string)?,
             Primitive::Binary => env.monadic_ref_env(Value::to_binary)?,
+            Primitive::Npy => encode::npy_encode(env)?,
+            Primitive::Npz => encode::npz_encode(env)?,
+            Primitive::Arrow => encode::arrow_encode(env)?,
             Primitive::Csv => env.monadic_ref_env(Value::to_csv)?,
+            Primitive::Dsv => {
+                let options = encode::DsvOptions::from_value(&env.pop(1)?, env)?;
+                let val = env.pop(2)?;
+                env.push(val.to_dsv(options, env)?);
+            }
+            Primitive::Encode => env.dyadic_oo_env(encode::text_encode)?,
+            Primitive::Compress => env.dyadic_oo_env(encode::compress)?,
+            Primitive::Tabulate => {
+                let options = encode::TableOptions::from_value(&env.pop(1)?, env)?;
+                let val = env.pop(2)?;
+                env.push(val.to_table(options, env)?);
+            }
             Primitive::Xlsx => {
                 env.monadic_ref_env(|value, env| value.to_xlsx(env).map(EcoVec::from))?
             }
             Primitive::ImageEncode => encode::image_encode(env)?,
             Primitive::GifEncode => encode::gif_encode(env)?,
+            Primitive::ApngEncode => encode::apng_encode(env)?,
             Primitive::AudioEncode => encode::audio_encode(env)?,
             Primitive::Layout => env.dyadic_oo_env(encode::layout_text)?,
-            Primitive::Fft => algorithm::fft(env)?,
+            Primitive::Fft => algorithm::fft(1, env)?,
+            Primitive::Convolve => algorithm::convolve(env)?,
+            Primitive::Lu => linalg::lu(env)?,
+            Primitive::Qr => linalg::qr(env)?,
+            Primitive::Svd => linalg::svd(env)?,
+            Primitive::LeastSquares => linalg::least_squares(env)?,
+            Primitive::Einsum => linalg::einsum(env)?,
+            Primitive::MatMul => linalg::matmul(env)?,
+            Primitive::Solve => linalg::solve(env)?,
+            Primitive::Det => linalg::det(env)?,
+            Primitive::Inverse => linalg::inverse(env)?,
+            Primitive::Eig => linalg::eig(env)?,
+            Primitive::PolyVal => numeric::poly_eval(env)?,
+            Primitive::PolyFit => numeric::poly_fit(env)?,
+            Primitive::Bfs => graph::bfs(env)?,
+            Primitive::Dfs => graph::dfs(env)?,
+            Primitive::Components => graph::components(env)?,
+            Primitive::TopoSort => graph::toposort(env)?,
+            Primitive::Shortest => graph::shortest(env)?,
+            Primitive::Dot => geometry::dot(env)?,
+            Primitive::Cross => geometry::cross(env)?,
+            Primitive::Norm => geometry::norm(env)?,
+            Primitive::DistMat => geometry::dist_mat(env)?,
+            Primitive::Hull => geometry::hull(env)?,
+            Primitive::InPoly => geometry::in_poly(env)?,
+            Primitive::Interp => interp::interp(None, false, env)?,
+            Primitive::Cubic => interp::interp(None, true, env)?,
+            Primitive::Resample => interp::resample(1, env)?,
+            Primitive::Bin => {
+                let edges = env.pop(1)?;
+                let values = env.pop(2)?;
+                let (counts, indices) = edges.bin(&values, env)?;
+                env.push(indices);
+                env.push(counts);
+            }
             Primitive::Stringify
             | Primitive::Quote
             | Primitive::Sig
@@ -1040,6 +1403,10 @@ impl Primitive {
                 let [f] = get_ops(ops, env)?;
                 zip::rows(f, true, env)?
             }
+            Primitive::Catch => zip::catch(ops, env)?,
+            Primitive::Resume => zip::resume(ops, env)?,
+            Primitive::Stream => loops::stream(ops, env)?,
+            Primitive::Handle => loops::handle(ops, env)?,
             Primitive::Table => table::table(ops, env)?,
             Primitive::Repeat => loops::repeat(ops, false, false, env)?,
             Primitive::Do => loops::do_(ops, env)?,
@@ -1051,6 +1418,7 @@ impl Primitive {
                 let [f] = get_ops(ops, env)?;
                 loops::partition(f, env)?
             }
+            Primitive::GroupBy => loops::group_by(ops, env)?,
             Primitive::Tuples => tuples::tuples(ops, env)?,
             Primitive::Stencil => stencil::stencil(ops, env)?,
 
@@ -1126,8 +1494,15 @@ impl Primitive {
             }
 
             // Misc
-            Primitive::Fill => fill!(ops, env, with_fill, without_fill_but),
+            Primitive::Fill => fill!(ops, env, with_fills, without_fill_but),
             Primitive::Try => algorithm::try_(ops, env)?,
+            Primitive::Requires => algorithm::requires(ops, env)?,
+            Primitive::SortBy => algorithm::sort_by(ops, env)?,
+            Primitive::Newton => numeric::newton(ops, env)?,
+            Primitive::Bisect => numeric::bisect(ops, env)?,
+            Primitive::NDerivative => numeric::nderivative(ops, env)?,
+            Primitive::NIntegral => numeric::nintegral(ops, env)?,
+            Primitive::Ensures => algorithm::ensures(ops, env)?,
             Primitive::Case => {
                 let [f] = get_ops(ops, env)?;
                 env.exec(f).map_err(|mut e| {
@@ -1146,32 +1521,7 @@ impl Primitive {
             }
             Primitive::Memo => {
                 let [f] = get_ops(ops, env)?;
-                let mut args = Vec::with_capacity(f.sig.args);
-                for i in 0..f.sig.args {
-                    args.push(env.pop(i + 1)?);
-                }
-                let mut memo = env.rt.memo.get_or_default().borrow_mut();
-                if let Some(f_memo) = memo.get_mut(&f.node) {
-                    if let Some(outputs) = f_memo.get(&args) {
-                        let outputs = outputs.clone();
-                        drop(memo);
-                        for val in outputs {
-                            env.push(val);
-                        }
-                        return Ok(());
-                    }
-                }
-                drop(memo);
-                for arg in args.iter().rev() {
-                    env.push(arg.clone());
-                }
-                env.exec(f.node.clone())?;
-                let outputs = env.clone_stack_top(f.sig.outputs)?;
-                let mut memo = env.rt.memo.get_or_default().borrow_mut();
-                memo.borrow_mut()
-                    .entry(f.node)
-                    .or_default()
-                    .insert(args, outputs.clone());
+                env.exec_memoized(f)?;
             }
             Primitive::Spawn => {
                 let [f] = get_ops(ops, env)?;
@@ -1181,6 +1531,11 @@ impl Primitive {
                 let [f] = get_ops(ops, env)?;
                 env.spawn(f.sig.args, true, f)?;
             }
+            Primitive::Scope => env.scope(ops)?,
+            Primitive::Update => {
+                let [f] = get_ops(ops, env)?;
+                env.update_cell(f)?;
+            }
             Primitive::Sys(op) => op.run_mod(ops, env)?,
             prim => {
                 return Err(env.error(if prim.modifier_args().is_some() {
@@ -1208,6 +1563,22 @@ impl ImplPrimitive {
             ImplPrimitive::DeshapeSub(i) => {
                 env.monadic_mut_env(|val, env| val.deshape_sub(*i, true, env))?
             }
+            &ImplPrimitive::ReshapeSub(mode) => {
+                let shape = env.pop(1)?;
+                let mut array = env.pop(2)?;
+                array.reshape(&shape, mode, env)?;
+                env.push(array);
+            }
+            &ImplPrimitive::InterpSub { axes, cubic } => interp::interp(Some(axes), cubic, env)?,
+            &ImplPrimitive::ResampleSub(degree) => interp::resample(degree, env)?,
+            &ImplPrimitive::SinSub(n) => {
+                let val = env.pop(1)?;
+                env.push(val.sin_turn(n, env)?);
+            }
+            &ImplPrimitive::AsinSub(n) => {
+                let val = env.pop(1)?;
+                env.push(val.asin_turn(n, env)?);
+            }
             ImplPrimitive::Root => env.dyadic_oo_env(Value::root)?,
             ImplPrimitive::Cos => env.monadic_env(Value::cos)?,
             ImplPrimitive::Asin => env.monadic_env(Value::asin)?,
@@ -1222,6 +1593,12 @@ impl ImplPrimitive {
                 env.push(b);
                 env.push(a);
             }
+            ImplPrimitive::UnError => {
+                let error = env.pop(1)?;
+                let (message, payload) = error.unerror(env)?;
+                env.push(payload);
+                env.push(message);
+            }
             ImplPrimitive::UnMap => {
                 let map = env.pop(1)?;
                 let (keys, vals) = map.unmap(env)?;
@@ -1300,6 +1677,7 @@ impl ImplPrimitive {
                 env.push(im);
             }
             ImplPrimitive::UnParse => env.monadic_ref_env(Value::unparse)?,
+            ImplPrimitive::UnTokenize => env.monadic_ref_env(Value::untokenize)?,
             ImplPrimitive::UnFix => env.monadic_mut_env(Value::unfix)?,
             ImplPrimitive::UnShape => env.monadic_ref_env(Value::unshape)?,
             ImplPrimitive::StackN { n, inverse } => stack_n(env, *n, *inverse)?,
@@ -1315,7 +1693,7 @@ impl ImplPrimitive {
                     env.push(arr);
                 } else {
                     let mut rows: Vec<Value> = arr.into_rows().collect();
-                    RNG.with_borrow_mut(|rng| rows.shuffle(rng));
+                    rows.shuffle(&mut env.rt.rng);
                     env.push(Value::from_row_values_infallible(rows));
                 }
             }
@@ -1329,17 +1707,30 @@ impl ImplPrimitive {
                 let val = Value::from_binary(&bytes, env)?;
                 env.push(val);
             }
+            ImplPrimitive::UnNpy => encode::npy_decode(env)?,
+            ImplPrimitive::UnNpz => encode::npz_decode(env)?,
+            ImplPrimitive::UnArrow => encode::arrow_decode(env)?,
             ImplPrimitive::UnCsv => {
                 let csv = env.pop(1)?.as_string(env, "CSV expects a string")?;
                 let val = Value::from_csv(&csv, env)?;
                 env.push(val);
             }
+            ImplPrimitive::UnEncode => env.dyadic_oo_env(encode::text_decode)?,
+            ImplPrimitive::UnCompress => env.dyadic_oo_env(encode::decompress)?,
+            ImplPrimitive::UnDsv => {
+                let options = encode::DsvOptions::from_value(&env.pop(1)?, env)?;
+                let dsv = env.pop(2)?.as_string(env, "DSV expects a string")?;
+                let val = Value::from_dsv(&dsv, options, env)?;
+                env.push(val);
+            }
             ImplPrimitive::UnXlsx => {
                 let xlsx = env.pop(1)?.as_bytes(env, "XLSX expects bytes")?;
                 let val = Value::from_xlsx(&xlsx, env)?;
                 env.push(val);
             }
-            ImplPrimitive::UnFft => algorithm::unfft(env)?,
+            ImplPrimitive::UnFft => algorithm::unfft(1, env)?,
+            &ImplPrimitive::FftSub(n) => algorithm::fft(n, env)?,
+            &ImplPrimitive::UnFftSub(n) => algorithm::unfft(n, env)?,
             ImplPrimitive::UnDatetime => env.monadic_ref_env(Value::undatetime)?,
             ImplPrimitive::ProgressiveIndexOf => env.dyadic_rr_env(Value::progressive_index_of)?,
             ImplPrimitive::ImageDecode => encode::image_decode(env)?,
@@ -1355,6 +1746,12 @@ impl ImplPrimitive {
                     .set_clipboard(&contents)
                     .map_err(|e| env.error(e))?;
             }
+            ImplPrimitive::UnChangeDirectory => {
+                let dir = (env.rt.backend)
+                    .current_directory()
+                    .map_err(|e| env.error(e))?;
+                env.push(dir);
+            }
             ImplPrimitive::MatrixDiv => env.dyadic_rr_env(Value::matrix_div)?,
             // Unders
             ImplPrimitive::UndoUnbits => {
@@ -1541,6 +1938,8 @@ impl ImplPrimitive {
             ImplPrimitive::SetSign => env.dyadic_oo_env(Value::set_sign)?,
             // Optimizations
             ImplPrimitive::FirstMinIndex => env.monadic_ref_env(Value::first_min_index)?,
+            ImplPrimitive::RiseTake => env.dyadic_oo_env(Value::rise_take)?,
+            ImplPrimitive::FallTake => env.dyadic_oo_env(Value::fall_take)?,
             ImplPrimitive::FirstMaxIndex => env.monadic_ref_env(Value::first_max_index)?,
             ImplPrimitive::LastMinIndex => env.monadic_ref_env(Value::last_min_index)?,
             ImplPrimitive::LastMaxIndex => env.monadic_ref_env(Value::last_max_index)?,
@@ -1550,18 +1949,24 @@ impl ImplPrimitive {
             ImplPrimitive::MultidimMemberOfRange => {
                 env.dyadic_ro_env(Value::multidim_memberof_range)?
             }
-            ImplPrimitive::RandomRow => env.monadic_ref_env(Value::random_row)?,
+            ImplPrimitive::RandomRow => {
+                let val = env.pop(1)?;
+                let row = val.random_row(env)?;
+                env.push(row);
+            }
             ImplPrimitive::LastWhere => env.monadic_ref_env(Value::last_where)?,
             ImplPrimitive::SortDown => env.monadic_mut(Value::sort_down)?,
             ImplPrimitive::AllSame => env.monadic_ref(Value::all_same)?,
             ImplPrimitive::ReplaceRand => {
                 env.pop(1)?;
-                env.push(random());
+                let x: f64 = env.rt.rng.gen();
+                env.push(x);
             }
             ImplPrimitive::ReplaceRand2 => {
                 env.pop(1)?;
                 env.pop(2)?;
-                env.push(random());
+                let x: f64 = env.rt.rng.gen();
+                env.push(x);
             }
             ImplPrimitive::CountUnique => env.monadic_ref(Value::count_unique)?,
             ImplPrimitive::MatchPattern => {
@@ -1677,6 +2082,17 @@ impl ImplPrimitive {
                 return Err(env.error(env.error(format!("Pattern match failed: {message}"))));
             }
             &ImplPrimitive::TransposeN(n) => env.monadic_mut(|val| val.transpose_depth(0, n))?,
+            &ImplPrimitive::DupPervasive(prim) => {
+                let f = prim.dyadic_pervasive_fn().unwrap();
+                let x = env.pop(1)?;
+                env.push(f(x.clone(), x, env)?);
+            }
+            &ImplPrimitive::FlipPervasive(prim) => {
+                let f = prim.dyadic_pervasive_fn().unwrap();
+                let a = env.pop(1)?;
+                let b = env.pop(2)?;
+                env.push(f(b, a, env)?);
+            }
             // Implementation details
             ImplPrimitive::ValidateType | ImplPrimitive::ValidateTypeConsume => {
                 let type_num = env
@@ -1732,6 +2148,30 @@ impl ImplPrimitive {
                 }
                 env.push(val);
             }
+            ImplPrimitive::ValidateOptions => {
+                let names = env.pop(1)?;
+                let options = env.pop(2)?;
+                if options.row_count() > 0 {
+                    if !options.is_map() {
+                        return Err(env.error(format!(
+                            "Optional arguments must be a map, but they are {}",
+                            options.type_name_plural()
+                        )));
+                    }
+                    let names = names.as_strings(env, "Option names must be strings")?;
+                    for (key, _) in options.map_kv() {
+                        let key = key.unboxed();
+                        let name = key.as_string_opt();
+                        if !name.as_ref().is_some_and(|name| names.contains(name)) {
+                            return Err(env.error(format!(
+                                "`{}` is not an optional argument. Options are {}",
+                                key.format(),
+                                names.join(", ")
+                            )));
+                        }
+                    }
+                }
+            }
             ImplPrimitive::ValidateVariant => {
                 let tag = env.pop(1)?;
                 let val = env.pop(2)?;
@@ -1806,6 +2246,8 @@ impl ImplPrimitive {
             ImplPrimitive::UndoPartition1 => loops::undo_partition_part1(ops, env)?,
             ImplPrimitive::UndoGroup1 => loops::undo_group_part1(ops, env)?,
             ImplPrimitive::ReduceContent => reduce::reduce_content(ops, env)?,
+            ImplPrimitive::StridedView => view::strided_view(ops, env)?,
+            ImplPrimitive::FusedFork => reduce::fused_fork(ops, env)?,
             ImplPrimitive::ReduceConjoinInventory => zip::reduce_conjoin_inventory(ops, env)?,
             ImplPrimitive::AstarFirst => {
                 let [neighbors, heuristic, is_goal] = get_ops(ops, env)?;
@@ -1836,7 +2278,7 @@ impl ImplPrimitive {
             ImplPrimitive::RepeatCountConvergence => loops::repeat(ops, false, true, env)?,
             ImplPrimitive::UnScan => reduce::unscan(ops, env)?,
             ImplPrimitive::UnDump => dump(ops, env, true)?,
-            ImplPrimitive::UnFill => fill!(ops, env, with_unfill, without_unfill_but),
+            ImplPrimitive::UnFill => fill!(ops, env, with_unfills, without_unfill_but),
             ImplPrimitive::ReduceTable => table::reduce_table(ops, env)?,
             ImplPrimitive::UnBoth => {
                 let [f] = get_ops(ops, env)?;
@@ -1976,16 +2418,27 @@ thread_local! {
     pub(crate) static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::from_entropy());
 }
 
-/// Generate a random number, equivalent to [`Primitive::Rand`]
+/// Generate a random number from the thread's generator
+///
+/// Each [`Uiua`] environment has its own generator, which is seeded from this one when it is created.
 pub fn random() -> f64 {
     RNG.with(|rng| rng.borrow_mut().gen::<f64>())
 }
 
-/// Seed the random number generator
+/// Seed the thread's random number generator
+///
+/// Environments created afterward on this thread will generate the same random numbers.
 pub fn seed_random(seed: u64) {
     RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(seed));
 }
 
+/// Fill a buffer with random bytes from the backend, falling back to the environment's generator
+fn random_bytes(env: &mut Uiua, bytes: &mut [u8]) {
+    if env.rt.backend.os_random_bytes(bytes).is_err() {
+        env.rt.rng.fill_bytes(bytes);
+    }
+}
+
 fn trace(env: &mut Uiua, inverse: bool) -> UiuaResult {
     let val = env.pop(1)?;
     let span: String = if inverse {
@@ -2477,8 +2930,9 @@ mod tests {
             for line in &prim.doc().lines {
                 if let PrimDocLine::Example(ex) = line {
                     if [
-                        "&sl", "&tcpc", "&tlsc", "&ast", "&clip", "&fo", "&fc", "&fde", "&ftr",
-                        "&fld", "&fif", "&fras", "&frab", "&fmd", "timezone", "&b",
+                        "&sl", "&tcpc", "&tlsc", "&udp", "&ws", "&http", "&ast", "&clip", "&fo",
+                        "&fc", "&fde", "&ftr", "&fld", "&fif", "&fras", "&frab", "&fmd", "&fsv",
+                        "&flv", "&fmeta", "&fmv", "&fglob", "&fwt", "timezone", "&b",
                     ]
                     .iter()
                     .any(|prim| ex.input.contains(prim))
@@ -2577,6 +3031,10 @@ mod tests {
             &*Primitive::from_format_name_multi("tabkee").unwrap(),
             [(Primitive::Table, _), (Primitive::Keep, _)]
         ));
+        assert!(matches!(
+            &*Primitive::from_format_name_multi("&imd").unwrap(),
+            [(Primitive::Un, _), (Primitive::ImageEncode, _)]
+        ));
         assert_eq!(Primitive::from_format_name_multi("foo"), None);
     }
 
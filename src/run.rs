@@ -4,28 +4,37 @@ use std::{
     cell::RefCell,
     cmp::Ordering,
     collections::HashMap,
+    fmt,
     hash::Hash,
-    mem::{size_of, take},
+    mem::{replace, size_of, take},
     panic::{catch_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::Arc,
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc,
+    },
     time::Duration,
 };
 
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
-use ecow::EcoVec;
-use thread_local::ThreadLocal;
+use ecow::{eco_vec, EcoVec};
+use parking_lot::{Mutex, ReentrantMutex};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 use crate::{
-    algorithm::{self, validate_size_impl},
-    fill::Fill,
+    algorithm::{
+        self,
+        loops::{HandlerFrame, StreamFrame},
+        validate_size_impl,
+    },
+    fill::{Fill, FillKind},
     invert::match_format_pattern,
     lex::Span,
-    Array, ArrayLen, Assembly, BindingKind, BindingMeta, Boxed, CodeSpan, Compiler, Function,
-    FunctionId, Ident, Inputs, IntoSysBackend, LocalName, Node, Primitive, Report, SafeSys,
-    SigNode, Signature, SysBackend, TraceFrame, UiuaError, UiuaErrorKind, UiuaResult, Value,
-    VERSION,
+    Array, ArrayLen, Assembly, BindingKind, BindingMeta, Boxed, CodeSpan, Compiler, ExecutionTrace,
+    Function, FunctionId, Ident, Inputs, IntoSysBackend, LocalName, Locale, MemoCache, MessageId,
+    Node, Primitive, Report, SafeSys, SigNode, Signature, SysBackend, SysPermissions, TraceFrame,
+    UiuaError, UiuaErrorKind, UiuaResult, Value, RNG, VERSION,
 };
 
 /// The Uiua interpreter
@@ -47,12 +56,20 @@ pub(crate) struct Runtime {
     pub(crate) call_stack: Vec<StackFrame>,
     /// The local stack
     pub(crate) local_stack: EcoVec<(usize, Value)>,
+    /// The stack of streams consuming yielded values
+    pub(crate) stream_stack: Vec<StreamFrame>,
+    /// Whether contract conditions are checked
+    pub(crate) contracts: bool,
+    /// The stack of effect handlers
+    pub(crate) handler_stack: Vec<HandlerFrame>,
     /// The stack for tracking recursion points
     recur_stack: Vec<usize>,
     /// The fill stack
-    fill_stack: Vec<Value>,
+    ///
+    /// Each entry is a group of fill values with different types
+    fill_stack: Vec<EcoVec<Value>>,
     /// The unfill stack
-    unfill_stack: Vec<Value>,
+    unfill_stack: Vec<EcoVec<Value>>,
     /// The fill boundary stack
     fill_boundary_stack: Vec<(usize, usize)>,
     /// The depth of arrays under construction
@@ -61,8 +78,22 @@ pub(crate) struct Runtime {
     pub(crate) execution_limit: Option<f64>,
     /// The time at which execution started
     pub(crate) execution_start: f64,
+    /// The remaining fuel, shared between threads
+    fuel: Option<Arc<AtomicU64>>,
     /// The recursion limit
     recursion_limit: usize,
+    /// How loops may be run in parallel
+    pub(crate) parallelism: Parallelism,
+    /// The tolerance for numbers to be considered equal
+    pub(crate) tolerance: f64,
+    /// Whether [`Primitive::Match`] uses the tolerance
+    pub(crate) match_tolerance: bool,
+    /// The audio sample rate, overriding the backend's
+    pub(crate) audio_sample_rate: Option<u32>,
+    /// The timeout for HTTP requests
+    pub(crate) http_timeout: Option<Duration>,
+    /// The classes of system functions that are permitted
+    pub(crate) sys_permissions: SysPermissions,
     /// Whether the program was interrupted
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) interrupted: Option<Arc<dyn Fn() -> bool + Send + Sync>>,
@@ -70,6 +101,18 @@ pub(crate) struct Runtime {
     pub(crate) interrupted: Option<Arc<dyn Fn() -> bool>>,
     /// Whether to print the time taken to execute each instruction
     time_instrs: bool,
+    /// The execution trace being recorded
+    trace: Option<Arc<ExecutionTrace>>,
+    /// The id of this thread in the execution trace
+    trace_thread: u64,
+    /// The random number generator
+    pub(crate) rng: SmallRng,
+    /// The locale that error messages are shown in
+    pub(crate) locale: Locale,
+    /// Whether to catch interpreter crashes in every entry point
+    pub(crate) hardened: bool,
+    /// Whether an entry point is already catching crashes
+    catching_crashes: bool,
     /// The time at which the last instruction was executed
     last_time: f64,
     /// Arguments passed from the command line
@@ -87,15 +130,18 @@ pub(crate) struct Runtime {
     /// Values for output comments
     pub(crate) output_comments: HashMap<usize, Vec<Vec<Value>>>,
     /// Memoized values
-    pub(crate) memo: Arc<ThreadLocal<RefCell<MemoMap>>>,
+    pub(crate) memo: Arc<MemoCache>,
+    /// Cells shared between threads
+    cells: Arc<Cells>,
     /// The results of tests
     pub(crate) test_results: Vec<UiuaResult>,
     /// Reports to print
     pub(crate) reports: Vec<Report>,
+    /// Call counts and compiled functions for the JIT
+    #[cfg(feature = "jit")]
+    pub(crate) jit: crate::jit::JitCache,
 }
 
-type MemoMap = HashMap<Node, HashMap<Vec<Value>, Vec<Value>>>;
-
 impl AsRef<Assembly> for Uiua {
     fn as_ref(&self) -> &Assembly {
         &self.asm
@@ -153,6 +199,13 @@ struct Thread {
     pub channel: Channel,
 }
 
+/// Mutable cells shared by all threads of a runtime
+///
+/// The cell mutexes are reentrant so that a thread updating a cell
+/// can still access it from within the update function.
+#[derive(Default)]
+struct Cells(Mutex<Vec<Arc<ReentrantMutex<RefCell<Value>>>>>);
+
 impl Default for Uiua {
     fn default() -> Self {
         Self::with_safe_sys()
@@ -185,6 +238,46 @@ impl FromStr for RunMode {
     }
 }
 
+/// How loops with independent iterations may be run in parallel
+///
+/// Parallelism is only ever used for pure functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Parallelism {
+    /// Always run loops serially
+    Off,
+    /// Run loops in parallel when timing suggests it is worthwhile
+    #[default]
+    Auto,
+    /// Run loops in parallel in fixed-size chunks
+    ///
+    /// Whether a loop is parallelized and how its iterations are grouped
+    /// do not depend on timing or the number of threads, so results
+    /// and errors are always identical to serial execution
+    Deterministic,
+}
+
+impl fmt::Display for Parallelism {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parallelism::Off => write!(f, "off"),
+            Parallelism::Auto => write!(f, "auto"),
+            Parallelism::Deterministic => write!(f, "deterministic"),
+        }
+    }
+}
+
+impl FromStr for Parallelism {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Parallelism::Off),
+            "auto" => Ok(Parallelism::Auto),
+            "deterministic" => Ok(Parallelism::Deterministic),
+            _ => Err(format!("unknown parallelism `{}`", s)),
+        }
+    }
+}
+
 impl Default for Runtime {
     fn default() -> Self {
         Runtime {
@@ -195,6 +288,9 @@ impl Default for Runtime {
                 ..Default::default()
             }],
             local_stack: EcoVec::new(),
+            stream_stack: Vec::new(),
+            handler_stack: Vec::new(),
+            contracts: true,
             recur_stack: Vec::new(),
             fill_stack: Vec::new(),
             fill_boundary_stack: Vec::new(),
@@ -202,11 +298,18 @@ impl Default for Runtime {
             array_depth: 0,
             backend: Arc::new(SafeSys::default()),
             time_instrs: false,
+            trace: None,
+            trace_thread: 0,
+            rng: RNG.with_borrow_mut(|rng| SmallRng::seed_from_u64(rng.gen())),
+            locale: Locale::default(),
+            hardened: false,
+            catching_crashes: false,
             last_time: 0.0,
             cli_arguments: Vec::new(),
             cli_file_path: PathBuf::new(),
             execution_limit: None,
             execution_start: 0.0,
+            fuel: None,
             #[cfg(debug_assertions)]
             recursion_limit: 20,
             #[cfg(not(debug_assertions))]
@@ -214,13 +317,22 @@ impl Default for Runtime {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(100),
+            parallelism: Parallelism::default(),
+            tolerance: 0.0,
+            match_tolerance: false,
+            audio_sample_rate: None,
+            http_timeout: None,
+            sys_permissions: SysPermissions::ALL,
             interrupted: None,
             thread: ThisThread::default(),
             output_comments: HashMap::new(),
-            memo: Arc::new(ThreadLocal::new()),
+            memo: Arc::new(MemoCache::default()),
+            cells: Arc::default(),
             unevaluated_constants: HashMap::new(),
             test_results: Vec::new(),
             reports: Vec::new(),
+            #[cfg(feature = "jit")]
+            jit: Default::default(),
         }
     }
 }
@@ -284,6 +396,66 @@ impl Uiua {
         self.rt.time_instrs = time_instrs;
         self
     }
+    /// Set whether to record an execution trace
+    ///
+    /// The trace records spans of primitive and function execution.
+    /// It can be retrieved with [`Uiua::trace`].
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.rt.trace = trace.then(|| Arc::new(ExecutionTrace::new(self.rt.backend.now())));
+        self
+    }
+    /// Get the execution trace, if one is being recorded
+    pub fn trace(&self) -> Option<&ExecutionTrace> {
+        self.rt.trace.as_deref()
+    }
+    /// Seed the random number generator
+    ///
+    /// By default, the generator is seeded from the thread's generator, which can be seeded with [`seed_random`](crate::seed_random).
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rt.rng = SmallRng::seed_from_u64(seed);
+        self
+    }
+    /// Set the locale that error messages are shown in
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.rt.locale = locale;
+        self
+    }
+    /// Get the locale that error messages are shown in
+    pub fn locale(&self) -> Locale {
+        self.rt.locale
+    }
+    /// Set whether to catch interpreter crashes in every entry point
+    ///
+    /// Running code with [`Uiua::run_str`] and the other `run_*` methods always catches crashes.
+    /// In hardened mode, [`Uiua::call`] catches them as well, and the runtime's stacks are cleared after a crash.
+    /// Only the outermost call catches crashes, so calls made while code is running leave the stacks alone.
+    /// A caught crash is returned as an error for which [`UiuaError::is_bug`] is true.
+    /// Internal consistency checks that would panic in debug builds also return such errors instead.
+    /// Typed accessors like [`Value::as_nums`] already return errors for the wrong type.
+    ///
+    /// This is useful when embedding the interpreter in a long-lived process.
+    ///
+    /// Some failures still end the process:
+    /// - Crashes cannot be caught if the program is built with `panic = "abort"`
+    /// - Stack overflows abort the process. The recursion limit usually prevents them,
+    ///   but deep recursion in native code can still overflow a small thread stack.
+    /// - Failing to allocate memory aborts the process. Arrays that are obviously too large
+    ///   are rejected with an error, but many smaller arrays can still exhaust memory.
+    pub fn hardened(mut self, hardened: bool) -> Self {
+        self.rt.hardened = hardened;
+        self
+    }
+    /// Set whether the conditions of [`Primitive::Requires`] and [`Primitive::Ensures`] are checked
+    ///
+    /// Default is `true`. Disabling contracts skips their conditions, which is useful for release runs.
+    pub fn with_contracts(mut self, contracts: bool) -> Self {
+        self.rt.contracts = contracts;
+        self
+    }
+    /// Get whether contract conditions are checked
+    pub fn contracts(&self) -> bool {
+        self.rt.contracts
+    }
     /// Limit the execution duration
     pub fn with_execution_limit(mut self, limit: Duration) -> Self {
         self.rt.execution_limit = Some(limit.as_secs_f64());
@@ -294,6 +466,27 @@ impl Uiua {
         self.rt.execution_limit = limit.map(|limit| limit.as_secs_f64());
         self
     }
+    /// Limit the number of instructions that can be executed
+    ///
+    /// Each executed instruction uses one unit of fuel.
+    /// Execution fails once the fuel runs out.
+    /// Threads spawned by the program share the same fuel.
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.set_fuel(Some(fuel));
+        self
+    }
+    /// Set the remaining fuel
+    ///
+    /// `None` removes the limit
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.rt.fuel = fuel.map(|fuel| Arc::new(AtomicU64::new(fuel)));
+    }
+    /// Get the remaining fuel
+    ///
+    /// Returns `None` if there is no fuel limit
+    pub fn fuel(&self) -> Option<u64> {
+        (self.rt.fuel.as_ref()).map(|fuel| fuel.load(atomic::Ordering::Relaxed))
+    }
     /// Set the recursion limit
     ///
     /// Default is 100 for release builds and 20 for debug builds
@@ -301,6 +494,73 @@ impl Uiua {
         self.rt.recursion_limit = limit;
         self
     }
+    /// Set how loops may be run in parallel
+    ///
+    /// Default is [`Parallelism::Auto`]
+    pub fn with_parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.rt.parallelism = parallelism;
+        self
+    }
+    /// Get how loops may be run in parallel
+    pub fn parallelism(&self) -> Parallelism {
+        self.rt.parallelism
+    }
+    /// Set the tolerance for numbers to be considered equal
+    ///
+    /// Numbers that differ by at most this amount are considered equal when checking if a converging [`Primitive::Repeat`] has converged.
+    ///
+    /// Default is `0`, which requires numbers to be exactly equal
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.rt.tolerance = tolerance;
+        self
+    }
+    /// Get the tolerance for numbers to be considered equal
+    pub fn tolerance(&self) -> f64 {
+        self.rt.tolerance
+    }
+    /// Set whether [`Primitive::Match`] uses the [tolerance](Uiua::with_tolerance)
+    ///
+    /// Default is `false`
+    pub fn with_match_tolerance(mut self, match_tolerance: bool) -> Self {
+        self.rt.match_tolerance = match_tolerance;
+        self
+    }
+    /// Set the audio sample rate
+    ///
+    /// This is the rate returned by `&asr` and used by `&ap`.
+    ///
+    /// Default is the backend's sample rate
+    pub fn with_audio_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.rt.audio_sample_rate = Some(sample_rate);
+        self
+    }
+    /// Get the audio sample rate
+    pub fn audio_sample_rate(&self) -> u32 {
+        (self.rt.audio_sample_rate).unwrap_or_else(|| self.rt.backend.audio_sample_rate())
+    }
+    /// Set the timeout for HTTP requests made with `&http`
+    ///
+    /// Default is no timeout
+    pub fn with_http_timeout(mut self, timeout: Duration) -> Self {
+        self.rt.http_timeout = Some(timeout);
+        self
+    }
+    /// Get the timeout for HTTP requests
+    pub fn http_timeout(&self) -> Option<Duration> {
+        self.rt.http_timeout
+    }
+    /// Set which classes of system functions are permitted
+    ///
+    /// Calling a system function that is not permitted is an error.
+    /// Default is to permit all system functions.
+    pub fn with_sys_permissions(mut self, permissions: SysPermissions) -> Self {
+        self.rt.sys_permissions = permissions;
+        self
+    }
+    /// Get which classes of system functions are permitted
+    pub fn sys_permissions(&self) -> SysPermissions {
+        self.rt.sys_permissions
+    }
     /// Set the interrupted hook
     #[cfg(not(target_arch = "wasm32"))]
     pub fn with_interrupt_hook(mut self, hook: impl Fn() -> bool + Send + Sync + 'static) -> Self {
@@ -341,6 +601,7 @@ impl Uiua {
         compile: impl FnOnce(&mut Compiler) -> UiuaResult<&mut Compiler>,
     ) -> UiuaResult<Compiler> {
         let mut comp = Compiler::with_backend(self.rt.backend.clone());
+        comp.locale(self.rt.locale);
         let asm = compile(&mut comp)?.finish();
         self.run_asm(asm)?;
         comp.set_backend(SafeSys::default());
@@ -401,7 +662,18 @@ impl Uiua {
                 env.rt = Runtime {
                     backend: env.rt.backend.clone(),
                     execution_limit: env.rt.execution_limit,
+                    fuel: env.rt.fuel.clone(),
+                    parallelism: env.rt.parallelism,
+                    tolerance: env.rt.tolerance,
+                    match_tolerance: env.rt.match_tolerance,
+                    audio_sample_rate: env.rt.audio_sample_rate,
+                    http_timeout: env.rt.http_timeout,
+                    sys_permissions: env.rt.sys_permissions,
+                    memo: env.rt.memo.clone(),
+                    cells: env.rt.cells.clone(),
                     time_instrs: env.rt.time_instrs,
+                    trace: env.rt.trace.clone(),
+                    hardened: env.rt.hardened,
                     output_comments: take(&mut env.rt.output_comments),
                     reports: take(&mut env.rt.reports),
                     stack: take(&mut env.rt.stack),
@@ -412,11 +684,63 @@ impl Uiua {
         }
         run_asm(self, asm)
     }
+    /// Run a Uiua assembly that is shared with other environments
+    ///
+    /// An assembly's code and constant arrays are reference counted, so they are shared rather than copied.
+    /// This makes it cheap to run the same compiled program in many environments at once, such as when handling concurrent requests in a server.
+    pub fn run_shared_asm(&mut self, asm: &Arc<Assembly>) -> UiuaResult {
+        self.run_asm(Assembly::clone(asm))
+    }
+    /// Run a Uiua assembly once for each set of input values
+    ///
+    /// Each set of inputs is pushed onto an empty stack as with [`Uiua::push_all`], and the stack left by the run is returned as with [`Uiua::take_stack`].
+    ///
+    /// This is faster than calling [`Uiua::run_asm`] for each set of inputs, as the runtime's stacks, memoization cache, and other state are reused between runs.
+    /// An error in one run does not affect the others.
+    pub fn run_batch<I, V>(&mut self, asm: Assembly, inputs: I) -> Vec<UiuaResult<Vec<Value>>>
+    where
+        I: IntoIterator,
+        I::Item: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.asm = asm;
+        let root = self.asm.root.clone();
+        let mut outputs = Vec::new();
+        for input in inputs {
+            self.reset_stacks();
+            self.push_all(input);
+            self.rt.execution_start = self.rt.backend.now();
+            let res = (self.catching_crash(|env| env.exec(root.clone()))).unwrap_or_else(Err);
+            self.rt.test_results.clear();
+            outputs.push(res.map(|()| self.rt.stack.drain(..).collect()));
+        }
+        self.reset_stacks();
+        outputs
+    }
+    /// Clear the runtime's stacks without freeing their memory
+    fn reset_stacks(&mut self) {
+        let rt = &mut self.rt;
+        rt.stack.clear();
+        rt.under_stack.clear();
+        rt.call_stack.truncate(1);
+        rt.local_stack.clear();
+        rt.stream_stack.clear();
+        rt.handler_stack.clear();
+        rt.recur_stack.clear();
+        rt.fill_stack.clear();
+        rt.unfill_stack.clear();
+        rt.fill_boundary_stack.clear();
+        rt.array_depth = 0;
+    }
     fn catching_crash<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> UiuaResult<T> {
-        match catch_unwind(AssertUnwindSafe(|| f(self))) {
+        let was_catching = replace(&mut self.rt.catching_crashes, true);
+        let res = catch_unwind(AssertUnwindSafe(|| f(self)));
+        self.rt.catching_crashes = was_catching;
+        match res {
             Ok(res) => Ok(res),
-            Err(_) => Err(self.error(format!(
-                "\
+            Err(_) => Err(self
+                .error(format!(
+                    "\
 The interpreter has crashed!
 Hooray! You found a bug!
 Please report this at http://github.com/uiua-lang/uiua/issues/new \
@@ -425,8 +749,9 @@ or on Discord at https://discord.gg/9CU2ME4kmn.
 Uiua version {VERSION}
 
 at {}",
-                self.span()
-            ))),
+                    self.span()
+                ))
+                .bug()),
         }
     }
 }
@@ -484,6 +809,17 @@ impl Uiua {
             formatted_node = format!("{node:?}");
             self.rt.last_time = self.rt.backend.now();
         }
+        if let Some(fuel) = &self.rt.fuel {
+            let used =
+                fuel.fetch_update(atomic::Ordering::Relaxed, atomic::Ordering::Relaxed, |f| {
+                    f.checked_sub(1)
+                });
+            if used.is_err() {
+                return Err(
+                    UiuaErrorKind::OutOfFuel(self.span(), self.inputs().clone().into()).into(),
+                );
+            }
+        }
         let res = match node {
             Node::Run(nodes) => nodes.into_iter().try_for_each(|node| self.exec(node)),
             Node::Prim(prim, span) => self.with_prim_span(span, Some(prim), |env| prim.run(env)),
@@ -613,6 +949,12 @@ impl Uiua {
             Node::RemoveLabel(_, span) => {
                 self.with_span(span, |env| env.monadic_mut(|val| val.set_label(None)))
             }
+            Node::CheckTypes(check, span) => self.with_span(span, |env| check.check(env)),
+            Node::DispatchTypes(dispatch, span) => self.with_span(span, |env| {
+                let i = dispatch.dispatch(env)?;
+                env.push(i);
+                Ok(())
+            }),
             Node::ValidateType {
                 index,
                 name,
@@ -823,14 +1165,43 @@ impl Uiua {
             .unwrap()
             .spans
             .push((span, prim));
-        let res = f(self);
+        let res = match prim {
+            Some(prim) => self.traced(|| prim.name().into(), "primitive", f),
+            None => f(self),
+        };
         self.rt.call_stack.last_mut().unwrap().spans.pop();
         res
     }
+    /// Record a span in the execution trace if one is being recorded
+    #[inline]
+    fn traced<T>(
+        &mut self,
+        name: impl FnOnce() -> String,
+        category: &'static str,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let Some(trace) = self.rt.trace.clone() else {
+            return f(self);
+        };
+        let start = self.rt.backend.now();
+        let res = f(self);
+        let end = self.rt.backend.now();
+        trace.record(name(), category, self.rt.trace_thread, start, end);
+        res
+    }
     /// Call a function
     #[inline]
     pub fn call(&mut self, f: &Function) -> UiuaResult {
         let call_span = self.span_index();
+        // Calls made while code is already running are caught by the outermost entry point
+        if self.rt.hardened && !self.rt.catching_crashes {
+            let res =
+                (self.catching_crash(|env| env.call_with_span(f, call_span))).unwrap_or_else(Err);
+            if res.as_ref().is_err_and(UiuaError::is_bug) {
+                self.reset_stacks();
+            }
+            return res;
+        }
         self.call_with_span(f, call_span)
     }
     /// Call and truncate the stack to before the args were pushed if the call fails
@@ -880,19 +1251,29 @@ impl Uiua {
         res
     }
     fn call_with_span(&mut self, f: &Function, call_span: usize) -> UiuaResult {
-        self.without_fill(|env| {
-            env.exec_with_frame_span(
-                env.asm[f].clone(),
-                StackFrame {
-                    sig: f.sig,
-                    id: Some(f.id.clone()),
-                    call_span,
-                    start_height: env.stack_height(),
-                    ..Default::default()
-                },
-                call_span,
-            )
-        })
+        #[cfg(feature = "jit")]
+        if crate::jit::try_call(self, f) {
+            return Ok(());
+        }
+        self.traced(
+            || f.id.to_string(),
+            "function",
+            |env| {
+                env.without_fill(|env| {
+                    env.exec_with_frame_span(
+                        env.asm[f].clone(),
+                        StackFrame {
+                            sig: f.sig,
+                            id: Some(f.id.clone()),
+                            call_span,
+                            start_height: env.stack_height(),
+                            ..Default::default()
+                        },
+                        call_span,
+                    )
+                })
+            },
+        )
     }
     fn exec_with_span(&mut self, sn: SigNode, call_span: usize) -> UiuaResult {
         self.exec_with_frame_span(
@@ -934,10 +1315,11 @@ impl Uiua {
                 "Function modified the stack by {height_diff} values, but its \
                 signature of {sig} implies a change of {sig_diff}"
             );
-            #[cfg(debug_assertions)]
-            panic!("{message}");
-            #[cfg(not(debug_assertions))]
-            return Err(self.error_with_span(self.asm.spans[_call_span].clone(), message));
+            if cfg!(debug_assertions) && !self.rt.hardened {
+                panic!("{message}");
+            }
+            let span = self.asm.spans[_call_span].clone();
+            return Err(self.error_with_span(span, message).bug());
         }
         Ok(())
     }
@@ -973,6 +1355,11 @@ impl Uiua {
         }
         .into()
     }
+    /// Construct an error with a localized message
+    pub(crate) fn message_error(&self, id: MessageId, args: &[&dyn fmt::Display]) -> UiuaError {
+        self.error(id.format(self.rt.locale, args))
+            .with_message_id(id)
+    }
     /// Construct an error with a custom span
     pub fn error_with_span(&self, span: Span, message: impl ToString) -> UiuaError {
         UiuaErrorKind::Run {
@@ -996,10 +1383,10 @@ impl Uiua {
     }
     /// Pop a value from the stack
     pub fn pop(&mut self, arg: impl StackArg) -> UiuaResult<Value> {
-        self.rt
-            .stack
-            .pop()
-            .ok_or_else(|| self.error(format!("Stack was empty when getting {}", arg.arg_name())))
+        self.rt.stack.pop().ok_or_else(|| {
+            let arg = arg.localized_arg_name(self.rt.locale);
+            self.message_error(MessageId::StackEmpty, &[&arg])
+        })
     }
     /// Pop a value and try to convert it
     pub fn pop_convert<T>(
@@ -1190,10 +1577,8 @@ impl Uiua {
     }
     pub(crate) fn require_height(&self, n: usize) -> UiuaResult<usize> {
         if self.rt.stack.len() < n {
-            return Err(self.error(format!(
-                "Stack was empty when getting argument {}",
-                self.rt.stack.len() + 1
-            )));
+            let arg = (self.rt.stack.len() + 1).localized_arg_name(self.rt.locale);
+            return Err(self.message_error(MessageId::StackEmpty, &[&arg]));
         }
         Ok(self.rt.stack.len() - n)
     }
@@ -1277,12 +1662,6 @@ impl Uiua {
         self.push(a);
         Ok(())
     }
-    pub(crate) fn dyadic_rr<V: Into<Value>>(&mut self, f: fn(&Value, &Value) -> V) -> UiuaResult {
-        let a = self.pop(1)?;
-        let b = self.pop(2)?;
-        self.push(f(&a, &b));
-        Ok(())
-    }
     pub(crate) fn dyadic_oo_env<V: Into<Value>>(
         &mut self,
         f: fn(Value, Value, &Self) -> UiuaResult<V>,
@@ -1341,27 +1720,33 @@ impl Uiua {
         Ok(self.rt.stack.split_off(len - n))
     }
     pub(crate) fn value_fill(&self) -> Option<&Value> {
+        self.value_fills()?.first()
+    }
+    pub(crate) fn value_fills(&self) -> Option<&[Value]> {
         if (self.rt.fill_boundary_stack.last()).is_some_and(|&(i, _)| i >= self.rt.fill_stack.len())
         {
             None
         } else {
-            self.last_fill()
+            self.last_fills()
         }
     }
-    pub(crate) fn value_unfill(&self) -> Option<&Value> {
+    pub(crate) fn value_unfills(&self) -> Option<&[Value]> {
         if (self.rt.fill_boundary_stack.last())
             .is_some_and(|&(_, i)| i >= self.rt.unfill_stack.len())
         {
             None
         } else {
-            self.last_unfill()
+            self.last_unfills()
         }
     }
     pub(crate) fn last_fill(&self) -> Option<&Value> {
-        self.rt.fill_stack.last()
+        self.last_fills()?.first()
     }
-    pub(crate) fn last_unfill(&self) -> Option<&Value> {
-        self.rt.unfill_stack.last()
+    pub(crate) fn last_fills(&self) -> Option<&[Value]> {
+        self.rt.fill_stack.last().map(|fills| fills.as_slice())
+    }
+    pub(crate) fn last_unfills(&self) -> Option<&[Value]> {
+        self.rt.unfill_stack.last().map(|fills| fills.as_slice())
     }
     pub(crate) fn fill(&self) -> Fill {
         Fill::new(self)
@@ -1369,28 +1754,64 @@ impl Uiua {
     pub(crate) fn unfill(&self) -> Fill {
         Fill::new_un(self)
     }
-    /// Do something with the fill context set
-    pub(crate) fn with_fill<T>(
+    /// Do something with a fill value set
+    ///
+    /// This is equivalent to using [`Primitive::Fill`] in Uiua code
+    pub fn with_fill<T>(
         &mut self,
         value: Value,
         in_ctx: impl FnOnce(&mut Self) -> UiuaResult<T>,
     ) -> UiuaResult<T> {
-        self.rt.fill_stack.push(value);
+        self.rt.fill_stack.push(eco_vec![value]);
+        let res = in_ctx(self);
+        self.rt.fill_stack.pop();
+        res
+    }
+    /// Do something with fill values of different types set
+    ///
+    /// Each operation that uses a fill will use the value that matches the type of array it is working on.
+    /// The first value is used by operations that are not specific to a type.
+    ///
+    /// Returns an error if two values have the same type
+    pub fn with_fills<T>(
+        &mut self,
+        values: impl IntoIterator<Item = Value>,
+        in_ctx: impl FnOnce(&mut Self) -> UiuaResult<T>,
+    ) -> UiuaResult<T> {
+        let fills = self.fill_group(values)?;
+        self.rt.fill_stack.push(fills);
         let res = in_ctx(self);
         self.rt.fill_stack.pop();
         res
     }
     /// Do something with the unfill context set
-    pub(crate) fn with_unfill<T>(
+    pub(crate) fn with_unfills<T>(
         &mut self,
-        value: Value,
+        values: impl IntoIterator<Item = Value>,
         in_ctx: impl FnOnce(&mut Self) -> UiuaResult<T>,
     ) -> UiuaResult<T> {
-        self.rt.unfill_stack.push(value);
+        let fills = self.fill_group(values)?;
+        self.rt.unfill_stack.push(fills);
         let res = in_ctx(self);
         self.rt.unfill_stack.pop();
         res
     }
+    fn fill_group(&self, values: impl IntoIterator<Item = Value>) -> UiuaResult<EcoVec<Value>> {
+        let fills: EcoVec<Value> = values.into_iter().collect();
+        for (i, a) in fills.iter().enumerate() {
+            if let Some(b) = fills[..i]
+                .iter()
+                .find(|b| FillKind::of(b) == FillKind::of(a))
+            {
+                return Err(self.error(format!(
+                    "Fill values must have different types, \
+                    but there are two {} fills",
+                    FillKind::of(b)
+                )));
+            }
+        }
+        Ok(fills)
+    }
     /// Do something with the top fill context unset
     pub(crate) fn without_fill<T>(&mut self, in_ctx: impl FnOnce(&mut Self) -> T) -> T {
         self.rt
@@ -1406,7 +1827,10 @@ impl Uiua {
         but: impl FnOnce(&mut Self) -> UiuaResult,
         in_ctx: impl FnOnce(&mut Self) -> UiuaResult,
     ) -> UiuaResult {
-        let fills = self.rt.fill_stack[self.rt.fill_stack.len().max(n) - n..].to_vec();
+        let fills: Vec<Value> = self.rt.fill_stack[self.rt.fill_stack.len().max(n) - n..]
+            .iter()
+            .filter_map(|fills| fills.first().cloned())
+            .collect();
         if fills.len() < n {
             for _ in 0..n - fills.len() {
                 self.push(Value::default());
@@ -1424,7 +1848,10 @@ impl Uiua {
         but: impl FnOnce(&mut Self) -> UiuaResult,
         in_ctx: impl FnOnce(&mut Self) -> UiuaResult,
     ) -> UiuaResult {
-        let fills = self.rt.unfill_stack[self.rt.unfill_stack.len().max(n) - n..].to_vec();
+        let fills: Vec<Value> = self.rt.unfill_stack[self.rt.unfill_stack.len().max(n) - n..]
+            .iter()
+            .filter_map(|fills| fills.first().cloned())
+            .collect();
         if fills.len() < n {
             for _ in 0..n - fills.len() {
                 self.push(Value::default());
@@ -1441,18 +1868,21 @@ impl Uiua {
     }
     pub(crate) fn respect_recursion_limit(&mut self) -> UiuaResult {
         if self.rt.call_stack.len() > self.rt.recursion_limit {
-            Err(
-                self.error(if cfg!(target_arch = "wasm32") || cfg!(debug_assertions) {
-                    "Recursion limit reached".into()
-                } else {
-                    format!(
-                        "Recursion limit reached. \
-                        You can try setting UIUA_RECURSION_LIMIT to a higher value. \
-                        The current limit is {}.",
-                        self.rt.recursion_limit
-                    )
-                }),
-            )
+            let mut message = MessageId::RecursionLimit.format(self.rt.locale, &[]);
+            if !(cfg!(target_arch = "wasm32") || cfg!(debug_assertions))
+                && self.rt.locale == Locale::English
+            {
+                message = format!(
+                    "{message}. \
+                    You can try setting UIUA_RECURSION_LIMIT to a higher value. \
+                    The current limit is {}.",
+                    self.rt.recursion_limit
+                );
+            }
+            Err(self
+                .error(message)
+                .with_message_id(MessageId::RecursionLimit)
+                .limit())
         } else {
             Ok(())
         }
@@ -1460,7 +1890,9 @@ impl Uiua {
     /// Spawn a thread
     pub(crate) fn spawn(&mut self, capture_count: usize, _pool: bool, f: SigNode) -> UiuaResult {
         if !self.rt.backend.allow_thread_spawning() {
-            return Err(self.error("Thread spawning is not allowed in this environment"));
+            return Err(self
+                .error("Thread spawning is not allowed in this environment")
+                .system());
         }
         if self.rt.stack.len() < capture_count {
             return Err(self.error(format!(
@@ -1478,51 +1910,24 @@ impl Uiua {
             }),
             ..ThisThread::default()
         };
-        let mut env = Uiua {
-            asm: self.asm.clone(),
-            rt: Runtime {
-                stack: (self.rt.stack)
-                    .drain(self.rt.stack.len() - capture_count..)
-                    .collect(),
-                under_stack: Vec::new(),
-                local_stack: self.rt.local_stack.clone(),
-                fill_stack: Vec::new(),
-                fill_boundary_stack: Vec::new(),
-                unfill_stack: Vec::new(),
-                recur_stack: self.rt.recur_stack.clone(),
-                call_stack: Vec::from_iter(self.rt.call_stack.last().cloned()),
-                array_depth: 0,
-                time_instrs: self.rt.time_instrs,
-                last_time: self.rt.last_time,
-                cli_arguments: self.rt.cli_arguments.clone(),
-                cli_file_path: self.rt.cli_file_path.clone(),
-                backend: self.rt.backend.clone(),
-                execution_limit: self.rt.execution_limit,
-                execution_start: self.rt.execution_start,
-                recursion_limit: self.rt.recursion_limit,
-                interrupted: self.rt.interrupted.clone(),
-                output_comments: HashMap::new(),
-                memo: self.rt.memo.clone(),
-                unevaluated_constants: HashMap::new(),
-                test_results: Vec::new(),
-                reports: Vec::new(),
-                thread,
-            },
-        };
+        let stack = (self.rt.stack)
+            .drain(self.rt.stack.len() - capture_count..)
+            .collect();
+        let mut env = self.thread_env(stack, thread);
         #[cfg(not(target_arch = "wasm32"))]
         let recv = {
             let (send, recv) = crossbeam_channel::unbounded();
             if _pool {
-                rayon::spawn(move || _ = send.send(env.exec(f).map(|_| env.take_stack())));
+                rayon::spawn(move || _ = send.send(env.exec_thread(f)));
             } else {
                 std::thread::Builder::new()
-                    .spawn(move || _ = send.send(env.exec(f).map(|_| env.take_stack())))
+                    .spawn(move || _ = send.send(env.exec_thread(f)))
                     .map_err(|e| self.error(format!("Error spawning thread: {e}")))?;
             }
             recv
         };
         #[cfg(target_arch = "wasm32")]
-        let result = env.exec(f).map(|_| env.take_stack());
+        let result = env.exec_thread(f);
 
         let id = self.rt.thread.next_child_id;
         self.rt.thread.next_child_id += 1;
@@ -1542,6 +1947,188 @@ impl Uiua {
         self.push(id);
         Ok(())
     }
+    /// Run functions in child threads and wait for all of them
+    ///
+    /// Each function gets copies of the arguments it needs, as with fork.
+    /// If any function fails, the others are interrupted and the first error is returned.
+    pub(crate) fn scope(&mut self, fs: EcoVec<SigNode>) -> UiuaResult {
+        if !self.rt.backend.allow_thread_spawning() {
+            return Err(self
+                .error("Thread spawning is not allowed in this environment")
+                .system());
+        }
+        let arg_count = fs.iter().map(|f| f.sig.args).max().unwrap_or(0);
+        let args = self.pop_n(arg_count)?;
+        let mut envs: Vec<Uiua> = (fs.iter())
+            .map(|f| {
+                let stack = args[arg_count - f.sig.args..].to_vec();
+                self.thread_env(stack, ThisThread::default())
+            })
+            .collect();
+        #[cfg(not(target_arch = "wasm32"))]
+        let results = {
+            use std::sync::atomic::{AtomicBool, Ordering};
+            let cancelled = Arc::new(AtomicBool::new(false));
+            for env in &mut envs {
+                let cancelled = cancelled.clone();
+                let parent = env.rt.interrupted.take();
+                env.rt.interrupted = Some(Arc::new(move || {
+                    cancelled.load(Ordering::Relaxed) || parent.as_ref().is_some_and(|f| f())
+                }));
+            }
+            let (send, recv) = crossbeam_channel::unbounded();
+            std::thread::scope(|scope| {
+                for (i, (mut env, f)) in envs.into_iter().zip(fs.iter().cloned()).enumerate() {
+                    let send = send.clone();
+                    std::thread::Builder::new()
+                        .spawn_scoped(scope, move || {
+                            _ = send.send((i, env.exec(f).map(|_| env.take_stack())))
+                        })
+                        .map_err(|e| self.error(format!("Error spawning thread: {e}")))?;
+                }
+                drop(send);
+                let mut results = vec![Vec::new(); fs.len()];
+                let mut error = None;
+                for (i, res) in recv {
+                    match res {
+                        Ok(stack) => results[i] = stack,
+                        Err(e) => {
+                            if error.is_none() {
+                                cancelled.store(true, Ordering::Relaxed);
+                                error = Some(e);
+                            }
+                        }
+                    }
+                }
+                error.map_or(Ok(results), Err)
+            })?
+        };
+        #[cfg(target_arch = "wasm32")]
+        let results = (envs.iter_mut().zip(fs.iter().cloned()))
+            .map(|(env, f)| env.exec(f).map(|_| env.take_stack()))
+            .collect::<UiuaResult<Vec<_>>>()?;
+        for stack in results.into_iter().rev() {
+            self.push_all(stack);
+        }
+        Ok(())
+    }
+    /// Run a thread's function and take its stack
+    ///
+    /// Crashes are caught here because a panic in a thread pool job would abort the process
+    fn exec_thread(&mut self, f: SigNode) -> UiuaResult<Vec<Value>> {
+        (self.catching_crash(|env| env.exec(f)))
+            .unwrap_or_else(Err)
+            .map(|()| self.take_stack())
+    }
+    /// Split off an independent random number generator for a child environment
+    fn split_rng(&mut self) -> SmallRng {
+        SmallRng::seed_from_u64(self.rt.rng.gen())
+    }
+    /// Create an environment for running a function in a child thread
+    fn thread_env(&mut self, stack: Vec<Value>, thread: ThisThread) -> Self {
+        Uiua {
+            asm: self.asm.clone(),
+            rt: Runtime {
+                stack,
+                under_stack: Vec::new(),
+                local_stack: self.rt.local_stack.clone(),
+                stream_stack: Vec::new(),
+                handler_stack: self.rt.handler_stack.clone(),
+                contracts: self.rt.contracts,
+                fill_stack: Vec::new(),
+                fill_boundary_stack: Vec::new(),
+                unfill_stack: Vec::new(),
+                recur_stack: self.rt.recur_stack.clone(),
+                call_stack: Vec::from_iter(self.rt.call_stack.last().cloned()),
+                array_depth: 0,
+                time_instrs: self.rt.time_instrs,
+                trace: self.rt.trace.clone(),
+                trace_thread: (self.rt.trace.as_ref()).map_or(0, |trace| trace.new_thread()),
+                rng: self.split_rng(),
+                locale: self.rt.locale,
+                hardened: self.rt.hardened,
+                catching_crashes: false,
+                last_time: self.rt.last_time,
+                cli_arguments: self.rt.cli_arguments.clone(),
+                cli_file_path: self.rt.cli_file_path.clone(),
+                backend: self.rt.backend.clone(),
+                execution_limit: self.rt.execution_limit,
+                execution_start: self.rt.execution_start,
+                fuel: self.rt.fuel.clone(),
+                recursion_limit: self.rt.recursion_limit,
+                parallelism: self.rt.parallelism,
+                tolerance: self.rt.tolerance,
+                match_tolerance: self.rt.match_tolerance,
+                audio_sample_rate: self.rt.audio_sample_rate,
+                http_timeout: self.rt.http_timeout,
+                sys_permissions: self.rt.sys_permissions,
+                interrupted: self.rt.interrupted.clone(),
+                output_comments: HashMap::new(),
+                memo: self.rt.memo.clone(),
+                cells: self.rt.cells.clone(),
+                unevaluated_constants: HashMap::new(),
+                test_results: Vec::new(),
+                reports: Vec::new(),
+                thread,
+                #[cfg(feature = "jit")]
+                jit: self.rt.jit.clone(),
+            },
+        }
+    }
+    /// Create an environment for running part of a parallel loop
+    ///
+    /// The worker shares the current call, local, and fill contexts, but has its own stacks
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn parallel_worker(&self) -> Self {
+        Uiua {
+            asm: self.asm.clone(),
+            rt: Runtime {
+                stack: Vec::new(),
+                under_stack: Vec::new(),
+                local_stack: self.rt.local_stack.clone(),
+                stream_stack: Vec::new(),
+                handler_stack: self.rt.handler_stack.clone(),
+                contracts: self.rt.contracts,
+                fill_stack: self.rt.fill_stack.clone(),
+                fill_boundary_stack: self.rt.fill_boundary_stack.clone(),
+                unfill_stack: self.rt.unfill_stack.clone(),
+                recur_stack: self.rt.recur_stack.clone(),
+                call_stack: self.rt.call_stack.clone(),
+                array_depth: self.rt.array_depth,
+                time_instrs: false,
+                trace: self.rt.trace.clone(),
+                trace_thread: (self.rt.trace.as_ref()).map_or(0, |trace| trace.new_thread()),
+                rng: self.rt.rng.clone(),
+                locale: self.rt.locale,
+                hardened: self.rt.hardened,
+                catching_crashes: self.rt.catching_crashes,
+                last_time: self.rt.last_time,
+                cli_arguments: self.rt.cli_arguments.clone(),
+                cli_file_path: self.rt.cli_file_path.clone(),
+                backend: self.rt.backend.clone(),
+                execution_limit: self.rt.execution_limit,
+                execution_start: self.rt.execution_start,
+                fuel: self.rt.fuel.clone(),
+                recursion_limit: self.rt.recursion_limit,
+                parallelism: self.rt.parallelism,
+                tolerance: self.rt.tolerance,
+                match_tolerance: self.rt.match_tolerance,
+                audio_sample_rate: self.rt.audio_sample_rate,
+                http_timeout: self.rt.http_timeout,
+                sys_permissions: self.rt.sys_permissions,
+                interrupted: self.rt.interrupted.clone(),
+                output_comments: HashMap::new(),
+                memo: self.rt.memo.clone(),
+                cells: self.rt.cells.clone(),
+                unevaluated_constants: self.rt.unevaluated_constants.clone(),
+                test_results: Vec::new(),
+                reports: Vec::new(),
+                thread: ThisThread::default(),
+                #[cfg(feature = "jit")]
+                jit: self.rt.jit.clone(),
+            },
+        }
+    }
     /// Wait for a thread to finish
     pub(crate) fn wait(&mut self, id: Value) -> UiuaResult {
         let ids = id.as_natural_array(self, "Thread id must be an array of natural numbers")?;
@@ -1612,7 +2199,9 @@ impl Uiua {
     }
     pub(crate) fn send(&self, id: Value, value: Value) -> UiuaResult {
         if cfg!(target_arch = "wasm32") {
-            return Err(self.error("send is not supported in this environment"));
+            return Err(self
+                .error("send is not supported in this environment")
+                .system());
         }
         let ids = id.as_natural_array(self, "Thread id must be an array of natural numbers")?;
         for id in ids.data {
@@ -1625,7 +2214,9 @@ impl Uiua {
     }
     pub(crate) fn recv(&mut self, id: Value) -> UiuaResult {
         if cfg!(target_arch = "wasm32") {
-            return Err(self.error("recv is not supported in this environment"));
+            return Err(self
+                .error("recv is not supported in this environment")
+                .system());
         }
         let ids = id.as_natural_array(self, "Thread id must be an array of natural numbers")?;
         let mut values = Vec::with_capacity(ids.data.len());
@@ -1647,7 +2238,9 @@ impl Uiua {
     }
     pub(crate) fn try_recv(&mut self, id: Value) -> UiuaResult {
         if cfg!(target_arch = "wasm32") {
-            return Err(self.error("try_recv is not supported in this environment"));
+            return Err(self
+                .error("try_recv is not supported in this environment")
+                .system());
         }
         let id = id.as_nat(self, "Thread id must be a natural number")?;
         let value = match self.channel(id)?.recv.try_recv() {
@@ -1664,6 +2257,46 @@ impl Uiua {
         self.push(value);
         Ok(())
     }
+    pub(crate) fn new_cell(&self, value: Value) -> usize {
+        let mut cells = self.rt.cells.0.lock();
+        cells.push(Arc::new(ReentrantMutex::new(RefCell::new(value))));
+        cells.len() - 1
+    }
+    pub(crate) fn load_cell(&mut self, id: Value) -> UiuaResult {
+        let cell = self.cell(id)?;
+        let value = cell.lock().borrow().clone();
+        self.push(value);
+        Ok(())
+    }
+    pub(crate) fn store_cell(&self, id: Value, value: Value) -> UiuaResult {
+        let cell = self.cell(id)?;
+        *cell.lock().borrow_mut() = value;
+        Ok(())
+    }
+    /// Atomically replace a cell's value with the result of a function
+    pub(crate) fn update_cell(&mut self, f: SigNode) -> UiuaResult {
+        if f.sig.args == 0 || f.sig.outputs != 1 {
+            return Err(self.error(format!(
+                "{}'s function must have at least 1 argument and exactly 1 output, \
+                but its signature is {}",
+                Primitive::Update.format(),
+                f.sig
+            )));
+        }
+        let id = self.pop(1)?;
+        let cell = self.cell(id)?;
+        let guard = cell.lock();
+        let old = guard.borrow().clone();
+        self.push(old.clone());
+        self.exec(f)?;
+        *guard.borrow_mut() = self.pop("updated value")?;
+        self.push(old);
+        Ok(())
+    }
+    fn cell(&self, id: Value) -> UiuaResult<Arc<ReentrantMutex<RefCell<Value>>>> {
+        let id = id.as_nat(self, "Cell id must be a natural number")?;
+        (self.rt.cells.0.lock().get(id).cloned()).ok_or_else(|| self.error("Invalid cell id"))
+    }
     fn channel(&self, id: usize) -> UiuaResult<&Channel> {
         Ok(if id == 0 {
             self.rt
@@ -1689,6 +2322,16 @@ impl Uiua {
 pub trait StackArg {
     /// Get the name of the argument
     fn arg_name(self) -> String;
+    /// Get the name of the argument in a locale
+    ///
+    /// Only numbered arguments are translated
+    fn localized_arg_name(self, locale: Locale) -> String
+    where
+        Self: Sized,
+    {
+        let _ = locale;
+        self.arg_name()
+    }
 }
 
 impl StackArg for () {
@@ -1701,16 +2344,25 @@ impl StackArg for usize {
     fn arg_name(self) -> String {
         format!("argument {self}")
     }
+    fn localized_arg_name(self, locale: Locale) -> String {
+        MessageId::Argument.format(locale, &[&self])
+    }
 }
 impl StackArg for u8 {
     fn arg_name(self) -> String {
         format!("argument {self}")
     }
+    fn localized_arg_name(self, locale: Locale) -> String {
+        MessageId::Argument.format(locale, &[&self])
+    }
 }
 impl StackArg for i32 {
     fn arg_name(self) -> String {
         format!("argument {self}")
     }
+    fn localized_arg_name(self, locale: Locale) -> String {
+        MessageId::Argument.format(locale, &[&self])
+    }
 }
 impl StackArg for &str {
     fn arg_name(self) -> String {
@@ -1738,4 +2390,7 @@ where
     fn arg_name(self) -> String {
         self().arg_name()
     }
+    fn localized_arg_name(self, locale: Locale) -> String {
+        self().localized_arg_name(locale)
+    }
 }
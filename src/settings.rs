@@ -0,0 +1,361 @@
+//! Layered interpreter settings
+
+use std::{
+    collections::HashMap,
+    env, fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    lex::Span, Compiler, Locale, MemoConfig, Parallelism, SafeSys, SysPermissions, Uiua,
+    UiuaErrorKind, UiuaResult, Value,
+};
+
+/// The name of the file that settings are loaded from
+pub const SETTINGS_FILE_NAME: &str = ".run.ua";
+
+/// Where the value of a setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingSource {
+    /// The interpreter's default
+    Default,
+    /// A settings file
+    File(PathBuf),
+    /// An environment variable
+    Env(&'static str),
+    /// A command line flag
+    Flag,
+}
+
+impl fmt::Display for SettingSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingSource::Default => write!(f, "default"),
+            SettingSource::File(path) => write!(f, "{}", path.display()),
+            SettingSource::Env(var) => write!(f, "${var}"),
+            SettingSource::Flag => write!(f, "flag"),
+        }
+    }
+}
+
+/// The effective value of a setting, as shown by `uiua config`
+#[derive(Debug, Clone)]
+pub struct SettingEntry {
+    /// The name of the setting's binding in a settings file
+    pub name: &'static str,
+    /// The environment variable that sets the setting
+    pub var: &'static str,
+    /// The setting's value, or `None` if the interpreter's default is used
+    pub value: Option<String>,
+    /// Where the value came from
+    pub source: SettingSource,
+}
+
+trait SettingValue: Sized + fmt::Display {
+    const DESCRIPTION: &'static str;
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self>;
+    fn parse(s: &str) -> Option<Self>;
+}
+
+impl SettingValue for f64 {
+    const DESCRIPTION: &'static str = "a finite non-negative number";
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+        (value.as_num(env, "").ok()).filter(|n| n.is_finite() && *n >= 0.0)
+    }
+    fn parse(s: &str) -> Option<Self> {
+        (s.trim().parse().ok()).filter(|n: &f64| n.is_finite() && *n >= 0.0)
+    }
+}
+
+impl SettingValue for bool {
+    const DESCRIPTION: &'static str = "a boolean";
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+        value.as_bool(env, "").ok()
+    }
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "1" | "true" => Some(true),
+            "0" | "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! nat_setting_value {
+    ($($ty:ty),*) => {
+        $(
+            impl SettingValue for $ty {
+                const DESCRIPTION: &'static str = "a natural number";
+                fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+                    (value.as_nat(env, "").ok()).and_then(|n| n.try_into().ok())
+                }
+                fn parse(s: &str) -> Option<Self> {
+                    s.trim().parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+nat_setting_value!(usize, u32, u64);
+
+impl SettingValue for Parallelism {
+    const DESCRIPTION: &'static str = r#""off", "auto", or "deterministic""#;
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+        Self::parse(&value.as_string(env, "").ok()?)
+    }
+    fn parse(s: &str) -> Option<Self> {
+        s.trim().parse().ok()
+    }
+}
+
+impl SettingValue for Locale {
+    const DESCRIPTION: &'static str = r#"a language code like "en", "es", or "de""#;
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+        Self::parse(&value.as_string(env, "").ok()?)
+    }
+    fn parse(s: &str) -> Option<Self> {
+        s.trim().parse().ok()
+    }
+}
+
+impl SettingValue for SysPermissions {
+    const DESCRIPTION: &'static str = r#"a comma-separated list of system function classes like "stdio,filesystem", "all", or "none""#;
+    fn from_value(value: &Value, env: &Uiua) -> Option<Self> {
+        Self::parse(&value.as_string(env, "").ok()?)
+    }
+    fn parse(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+
+macro_rules! settings {
+    ($(
+        $(#[doc = $doc:literal])+
+        ($field:ident, $ty:ty, $name:literal, $var:literal)
+    ),* $(,)?) => {
+        /// Settings for the interpreter
+        ///
+        /// Settings are layered. Each layer overrides the ones before it:
+        /// - The interpreter's defaults
+        /// - A `.run.ua` settings file
+        /// - `UIUA_*` environment variables
+        /// - Command line flags
+        ///
+        /// A settings file is a Uiua program that binds values to the settings' names.
+        #[derive(Debug, Clone, Default)]
+        pub struct Settings {
+            $(
+                $(#[doc = $doc])+
+                #[doc = ""]
+                #[doc = concat!("Set with `", $name, "` in a settings file or the `", $var, "` environment variable")]
+                pub $field: Option<$ty>,
+            )*
+            sources: HashMap<&'static str, SettingSource>,
+        }
+
+        impl Settings {
+            /// Override these settings with those that are set in another layer
+            pub fn layer(mut self, other: Self) -> Self {
+                $(
+                    if other.$field.is_some() {
+                        self.$field = other.$field;
+                        if let Some(source) = other.sources.get($name) {
+                            self.sources.insert($name, source.clone());
+                        }
+                    }
+                )*
+                self
+            }
+            /// Mark all settings that are set as coming from command line flags
+            pub fn mark_as_flags(mut self) -> Self {
+                $(
+                    if self.$field.is_some() {
+                        self.sources.insert($name, SettingSource::Flag);
+                    }
+                )*
+                self
+            }
+            /// Load settings from a settings file
+            pub fn from_file(path: impl AsRef<Path>) -> UiuaResult<Self> {
+                let path = path.as_ref();
+                let asm = Compiler::new().print_diagnostics(false).load_file(path)?.finish();
+                let mut env = Uiua::with_backend(SafeSys::default());
+                env.run_asm(asm)?;
+                let mut bindings = env.bound_values();
+                let mut settings = Settings::default();
+                $(
+                    if let Some(value) = bindings.remove($name) {
+                        let Some(val) = <$ty>::from_value(&value, &env) else {
+                            return Err(env.error(format!(
+                                "Setting {} expects {}",
+                                $name,
+                                <$ty>::DESCRIPTION
+                            )));
+                        };
+                        settings.$field = Some(val);
+                        settings.sources.insert($name, SettingSource::File(path.into()));
+                    }
+                )*
+                Ok(settings)
+            }
+            /// Load settings from environment variables
+            pub fn from_env() -> Result<Self, String> {
+                let mut settings = Settings::default();
+                $(
+                    if let Ok(s) = env::var($var) {
+                        let Some(val) = <$ty>::parse(&s) else {
+                            return Err(format!(
+                                "{} expects {}, but it is {s:?}",
+                                $var,
+                                <$ty>::DESCRIPTION
+                            ));
+                        };
+                        settings.$field = Some(val);
+                        settings.sources.insert($name, SettingSource::Env($var));
+                    }
+                )*
+                Ok(settings)
+            }
+            /// Get the effective value of each setting
+            pub fn entries(&self) -> Vec<SettingEntry> {
+                vec![$(
+                    SettingEntry {
+                        name: $name,
+                        var: $var,
+                        value: self.$field.as_ref().map(|val| val.to_string()),
+                        source: (self.sources.get($name).cloned())
+                            .unwrap_or(SettingSource::Default),
+                    },
+                )*]
+            }
+        }
+    };
+}
+
+settings!(
+    /// The maximum execution time in seconds
+    (execution_limit, f64, "ExecutionLimit", "UIUA_EXECUTION_LIMIT"),
+    /// The number of instructions that can be executed
+    (fuel, u64, "Fuel", "UIUA_FUEL"),
+    /// The maximum depth of recursive calls
+    (recursion_limit, usize, "RecursionLimit", "UIUA_RECURSION_LIMIT"),
+    /// How loops may be run in parallel
+    (parallelism, Parallelism, "Parallelism", "UIUA_PARALLELISM"),
+    /// Whether the conditions of `requires` and `ensures` are checked
+    (contracts, bool, "Contracts", "UIUA_CONTRACTS"),
+    /// The tolerance for numbers to be considered equal
+    (tolerance, f64, "Tolerance", "UIUA_TOLERANCE"),
+    /// Whether `match` uses the tolerance
+    (match_tolerance, bool, "MatchTolerance", "UIUA_MATCH_TOLERANCE"),
+    /// The maximum number of entries in the `memo` cache
+    (memo_capacity, usize, "MemoCapacity", "UIUA_MEMO_CAPACITY"),
+    /// The timeout for HTTP requests in seconds
+    (http_timeout, f64, "HttpTimeout", "UIUA_HTTP_TIMEOUT"),
+    /// The audio sample rate
+    (audio_sample_rate, u32, "AudioSampleRate", "UIUA_AUDIO_SAMPLE_RATE"),
+    /// The seed for the random number generator
+    (seed, u64, "Seed", "UIUA_SEED"),
+    /// The language that error messages are shown in
+    (locale, Locale, "Locale", "UIUA_LOCALE"),
+    /// The maximum number of decimal digits shown when printing numbers
+    (display_precision, usize, "DisplayPrecision", "UIUA_DISPLAY_PRECISION"),
+    /// The classes of system functions that are permitted
+    (permissions, SysPermissions, "Permissions", "UIUA_PERMISSIONS"),
+);
+
+impl Settings {
+    /// Load settings from the defaults, a settings file, and environment variables
+    ///
+    /// The settings file is searched for in the directory of the given path and its ancestors.
+    /// If no path is given, the search starts in the current directory.
+    pub fn load(path: Option<&Path>) -> UiuaResult<Self> {
+        let mut settings = Settings::default();
+        if let Some(file_path) = Self::search_file(path) {
+            settings = settings.layer(Self::from_file(file_path)?);
+        }
+        let from_env = Self::from_env().map_err(|message| UiuaErrorKind::Run {
+            message: Span::Builtin.sp(message),
+            info: Vec::new(),
+            inputs: Box::default(),
+        })?;
+        Ok(settings.layer(from_env))
+    }
+    fn search_file(path: Option<&Path>) -> Option<PathBuf> {
+        let mut dir = path
+            .and_then(|p| p.canonicalize().ok())
+            .or_else(|| env::current_dir().ok())?;
+        if dir.is_file() {
+            dir.pop();
+        }
+        loop {
+            let file_path = dir.join(SETTINGS_FILE_NAME);
+            if file_path.exists() {
+                return Some(file_path);
+            }
+            if !dir.pop() {
+                return None;
+            }
+        }
+    }
+}
+
+impl Uiua {
+    /// Apply settings to the interpreter
+    ///
+    /// Settings that are not set are left unchanged.
+    /// The display precision applies to the whole process, so it is set with
+    /// [`set_display_precision`](crate::set_display_precision) instead.
+    pub fn with_settings(mut self, settings: &Settings) -> Self {
+        if let Some(limit) = settings.execution_limit {
+            self = self.with_execution_limit(secs_to_duration(limit));
+        }
+        if let Some(fuel) = settings.fuel {
+            self = self.with_fuel(fuel);
+        }
+        if let Some(limit) = settings.recursion_limit {
+            self = self.with_recursion_limit(limit);
+        }
+        if let Some(parallelism) = settings.parallelism {
+            self = self.with_parallelism(parallelism);
+        }
+        if let Some(contracts) = settings.contracts {
+            self = self.with_contracts(contracts);
+        }
+        if let Some(tolerance) = settings.tolerance {
+            self = self.with_tolerance(tolerance);
+        }
+        if let Some(match_tolerance) = settings.match_tolerance {
+            self = self.with_match_tolerance(match_tolerance);
+        }
+        if let Some(capacity) = settings.memo_capacity {
+            let config = MemoConfig {
+                capacity: Some(capacity),
+                ..self.memo_cache().config()
+            };
+            self = self.with_memo_config(config);
+        }
+        if let Some(timeout) = settings.http_timeout {
+            self = self.with_http_timeout(secs_to_duration(timeout));
+        }
+        if let Some(sample_rate) = settings.audio_sample_rate {
+            self = self.with_audio_sample_rate(sample_rate);
+        }
+        if let Some(seed) = settings.seed {
+            self = self.with_seed(seed);
+        }
+        if let Some(locale) = settings.locale {
+            self = self.with_locale(locale);
+        }
+        if let Some(permissions) = settings.permissions {
+            self = self.with_sys_permissions(permissions);
+        }
+        self
+    }
+}
+
+/// Durations too long to represent are effectively unlimited
+fn secs_to_duration(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+}
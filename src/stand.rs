@@ -1,19 +1,36 @@
-use std::{env, fs, io, path::Path};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
 use once_cell::sync::Lazy;
 
-use crate::{Assembly, Compiler, NativeSys, UiuaResult};
+use crate::{Assembly, Compiler, NativeSys, UiuaError, UiuaResult};
 
 const STAND_DATA_SIGNATURE: &[u8] = b"Uiua standalone";
 
 pub fn build_exe(root: &Path) -> UiuaResult<Vec<u8>> {
     let asm = Compiler::with_backend(NativeSys).load_file(root)?.finish();
+    bundle_exe(&asm, None)
+}
+
+/// Bundle an assembly with a runtime executable
+///
+/// If no runtime is given, the current exe is used.
+/// A runtime built for a different platform can be given to cross-compile.
+pub fn bundle_exe(asm: &Assembly, runtime: Option<&Path>) -> UiuaResult<Vec<u8>> {
     // Serialize the files
     let asm_bytes = asm.to_uasm().into_bytes();
-    // Append the files to the current exe
-    let mut bytes = env::current_exe()
-        .and_then(fs::read)
-        .unwrap_or_else(|e| panic!("Unable to read current exe: {e}"));
+    // Append the files to the runtime
+    let runtime = match runtime {
+        Some(path) => path.to_path_buf(),
+        None => env::current_exe().map_err(|e| UiuaError::load(PathBuf::new(), e))?,
+    };
+    let mut bytes = fs::read(&runtime).map_err(|e| UiuaError::load(runtime, e))?;
+    // Don't stack programs if the runtime is itself a standalone exe
+    if let Some((start, _)) = split_stand_data(&bytes) {
+        bytes.truncate(start);
+    }
     bytes.extend_from_slice(&asm_bytes);
     // Append the length of the serialized files and a signature
     bytes.extend((asm_bytes.len() as u64).to_le_bytes());
@@ -21,19 +38,39 @@ pub fn build_exe(root: &Path) -> UiuaResult<Vec<u8>> {
     Ok(bytes)
 }
 
+/// Write an executable and make it runnable
+pub fn write_exe(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    fs::write(path, bytes)?;
+    // Set executable permissions on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    Ok(())
+}
+
+/// Get the start and end of the serialized assembly in a standalone exe
+fn split_stand_data(bytes: &[u8]) -> Option<(usize, usize)> {
+    let bytes = bytes.strip_suffix(STAND_DATA_SIGNATURE)?;
+    // Get the length of the serialized files
+    let end = bytes.len().checked_sub(8)?;
+    let asm_len = u64::from_le_bytes(bytes[end..].try_into().unwrap());
+    let start = end.checked_sub(asm_len as usize)?;
+    Some((start, end))
+}
+
 fn load_asm() -> io::Result<Option<Assembly>> {
     // Read the current exe
     let bytes = fs::read(env::current_exe()?)?;
     // Check if it is a standalone exe
-    let Some(bytes) = bytes.strip_suffix(STAND_DATA_SIGNATURE) else {
+    let Some((start, end)) = split_stand_data(&bytes) else {
         return Ok(None);
     };
-    // Get the length of the serialized files
-    let (bytes, len_bytes) = bytes.split_at(bytes.len() - 8);
-    let asm_len = u64::from_le_bytes(len_bytes.try_into().unwrap());
-    let start = bytes.len() - asm_len as usize;
     // Deserialize the files
-    let uasm: String = std::str::from_utf8(&bytes[start..]).unwrap().to_string();
+    let uasm: String = std::str::from_utf8(&bytes[start..end]).unwrap().to_string();
     let asm = Assembly::from_uasm(&uasm).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
@@ -11,11 +11,13 @@ use std::{
     time::Duration,
 };
 
+use ecow::EcoVec;
 use enum_iterator::{all, Sequence};
 #[cfg(feature = "image")]
 use image::DynamicImage;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use rand::Rng;
 use serde::*;
 use time::UtcOffset;
 
@@ -165,6 +167,84 @@ impl SysOpClass {
     pub fn all() -> impl Iterator<Item = Self> {
         all()
     }
+    /// Get the class's name, as used in [`SysPermissions`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            SysOpClass::Filesystem => "filesystem",
+            SysOpClass::StdIO => "stdio",
+            SysOpClass::Env => "env",
+            SysOpClass::Stream => "stream",
+            SysOpClass::Command => "command",
+            SysOpClass::Media => "media",
+            SysOpClass::Tcp => "tcp",
+            SysOpClass::Ffi => "ffi",
+            SysOpClass::Misc => "misc",
+        }
+    }
+}
+
+/// The classes of system functions that a program is permitted to use
+///
+/// Parsed from a comma-separated list of class names like `"stdio,filesystem"`,
+/// or `"all"` or `"none"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SysPermissions(u16);
+
+impl SysPermissions {
+    /// Permit all system functions
+    pub const ALL: Self = Self(u16::MAX);
+    /// Permit no system functions
+    pub const NONE: Self = Self(0);
+    /// Permit a class of system functions
+    pub fn allow(self, class: SysOpClass) -> Self {
+        Self(self.0 | 1 << class as u16)
+    }
+    /// Check whether a class of system functions is permitted
+    pub fn allows(&self, class: SysOpClass) -> bool {
+        self.0 & 1 << class as u16 != 0
+    }
+}
+
+impl Default for SysPermissions {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl fmt::Display for SysPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if SysOpClass::all().all(|class| self.allows(class)) {
+            return write!(f, "all");
+        }
+        let mut classes = SysOpClass::all().filter(|class| self.allows(*class));
+        let Some(first) = classes.next() else {
+            return write!(f, "none");
+        };
+        write!(f, "{}", first.name())?;
+        for class in classes {
+            write!(f, ",{}", class.name())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for SysPermissions {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "all" => return Ok(Self::ALL),
+            "none" | "" => return Ok(Self::NONE),
+            _ => {}
+        }
+        let mut perms = Self::NONE;
+        for name in s.split(',').map(str::trim) {
+            let class = SysOpClass::all()
+                .find(|class| class.name() == name)
+                .ok_or_else(|| format!("Unknown system function class {name:?}"))?;
+            perms = perms.allow(class);
+        }
+        Ok(perms)
+    }
 }
 
 sys_op! {
@@ -244,9 +324,28 @@ sys_op! {
     /// [un][&raw] will return the current state of the terminal.
     /// [under][&raw] will set raw mode, and then revert it to the previous state.
     (1(0), RawMode, Env, "&raw", "set raw mode", Mutating),
+    /// Move the terminal cursor
+    ///
+    /// Expects a 2-element array of the row and column to move to.
+    /// The top-left corner is `[0 0]`.
+    /// Row comes first so that the position matches the order of [&ts].
+    ///
+    /// Combine with [&raw], [&tclr], and [&tcol] to draw interactive terminal interfaces.
+    (1(0), TermCursor, StdIO, "&tcur", "set terminal cursor position", Mutating),
+    /// Clear the terminal
+    ///
+    /// The cursor is moved to the top-left corner.
+    (0(0), TermClear, StdIO, "&tclr", "clear terminal", Mutating),
+    /// Set the color of text printed to the terminal
+    ///
+    /// Expects a color as a 3-element array of red, green, and blue values between `0` and `1`.
+    /// Text printed afterwards will be in that color.
+    /// An empty array resets the color to the terminal's default.
+    (1(0), TermColor, StdIO, "&tcol", "set terminal text color", Mutating),
     /// Get the command line arguments
     ///
-    /// The first element will always be the name of your script
+    /// The first element will always be the name of your script.
+    /// The rest are the arguments passed after it, as in `uiua run script.ua -- arg1 arg2`.
     // Doesn't actually mutate, but this is necessary for the LSP
     (0, Args, Env, "&args", "arguments", Mutating),
     /// Get the value of an environment variable
@@ -254,6 +353,16 @@ sys_op! {
     /// Expects a string and returns a string.
     /// If the environment variable does not exist, an error is thrown.
     (1, Var, Env, "&var", "environment variable"),
+    /// Get all environment variables
+    ///
+    /// Returns a [map] of variable names to values, both as [box]ed strings.
+    /// ex: # Experimental!
+    ///   : ⧻ &vars
+    /// Use [has] to check if a variable is set without throwing an error like [&var] does.
+    /// ex: # Experimental!
+    ///   : has "NOT_A_REAL_VARIABLE" &vars
+    // Doesn't actually mutate, but this is necessary for the LSP
+    (0, Vars, Env, "&vars", "environment variables", Mutating),
     /// Run a command and wait for it to finish
     ///
     /// Standard IO will be inherited. Returns the exit code of the command.
@@ -275,7 +384,24 @@ sys_op! {
     /// Using [&cl] on *all 3* handles will kill the child process.
     /// [under][&runs] calls [&cl] on all 3 streams automatically.
     (1(3), RunStream, Command, "&runs", "run command stream", Mutating),
+    /// Run a command with streaming IO and extra environment variables
+    ///
+    /// Expects a command, then a [map] of environment variable names to values.
+    /// The command is the same as for [&runs], and the same 3 stream handles are returned.
+    /// The variables are added to the environment inherited from the current process.
+    /// [under][&runse] calls [&cl] on all 3 streams automatically.
+    (2(3), RunStreamEnv, Command, "&runse", "run command stream with environment", Mutating),
+    /// Wait for a streamed command to finish
+    ///
+    /// Expects any of the 3 stream handles returned by [&runs] or [&runse].
+    /// Returns the exit code of the command.
+    /// The command's stdout and stderr should be read first, or the command may block trying to write to them.
+    /// If the command reads from its stdin, [&cl] the stdin handle first so that the command sees the end of its input.
+    (1, RunWait, Command, "&runw", "run command wait", Mutating),
     /// Change the current directory
+    ///
+    /// [un][&cd] will return the current directory.
+    /// [under][&cd] will change the directory, and then change back to the previous one.
     (1(0), ChangeDirectory, Filesystem, "&cd", "change directory", Mutating),
     /// Get the contents of the clipboard
     ///
@@ -284,6 +410,7 @@ sys_op! {
     ///
     /// The inverse sets the clipboard, expecting a string.
     /// ex: °&clip +@A⇡6 # Try running then pasting!
+    /// [under][&clip] will get the clipboard contents, and then set the clipboard to the result of the function.
     (0, Clip, Misc, "&clip", "get clipboard contents"),
     /// Sleep for n seconds
     ///
@@ -327,7 +454,41 @@ sys_op! {
     /// For each line, it will be pushed onto the stack and the function will be called.
     /// Additional arguments to the function will be bellow the line.
     /// Outputs in excess of the number of accumulators will be collected into arrays.
+    /// The stream handle `0` is stdin, so [&rl] can be used to process piped input one line at a time.
     (1[1], ReadLines, Stream, "&rl", "read lines", Mutating),
+    /// Read chunks of bytes from a stream
+    ///
+    /// Expects a chunk size and a stream handle.
+    /// [&rch] calls its function on each chunk of at most that many bytes without reading the entire stream into memory.
+    /// Every chunk except possibly the last will have exactly the chunk size.
+    /// ex: # Experimental!
+    ///   : &fwa "example.bin" ⇡10
+    ///   : &rch(⧻) 4 &fo "example.bin"
+    /// As with [&rl], additional arguments to the function will be below the chunk, and outputs in excess of the number of accumulators will be collected into arrays.
+    /// ex: # Experimental!
+    ///   : &fwa "example.bin" ⇡10
+    ///   : &rch(+/+) 4 &fo "example.bin" 0
+    /// The stream handle `0` is stdin, so [&rch] can be used to process piped input that is too large to fit in memory.
+    (2[1], ReadChunks, Stream, "&rch", "read chunks", Mutating),
+    /// Read a random sample of lines from a stream
+    ///
+    /// Expects a count, a seed, and a stream handle.
+    /// Returns a list of up to that many [box]ed lines, chosen uniformly at random and kept in the order they appear in the stream.
+    /// Like [&rl], the stream is not read entirely into memory, so this works for streams that are too large to read at once.
+    /// Like [gen], the same seed always gives the same sample of the same stream.
+    /// ex: # Experimental!
+    ///   : &rls 3 0 &fo "example.txt"
+    (3, ReadLineSample, Stream, "&rls", "read line sample", Mutating),
+    /// Decompress a stream
+    ///
+    /// Expects a compression format and a stream handle.
+    /// Returns a new stream handle. Reading from it with [&rs], [&rb], [&ru], or [&rl] reads and decompresses data from the original stream as needed.
+    /// The supported formats are the same as for [compress].
+    /// ex: # Experimental!
+    ///   : &fwa "example.txt.gz" compress "gzip" "Hello\nWorld"
+    ///   : &rl(&p) &dcs "gzip" &fo "example.txt.gz"
+    /// Closing the new handle with [&cl] does not close the original stream.
+    (2, DecompressStream, Stream, "&dcs", "decompress stream", Mutating),
     /// Write an array to a stream
     ///
     /// If the stream is a file, the file may not be written to until it is closed with [&cl].
@@ -340,7 +501,7 @@ sys_op! {
     (1(1), Invoke, Command, "&invk", "invoke", Mutating),
     /// Close a stream by its handle
     ///
-    /// This will close files, tcp listeners, and tcp sockets.
+    /// This will close files, tcp listeners, tcp sockets, udp sockets, and websockets.
     (1(0), Close, Stream, "&cl", "close handle", Mutating),
     /// Open a file and return a handle to it
     ///
@@ -390,6 +551,50 @@ sys_op! {
     ///
     /// ex: &fif "example.txt"
     (1, FIsFile, Filesystem, "&fif", "file - is file"),
+    /// Get metadata about a file or directory
+    ///
+    /// Returns a [map] with the keys `"size"`, `"modified"`, and `"dir"`.
+    /// `"size"` is the size in bytes.
+    /// `"modified"` is the time of the last modification in seconds since the Unix epoch, or `NaN` if it is not known.
+    /// `"dir"` is `1` if the path is a directory and `0` otherwise.
+    /// ex: # Experimental!
+    ///   : &fwa "hello.txt" "Hello, World!"
+    ///   : &fmeta "hello.txt"
+    /// ex: # Experimental!
+    ///   : &fwa "hello.txt" "Hello, World!"
+    ///   : get "size" &fmeta "hello.txt"
+    (1, FMetadata, Filesystem, "&fmeta", "file - metadata"),
+    /// Move or rename a file or directory
+    ///
+    /// Expects the current path, then the new path.
+    /// ex: # Experimental!
+    ///   : &fwa "temp.txt" "Hi!"
+    ///   : &fmv "temp.txt" "renamed.txt"
+    ///   : &fras "renamed.txt"
+    (2(0), FMove, Filesystem, "&fmv", "file - move", Mutating),
+    /// List the paths that match a glob pattern
+    ///
+    /// The result is a sorted list of boxed strings.
+    /// In the pattern, `*` matches any part of a file or directory name, `?` matches any single character, and `**` matches any number of nested directories.
+    /// ex: # Experimental!
+    ///   : &fglob "*.txt"
+    /// A pattern ending in `**` lists a directory recursively.
+    /// ex: # Experimental!
+    ///   : &fglob "**"
+    (1, FGlob, Filesystem, "&fglob", "file - glob"),
+    /// Watch a file or directory for changes
+    ///
+    /// Returns a handle that can be passed to [&fwtr] to get the paths that have changed.
+    /// Directories are watched recursively.
+    /// Use [&cl] to stop watching.
+    /// [under][&fwt] calls [&cl] automatically.
+    (1, FWatch, Filesystem, "&fwt", "file - watch", Mutating),
+    /// Get the paths that have changed since the last call
+    ///
+    /// Expects a handle returned by [&fwt].
+    /// Returns a list of boxed strings of the paths that were created, modified, or removed since the watch began or since the last call to [&fwtr].
+    /// This does not wait for changes, so the list may be empty.
+    (1, FWatchRead, Filesystem, "&fwtr", "file - watch read", Mutating),
     /// Read all the contents of a file into a string
     ///
     /// Expects a path and returns a rank-`1` character array.
@@ -422,6 +627,26 @@ sys_op! {
     ///   : &fwa Path +@A⇡26
     ///   : &fras Path
     (2(0), FWriteAll, Filesystem, "&fwa", "file - write all", Mutating),
+    /// Save a value to a file
+    ///
+    /// Expects a path and a value.
+    /// The value is encoded with [binary], so it can be loaded back exactly with [&flv].
+    /// The file will be created if it does not exist and overwritten if it does.
+    ///
+    /// ex: # Experimental!
+    ///   : &fsv "values.uav" {[1 2 3] "hi" ÷2 °△2_3}
+    ///   : &flv "values.uav"
+    (2(0), FSave, Filesystem, "&fsv", "file - save value", Mutating),
+    /// Load a value from a file
+    ///
+    /// Expects a path to a file written with [&fsv] or [binary].
+    /// You can use [under][&flv] to save the value back to the file after modifying it.
+    ///
+    /// ex: # Experimental!
+    ///   : &fsv "counter.uav" 0
+    ///   : ⍜&flv(+1) "counter.uav"
+    ///   : &flv "counter.uav"
+    (1, FLoad, Filesystem, "&flv", "file - load value"),
     /// Show an image
     ///
     /// How the image is shown depends on the system backend.
@@ -449,6 +674,18 @@ sys_op! {
     ///
     /// See also: [gif]
     (2(0), GifShow, Media, "&gifs", "gif - show", Mutating),
+    /// Show an animation
+    ///
+    /// The first argument is a framerate in frames per second.
+    /// The second argument is the animation data and must be a rank 3 or 4 numeric array.
+    /// The rows of the array are the frames of the animation, and their format must conform to that of [img].
+    ///
+    /// On the web, the frames are drawn to a canvas at the given framerate.
+    /// Unlike [&gifs], this does not reduce the number of colors.
+    /// Other environments show the frames as a gif.
+    ///
+    /// See also: [&gifs]
+    (2(0), FramesShow, Media, "&frms", "frames - show", Mutating),
     /// Play some audio
     ///
     /// The audio must be a rank 1 or 2 numeric array.
@@ -463,6 +700,7 @@ sys_op! {
     (1(0), AudioPlay, Media, "&ap", "audio - play", Mutating),
     /// Get the sample rate of the audio output backend
     ///
+    /// When embedding, this can be configured with `Uiua::with_audio_sample_rate`.
     /// ex: &asr
     /// Here is how you can generate a list of sample times for `4` seconds of audio:
     /// ex: ÷:⇡×, 4 &asr
@@ -534,14 +772,109 @@ sys_op! {
     ///
     /// See also: [&tcpc]
     (1, TlsConnect, Tcp, "&tlsc", "tls - connect", Mutating),
-    /// Set a TCP socket to non-blocking mode
+    /// Set a TCP or UDP socket to non-blocking mode
+    ///
+    /// Reading from a non-blocking socket that has no data available will throw an error instead of waiting.
     (1, TcpSetNonBlocking, Tcp, "&tcpsnb", "tcp - set non-blocking", Mutating),
-    /// Set the read timeout of a TCP socket in seconds
+    /// Set the read timeout of a TCP or UDP socket in seconds
     (2(0), TcpSetReadTimeout, Tcp, "&tcpsrt", "tcp - set read timeout", Mutating),
-    /// Set the write timeout of a TCP socket in seconds
+    /// Set the write timeout of a TCP or UDP socket in seconds
     (2(0), TcpSetWriteTimeout, Tcp, "&tcpswt", "tcp - set write timeout", Mutating),
     /// Get the connection address of a TCP socket
+    ///
+    /// For a UDP socket, this is the local address it is bound to.
     (1, TcpAddr, Tcp, "&tcpaddr", "tcp - address", Mutating),
+    /// Create a UDP socket and bind it to an address
+    ///
+    /// Returns a socket handle.
+    /// Use [&udps] to send datagrams and [&udpr] to receive them.
+    /// Binding to port `0` lets the system choose a free port. Use [&tcpaddr] to find out which one.
+    /// [under][&udpb] calls [&cl] automatically.
+    /// ex: # Experimental!
+    ///   : ⍜(&udpb "127.0.0.1:0"|&udps "Hello!" "127.0.0.1:8080")
+    (1, UdpBind, Tcp, "&udpb", "udp - bind", Mutating),
+    /// Send a datagram with a UDP socket
+    ///
+    /// Expects the data, a destination address, and a socket handle from [&udpb].
+    /// The data can be a string or a byte array.
+    /// ex: # Experimental!
+    ///   : ⍜(&udpb "127.0.0.1:0"|&udps "Hello!" "127.0.0.1:8080")
+    (3(0), UdpSend, Tcp, "&udps", "udp - send", Mutating),
+    /// Receive a datagram with a UDP socket
+    ///
+    /// Expects a socket handle from [&udpb].
+    /// Returns the datagram's contents as a byte array, and the address it was sent from.
+    /// This waits until a datagram arrives, unless a timeout was set with [&tcpsrt] or the socket was made non-blocking with [&tcpsnb].
+    /// ex: # Experimental!
+    ///   : ⍜(&udpb "127.0.0.1:0"|°utf₈⊙◌&udpr ⟜(&udps "Hello!" &tcpaddr.))
+    (1(2), UdpReceive, Tcp, "&udpr", "udp - receive", Mutating),
+    /// Connect to a WebSocket server
+    ///
+    /// Expects a `ws://` or `wss://` URL.
+    /// Returns a socket handle.
+    /// Use [&wss] to send messages and [&wsr] to receive them.
+    /// [under][&wsc] calls [&cl] automatically, which closes the connection.
+    /// ex: # Experimental!
+    ///   : ⍜(&wsc "wss://echo.websocket.org"|&wsr ⊸&wss "Hello!")
+    (1, WsConnect, Tcp, "&wsc", "websocket - connect", Mutating),
+    /// Send a message over a WebSocket
+    ///
+    /// Expects a message and a socket handle from [&wsc].
+    /// A string is sent as a text message. A byte array is sent as a binary message.
+    /// ex: # Experimental!
+    ///   : ⍜(&wsc "wss://echo.websocket.org"|&wss "Hello!")
+    (2(0), WsSend, Tcp, "&wss", "websocket - send", Mutating),
+    /// Receive a message from a WebSocket
+    ///
+    /// Expects a socket handle from [&wsc].
+    /// A text message is returned as a string. A binary message is returned as a byte array.
+    /// This waits until a message arrives.
+    /// If the connection was closed, the number `0` is returned instead.
+    /// ex: # Experimental!
+    ///   : ⍜(&wsc "wss://echo.websocket.org"|&wsr ⊸&wss "Hello!")
+    ///
+    /// On the web, messages are received in the background. Run the code again to get any that have arrived.
+    (1, WsReceive, Tcp, "&wsr", "websocket - receive", Mutating),
+    /// Make an HTTP request
+    ///
+    /// Expects a method, a URL, a map of headers, and a body.
+    /// The headers map should map header names to values, both strings. An empty array means no headers.
+    /// The body can be a string or a byte array.
+    /// Returns the response's status code, a map of its headers, and its body.
+    /// The body is a string if it is valid UTF-8 and a byte array otherwise.
+    /// HTTPS URLs are supported.
+    /// ex: # Experimental!
+    ///   : &http "GET" "https://example.com" [] ""
+    ///
+    /// The request's timeout can be configured when embedding with `Uiua::with_http_timeout`.
+    ///
+    /// In the web editor, the request is made in the background. Run the code again to get the response.
+    (4(3), HttpRequest, Tcp, "&http", "http - request", Mutating),
+    /// Open a serial port
+    ///
+    /// Expects a port name and a baud rate.
+    /// Returns a stream handle.
+    /// Read from the port with [&rs], [&rb], or [&ru], and write to it with [&w].
+    /// Reads and writes wait indefinitely unless a timeout is set with [&serst].
+    /// [under][&sero] calls [&cl] automatically.
+    /// On Linux and macOS, port names look like `"/dev/ttyUSB0"`. On Windows, they look like `"COM3"`.
+    ///
+    /// See also: [&serl]
+    (2, SerialOpen, Stream, "&sero", "serial - open", Mutating),
+    /// List the available serial ports
+    ///
+    /// Returns a list of boxed port names that can be passed to [&sero].
+    (0, SerialList, Stream, "&serl", "serial - list ports", Mutating),
+    /// Set the baud rate of a serial port
+    ///
+    /// Expects a baud rate and a handle from [&sero].
+    (2(0), SerialSetBaudRate, Stream, "&sersb", "serial - set baud rate", Mutating),
+    /// Set the read and write timeout of a serial port in seconds
+    ///
+    /// Expects a timeout and a handle from [&sero].
+    /// An infinite timeout waits forever.
+    /// Reading everything from a serial port with [&rs] or [&rb] and [infinity] reads until the timeout expires.
+    (2(0), SerialSetTimeout, Stream, "&serst", "serial - set timeout", Mutating),
     /// Capture an image from a webcam
     ///
     /// Takes the index of the webcam to capture from.
@@ -733,9 +1066,14 @@ pub enum HandleKind {
     TlsListener(SocketAddr),
     TcpSocket(SocketAddr),
     TlsSocket(SocketAddr),
+    UdpSocket(SocketAddr),
+    WebSocket(String),
+    SerialPort(String),
+    FileWatcher(PathBuf),
     ChildStdin(String),
     ChildStdout(String),
     ChildStderr(String),
+    Decompressed(String),
 }
 
 impl fmt::Display for HandleKind {
@@ -746,9 +1084,14 @@ impl fmt::Display for HandleKind {
             Self::TlsListener(addr) => write!(f, "tls listener {}", addr),
             Self::TcpSocket(addr) => write!(f, "tcp socket {}", addr),
             Self::TlsSocket(addr) => write!(f, "tls socket {}", addr),
+            Self::UdpSocket(addr) => write!(f, "udp socket {}", addr),
+            Self::WebSocket(url) => write!(f, "websocket {url}"),
+            Self::SerialPort(port) => write!(f, "serial port {port}"),
+            Self::FileWatcher(path) => write!(f, "watcher {}", path.display()),
             Self::ChildStdin(com) => write!(f, "stdin {com}"),
             Self::ChildStdout(com) => write!(f, "stdout {com}"),
             Self::ChildStderr(com) => write!(f, "stderr {com}"),
+            Self::Decompressed(format) => write!(f, "{format} decompressor"),
         }
     }
 }
@@ -828,10 +1171,28 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn get_raw_mode(&self) -> Result<bool, String> {
         Err("Getting raw mode is not supported in this environment".into())
     }
+    /// Move the terminal cursor to a row and column
+    fn set_cursor(&self, row: usize, col: usize) -> Result<(), String> {
+        Err("Moving the cursor is not supported in this environment".into())
+    }
+    /// Clear the terminal
+    fn clear_terminal(&self) -> Result<(), String> {
+        Err("Clearing the terminal is not supported in this environment".into())
+    }
+    /// Set the color of text printed to the terminal
+    ///
+    /// `None` resets the color to the default.
+    fn set_text_color(&self, color: Option<[u8; 3]>) -> Result<(), String> {
+        Err("Setting the text color is not supported in this environment".into())
+    }
     /// Get an environment variable
     fn var(&self, name: &str) -> Option<String> {
         None
     }
+    /// Get all environment variables
+    fn vars(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
     /// Get the size of the terminal
     fn term_size(&self) -> Result<(usize, usize), String> {
         Err("Getting the terminal size is not supported in this environment".into())
@@ -860,6 +1221,48 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn trash(&self, path: &str) -> Result<(), String> {
         Err("Trashing files is not supported in this environment".into())
     }
+    /// Get metadata about a file or directory
+    fn file_metadata(&self, path: &str) -> Result<FileMetadata, String> {
+        Err("Getting file metadata is not supported in this environment".into())
+    }
+    /// Move or rename a file or directory
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        Err("Moving files is not supported in this environment".into())
+    }
+    /// List the paths that match a glob pattern
+    ///
+    /// The default implementation walks directories with [`SysBackend::list_dir`] and [`SysBackend::is_file`]
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let pattern = pattern.replace('\\', "/");
+        let mut parts: Vec<&str> = pattern.split('/').filter(|part| !part.is_empty()).collect();
+        // Start from the longest prefix without wildcards
+        let literal_len = (parts.iter())
+            .position(|part| part.contains(['*', '?']))
+            .unwrap_or(parts.len());
+        let mut dir = parts.drain(..literal_len).collect::<Vec<_>>().join("/");
+        if pattern.starts_with('/') {
+            dir.insert(0, '/');
+        }
+        let mut paths = Vec::new();
+        if parts.is_empty() {
+            if self.file_exists(&dir) {
+                paths.push(dir);
+            }
+        } else {
+            glob_dir(self, &dir, &parts, &mut paths)?;
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+    /// Watch a file or directory for changes
+    fn watch(&self, path: &str) -> Result<Handle, String> {
+        Err("Watching files is not supported in this environment".into())
+    }
+    /// Get the paths that have changed since the last call
+    fn watch_changes(&self, handle: Handle) -> Result<Vec<String>, String> {
+        Err("Watching files is not supported in this environment".into())
+    }
     /// Read at most `count` bytes from a stream
     fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
         Err("Reading from streams is not supported in this environment".into())
@@ -887,6 +1290,14 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn read_lines<'a>(&self, handle: Handle) -> Result<ReadLinesReturnFn<'a>, String> {
         Err("Reading from streams is not supported in this environment".into())
     }
+    /// Wrap a stream so that reading from the returned stream decompresses
+    fn decompress_stream(
+        &self,
+        handle: Handle,
+        format: crate::encode::Compression,
+    ) -> Result<Handle, String> {
+        Err("Decompressing streams is not supported in this environment".into())
+    }
     /// Write bytes to a stream
     fn write(&self, handle: Handle, contents: &[u8]) -> Result<(), String> {
         Err("Writing to streams is not supported in this environment".into())
@@ -942,6 +1353,25 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn show_gif(&self, gif_bytes: Vec<u8>, label: Option<&str>) -> Result<(), String> {
         Err("Showing gifs not supported in this environment".into())
     }
+    /// Show a sequence of image frames at a framerate
+    ///
+    /// By default, this encodes the frames as a GIF and calls [`SysBackend::show_gif`]
+    #[cfg(feature = "image")]
+    fn show_frames(
+        &self,
+        frames: Vec<DynamicImage>,
+        frame_rate: f64,
+        label: Option<&str>,
+    ) -> Result<(), String> {
+        #[cfg(feature = "gif")]
+        {
+            let frames = frames.into_iter().map(DynamicImage::into_rgba8).collect();
+            let bytes = crate::encode::images_to_gif_bytes(frames, frame_rate)?;
+            self.show_gif(bytes, label)
+        }
+        #[cfg(not(feature = "gif"))]
+        Err("Showing frames not supported in this environment".into())
+    }
     /// Play audio from WAV bytes
     fn play_audio(&self, wave_bytes: Vec<u8>, label: Option<&str>) -> Result<(), String> {
         Err("Playing audio not supported in this environment".into())
@@ -960,6 +1390,12 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn now(&self) -> f64 {
         now()
     }
+    /// Fill a buffer with random bytes from the operating system
+    ///
+    /// If this fails, the interpreter's own random number generator is used instead
+    fn os_random_bytes(&self, bytes: &mut [u8]) -> Result<(), String> {
+        Err("OS randomness is not available in this environment".into())
+    }
     /// Create a TCP listener and bind it to an address
     fn tcp_listen(&self, addr: &str) -> Result<Handle, String> {
         Err("TCP listeners are not supported in this environment".into())
@@ -980,15 +1416,15 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn tls_connect(&self, addr: &str) -> Result<Handle, String> {
         Err("TLS sockets are not supported in this environment".into())
     }
-    /// Get the connection address of a TCP socket or listener
+    /// Get the connection address of a TCP socket or listener, or the local address of a UDP socket
     fn tcp_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
         Err("TCP sockets are not supported in this environment".into())
     }
-    /// Set a TCP socket to non-blocking mode
+    /// Set a TCP or UDP socket to non-blocking mode
     fn tcp_set_non_blocking(&self, handle: Handle, non_blocking: bool) -> Result<(), String> {
         Err("TCP sockets are not supported in this environment".into())
     }
-    /// Set the read timeout of a TCP socket
+    /// Set the read timeout of a TCP or UDP socket
     fn tcp_set_read_timeout(
         &self,
         handle: Handle,
@@ -996,7 +1432,7 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     ) -> Result<(), String> {
         Err("TCP sockets are not supported in this environment".into())
     }
-    /// Set the write timeout of a TCP socket
+    /// Set the write timeout of a TCP or UDP socket
     fn tcp_set_write_timeout(
         &self,
         handle: Handle,
@@ -1004,6 +1440,56 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     ) -> Result<(), String> {
         Err("TCP sockets are not supported in this environment".into())
     }
+    /// Create a UDP socket and bind it to an address
+    fn udp_bind(&self, addr: &str) -> Result<Handle, String> {
+        Err("UDP sockets are not supported in this environment".into())
+    }
+    /// Send a datagram from a UDP socket to an address
+    fn udp_send(&self, handle: Handle, data: &[u8], addr: &str) -> Result<(), String> {
+        Err("UDP sockets are not supported in this environment".into())
+    }
+    /// Receive a datagram with a UDP socket
+    ///
+    /// Returns the datagram's contents and the address it was sent from
+    fn udp_receive(&self, handle: Handle) -> Result<(Vec<u8>, SocketAddr), String> {
+        Err("UDP sockets are not supported in this environment".into())
+    }
+    /// Connect to a WebSocket server
+    fn ws_connect(&self, url: &str) -> Result<Handle, String> {
+        Err("WebSockets are not supported in this environment".into())
+    }
+    /// Send a message over a WebSocket
+    fn ws_send(&self, handle: Handle, message: WebSocketMessage) -> Result<(), String> {
+        Err("WebSockets are not supported in this environment".into())
+    }
+    /// Receive a message from a WebSocket
+    ///
+    /// Should return `Ok(None)` if the connection was closed.
+    fn ws_receive(&self, handle: Handle) -> Result<Option<WebSocketMessage>, String> {
+        Err("WebSockets are not supported in this environment".into())
+    }
+    /// Open a serial port
+    fn serial_open(&self, port: &str, baud_rate: u32) -> Result<Handle, String> {
+        Err("Serial ports are not supported in this environment".into())
+    }
+    /// List the available serial ports
+    fn serial_list(&self) -> Result<Vec<String>, String> {
+        Err("Serial ports are not supported in this environment".into())
+    }
+    /// Set the baud rate of a serial port
+    fn serial_set_baud_rate(&self, handle: Handle, baud_rate: u32) -> Result<(), String> {
+        Err("Serial ports are not supported in this environment".into())
+    }
+    /// Set the read and write timeout of a serial port
+    ///
+    /// `None` means no timeout
+    fn serial_set_timeout(&self, handle: Handle, timeout: Option<Duration>) -> Result<(), String> {
+        Err("Serial ports are not supported in this environment".into())
+    }
+    /// Make an HTTP request
+    fn http_request(&self, request: HttpRequest) -> Result<HttpResponse, String> {
+        Err("HTTP requests are not supported in this environment".into())
+    }
     /// Close a stream
     fn close(&self, handle: Handle) -> Result<(), String> {
         Ok(())
@@ -1028,10 +1514,27 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn run_command_stream(&self, command: &str, args: &[&str]) -> Result<[Handle; 3], String> {
         Err("Running streamed commands is not supported in this environment".into())
     }
+    /// Run a command with extra environment variables and return an IO stream handle
+    fn run_command_stream_env(
+        &self,
+        command: &str,
+        args: &[&str],
+        vars: &[(&str, &str)],
+    ) -> Result<[Handle; 3], String> {
+        Err("Running streamed commands is not supported in this environment".into())
+    }
+    /// Wait for a streamed command to finish and return its exit code
+    fn wait_command(&self, handle: Handle) -> Result<i32, String> {
+        Err("Waiting for commands is not supported in this environment".into())
+    }
     /// Change the current directory
     fn change_directory(&self, path: &str) -> Result<(), String> {
         Err("Changing directories is not supported in this environment".into())
     }
+    /// Get the current directory
+    fn current_directory(&self) -> Result<String, String> {
+        Err("Getting the current directory is not supported in this environment".into())
+    }
     /// Capture an image from the webcam
     fn webcam_capture(&self, index: usize) -> Result<WebcamImage, String> {
         Err("Capturing from webcam is not supported in this environment".into())
@@ -1081,6 +1584,52 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     }
 }
 
+/// An HTTP request made with `&http`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HttpRequest {
+    /// The request method, like `GET` or `POST`
+    pub method: String,
+    /// The URL to send the request to
+    pub url: String,
+    /// The request headers
+    pub headers: Vec<(String, String)>,
+    /// The request body
+    pub body: Vec<u8>,
+    /// How long to wait for the request to complete
+    pub timeout: Option<Duration>,
+}
+
+/// An HTTP response returned by `&http`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    /// The response status code
+    pub status: u16,
+    /// The response headers
+    pub headers: Vec<(String, String)>,
+    /// The response body
+    pub body: Vec<u8>,
+}
+
+/// Metadata about a file or directory returned by `&fmeta`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileMetadata {
+    /// The size in bytes
+    pub size: u64,
+    /// The time of the last modification in seconds since the Unix epoch
+    pub modified: Option<f64>,
+    /// Whether the path is a directory
+    pub is_dir: bool,
+}
+
+/// A message sent or received with `&wss` or `&wsr`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketMessage {
+    /// A text message
+    Text(String),
+    /// A binary message
+    Binary(Vec<u8>),
+}
+
 /// A target for a git repository
 #[derive(Debug, Clone, Default)]
 pub enum GitTarget {
@@ -1172,38 +1721,52 @@ impl IntoSysBackend for Arc<dyn SysBackend> {
 
 impl SysOp {
     pub(crate) fn run(&self, env: &mut Uiua) -> UiuaResult {
+        if !env.rt.sys_permissions.allows(self.class()) {
+            return Err(env.error(format!(
+                "{} is not permitted because {} system functions are disabled",
+                self.name(),
+                self.class().name()
+            )));
+        }
         match self {
             SysOp::Show => {
                 let val = env.pop(1)?;
-                env.rt.backend.show(val).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .show(val)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::Prin => {
                 let s = env.pop(1)?.format();
                 (env.rt.backend)
                     .print_str_stdout(&s)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::Print => {
                 let s = env.pop(1)?.format();
                 (env.rt.backend)
                     .print_str_stdout(&format!("{s}\n"))
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::PrinErr => {
                 let s = env.pop(1)?.format();
                 (env.rt.backend)
                     .print_str_stderr(&s)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::PrintErr => {
                 let s = env.pop(1)?.format();
                 (env.rt.backend)
                     .print_str_stderr(&format!("{s}\n"))
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::ScanLine => {
                 let start = env.rt.backend.now();
-                let res = env.rt.backend.scan_line_stdin().map_err(|e| env.error(e));
+                let res = env
+                    .rt
+                    .backend
+                    .scan_line_stdin()
+                    .map_err(|e| env.error(e).system());
                 env.rt.execution_start += env.rt.backend.now() - start;
                 if let Some(line) = res? {
                     env.push(line);
@@ -1212,18 +1775,61 @@ impl SysOp {
                 }
             }
             SysOp::TermSize => {
-                let (width, height) = env.rt.backend.term_size().map_err(|e| env.error(e))?;
+                let (width, height) = env
+                    .rt
+                    .backend
+                    .term_size()
+                    .map_err(|e| env.error(e).system())?;
                 env.push(cowslice![height as f64, width as f64])
             }
             SysOp::Exit => {
                 let status = env.pop(1)?.as_int(env, "Status must be an integer")? as i32;
-                (env.rt.backend).exit(status).map_err(|e| env.error(e))?;
+                (env.rt.backend)
+                    .exit(status)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::RawMode => {
                 let raw_mode = env.pop(1)?.as_bool(env, "Raw mode must be a boolean")?;
                 (env.rt.backend)
                     .set_raw_mode(raw_mode)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::TermCursor => {
+                let pos = env
+                    .pop(1)?
+                    .as_nats(env, "Cursor position must be natural numbers")?;
+                let [row, col] = *pos.as_slice() else {
+                    return Err(env.error(format!(
+                        "Cursor position must have 2 elements, but it has {}",
+                        pos.len()
+                    )));
+                };
+                (env.rt.backend)
+                    .set_cursor(row, col)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::TermClear => env
+                .rt
+                .backend
+                .clear_terminal()
+                .map_err(|e| env.error(e).system())?,
+            SysOp::TermColor => {
+                let color = env.pop(1)?;
+                let color = if color.row_count() == 0 {
+                    None
+                } else {
+                    let nums = color.as_nums(env, "Color must be a list of numbers")?;
+                    let [r, g, b] = *nums.as_slice() else {
+                        return Err(env.error(format!(
+                            "Color must have 3 elements, but its shape is {}",
+                            color.shape()
+                        )));
+                    };
+                    Some([r, g, b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+                };
+                (env.rt.backend)
+                    .set_text_color(color)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::Args => {
                 let mut args = Vec::new();
@@ -1241,11 +1847,17 @@ impl SysOp {
                     })?;
                 env.push(var);
             }
+            SysOp::Vars => {
+                let (names, values): (Vec<_>, Vec<_>) = env.rt.backend.vars().into_iter().unzip();
+                let mut vars: Value = Array::<Boxed>::from_iter(values).into();
+                vars.map(Array::<Boxed>::from_iter(names).into(), env)?;
+                env.push(vars);
+            }
             SysOp::FOpen => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let handle = (env.rt.backend)
                     .open_file(path.as_ref(), true)
-                    .map_err(|e| env.error(e))?
+                    .map_err(|e| env.error(e).system())?
                     .value(HandleKind::File(path.into()));
                 env.push(handle);
             }
@@ -1253,7 +1865,7 @@ impl SysOp {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let handle: Value = (env.rt.backend)
                     .create_file(path.as_ref())
-                    .map_err(|e| env.error(e))?
+                    .map_err(|e| env.error(e).system())?
                     .value(HandleKind::File(path.into()));
                 env.push(handle);
             }
@@ -1261,15 +1873,21 @@ impl SysOp {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 (env.rt.backend)
                     .make_dir(path.as_ref())
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::FDelete => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                env.rt.backend.delete(&path).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .delete(&path)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::FTrash => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                env.rt.backend.trash(&path).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .trash(&path)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::ReadStr => {
                 let count = env
@@ -1283,7 +1901,11 @@ impl SysOp {
                     Handle::STDOUT => return Err(env.error("Cannot read from stdout")),
                     Handle::STDERR => return Err(env.error("Cannot read from stderr")),
                     Handle::STDIN => {
-                        let buf = env.rt.backend.scan_stdin(count).map_err(|e| env.error(e))?;
+                        let buf = env
+                            .rt
+                            .backend
+                            .scan_stdin(count)
+                            .map_err(|e| env.error(e).system())?;
                         match String::from_utf8(buf) {
                             Ok(s) => s,
                             Err(e) => {
@@ -1295,14 +1917,14 @@ impl SysOp {
                                         env.rt
                                             .backend
                                             .scan_stdin(Some(1))
-                                            .map_err(|e| env.error(e))?,
+                                            .map_err(|e| env.error(e).system())?,
                                     );
                                     if let Ok(s) = std::str::from_utf8(&rest) {
                                         buf.extend_from_slice(s.as_bytes());
                                         break;
                                     }
                                 }
-                                String::from_utf8(buf).map_err(|e| env.error(e))?
+                                String::from_utf8(buf).map_err(|e| env.error(e).system())?
                             }
                         }
                     }
@@ -1312,7 +1934,7 @@ impl SysOp {
                                 .rt
                                 .backend
                                 .read(handle, count)
-                                .map_err(|e| env.error(e))?;
+                                .map_err(|e| env.error(e).system())?;
                             match String::from_utf8(buf) {
                                 Ok(s) => s,
                                 Err(e) => {
@@ -1324,20 +1946,23 @@ impl SysOp {
                                             env.rt
                                                 .backend
                                                 .read(handle, 1)
-                                                .map_err(|e| env.error(e))?,
+                                                .map_err(|e| env.error(e).system())?,
                                         );
                                         if let Ok(s) = std::str::from_utf8(&rest) {
                                             buf.extend_from_slice(s.as_bytes());
                                             break;
                                         }
                                     }
-                                    String::from_utf8(buf).map_err(|e| env.error(e))?
+                                    String::from_utf8(buf).map_err(|e| env.error(e).system())?
                                 }
                             }
                         } else {
-                            let bytes =
-                                env.rt.backend.read_all(handle).map_err(|e| env.error(e))?;
-                            String::from_utf8(bytes).map_err(|e| env.error(e))?
+                            let bytes = env
+                                .rt
+                                .backend
+                                .read_all(handle)
+                                .map_err(|e| env.error(e).system())?;
+                            String::from_utf8(bytes).map_err(|e| env.error(e).system())?
                         }
                     }
                 };
@@ -1354,15 +1979,22 @@ impl SysOp {
                 let bytes = match handle {
                     Handle::STDOUT => return Err(env.error("Cannot read from stdout")),
                     Handle::STDERR => return Err(env.error("Cannot read from stderr")),
-                    Handle::STDIN => env.rt.backend.scan_stdin(count).map_err(|e| env.error(e))?,
+                    Handle::STDIN => env
+                        .rt
+                        .backend
+                        .scan_stdin(count)
+                        .map_err(|e| env.error(e).system())?,
                     _ => {
                         if let Some(count) = count {
                             env.rt
                                 .backend
                                 .read(handle, count)
-                                .map_err(|e| env.error(e))?
+                                .map_err(|e| env.error(e).system())?
                         } else {
-                            env.rt.backend.read_all(handle).map_err(|e| env.error(e))?
+                            env.rt
+                                .backend
+                                .read_all(handle)
+                                .map_err(|e| env.error(e).system())?
                         }
                     }
                 };
@@ -1392,7 +2024,7 @@ impl SysOp {
                             .rt
                             .backend
                             .scan_until_stdin(&delim_bytes)
-                            .map_err(|e| env.error(e))?;
+                            .map_err(|e| env.error(e).system())?;
                         if is_string {
                             let s = String::from_utf8_lossy(&buffer).into_owned();
                             env.push(s);
@@ -1407,7 +2039,7 @@ impl SysOp {
                                 .rt
                                 .backend
                                 .read_until(handle, &delim)
-                                .map_err(|e| env.error(e))?;
+                                .map_err(|e| env.error(e).system())?;
                             env.push(Array::from(bytes.as_slice()));
                         }
                         Value::Byte(arr) => {
@@ -1416,7 +2048,7 @@ impl SysOp {
                                 .rt
                                 .backend
                                 .read_until(handle, &delim)
-                                .map_err(|e| env.error(e))?;
+                                .map_err(|e| env.error(e).system())?;
                             env.push(Array::from(bytes.as_slice()));
                         }
                         Value::Char(arr) => {
@@ -1425,14 +2057,60 @@ impl SysOp {
                                 .rt
                                 .backend
                                 .read_until(handle, &delim)
-                                .map_err(|e| env.error(e))?;
-                            let s = String::from_utf8(bytes).map_err(|e| env.error(e))?;
+                                .map_err(|e| env.error(e).system())?;
+                            let s = String::from_utf8(bytes).map_err(|e| env.error(e).system())?;
                             env.push(s);
                         }
                         _ => return Err(env.error("Delimiter must be a string or byte array")),
                     },
                 }
             }
+            SysOp::DecompressStream => {
+                let format = env.pop(1)?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let name = format.as_string(env, "Compression format must be a string")?;
+                let format = crate::encode::Compression::from_value(&format, env)?;
+                let handle = (env.rt.backend)
+                    .decompress_stream(handle, format)
+                    .map_err(|e| env.error(e).system())?
+                    .value(HandleKind::Decompressed(name));
+                env.push(handle);
+            }
+            SysOp::ReadLineSample => {
+                let count = env
+                    .pop(1)?
+                    .as_nat(env, "Sample count must be a natural number")?;
+                let mut rng = env.pop(2)?.seeded_rng();
+                let handle = env.pop(3)?.as_handle(env, "")?;
+                let mut read_lines = env
+                    .rt
+                    .backend
+                    .read_lines(handle)
+                    .map_err(|e| env.error(e).system())?;
+                // Reservoir sampling
+                let mut reservoir: Vec<(usize, String)> = Vec::new();
+                let mut i = 0;
+                read_lines(
+                    env,
+                    Box::new(|line, _| {
+                        if reservoir.len() < count {
+                            reservoir.push((i, line));
+                        } else {
+                            let j = rng.gen_range(0..=i);
+                            if j < count {
+                                reservoir[j] = (i, line);
+                            }
+                        }
+                        i += 1;
+                        Ok(())
+                    }),
+                )?;
+                reservoir.sort_unstable_by_key(|(i, _)| *i);
+                let lines: ecow::EcoVec<Boxed> = (reservoir.into_iter())
+                    .map(|(_, line)| Boxed(line.into()))
+                    .collect();
+                env.push(Array::from(lines));
+            }
             SysOp::Write => {
                 let data = env.pop(1)?;
                 let handle = env.pop(2)?.as_handle(env, "")?;
@@ -1448,18 +2126,18 @@ impl SysOp {
                         .rt
                         .backend
                         .print_str_stdout(&String::from_utf8_lossy(&bytes))
-                        .map_err(|e| env.error(e))?,
+                        .map_err(|e| env.error(e).system())?,
                     Handle::STDERR => env
                         .rt
                         .backend
                         .print_str_stderr(&String::from_utf8_lossy(&bytes))
-                        .map_err(|e| env.error(e))?,
+                        .map_err(|e| env.error(e).system())?,
                     Handle::STDIN => return Err(env.error("Cannot write to stdin")),
                     _ => env
                         .rt
                         .backend
                         .write(handle, &bytes)
-                        .map_err(|e| env.error(e))?,
+                        .map_err(|e| env.error(e).system())?,
                 }
             }
             SysOp::FReadAllStr => {
@@ -1471,8 +2149,8 @@ impl SysOp {
                         "example.txt" => Ok(EXAMPLE_TXT.as_bytes().to_vec()),
                         _ => Err(e),
                     })
-                    .map_err(|e| env.error(e))?;
-                let s = String::from_utf8(bytes).map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+                let s = String::from_utf8(bytes).map_err(|e| env.error(e).system())?;
                 env.push(s);
             }
             SysOp::FReadAllBytes => {
@@ -1484,7 +2162,7 @@ impl SysOp {
                         "example.txt" => Ok(EXAMPLE_TXT.as_bytes().to_vec()),
                         _ => Err(e),
                     })
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 let bytes = bytes.into_iter().map(Into::into);
                 env.push(Array::<u8>::from_iter(bytes));
             }
@@ -1512,7 +2190,22 @@ impl SysOp {
                             Err(e)
                         }
                     })
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::FSave => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let bytes = env.pop(2)?.to_binary(env)?;
+                (env.rt.backend)
+                    .file_write_all(path.as_ref(), &bytes)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::FLoad => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let bytes = (env.rt.backend)
+                    .file_read_all(path.as_ref())
+                    .map_err(|e| env.error(e).system())?;
+                let val = Value::from_binary(&bytes, env)?;
+                env.push(val);
             }
             SysOp::FExists => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
@@ -1521,29 +2214,91 @@ impl SysOp {
             }
             SysOp::FListDir => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                let paths = env.rt.backend.list_dir(&path).map_err(|e| env.error(e))?;
+                let paths = env
+                    .rt
+                    .backend
+                    .list_dir(&path)
+                    .map_err(|e| env.error(e).system())?;
                 env.push(Array::<Boxed>::from_iter(paths));
             }
             SysOp::FIsFile => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                let is_file = env.rt.backend.is_file(&path).map_err(|e| env.error(e))?;
+                let is_file = env
+                    .rt
+                    .backend
+                    .is_file(&path)
+                    .map_err(|e| env.error(e).system())?;
                 env.push(is_file);
             }
+            SysOp::FMetadata => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let meta = (env.rt.backend)
+                    .file_metadata(&path)
+                    .map_err(|e| env.error(e).system())?;
+                let mut val: Value = [
+                    meta.size as f64,
+                    meta.modified.unwrap_or(f64::NAN),
+                    meta.is_dir as u8 as f64,
+                ]
+                .into_iter()
+                .collect();
+                let keys: Array<Boxed> = ["size", "modified", "dir"].into_iter().collect();
+                val.map(keys.into(), env)?;
+                env.push(val);
+            }
+            SysOp::FMove => {
+                let from = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let to = env.pop(2)?.as_string(env, "Path must be a string")?;
+                env.rt
+                    .backend
+                    .rename(&from, &to)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::FGlob => {
+                let pattern = env.pop(1)?.as_string(env, "Pattern must be a string")?;
+                let paths = env
+                    .rt
+                    .backend
+                    .glob(&pattern)
+                    .map_err(|e| env.error(e).system())?;
+                env.push(Array::<Boxed>::from_iter(paths));
+            }
+            SysOp::FWatch => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let handle = (env.rt.backend)
+                    .watch(&path)
+                    .map_err(|e| env.error(e).system())?
+                    .value(HandleKind::FileWatcher(path.into()));
+                env.push(handle);
+            }
+            SysOp::FWatchRead => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let paths = (env.rt.backend)
+                    .watch_changes(handle)
+                    .map_err(|e| env.error(e).system())?;
+                env.push(Array::<Boxed>::from_iter(paths));
+            }
             SysOp::Invoke => {
                 let path = env.pop(1)?.as_string(env, "Invoke path must be a string")?;
-                env.rt.backend.invoke(&path).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .invoke(&path)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::ImShow => {
                 #[cfg(feature = "image")]
                 {
                     let value = env.pop(1)?;
-                    let image = crate::encode::value_to_image(&value).map_err(|e| env.error(e))?;
+                    let image =
+                        crate::encode::value_to_image(&value).map_err(|e| env.error(e).system())?;
                     (env.rt.backend)
                         .show_image(image, value.meta().label.as_deref())
-                        .map_err(|e| env.error(e))?;
+                        .map_err(|e| env.error(e).system())?;
                 }
                 #[cfg(not(feature = "image"))]
-                return Err(env.error("Image encoding is not supported in this environment"));
+                return Err(env
+                    .error("Image encoding is not supported in this environment")
+                    .system());
             }
             SysOp::GifShow => {
                 #[cfg(feature = "gif")]
@@ -1551,36 +2306,62 @@ impl SysOp {
                     let delay = env.pop(1)?.as_num(env, "Delay must be a number")?;
                     let value = env.pop(2)?;
                     let bytes = crate::encode::value_to_gif_bytes(&value, delay)
-                        .map_err(|e| env.error(e))?;
+                        .map_err(|e| env.error(e).system())?;
                     (env.rt.backend)
                         .show_gif(bytes, value.meta().label.as_deref())
-                        .map_err(|e| env.error(e))?;
+                        .map_err(|e| env.error(e).system())?;
                 }
                 #[cfg(not(feature = "gif"))]
-                return Err(env.error("GIF showing is not supported in this environment"));
+                return Err(env
+                    .error("GIF showing is not supported in this environment")
+                    .system());
+            }
+            SysOp::FramesShow => {
+                #[cfg(feature = "image")]
+                {
+                    let frame_rate = env.pop(1)?.as_num(env, "Frame rate must be a number")?;
+                    let value = env.pop(2)?;
+                    if value.row_count() == 0 {
+                        return Err(env.error("Cannot show an animation with no frames"));
+                    }
+                    let frames = (value.rows())
+                        .map(|row| crate::encode::value_to_image(&row))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| env.error(e).system())?;
+                    (env.rt.backend)
+                        .show_frames(frames, frame_rate, value.meta().label.as_deref())
+                        .map_err(|e| env.error(e).system())?;
+                }
+                #[cfg(not(feature = "image"))]
+                return Err(env
+                    .error("Showing frames is not supported in this environment")
+                    .system());
             }
             SysOp::AudioPlay => {
                 #[cfg(feature = "audio_encode")]
                 {
                     let value = env.pop(1)?;
-                    let bytes = crate::encode::value_to_wav_bytes(
-                        &value,
-                        env.rt.backend.audio_sample_rate(),
-                    )
-                    .map_err(|e| env.error(e))?;
+                    let bytes = crate::encode::value_to_wav_bytes(&value, env.audio_sample_rate())
+                        .map_err(|e| env.error(e).system())?;
                     (env.rt.backend)
                         .play_audio(bytes, value.meta().label.as_deref())
-                        .map_err(|e| env.error(e))?;
+                        .map_err(|e| env.error(e).system())?;
                 }
                 #[cfg(not(feature = "audio_encode"))]
-                return Err(env.error("Audio encoding is not supported in this environment"));
+                return Err(env
+                    .error("Audio encoding is not supported in this environment")
+                    .system());
             }
             SysOp::AudioSampleRate => {
-                let sample_rate = env.rt.backend.audio_sample_rate();
+                let sample_rate = env.audio_sample_rate();
                 env.push(f64::from(sample_rate));
             }
             SysOp::Clip => {
-                let contents = env.rt.backend.clipboard().map_err(|e| env.error(e))?;
+                let contents = env
+                    .rt
+                    .backend
+                    .clipboard()
+                    .map_err(|e| env.error(e).system())?;
                 env.push(contents);
             }
             SysOp::Sleep => {
@@ -1596,14 +2377,21 @@ impl SysOp {
                     let max = limit - elapsed;
                     seconds = seconds.min(max);
                 }
-                env.rt.backend.sleep(seconds).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .sleep(seconds)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::TcpListen => {
                 let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
                 let handle = (env.rt.backend)
                     .tcp_listen(&addr)
-                    .map_err(|e| env.error(e))?;
-                let sock_addr = env.rt.backend.tcp_addr(handle).map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+                let sock_addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
                 let handle = handle.value(HandleKind::TcpListener(sock_addr));
                 env.push(handle);
             }
@@ -1617,8 +2405,12 @@ impl SysOp {
                     .into_bytes(env, "Key must be a byte or character array")?;
                 let handle = (env.rt.backend)
                     .tls_listen(&addr, &cert, &key)
-                    .map_err(|e| env.error(e))?;
-                let sock_addr = env.rt.backend.tcp_addr(handle).map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+                let sock_addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
                 let handle = handle.value(HandleKind::TlsListener(sock_addr));
                 env.push(handle);
             }
@@ -1626,10 +2418,10 @@ impl SysOp {
                 let handle = env.pop(1)?.as_handle(env, "")?;
                 let handle = (env.rt.backend)
                     .tcp_accept(handle)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 let addr = (env.rt.backend)
                     .tcp_addr(handle)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 let handle = handle.value(HandleKind::TcpSocket(addr));
                 env.push(handle);
             }
@@ -1637,8 +2429,12 @@ impl SysOp {
                 let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
                 let handle = (env.rt.backend)
                     .tcp_connect(&addr)
-                    .map_err(|e| env.error(e))?;
-                let sock_addr = env.rt.backend.tcp_addr(handle).map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+                let sock_addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
                 let handle = handle.value(HandleKind::TcpSocket(sock_addr));
                 env.push(handle);
             }
@@ -1646,21 +2442,145 @@ impl SysOp {
                 let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
                 let handle = (env.rt.backend)
                     .tls_connect(&addr)
-                    .map_err(|e| env.error(e))?;
-                let sock_addr = env.rt.backend.tcp_addr(handle).map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+                let sock_addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
                 let handle = handle.value(HandleKind::TlsSocket(sock_addr));
                 env.push(handle);
             }
             SysOp::TcpAddr => {
                 let handle = env.pop(1)?.as_handle(env, "")?;
-                let addr = env.rt.backend.tcp_addr(handle).map_err(|e| env.error(e))?;
+                let addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
+                env.push(addr.to_string());
+            }
+            SysOp::UdpBind => {
+                let addr = env.pop(1)?.as_string(env, "Address must be a string")?;
+                let handle = env
+                    .rt
+                    .backend
+                    .udp_bind(&addr)
+                    .map_err(|e| env.error(e).system())?;
+                let sock_addr = env
+                    .rt
+                    .backend
+                    .tcp_addr(handle)
+                    .map_err(|e| env.error(e).system())?;
+                let handle = handle.value(HandleKind::UdpSocket(sock_addr));
+                env.push(handle);
+            }
+            SysOp::UdpSend => {
+                let data = match env.pop(1)? {
+                    val @ Value::Char(_) => {
+                        val.as_string(env, "Data must be a string")?.into_bytes()
+                    }
+                    val => val.as_bytes(env, "Data must be a string or byte array")?,
+                };
+                let addr = env.pop(2)?.as_string(env, "Address must be a string")?;
+                let handle = env.pop(3)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .udp_send(handle, &data, &addr)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::UdpReceive => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let (data, addr) = (env.rt.backend)
+                    .udp_receive(handle)
+                    .map_err(|e| env.error(e).system())?;
                 env.push(addr.to_string());
+                env.push(Array::<u8>::from(data.as_slice()));
+            }
+            SysOp::WsConnect => {
+                let url = env.pop(1)?.as_string(env, "URL must be a string")?;
+                let handle = env
+                    .rt
+                    .backend
+                    .ws_connect(&url)
+                    .map_err(|e| env.error(e).system())?;
+                env.push(handle.value(HandleKind::WebSocket(url)));
+            }
+            SysOp::WsSend => {
+                let message = match env.pop(1)? {
+                    val @ Value::Char(_) => {
+                        WebSocketMessage::Text(val.as_string(env, "Message must be a string")?)
+                    }
+                    val => WebSocketMessage::Binary(
+                        val.as_bytes(env, "Message must be a string or byte array")?,
+                    ),
+                };
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .ws_send(handle, message)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::WsReceive => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let message = (env.rt.backend)
+                    .ws_receive(handle)
+                    .map_err(|e| env.error(e).system())?;
+                match message {
+                    Some(WebSocketMessage::Text(s)) => env.push(s),
+                    Some(WebSocketMessage::Binary(bytes)) => {
+                        env.push(Array::<u8>::from(bytes.as_slice()))
+                    }
+                    None => env.push(0u8),
+                }
+            }
+            SysOp::HttpRequest => {
+                let method = env.pop(1)?.as_string(env, "Method must be a string")?;
+                let url = env.pop(2)?.as_string(env, "URL must be a string")?;
+                let headers = env.pop(3)?;
+                let headers = if headers.row_count() == 0 && !headers.is_map() {
+                    Vec::new()
+                } else {
+                    let (keys, values) = headers.unmap(env)?;
+                    let keys = keys.as_strings(env, "Header names must be strings")?;
+                    let values = values.as_strings(env, "Header values must be strings")?;
+                    keys.into_iter().zip(values).collect()
+                };
+                let body = match env.pop(4)? {
+                    val @ Value::Char(_) => {
+                        val.as_string(env, "Body must be a string")?.into_bytes()
+                    }
+                    val => val.as_bytes(env, "Body must be a string or byte array")?,
+                };
+                let request = HttpRequest {
+                    method,
+                    url,
+                    headers,
+                    body,
+                    timeout: env.rt.http_timeout,
+                };
+                let response = (env.rt.backend)
+                    .http_request(request)
+                    .map_err(|e| env.error(e).system())?;
+                let mut keys = EcoVec::with_capacity(response.headers.len());
+                let mut values = EcoVec::with_capacity(response.headers.len());
+                for (name, value) in response.headers {
+                    keys.push(Boxed(name.into()));
+                    values.push(Boxed(value.into()));
+                }
+                let mut headers: Value = Array::from(values).into();
+                headers.map(Array::from(keys).into(), env)?;
+                let body = match String::from_utf8(response.body) {
+                    Ok(s) => Value::from(s),
+                    Err(e) => Array::<u8>::from(e.into_bytes().as_slice()).into(),
+                };
+                env.push(body);
+                env.push(headers);
+                env.push(response.status as f64);
             }
             SysOp::TcpSetNonBlocking => {
                 let handle = env.pop(1)?.as_handle(env, "")?;
                 (env.rt.backend)
                     .tcp_set_non_blocking(handle, true)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::TcpSetReadTimeout => {
                 let timeout = env.pop(1)?.as_num(env, "Timeout must be a number")?.abs();
@@ -1672,7 +2592,7 @@ impl SysOp {
                 let handle = env.pop(2)?.as_handle(env, "")?;
                 (env.rt.backend)
                     .tcp_set_read_timeout(handle, timeout)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::TcpSetWriteTimeout => {
                 let timeout = env.pop(1)?.as_num(env, "Timeout must be a number")?.abs();
@@ -1684,18 +2604,65 @@ impl SysOp {
                 let handle = env.pop(2)?.as_handle(env, "")?;
                 (env.rt.backend)
                     .tcp_set_write_timeout(handle, timeout)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::SerialOpen => {
+                let port = env.pop(1)?.as_string(env, "Port name must be a string")?;
+                let baud_rate = env
+                    .pop(2)?
+                    .as_nat(env, "Baud rate must be a natural number")?;
+                let baud_rate = u32::try_from(baud_rate)
+                    .map_err(|_| env.error(format!("Baud rate {baud_rate} is too large")))?;
+                let handle = (env.rt.backend)
+                    .serial_open(&port, baud_rate)
+                    .map_err(|e| env.error(e).system())?
+                    .value(HandleKind::SerialPort(port));
+                env.push(handle);
+            }
+            SysOp::SerialList => {
+                let ports = env
+                    .rt
+                    .backend
+                    .serial_list()
+                    .map_err(|e| env.error(e).system())?;
+                env.push(Array::<Boxed>::from_iter(ports));
+            }
+            SysOp::SerialSetBaudRate => {
+                let baud_rate = env
+                    .pop(1)?
+                    .as_nat(env, "Baud rate must be a natural number")?;
+                let baud_rate = u32::try_from(baud_rate)
+                    .map_err(|_| env.error(format!("Baud rate {baud_rate} is too large")))?;
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .serial_set_baud_rate(handle, baud_rate)
+                    .map_err(|e| env.error(e).system())?;
+            }
+            SysOp::SerialSetTimeout => {
+                let timeout = env.pop(1)?.as_num(env, "Timeout must be a number")?.abs();
+                let timeout = if timeout.is_infinite() {
+                    None
+                } else {
+                    Some(Duration::from_secs_f64(timeout))
+                };
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                (env.rt.backend)
+                    .serial_set_timeout(handle, timeout)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::Close => {
                 let handle = env.pop(1)?.as_handle(env, "")?;
-                env.rt.backend.close(handle).map_err(|e| env.error(e))?;
+                env.rt
+                    .backend
+                    .close(handle)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::RunInherit => {
                 let (command, args) = value_to_command(&env.pop(1)?, env)?;
                 let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
                 let code = (env.rt.backend)
                     .run_command_inherit(&command, &args)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 env.push(code);
             }
             SysOp::RunCapture => {
@@ -1703,7 +2670,7 @@ impl SysOp {
                 let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
                 let (code, stdout, stderr) = (env.rt.backend)
                     .run_command_capture(&command, &args)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 env.push(stderr);
                 env.push(stdout);
                 env.push(code);
@@ -1713,34 +2680,55 @@ impl SysOp {
                 let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
                 let handles = (env.rt.backend)
                     .run_command_stream(&command, &args)
-                    .map_err(|e| env.error(e))?;
-                for (handle, kind) in handles
-                    .into_iter()
-                    .zip([
-                        HandleKind::ChildStdin,
-                        HandleKind::ChildStdout,
-                        HandleKind::ChildStderr,
-                    ])
-                    .rev()
-                {
-                    env.push(handle.value(kind(command.clone())));
+                    .map_err(|e| env.error(e).system())?;
+                push_command_stream_handles(env, handles, command);
+            }
+            SysOp::RunStreamEnv => {
+                let (command, args) = value_to_command(&env.pop(1)?, env)?;
+                let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
+                let vars = env.pop(2)?;
+                if !vars.is_map() {
+                    return Err(env.error("Environment variables must be a map"));
                 }
+                let mut var_strings = Vec::new();
+                for (key, value) in vars.map_kv() {
+                    let key = key.as_string(env, "Environment variable names must be strings")?;
+                    let value =
+                        value.as_string(env, "Environment variable values must be strings")?;
+                    var_strings.push((key, value));
+                }
+                let vars: Vec<_> = (var_strings.iter())
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+                let handles = (env.rt.backend)
+                    .run_command_stream_env(&command, &args, &vars)
+                    .map_err(|e| env.error(e).system())?;
+                push_command_stream_handles(env, handles, command);
+            }
+            SysOp::RunWait => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let code = (env.rt.backend)
+                    .wait_command(handle)
+                    .map_err(|e| env.error(e).system())?;
+                env.push(code);
             }
             SysOp::ChangeDirectory => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 (env.rt.backend)
                     .change_directory(&path)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::WebcamCapture => {
                 let index = env.pop(1)?.as_nat(env, "Webcam index must be an integer")?;
                 let _image = (env.rt.backend)
                     .webcam_capture(index)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 #[cfg(feature = "image")]
                 env.push(crate::encode::rgb_image_to_array(_image));
                 #[cfg(not(feature = "image"))]
-                return Err(env.error("Webcam capture is not supported in this environment"));
+                return Err(env
+                    .error("Webcam capture is not supported in this environment")
+                    .system());
             }
             SysOp::Ffi => {
                 let sig_def = env.pop(1)?;
@@ -1768,27 +2756,27 @@ impl SysOp {
                 let result_ty = (sig_frags.next().unwrap())
                     .as_string(env, "FFI result type must be a string")?
                     .parse::<FfiType>()
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 let name =
                     (sig_frags.next().unwrap()).as_string(env, "FFI name must be a string")?;
                 let arg_tys = sig_frags
                     .map(|frag| {
                         frag.as_string(env, "FFI argument type must be a string")
-                            .and_then(|ty| ty.parse::<FfiType>().map_err(|e| env.error(e)))
+                            .and_then(|ty| ty.parse::<FfiType>().map_err(|e| env.error(e).system()))
                     })
                     .collect::<UiuaResult<Vec<_>>>()?;
                 let args = env.pop(2)?;
                 let args: Vec<Value> = args.into_rows().map(Value::unpacked).collect();
                 let result = (env.rt.backend)
                     .ffi(&file_name, result_ty, &name, &arg_tys, &args)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 env.push(result);
             }
             SysOp::MemCopy => {
                 let ty = env
                     .pop(1)?
                     .as_string(env, "Pointer copy type must be a string")?;
-                let ty = ty.parse::<FfiType>().map_err(|e| env.error(e))?;
+                let ty = ty.parse::<FfiType>().map_err(|e| env.error(e).system())?;
                 let ptr = env
                     .pop(2)?
                     .meta()
@@ -1800,7 +2788,7 @@ impl SysOp {
                     .as_nat(env, "Copied length must be a non-negative integer")?;
                 let value = (env.rt.backend)
                     .mem_copy(ty, ptr, len)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 env.push(value);
             }
             SysOp::MemFree => {
@@ -1810,10 +2798,17 @@ impl SysOp {
                     .pointer
                     .map(|p| p.get())
                     .ok_or_else(|| env.error("Freed pointer must be a pointer value"))?;
-                (env.rt.backend).mem_free(ptr).map_err(|e| env.error(e))?;
+                (env.rt.backend)
+                    .mem_free(ptr)
+                    .map_err(|e| env.error(e).system())?;
             }
             SysOp::Breakpoint => {
-                if !env.rt.backend.breakpoint(env).map_err(|e| env.error(e))? {
+                if !env
+                    .rt
+                    .backend
+                    .breakpoint(env)
+                    .map_err(|e| env.error(e).system())?
+                {
                     return Err(UiuaErrorKind::Interrupted.into());
                 }
             }
@@ -1844,7 +2839,7 @@ impl SysOp {
                     .rt
                     .backend
                     .read_lines(handle)
-                    .map_err(|e| env.error(e))?;
+                    .map_err(|e| env.error(e).system())?;
                 let sig = f.sig;
                 if sig.args == 0 {
                     return env.exec(f);
@@ -1871,6 +2866,49 @@ impl SysOp {
                     env.push(val);
                 }
             }
+            SysOp::ReadChunks => {
+                let [f] = get_ops(ops, env)?;
+                let size = env
+                    .pop(1)?
+                    .as_nat(env, "Chunk size must be a natural number")?;
+                if size == 0 {
+                    return Err(env.error("Chunk size must be greater than 0"));
+                }
+                let handle = env.pop(2)?.as_handle(env, "")?;
+                let sig = f.sig;
+                if sig.args == 0 {
+                    return Err(env.error(format!(
+                        "&rch's function must take at least 1 argument, \
+                        but its signature is {sig}"
+                    )));
+                }
+                let acc_count = sig.args - 1;
+                let out_count = sig.outputs.saturating_sub(acc_count);
+                let mut outputs = multi_output(out_count, Vec::new());
+                env.without_fill(|env| -> UiuaResult {
+                    loop {
+                        let bytes = match handle {
+                            Handle::STDOUT => return Err(env.error("Cannot read from stdout")),
+                            Handle::STDERR => return Err(env.error("Cannot read from stderr")),
+                            Handle::STDIN => env.rt.backend.scan_stdin(Some(size)),
+                            _ => env.rt.backend.read(handle, size),
+                        }
+                        .map_err(|e| env.error(e).system())?;
+                        if bytes.is_empty() {
+                            break Ok(());
+                        }
+                        env.push(Array::from(bytes.as_slice()));
+                        env.exec(f.clone())?;
+                        for i in 0..out_count {
+                            outputs[i].push(env.pop("read chunks output")?);
+                        }
+                    }
+                })?;
+                for rows in outputs.into_iter().rev() {
+                    let val = Value::from_row_values(rows, env)?;
+                    env.push(val);
+                }
+            }
             SysOp::AudioStream => {
                 let [f] = get_ops(ops, env)?;
                 let push_time = f.sig.args > 0;
@@ -1919,7 +2957,7 @@ impl SysOp {
                         ))),
                     }
                 }));
-                res.map_err(|e| env.error(e))?;
+                res.map_err(|e| env.error(e).system())?;
             }
             prim => {
                 return Err(env.error(if prim.modifier_args().is_some() {
@@ -1941,6 +2979,102 @@ impl SysOp {
     }
 }
 
+fn glob_dir<B: SysBackend + ?Sized>(
+    backend: &B,
+    dir: &str,
+    parts: &[&str],
+    paths: &mut Vec<String>,
+) -> Result<(), String> {
+    let Some((&part, rest)) = parts.split_first() else {
+        return Ok(());
+    };
+    let list_path = if dir.is_empty() { "." } else { dir };
+    let join = |name: &str| {
+        if dir.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{name}", dir.trim_end_matches('/'))
+        }
+    };
+    if part == "**" {
+        // Match no directories
+        if rest.is_empty() {
+            if !dir.is_empty() {
+                paths.push(dir.into());
+            }
+        } else {
+            glob_dir(backend, dir, rest, paths)?;
+        }
+        // Match one or more directories
+        for entry in backend.list_dir(list_path)? {
+            let name = entry.rsplit(['/', '\\']).next().unwrap_or(&entry);
+            let path = join(name);
+            if !backend.is_file(&path).unwrap_or(true) {
+                glob_dir(backend, &path, parts, paths)?;
+            } else if rest.is_empty() {
+                paths.push(path);
+            }
+        }
+    } else {
+        for entry in backend.list_dir(list_path)? {
+            let name = entry.rsplit(['/', '\\']).next().unwrap_or(&entry);
+            if !glob_match(part, name) {
+                continue;
+            }
+            let path = join(name);
+            if rest.is_empty() {
+                paths.push(path);
+            } else if !backend.is_file(&path).unwrap_or(true) {
+                glob_dir(backend, &path, rest, paths)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Match a single path component against a pattern with `*` and `?` wildcards
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    p = sp + 1;
+                    n = sn + 1;
+                    star = Some((sp, sn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn push_command_stream_handles(env: &mut Uiua, handles: [Handle; 3], command: String) {
+    for (handle, kind) in handles
+        .into_iter()
+        .zip([
+            HandleKind::ChildStdin,
+            HandleKind::ChildStdout,
+            HandleKind::ChildStderr,
+        ])
+        .rev()
+    {
+        env.push(handle.value(kind(command.clone())));
+    }
+}
+
 fn value_to_command(value: &Value, env: &Uiua) -> UiuaResult<(String, Vec<String>)> {
     let mut strings = Vec::new();
     match value {
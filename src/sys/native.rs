@@ -12,7 +12,7 @@ use std::{
         Arc,
     },
     thread::sleep,
-    time::Duration,
+    time::{Duration, UNIX_EPOCH},
 };
 
 use colored::Colorize;
@@ -20,7 +20,8 @@ use dashmap::DashMap;
 use once_cell::sync::Lazy;
 
 use crate::{
-    terminal_size, GitTarget, Handle, ReadLinesFn, ReadLinesReturnFn, Span, SysBackend, Uiua, Value,
+    encode::Compression, terminal_size, FileMetadata, GitTarget, Handle, ReadLinesFn,
+    ReadLinesReturnFn, Span, SysBackend, Uiua, Value,
 };
 
 /// The default native system backend
@@ -38,6 +39,14 @@ struct GlobalNativeSys {
     tls_listeners: DashMap<Handle, TlsListener>,
     tcp_sockets: DashMap<Handle, TcpStream>,
     tls_sockets: DashMap<Handle, TlsSocket>,
+    udp_sockets: DashMap<Handle, UdpSocket>,
+    #[cfg(feature = "websocket")]
+    websockets: DashMap<Handle, WebSocket>,
+    #[cfg(feature = "notify")]
+    watchers: DashMap<Handle, FileWatcher>,
+    decompressors: DashMap<Handle, Decompressor>,
+    #[cfg(feature = "serial")]
+    serial_ports: DashMap<Handle, SerialPort>,
     #[cfg(feature = "webcam")]
     cam_channels: DashMap<usize, WebcamChannel>,
     hostnames: DashMap<Handle, String>,
@@ -60,17 +69,57 @@ enum SysStream<'a> {
     ChildStderr(dashmap::mapref::one::RefMut<'a, Handle, ChildStream<BufReader<ChildStderr>>>),
     TcpSocket(dashmap::mapref::one::Ref<'a, Handle, TcpStream>),
     TlsSocket(dashmap::mapref::one::Ref<'a, Handle, TlsSocket>),
+    Decompressor(Decompressor),
+    #[cfg(feature = "serial")]
+    SerialPort(SerialPort),
+}
+
+type Decompressor = Arc<parking_lot::Mutex<BufReader<Box<dyn Read + Send>>>>;
+#[cfg(feature = "serial")]
+type SerialPort = Arc<parking_lot::Mutex<Box<dyn serialport::SerialPort>>>;
+
+/// Reads from a serial port, treating a timeout as the end of the stream
+#[cfg(feature = "serial")]
+struct SerialReader<'a>(&'a mut dyn serialport::SerialPort);
+
+#[cfg(feature = "serial")]
+impl Read for SerialReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf) {
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(0),
+            res => res,
+        }
+    }
+}
+
+/// Reads from a stream through the backend so that it can be wrapped in a decompressor
+struct HandleReader(Handle);
+
+impl Read for HandleReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes = NativeSys
+            .read(self.0, buf.len())
+            .map_err(std::io::Error::other)?;
+        buf[..bytes.len()].copy_from_slice(&bytes);
+        Ok(bytes.len())
+    }
+}
+
+#[cfg(feature = "notify")]
+struct FileWatcher {
+    _watcher: notify::RecommendedWatcher,
+    recv: crossbeam_channel::Receiver<notify::Result<notify::Event>>,
 }
 
 struct ChildStream<T> {
     stream: T,
-    child: Arc<Child>,
+    child: Arc<parking_lot::Mutex<Child>>,
 }
 
 impl<T> Drop for ChildStream<T> {
     fn drop(&mut self) {
         if let Some(child) = Arc::get_mut(&mut self.child) {
-            _ = child.kill();
+            _ = child.get_mut().kill();
         }
     }
 }
@@ -200,6 +249,45 @@ struct TlsListener {
     config: std::sync::Arc<rustls::ServerConfig>,
 }
 
+#[cfg(feature = "websocket")]
+type WebSocket = tungstenite::WebSocket<WebSocketStream>;
+
+#[cfg(feature = "websocket")]
+enum WebSocketStream {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+#[cfg(feature = "websocket")]
+impl Read for WebSocketStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            WebSocketStream::Plain(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            WebSocketStream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl Write for WebSocketStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            WebSocketStream::Plain(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            WebSocketStream::Tls(stream) => stream.write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            WebSocketStream::Plain(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            WebSocketStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
 impl Default for GlobalNativeSys {
     fn default() -> Self {
         Self {
@@ -213,6 +301,14 @@ impl Default for GlobalNativeSys {
             tls_listeners: DashMap::new(),
             tcp_sockets: DashMap::new(),
             tls_sockets: DashMap::new(),
+            udp_sockets: DashMap::new(),
+            #[cfg(feature = "websocket")]
+            websockets: DashMap::new(),
+            #[cfg(feature = "notify")]
+            watchers: DashMap::new(),
+            decompressors: DashMap::new(),
+            #[cfg(feature = "serial")]
+            serial_ports: DashMap::new(),
             #[cfg(feature = "webcam")]
             cam_channels: DashMap::new(),
             hostnames: DashMap::new(),
@@ -241,7 +337,21 @@ impl GlobalNativeSys {
                 && !self.tcp_listeners.contains_key(&handle)
                 && !self.tcp_sockets.contains_key(&handle)
                 && !self.tls_sockets.contains_key(&handle)
+                && !self.udp_sockets.contains_key(&handle)
+                && !self.decompressors.contains_key(&handle)
             {
+                #[cfg(feature = "websocket")]
+                if self.websockets.contains_key(&handle) {
+                    continue;
+                }
+                #[cfg(feature = "notify")]
+                if self.watchers.contains_key(&handle) {
+                    continue;
+                }
+                #[cfg(feature = "serial")]
+                if self.serial_ports.contains_key(&handle) {
+                    continue;
+                }
                 return handle;
             }
         }
@@ -260,7 +370,13 @@ impl GlobalNativeSys {
             SysStream::TcpSocket(socket)
         } else if let Some(tls_socket) = self.tls_sockets.get(&handle) {
             SysStream::TlsSocket(tls_socket)
+        } else if let Some(decompressor) = self.decompressors.get(&handle) {
+            SysStream::Decompressor(decompressor.clone())
         } else {
+            #[cfg(feature = "serial")]
+            if let Some(port) = self.serial_ports.get(&handle) {
+                return Ok(SysStream::SerialPort(port.clone()));
+            }
             return Err("Invalid stream handle".to_string());
         })
     }
@@ -372,8 +488,10 @@ impl SysBackend for NativeSys {
             return Ok(Vec::new());
         }
         Ok(if let Some(count) = count {
-            let mut buffer = vec![0; count];
-            stdin().read_exact(&mut buffer).map_err(|e| e.to_string())?;
+            let mut buffer = Vec::with_capacity(count);
+            (stdin().lock().take(count as u64))
+                .read_to_end(&mut buffer)
+                .map_err(|e| e.to_string())?;
             buffer
         } else {
             let mut buffer = Vec::new();
@@ -409,9 +527,32 @@ impl SysBackend for NativeSys {
     fn get_raw_mode(&self) -> Result<bool, String> {
         Ok(rawrrr::is_raw())
     }
+    fn set_cursor(&self, row: usize, col: usize) -> Result<(), String> {
+        self.print_str_stdout(&format!("\x1b[{};{}H", row + 1, col + 1))
+    }
+    fn clear_terminal(&self) -> Result<(), String> {
+        self.print_str_stdout("\x1b[2J\x1b[H")
+    }
+    fn set_text_color(&self, color: Option<[u8; 3]>) -> Result<(), String> {
+        if let Some([r, g, b]) = color {
+            self.print_str_stdout(&format!("\x1b[38;2;{r};{g};{b}m"))
+        } else {
+            self.print_str_stdout("\x1b[39m")
+        }
+    }
     fn var(&self, name: &str) -> Option<String> {
         env::var(name).ok()
     }
+    fn vars(&self) -> Vec<(String, String)> {
+        env::vars_os()
+            .map(|(name, value)| {
+                (
+                    name.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .collect()
+    }
     fn file_exists(&self, path: &str) -> bool {
         fs::metadata(path).is_ok()
     }
@@ -428,6 +569,54 @@ impl SysBackend for NativeSys {
         }
         Ok(paths)
     }
+    fn file_metadata(&self, path: &str) -> Result<FileMetadata, String> {
+        let meta = fs::metadata(path).map_err(|e| e.to_string())?;
+        Ok(FileMetadata {
+            size: meta.len(),
+            modified: (meta.modified().ok())
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|dur| dur.as_secs_f64()),
+            is_dir: meta.is_dir(),
+        })
+    }
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        fs::rename(from, to).map_err(|e| e.to_string())
+    }
+    #[cfg(feature = "notify")]
+    fn watch(&self, path: &str) -> Result<Handle, String> {
+        use notify::{RecursiveMode, Watcher};
+        let (send, recv) = crossbeam_channel::unbounded();
+        let mut watcher = notify::recommended_watcher(move |res| _ = send.send(res))
+            .map_err(|e| e.to_string())?;
+        (watcher.watch(Path::new(path), RecursiveMode::Recursive)).map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        (NATIVE_SYS.watchers).insert(
+            handle,
+            FileWatcher {
+                _watcher: watcher,
+                recv,
+            },
+        );
+        Ok(handle)
+    }
+    #[cfg(feature = "notify")]
+    fn watch_changes(&self, handle: Handle) -> Result<Vec<String>, String> {
+        use notify::EventKind;
+        let watcher = (NATIVE_SYS.watchers.get(&handle)).ok_or("Invalid watcher handle")?;
+        let mut paths = Vec::new();
+        for res in watcher.recv.try_iter() {
+            let event = res.map_err(|e| e.to_string())?;
+            if let EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) = event.kind {
+                for path in event.paths {
+                    let path: String = path.to_string_lossy().into();
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+            }
+        }
+        Ok(paths)
+    }
     fn open_file(&self, path: &Path, write: bool) -> Result<Handle, String> {
         let handle = NATIVE_SYS.new_handle();
         let file = OpenOptions::new()
@@ -501,6 +690,20 @@ impl SysBackend for NativeSys {
                 buf.truncate(n);
                 buf
             }
+            SysStream::Decompressor(decompressor) => {
+                let mut buf = vec![0; len];
+                let n = (decompressor.lock().read(&mut buf)).map_err(|e| e.to_string())?;
+                buf.truncate(n);
+                buf
+            }
+            #[cfg(feature = "serial")]
+            SysStream::SerialPort(port) => {
+                let mut buf = vec![0; len];
+                let mut port = port.lock();
+                let n = (SerialReader(&mut **port).read(&mut buf)).map_err(|e| e.to_string())?;
+                buf.truncate(n);
+                buf
+            }
         })
     }
     fn read_all(&self, handle: Handle) -> Result<Vec<u8>, String> {
@@ -537,10 +740,32 @@ impl SysBackend for NativeSys {
                 ((&mut &*socket).read_to_end(&mut buf)).map_err(|e| e.to_string())?;
                 buf
             }
+            SysStream::Decompressor(decompressor) => {
+                let mut buf = Vec::new();
+                (decompressor.lock().read_to_end(&mut buf)).map_err(|e| e.to_string())?;
+                buf
+            }
+            #[cfg(feature = "serial")]
+            SysStream::SerialPort(port) => {
+                let mut buf = Vec::new();
+                let mut port = port.lock();
+                (SerialReader(&mut **port).read_to_end(&mut buf)).map_err(|e| e.to_string())?;
+                buf
+            }
         })
     }
     fn read_lines<'a>(&self, handle: Handle) -> Result<ReadLinesReturnFn<'a>, String> {
         Ok(Box::new(move |env: &mut Uiua, mut f: ReadLinesFn| {
+            if handle == Handle::STDIN {
+                if !NATIVE_SYS.output_enabled.load(atomic::Ordering::Relaxed) {
+                    return Ok(());
+                }
+                for line in stdin().lock().lines() {
+                    let line = line.map_err(|e| env.error(format!("Error reading line: {e}")))?;
+                    f(line, env)?;
+                }
+                return Ok(());
+            }
             match NATIVE_SYS.get_stream(handle).map_err(|e| env.error(e))? {
                 SysStream::File(mut file) => {
                     for line in (&mut *file).lines() {
@@ -578,6 +803,22 @@ impl SysBackend for NativeSys {
                         f(line, env)?;
                     }
                 }
+                SysStream::Decompressor(decompressor) => {
+                    for line in (&mut *decompressor.lock()).lines() {
+                        let line =
+                            line.map_err(|e| env.error(format!("Error reading line: {e}")))?;
+                        f(line, env)?;
+                    }
+                }
+                #[cfg(feature = "serial")]
+                SysStream::SerialPort(port) => {
+                    let mut port = port.lock();
+                    for line in BufReader::new(SerialReader(&mut **port)).lines() {
+                        let line =
+                            line.map_err(|e| env.error(format!("Error reading line: {e}")))?;
+                        f(line, env)?;
+                    }
+                }
             }
             Ok(())
         }))
@@ -607,6 +848,12 @@ impl SysBackend for NativeSys {
             SysStream::TlsSocket(socket) => {
                 (&mut &*socket).write_all(conts).map_err(|e| e.to_string())
             }
+            SysStream::Decompressor(_) => Err("Cannot write to a decompressed stream".into()),
+            #[cfg(feature = "serial")]
+            SysStream::SerialPort(port) => {
+                let mut port = port.lock();
+                (port.write_all(conts).and_then(|_| port.flush())).map_err(|e| e.to_string())
+            }
         }
     }
     #[cfg(feature = "clipboard")]
@@ -743,6 +990,12 @@ impl SysBackend for NativeSys {
             Err(e) => Err(format!("Failed to initialize audio output stream: {e}").to_string()),
         }
     }
+    fn os_random_bytes(&self, bytes: &mut [u8]) -> Result<(), String> {
+        use rand::RngCore;
+        rand::rngs::OsRng
+            .try_fill_bytes(bytes)
+            .map_err(|e| e.to_string())
+    }
     #[cfg(feature = "audio")]
     fn audio_sample_rate(&self) -> u32 {
         hodaun::default_output_device()
@@ -898,15 +1151,52 @@ impl SysBackend for NativeSys {
         );
         Ok(handle)
     }
+    #[cfg(feature = "http")]
+    fn http_request(&self, request: crate::HttpRequest) -> Result<crate::HttpResponse, String> {
+        let mut req = ureq::request(&request.method, &request.url);
+        if let Some(timeout) = request.timeout {
+            req = req.timeout(timeout);
+        }
+        for (name, value) in &request.headers {
+            req = req.set(name, value);
+        }
+        let res = if request.body.is_empty() {
+            req.call()
+        } else {
+            req.send_bytes(&request.body)
+        };
+        let res = match res {
+            Ok(res) | Err(ureq::Error::Status(_, res)) => res,
+            Err(e) => return Err(e.to_string()),
+        };
+        let status = res.status();
+        let headers = (res.headers_names().into_iter())
+            .filter_map(|name| {
+                let value = res.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        let mut body = Vec::new();
+        (res.into_reader().read_to_end(&mut body)).map_err(|e| e.to_string())?;
+        Ok(crate::HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
     fn tcp_addr(&self, handle: Handle) -> Result<SocketAddr, String> {
         (NATIVE_SYS.get_tcp_stream(handle, |s| s.peer_addr()))
             .or_else(|| NATIVE_SYS.get_tcp_listener(handle, |l| l.local_addr()))
+            .or_else(|| (NATIVE_SYS.udp_sockets.get(&handle)).map(|s| s.local_addr()))
             .ok_or_else(|| "Invalid tcp socket handle".to_string())
             .and_then(|r| r.map_err(|e| e.to_string()))
     }
     fn tcp_set_non_blocking(&self, handle: Handle, non_blocking: bool) -> Result<(), String> {
         NATIVE_SYS
             .get_tcp_stream(handle, |s| s.set_nonblocking(non_blocking))
+            .or_else(|| {
+                (NATIVE_SYS.udp_sockets.get(&handle)).map(|s| s.set_nonblocking(non_blocking))
+            })
             .ok_or_else(|| "Invalid tcp socket handle".to_string())?
             .map_err(|e| e.to_string())
     }
@@ -917,6 +1207,7 @@ impl SysBackend for NativeSys {
     ) -> Result<(), String> {
         NATIVE_SYS
             .get_tcp_stream(handle, |s| s.set_read_timeout(timeout))
+            .or_else(|| (NATIVE_SYS.udp_sockets.get(&handle)).map(|s| s.set_read_timeout(timeout)))
             .ok_or_else(|| "Invalid tcp socket handle".to_string())?
             .map_err(|e| e.to_string())
     }
@@ -927,13 +1218,105 @@ impl SysBackend for NativeSys {
     ) -> Result<(), String> {
         NATIVE_SYS
             .get_tcp_stream(handle, |s| s.set_write_timeout(timeout))
+            .or_else(|| (NATIVE_SYS.udp_sockets.get(&handle)).map(|s| s.set_write_timeout(timeout)))
             .ok_or_else(|| "Invalid tcp socket handle".to_string())?
             .map_err(|e| e.to_string())
     }
+    fn udp_bind(&self, addr: &str) -> Result<Handle, String> {
+        let handle = NATIVE_SYS.new_handle();
+        let socket = UdpSocket::bind(addr).map_err(|e| e.to_string())?;
+        NATIVE_SYS.udp_sockets.insert(handle, socket);
+        Ok(handle)
+    }
+    fn udp_send(&self, handle: Handle, data: &[u8], addr: &str) -> Result<(), String> {
+        let socket = (NATIVE_SYS.udp_sockets.get(&handle))
+            .ok_or_else(|| "Invalid udp socket handle".to_string())?;
+        socket.send_to(data, addr).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+    fn udp_receive(&self, handle: Handle) -> Result<(Vec<u8>, SocketAddr), String> {
+        let socket = (NATIVE_SYS.udp_sockets.get(&handle))
+            .ok_or_else(|| "Invalid udp socket handle".to_string())?;
+        // Large enough for any datagram
+        let mut buf = vec![0; u16::MAX as usize];
+        let (len, addr) = socket.recv_from(&mut buf).map_err(|e| e.to_string())?;
+        buf.truncate(len);
+        Ok((buf, addr))
+    }
+    #[cfg(feature = "websocket")]
+    fn ws_connect(&self, url: &str) -> Result<Handle, String> {
+        use tungstenite::client::IntoClientRequest;
+        let request = url.into_client_request().map_err(|e| e.to_string())?;
+        let secure = match request.uri().scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            _ => return Err(format!("{url} is not a ws:// or wss:// URL")),
+        };
+        let host = (request.uri().host())
+            .ok_or_else(|| format!("{url} has no host"))?
+            .to_string();
+        let port = (request.uri().port_u16()).unwrap_or(if secure { 443 } else { 80 });
+        let stream = TcpStream::connect((host.as_str(), port)).map_err(|e| e.to_string())?;
+        let stream = if secure {
+            #[cfg(feature = "tls")]
+            {
+                let root_store = rustls::RootCertStore::from_iter(
+                    webpki_roots::TLS_SERVER_ROOTS.iter().cloned(),
+                );
+                let config = rustls::ClientConfig::builder()
+                    .with_root_certificates(root_store)
+                    .with_no_client_auth();
+                let server_name =
+                    rustls::pki_types::ServerName::try_from(host).map_err(|e| e.to_string())?;
+                let conn = rustls::ClientConnection::new(config.into(), server_name)
+                    .map_err(|e| e.to_string())?;
+                WebSocketStream::Tls(Box::new(rustls::StreamOwned::new(conn, stream)))
+            }
+            #[cfg(not(feature = "tls"))]
+            return Err("Secure WebSockets are not supported in this environment".into());
+        } else {
+            WebSocketStream::Plain(stream)
+        };
+        let (socket, _) = tungstenite::client(request, stream).map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        NATIVE_SYS.websockets.insert(handle, socket);
+        Ok(handle)
+    }
+    #[cfg(feature = "websocket")]
+    fn ws_send(&self, handle: Handle, message: crate::WebSocketMessage) -> Result<(), String> {
+        let mut socket = (NATIVE_SYS.websockets.get_mut(&handle))
+            .ok_or_else(|| "Invalid websocket handle".to_string())?;
+        let message = match message {
+            crate::WebSocketMessage::Text(text) => tungstenite::Message::Text(text),
+            crate::WebSocketMessage::Binary(bytes) => tungstenite::Message::Binary(bytes),
+        };
+        socket.send(message).map_err(|e| e.to_string())
+    }
+    #[cfg(feature = "websocket")]
+    fn ws_receive(&self, handle: Handle) -> Result<Option<crate::WebSocketMessage>, String> {
+        use tungstenite::{Error, Message};
+        let mut socket = (NATIVE_SYS.websockets.get_mut(&handle))
+            .ok_or_else(|| "Invalid websocket handle".to_string())?;
+        loop {
+            // Pings are answered automatically, so only data and closing matter
+            match socket.read() {
+                Ok(Message::Text(text)) => return Ok(Some(crate::WebSocketMessage::Text(text))),
+                Ok(Message::Binary(bytes)) => {
+                    return Ok(Some(crate::WebSocketMessage::Binary(bytes)))
+                }
+                Ok(Message::Close(_)) | Err(Error::ConnectionClosed | Error::AlreadyClosed) => {
+                    return Ok(None)
+                }
+                Ok(_) => {}
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    }
     fn close(&self, handle: Handle) -> Result<(), String> {
         if NATIVE_SYS.child_stdins.remove(&handle).is_some()
             | NATIVE_SYS.child_stdouts.remove(&handle).is_some()
             | NATIVE_SYS.child_stderrs.remove(&handle).is_some()
+            | NATIVE_SYS.decompressors.remove(&handle).is_some()
         {
             Ok(())
         } else if let Some((_, mut file)) = NATIVE_SYS.files.remove(&handle) {
@@ -946,13 +1329,73 @@ impl SysBackend for NativeSys {
             (&mut &socket).flush().map_err(|e| e.to_string())
         } else if NATIVE_SYS.tcp_listeners.remove(&handle).is_some()
             || NATIVE_SYS.tls_listeners.remove(&handle).is_some()
+            || NATIVE_SYS.udp_sockets.remove(&handle).is_some()
         {
             NATIVE_SYS.hostnames.remove(&handle);
             Ok(())
         } else {
+            #[cfg(feature = "websocket")]
+            if let Some((_, mut socket)) = NATIVE_SYS.websockets.remove(&handle) {
+                let res = match socket.close(None) {
+                    Ok(()) => socket.flush(),
+                    Err(e) => Err(e),
+                };
+                return match res {
+                    Ok(()) | Err(tungstenite::Error::ConnectionClosed) => Ok(()),
+                    Err(e) => Err(e.to_string()),
+                };
+            }
+            #[cfg(feature = "notify")]
+            if NATIVE_SYS.watchers.remove(&handle).is_some() {
+                return Ok(());
+            }
+            #[cfg(feature = "serial")]
+            if let Some((_, port)) = NATIVE_SYS.serial_ports.remove(&handle) {
+                return port.lock().flush().map_err(|e| e.to_string());
+            }
             Err("Invalid stream handle".to_string())
         }
     }
+    #[cfg(feature = "serial")]
+    fn serial_open(&self, port: &str, baud_rate: u32) -> Result<Handle, String> {
+        let port = serialport::new(port, baud_rate)
+            .timeout(Duration::MAX)
+            .open()
+            .map_err(|e| e.to_string())?;
+        let handle = NATIVE_SYS.new_handle();
+        (NATIVE_SYS.serial_ports).insert(handle, Arc::new(parking_lot::Mutex::new(port)));
+        Ok(handle)
+    }
+    #[cfg(feature = "serial")]
+    fn serial_list(&self) -> Result<Vec<String>, String> {
+        let ports = serialport::available_ports().map_err(|e| e.to_string())?;
+        Ok(ports.into_iter().map(|port| port.port_name).collect())
+    }
+    #[cfg(feature = "serial")]
+    fn serial_set_baud_rate(&self, handle: Handle, baud_rate: u32) -> Result<(), String> {
+        let port = (NATIVE_SYS.serial_ports.get(&handle))
+            .ok_or_else(|| "Invalid serial port handle".to_string())?;
+        let res = port.lock().set_baud_rate(baud_rate);
+        res.map_err(|e| e.to_string())
+    }
+    #[cfg(feature = "serial")]
+    fn serial_set_timeout(&self, handle: Handle, timeout: Option<Duration>) -> Result<(), String> {
+        let port = (NATIVE_SYS.serial_ports.get(&handle))
+            .ok_or_else(|| "Invalid serial port handle".to_string())?;
+        let timeout = timeout.unwrap_or(Duration::MAX);
+        let res = port.lock().set_timeout(timeout);
+        res.map_err(|e| e.to_string())
+    }
+    fn decompress_stream(&self, handle: Handle, format: Compression) -> Result<Handle, String> {
+        NATIVE_SYS.get_stream(handle)?;
+        let decoder = format.decoder(HandleReader(handle))?;
+        let new_handle = NATIVE_SYS.new_handle();
+        (NATIVE_SYS.decompressors).insert(
+            new_handle,
+            Arc::new(parking_lot::Mutex::new(BufReader::new(decoder))),
+        );
+        Ok(new_handle)
+    }
     #[cfg(feature = "invoke")]
     fn invoke(&self, path: &str) -> Result<(), String> {
         open::that(path).map_err(|e| e.to_string())
@@ -982,8 +1425,17 @@ impl SysBackend for NativeSys {
         ))
     }
     fn run_command_stream(&self, command: &str, args: &[&str]) -> Result<[Handle; 3], String> {
+        self.run_command_stream_env(command, args, &[])
+    }
+    fn run_command_stream_env(
+        &self,
+        command: &str,
+        args: &[&str],
+        vars: &[(&str, &str)],
+    ) -> Result<[Handle; 3], String> {
         let mut child = Command::new(command)
             .args(args)
+            .envs(vars.iter().copied())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -992,7 +1444,7 @@ impl SysBackend for NativeSys {
         let stdin = child.stdin.take().unwrap();
         let stdout = child.stdout.take().unwrap();
         let stderr = child.stderr.take().unwrap();
-        let child = Arc::new(child);
+        let child = Arc::new(parking_lot::Mutex::new(child));
         let stdin_handle = NATIVE_SYS.new_handle();
         NATIVE_SYS.child_stdins.insert(
             stdin_handle,
@@ -1019,9 +1471,26 @@ impl SysBackend for NativeSys {
         );
         Ok([stdin_handle, stdout_handle, stderr_handle])
     }
+    fn wait_command(&self, handle: Handle) -> Result<i32, String> {
+        let child = if let Some(stream) = NATIVE_SYS.child_stdins.get(&handle) {
+            stream.child.clone()
+        } else if let Some(stream) = NATIVE_SYS.child_stdouts.get(&handle) {
+            stream.child.clone()
+        } else if let Some(stream) = NATIVE_SYS.child_stderrs.get(&handle) {
+            stream.child.clone()
+        } else {
+            return Err("Handle is not a command stream".into());
+        };
+        let status = child.lock().wait().map_err(|e| e.to_string())?;
+        Ok(status.code().unwrap_or(0))
+    }
     fn change_directory(&self, path: &str) -> Result<(), String> {
         env::set_current_dir(path).map_err(|e| e.to_string())
     }
+    fn current_directory(&self) -> Result<String, String> {
+        let dir = env::current_dir().map_err(|e| e.to_string())?;
+        Ok(dir.to_string_lossy().into_owned())
+    }
     #[cfg(feature = "webcam")]
     fn webcam_capture(&self, index: usize) -> Result<crate::WebcamImage, String> {
         let cam_channels = &NATIVE_SYS.cam_channels;
@@ -0,0 +1,100 @@
+//! Recording execution traces in the Chrome trace-event format
+
+use std::sync::atomic::{self, AtomicU64};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// A recorded span of execution
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    /// The name of the primitive or function
+    pub name: String,
+    /// The kind of thing that was executed
+    #[serde(rename = "cat")]
+    pub category: &'static str,
+    /// The start time in microseconds since tracing began
+    #[serde(rename = "ts")]
+    pub start: f64,
+    /// The duration in microseconds
+    #[serde(rename = "dur")]
+    pub duration: f64,
+    /// The id of the thread that executed the span
+    #[serde(rename = "tid")]
+    pub thread: u64,
+}
+
+/// A trace of a program's execution
+///
+/// A trace is shared by all threads of a runtime.
+/// It can be exported with [`ExecutionTrace::to_chrome_json`] and opened in
+/// `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+#[derive(Debug)]
+pub struct ExecutionTrace {
+    start: f64,
+    events: Mutex<Vec<TraceEvent>>,
+    next_thread: AtomicU64,
+}
+
+impl ExecutionTrace {
+    pub(crate) fn new(start: f64) -> Self {
+        ExecutionTrace {
+            start,
+            events: Mutex::new(Vec::new()),
+            next_thread: AtomicU64::new(1),
+        }
+    }
+    /// Get an id for a new thread
+    pub(crate) fn new_thread(&self) -> u64 {
+        self.next_thread.fetch_add(1, atomic::Ordering::Relaxed)
+    }
+    /// Record a span, with start and end times in seconds
+    pub(crate) fn record(
+        &self,
+        name: String,
+        category: &'static str,
+        thread: u64,
+        start: f64,
+        end: f64,
+    ) {
+        self.events.lock().push(TraceEvent {
+            name,
+            category,
+            start: (start - self.start) * 1e6,
+            duration: ((end - start) * 1e6).max(0.0),
+            thread,
+        });
+    }
+    /// Get the recorded events
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events.lock().clone()
+    }
+    /// Export the trace as a JSON object in the Chrome trace-event format
+    pub fn to_chrome_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Complete<'a> {
+            #[serde(flatten)]
+            event: &'a TraceEvent,
+            ph: &'static str,
+            pid: u64,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Trace<'a> {
+            trace_events: Vec<Complete<'a>>,
+            display_time_unit: &'static str,
+        }
+        let events = self.events.lock();
+        let trace = Trace {
+            trace_events: (events.iter())
+                .map(|event| Complete {
+                    event,
+                    ph: "X",
+                    pid: 1,
+                })
+                .collect(),
+            display_time_unit: "ms",
+        };
+        serde_json::to_string(&trace).expect("trace should serialize")
+    }
+}
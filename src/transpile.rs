@@ -0,0 +1,752 @@
+//! Transpile numeric functions to Rust source
+//!
+//! This is experimental.
+//! Only a restricted subset of Uiua is supported: number arrays, pervasive math,
+//! [`reduce`](crate::Primitive::Reduce) and [`scan`](crate::Primitive::Scan) with a pervasive function,
+//! some structural functions, and stack manipulation.
+//! Functions that use anything else are skipped.
+//!
+//! The generated source is self-contained.
+//! It defines an `Arr` type for number arrays and a public Rust function for each transpiled Uiua function.
+//! Errors that the interpreter would report, like mismatched shapes, become panics.
+
+use std::{collections::HashSet, fmt::Write};
+
+use ecow::EcoString;
+
+use crate::{ArrayLen, Assembly, BindingKind, ImplPrimitive, Node, Primitive, SigNode, Value};
+
+/// The result of transpiling an assembly
+#[derive(Debug, Clone, Default)]
+pub struct RustSource {
+    /// The generated source
+    pub source: String,
+    /// The names of the functions that were transpiled
+    pub functions: Vec<EcoString>,
+    /// The names of the functions that were skipped and the reasons why
+    pub skipped: Vec<(EcoString, String)>,
+}
+
+/// Transpile the functions bound in an assembly to Rust source
+///
+/// Function names are converted to snake case.
+/// The first argument of a Uiua function, which is on top of the stack, is the first argument of the Rust function.
+/// Functions with multiple outputs return tuples, with the top of the stack first.
+pub fn assembly_to_rust(asm: &Assembly) -> RustSource {
+    let mut res = RustSource {
+        source: PRELUDE.into(),
+        ..Default::default()
+    };
+    let mut used_names = HashSet::new();
+    for binding in &asm.bindings {
+        let BindingKind::Func(f) = &binding.kind else {
+            continue;
+        };
+        let name: EcoString = binding.span.as_str(&asm.inputs, |s| s.into());
+        let Some(base_name) = rust_ident(&name) else {
+            let reason = "The name cannot be converted to a Rust identifier";
+            res.skipped.push((name, reason.into()));
+            continue;
+        };
+        let mut rust_name = base_name.clone();
+        for i in 2.. {
+            if !used_names.contains(&rust_name) {
+                break;
+            }
+            rust_name = format!("{base_name}_{i}");
+        }
+        let doc = binding.meta.comment.as_ref().map(|c| c.text.as_str());
+        let sn = SigNode::new(f.sig, asm[f].clone());
+        match function_to_rust(asm, &rust_name, &sn, doc) {
+            Ok(src) => {
+                res.source.push('\n');
+                res.source.push_str(&src);
+                res.functions.push(name);
+                used_names.insert(rust_name);
+            }
+            Err(e) => res.skipped.push((name, e)),
+        }
+    }
+    res
+}
+
+fn function_to_rust(
+    asm: &Assembly,
+    name: &str,
+    sn: &SigNode,
+    doc: Option<&str>,
+) -> Result<String, String> {
+    let sig = sn.sig;
+    let mut gen = Emitter::new(asm, 1);
+    gen.stack = (0..sig.args)
+        .rev()
+        .map(|i| Operand::param(format!("a{i}")))
+        .collect();
+    gen.node(&sn.node)?;
+    if gen.stack.len() != sig.outputs {
+        return Err("The function's signature could not be followed".into());
+    }
+    let mut src = String::new();
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            src.push_str(format!("/// {line}").trim_end());
+            src.push('\n');
+        }
+        src.push_str("///\n");
+    }
+    _ = writeln!(
+        src,
+        "/// Transpiled from a Uiua function with signature {sig}"
+    );
+    let params: Vec<String> = (0..sig.args).map(|i| format!("a{i}: &Arr")).collect();
+    _ = write!(src, "pub fn {name}({})", params.join(", "));
+    match sig.outputs {
+        0 => {}
+        1 => src.push_str(" -> Arr"),
+        n => _ = write!(src, " -> ({})", vec!["Arr"; n].join(", ")),
+    }
+    src.push_str(" {\n");
+    src.push_str(&gen.body);
+    let outputs = gen.return_values();
+    match outputs.len() {
+        0 => {}
+        1 => _ = writeln!(src, "    {}", outputs[0]),
+        _ => _ = writeln!(src, "    ({})", outputs.join(", ")),
+    }
+    src.push_str("}\n");
+    Ok(src)
+}
+
+/// A value on the simulated stack
+#[derive(Debug, Clone)]
+struct Operand {
+    name: String,
+    /// Whether the variable is a reference
+    borrowed: bool,
+}
+
+impl Operand {
+    fn param(name: String) -> Self {
+        Operand {
+            name,
+            borrowed: true,
+        }
+    }
+    /// The expression for passing this value as an `&Arr`
+    fn arg(&self) -> String {
+        if self.borrowed {
+            self.name.clone()
+        } else {
+            format!("&{}", self.name)
+        }
+    }
+}
+
+/// Generates the body of a function by simulating the stack
+struct Emitter<'a> {
+    asm: &'a Assembly,
+    body: String,
+    stack: Vec<Operand>,
+    next_var: usize,
+    indent: usize,
+    call_depth: usize,
+}
+
+const MAX_CALL_DEPTH: usize = 50;
+
+impl<'a> Emitter<'a> {
+    fn new(asm: &'a Assembly, indent: usize) -> Self {
+        Emitter {
+            asm,
+            body: String::new(),
+            stack: Vec::new(),
+            next_var: 0,
+            indent,
+            call_depth: 0,
+        }
+    }
+    fn bind(&mut self, expr: String) {
+        let name = format!("v{}", self.next_var);
+        self.next_var += 1;
+        _ = writeln!(
+            self.body,
+            "{}let {name} = {expr};",
+            "    ".repeat(self.indent)
+        );
+        self.stack.push(Operand {
+            name,
+            borrowed: false,
+        });
+    }
+    fn pop(&mut self) -> Result<Operand, String> {
+        (self.stack.pop()).ok_or_else(|| "The function's signature could not be followed".into())
+    }
+    fn top(&self, n: usize) -> Result<Operand, String> {
+        (self.stack.len().checked_sub(n + 1))
+            .map(|i| self.stack[i].clone())
+            .ok_or_else(|| "The function's signature could not be followed".into())
+    }
+    /// Get the expressions for returning the values on the stack, top first
+    fn return_values(&self) -> Vec<String> {
+        let mut values = Vec::new();
+        for (i, op) in self.stack.iter().enumerate().rev() {
+            let used_later = self.stack[..i].iter().any(|o| o.name == op.name);
+            values.push(if op.borrowed || used_later {
+                format!("{}.clone()", op.name)
+            } else {
+                op.name.clone()
+            });
+        }
+        values
+    }
+    fn node(&mut self, node: &Node) -> Result<(), String> {
+        match node {
+            Node::Run(nodes) => {
+                for node in nodes {
+                    self.node(node)?;
+                }
+            }
+            Node::Push(val) => {
+                let lit = array_literal(val)?;
+                self.bind(lit);
+            }
+            Node::Prim(prim, _) => self.prim(*prim)?,
+            Node::ImplPrim(ImplPrimitive::DupPervasive(prim), _) => {
+                self.prim(Primitive::Dup)?;
+                self.prim(*prim)?;
+            }
+            Node::ImplPrim(ImplPrimitive::FlipPervasive(prim), _) => {
+                self.prim(Primitive::Flip)?;
+                self.prim(*prim)?;
+            }
+            Node::Mod(prim, args, _) => self.modifier(*prim, args)?,
+            Node::Call(f, _) => {
+                if self.call_depth >= MAX_CALL_DEPTH {
+                    return Err("Recursive functions are not supported".into());
+                }
+                self.call_depth += 1;
+                let asm = self.asm;
+                self.node(&asm[f])?;
+                self.call_depth -= 1;
+            }
+            Node::NoInline(inner) | Node::TrackCaller(inner) => self.node(inner)?,
+            Node::Array {
+                len: ArrayLen::Static(len),
+                inner,
+                boxed: false,
+                ..
+            } => {
+                self.node(inner)?;
+                let rows: Vec<String> = (0..*len)
+                    .map(|_| self.pop().map(|op| op.arg()))
+                    .collect::<Result<_, _>>()?;
+                self.bind(format!("Arr::array(&[{}])", rows.join(", ")));
+            }
+            Node::SetOutputComment { .. } => {}
+            node => return Err(unsupported(node)),
+        }
+        Ok(())
+    }
+    fn prim(&mut self, prim: Primitive) -> Result<(), String> {
+        use Primitive::*;
+        if let Some(f) = monadic_closure(prim) {
+            let x = self.pop()?;
+            self.bind(format!("Arr::monadic({}, {f})", x.arg()));
+            return Ok(());
+        }
+        if let Some(f) = dyadic_closure(prim) {
+            let x = self.pop()?;
+            let y = self.pop()?;
+            self.bind(format!("Arr::dyadic({}, {}, {f})", x.arg(), y.arg()));
+            return Ok(());
+        }
+        match prim {
+            Identity => {}
+            Dup => {
+                let x = self.top(0)?;
+                self.stack.push(x);
+            }
+            Over => {
+                let y = self.top(1)?;
+                self.stack.push(y);
+            }
+            Flip => {
+                let x = self.pop()?;
+                let y = self.pop()?;
+                self.stack.push(x);
+                self.stack.push(y);
+            }
+            Pop => _ = self.pop()?,
+            Len | Shape | Reverse | First | Last | Deshape | Range | Transpose => {
+                let method = match prim {
+                    Len => "len",
+                    Shape => "shape",
+                    Reverse => "reverse",
+                    First => "first",
+                    Last => "last",
+                    Deshape => "deshape",
+                    Range => "range",
+                    _ => "transpose",
+                };
+                let x = self.pop()?;
+                self.bind(format!("Arr::{method}({})", x.arg()));
+            }
+            Join | Select => {
+                let method = if prim == Join { "join" } else { "select" };
+                let x = self.pop()?;
+                let y = self.pop()?;
+                self.bind(format!("Arr::{method}({}, {})", x.arg(), y.arg()));
+            }
+            Couple => {
+                let x = self.pop()?;
+                let y = self.pop()?;
+                self.bind(format!("Arr::array(&[{}, {}])", x.arg(), y.arg()));
+            }
+            prim => return Err(format!("{} is not supported", prim.format())),
+        }
+        Ok(())
+    }
+    fn modifier(&mut self, prim: Primitive, args: &[SigNode]) -> Result<(), String> {
+        use Primitive::*;
+        match (prim, args) {
+            (Reduce | Scan, [f]) => {
+                let op = match f.node.as_slice() {
+                    [Node::Prim(op, _)] => *op,
+                    _ => {
+                        return Err(format!(
+                            "{} is only supported with a pervasive function",
+                            prim.format()
+                        ))
+                    }
+                };
+                let Some(closure) = dyadic_closure(op) else {
+                    return Err(format!("{}{} is not supported", prim.format(), op.format()));
+                };
+                let x = self.pop()?;
+                if prim == Reduce {
+                    let identity = match op {
+                        Add | Sub | Atan => "Some(0.0)",
+                        Mul => "Some(1.0)",
+                        Div | Modulus | Min => "Some(f64::INFINITY)",
+                        Max => "Some(f64::NEG_INFINITY)",
+                        _ => "None",
+                    };
+                    self.bind(format!("Arr::reduce({}, {identity}, {closure})", x.arg()));
+                } else {
+                    self.bind(format!("Arr::scan({}, {closure})", x.arg()));
+                }
+            }
+            (Dip, [f]) => {
+                let x = self.pop()?;
+                self.node(&f.node)?;
+                self.stack.push(x);
+            }
+            (Gap, [f]) => {
+                self.pop()?;
+                self.node(&f.node)?;
+            }
+            (On, [f]) => {
+                let x = self.top(0)?;
+                self.node(&f.node)?;
+                self.stack.push(x);
+            }
+            (By, [f]) if f.sig.args > 0 => {
+                let y = self.top(f.sig.args - 1)?;
+                self.node(&f.node)?;
+                let i = self.stack.len().saturating_sub(f.sig.outputs);
+                self.stack.insert(i, y);
+            }
+            (Fork, [f, g]) => {
+                let n = f.sig.args.max(g.sig.args);
+                let start =
+                    self.stack.len().checked_sub(n).ok_or_else(|| {
+                        "The function's signature could not be followed".to_string()
+                    })?;
+                let fork_args = self.stack.split_off(start);
+                self.stack.extend_from_slice(&fork_args[n - g.sig.args..]);
+                self.node(&g.node)?;
+                self.stack.extend_from_slice(&fork_args[n - f.sig.args..]);
+                self.node(&f.node)?;
+            }
+            (Rows, [f]) if f.sig == (1, 1) => {
+                let x = self.pop()?;
+                let param = format!("r{}", self.next_var);
+                let mut inner = Emitter::new(self.asm, self.indent + 1);
+                inner.next_var = self.next_var + 1;
+                inner.call_depth = self.call_depth;
+                inner.stack.push(Operand::param(param.clone()));
+                inner.node(&f.node)?;
+                if inner.stack.len() != 1 {
+                    return Err("The function's signature could not be followed".into());
+                }
+                self.next_var = inner.next_var;
+                let ret = inner.return_values().remove(0);
+                let indent = "    ".repeat(self.indent);
+                let closure = format!(
+                    "|{param}: &Arr| {{\n{}{indent}    {ret}\n{indent}}}",
+                    inner.body
+                );
+                self.bind(format!("Arr::rows({}, {closure})", x.arg()));
+            }
+            (prim, _) => return Err(format!("{} is not supported", prim.format())),
+        }
+        Ok(())
+    }
+}
+
+fn unsupported(node: &Node) -> String {
+    match node {
+        Node::ImplPrim(prim, _) | Node::ImplMod(prim, ..) => format!("{prim} is not supported"),
+        Node::Array { boxed: true, .. } => "Box arrays are not supported".into(),
+        Node::Array { .. } => "Arrays with a dynamic number of rows are not supported".into(),
+        _ => "The function uses features that are not supported".into(),
+    }
+}
+
+/// The closure that implements a monadic pervasive primitive
+fn monadic_closure(prim: Primitive) -> Option<&'static str> {
+    use Primitive::*;
+    Some(match prim {
+        Not => "|a| 1.0 - a",
+        Sign => "Arr::num_sign",
+        Neg => "|a| -a",
+        Abs => "f64::abs",
+        Sqrt => "f64::sqrt",
+        Sin => "f64::sin",
+        Floor => "f64::floor",
+        Ceil => "f64::ceil",
+        Round => "f64::round",
+        _ => return None,
+    })
+}
+
+/// The closure that implements a dyadic pervasive primitive
+///
+/// `a` is the first argument, which is on top of the stack
+fn dyadic_closure(prim: Primitive) -> Option<&'static str> {
+    use Primitive::*;
+    Some(match prim {
+        Add => "|a, b| b + a",
+        Sub => "|a, b| b - a",
+        Mul => "|a, b| b * a",
+        Div => "|a, b| b / a",
+        Modulus => "|a, b| b.rem_euclid(a).abs()",
+        Pow => "|a, b| b.powf(a)",
+        Log => "|a, b| b.log(a)",
+        Min => "f64::min",
+        Max => "f64::max",
+        Atan => "f64::atan2",
+        Eq => "|a, b| Arr::num_cmp(b, a).is_eq() as u8 as f64",
+        Ne => "|a, b| Arr::num_cmp(b, a).is_ne() as u8 as f64",
+        Lt => "|a, b| Arr::num_cmp(b, a).is_lt() as u8 as f64",
+        Le => "|a, b| Arr::num_cmp(b, a).is_le() as u8 as f64",
+        Gt => "|a, b| Arr::num_cmp(b, a).is_gt() as u8 as f64",
+        Ge => "|a, b| Arr::num_cmp(b, a).is_ge() as u8 as f64",
+        _ => return None,
+    })
+}
+
+fn array_literal(val: &Value) -> Result<String, String> {
+    let data: Vec<f64> = match val {
+        Value::Num(arr) => arr.data.to_vec(),
+        Value::Byte(arr) => arr.data.iter().map(|&b| b as f64).collect(),
+        val => return Err(format!("{} arrays are not supported", val.type_name())),
+    };
+    let elems: Vec<String> = data.iter().map(|&x| float_literal(x)).collect();
+    Ok(match val.shape().dims() {
+        [] => format!("Arr::scalar({})", elems[0]),
+        [_] => format!("Arr::list(vec![{}])", elems.join(", ")),
+        dims => format!("Arr::new(vec!{dims:?}, vec![{}])", elems.join(", ")),
+    })
+}
+
+fn float_literal(x: f64) -> String {
+    if x.is_nan() {
+        "f64::NAN".into()
+    } else if x == f64::INFINITY {
+        "f64::INFINITY".into()
+    } else if x == f64::NEG_INFINITY {
+        "f64::NEG_INFINITY".into()
+    } else {
+        format!("{x:?}")
+    }
+}
+
+/// Convert a Uiua name to a snake case Rust identifier
+fn rust_ident(name: &str) -> Option<String> {
+    let mut ident = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        let c = match c {
+            '₀'..='₉' => char::from_digit(c as u32 - '₀' as u32, 10)?,
+            c => c,
+        };
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            ident.push(c);
+            prev_lower = true;
+        } else {
+            return None;
+        }
+    }
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    Some(ident)
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+const PRELUDE: &str = r#"// Generated from Uiua code. Do not edit.
+#![allow(dead_code, clippy::all)]
+
+use std::cmp::Ordering;
+
+/// A number array
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arr {
+    /// The shape of the array
+    pub shape: Vec<usize>,
+    /// The elements of the array in row-major order
+    pub data: Vec<f64>,
+}
+
+impl From<f64> for Arr {
+    fn from(x: f64) -> Self {
+        Arr::scalar(x)
+    }
+}
+
+impl From<Vec<f64>> for Arr {
+    fn from(data: Vec<f64>) -> Self {
+        Arr::list(data)
+    }
+}
+
+impl Arr {
+    /// Create an array from a shape and elements
+    pub fn new(shape: Vec<usize>, data: Vec<f64>) -> Self {
+        let len: usize = shape.iter().product();
+        assert_eq!(len, data.len(), "Shape {shape:?} does not match {} elements", data.len());
+        Arr { shape, data }
+    }
+    /// Create a scalar
+    pub fn scalar(x: f64) -> Self {
+        Arr { shape: Vec::new(), data: vec![x] }
+    }
+    /// Create a list
+    pub fn list(data: Vec<f64>) -> Self {
+        Arr { shape: vec![data.len()], data }
+    }
+    fn row_shape(&self) -> &[usize] {
+        self.shape.get(1..).unwrap_or(&[])
+    }
+    fn row_len(&self) -> usize {
+        self.row_shape().iter().product()
+    }
+    fn row(&self, i: usize) -> Arr {
+        let len = self.row_len();
+        let data = self.data[i * len..(i + 1) * len].to_vec();
+        Arr { shape: self.row_shape().to_vec(), data }
+    }
+    fn num_cmp(a: f64, b: f64) -> Ordering {
+        a.partial_cmp(&b).unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+    }
+    fn num_sign(a: f64) -> f64 {
+        if a.is_nan() {
+            f64::NAN
+        } else if a == 0.0 {
+            0.0
+        } else {
+            a.signum()
+        }
+    }
+    fn monadic(x: &Arr, f: impl Fn(f64) -> f64) -> Arr {
+        let data = x.data.iter().map(|&a| f(a)).collect();
+        Arr { shape: x.shape.clone(), data }
+    }
+    fn dyadic(x: &Arr, y: &Arr, f: impl Fn(f64, f64) -> f64) -> Arr {
+        if x.shape == y.shape {
+            let data = x.data.iter().zip(&y.data).map(|(&a, &b)| f(a, b)).collect();
+            Arr { shape: x.shape.clone(), data }
+        } else if y.shape.starts_with(&x.shape) {
+            let n = y.data.len() / x.data.len().max(1);
+            let data = y.data.iter().enumerate().map(|(i, &b)| f(x.data[i / n], b)).collect();
+            Arr { shape: y.shape.clone(), data }
+        } else if x.shape.starts_with(&y.shape) {
+            let n = x.data.len() / y.data.len().max(1);
+            let data = x.data.iter().enumerate().map(|(i, &a)| f(a, y.data[i / n])).collect();
+            Arr { shape: x.shape.clone(), data }
+        } else {
+            panic!("Shapes {:?} and {:?} do not match", x.shape, y.shape)
+        }
+    }
+    fn reduce(x: &Arr, identity: Option<f64>, f: impl Fn(f64, f64) -> f64) -> Arr {
+        if x.shape.is_empty() {
+            return x.clone();
+        }
+        let len = x.row_len();
+        if x.shape[0] == 0 {
+            let identity =
+                identity.expect("Cannot reduce empty array. Function has no identity value.");
+            return Arr { shape: x.row_shape().to_vec(), data: vec![identity; len] };
+        }
+        let mut acc = x.row(0);
+        if len > 0 {
+            for row in x.data[len..].chunks_exact(len) {
+                for (a, &b) in acc.data.iter_mut().zip(row) {
+                    *a = f(*a, b);
+                }
+            }
+        }
+        acc
+    }
+    fn scan(x: &Arr, f: impl Fn(f64, f64) -> f64) -> Arr {
+        let len = x.row_len();
+        let mut data = x.data.clone();
+        if !x.shape.is_empty() && len > 0 {
+            for i in len..data.len() {
+                data[i] = f(data[i - len], data[i]);
+            }
+        }
+        Arr { shape: x.shape.clone(), data }
+    }
+    fn rows(x: &Arr, f: impl Fn(&Arr) -> Arr) -> Arr {
+        if x.shape.is_empty() {
+            return f(x);
+        }
+        let rows: Vec<Arr> = (0..x.shape[0]).map(|i| f(&x.row(i))).collect();
+        if rows.is_empty() {
+            return Arr::list(Vec::new());
+        }
+        Arr::array(&rows.iter().collect::<Vec<_>>())
+    }
+    fn array(rows: &[&Arr]) -> Arr {
+        let row_shape = rows.first().map(|row| row.shape.clone()).unwrap_or_default();
+        let mut shape = vec![rows.len()];
+        shape.extend_from_slice(&row_shape);
+        let mut data = Vec::new();
+        for row in rows {
+            assert_eq!(row.shape, row_shape, "Cannot combine arrays with different shapes");
+            data.extend_from_slice(&row.data);
+        }
+        Arr { shape, data }
+    }
+    fn len(x: &Arr) -> Arr {
+        Arr::scalar(x.shape.first().copied().unwrap_or(1) as f64)
+    }
+    fn shape(x: &Arr) -> Arr {
+        Arr::list(x.shape.iter().map(|&n| n as f64).collect())
+    }
+    fn reverse(x: &Arr) -> Arr {
+        if x.shape.is_empty() {
+            return x.clone();
+        }
+        let rows: Vec<Arr> = (0..x.shape[0]).rev().map(|i| x.row(i)).collect();
+        let data = rows.into_iter().flat_map(|row| row.data).collect();
+        Arr { shape: x.shape.clone(), data }
+    }
+    fn first(x: &Arr) -> Arr {
+        if x.shape.is_empty() {
+            return x.clone();
+        }
+        assert!(x.shape[0] > 0, "Cannot take first of an empty array");
+        x.row(0)
+    }
+    fn last(x: &Arr) -> Arr {
+        if x.shape.is_empty() {
+            return x.clone();
+        }
+        assert!(x.shape[0] > 0, "Cannot take last of an empty array");
+        x.row(x.shape[0] - 1)
+    }
+    fn deshape(x: &Arr) -> Arr {
+        Arr::list(x.data.clone())
+    }
+    fn range(x: &Arr) -> Arr {
+        assert!(x.shape.is_empty(), "Range of an array with shape {:?} is not supported", x.shape);
+        let n = x.data[0];
+        assert!(n.fract() == 0.0, "Range must be an integer, but it is {n}");
+        if n >= 0.0 {
+            Arr::list((0..n as usize).map(|i| i as f64).collect())
+        } else {
+            Arr::list((1..=-n as usize).map(|i| -(i as f64)).collect())
+        }
+    }
+    fn transpose(x: &Arr) -> Arr {
+        if x.shape.len() < 2 {
+            return x.clone();
+        }
+        let rows = x.shape[0];
+        let len = x.row_len();
+        let mut data = vec![0.0; x.data.len()];
+        for i in 0..rows {
+            for j in 0..len {
+                data[j * rows + i] = x.data[i * len + j];
+            }
+        }
+        let mut shape = x.row_shape().to_vec();
+        shape.push(rows);
+        Arr { shape, data }
+    }
+    fn join(x: &Arr, y: &Arr) -> Arr {
+        let shape = if x.shape.len() == y.shape.len() {
+            if x.shape.is_empty() {
+                Some(vec![2])
+            } else if x.row_shape() == y.row_shape() {
+                let mut shape = x.shape.clone();
+                shape[0] += y.shape[0];
+                Some(shape)
+            } else {
+                None
+            }
+        } else if x.shape.len() + 1 == y.shape.len() && x.shape == y.row_shape() {
+            let mut shape = y.shape.clone();
+            shape[0] += 1;
+            Some(shape)
+        } else if y.shape.len() + 1 == x.shape.len() && x.row_shape() == y.shape {
+            let mut shape = x.shape.clone();
+            shape[0] += 1;
+            Some(shape)
+        } else {
+            None
+        };
+        let shape = shape.unwrap_or_else(|| {
+            panic!("Cannot join arrays of shapes {:?} and {:?}", x.shape, y.shape)
+        });
+        let mut data = x.data.clone();
+        data.extend_from_slice(&y.data);
+        Arr { shape, data }
+    }
+    fn select(indices: &Arr, x: &Arr) -> Arr {
+        assert!(!x.shape.is_empty(), "Cannot select from a scalar");
+        let count = x.shape[0] as isize;
+        let len = x.row_len();
+        let mut data = Vec::with_capacity(indices.data.len() * len);
+        for &index in &indices.data {
+            assert!(index.fract() == 0.0, "Index must be an integer, but it is {index}");
+            let i = if index < 0.0 { index as isize + count } else { index as isize };
+            assert!((0..count).contains(&i), "Index {index} is out of bounds of length {count}");
+            let i = i as usize;
+            data.extend_from_slice(&x.data[i * len..(i + 1) * len]);
+        }
+        let mut shape = indices.shape.clone();
+        shape.extend_from_slice(x.row_shape());
+        Arr { shape, data }
+    }
+}
+"#;
@@ -16,7 +16,8 @@ use serde::*;
 use crate::{
     check::SigCheckError,
     compile::invert::{InversionError, InversionResult},
-    Assembly, BindingKind, DynamicFunction, Function, ImplPrimitive, Primitive, Signature, Value,
+    Assembly, BindingKind, DynamicFunction, Function, ImplPrimitive, Primitive, Signature,
+    TypeCheck, TypeDispatch, Value,
 };
 
 node!(
@@ -46,6 +47,10 @@ node!(
     SetOutputComment { i: usize, n: usize },
     /// Validate that a value has a certain type
     ValidateType { index: usize, type_num: u8, name: EcoString, span: usize },
+    /// Check values against doc comment type annotations
+    CheckTypes(check(Arc<TypeCheck>), span(usize)),
+    /// Push the index of the first overload whose type annotations match the arguments
+    DispatchTypes(dispatch(Arc<TypeDispatch>), span(usize)),
     /// Call a Rust function
     Dynamic(func(DynamicFunction)),
     /// Push some values to the under stack
@@ -692,6 +697,8 @@ impl fmt::Debug for Node {
             Node::ValidateType { type_num, name, .. } => {
                 write!(f, "<validate {name} as {type_num}>")
             }
+            Node::CheckTypes(check, _) => write!(f, "<check {} types>", check.name),
+            Node::DispatchTypes(dispatch, _) => write!(f, "<dispatch {}>", dispatch.name),
             Node::Dynamic(func) => write!(f, "<dynamic function {}>", func.index),
             Node::PushUnder(count, _) => write!(f, "push-u-{count}"),
             Node::CopyToUnder(count, _) => write!(f, "copy-u-{count}"),
@@ -772,6 +779,8 @@ impl Node {
                     .or(cust.un.as_ref())
                     .is_some_and(|sn| recurse(&sn.node, purity, asm, visited)),
                 Node::WithLocal { inner, .. } => recurse(&inner.node, purity, asm, visited),
+                Node::GetLocal { .. } => Purity::Impure >= purity,
+                Node::SetLocal { .. } => Purity::Mutating >= purity,
                 _ => true,
             };
             visited.truncate(len);
@@ -15,7 +15,7 @@ use crate::{
     array::*,
     cowslice::CowSlice,
     grid_fmt::GridFmt,
-    Boxed, Complex, Shape, Uiua, UiuaResult,
+    Boxed, Complex, Shape, Uiua, UiuaError, UiuaResult,
 };
 
 /// A generic array value
@@ -86,8 +86,8 @@ impl Value {
         arr.meta_mut().pointer = Some(MetaPtr::null());
         Value::from(arr)
     }
-    pub(crate) fn builder(capacity: usize) -> ValueBuilder {
-        ValueBuilder::with_capacity(capacity)
+    pub(crate) fn builder(capacity: usize) -> RowBuilder {
+        RowBuilder::with_capacity(capacity)
     }
     pub(crate) fn type_id(&self) -> u8 {
         match self {
@@ -112,6 +112,13 @@ impl Value {
             _ => None,
         }
     }
+    /// Get a reference to a possible complex array
+    pub fn as_complex_array(&self) -> Option<&Array<Complex>> {
+        match self {
+            Self::Complex(array) => Some(array),
+            _ => None,
+        }
+    }
     /// Get a reference to a possible character array
     pub fn as_char_array(&self) -> Option<&Array<char>> {
         match self {
@@ -130,6 +137,35 @@ impl Value {
     pub fn as_box(&self) -> Option<&Boxed> {
         self.as_box_array().and_then(Array::as_scalar)
     }
+    /// Get the elements of a possible number array
+    ///
+    /// Elements are in row-major order. Byte arrays are stored separately, so use [`Value::as_byte_slice`] for them.
+    /// ```rust
+    /// use uiua::*;
+    ///
+    /// let value = Value::from([1.5, 2.0, 3.0]);
+    /// assert_eq!(value.as_num_slice(), Some([1.5, 2.0, 3.0].as_slice()));
+    /// assert_eq!(value.as_char_slice(), None);
+    /// ```
+    pub fn as_num_slice(&self) -> Option<&[f64]> {
+        self.as_num_array().map(Array::as_slice)
+    }
+    /// Get the elements of a possible byte array
+    pub fn as_byte_slice(&self) -> Option<&[u8]> {
+        self.as_byte_array().map(Array::as_slice)
+    }
+    /// Get the elements of a possible complex array
+    pub fn as_complex_slice(&self) -> Option<&[Complex]> {
+        self.as_complex_array().map(Array::as_slice)
+    }
+    /// Get the elements of a possible character array
+    pub fn as_char_slice(&self) -> Option<&[char]> {
+        self.as_char_array().map(Array::as_slice)
+    }
+    /// Get the elements of a possible box array
+    pub fn as_box_slice(&self) -> Option<&[Boxed]> {
+        self.as_box_array().map(Array::as_slice)
+    }
     /// Get an iterator over the rows of the value
     pub fn rows(&self) -> Box<dyn ExactSizeIterator<Item = Self> + '_> {
         if self.shape().first() == Some(&1) {
@@ -299,30 +335,14 @@ impl Value {
     }
 }
 
-#[repr(C)]
-struct Repr {
-    discriminant: u8,
-    _arr: Array<f64>,
-}
-
 impl Value {
-    /// # Safety
-    /// The value or layout of data accessed from the Repr's array must not be dependent on the array's type
-    unsafe fn repr(&self) -> &Repr {
-        &*(self as *const Self as *const Repr)
-    }
-    /// # Safety
-    /// The value or layout of data accessed from the Repr's array must not be dependent on the array's type
-    unsafe fn repr_mut(&mut self) -> &mut Repr {
-        &mut *(self as *mut Self as *mut Repr)
-    }
     /// Get the shape of the value
     pub fn shape(&self) -> &Shape {
-        &unsafe { self.repr() }._arr.shape
+        val_as_arr!(self, |arr| &arr.shape)
     }
     /// Get a mutable reference to the shape
     pub fn shape_mut(&mut self) -> &mut Shape {
-        &mut unsafe { self.repr_mut() }._arr.shape
+        val_as_arr!(self, |arr| &mut arr.shape)
     }
     /// Get the number of elements
     pub fn element_count(&self) -> usize {
@@ -330,15 +350,15 @@ impl Value {
     }
     /// Get the value's metadata
     pub fn meta(&self) -> &ArrayMeta {
-        unsafe { self.repr() }._arr.meta()
+        val_as_arr!(self, Array::meta)
     }
     /// Get a mutable reference to the value's metadata
     pub fn meta_mut(&mut self) -> &mut ArrayMeta {
-        unsafe { self.repr_mut() }._arr.meta_mut()
+        val_as_arr!(self, Array::meta_mut)
     }
     /// Get a mutable reference to the value's metadata
     pub fn get_meta_mut(&mut self) -> Option<&mut ArrayMeta> {
-        unsafe { self.repr_mut() }._arr.get_meta_mut()
+        val_as_arr!(self, Array::get_meta_mut)
     }
     /// Take the label from the value
     pub fn take_label(&mut self) -> Option<EcoString> {
@@ -374,6 +394,21 @@ impl Value {
     pub fn map_keys(&self) -> Option<&MapKeys> {
         self.meta().map_keys.as_ref()
     }
+    /// Get the categories of an [`intern`](crate::Primitive::Intern)ed value
+    ///
+    /// Returns `None` if the value has changed since it was interned
+    pub fn categories(&self) -> Option<&Categories> {
+        let categories = self.meta().categories.as_deref()?;
+        let unchanged = match (self, &categories.source) {
+            (Value::Num(a), Value::Num(b)) => a.data.is_copy_of(&b.data),
+            (Value::Byte(a), Value::Byte(b)) => a.data.is_copy_of(&b.data),
+            (Value::Complex(a), Value::Complex(b)) => a.data.is_copy_of(&b.data),
+            (Value::Char(a), Value::Char(b)) => a.data.is_copy_of(&b.data),
+            (Value::Box(a), Value::Box(b)) => a.data.is_copy_of(&b.data),
+            _ => false,
+        };
+        (unchanged && self.shape() == categories.source.shape()).then_some(categories)
+    }
     /// Get a mutable reference to the value's map keys
     pub fn map_keys_mut(&mut self) -> Option<&mut MapKeys> {
         self.get_meta_mut().and_then(|meta| meta.map_keys.as_mut())
@@ -1732,8 +1767,68 @@ value_un_impl!(
     [Char, generic]
 );
 value_un_impl!(complex_im, [Num, num], [Byte, byte], (Complex, com));
+value_un_impl!(complex_arg, [Num, num], (Byte, byte), (Complex, com));
+value_un_impl!(
+    complex_conj,
+    [Num, generic],
+    [Byte, generic],
+    [Complex, com]
+);
 
 impl Value {
+    /// Get the sine of a value where a full turn is `turn` units
+    pub(crate) fn sin_turn(self, turn: i32, env: &Uiua) -> UiuaResult<Self> {
+        self.un_turn(turn.into(), sin_turn::num, sin_turn::com, sin::error, env)
+    }
+    /// Get the arcsine of a value where a full turn is `turn` units
+    pub(crate) fn asin_turn(self, turn: i32, env: &Uiua) -> UiuaResult<Self> {
+        self.un_turn(
+            turn.into(),
+            asin_turn::num,
+            asin_turn::com,
+            asin::error,
+            env,
+        )
+    }
+    fn un_turn(
+        self,
+        turn: f64,
+        num: fn(f64, f64) -> f64,
+        com: fn(Complex, f64) -> Complex,
+        error: fn(&'static str, &Uiua) -> UiuaError,
+        env: &Uiua,
+    ) -> UiuaResult<Self> {
+        self.keep_meta(|val| {
+            Ok(match val {
+                Value::Num(mut array) => {
+                    for val in &mut array.data {
+                        *val = num(*val, turn);
+                    }
+                    array.into()
+                }
+                Value::Byte(array) => {
+                    let data: EcoVec<f64> =
+                        (array.data.iter()).map(|&b| num(b.into(), turn)).collect();
+                    (array.shape, data).into()
+                }
+                Value::Complex(mut array) => {
+                    for val in &mut array.data {
+                        *val = com(*val, turn);
+                    }
+                    array.into()
+                }
+                Value::Box(mut array) => {
+                    let mut new_data = EcoVec::with_capacity(array.element_count());
+                    for b in array.data {
+                        new_data.push(Boxed(b.0.un_turn(turn, num, com, error, env)?));
+                    }
+                    array.data = new_data.into();
+                    array.into()
+                }
+                val => return Err(error(val.type_name(), env)),
+            })
+        })
+    }
     /// Get the `absolute value` of a value
     pub fn abs(self, env: &Uiua) -> UiuaResult<Self> {
         match self {
@@ -2058,6 +2153,47 @@ impl PartialEq for Value {
 
 impl Eq for Value {}
 
+impl Value {
+    /// Check if two values are equal, allowing numbers to differ by at most some tolerance
+    pub(crate) fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        fn nums_eq<A, B>(a: &Array<A>, b: &Array<B>, tolerance: f64) -> bool
+        where
+            A: ArrayValue + Copy + Into<f64>,
+            B: ArrayValue + Copy + Into<f64>,
+        {
+            a.shape == b.shape
+                && a.map_keys() == b.map_keys()
+                && (a.data.iter().zip(&b.data)).all(|(&a, &b)| {
+                    let (a, b): (f64, f64) = (a.into(), b.into());
+                    a.array_eq(&b) || (a - b).abs() <= tolerance
+                })
+        }
+        if self == other {
+            return true;
+        }
+        if tolerance <= 0.0 || self.meta().pointer.is_some() || other.meta().pointer.is_some() {
+            return false;
+        }
+        match (self, other) {
+            (Value::Num(a), Value::Num(b)) => nums_eq(a, b, tolerance),
+            (Value::Num(a), Value::Byte(b)) => nums_eq(a, b, tolerance),
+            (Value::Byte(a), Value::Num(b)) => nums_eq(a, b, tolerance),
+            (Value::Complex(a), Value::Complex(b)) => {
+                a.shape == b.shape
+                    && a.map_keys() == b.map_keys()
+                    && (a.data.iter().zip(&b.data))
+                        .all(|(&a, &b)| a.array_eq(&b) || (a - b).abs() <= tolerance)
+            }
+            (Value::Box(a), Value::Box(b)) => {
+                a.shape == b.shape
+                    && a.map_keys() == b.map_keys()
+                    && (a.data.iter().zip(&b.data)).all(|(a, b)| a.0.approx_eq(&b.0, tolerance))
+            }
+            _ => false,
+        }
+    }
+}
+
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -2138,13 +2274,13 @@ impl PartialEq<i32> for Value {
 }
 
 #[derive(Clone, Default)]
-pub(crate) struct ValueBuilder {
+pub(crate) struct RowBuilder {
     value: Option<Value>,
     rows: usize,
     capacity: usize,
 }
 
-impl ValueBuilder {
+impl RowBuilder {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             value: None,